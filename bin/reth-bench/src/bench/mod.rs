@@ -9,6 +9,7 @@ mod context;
 mod new_payload_fcu;
 mod new_payload_only;
 mod output;
+mod replay_rpc;
 
 /// `reth bench` command
 #[derive(Debug, Parser)]
@@ -28,6 +29,9 @@ pub enum Subcommands {
 
     /// Benchmark which only calls subsequent `newPayload` calls.
     NewPayloadOnly(new_payload_only::Command),
+
+    /// Replays calls recorded with `--rpc.record-dir` and diffs the responses.
+    ReplayRpc(replay_rpc::Command),
 }
 
 impl BenchmarkCommand {
@@ -39,6 +43,7 @@ impl BenchmarkCommand {
         match self.command {
             Subcommands::NewPayloadFcu(command) => command.execute(ctx).await,
             Subcommands::NewPayloadOnly(command) => command.execute(ctx).await,
+            Subcommands::ReplayRpc(command) => command.execute(ctx).await,
         }
     }
 
@@ -14,6 +14,13 @@ pub(crate) const COMBINED_OUTPUT_SUFFIX: &str = "combined_latency.csv";
 /// This is the suffix for new payload output csv files.
 pub(crate) const NEW_PAYLOAD_OUTPUT_SUFFIX: &str = "new_payload_latency.csv";
 
+/// This is the suffix for replayed RPC call output csv files.
+pub(crate) const REPLAY_RPC_LATENCY_OUTPUT_SUFFIX: &str = "replay_rpc_latency.csv";
+
+/// This is the suffix for the files containing the replayed RPC calls whose responses differ from
+/// the recording.
+pub(crate) const REPLAY_RPC_MISMATCHES_OUTPUT_SUFFIX: &str = "replay_rpc_mismatches.jsonl";
+
 /// This represents the results of a single `newPayload` call in the benchmark, containing the gas
 /// used and the `newPayload` latency.
 #[derive(Debug)]
@@ -177,6 +184,36 @@ impl Serialize for TotalGasRow {
     }
 }
 
+/// This represents the result of a single replayed RPC call, containing the recorded and replayed
+/// latency and whether the response matched the recording.
+#[derive(Debug)]
+pub(crate) struct ReplayRpcResult {
+    /// The method of the call.
+    pub(crate) method: String,
+    /// The latency of the call when it was recorded.
+    pub(crate) recorded_latency: Duration,
+    /// The latency of the replayed call.
+    pub(crate) latency: Duration,
+    /// Whether the response matched the recording.
+    pub(crate) matches: bool,
+}
+
+/// This is another [`Serialize`] implementation for the [`ReplayRpcResult`] struct, serializing
+/// the durations as microseconds because the csv writer would fail otherwise.
+impl Serialize for ReplayRpcResult {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        let mut state = serializer.serialize_struct("ReplayRpcResult", 4)?;
+        state.serialize_field("method", &self.method)?;
+        state.serialize_field("recorded_latency", &self.recorded_latency.as_micros())?;
+        state.serialize_field("latency", &self.latency.as_micros())?;
+        state.serialize_field("matches", &self.matches)?;
+        state.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,286 @@
+//! Runs the `reth bench replay-rpc` command, replaying recorded RPC calls against a node and
+//! diffing the responses.
+
+use crate::bench::output::{
+    ReplayRpcResult, REPLAY_RPC_LATENCY_OUTPUT_SUFFIX, REPLAY_RPC_MISMATCHES_OUTPUT_SUFFIX,
+};
+use clap::Parser;
+use csv::Writer;
+use reqwest::Client;
+use reth_cli_runner::CliContext;
+use reth_rpc_layer::{read_rpc_records, rpc_record_files};
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tracing::{debug, info, warn};
+
+/// Methods whose responses are not comparable between the recording and the replay.
+const SKIPPED_METHODS: &[&str] = &["eth_subscribe", "eth_unsubscribe"];
+
+/// `reth bench replay-rpc` command
+///
+/// Reads calls recorded with `--rpc.record-dir`, sends them to the node at `--rpc-url` in the
+/// order they were recorded and reports the calls whose responses differ from the recording.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The RPC url of the node to replay the calls against.
+    #[arg(long, value_name = "RPC_URL", verbatim_doc_comment)]
+    rpc_url: String,
+
+    /// The recording to replay, either a recording file or a recording directory.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    recording: PathBuf,
+
+    /// Only replay calls of these methods.
+    #[arg(long = "method", value_name = "METHOD", verbatim_doc_comment)]
+    methods: Vec<String>,
+
+    /// The path to the output directory for the latencies and mismatching responses.
+    #[arg(long, short, value_name = "BENCHMARK_OUTPUT", verbatim_doc_comment)]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `benchmark replay-rpc` command
+    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+        let (results, failed) = self.replay().await?;
+
+        // write the csv output to files
+        if let Some(path) = self.output {
+            let output_path = path.join(REPLAY_RPC_LATENCY_OUTPUT_SUFFIX);
+            info!("Writing replay latency output to file: {:?}", output_path);
+            let mut writer = Writer::from_path(output_path)?;
+            for result in &results {
+                writer.serialize(result)?;
+            }
+            writer.flush()?;
+
+            info!("Finished writing benchmark output files to {:?}.", path);
+        }
+
+        let mismatched = results.iter().filter(|result| !result.matches).count();
+        let recorded_latency: Duration = results.iter().map(|result| result.recorded_latency).sum();
+        let latency: Duration = results.iter().map(|result| result.latency).sum();
+        info!(
+            replayed = results.len(),
+            matched = results.len() - mismatched,
+            mismatched,
+            failed,
+            ?recorded_latency,
+            ?latency,
+            "Finished replaying RPC recording"
+        );
+
+        Ok(())
+    }
+
+    /// Replays the recorded calls, writing the mismatching responses to the output directory.
+    ///
+    /// Returns the results of the replayed calls and the number of calls that failed.
+    async fn replay(&self) -> eyre::Result<(Vec<ReplayRpcResult>, usize)> {
+        let files = if self.recording.is_dir() {
+            rpc_record_files(&self.recording)?
+        } else {
+            vec![self.recording.clone()]
+        };
+        info!(files = files.len(), "Replaying RPC recording against {}", self.rpc_url);
+
+        let client = Client::new();
+        let mut mismatches = match &self.output {
+            Some(path) => {
+                let output_path = path.join(REPLAY_RPC_MISMATCHES_OUTPUT_SUFFIX);
+                info!("Writing mismatching responses to file: {:?}", output_path);
+                Some(BufWriter::new(File::create(output_path)?))
+            }
+            None => None,
+        };
+
+        let mut results = Vec::new();
+        let mut failed = 0usize;
+        let mut warned_block_number = false;
+        for file in files {
+            debug!(?file, "Replaying recording file");
+            for record in read_rpc_records(&file)? {
+                let record = record?;
+                let Some(method) = record.method().map(str::to_string) else { continue };
+                if SKIPPED_METHODS.contains(&method.as_str()) ||
+                    (!self.methods.is_empty() && !self.methods.contains(&method))
+                {
+                    continue
+                }
+
+                if !warned_block_number {
+                    warned_block_number = true;
+                    if let Some(recorded) = record.block_number {
+                        let current = self.block_number(&client).await?;
+                        if current != recorded {
+                            warn!(
+                                recorded,
+                                current,
+                                "Node is at a different block than the recording, responses of state dependent calls may differ"
+                            );
+                        }
+                    }
+                }
+
+                let start = Instant::now();
+                let response = match self.send(&client, &record.request).await {
+                    Ok(response) => response,
+                    Err(err) => {
+                        warn!(%method, %err, "Failed to replay call");
+                        failed += 1;
+                        continue
+                    }
+                };
+                let latency = start.elapsed();
+
+                let matches = response_payload(&response) == response_payload(&record.response);
+                if !matches {
+                    warn!(%method, block_number = ?record.block_number, "Response differs from the recording");
+                    if let Some(writer) = mismatches.as_mut() {
+                        serde_json::to_writer(
+                            &mut *writer,
+                            &json!({
+                                "blockNumber": record.block_number,
+                                "request": record.request,
+                                "expected": record.response,
+                                "actual": response,
+                            }),
+                        )?;
+                        writer.write_all(b"\n")?;
+                    }
+                }
+
+                results.push(ReplayRpcResult {
+                    method,
+                    recorded_latency: Duration::from_micros(record.duration_micros),
+                    latency,
+                    matches,
+                });
+            }
+        }
+
+        if let Some(mut writer) = mismatches {
+            writer.flush()?;
+        }
+
+        Ok((results, failed))
+    }
+
+    /// Sends the JSON-RPC request to the node and returns the JSON response.
+    async fn send(&self, client: &Client, request: &Value) -> eyre::Result<Value> {
+        let response = client
+            .post(&self.rpc_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(request)?)
+            .send()
+            .await?
+            .bytes()
+            .await?;
+        Ok(serde_json::from_slice(&response)?)
+    }
+
+    /// Returns the current block number of the node.
+    async fn block_number(&self, client: &Client) -> eyre::Result<u64> {
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber", "params": []});
+        let response = self.send(client, &request).await?;
+        let number = response["result"]
+            .as_str()
+            .ok_or_else(|| eyre::eyre!("invalid eth_blockNumber response: {response}"))?;
+        Ok(u64::from_str_radix(number.trim_start_matches("0x"), 16)?)
+    }
+}
+
+/// Returns the part of a JSON-RPC response that is compared, the result or error.
+fn response_payload(response: &Value) -> (Option<&Value>, Option<&Value>) {
+    (response.get("result"), response.get("error"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::{
+        server::{RpcServiceBuilder, Server, ServerHandle},
+        RpcModule,
+    };
+    use reth_rpc_layer::{RpcRecordConfig, RpcRecorder};
+    use std::path::Path;
+
+    /// Starts a server that answers `eth_blockNumber` with the given block number, recording the
+    /// calls if a recorder is given.
+    async fn start_server(
+        block_number: u64,
+        recorder: Option<RpcRecorder>,
+    ) -> (String, ServerHandle) {
+        let mut module = RpcModule::new(());
+        module
+            .register_method("eth_blockNumber", move |_, _| format!("0x{block_number:x}"))
+            .unwrap();
+        let server = Server::builder()
+            .set_rpc_middleware(RpcServiceBuilder::new().option_layer(recorder))
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+        (url, server.start(module))
+    }
+
+    fn command(rpc_url: String, recording: &Path) -> Command {
+        Command { rpc_url, recording: recording.to_path_buf(), methods: Vec::new(), output: None }
+    }
+
+    #[tokio::test]
+    async fn replays_recorded_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = RpcRecorder::new(RpcRecordConfig::new(dir.path().to_path_buf())).unwrap();
+        recorder.set_block_number(1);
+        let (url, handle) = start_server(1, Some(recorder)).await;
+
+        let recorded = command(url, dir.path());
+        let response = recorded.block_number(&Client::new()).await.unwrap();
+        assert_eq!(response, 1);
+        handle.stop().unwrap();
+
+        // the records are written in the background
+        let mut records = Vec::new();
+        for _ in 0..100 {
+            records = rpc_record_files(dir.path())
+                .unwrap()
+                .iter()
+                .flat_map(|path| read_rpc_records(path).unwrap())
+                .collect::<std::io::Result<Vec<_>>>()
+                .unwrap();
+            if !records.is_empty() {
+                break
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].method(), Some("eth_blockNumber"));
+        assert_eq!(records[0].block_number, Some(1));
+
+        // a node at the same block returns the recorded response
+        let (url, _handle) = start_server(1, None).await;
+        let (results, failed) = command(url, dir.path()).replay().await.unwrap();
+        assert_eq!(failed, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].method, "eth_blockNumber");
+        assert!(results[0].matches);
+
+        // a node at another block doesn't, the mismatch is written to the output directory
+        let output = tempfile::tempdir().unwrap();
+        let (url, _handle) = start_server(2, None).await;
+        let replay =
+            Command { output: Some(output.path().to_path_buf()), ..command(url, dir.path()) };
+        let (results, _) = replay.replay().await.unwrap();
+        assert!(!results[0].matches);
+        let mismatches =
+            std::fs::read_to_string(output.path().join(REPLAY_RPC_MISMATCHES_OUTPUT_SUFFIX))
+                .unwrap();
+        assert_eq!(mismatches.lines().count(), 1);
+    }
+}
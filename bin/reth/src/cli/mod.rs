@@ -6,7 +6,8 @@ use crate::{
         LogArgs,
     },
     commands::{
-        config_cmd, db, debug_cmd, dump_genesis, import, init_cmd, init_state,
+        config_cmd, db, debug_cmd, dump_genesis, export_era, import, import_era, init_cmd,
+        init_state,
         node::{self, NoArgs},
         p2p, recover, stage, test_vectors,
     },
@@ -150,6 +151,8 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::InitState(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ImportEra(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ExportEra(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             #[cfg(feature = "optimism")]
             Commands::ImportOp(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             #[cfg(feature = "optimism")]
@@ -192,6 +195,12 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(import::ImportCommand),
+    /// This syncs blocks from Era1 archives.
+    #[command(name = "import-era")]
+    ImportEra(import_era::ImportEraCommand),
+    /// Exports blocks, receipts and total difficulty into Era1 archives.
+    #[command(name = "export-era")]
+    ExportEra(export_era::ExportEraCommand),
     /// This syncs RLP encoded OP blocks below Bedrock from a file, without executing.
     #[cfg(feature = "optimism")]
     #[command(name = "import-op")]
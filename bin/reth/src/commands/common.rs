@@ -64,13 +64,7 @@ impl EnvironmentArgs {
             reth_fs_util::create_dir_all(&sf_path)?;
         }
 
-        let config_path = self.config.clone().unwrap_or_else(|| data_dir.config());
-        let mut config: Config = confy::load_path(config_path).unwrap_or_default();
-
-        // Make sure ETL doesn't default to /tmp/, but to whatever datadir is set to
-        if config.stages.etl.dir.is_none() {
-            config.stages.etl.dir = Some(EtlConfig::from_datadir(data_dir.data_dir()));
-        }
+        let config = self.load_config(&data_dir);
 
         info!(target: "reth::cli", ?db_path, ?sf_path, "Opening storage");
         let (db, sfp) = match access {
@@ -93,6 +87,19 @@ impl EnvironmentArgs {
         Ok(Environment { config, provider_factory, data_dir })
     }
 
+    /// Loads the configuration file, or the default configuration if it's missing.
+    pub fn load_config(&self, data_dir: &ChainPath<DataDirPath>) -> Config {
+        let config_path = self.config.clone().unwrap_or_else(|| data_dir.config());
+        let mut config: Config = confy::load_path(config_path).unwrap_or_default();
+
+        // Make sure ETL doesn't default to /tmp/, but to whatever datadir is set to
+        if config.stages.etl.dir.is_none() {
+            config.stages.etl.dir = Some(EtlConfig::from_datadir(data_dir.data_dir()));
+        }
+
+        config
+    }
+
     /// Returns a [`ProviderFactory`] after executing consistency checks.
     ///
     /// If it's a read-write environment and an issue is found, it will attempt to heal (including a
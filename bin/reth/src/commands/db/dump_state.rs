@@ -0,0 +1,358 @@
+//! Command that dumps the state at a block in the format consumed by `reth init-state`.
+
+use crate::utils::DbTool;
+use clap::Parser;
+use eyre::{bail, WrapErr};
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    transaction::DbTx,
+};
+use reth_db_common::init::{GenesisAccountWithAddress, StateRoot};
+use reth_primitives::{Account, Address, BlockNumber, GenesisAccount, B256};
+use reth_provider::{
+    AccountExtReader, AccountReader, BlockNumReader, HeaderProvider, ProviderError, StateProvider,
+    StateProviderBox, StorageReader,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Write},
+    ops::Bound,
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+
+/// Number of dumped accounts after which progress is logged.
+const LOG_INTERVAL: usize = 100_000;
+
+/// The arguments for the `reth db dump-state` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The block to dump the state at. Defaults to the latest block.
+    ///
+    /// The state of blocks below the tip is reconstructed from the history tables, which must not
+    /// be pruned for the requested block.
+    #[arg(long)]
+    block: Option<BlockNumber>,
+
+    /// The JSONL file to write the state dump to.
+    #[arg(long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// Only dump the given accounts.
+    #[arg(long, value_delimiter = ',')]
+    addresses: Vec<Address>,
+
+    /// Only dump the accounts listed in the file, one address per line.
+    #[arg(long, value_name = "FILE")]
+    addresses_file: Option<PathBuf>,
+
+    /// Continues an interrupted dump to the output file, instead of overwriting it.
+    ///
+    /// The dump must have been started for the same block.
+    #[arg(long)]
+    resume: bool,
+}
+
+impl Command {
+    /// Execute `db dump-state` command
+    pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        // the dump reads the entire state in a single transaction
+        let provider = tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+        let tip = provider.best_block_number()?;
+        let block = self.block.unwrap_or(tip);
+        if block > tip {
+            bail!("Block {block} is above the latest executed block {tip}")
+        }
+        let root = provider
+            .header_by_number(block)?
+            .ok_or(ProviderError::HeaderNotFound(block.into()))?
+            .state_root;
+
+        let mut filter = self.addresses.into_iter().collect::<BTreeSet<_>>();
+        if let Some(path) = &self.addresses_file {
+            filter.extend(read_addresses(path)?);
+        }
+
+        // accounts and storage slots that changed after the block, the plain state of everything
+        // else is also the state at the block
+        let (changed_accounts, changed_storages) = if block < tip {
+            info!(target: "reth::cli", block, tip, "Collecting changes after block");
+            (
+                provider.changed_accounts_with_range(block + 1..=tip)?,
+                provider.changed_storages_with_range(block + 1..=tip)?,
+            )
+        } else {
+            Default::default()
+        };
+
+        let (writer, last_address) = if self.resume && self.output.exists() {
+            let (file, last_address) = open_for_resume(&self.output, root)?;
+            info!(target: "reth::cli", ?last_address, "Resuming state dump");
+            (file, last_address)
+        } else {
+            let mut file = File::create(&self.output)?;
+            serde_json::to_writer(&mut file, &StateRoot { root })?;
+            writeln!(file)?;
+            (file, None)
+        };
+        let start = last_address.map_or(Bound::Unbounded, Bound::Excluded);
+
+        let mut dumper = StateDumper {
+            tx: provider.tx_ref(),
+            state: tool
+                .provider_factory
+                .provider()?
+                .disable_long_read_transaction_safety()
+                .state_provider_by_block_number(block)?,
+            changed_accounts: &changed_accounts,
+            changed_storages: &changed_storages,
+            writer: BufWriter::new(writer),
+            dumped: 0,
+        };
+
+        info!(target: "reth::cli", block, %root, output = %self.output.display(), "Dumping state");
+
+        if filter.is_empty() {
+            // merge the plain state with the accounts that were removed after the block
+            let mut changed = changed_accounts.range((start, Bound::Unbounded)).copied().peekable();
+            let mut cursor = provider.tx_ref().cursor_read::<tables::PlainAccountState>()?;
+            for entry in cursor.walk(last_address)? {
+                let (address, account) = entry?;
+                if Some(address) == last_address {
+                    continue
+                }
+                while let Some(removed) = changed.next_if(|changed| *changed < address) {
+                    dumper.dump_account(removed, None)?;
+                }
+                changed.next_if_eq(&address);
+                dumper.dump_account(address, Some(account))?;
+            }
+            for removed in changed {
+                dumper.dump_account(removed, None)?;
+            }
+        } else {
+            for address in filter.range((start, Bound::Unbounded)).copied() {
+                let account = provider.tx_ref().get::<tables::PlainAccountState>(address)?;
+                dumper.dump_account(address, account)?;
+            }
+        }
+
+        dumper.writer.flush()?;
+        info!(target: "reth::cli", accounts = dumper.dumped, "State dump finished");
+
+        Ok(())
+    }
+}
+
+/// Writes accounts at a block to the state dump.
+struct StateDumper<'a, TX, W> {
+    /// Read transaction over the plain state.
+    tx: &'a TX,
+    /// State provider at the dumped block.
+    state: StateProviderBox,
+    /// Accounts that changed after the dumped block.
+    changed_accounts: &'a BTreeSet<Address>,
+    /// Storage slots that changed after the dumped block.
+    changed_storages: &'a BTreeMap<Address, BTreeSet<B256>>,
+    writer: W,
+    dumped: usize,
+}
+
+impl<TX: DbTx, W: Write> StateDumper<'_, TX, W> {
+    /// Writes the account at the dumped block, given its current plain state.
+    ///
+    /// Accounts that don't exist at the dumped block are skipped.
+    fn dump_account(&mut self, address: Address, current: Option<Account>) -> eyre::Result<()> {
+        let account = if self.changed_accounts.contains(&address) {
+            self.state.basic_account(address)?
+        } else {
+            current
+        };
+        let Some(account) = account else { return Ok(()) };
+
+        let code = match account.bytecode_hash {
+            Some(hash) => Some(
+                self.tx
+                    .get::<tables::Bytecodes>(hash)?
+                    .ok_or_else(|| eyre::eyre!("Bytecode {hash} of account {address} not found"))?
+                    .original_bytes(),
+            ),
+            None => None,
+        };
+
+        let mut storage = BTreeMap::new();
+        let mut cursor = self.tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        let mut entry = cursor.seek_exact(address)?.map(|(_, entry)| entry);
+        while let Some(current) = entry {
+            storage.insert(current.key, current.value);
+            entry = cursor.next_dup_val()?;
+        }
+        for slot in self.changed_storages.get(&address).into_iter().flatten() {
+            match self.state.storage(address, *slot)?.filter(|value| !value.is_zero()) {
+                Some(value) => storage.insert(*slot, value),
+                None => storage.remove(slot),
+            };
+        }
+        let storage = storage
+            .into_iter()
+            .map(|(slot, value)| (slot, B256::from(value.to_be_bytes())))
+            .collect::<BTreeMap<_, _>>();
+
+        let genesis_account = GenesisAccount {
+            nonce: Some(account.nonce),
+            balance: account.balance,
+            code,
+            storage: (!storage.is_empty()).then_some(storage),
+            private_key: None,
+        };
+        serde_json::to_writer(
+            &mut self.writer,
+            &GenesisAccountWithAddress { genesis_account, address },
+        )?;
+        writeln!(self.writer)?;
+
+        self.dumped += 1;
+        if self.dumped % LOG_INTERVAL == 0 {
+            info!(target: "reth::cli", accounts = self.dumped, %address, "Dumping state");
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a file with one address per line, ignoring empty lines.
+fn read_addresses(path: &Path) -> eyre::Result<Vec<Address>> {
+    let file = File::open(path).wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+    BufReader::new(file)
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|line| Ok(line?.trim().parse()?))
+        .collect()
+}
+
+/// Opens an existing state dump for appending and returns the last dumped address.
+///
+/// A trailing incomplete line, left by an interrupted dump, is truncated.
+fn open_for_resume(path: &Path, root: B256) -> eyre::Result<(File, Option<Address>)> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut line = String::new();
+
+    reader.read_line(&mut line)?;
+    if !line.ends_with('\n') {
+        bail!("State dump {} has no complete state root line", path.display())
+    }
+    let dumped_root = serde_json::from_str::<StateRoot>(&line)?.root;
+    if dumped_root != root {
+        bail!("State dump {} is for state root {dumped_root}, expected {root}", path.display())
+    }
+
+    let mut complete_len = line.len() as u64;
+    let mut last_address = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || !line.ends_with('\n') {
+            break
+        }
+        let account = serde_json::from_str::<GenesisAccountWithAddress>(&line)?;
+        last_address = Some(account.address);
+        complete_len += line.len() as u64;
+    }
+
+    let file = OpenOptions::new().append(true).open(path)?;
+    file.set_len(complete_len)?;
+    Ok((file, last_address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{Chain, ChainSpec};
+    use reth_config::config::EtlConfig;
+    use reth_db_common::init::{init_from_state_dump, init_genesis};
+    use reth_primitives::{Bytes, Genesis, U256};
+    use reth_provider::test_utils::create_test_provider_factory_with_chain_spec;
+    use std::sync::Arc;
+
+    fn chain_spec(alloc: BTreeMap<Address, GenesisAccount>) -> Arc<ChainSpec> {
+        Arc::new(ChainSpec {
+            chain: Chain::from_id(1),
+            genesis: Genesis { alloc, ..Default::default() },
+            hardforks: BTreeMap::default(),
+            genesis_hash: None,
+            paris_block_and_final_difficulty: None,
+            deposit_contract: None,
+            ..Default::default()
+        })
+    }
+
+    fn dump_command(output: PathBuf, resume: bool) -> Command {
+        Command { block: None, output, addresses: Vec::new(), addresses_file: None, resume }
+    }
+
+    #[test]
+    fn init_state_accepts_the_dump() {
+        let alloc = BTreeMap::from([
+            (
+                Address::with_last_byte(1),
+                GenesisAccount { balance: U256::from(1), nonce: Some(2), ..Default::default() },
+            ),
+            (
+                Address::with_last_byte(2),
+                GenesisAccount {
+                    code: Some(Bytes::from_static(&[0x60, 0x00])),
+                    storage: Some(BTreeMap::from([
+                        (B256::with_last_byte(1), B256::with_last_byte(3)),
+                        (B256::with_last_byte(2), B256::with_last_byte(4)),
+                    ])),
+                    ..Default::default()
+                },
+            ),
+            (
+                Address::with_last_byte(3),
+                GenesisAccount { balance: U256::from(5), ..Default::default() },
+            ),
+        ]);
+        let source = create_test_provider_factory_with_chain_spec(chain_spec(alloc.clone()));
+        init_genesis(source.clone()).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("state.jsonl");
+        let tool = DbTool::new(source.clone()).unwrap();
+        dump_command(output.clone(), false).execute(&tool).unwrap();
+
+        // an empty database accepts the dump, which includes the expected state root
+        let target = create_test_provider_factory_with_chain_spec(chain_spec(BTreeMap::new()));
+        init_genesis(target.clone()).unwrap();
+        let reader = BufReader::new(File::open(&output).unwrap());
+        init_from_state_dump(reader, target.clone(), EtlConfig::default()).unwrap();
+
+        let provider = target.provider().unwrap();
+        for (address, account) in alloc {
+            let imported = provider.basic_account(address).unwrap().unwrap();
+            assert_eq!(imported.nonce, account.nonce.unwrap_or_default());
+            assert_eq!(imported.balance, account.balance);
+            for (slot, value) in account.storage.unwrap_or_default() {
+                let imported = provider
+                    .tx_ref()
+                    .cursor_dup_read::<tables::PlainStorageState>()
+                    .unwrap()
+                    .seek_by_key_subkey(address, slot)
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(B256::from(imported.value.to_be_bytes()), value);
+            }
+        }
+
+        // resuming an interrupted dump produces the same dump
+        let full = std::fs::read_to_string(&output).unwrap();
+        let second_line_end = full.match_indices('\n').nth(1).unwrap().0 + 1;
+        std::fs::write(&output, &full[..second_line_end + 10]).unwrap();
+        dump_command(output.clone(), true).execute(&tool).unwrap();
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), full);
+    }
+}
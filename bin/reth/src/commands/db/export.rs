@@ -0,0 +1,212 @@
+//! Command that exports database tables into a portable file, to be loaded with `reth db import`.
+//!
+//! The file is a zstd stream of the database version followed by each table, with its raw keys
+//! and values as stored in the database, ordered by key:
+//!
+//! ```text
+//! MAGIC | DB_VERSION (u64 LE)
+//! [ name length (u8) | name | [ key length (u32 LE) | key | value length (u32 LE) | value ]*
+//!   END_OF_TABLE (u32 LE) ]*
+//! 0 (u8)
+//! ```
+
+use crate::{commands::db::get::maybe_json_value_parser, utils::DbTool};
+use clap::Parser;
+use eyre::WrapErr;
+use reth_db::{tables_to_generic, version::DB_VERSION, DatabaseEnv, RawKey, RawTable, Tables};
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    table::{Key, Table},
+    transaction::DbTx,
+};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    ops::Bound,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+/// Marks the start of a table export file.
+pub(crate) const MAGIC: &[u8; 8] = b"RETHTBLS";
+
+/// Marks the end of the entries of a table, in place of a key length.
+pub(crate) const END_OF_TABLE: u32 = u32::MAX;
+
+/// The arguments for the `reth db export` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The tables to export, separated by commas.
+    #[arg(long, value_delimiter = ',', required = true)]
+    tables: Vec<Tables>,
+
+    /// The range of keys to export from every table, as `START..END`, or `START..=END` to include
+    /// the end. Either bound can be omitted, and keys are parsed the same way as in `reth db get`.
+    #[arg(long)]
+    range: Option<KeyRange>,
+
+    /// The file to write the tables to.
+    #[arg(long, short)]
+    output: PathBuf,
+
+    /// The zstd compression level.
+    #[arg(long, default_value_t = 3)]
+    compression_level: i32,
+}
+
+impl Command {
+    /// Execute `db export` command
+    pub fn execute(self, tool: &DbTool<Arc<DatabaseEnv>>) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let file = File::create(&self.output)
+            .wrap_err_with(|| format!("Could not create {}", self.output.display()))?;
+        let mut writer = zstd::Encoder::new(BufWriter::new(file), self.compression_level)?;
+
+        let mut tx = tool.provider_factory.db_ref().tx()?;
+        tx.disable_long_read_transaction_safety();
+        let range = self.range.unwrap_or_default();
+        for (table, entries) in export_tables(&tx, &self.tables, &range, &mut writer)? {
+            info!(target: "reth::cli", table = table.name(), entries, "Exported table");
+        }
+
+        writer.finish()?.flush()?;
+        println!("Exported {} tables to {}", self.tables.len(), self.output.display());
+
+        Ok(())
+    }
+}
+
+/// A range of keys, parsed into the key type of each table it's applied to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyRange {
+    start: Bound<String>,
+    end: Bound<String>,
+}
+
+impl Default for KeyRange {
+    fn default() -> Self {
+        Self { start: Bound::Unbounded, end: Bound::Unbounded }
+    }
+}
+
+impl KeyRange {
+    /// Returns the bounds of the range as raw keys of the table.
+    fn bounds<T: Table>(&self) -> eyre::Result<(Bound<RawKey<T::Key>>, Bound<RawKey<T::Key>>)> {
+        fn parse<K: Key>(bound: &Bound<String>) -> serde_json::Result<Bound<RawKey<K>>> {
+            let decode = |key: &String| serde_json::from_str::<K>(key).map(RawKey::new);
+            Ok(match bound {
+                Bound::Included(key) => Bound::Included(decode(key)?),
+                Bound::Excluded(key) => Bound::Excluded(decode(key)?),
+                Bound::Unbounded => Bound::Unbounded,
+            })
+        }
+
+        parse(&self.start)
+            .and_then(|start| Ok((start, parse(&self.end)?)))
+            .wrap_err_with(|| format!("Invalid key range for table {}", T::NAME))
+    }
+}
+
+impl FromStr for KeyRange {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((start, end)) = s.split_once("..") else {
+            eyre::bail!("Expected a range of keys as `START..END` or `START..=END`")
+        };
+        let (end, inclusive) = match end.strip_prefix('=') {
+            Some(end) => (end, true),
+            None => (end, false),
+        };
+
+        let start = match start.trim() {
+            "" => Bound::Unbounded,
+            start => Bound::Included(maybe_json_value_parser(start)?),
+        };
+        let end = match end.trim() {
+            "" if inclusive => eyre::bail!("An inclusive range needs an end key"),
+            "" => Bound::Unbounded,
+            end if inclusive => Bound::Included(maybe_json_value_parser(end)?),
+            end => Bound::Excluded(maybe_json_value_parser(end)?),
+        };
+
+        Ok(Self { start, end })
+    }
+}
+
+/// Writes the entries of the tables within the range, and returns the number of entries exported
+/// from each table.
+pub(crate) fn export_tables(
+    tx: &impl DbTx,
+    tables: &[Tables],
+    range: &KeyRange,
+    writer: &mut impl Write,
+) -> eyre::Result<Vec<(Tables, usize)>> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&DB_VERSION.to_le_bytes())?;
+
+    let mut exported = Vec::with_capacity(tables.len());
+    for table in tables {
+        let name = table.name();
+        writer.write_all(&[name.len() as u8])?;
+        writer.write_all(name.as_bytes())?;
+
+        let entries = tables_to_generic!(table, |Table| export_table::<Table>(tx, range, writer))?;
+        exported.push((*table, entries));
+    }
+    writer.write_all(&[0])?;
+
+    Ok(exported)
+}
+
+/// Writes the entries of a table within the range, and returns their number.
+fn export_table<T: Table>(
+    tx: &impl DbTx,
+    range: &KeyRange,
+    writer: &mut impl Write,
+) -> eyre::Result<usize> {
+    let mut cursor = tx.cursor_read::<RawTable<T>>()?;
+    let mut entries = 0;
+    for entry in cursor.walk_range(range.bounds::<T>()?)? {
+        let (key, value) = entry?;
+        write_bytes(writer, key.raw_key())?;
+        write_bytes(writer, value.raw_value())?;
+        entries += 1;
+    }
+    writer.write_all(&END_OF_TABLE.to_le_bytes())?;
+
+    Ok(entries)
+}
+
+/// Writes the bytes prefixed by their length.
+fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_key_range() {
+        assert_eq!("..".parse::<KeyRange>().unwrap(), KeyRange::default());
+        assert_eq!(
+            "10..20".parse::<KeyRange>().unwrap(),
+            KeyRange {
+                start: Bound::Included("10".to_string()),
+                end: Bound::Excluded("20".to_string())
+            }
+        );
+        assert_eq!(
+            "..=0x01".parse::<KeyRange>().unwrap(),
+            KeyRange { start: Bound::Unbounded, end: Bound::Included("\"0x01\"".to_string()) }
+        );
+        assert!("10".parse::<KeyRange>().is_err());
+        assert!("10..=".parse::<KeyRange>().is_err());
+    }
+}
@@ -1,14 +1,18 @@
 use crate::utils::DbTool;
 use clap::Parser;
 use reth_db::{
-    static_file::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask, TransactionMask},
+    static_file::{
+        AccountChangeSetMask, ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask,
+        StorageChangeSetMask, TransactionMask,
+    },
     tables, RawKey, RawTable, Receipts, TableViewer, Transactions,
 };
 use reth_db_api::{
     database::Database,
+    models::{AccountChangeSet, StorageChangeSet},
     table::{Decompress, DupSort, Table},
 };
-use reth_primitives::{BlockHash, Header, StaticFileSegment};
+use reth_primitives::{BlockHash, BlockNumber, Header, StaticFileSegment};
 use reth_provider::StaticFileProviderFactory;
 use tracing::error;
 
@@ -71,6 +75,14 @@ impl Command {
                         table_key::<tables::Receipts>(&key)?,
                         <ReceiptMask<<Receipts as Table>::Value>>::MASK,
                     ),
+                    StaticFileSegment::AccountChangeSets => (
+                        serde_json::from_str::<BlockNumber>(&key)?,
+                        <AccountChangeSetMask<AccountChangeSet>>::MASK,
+                    ),
+                    StaticFileSegment::StorageChangeSets => (
+                        serde_json::from_str::<BlockNumber>(&key)?,
+                        <StorageChangeSetMask<StorageChangeSet>>::MASK,
+                    ),
                 };
 
                 let content = tool.provider_factory.static_file_provider().find_static_file(
@@ -112,6 +124,16 @@ impl Command {
                                     )?;
                                     println!("{}", serde_json::to_string_pretty(&receipt)?);
                                 }
+                                StaticFileSegment::AccountChangeSets => {
+                                    let changeset =
+                                        AccountChangeSet::decompress(content[0].as_slice())?;
+                                    println!("{}", serde_json::to_string_pretty(&changeset)?);
+                                }
+                                StaticFileSegment::StorageChangeSets => {
+                                    let changeset =
+                                        StorageChangeSet::decompress(content[0].as_slice())?;
+                                    println!("{}", serde_json::to_string_pretty(&changeset)?);
+                                }
                             }
                         }
                     }
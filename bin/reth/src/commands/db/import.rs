@@ -0,0 +1,450 @@
+//! Command that imports database tables from a file written by `reth db export`.
+
+use super::export::{END_OF_TABLE, MAGIC};
+use clap::Parser;
+use eyre::WrapErr;
+use reth_config::config::EtlConfig;
+use reth_db::{
+    tables, tables_to_generic, version::DB_VERSION, DatabaseEnv, RawKey, RawTable, RawValue, Tables,
+};
+use reth_db_api::{
+    cursor::DbCursorRW,
+    database::Database,
+    table::Table,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_etl::Collector;
+use reth_provider::ProviderFactory;
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::PathBuf,
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+/// Number of entries written to a table before they're committed along with the import progress.
+const COMMIT_THRESHOLD: usize = 100_000;
+
+/// Key of the import progress in [`tables::StageCheckpointProgresses`].
+const IMPORT_PROGRESS_KEY: &str = "DbImport";
+
+/// The arguments for the `reth db import` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The file written by `reth db export` to import the tables from.
+    input: PathBuf,
+
+    /// Clears each table before importing its entries.
+    ///
+    /// Otherwise, the imported entries replace the entries with the same keys. Non-empty `DupSort`
+    /// tables can only be imported into with this flag.
+    ///
+    /// An interrupted import resumes after its last committed entry when it's run again with the
+    /// same file, and the tables it already cleared aren't cleared again.
+    #[arg(long)]
+    clear: bool,
+}
+
+impl Command {
+    /// Execute `db import` command
+    pub fn execute(
+        self,
+        provider_factory: ProviderFactory<Arc<DatabaseEnv>>,
+        etl: EtlConfig,
+    ) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let file = File::open(&self.input)
+            .wrap_err_with(|| format!("Could not open {}", self.input.display()))?;
+        let mut reader = zstd::Decoder::new(file)?;
+
+        let imported = import_tables(
+            provider_factory.db_ref(),
+            &mut reader,
+            self.clear,
+            &etl,
+            COMMIT_THRESHOLD,
+        )?;
+        for (table, entries) in &imported {
+            info!(target: "reth::cli", table = table.name(), entries, "Imported table");
+        }
+        println!("Imported {} tables from {}", imported.len(), self.input.display());
+
+        Ok(())
+    }
+}
+
+/// Progress of an import, committed with the imported entries so an interrupted import can resume
+/// after the last committed entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct ImportProgress {
+    /// Number of tables of the file that were imported.
+    tables: u64,
+    /// Raw key of the last committed entry of the next table, if any of its entries were
+    /// committed.
+    last_key: Option<Vec<u8>>,
+}
+
+impl ImportProgress {
+    /// Reads the progress of an interrupted import, if any.
+    fn read<TX: DbTx>(tx: &TX) -> eyre::Result<Option<Self>> {
+        let Some(progress) =
+            tx.get::<tables::StageCheckpointProgresses>(IMPORT_PROGRESS_KEY.to_string())?
+        else {
+            return Ok(None)
+        };
+        let (tables, last_key) = progress
+            .split_first_chunk::<8>()
+            .ok_or_else(|| eyre::eyre!("Invalid import progress"))?;
+        Ok(Some(Self {
+            tables: u64::from_be_bytes(*tables),
+            last_key: (!last_key.is_empty()).then(|| last_key.to_vec()),
+        }))
+    }
+
+    /// Saves the progress in the transaction.
+    fn write(&self, tx: &impl DbTxMut) -> eyre::Result<()> {
+        let mut progress = self.tables.to_be_bytes().to_vec();
+        progress.extend_from_slice(self.last_key.as_deref().unwrap_or_default());
+        tx.put::<tables::StageCheckpointProgresses>(IMPORT_PROGRESS_KEY.to_string(), progress)?;
+        Ok(())
+    }
+}
+
+/// Loads every table of the file into the database, and returns the number of entries of each
+/// table.
+///
+/// The entries of a table are sorted with an ETL collector, and committed every
+/// `commit_threshold` entries along with the [`ImportProgress`]. An interrupted import skips the
+/// tables it finished, and resumes the next one after its last committed key.
+pub(crate) fn import_tables<DB: Database>(
+    db: &DB,
+    reader: &mut impl Read,
+    clear: bool,
+    etl: &EtlConfig,
+    commit_threshold: usize,
+) -> eyre::Result<Vec<(Tables, usize)>> {
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != *MAGIC {
+        eyre::bail!("Not a file written by `reth db export`")
+    }
+    let mut version = [0; 8];
+    reader.read_exact(&mut version)?;
+    let version = u64::from_le_bytes(version);
+    if version != DB_VERSION {
+        eyre::bail!(
+            "The file was exported from database version v{version}, expected v{DB_VERSION}"
+        )
+    }
+
+    let progress = db.view(ImportProgress::read)??;
+    if let Some(progress) = &progress {
+        info!(target: "reth::cli", tables = progress.tables, "Resuming the import");
+    }
+    let progress = progress.unwrap_or_default();
+
+    let mut imported = Vec::new();
+    loop {
+        let mut name = vec![0; read_array::<1>(reader)?[0] as usize];
+        if name.is_empty() {
+            break
+        }
+        reader.read_exact(&mut name)?;
+        let table = String::from_utf8(name)?.parse::<Tables>().map_err(|err| eyre::eyre!(err))?;
+
+        let index = imported.len() as u64;
+        let entries = if index < progress.tables {
+            skip_table(reader)?
+        } else {
+            let resume_after =
+                if index == progress.tables { progress.last_key.clone() } else { None };
+            tables_to_generic!(table, |Table| import_table::<Table, _>(
+                db,
+                reader,
+                clear,
+                table.is_dupsort(),
+                etl,
+                commit_threshold,
+                index,
+                resume_after
+            ))?
+        };
+        imported.push((table, entries));
+    }
+
+    let tx = db.tx_mut()?;
+    tx.delete::<tables::StageCheckpointProgresses>(IMPORT_PROGRESS_KEY.to_string(), None)?;
+    tx.commit()?;
+
+    Ok(imported)
+}
+
+/// Loads the entries of a table up to its end marker into the database, and returns their number.
+///
+/// `index` is the position of the table in the file. If `resume_after` is set, the entries up to
+/// that raw key were already committed, and the table isn't cleared or checked again.
+#[allow(clippy::too_many_arguments)]
+fn import_table<T: Table, DB: Database>(
+    db: &DB,
+    reader: &mut impl Read,
+    clear: bool,
+    dupsort: bool,
+    etl: &EtlConfig,
+    commit_threshold: usize,
+    index: u64,
+    resume_after: Option<Vec<u8>>,
+) -> eyre::Result<usize> {
+    let mut collector =
+        Collector::<RawKey<T::Key>, RawValue<T::Value>>::new(etl.file_size, etl.dir.clone());
+    while let Some(key) = read_bytes(reader)? {
+        let value = read_bytes(reader)?
+            .ok_or_else(|| eyre::eyre!("Missing the value of an entry of table {}", T::NAME))?;
+        collector.insert(RawKey::from_vec(key), RawValue::from_vec(value))?;
+    }
+
+    let mut tx = db.tx_mut()?;
+    if resume_after.is_none() {
+        if clear {
+            tx.clear::<T>()?;
+        }
+        if dupsort && tx.entries::<T>()? != 0 {
+            eyre::bail!("Table {} is not empty, use --clear to replace its entries", T::NAME)
+        }
+    }
+    let empty = tx.entries::<T>()? == 0;
+
+    // Entries can only be appended to empty tables, and the values of `DupSort` tables aren't
+    // sorted by the collector.
+    let mut cursor = tx.cursor_write::<RawTable<T>>()?;
+    let mut last_key: Option<Vec<u8>> = None;
+    let mut uncommitted = 0;
+    for entry in collector.iter()? {
+        let (key, value) = entry?;
+        if resume_after.as_ref().is_some_and(|resume_after| key <= *resume_after) {
+            continue
+        }
+
+        // All values of a `DupSort` key are committed together, so the import can resume after
+        // the key.
+        if uncommitted >= commit_threshold && last_key.as_ref() != Some(&key) {
+            drop(cursor);
+            ImportProgress { tables: index, last_key: last_key.clone() }.write(&tx)?;
+            tx.commit()?;
+            tx = db.tx_mut()?;
+            cursor = tx.cursor_write::<RawTable<T>>()?;
+            uncommitted = 0;
+        }
+
+        let (raw_key, value) = (RawKey::from_vec(key.clone()), RawValue::from_vec(value));
+        if empty && !dupsort {
+            cursor.append(raw_key, value)?;
+        } else {
+            cursor.upsert(raw_key, value)?;
+        }
+        last_key = Some(key);
+        uncommitted += 1;
+    }
+    drop(cursor);
+    ImportProgress { tables: index + 1, last_key: None }.write(&tx)?;
+    tx.commit()?;
+
+    Ok(collector.len())
+}
+
+/// Reads the entries of a table that was already imported up to its end marker, and returns their
+/// number.
+fn skip_table(reader: &mut impl Read) -> eyre::Result<usize> {
+    let mut entries = 0;
+    while read_bytes(reader)?.is_some() {
+        read_bytes(reader)?;
+        entries += 1;
+    }
+    Ok(entries)
+}
+
+/// Reads bytes prefixed by their length, or [`None`] at the end of a table.
+fn read_bytes(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let len = u32::from_le_bytes(read_array(reader)?);
+    if len == END_OF_TABLE {
+        return Ok(None)
+    }
+
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(Some(bytes))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut array = [0; N];
+    reader.read_exact(&mut array)?;
+    Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::db::export::{export_tables, KeyRange};
+    use reth_db::MemoryDatabase;
+    use reth_db_api::{cursor::DbCursorRO, models::AccountBeforeTx, table::Encode};
+    use reth_primitives::{Address, B256};
+
+    #[test]
+    fn export_import_tables() {
+        let source = MemoryDatabase::new();
+        let tx = source.tx_mut().unwrap();
+        for number in 0..10 {
+            tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8)).unwrap();
+            for address in [Address::with_last_byte(2), Address::with_last_byte(1)] {
+                tx.put::<tables::AccountChangeSets>(
+                    number,
+                    AccountBeforeTx { address, info: None },
+                )
+                .unwrap();
+            }
+        }
+        tx.commit().unwrap();
+
+        let mut file = Vec::new();
+        let tx = source.tx().unwrap();
+        let range = "3..=5".parse::<KeyRange>().unwrap();
+        let exported = export_tables(
+            &tx,
+            &[Tables::CanonicalHeaders, Tables::AccountChangeSets],
+            &range,
+            &mut file,
+        )
+        .unwrap();
+        assert_eq!(exported, vec![(Tables::CanonicalHeaders, 3), (Tables::AccountChangeSets, 6)]);
+
+        // The imported entries replace the existing ones
+        let target = MemoryDatabase::new();
+        let tx = target.tx_mut().unwrap();
+        tx.put::<tables::CanonicalHeaders>(4, B256::ZERO).unwrap();
+        tx.put::<tables::CanonicalHeaders>(20, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let etl = EtlConfig::new(Some(dir.path().to_path_buf()), EtlConfig::default_file_size());
+        let imported = import_tables(&target, &mut file.as_slice(), false, &etl, 2).unwrap();
+        assert_eq!(imported, exported);
+        assert_eq!(target.view(ImportProgress::read).unwrap().unwrap(), None);
+
+        let tx = target.tx().unwrap();
+        assert_eq!(
+            tx.cursor_read::<tables::CanonicalHeaders>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            [3, 4, 5]
+                .map(|number| (number, B256::with_last_byte(number as u8)))
+                .into_iter()
+                .chain([(20, B256::ZERO)])
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            tx.cursor_read::<tables::AccountChangeSets>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .map(|entry| entry.map(|(number, account)| (number, account.address)))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            [3, 4, 5]
+                .into_iter()
+                .flat_map(|number| {
+                    [(number, Address::with_last_byte(1)), (number, Address::with_last_byte(2))]
+                })
+                .collect::<Vec<_>>()
+        );
+        drop(tx);
+
+        // DupSort tables are only imported into if they're empty, or with `clear`
+        assert!(import_tables(&target, &mut file.as_slice(), false, &etl, 2).is_err());
+        assert_eq!(
+            target.view(ImportProgress::read).unwrap().unwrap(),
+            Some(ImportProgress { tables: 1, last_key: None })
+        );
+        // The failed import resumes after the first table, which isn't cleared
+        assert_eq!(import_tables(&target, &mut file.as_slice(), true, &etl, 2).unwrap(), exported);
+        assert_eq!(target.tx().unwrap().entries::<tables::CanonicalHeaders>().unwrap(), 4);
+        assert_eq!(target.tx().unwrap().entries::<tables::AccountChangeSets>().unwrap(), 6);
+        assert_eq!(import_tables(&target, &mut file.as_slice(), true, &etl, 2).unwrap(), exported);
+        assert_eq!(target.tx().unwrap().entries::<tables::CanonicalHeaders>().unwrap(), 3);
+    }
+
+    #[test]
+    fn resume_interrupted_import() {
+        let source = MemoryDatabase::new();
+        let tx = source.tx_mut().unwrap();
+        for number in 0..5 {
+            tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8)).unwrap();
+            for address in [Address::with_last_byte(1), Address::with_last_byte(2)] {
+                tx.put::<tables::AccountChangeSets>(
+                    number,
+                    AccountBeforeTx { address, info: None },
+                )
+                .unwrap();
+            }
+        }
+        tx.commit().unwrap();
+
+        let mut file = Vec::new();
+        let exported = export_tables(
+            &source.tx().unwrap(),
+            &[Tables::CanonicalHeaders, Tables::AccountChangeSets],
+            &KeyRange::default(),
+            &mut file,
+        )
+        .unwrap();
+
+        // The import was interrupted after committing the values of the first two blocks of the
+        // second table.
+        let target = MemoryDatabase::new();
+        let tx = target.tx_mut().unwrap();
+        for number in 0..5 {
+            tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        for number in 0..2 {
+            for address in [Address::with_last_byte(1), Address::with_last_byte(2)] {
+                tx.put::<tables::AccountChangeSets>(
+                    number,
+                    AccountBeforeTx { address, info: None },
+                )
+                .unwrap();
+            }
+        }
+        // A cleared table isn't cleared again when resuming
+        tx.put::<tables::CanonicalHeaders>(20, B256::ZERO).unwrap();
+        let progress = ImportProgress { tables: 1, last_key: Some(1u64.encode().to_vec()) };
+        progress.write(&tx).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(target.view(ImportProgress::read).unwrap().unwrap(), Some(progress));
+
+        let dir = tempfile::tempdir().unwrap();
+        let etl = EtlConfig::new(Some(dir.path().to_path_buf()), EtlConfig::default_file_size());
+        let imported = import_tables(&target, &mut file.as_slice(), true, &etl, 3).unwrap();
+        assert_eq!(imported, exported);
+
+        let tx = target.tx().unwrap();
+        assert_eq!(tx.entries::<tables::CanonicalHeaders>().unwrap(), 6);
+        assert_eq!(
+            tx.cursor_read::<tables::AccountChangeSets>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .map(|entry| entry.map(|(number, account)| (number, account.address)))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            (0..5)
+                .flat_map(|number| {
+                    [(number, Address::with_last_byte(1)), (number, Address::with_last_byte(2))]
+                })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(ImportProgress::read(&tx).unwrap(), None);
+    }
+}
@@ -0,0 +1,71 @@
+//! Command that migrates the database to the latest database version.
+
+use clap::Parser;
+use reth_config::config::EtlConfig;
+use reth_db::{
+    mdbx::DatabaseArguments,
+    migration::{Migrator, MIN_MIGRATABLE_DB_VERSION},
+    open_db,
+    version::{check_db_version_file, get_db_version, DatabaseVersionError, DB_VERSION},
+};
+use std::path::Path;
+use tracing::warn;
+
+/// The arguments for the `reth db migrate` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The number of entries a migration changes before committing its progress.
+    ///
+    /// An interrupted migration resumes from its last committed progress.
+    #[arg(long, default_value_t = 100_000)]
+    commit_threshold: u64,
+}
+
+impl Command {
+    /// Execute `db migrate` command
+    pub fn execute(
+        self,
+        db_path: &Path,
+        db_args: DatabaseArguments,
+        etl: EtlConfig,
+    ) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let version = match get_db_version(db_path) {
+            Ok(version) => version,
+            Err(DatabaseVersionError::MissingFile) => {
+                println!("Local database is uninitialized");
+                return Ok(())
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if version == DB_VERSION {
+            println!("Local database is already at the latest database version (v{DB_VERSION})");
+            return Ok(())
+        }
+        if !(MIN_MIGRATABLE_DB_VERSION..DB_VERSION).contains(&version) {
+            return Err(DatabaseVersionError::VersionMismatch { version }.into())
+        }
+
+        // The version check of `init_db` would refuse to open the database, so the database is
+        // opened directly, creating the tables that were added since its version.
+        let db = open_db(db_path, db_args)?;
+        db.create_tables()?;
+
+        let migrator = Migrator::new(&db, db_path)
+            .with_etl(etl.file_size, etl.dir)
+            .with_commit_threshold(self.commit_threshold);
+        for migration in migrator.pending()? {
+            println!("Pending migration to v{}: {}", migration.version(), migration.description());
+        }
+
+        let migrations = migrator.run()?;
+        check_db_version_file(db_path)?;
+        println!(
+            "Migrated the database from v{version} to v{DB_VERSION} with {migrations} migrations"
+        );
+
+        Ok(())
+    }
+}
@@ -5,17 +5,27 @@ use crate::{
     utils::DbTool,
 };
 use clap::{Parser, Subcommand};
-use reth_db::version::{get_db_version, DatabaseVersionError, DB_VERSION};
+use human_bytes::human_bytes;
+use reth_db::{
+    compact_db,
+    version::{get_db_version, DatabaseVersionError, DB_VERSION},
+};
 use std::io::{self, Write};
 
 mod checksum;
 mod clear;
 mod diff;
+mod dump_state;
+mod export;
 mod get;
+mod import;
 mod list;
+mod migrate;
+mod static_file;
 mod stats;
 /// DB List TUI
 mod tui;
+mod verify;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -40,6 +50,16 @@ pub enum Subcommands {
     Diff(diff::Command),
     /// Gets the content of a table for the given key
     Get(get::Command),
+    /// Dumps the state at a block in the JSONL format consumed by `init-state`
+    DumpState(dump_state::Command),
+    /// Checks the consistency between database tables
+    Verify(verify::Command),
+    /// Exports tables, or a range of their keys, into a portable file
+    Export(export::Command),
+    /// Imports tables from a file written by `reth db export`
+    Import(import::Command),
+    /// Static file maintenance commands
+    StaticFile(static_file::Command),
     /// Deletes all database entries
     Drop {
         /// Bypasses the interactive confirmation and drops the database directly
@@ -48,6 +68,10 @@ pub enum Subcommands {
     },
     /// Deletes all table entries
     Clear(clear::Command),
+    /// Migrates the database to the latest database version
+    Migrate(migrate::Command),
+    /// Compacts the database offline by copying it without free pages and swapping the copy in
+    Compact,
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -98,6 +122,32 @@ impl Command {
                     command.execute(&tool)?;
                 });
             }
+            Subcommands::DumpState(command) => {
+                db_ro_exec!(self.env, tool, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::Verify(command) => {
+                let access_rights =
+                    if command.repair { AccessRights::RW } else { AccessRights::RO };
+                let Environment { provider_factory, config, .. } = self.env.init(access_rights)?;
+                command.execute(provider_factory, config)?;
+            }
+            Subcommands::Export(command) => {
+                db_ro_exec!(self.env, tool, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::Import(command) => {
+                let Environment { provider_factory, config, .. } =
+                    self.env.init(AccessRights::RW)?;
+                command.execute(provider_factory, config.stages.etl)?;
+            }
+            Subcommands::StaticFile(command) => {
+                let Environment { provider_factory, config, .. } =
+                    self.env.init(command.access_rights())?;
+                command.execute(provider_factory, config)?;
+            }
             Subcommands::Drop { force } => {
                 if !force {
                     // Ask for confirmation
@@ -122,6 +172,18 @@ impl Command {
                 let Environment { provider_factory, .. } = self.env.init(AccessRights::RW)?;
                 command.execute(provider_factory)?;
             }
+            Subcommands::Migrate(command) => {
+                let config = self.env.load_config(&data_dir);
+                command.execute(&db_path, self.env.db.database_args(), config.stages.etl)?;
+            }
+            Subcommands::Compact => {
+                let (before, after) = compact_db(&db_path, self.env.db.database_args())?;
+                println!(
+                    "Compacted the database: {} -> {}",
+                    human_bytes(before as f64),
+                    human_bytes(after as f64)
+                );
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
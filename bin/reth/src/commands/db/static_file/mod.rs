@@ -0,0 +1,82 @@
+//! Static file maintenance commands
+
+use crate::commands::common::AccessRights;
+use clap::{Parser, Subcommand};
+use reth_config::Config;
+use reth_db::DatabaseEnv;
+use reth_primitives::StaticFileSegment;
+use reth_provider::ProviderFactory;
+use reth_static_file_types::SegmentRangeInclusive;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+mod recompress;
+mod verify;
+
+/// The arguments for the `reth db static-file` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    #[command(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Subcommand, Debug)]
+/// `reth db static-file` subcommands
+pub enum Subcommands {
+    /// Checks the integrity of the static files
+    Verify(verify::Command),
+    /// Trains zstd dictionaries for a segment and recompresses its static files with them
+    Recompress(recompress::Command),
+}
+
+impl Command {
+    /// Returns the access rights the subcommand needs.
+    pub(crate) const fn access_rights(&self) -> AccessRights {
+        match &self.command {
+            Subcommands::Verify(command) if command.repair => AccessRights::RW,
+            Subcommands::Verify(_) => AccessRights::RO,
+            Subcommands::Recompress(command) if command.dry_run => AccessRights::RO,
+            Subcommands::Recompress(_) => AccessRights::RW,
+        }
+    }
+
+    /// Execute `db static-file` command
+    pub fn execute(
+        self,
+        provider_factory: ProviderFactory<Arc<DatabaseEnv>>,
+        config: Config,
+    ) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Verify(command) => command.execute(provider_factory, config),
+            Subcommands::Recompress(command) => command.execute(provider_factory),
+        }
+    }
+}
+
+/// Returns the static files of the segments in the directory, ordered by block range.
+fn static_files(
+    directory: &Path,
+    segments: &[StaticFileSegment],
+) -> eyre::Result<BTreeMap<StaticFileSegment, Vec<(SegmentRangeInclusive, PathBuf)>>> {
+    let mut static_files = BTreeMap::<_, Vec<_>>::new();
+    for entry in reth_fs_util::read_dir(directory)? {
+        let path = entry?.path();
+        let Some((segment, range)) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(StaticFileSegment::parse_filename)
+        else {
+            continue
+        };
+        if segments.contains(&segment) {
+            static_files.entry(segment).or_default().push((range, path));
+        }
+    }
+    for files in static_files.values_mut() {
+        files.sort_by_key(|(range, _)| range.start());
+    }
+    Ok(static_files)
+}
@@ -0,0 +1,262 @@
+//! Command that trains zstd dictionaries for a static file segment and recompresses it.
+
+use super::static_files;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_db::DatabaseEnv;
+use reth_nippy_jar::{
+    compression::{Compression, Compressors, Zstd},
+    NippyJar, NippyJarCursor,
+};
+use reth_primitives::StaticFileSegment;
+use reth_provider::{ProviderFactory, StaticFileProviderFactory};
+use reth_static_file_types::SegmentHeader;
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Every n-th sampled row is held out of the training set to evaluate the dictionaries.
+const EVALUATION_INTERVAL: usize = 10;
+
+/// Sampled values of each column.
+type ColumnSamples = Vec<Vec<Vec<u8>>>;
+
+/// The arguments for the `reth db static-file recompress` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The segment to recompress.
+    #[arg(value_enum)]
+    segment: StaticFileSegment,
+
+    /// The number of rows sampled evenly across the static files of the segment to train and
+    /// evaluate the dictionaries.
+    #[arg(long, default_value_t = 100_000)]
+    samples: usize,
+
+    /// The maximum size of the dictionary of each column, in bytes.
+    #[arg(long, default_value_t = 5_000_000)]
+    max_dict_size: usize,
+
+    /// Only trains and evaluates the dictionaries, without rewriting the static files.
+    #[arg(long)]
+    pub(crate) dry_run: bool,
+}
+
+impl Command {
+    /// Execute `db static-file recompress` command
+    pub fn execute(self, provider_factory: ProviderFactory<Arc<DatabaseEnv>>) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let static_file_provider = provider_factory.static_file_provider();
+        let files = static_files(static_file_provider.directory(), &[self.segment])?
+            .remove(&self.segment)
+            .unwrap_or_default();
+        if files.is_empty() {
+            eyre::bail!("No {} static files found", self.segment.as_str())
+        }
+
+        let jars = files
+            .iter()
+            .map(|(_, path)| NippyJar::<SegmentHeader>::load(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let total_rows = jars.iter().map(|jar| jar.rows()).sum::<usize>();
+        let columns = self.segment.columns();
+
+        info!(target: "reth::cli", segment = self.segment.as_str(), files = jars.len(), total_rows, "Sampling static files");
+        let (training, evaluation) = sample_rows(&jars, columns, self.samples.max(1))?;
+        if evaluation[0].is_empty() {
+            eyre::bail!("Not enough rows to train and evaluate the dictionaries")
+        }
+
+        let training_rows = training[0].len();
+        let started = Instant::now();
+        let mut zstd = Zstd::new(true, self.max_dict_size, columns);
+        zstd.prepare_compression(training).wrap_err("Failed to train the dictionaries")?;
+        println!(
+            "Trained the dictionaries on {training_rows} rows in {:?}, evaluating them on {} rows",
+            started.elapsed(),
+            evaluation[0].len()
+        );
+
+        evaluate(&zstd, &evaluation)?;
+
+        if self.dry_run {
+            return Ok(())
+        }
+
+        // Each static file is replaced atomically, so an interrupted run leaves every static file
+        // either in its previous or its new compression.
+        let (mut total_before, mut total_after) = (0, 0);
+        for (range, path) in &files {
+            let before = data_size(path)?;
+            static_file_provider.recompress_jar(
+                self.segment,
+                range,
+                Some(Compressors::Zstd(zstd.clone())),
+            )?;
+            let after = data_size(path)?;
+
+            println!(
+                "Recompressed {}: {} -> {}",
+                path.display(),
+                human_bytes(before as f64),
+                human_bytes(after as f64)
+            );
+            total_before += before;
+            total_after += after;
+        }
+        println!(
+            "Recompressed {} static files: {} -> {}",
+            files.len(),
+            human_bytes(total_before as f64),
+            human_bytes(total_after as f64)
+        );
+
+        Ok(())
+    }
+}
+
+/// Samples rows evenly across the jars, and splits their values per column into a training and
+/// an evaluation set.
+fn sample_rows(
+    jars: &[NippyJar<SegmentHeader>],
+    columns: usize,
+    samples: usize,
+) -> eyre::Result<(ColumnSamples, ColumnSamples)> {
+    let total_rows = jars.iter().map(|jar| jar.rows()).sum::<usize>();
+    let step = (total_rows / samples).max(1);
+
+    let mut training = vec![Vec::new(); columns];
+    let mut evaluation = vec![Vec::new(); columns];
+    let (mut index, mut sampled) = (0, 0);
+    for jar in jars {
+        let mut cursor = NippyJarCursor::new(jar)?;
+        // first row of the jar that is a multiple of `step` across all jars
+        let mut row = (step - index % step) % step;
+        while row < jar.rows() {
+            let Some(values) = cursor.row_by_number(row)? else { break };
+            let set = if sampled % EVALUATION_INTERVAL == EVALUATION_INTERVAL - 1 {
+                &mut evaluation
+            } else {
+                &mut training
+            };
+            for (column, value) in values.into_iter().enumerate() {
+                set[column].push(value.to_vec());
+            }
+            sampled += 1;
+            row += step;
+        }
+        index += jar.rows();
+    }
+
+    Ok((training, evaluation))
+}
+
+/// Prints the compression ratio of the evaluation set with and without the dictionaries, and the
+/// decode throughput with the dictionaries.
+fn evaluate(zstd: &Zstd, evaluation: &[Vec<Vec<u8>>]) -> eyre::Result<()> {
+    let plain = Zstd::new(false, 0, evaluation.len());
+    let dictionary_sizes = zstd.dictionary_sizes();
+    let mut compressors = zstd.compressors()?.unwrap_or_default();
+    let mut decompressors = zstd.decompressors()?;
+
+    let mut table = ComfyTable::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.set_header([
+        "Column",
+        "Dictionary Size",
+        "Raw Size",
+        "Zstd Size",
+        "Zstd Ratio",
+        "Zstd Dictionary Size",
+        "Zstd Dictionary Ratio",
+        "Decode Throughput",
+    ]);
+
+    for (column, values) in evaluation.iter().enumerate() {
+        let (mut raw, mut without_dict) = (0, 0);
+        let mut compressed = Vec::with_capacity(values.len());
+        for value in values {
+            raw += value.len();
+            without_dict += plain.compress(value)?.len();
+            compressed.push(compressors[column].compress(value)?);
+        }
+        let with_dict = compressed.iter().map(Vec::len).sum::<usize>();
+
+        let mut elapsed = Duration::ZERO;
+        for (value, compressed) in values.iter().zip(&compressed) {
+            let started = Instant::now();
+            let decompressed = decompressors[column].decompress(compressed, value.len())?;
+            elapsed += started.elapsed();
+            if decompressed != *value {
+                eyre::bail!("Value of column {column} changed after a round trip")
+            }
+        }
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new(column))
+            .add_cell(Cell::new(human_bytes(dictionary_sizes[column] as f64)))
+            .add_cell(Cell::new(human_bytes(raw as f64)))
+            .add_cell(Cell::new(human_bytes(without_dict as f64)))
+            .add_cell(Cell::new(format!("{:.2}", raw as f64 / without_dict.max(1) as f64)))
+            .add_cell(Cell::new(human_bytes(with_dict as f64)))
+            .add_cell(Cell::new(format!("{:.2}", raw as f64 / with_dict.max(1) as f64)))
+            .add_cell(Cell::new(format!(
+                "{}/s",
+                human_bytes(raw as f64 / elapsed.as_secs_f64().max(f64::EPSILON))
+            )));
+        table.add_row(row);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
+/// Returns the size of the data file of a static file.
+fn data_size(path: &Path) -> eyre::Result<u64> {
+    Ok(reth_fs_util::metadata(path)?.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_nippy_jar::ColumnResult;
+    use reth_static_file_types::SegmentRangeInclusive;
+
+    #[test]
+    fn sample_rows_across_static_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let jars = [0u8..10, 10..25]
+            .into_iter()
+            .enumerate()
+            .map(|(index, values)| {
+                let rows = values.len() as u64;
+                let header = SegmentHeader::new(
+                    SegmentRangeInclusive::new(0, 0),
+                    None,
+                    None,
+                    StaticFileSegment::Headers,
+                );
+                NippyJar::new(1, &dir.path().join(index.to_string()), header)
+                    .freeze(vec![values.map(|value| ColumnResult::Ok(vec![value]))], rows)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // every 4th row, continuing across static files
+        let (training, evaluation) = sample_rows(&jars, 1, 6).unwrap();
+        assert_eq!(training, vec![[0u8, 4, 8, 12, 16, 20, 24].map(|value| vec![value]).to_vec()]);
+        assert!(evaluation[0].is_empty());
+
+        // every row, holding out every 10th one
+        let (training, evaluation) = sample_rows(&jars, 1, 25).unwrap();
+        assert_eq!(training[0].len(), 23);
+        assert_eq!(evaluation, vec![vec![vec![9u8], vec![19]]]);
+    }
+}
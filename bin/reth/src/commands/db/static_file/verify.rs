@@ -0,0 +1,731 @@
+//! Command that verifies the integrity of the static files.
+
+use super::static_files;
+use crate::commands::stage::unwind::build_pipeline;
+use clap::{Parser, ValueEnum};
+use reth_config::Config;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    models::{AccountChangeSet, CompactU256, StorageChangeSet},
+    table::{Decompress, Table},
+    transaction::{DbTx, DbTxMut},
+};
+use reth_nippy_jar::{NippyJar, NippyJarCursor, NippyJarError};
+use reth_primitives::{
+    BlockHash, BlockNumber, Header, Receipt, StaticFileSegment, TransactionSignedNoHash, TxNumber,
+};
+use reth_provider::{
+    providers::StaticFileWriter, BlockHashReader, BlockReader, DatabaseProvider, ProviderFactory,
+    ProviderResult, PruneCheckpointReader, StageCheckpointReader, StageCheckpointWriter,
+    StaticFileProviderFactory, TransactionsProvider,
+};
+use reth_prune_types::PruneSegment;
+use reth_stages::{StageCheckpoint, StageId};
+use reth_static_file_types::{SegmentHeader, SegmentRangeInclusive};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt,
+    ops::{Range, RangeInclusive},
+    path::Path,
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+/// The arguments for the `reth db static-file verify` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The segments to verify. Verifies all segments by default.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    segments: Vec<StaticFileSegment>,
+
+    /// The maximum number of problems printed per segment.
+    #[arg(long, default_value_t = 100)]
+    max_reports: usize,
+
+    /// Repairs the corrupted data by truncating the latest static file of a segment from the
+    /// first corrupted block, and unwinding all stages to the block before it.
+    ///
+    /// The stages run again from that block on the next node start. Corruption of older static
+    /// files, or of the changeset static files, can't be repaired and requires a resync.
+    #[arg(long)]
+    pub(crate) repair: bool,
+}
+
+/// The first corrupted block of a static file, and its first transaction if known.
+#[derive(Debug, Clone, Copy)]
+struct Corrupted {
+    block: BlockNumber,
+    tx: Option<TxNumber>,
+}
+
+impl Command {
+    /// Execute `db static-file verify` command
+    pub fn execute<DB: Database + 'static>(
+        self,
+        provider_factory: ProviderFactory<Arc<DB>>,
+        config: Config,
+    ) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let segments = if self.segments.is_empty() {
+            StaticFileSegment::value_variants().to_vec()
+        } else {
+            self.segments.clone()
+        };
+
+        let static_file_provider = provider_factory.static_file_provider();
+        let static_files = static_files(static_file_provider.directory(), &segments)?;
+
+        // the static files are checked against the database in a single transaction
+        let provider = provider_factory.provider()?.disable_long_read_transaction_safety();
+        let mut verifier = Verifier::new(&provider, self.max_reports)?;
+        let mut corrupted = Vec::new();
+        for (segment, files) in &static_files {
+            info!(target: "reth::cli", segment = segment.as_str(), files = files.len(), "Verifying static files");
+            let mut previous = None;
+            for (range, path) in files {
+                if let Some(first) =
+                    verifier.verify_static_file(*segment, *range, path, &mut previous)?
+                {
+                    corrupted.push((*segment, *range, path, first));
+                }
+            }
+        }
+
+        if corrupted.is_empty() {
+            println!("No corrupted static files found");
+            return Ok(())
+        }
+        for (segment, count) in &verifier.found {
+            println!("{}: {count} problems", segment.as_str());
+        }
+
+        if !self.repair {
+            return Ok(())
+        }
+        let indexed_transactions = verifier.indexed_transactions;
+        drop(provider);
+
+        let mut repairable = true;
+        for (segment, range, path, first) in &corrupted {
+            let name = file_name(path);
+            if segment.is_changesets() {
+                println!("Cannot repair {name}: the changesets are needed to unwind the state");
+                repairable = false;
+            } else if static_files[segment].last().map(|(range, _)| range) != Some(range) {
+                println!("Cannot repair {name}: only the latest static file of a segment can be truncated");
+                repairable = false;
+            } else if first.block == 0 {
+                println!("Cannot repair {name}: the genesis block is corrupted");
+                repairable = false;
+            }
+        }
+        if !repairable {
+            println!("Resync the node to recover the corrupted static files");
+            return Ok(())
+        }
+
+        let provider_rw = provider_factory.provider_rw()?;
+        let tx = provider_rw.tx_ref();
+        let mut unwind_to = BlockNumber::MAX;
+        for (segment, _, _, Corrupted { block, tx: first_tx }) in corrupted {
+            println!("Truncating the {} static files from block {block}", segment.as_str());
+            let mut writer = static_file_provider.latest_writer(segment)?;
+            match segment {
+                StaticFileSegment::Headers => {
+                    let highest =
+                        static_file_provider.get_highest_static_file_block(segment).unwrap_or(0);
+
+                    // the headers stage can't read the truncated hashes to unwind their lookup
+                    delete_lookups::<tables::HeaderNumbers, _>(
+                        tx,
+                        block..=highest,
+                        |_| true,
+                        |number| static_file_provider.block_hash(number),
+                    )?;
+                    writer.prune_headers((highest + 1).saturating_sub(block))?;
+                }
+                StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+                    let first_tx = provider_rw
+                        .block_body_indices(block)?
+                        .map(|indices| indices.first_tx_num)
+                        .or(first_tx);
+                    let truncated = static_file_provider
+                        .get_highest_static_file_tx(segment)
+                        .zip(first_tx)
+                        .map(|(highest, first)| first..=highest);
+                    let to_delete = truncated.as_ref().map_or(0, |truncated| {
+                        (truncated.end() + 1).saturating_sub(*truncated.start())
+                    });
+
+                    if segment == StaticFileSegment::Transactions {
+                        // the transaction lookup stage can't read the truncated transactions to
+                        // unwind their lookup
+                        if let Some(truncated) = truncated {
+                            delete_lookups::<tables::TransactionHashNumbers, _>(
+                                tx,
+                                truncated,
+                                |tx_num| indexed_transactions.contains(&tx_num),
+                                |tx_num| {
+                                    Ok(static_file_provider
+                                        .transaction_by_id_no_hash(tx_num)?
+                                        .map(|transaction| transaction.hash()))
+                                },
+                            )?;
+                        }
+                        writer.prune_transactions(to_delete, block - 1)?;
+                    } else {
+                        writer.prune_receipts(to_delete, block - 1)?;
+                    }
+                }
+                StaticFileSegment::AccountChangeSets | StaticFileSegment::StorageChangeSets => {
+                    eyre::bail!("The {} static files can't be repaired", segment.as_str())
+                }
+            }
+            // checksums of the truncated values can't be trusted
+            writer.rebuild_checksums()?;
+            unwind_to = unwind_to.min(block - 1);
+        }
+
+        // The execution stage unwind reduces its progress by the gas used of every unwound block,
+        // whose header may have been truncated, so the progress is dropped from its checkpoint.
+        if let Some(checkpoint) = provider_rw.get_stage_checkpoint(StageId::Execution)? {
+            provider_rw.save_stage_checkpoint(
+                StageId::Execution,
+                StageCheckpoint::new(checkpoint.block_number),
+            )?;
+        }
+        provider_rw.commit()?;
+
+        // The truncated data can't be moved to static files first, as `unwind_blocks` does.
+        println!("Unwinding to block {unwind_to}");
+        let mut pipeline = build_pipeline(config, provider_factory)?;
+        pipeline.unwind(unwind_to, None)?;
+        println!("The stages run again from block {} on the next node start", unwind_to + 1);
+
+        Ok(())
+    }
+}
+
+/// Verifies static files and collects the found problems.
+struct Verifier<'a, TX> {
+    provider: &'a DatabaseProvider<TX>,
+    max_reports: usize,
+    /// Number of problems found per segment.
+    found: BTreeMap<StaticFileSegment, usize>,
+    /// The transactions indexed by `TransactionHashNumbers`, which are the transactions up to the
+    /// stage checkpoint that aren't pruned.
+    indexed_transactions: Range<TxNumber>,
+}
+
+impl<'a, TX: DbTx> Verifier<'a, TX> {
+    fn new(provider: &'a DatabaseProvider<TX>, max_reports: usize) -> eyre::Result<Self> {
+        let pruned = provider
+            .get_prune_checkpoint(PruneSegment::TransactionLookup)?
+            .and_then(|checkpoint| checkpoint.tx_number)
+            .map_or(0, |tx_number| tx_number + 1);
+        let checkpoint = provider
+            .get_stage_checkpoint(StageId::TransactionLookup)?
+            .unwrap_or_default()
+            .block_number;
+        let end =
+            provider.block_body_indices(checkpoint)?.map_or(0, |indices| indices.next_tx_num());
+
+        Ok(Self {
+            provider,
+            max_reports,
+            found: BTreeMap::new(),
+            indexed_transactions: pruned..end,
+        })
+    }
+
+    /// Records a problem found in a static file of the segment.
+    fn report(&mut self, segment: StaticFileSegment, message: impl fmt::Display) {
+        let count = self.found.entry(segment).or_default();
+        *count += 1;
+        if *count <= self.max_reports {
+            println!("[{}] {message}", segment.as_str());
+        } else if *count == self.max_reports + 1 {
+            println!("[{}] more problems omitted", segment.as_str());
+        }
+    }
+
+    /// Verifies a static file, given the header of the previous static file of the segment.
+    ///
+    /// Returns the first corrupted block of the static file, if any.
+    fn verify_static_file(
+        &mut self,
+        segment: StaticFileSegment,
+        range: SegmentRangeInclusive,
+        path: &Path,
+        previous: &mut Option<SegmentHeader>,
+    ) -> eyre::Result<Option<Corrupted>> {
+        let name = file_name(path);
+
+        let jar = match NippyJar::<SegmentHeader>::load(path) {
+            Ok(jar) => jar,
+            Err(err) => {
+                self.report(segment, format!("{name}: cannot load the configuration: {err}"));
+                *previous = None;
+                return Ok(Some(Corrupted { block: range.start(), tx: None }))
+            }
+        };
+        let header = jar.user_header().clone();
+        let whole_file = Corrupted { block: range.start(), tx: header.tx_start() };
+
+        let mut problems = Vec::new();
+        if header.segment() != segment {
+            problems.push(format!("the header is of segment {}", header.segment().as_str()));
+        }
+        if header.expected_block_start() != range.start() ||
+            header.expected_block_end() != range.end()
+        {
+            problems.push(format!(
+                "the header expects blocks {}..={}",
+                header.expected_block_start(),
+                header.expected_block_end()
+            ));
+        }
+        if jar.columns() != segment.columns() {
+            problems.push(format!("{} columns instead of {}", jar.columns(), segment.columns()));
+        }
+        let header_rows =
+            if segment.is_block_based() { header.block_len() } else { header.tx_len() }
+                .unwrap_or_default();
+        if jar.rows() as u64 != header_rows {
+            problems.push(format!("{} rows but the header has {header_rows}", jar.rows()));
+        }
+        if let Some(previous) = previous.as_ref() {
+            if let (Some(end), Some(start)) = (previous.block_end(), header.block_start()) {
+                if start != end + 1 {
+                    problems.push(format!(
+                        "starts at block {start} but the previous static file ends at block {end}"
+                    ));
+                }
+            }
+            if let (Some(end), Some(start)) = (previous.tx_end(), header.tx_start()) {
+                if start != end + 1 {
+                    problems.push(format!(
+                        "starts at transaction {start} but the previous static file ends at transaction {end}"
+                    ));
+                }
+            }
+        }
+        if !segment.is_block_based() {
+            problems.extend(self.check_body_indices(&header)?);
+        }
+        *previous = Some(header.clone());
+
+        let mut checksum_mismatch = false;
+        match jar.check_integrity() {
+            Ok(()) => {}
+            Err(err @ NippyJarError::ChecksumMismatch { .. }) => {
+                self.report(segment, format!("{name}: {err}"));
+                checksum_mismatch = true;
+            }
+            Err(err) => problems.push(err.to_string()),
+        }
+
+        if !problems.is_empty() {
+            for problem in problems {
+                self.report(segment, format!("{name}: {problem}"));
+            }
+            return Ok(Some(whole_file))
+        }
+
+        let block_start = header.block_start().unwrap_or(range.start());
+        let first_key =
+            if segment.is_block_based() { block_start } else { header.tx_start().unwrap_or(0) };
+
+        let mut cursor = NippyJarCursor::new(&jar)?;
+        for key in first_key.. {
+            let problem = match cursor.next_row() {
+                Ok(None) => break,
+                Ok(Some(values)) => match decode_row(segment, &values) {
+                    Ok(row) => self.check_row(key, &row)?,
+                    Err(problem) => Some(problem),
+                },
+                Err(err) => Some(err.to_string()),
+            };
+
+            if let Some(problem) = problem {
+                self.report(
+                    segment,
+                    format!("{name}: row of {} {key}: {problem}", key_name(segment)),
+                );
+                return Ok(Some(if segment.is_block_based() {
+                    Corrupted { block: key, tx: None }
+                } else {
+                    let block = self.provider.transaction_block(key)?.unwrap_or(block_start);
+                    Corrupted { block, tx: Some(key) }
+                }))
+            }
+        }
+
+        // the checksums don't tell which values are corrupted
+        Ok(checksum_mismatch.then_some(whole_file))
+    }
+
+    /// Checks the transaction range of a static file against the body indices of its blocks.
+    fn check_body_indices(&self, header: &SegmentHeader) -> eyre::Result<Option<String>> {
+        let (Some(block_start), Some(block_end)) = (header.block_start(), header.block_end())
+        else {
+            return Ok(None)
+        };
+        let (Some(first), Some(last)) = (
+            self.provider.block_body_indices(block_start)?,
+            self.provider.block_body_indices(block_end)?,
+        ) else {
+            return Ok(Some(format!("missing body indices of blocks {block_start}..={block_end}")))
+        };
+
+        let expected = first.first_tx_num..last.next_tx_num();
+        let stored = header
+            .tx_range()
+            .map_or(expected.start..expected.start, |range| range.start()..range.end() + 1);
+        if stored != expected {
+            return Ok(Some(format!(
+                "stores transactions {stored:?} but blocks {block_start}..={block_end} have transactions {expected:?}"
+            )))
+        }
+        Ok(None)
+    }
+
+    /// Checks a decoded row stored at the given block or transaction against the database.
+    fn check_row(&self, key: u64, row: &Row) -> eyre::Result<Option<String>> {
+        let tx = self.provider.tx_ref();
+        match row {
+            Row::Header(header, hash) => {
+                if header.number != key {
+                    return Ok(Some(format!("header of block {}", header.number)))
+                }
+                let computed = header.hash_slow();
+                if computed != *hash {
+                    return Ok(Some(format!("stored hash {hash} but the header hash is {computed}")))
+                }
+                let indexed = tx.get::<tables::HeaderNumbers>(*hash)?;
+                if indexed != Some(key) {
+                    return Ok(Some(format!("HeaderNumbers maps hash {hash} to {indexed:?}")))
+                }
+            }
+            Row::Transaction(transaction) => {
+                if self.indexed_transactions.contains(&key) {
+                    let hash = transaction.hash();
+                    let indexed = tx.get::<tables::TransactionHashNumbers>(hash)?;
+                    if indexed != Some(key) {
+                        return Ok(Some(format!(
+                            "TransactionHashNumbers maps hash {hash} to {indexed:?}"
+                        )))
+                    }
+                }
+            }
+            Row::Other => {}
+        }
+        Ok(None)
+    }
+}
+
+/// A decoded static file row, holding the values checked against the database.
+enum Row {
+    Header(Header, BlockHash),
+    Transaction(TransactionSignedNoHash),
+    Other,
+}
+
+/// Decodes the column values of a row of the segment.
+fn decode_row(segment: StaticFileSegment, values: &[&[u8]]) -> Result<Row, String> {
+    let decode_error = |err| format!("cannot decode the row: {err}");
+    Ok(match segment {
+        StaticFileSegment::Headers => {
+            CompactU256::decompress(values[1]).map_err(decode_error)?;
+            Row::Header(
+                Header::decompress(values[0]).map_err(decode_error)?,
+                BlockHash::decompress(values[2]).map_err(decode_error)?,
+            )
+        }
+        StaticFileSegment::Transactions => {
+            Row::Transaction(TransactionSignedNoHash::decompress(values[0]).map_err(decode_error)?)
+        }
+        StaticFileSegment::Receipts => {
+            Receipt::decompress(values[0]).map_err(decode_error)?;
+            Row::Other
+        }
+        StaticFileSegment::AccountChangeSets => {
+            AccountChangeSet::decompress(values[0]).map_err(decode_error)?;
+            Row::Other
+        }
+        StaticFileSegment::StorageChangeSets => {
+            StorageChangeSet::decompress(values[0]).map_err(decode_error)?;
+            Row::Other
+        }
+    })
+}
+
+/// Deletes the lookup entries of the truncated keys, which the stages can't unwind as they can't
+/// read the truncated values.
+///
+/// The entries are found by the lookup keys read from the static file. If one can't be read, or its
+/// entry is missing although `indexed` expects it, the static file is corrupted at that key and the
+/// whole lookup table is scanned instead.
+fn delete_lookups<T, TX>(
+    tx: &TX,
+    truncated: RangeInclusive<u64>,
+    indexed: impl Fn(u64) -> bool,
+    lookup_key: impl Fn(u64) -> ProviderResult<Option<T::Key>>,
+) -> eyre::Result<()>
+where
+    T: Table<Value = u64>,
+    TX: DbTx + DbTxMut,
+{
+    let from = *truncated.start();
+    let mut keys = Vec::new();
+    for number in truncated {
+        match lookup_key(number) {
+            Ok(Some(key)) if tx.get::<T>(key.clone())? == Some(number) => keys.push(key),
+            Ok(_) if !indexed(number) => {}
+            _ => {
+                warn!(target: "reth::cli", table = T::NAME, number, "Cannot find the lookup entry of a truncated value, scanning the table");
+                return delete_lookups_from::<T>(tx, from)
+            }
+        }
+    }
+    for key in keys {
+        tx.delete::<T>(key, None)?;
+    }
+    Ok(())
+}
+
+/// Deletes the entries of a lookup table that point to `from` or above.
+fn delete_lookups_from<T: Table<Value = u64>>(tx: &impl DbTxMut, from: u64) -> eyre::Result<()> {
+    let mut cursor = tx.cursor_write::<T>()?;
+    let mut walker = cursor.walk(None)?;
+    while let Some((_, value)) = walker.next().transpose()? {
+        if value >= from {
+            walker.delete_current()?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns what the rows of the segment are keyed by.
+const fn key_name(segment: StaticFileSegment) -> &'static str {
+    if segment.is_block_based() {
+        "block"
+    } else {
+        "transaction"
+    }
+}
+
+fn file_name(path: &Path) -> Cow<'_, str> {
+    path.file_name().unwrap_or_default().to_string_lossy()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_common::init::init_genesis;
+    use reth_primitives::{Genesis, B256, EMPTY_ROOT_HASH};
+    use reth_provider::{test_utils::create_test_provider_factory_with_chain_spec, BlockNumReader};
+    use reth_testing_utils::generators::{self, random_block};
+
+    /// Creates a database with an empty genesis state and the given number of blocks on top, each
+    /// with two transactions, whose headers, transactions and receipts are in static files.
+    fn seeded_factory(blocks: u64) -> ProviderFactory<Arc<TempDatabase<DatabaseEnv>>> {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().genesis(Genesis::default()).build());
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec);
+        let mut parent = init_genesis(factory.clone()).unwrap();
+
+        let mut rng = generators::rng();
+        let blocks = (1..=blocks)
+            .map(|number| {
+                let mut block =
+                    random_block(&mut rng, number, Some(parent), Some(2), Some(0)).unseal();
+                block.header.state_root = EMPTY_ROOT_HASH;
+                let block = block.seal_slow();
+                parent = block.hash();
+                block
+            })
+            .collect::<Vec<_>>();
+        let tip = blocks.len() as u64;
+
+        let static_file_provider = factory.static_file_provider();
+        let provider_rw = factory.provider_rw().unwrap();
+        for block in &blocks {
+            provider_rw
+                .insert_historical_block(block.clone().seal_with_senders().unwrap(), None)
+                .unwrap();
+        }
+        static_file_provider.commit().unwrap();
+
+        for segment in [StaticFileSegment::Transactions, StaticFileSegment::Receipts] {
+            let mut writer = static_file_provider.latest_writer(segment).unwrap();
+            writer.increment_block(segment, 0).unwrap();
+            let mut tx_num = 0;
+            for block in &blocks {
+                for transaction in &block.body {
+                    if segment == StaticFileSegment::Transactions {
+                        writer.append_transaction(tx_num, transaction.clone().into()).unwrap();
+                    } else {
+                        writer.append_receipt(tx_num, Receipt::default()).unwrap();
+                    }
+                    tx_num += 1;
+                }
+                writer.increment_block(segment, block.number).unwrap();
+            }
+            writer.commit().unwrap();
+        }
+
+        // the transactions were moved to the static files
+        provider_rw.tx_ref().clear::<tables::Transactions>().unwrap();
+        provider_rw.update_pipeline_stages(tip, false).unwrap();
+        provider_rw.commit().unwrap();
+
+        factory
+    }
+
+    /// Verifies all static files and returns the first corrupted block and transaction per
+    /// corrupted static file.
+    fn verify<DB: Database>(
+        factory: &ProviderFactory<DB>,
+    ) -> Vec<(StaticFileSegment, BlockNumber, Option<TxNumber>)> {
+        let static_files = static_files(
+            factory.static_file_provider().directory(),
+            StaticFileSegment::value_variants(),
+        )
+        .unwrap();
+        let provider = factory.provider().unwrap();
+        let mut verifier = Verifier::new(&provider, 0).unwrap();
+
+        let mut corrupted = Vec::new();
+        for (segment, files) in &static_files {
+            let mut previous = None;
+            for (range, path) in files {
+                if let Some(Corrupted { block, tx }) =
+                    verifier.verify_static_file(*segment, *range, path, &mut previous).unwrap()
+                {
+                    corrupted.push((*segment, block, tx));
+                }
+            }
+        }
+        corrupted
+    }
+
+    #[test]
+    fn parse_segments() {
+        let cmd = Command::parse_from(["reth", "--segments", "headers,receipts", "--repair"]);
+        assert_eq!(cmd.segments, vec![StaticFileSegment::Headers, StaticFileSegment::Receipts]);
+        assert!(cmd.repair);
+    }
+
+    #[test]
+    fn verify_finds_the_first_corrupted_row() {
+        let factory = seeded_factory(5);
+        assert_eq!(verify(&factory), Vec::new());
+
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        tx.delete::<tables::HeaderNumbers>(provider_rw.block_hash(3).unwrap().unwrap(), None)
+            .unwrap();
+        let last_tx = provider_rw.block_body_indices(4).unwrap().unwrap().last_tx_num();
+        let transaction = provider_rw.transaction_by_id(last_tx).unwrap().unwrap();
+        tx.delete::<tables::TransactionHashNumbers>(transaction.hash(), None).unwrap();
+        provider_rw.commit().unwrap();
+
+        assert_eq!(
+            verify(&factory),
+            vec![
+                (StaticFileSegment::Headers, 3, None),
+                (StaticFileSegment::Transactions, 4, Some(last_tx))
+            ]
+        );
+    }
+
+    #[test]
+    fn repair_truncates_the_static_files_and_unwinds() {
+        let factory = seeded_factory(5);
+
+        let provider_rw = factory.provider_rw().unwrap();
+        let hash = provider_rw.block_hash(3).unwrap().unwrap();
+        provider_rw.tx_ref().delete::<tables::HeaderNumbers>(hash, None).unwrap();
+        provider_rw.commit().unwrap();
+
+        let cmd = Command::parse_from(["reth", "--repair"]);
+        cmd.execute(factory.clone(), Config::default()).unwrap();
+
+        let static_file_provider = factory.static_file_provider();
+        for segment in [
+            StaticFileSegment::Headers,
+            StaticFileSegment::Transactions,
+            StaticFileSegment::Receipts,
+        ] {
+            assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(2));
+        }
+        assert_eq!(
+            static_file_provider.get_highest_static_file_tx(StaticFileSegment::Transactions),
+            Some(3)
+        );
+        assert_eq!(factory.last_block_number().unwrap(), 2);
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.tx_ref().entries::<tables::HeaderNumbers>().unwrap(), 3);
+        assert_eq!(provider.tx_ref().entries::<tables::TransactionHashNumbers>().unwrap(), 4);
+        drop(provider);
+        assert_eq!(verify(&factory), Vec::new());
+    }
+
+    #[test]
+    fn delete_lookups_of_truncated_transactions() {
+        let factory = seeded_factory(3);
+        let static_file_provider = factory.static_file_provider();
+        let lookup_key = |tx_num| -> ProviderResult<_> {
+            Ok(static_file_provider
+                .transaction_by_id_no_hash(tx_num)?
+                .map(|transaction| transaction.hash()))
+        };
+
+        // the entries are deleted by the hashes of the truncated transactions
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        delete_lookups::<tables::TransactionHashNumbers, _>(tx, 4..=5, |_| true, lookup_key)
+            .unwrap();
+        let mut remaining = tx
+            .cursor_read::<tables::TransactionHashNumbers>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .map(|entry| entry.unwrap().1)
+            .collect::<Vec<_>>();
+        remaining.sort_unstable();
+        assert_eq!(remaining, vec![0, 1, 2, 3]);
+
+        // a missing entry of an indexed transaction means it's corrupted, so the table is scanned
+        let hash = lookup_key(3).unwrap().unwrap();
+        tx.delete::<tables::TransactionHashNumbers>(hash, None).unwrap();
+        tx.put::<tables::TransactionHashNumbers>(B256::with_last_byte(1), 3).unwrap();
+        delete_lookups::<tables::TransactionHashNumbers, _>(tx, 2..=3, |_| true, lookup_key)
+            .unwrap();
+        assert_eq!(tx.entries::<tables::TransactionHashNumbers>().unwrap(), 2);
+
+        // unless the transaction isn't expected to be indexed
+        let hash = lookup_key(1).unwrap().unwrap();
+        tx.delete::<tables::TransactionHashNumbers>(hash, None).unwrap();
+        tx.put::<tables::TransactionHashNumbers>(B256::with_last_byte(1), 1).unwrap();
+        delete_lookups::<tables::TransactionHashNumbers, _>(
+            tx,
+            0..=1,
+            |tx_num| tx_num != 1,
+            lookup_key,
+        )
+        .unwrap();
+        assert_eq!(
+            tx.get::<tables::TransactionHashNumbers>(B256::with_last_byte(1)).unwrap(),
+            Some(1)
+        );
+        assert_eq!(tx.entries::<tables::TransactionHashNumbers>().unwrap(), 1);
+    }
+}
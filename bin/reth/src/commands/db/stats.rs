@@ -118,7 +118,8 @@ impl Command {
                 .add_cell(Cell::new(human_bytes(total_size as f64)));
             table.add_row(row);
 
-            let freelist = tx.inner.env().freelist()?;
+            let env = tx.inner.env();
+            let freelist = env.freelist()?;
             let pagesize = tx.inner.db_stat(&mdbx::Database::freelist_db())?.page_size() as usize;
             let freelist_size = freelist * pagesize;
 
@@ -131,6 +132,31 @@ impl Command {
                 .add_cell(Cell::new(human_bytes(freelist_size as f64)));
             table.add_row(row);
 
+            // Space that `reth db compact` would give back: free pages, and the data file beyond
+            // the last used page.
+            let info = env.info()?;
+            let file_size = info.geometry().current() as usize;
+            let used_pages = (info.last_pgno() + 1).saturating_sub(freelist);
+            let reclaimable_size = file_size.saturating_sub(used_pages * pagesize);
+
+            let mut row = Row::new();
+            row.add_cell(Cell::new("Data File"))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(human_bytes(file_size as f64)));
+            table.add_row(row);
+
+            let mut row = Row::new();
+            row.add_cell(Cell::new("Reclaimable"))
+                .add_cell(Cell::new(reclaimable_size / pagesize))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(human_bytes(reclaimable_size as f64)));
+            table.add_row(row);
+
             Ok::<(), eyre::Report>(())
         })??;
 
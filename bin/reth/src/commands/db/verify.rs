@@ -0,0 +1,752 @@
+//! Command that checks invariants across database tables.
+
+use crate::commands::stage::unwind::unwind_blocks;
+use clap::{Parser, ValueEnum};
+use reth_config::Config;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    models::{
+        storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress, ShardedKey,
+    },
+    transaction::{DbTx, DbTxMut},
+};
+use reth_primitives::{keccak256, BlockNumber, StaticFileSegment};
+use reth_provider::{
+    BlockHashReader, BlockNumReader, BlockReader, DatabaseProvider, HeaderProvider, ProviderError,
+    ProviderFactory, PruneCheckpointReader, StageCheckpointReader, StageCheckpointWriter,
+    TransactionsProvider,
+};
+use reth_prune_types::PruneSegment;
+use reth_stages::{StageCheckpoint, StageId};
+use reth_trie::{trie_cursor::noop::NoopTrieCursorFactory, StateRoot};
+use std::{collections::BTreeMap, fmt, sync::Arc};
+use tracing::{info, warn};
+
+/// Number of transactions that are loaded at once when checking the transaction lookup.
+const TRANSACTIONS_CHUNK_LEN: u64 = 100_000;
+
+/// The arguments for the `reth db verify` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The checks to run. Runs all checks by default.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    checks: Vec<Check>,
+
+    /// The maximum number of inconsistencies printed per check.
+    #[arg(long, default_value_t = 100)]
+    max_reports: usize,
+
+    /// Repairs the found inconsistencies by unwinding the affected stages.
+    ///
+    /// Inconsistencies of specific blocks are repaired by unwinding all stages to the block
+    /// before the first affected block. Inconsistencies of the hashed state and the state trie
+    /// are repaired by clearing them, so the hashing and merkle stages rebuild them.
+    #[arg(long)]
+    pub(crate) repair: bool,
+}
+
+/// A consistency check of the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Check {
+    /// `CanonicalHeaders` against `HeaderNumbers`.
+    Headers,
+    /// Block body indices against the stored transactions.
+    Bodies,
+    /// `TransactionHashNumbers` against the stored transactions.
+    TransactionLookup,
+    /// Account and storage changesets against the history indices.
+    History,
+    /// The hashed state against the plain state.
+    HashedState,
+    /// The state root of the hashed state and the state trie against the tip header.
+    StateRoot,
+}
+
+impl Check {
+    /// Returns true if inconsistencies found by this check are repaired by rebuilding the hashed
+    /// state and the state trie.
+    const fn repaired_by_rehash(&self) -> bool {
+        matches!(self, Self::HashedState | Self::StateRoot)
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.to_possible_value().expect("no skipped variants");
+        f.write_str(name.get_name())
+    }
+}
+
+impl Command {
+    /// Execute `db verify` command
+    pub fn execute<DB: Database + 'static>(
+        self,
+        provider_factory: ProviderFactory<Arc<DB>>,
+        config: Config,
+    ) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let checks = if self.checks.is_empty() {
+            Check::value_variants().to_vec()
+        } else {
+            self.checks.clone()
+        };
+
+        // the checks walk entire tables in a single transaction
+        let provider = provider_factory.provider()?.disable_long_read_transaction_safety();
+        let mut verifier = Verifier::new(&provider, self.max_reports);
+        for check in checks {
+            verifier.run(check)?;
+        }
+
+        let Verifier { found, first_affected_block, rehash, .. } = verifier;
+        if found.is_empty() {
+            println!("No inconsistencies found");
+            return Ok(())
+        }
+        for (check, count) in &found {
+            println!("{check}: {count} inconsistencies");
+        }
+
+        if !self.repair {
+            return Ok(())
+        }
+
+        let last_block = provider.last_block_number()?;
+        drop(provider);
+
+        match first_affected_block {
+            Some(0) => println!("Cannot repair inconsistencies of the genesis block"),
+            Some(block) if block <= last_block => {
+                println!("Unwinding to block {}", block - 1);
+                unwind_blocks(config, provider_factory.clone(), block..=last_block)?;
+            }
+            Some(_) => {
+                println!("Cannot repair inconsistencies above the latest block by unwinding")
+            }
+            None => {}
+        }
+
+        if rehash {
+            println!("Clearing the hashed state and state trie");
+            let provider_rw = provider_factory.provider_rw()?;
+            let tx = provider_rw.tx_ref();
+            tx.clear::<tables::HashedAccounts>()?;
+            tx.clear::<tables::HashedStorages>()?;
+            tx.clear::<tables::AccountsTrie>()?;
+            tx.clear::<tables::StoragesTrie>()?;
+            for stage in [
+                StageId::AccountHashing,
+                StageId::StorageHashing,
+                StageId::MerkleExecute,
+                StageId::MerkleUnwind,
+            ] {
+                provider_rw.save_stage_checkpoint(stage, StageCheckpoint::default())?;
+            }
+            provider_rw.save_stage_checkpoint_progress(StageId::MerkleExecute, Vec::new())?;
+            provider_rw.commit()?;
+            println!("The hashing and merkle stages rebuild them on the next pipeline run");
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs the checks and collects the found inconsistencies.
+struct Verifier<'a, TX> {
+    provider: &'a DatabaseProvider<TX>,
+    max_reports: usize,
+    /// Number of inconsistencies found per check.
+    found: BTreeMap<Check, usize>,
+    /// The first block affected by an inconsistency.
+    first_affected_block: Option<BlockNumber>,
+    /// Whether the hashed state and state trie need to be rebuilt.
+    rehash: bool,
+}
+
+impl<'a, TX: DbTx> Verifier<'a, TX> {
+    const fn new(provider: &'a DatabaseProvider<TX>, max_reports: usize) -> Self {
+        Self {
+            provider,
+            max_reports,
+            found: BTreeMap::new(),
+            first_affected_block: None,
+            rehash: false,
+        }
+    }
+
+    /// Runs the check and records the found inconsistencies.
+    fn run(&mut self, check: Check) -> eyre::Result<()> {
+        info!(target: "reth::cli", %check, "Running check");
+        match check {
+            Check::Headers => self.check_headers(),
+            Check::Bodies => self.check_bodies(),
+            Check::TransactionLookup => self.check_transaction_lookup(),
+            Check::History => self.check_history(),
+            Check::HashedState => self.check_hashed_state(),
+            Check::StateRoot => self.check_state_root(),
+        }
+    }
+
+    /// Records an inconsistency found by the check.
+    ///
+    /// `block` is the first block affected by the inconsistency, if known.
+    fn report(&mut self, check: Check, block: Option<BlockNumber>, message: impl fmt::Display) {
+        let count = self.found.entry(check).or_default();
+        *count += 1;
+        if *count <= self.max_reports {
+            println!("[{check}] {message}");
+        } else if *count == self.max_reports + 1 {
+            println!("[{check}] more inconsistencies omitted");
+        }
+
+        if check.repaired_by_rehash() {
+            self.rehash = true;
+        } else if let Some(block) = block {
+            self.first_affected_block =
+                Some(self.first_affected_block.map_or(block, |first| first.min(block)));
+        }
+    }
+
+    fn stage_checkpoint(&self, stage: StageId) -> eyre::Result<BlockNumber> {
+        Ok(self.provider.get_stage_checkpoint(stage)?.unwrap_or_default().block_number)
+    }
+
+    /// Checks that every canonical hash maps back to its block number, and vice versa.
+    fn check_headers(&mut self) -> eyre::Result<()> {
+        let provider = self.provider;
+        let tx = provider.tx_ref();
+        let last_block = provider.last_block_number()?;
+
+        for number in 0..=last_block {
+            let Some(hash) = provider.block_hash(number)? else {
+                self.report(
+                    Check::Headers,
+                    Some(number),
+                    format!("missing hash of block {number}"),
+                );
+                continue
+            };
+            match tx.get::<tables::HeaderNumbers>(hash)? {
+                Some(indexed) if indexed == number => {}
+                indexed => self.report(
+                    Check::Headers,
+                    Some(number),
+                    format!("HeaderNumbers maps hash {hash} of block {number} to {indexed:?}"),
+                ),
+            }
+        }
+
+        for entry in tx.cursor_read::<tables::HeaderNumbers>()?.walk(None)? {
+            let (hash, number) = entry?;
+            if provider.block_hash(number)? != Some(hash) {
+                self.report(
+                    Check::Headers,
+                    Some(number),
+                    format!("HeaderNumbers maps non canonical hash {hash} to block {number}"),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the block body indices are contiguous and match the stored transactions.
+    fn check_bodies(&mut self) -> eyre::Result<()> {
+        let provider = self.provider;
+        let tx = provider.tx_ref();
+
+        let mut expected_number = 0;
+        let mut next_tx_num = 0;
+        for entry in tx.cursor_read::<tables::BlockBodyIndices>()?.walk(None)? {
+            let (number, indices) = entry?;
+            if number != expected_number {
+                self.report(
+                    Check::Bodies,
+                    Some(expected_number),
+                    format!("missing body indices of blocks {expected_number}..{number}"),
+                );
+            }
+            if indices.first_tx_num != next_tx_num {
+                self.report(
+                    Check::Bodies,
+                    Some(number),
+                    format!(
+                        "body of block {number} starts at transaction {} instead of {next_tx_num}",
+                        indices.first_tx_num
+                    ),
+                );
+            }
+            expected_number = number + 1;
+            next_tx_num = indices.next_tx_num();
+        }
+
+        let bodies_checkpoint = self.stage_checkpoint(StageId::Bodies)?;
+        if expected_number != bodies_checkpoint + 1 {
+            self.report(
+                Check::Bodies,
+                Some(expected_number.min(bodies_checkpoint + 1)),
+                format!(
+                    "body indices end at block {} but the bodies stage is at block {bodies_checkpoint}",
+                    expected_number.saturating_sub(1)
+                ),
+            );
+        }
+
+        // transactions are in the static files, followed by the ones not yet moved from the
+        // database
+        let highest_static_tx = provider
+            .static_file_provider()
+            .get_highest_static_file_tx(StaticFileSegment::Transactions);
+        let highest_db_tx =
+            tx.cursor_read::<tables::Transactions>()?.last()?.map(|(tx_num, _)| tx_num);
+        let stored_tx_count = highest_static_tx.max(highest_db_tx).map_or(0, |tx_num| tx_num + 1);
+        if stored_tx_count < next_tx_num {
+            let block = provider.transaction_block(stored_tx_count)?;
+            self.report(
+                Check::Bodies,
+                block,
+                format!(
+                    "body indices reference {next_tx_num} transactions but only {stored_tx_count} are stored"
+                ),
+            );
+        } else if stored_tx_count > next_tx_num {
+            self.report(
+                Check::Bodies,
+                None,
+                format!(
+                    "{stored_tx_count} transactions are stored but body indices only reference {next_tx_num}"
+                ),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `TransactionHashNumbers` maps the hash of every transaction to its number.
+    fn check_transaction_lookup(&mut self) -> eyre::Result<()> {
+        let provider = self.provider;
+        let tx = provider.tx_ref();
+
+        let mut entries = 0;
+        for entry in tx.cursor_read::<tables::TransactionHashNumbers>()?.walk(None)? {
+            let (hash, tx_num) = entry?;
+            entries += 1;
+            match provider.transaction_by_id_no_hash(tx_num)? {
+                Some(transaction) if transaction.hash() == hash => {}
+                Some(transaction) => {
+                    let block = provider.transaction_block(tx_num)?;
+                    self.report(
+                        Check::TransactionLookup,
+                        block,
+                        format!(
+                            "TransactionHashNumbers maps {hash} to transaction {tx_num} with hash {}",
+                            transaction.hash()
+                        ),
+                    );
+                }
+                None => self.report(
+                    Check::TransactionLookup,
+                    None,
+                    format!("TransactionHashNumbers maps {hash} to missing transaction {tx_num}"),
+                ),
+            }
+        }
+
+        // unless the lookup is pruned, every transaction up to the stage checkpoint is indexed
+        if provider.get_prune_checkpoint(PruneSegment::TransactionLookup)?.is_some() {
+            return Ok(())
+        }
+        let checkpoint = self.stage_checkpoint(StageId::TransactionLookup)?;
+        let Some(indices) = provider.block_body_indices(checkpoint)? else { return Ok(()) };
+        let tx_count = indices.next_tx_num();
+        if entries == tx_count as usize {
+            return Ok(())
+        }
+
+        let mut start = 0;
+        while start < tx_count {
+            let end = tx_count.min(start + TRANSACTIONS_CHUNK_LEN);
+            let transactions = provider.transactions_by_tx_range(start..end)?;
+            for (tx_num, transaction) in (start..).zip(transactions) {
+                let hash = transaction.hash();
+                if tx.get::<tables::TransactionHashNumbers>(hash)? != Some(tx_num) {
+                    let block = provider.transaction_block(tx_num)?;
+                    self.report(
+                        Check::TransactionLookup,
+                        block,
+                        format!("transaction {tx_num} with hash {hash} is not indexed"),
+                    );
+                }
+            }
+            start = end;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every changeset is in the history indices, and that every indexed block has a
+    /// changeset.
+    fn check_history(&mut self) -> eyre::Result<()> {
+        let provider = self.provider;
+        let tx = provider.tx_ref();
+        let static_file_provider = provider.static_file_provider();
+
+        // Changesets of the blocks before these are in static files.
+        let database_account_changesets =
+            provider.first_database_changeset(StaticFileSegment::AccountChangeSets);
+        let database_storage_changesets =
+            provider.first_database_changeset(StaticFileSegment::StorageChangeSets);
+
+        let checkpoint = self.stage_checkpoint(StageId::IndexAccountHistory)?;
+        let mut history = tx.cursor_read::<tables::AccountsHistory>()?;
+        let mut changesets = tx.cursor_dup_read::<tables::AccountChangeSets>()?;
+        let changes = static_file_provider.account_changesets_iter(0..=checkpoint)?.chain(
+            changesets
+                .walk_range(database_account_changesets..=checkpoint)?
+                .map(|entry| entry.map_err(ProviderError::from)),
+        );
+        for entry in changes {
+            let (block, AccountBeforeTx { address, .. }) = entry?;
+            let indexed = history
+                .seek(ShardedKey::new(address, block))?
+                .is_some_and(|(key, list)| key.key == address && list.contains(block));
+            if !indexed {
+                self.report(
+                    Check::History,
+                    Some(block),
+                    format!("account change of {address} in block {block} is not indexed"),
+                );
+            }
+        }
+        for entry in tx.cursor_read::<tables::AccountsHistory>()?.walk(None)? {
+            let (key, list) = entry?;
+            for block in list.iter() {
+                let changed = if block < database_account_changesets {
+                    static_file_provider.account_changeset(block)?.is_some_and(|changeset| {
+                        changeset.accounts.iter().any(|change| change.address == key.key)
+                    })
+                } else {
+                    changesets
+                        .seek_by_key_subkey(block, key.key)?
+                        .is_some_and(|change| change.address == key.key)
+                };
+                if !changed {
+                    self.report(
+                        Check::History,
+                        Some(block),
+                        format!(
+                            "AccountsHistory lists block {block} for {} without a change",
+                            key.key
+                        ),
+                    );
+                }
+            }
+        }
+
+        let checkpoint = self.stage_checkpoint(StageId::IndexStorageHistory)?;
+        let mut history = tx.cursor_read::<tables::StoragesHistory>()?;
+        let mut changesets = tx.cursor_dup_read::<tables::StorageChangeSets>()?;
+        let changes = static_file_provider.storage_changesets_iter(0..=checkpoint)?.chain(
+            changesets
+                .walk_range(BlockNumberAddress::range(database_storage_changesets..=checkpoint))?
+                .map(|entry| entry.map_err(ProviderError::from)),
+        );
+        for entry in changes {
+            let (BlockNumberAddress((block, address)), change) = entry?;
+            let indexed = history
+                .seek(StorageShardedKey::new(address, change.key, block))?
+                .is_some_and(|(key, list)| {
+                    key.address == address &&
+                        key.sharded_key.key == change.key &&
+                        list.contains(block)
+                });
+            if !indexed {
+                self.report(
+                    Check::History,
+                    Some(block),
+                    format!(
+                        "storage change of {address} slot {} in block {block} is not indexed",
+                        change.key
+                    ),
+                );
+            }
+        }
+        for entry in tx.cursor_read::<tables::StoragesHistory>()?.walk(None)? {
+            let (key, list) = entry?;
+            let (address, slot) = (key.address, key.sharded_key.key);
+            for block in list.iter() {
+                let changed = if block < database_storage_changesets {
+                    static_file_provider.storage_changeset(block)?.is_some_and(|changeset| {
+                        changeset
+                            .storages
+                            .iter()
+                            .any(|change| change.address == address && change.key == slot)
+                    })
+                } else {
+                    changesets
+                        .seek_by_key_subkey(BlockNumberAddress((block, address)), slot)?
+                        .is_some_and(|change| change.key == slot)
+                };
+                if !changed {
+                    self.report(
+                        Check::History,
+                        Some(block),
+                        format!(
+                            "StoragesHistory lists block {block} for {address} slot {slot} without a change"
+                        ),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the hashing and merkle stages are at the same block as the execution stage,
+    /// which is required for the hashed state and state trie to match the plain state.
+    fn state_stages_in_sync(&self) -> eyre::Result<bool> {
+        let execution = self.stage_checkpoint(StageId::Execution)?;
+        for stage in [StageId::AccountHashing, StageId::StorageHashing, StageId::MerkleExecute] {
+            let checkpoint = self.stage_checkpoint(stage)?;
+            if checkpoint != execution {
+                warn!(target: "reth::cli", %stage, checkpoint, execution, "Stage is not in sync with execution, skipping check");
+                return Ok(false)
+            }
+        }
+        Ok(true)
+    }
+
+    /// Checks that the hashed state matches the plain state.
+    fn check_hashed_state(&mut self) -> eyre::Result<()> {
+        if !self.state_stages_in_sync()? {
+            return Ok(())
+        }
+        let provider = self.provider;
+        let tx = provider.tx_ref();
+
+        let mut hashed_accounts = tx.cursor_read::<tables::HashedAccounts>()?;
+        for entry in tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+            let (address, account) = entry?;
+            let hashed =
+                hashed_accounts.seek_exact(keccak256(address))?.map(|(_, account)| account);
+            if hashed != Some(account) {
+                self.report(
+                    Check::HashedState,
+                    None,
+                    format!("hashed account {hashed:?} of {address} does not match {account:?}"),
+                );
+            }
+        }
+        let (plain, hashed) =
+            (tx.entries::<tables::PlainAccountState>()?, tx.entries::<tables::HashedAccounts>()?);
+        if plain != hashed {
+            self.report(
+                Check::HashedState,
+                None,
+                format!("{plain} plain accounts but {hashed} hashed accounts"),
+            );
+        }
+
+        let mut hashed_storages = tx.cursor_dup_read::<tables::HashedStorages>()?;
+        for entry in tx.cursor_dup_read::<tables::PlainStorageState>()?.walk(None)? {
+            let (address, entry) = entry?;
+            let hashed_slot = keccak256(entry.key);
+            let hashed = hashed_storages
+                .seek_by_key_subkey(keccak256(address), hashed_slot)?
+                .filter(|hashed| hashed.key == hashed_slot)
+                .map(|hashed| hashed.value);
+            if hashed != Some(entry.value) {
+                self.report(
+                    Check::HashedState,
+                    None,
+                    format!(
+                        "hashed storage {hashed:?} of {address} slot {} does not match {}",
+                        entry.key, entry.value
+                    ),
+                );
+            }
+        }
+        let (plain, hashed) =
+            (tx.entries::<tables::PlainStorageState>()?, tx.entries::<tables::HashedStorages>()?);
+        if plain != hashed {
+            self.report(
+                Check::HashedState,
+                None,
+                format!("{plain} plain storage slots but {hashed} hashed storage slots"),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Recomputes the state root from the hashed state and checks it against the tip header and
+    /// the stored state trie.
+    fn check_state_root(&mut self) -> eyre::Result<()> {
+        if !self.state_stages_in_sync()? {
+            return Ok(())
+        }
+        let provider = self.provider;
+        let tx = provider.tx_ref();
+
+        let tip = self.stage_checkpoint(StageId::MerkleExecute)?;
+        let expected = self
+            .provider
+            .header_by_number(tip)?
+            .ok_or(ProviderError::HeaderNotFound(tip.into()))?
+            .state_root;
+
+        // ignore the stored trie nodes to compute the root from the hashed state alone
+        let computed = StateRoot::from_tx(tx)
+            .with_trie_cursor_factory(NoopTrieCursorFactory)
+            .with_no_threshold()
+            .root()?;
+        if computed != expected {
+            self.report(
+                Check::StateRoot,
+                None,
+                format!("hashed state root {computed} does not match state root {expected} of block {tip}"),
+            );
+        }
+
+        let stored = StateRoot::from_tx(tx).with_no_threshold().root()?;
+        if stored != computed {
+            self.report(
+                Check::StateRoot,
+                None,
+                format!("state trie root {stored} does not match hashed state root {computed}"),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_common::init::init_genesis;
+    use reth_primitives::{Account, Genesis, B256, EMPTY_ROOT_HASH};
+    use reth_provider::{test_utils::create_test_provider_factory_with_chain_spec, BlockWriter};
+    use reth_testing_utils::generators::{self, random_block};
+
+    /// Creates a database with an empty genesis state and the given number of blocks on top, each
+    /// with two transactions.
+    fn seeded_factory(blocks: u64) -> ProviderFactory<Arc<TempDatabase<DatabaseEnv>>> {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().genesis(Genesis::default()).build());
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec);
+        let mut parent = init_genesis(factory.clone()).unwrap();
+
+        let mut rng = generators::rng();
+        let provider_rw = factory.provider_rw().unwrap();
+        for number in 1..=blocks {
+            let mut block = random_block(&mut rng, number, Some(parent), Some(2), Some(0)).unseal();
+            block.header.state_root = EMPTY_ROOT_HASH;
+            let block = block.seal_slow();
+            parent = block.hash();
+            provider_rw.insert_block(block.seal_with_senders().unwrap(), None).unwrap();
+        }
+        provider_rw.update_pipeline_stages(blocks, false).unwrap();
+        provider_rw.commit().unwrap();
+
+        factory
+    }
+
+    /// Runs all checks and returns the number of found inconsistencies per check.
+    fn verify<DB: Database>(factory: &ProviderFactory<DB>) -> BTreeMap<Check, usize> {
+        let provider = factory.provider().unwrap();
+        let mut verifier = Verifier::new(&provider, 0);
+        for check in Check::value_variants() {
+            verifier.run(*check).unwrap();
+        }
+        verifier.found
+    }
+
+    fn repair<DB: Database + 'static>(factory: &ProviderFactory<Arc<DB>>) {
+        let cmd = Command::parse_from(["reth", "--repair"]);
+        cmd.execute(factory.clone(), Config::default()).unwrap();
+    }
+
+    #[test]
+    fn parse_checks() {
+        let cmd = Command::parse_from(["reth", "--checks", "headers,state-root", "--repair"]);
+        assert_eq!(cmd.checks, vec![Check::Headers, Check::StateRoot]);
+        assert!(cmd.repair);
+        assert_eq!(Check::TransactionLookup.to_string(), "transaction-lookup");
+    }
+
+    #[test]
+    fn repair_unwinds_to_the_block_before_the_first_inconsistency() {
+        let factory = seeded_factory(5);
+        assert_eq!(verify(&factory), BTreeMap::new());
+
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        let block_4 = provider_rw.block_hash(4).unwrap().unwrap();
+        tx.delete::<tables::HeaderNumbers>(block_4, None).unwrap();
+        let indices = provider_rw.block_body_indices(3).unwrap().unwrap();
+        let transaction = provider_rw.transaction_by_id(indices.first_tx_num).unwrap().unwrap();
+        tx.delete::<tables::TransactionHashNumbers>(transaction.hash(), None).unwrap();
+        provider_rw.commit().unwrap();
+
+        assert_eq!(
+            verify(&factory),
+            BTreeMap::from([(Check::Headers, 1), (Check::TransactionLookup, 1)])
+        );
+
+        repair(&factory);
+        assert_eq!(factory.last_block_number().unwrap(), 2);
+        assert_eq!(
+            factory.provider().unwrap().get_stage_checkpoint(StageId::Finish).unwrap(),
+            Some(StageCheckpoint::new(2))
+        );
+        assert_eq!(verify(&factory), BTreeMap::new());
+    }
+
+    #[test]
+    fn repair_clears_the_hashed_state() {
+        let factory = seeded_factory(3);
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .tx_ref()
+            .put::<tables::HashedAccounts>(B256::with_last_byte(1), Account::default())
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        assert_eq!(
+            verify(&factory),
+            BTreeMap::from([(Check::HashedState, 1), (Check::StateRoot, 1)])
+        );
+
+        repair(&factory);
+        let provider = factory.provider().unwrap();
+        // the blocks are kept, only the hashed state is rebuilt
+        assert_eq!(provider.last_block_number().unwrap(), 3);
+        assert_eq!(provider.tx_ref().entries::<tables::HashedAccounts>().unwrap(), 0);
+        for stage in [StageId::AccountHashing, StageId::StorageHashing, StageId::MerkleExecute] {
+            assert_eq!(
+                provider.get_stage_checkpoint(stage).unwrap(),
+                Some(StageCheckpoint::default())
+            );
+        }
+    }
+
+    #[test]
+    fn repair_keeps_an_inconsistent_genesis_block() {
+        let factory = seeded_factory(2);
+
+        let provider_rw = factory.provider_rw().unwrap();
+        let genesis = provider_rw.block_hash(0).unwrap().unwrap();
+        provider_rw.tx_ref().delete::<tables::HeaderNumbers>(genesis, None).unwrap();
+        provider_rw.commit().unwrap();
+
+        repair(&factory);
+        assert_eq!(factory.last_block_number().unwrap(), 2);
+        assert_eq!(verify(&factory), BTreeMap::from([(Check::Headers, 1)]));
+    }
+}
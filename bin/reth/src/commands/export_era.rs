@@ -0,0 +1,231 @@
+//! Command that exports blocks from the database into Era1 archives.
+
+use crate::commands::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use reth_chainspec::{ChainSpec, Hardfork};
+use reth_downloaders::era1::{era1_file_name, Era1Block, Era1Writer, MAX_BLOCKS_PER_ERA1};
+use reth_primitives::{BlockNumber, U256};
+use reth_provider::{
+    BlockNumReader, BlockReader, ChainSpecProvider, HeaderProvider, ProviderError, ReceiptProvider,
+};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Exports blocks, receipts and total difficulty into Era1 archives.
+#[derive(Debug, Parser)]
+pub struct ExportEraCommand {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The first block to export.
+    #[arg(long, default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export. Defaults to the latest block.
+    ///
+    /// Only pre-merge blocks can be exported, so Taiko chains, which are post-merge from
+    /// genesis, are not supported.
+    #[arg(long)]
+    to: Option<BlockNumber>,
+
+    /// The directory to write the Era1 files to.
+    ///
+    /// Files are split at epoch boundaries of 8192 blocks and named
+    /// `<network>-<epoch>-<short accumulator root>.era1`.
+    #[arg(value_name = "EXPORT_DIR", verbatim_doc_comment)]
+    dir: PathBuf,
+}
+
+impl ExportEraCommand {
+    /// Execute `export-era` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        ensure_not_taiko(&self.env.chain)?;
+
+        let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+        let provider = provider_factory.provider()?;
+
+        let to = match self.to {
+            Some(to) => to,
+            None => provider.last_block_number()?,
+        };
+        if self.from > to {
+            eyre::bail!("First block {} is above the last block {to}", self.from)
+        }
+
+        // Era1 archives only hold pre-merge blocks, and the total difficulty only grows, so it's
+        // enough to check the last block
+        let difficulty = provider
+            .header_by_number(to)?
+            .ok_or(ProviderError::HeaderNotFound(to.into()))?
+            .difficulty;
+        let total_difficulty =
+            provider.header_td_by_number(to)?.ok_or(ProviderError::TotalDifficultyNotFound(to))?;
+        ensure_pre_merge(&provider_factory.chain_spec(), to, total_difficulty, difficulty)?;
+
+        let network = provider_factory.chain_spec().chain.to_string();
+        info!(target: "reth::cli", from = self.from, to, dir = %self.dir.display(), "Exporting blocks");
+        export_blocks(&provider, &network, self.from..=to, &self.dir)?;
+
+        Ok(())
+    }
+}
+
+/// Rejects Taiko chains, which have no blocks that fit into Era1 archives.
+///
+/// Era1 archives hold pre-merge blocks along with their total difficulty. Taiko chains never had a
+/// proof-of-work phase, their specs activate Paris at genesis with a terminal total difficulty of
+/// 0 and every block has a difficulty of 0, so all of their blocks, including the genesis block,
+/// are post-merge blocks.
+fn ensure_not_taiko(chain_spec: &ChainSpec) -> eyre::Result<()> {
+    if chain_spec.is_taiko() {
+        eyre::bail!(
+            "Chain {} is a Taiko chain, which is post-merge from genesis and has no blocks that can be exported to Era1 archives",
+            chain_spec.chain
+        )
+    }
+    Ok(())
+}
+
+/// Rejects the export if block `to`, and with it the blocks above, is a post-merge block.
+fn ensure_pre_merge(
+    chain_spec: &ChainSpec,
+    to: BlockNumber,
+    total_difficulty: U256,
+    difficulty: U256,
+) -> eyre::Result<()> {
+    if chain_spec.fork(Hardfork::Paris).active_at_ttd(total_difficulty, difficulty) {
+        eyre::bail!("Block {to} is a post-merge block, which can't be exported to Era1 archives")
+    }
+    Ok(())
+}
+
+/// Exports the blocks into Era1 files in `dir`, one file per epoch of 8192 blocks, and returns
+/// the paths of the written files.
+fn export_blocks(
+    provider: &(impl BlockReader + ReceiptProvider + HeaderProvider),
+    network: &str,
+    blocks: std::ops::RangeInclusive<BlockNumber>,
+    dir: &Path,
+) -> eyre::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let epoch_len = MAX_BLOCKS_PER_ERA1 as u64;
+
+    let mut paths = Vec::new();
+    let (mut start, to) = blocks.into_inner();
+    while start <= to {
+        let epoch = start / epoch_len;
+        let end = to.min((epoch + 1) * epoch_len - 1);
+
+        // the accumulator root is only known once the file is written
+        let tmp_path = dir.join(format!("{network}-{epoch:05}.era1.tmp"));
+        let mut writer = Era1Writer::new(BufWriter::new(File::create(&tmp_path)?))?;
+        for number in start..=end {
+            let block = provider
+                .block(number.into())?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(number))?;
+            let receipts = provider
+                .receipts_by_block(number.into())?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(number))?
+                .into_iter()
+                .map(|receipt| receipt.with_bloom())
+                .collect();
+            let total_difficulty = provider
+                .header_td_by_number(number)?
+                .ok_or(ProviderError::TotalDifficultyNotFound(number))?;
+            writer.append(&Era1Block { block, receipts, total_difficulty })?;
+        }
+        let accumulator = writer.finish()?;
+
+        let path = dir.join(era1_file_name(network, epoch, accumulator));
+        fs::rename(&tmp_path, &path)?;
+        info!(target: "reth::cli", blocks = ?start..=end, %accumulator, path = %path.display(), "Exported Era1 file");
+        paths.push(path);
+
+        start = end + 1;
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::import_era::verify_imported;
+    use reth_chainspec::{ChainSpecBuilder, MAINNET, TAIKO_MAINNET};
+    use reth_db::tables;
+    use reth_db_api::transaction::DbTxMut;
+    use reth_db_common::init::init_genesis;
+    use reth_downloaders::era1::Era1File;
+    use reth_primitives::{Genesis, EMPTY_ROOT_HASH};
+    use reth_provider::{test_utils::create_test_provider_factory_with_chain_spec, BlockWriter};
+    use reth_testing_utils::generators::{self, random_block, random_receipt};
+    use std::sync::Arc;
+
+    #[test]
+    fn parse_export_era_command() {
+        let args = ExportEraCommand::parse_from(["reth", "--from", "10", "--to", "20", "."]);
+        assert_eq!(args.from, 10);
+        assert_eq!(args.to, Some(20));
+    }
+
+    #[test]
+    fn rejects_taiko_chains() {
+        let err = ensure_not_taiko(&TAIKO_MAINNET).unwrap_err();
+        assert!(err.to_string().contains("Taiko"), "{err}");
+        ensure_not_taiko(&MAINNET).unwrap();
+
+        // a mainnet block below the terminal total difficulty can be exported
+        ensure_pre_merge(&MAINNET, 1, U256::from(1), U256::from(1)).unwrap();
+        let ttd = U256::from(58_750_000_000_000_000_000_000_u128);
+        assert!(ensure_pre_merge(&MAINNET, 1, ttd, U256::from(1)).is_err());
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().genesis(Genesis::default()).build());
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec);
+        let mut parent = init_genesis(factory.clone()).unwrap();
+
+        // three blocks with two transactions and their receipts each
+        let mut rng = generators::rng();
+        let provider_rw = factory.provider_rw().unwrap();
+        let mut tx_num = 0;
+        for number in 1..=3 {
+            let mut block = random_block(&mut rng, number, Some(parent), Some(2), Some(0)).unseal();
+            block.header.state_root = EMPTY_ROOT_HASH;
+            let block = block.seal_slow();
+            parent = block.hash();
+            for tx in &block.body {
+                let receipt = random_receipt(&mut rng, tx, Some(1));
+                provider_rw.tx_ref().put::<tables::Receipts>(tx_num, receipt).unwrap();
+                tx_num += 1;
+            }
+            provider_rw.insert_block(block.seal_with_senders().unwrap(), None).unwrap();
+        }
+        provider_rw.update_pipeline_stages(3, false).unwrap();
+        provider_rw.commit().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let provider = factory.provider().unwrap();
+        let paths = export_blocks(&provider, "mainnet", 0..=3, dir.path()).unwrap();
+        assert_eq!(paths.len(), 1);
+
+        let era1 = Era1File::read(&paths[0]).unwrap();
+        era1.verify().unwrap();
+        era1.verify_trusted(&[era1.accumulator]).unwrap();
+        assert_eq!((era1.start_number(), era1.end_number()), (Some(0), Some(3)));
+        for era1_block in &era1.blocks {
+            let number = era1_block.block.number;
+            assert_eq!(Some(&era1_block.block), provider.block(number.into()).unwrap().as_ref());
+        }
+
+        // the records the import checks the imported blocks against match the exported database
+        let (file_client, records) = era1.into_file_client();
+        assert_eq!(file_client.tip(), Some(parent));
+        verify_imported(&provider, &records, 0..=3, false).unwrap();
+    }
+}
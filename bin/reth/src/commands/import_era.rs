@@ -0,0 +1,226 @@
+//! Command that imports blocks from Era1 archives.
+
+use crate::{
+    commands::{
+        common::{AccessRights, Environment, EnvironmentArgs},
+        import::build_import_pipeline,
+    },
+    version::SHORT_VERSION,
+};
+use clap::Parser;
+use eyre::WrapErr;
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_downloaders::era1::{Era1File, Era1Records, ERA1_EXTENSION};
+use reth_primitives::{BlockNumber, B256};
+use reth_provider::{
+    BlockNumReader, HeaderProvider, ProviderError, ReceiptProvider, StageCheckpointReader,
+};
+use reth_prune_types::PruneModes;
+use reth_stages::StageId;
+use reth_static_file::StaticFileProducer;
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, info};
+
+/// Imports blocks from Era1 archives.
+#[derive(Debug, Parser)]
+pub struct ImportEraCommand {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// Disables stages that require state.
+    #[arg(long, verbatim_doc_comment)]
+    no_state: bool,
+
+    /// The file with the trusted accumulator roots of the chain's epochs.
+    ///
+    /// The file lists the hex encoded accumulator root of every epoch of 8192 blocks, one per
+    /// line and starting at epoch 0. Files whose accumulator root isn't the trusted root of
+    /// their epoch are rejected.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    accumulators: PathBuf,
+
+    /// The path to an Era1 file, or a directory of Era1 files, to import.
+    ///
+    /// The files of a directory are imported in the order of their names. Every file is
+    /// verified against its accumulator root, and the root against the trusted accumulators,
+    /// before its blocks are imported.
+    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl ImportEraCommand {
+    /// Execute `import-era` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        if self.no_state {
+            info!(target: "reth::cli", "Disabled stages requiring state");
+        }
+
+        let trusted_accumulators = read_accumulators(&self.accumulators)?;
+
+        let Environment { provider_factory, config, .. } = self.env.init(AccessRights::RW)?;
+
+        let consensus = Arc::new(EthBeaconConsensus::new(self.env.chain.clone()));
+        info!(target: "reth::cli", "Consensus engine initialized");
+
+        let files = if self.path.is_dir() {
+            let mut files = fs::read_dir(&self.path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            files.retain(|path| path.extension().is_some_and(|ext| ext == ERA1_EXTENSION));
+            files.sort();
+            files
+        } else {
+            vec![self.path.clone()]
+        };
+
+        let mut total_imported_blocks = 0;
+        for path in files {
+            let era1 = Era1File::read(&path)?;
+            let (Some(start), Some(end)) = (era1.start_number(), era1.end_number()) else {
+                debug!(target: "reth::cli", path = %path.display(), "Skipping empty Era1 file");
+                continue
+            };
+
+            let last_block_number = provider_factory.last_block_number()?;
+            if end <= last_block_number {
+                info!(target: "reth::cli", path = %path.display(), "Skipping already imported Era1 file");
+                continue
+            }
+
+            era1.verify()?;
+            era1.verify_trusted(&trusted_accumulators)?;
+            info!(target: "reth::cli", path = %path.display(), blocks = ?start..=end, accumulator = %era1.accumulator, "Verified Era1 file");
+
+            let (file_client, records) = era1.into_file_client();
+            let tip = file_client.tip().ok_or(eyre::eyre!("file client has no tip"))?;
+
+            let (mut pipeline, events) = build_import_pipeline(
+                &config,
+                provider_factory.clone(),
+                &consensus,
+                Arc::new(file_client),
+                StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+                self.no_state,
+            )
+            .await?;
+
+            pipeline.set_tip(tip);
+            debug!(target: "reth::cli", ?tip, "Tip manually set");
+
+            let latest_block_number = provider_factory
+                .provider()?
+                .get_stage_checkpoint(StageId::Finish)?
+                .map(|ch| ch.block_number);
+            tokio::spawn(reth_node_events::node::handle_events(
+                None,
+                latest_block_number,
+                events,
+                provider_factory.db_ref().clone(),
+            ));
+
+            info!(target: "reth::cli", "Starting sync pipeline");
+            tokio::select! {
+                res = pipeline.run() => res?,
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+
+            let imported = start.max(last_block_number + 1)..=end;
+            verify_imported(
+                &provider_factory.provider()?,
+                &records,
+                imported.clone(),
+                self.no_state,
+            )?;
+            total_imported_blocks += imported.count() as u64;
+        }
+
+        info!(target: "reth::cli", total_imported_blocks, "Era1 files imported");
+
+        Ok(())
+    }
+}
+
+/// Reads the trusted accumulator roots, one per line, ignoring empty lines.
+fn read_accumulators(path: &Path) -> eyre::Result<Vec<B256>> {
+    let file = File::open(path).wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+    BufReader::new(file)
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|line| Ok(line?.trim().parse()?))
+        .collect()
+}
+
+/// Checks the imported blocks against the total difficulties and, if the blocks were executed,
+/// the receipts of the Era1 file.
+pub(crate) fn verify_imported(
+    provider: &(impl HeaderProvider + ReceiptProvider),
+    records: &Era1Records,
+    imported: RangeInclusive<BlockNumber>,
+    no_state: bool,
+) -> eyre::Result<()> {
+    for number in imported {
+        let total_difficulty = provider
+            .header_td_by_number(number)?
+            .ok_or(ProviderError::TotalDifficultyNotFound(number))?;
+        if Some(total_difficulty) != records.total_difficulty(number) {
+            eyre::bail!("Total difficulty {total_difficulty} of block {number} does not match the Era1 file")
+        }
+
+        // the receipts are only stored if the blocks were executed and they're not pruned
+        if no_state {
+            continue
+        }
+        if let Some(receipts) = provider.receipts_by_block(number.into())? {
+            let expected = records.receipts(number).unwrap_or_default();
+            if !receipts.iter().eq(expected.iter().map(|receipt| &receipt.receipt)) {
+                eyre::bail!("Receipts of block {number} do not match the Era1 file")
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_node_core::args::utils::SUPPORTED_CHAINS;
+
+    #[test]
+    fn parse_common_import_era_command_chain_args() {
+        for chain in SUPPORTED_CHAINS {
+            let args: ImportEraCommand = ImportEraCommand::parse_from([
+                "reth",
+                "--chain",
+                chain,
+                "--accumulators",
+                "roots.txt",
+                ".",
+            ]);
+            assert_eq!(
+                Ok(args.env.chain.chain),
+                chain.parse::<reth_chainspec::Chain>(),
+                "failed to parse chain {chain}"
+            );
+        }
+    }
+
+    #[test]
+    fn read_trusted_accumulators() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("accumulators.txt");
+        let roots = [B256::repeat_byte(1), B256::repeat_byte(2)];
+        fs::write(&path, format!("{}\n\n{}\n", roots[0], roots[1])).unwrap();
+        assert_eq!(read_accumulators(&path).unwrap(), roots);
+
+        fs::write(&path, "not a root\n").unwrap();
+        assert!(read_accumulators(&path).is_err());
+    }
+}
@@ -4,7 +4,9 @@ pub mod config_cmd;
 pub mod db;
 pub mod debug_cmd;
 pub mod dump_genesis;
+pub mod export_era;
 pub mod import;
+pub mod import_era;
 pub mod import_op;
 pub mod import_receipts_op;
 
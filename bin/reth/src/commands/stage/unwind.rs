@@ -52,91 +52,105 @@ impl Command {
             eyre::bail!("Cannot unwind genesis block")
         }
 
-        // Only execute a pipeline unwind if the start of the range overlaps the existing static
-        // files. If that's the case, then copy all available data from MDBX to static files, and
-        // only then, proceed with the unwind.
-        if let Some(highest_static_block) = provider_factory
-            .static_file_provider()
-            .get_highest_static_files()
-            .max()
-            .filter(|highest_static_file_block| highest_static_file_block >= range.start())
-        {
-            info!(target: "reth::cli", ?range, ?highest_static_block, "Executing a pipeline unwind.");
-            let mut pipeline = self.build_pipeline(config, provider_factory.clone()).await?;
-
-            // Move all applicable data from database to static files.
-            pipeline.move_to_static_files()?;
-
-            pipeline.unwind((*range.start()).saturating_sub(1), None)?;
-        } else {
-            info!(target: "reth::cli", ?range, "Executing a database unwind.");
-            let provider = provider_factory.provider_rw()?;
-
-            let _ = provider
-                .take_block_and_execution_range(range.clone())
-                .map_err(|err| eyre::eyre!("Transaction error on unwind: {err}"))?;
-
-            // update finalized block if needed
-            let last_saved_finalized_block_number = provider.last_finalized_block_number()?;
-            let range_min =
-                range.clone().min().ok_or(eyre::eyre!("Could not fetch lower range end"))?;
-            if range_min < last_saved_finalized_block_number {
-                provider.save_finalized_block_number(BlockNumber::from(range_min))?;
-            }
-
-            provider.commit()?;
-        }
+        unwind_blocks(config, provider_factory, range.clone())?;
 
         println!("Unwound {} blocks", range.count());
 
         Ok(())
     }
+}
 
-    async fn build_pipeline<DB: Database + 'static>(
-        self,
-        config: Config,
-        provider_factory: ProviderFactory<Arc<DB>>,
-    ) -> Result<Pipeline<Arc<DB>>, eyre::Error> {
-        let consensus: Arc<dyn Consensus> =
-            Arc::new(EthBeaconConsensus::new(provider_factory.chain_spec()));
-        let stage_conf = &config.stages;
-        let prune_modes = config.prune.clone().map(|prune| prune.segments).unwrap_or_default();
-
-        let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
-        let executor = block_executor!(provider_factory.chain_spec());
-
-        let pipeline = Pipeline::builder()
-            .with_tip_sender(tip_tx)
-            .add_stages(
-                DefaultStages::new(
-                    provider_factory.clone(),
-                    tip_rx,
-                    Arc::clone(&consensus),
-                    NoopHeaderDownloader::default(),
-                    NoopBodiesDownloader::default(),
-                    executor.clone(),
-                    stage_conf.clone(),
-                    prune_modes.clone(),
-                )
-                .set(ExecutionStage::new(
-                    executor,
-                    ExecutionStageThresholds {
-                        max_blocks: None,
-                        max_changes: None,
-                        max_cumulative_gas: None,
-                        max_duration: None,
-                    },
-                    stage_conf.execution_external_clean_threshold(),
-                    prune_modes,
-                    ExExManagerHandle::empty(),
-                )),
-            )
-            .build(
-                provider_factory.clone(),
-                StaticFileProducer::new(provider_factory, PruneModes::default()),
-            );
-        Ok(pipeline)
+/// Unwinds the blocks in the given range, keeping the blocks below it.
+///
+/// A pipeline unwind is executed if the range overlaps the static files, otherwise the blocks are
+/// removed from the database directly.
+pub(crate) fn unwind_blocks<DB: Database + 'static>(
+    config: Config,
+    provider_factory: ProviderFactory<Arc<DB>>,
+    range: RangeInclusive<u64>,
+) -> eyre::Result<()> {
+    // Only execute a pipeline unwind if the start of the range overlaps the existing static
+    // files. If that's the case, then copy all available data from MDBX to static files, and
+    // only then, proceed with the unwind.
+    if let Some(highest_static_block) = provider_factory
+        .static_file_provider()
+        .get_highest_static_files()
+        .max()
+        .filter(|highest_static_file_block| highest_static_file_block >= range.start())
+    {
+        info!(target: "reth::cli", ?range, ?highest_static_block, "Executing a pipeline unwind.");
+        let mut pipeline = build_pipeline(config, provider_factory.clone())?;
+
+        // Move all applicable data from database to static files.
+        pipeline.move_to_static_files()?;
+
+        pipeline.unwind((*range.start()).saturating_sub(1), None)?;
+    } else {
+        info!(target: "reth::cli", ?range, "Executing a database unwind.");
+        let provider = provider_factory.provider_rw()?;
+
+        let _ = provider
+            .take_block_and_execution_range(range.clone())
+            .map_err(|err| eyre::eyre!("Transaction error on unwind: {err}"))?;
+
+        // update finalized block if needed
+        let last_saved_finalized_block_number = provider.last_finalized_block_number()?;
+        let range_min =
+            range.clone().min().ok_or(eyre::eyre!("Could not fetch lower range end"))?;
+        if range_min < last_saved_finalized_block_number {
+            provider.save_finalized_block_number(BlockNumber::from(range_min))?;
+        }
+
+        provider.commit()?;
     }
+
+    Ok(())
+}
+
+/// Builds a pipeline with the default stages, which is only used to unwind them.
+pub(crate) fn build_pipeline<DB: Database + 'static>(
+    config: Config,
+    provider_factory: ProviderFactory<Arc<DB>>,
+) -> Result<Pipeline<Arc<DB>>, eyre::Error> {
+    let consensus: Arc<dyn Consensus> =
+        Arc::new(EthBeaconConsensus::new(provider_factory.chain_spec()));
+    let stage_conf = &config.stages;
+    let prune_modes = config.prune.clone().map(|prune| prune.segments).unwrap_or_default();
+
+    let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
+    let executor = block_executor!(provider_factory.chain_spec());
+
+    let pipeline = Pipeline::builder()
+        .with_tip_sender(tip_tx)
+        .add_stages(
+            DefaultStages::new(
+                provider_factory.clone(),
+                tip_rx,
+                Arc::clone(&consensus),
+                NoopHeaderDownloader::default(),
+                NoopBodiesDownloader::default(),
+                executor.clone(),
+                stage_conf.clone(),
+                prune_modes.clone(),
+            )
+            .set(ExecutionStage::new(
+                executor,
+                ExecutionStageThresholds {
+                    max_blocks: None,
+                    max_changes: None,
+                    max_cumulative_gas: None,
+                    max_duration: None,
+                },
+                stage_conf.execution_external_clean_threshold(),
+                prune_modes,
+                ExExManagerHandle::empty(),
+            )),
+        )
+        .build(
+            provider_factory.clone(),
+            StaticFileProducer::new(provider_factory, PruneModes::default()),
+        );
+    Ok(pipeline)
 }
 
 /// `reth stage unwind` subcommand
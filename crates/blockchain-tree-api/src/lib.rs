@@ -113,6 +113,15 @@ pub trait BlockchainTreeEngine: BlockchainTreeViewer + Send + Sync {
     ///
     /// Returns `Ok` if the blocks were canonicalized, or if the blocks were already canonical.
     fn make_canonical(&self, block_hash: BlockHash) -> Result<CanonicalOutcome, CanonicalError>;
+
+    /// Unwinds the canonical chain in the database down to, and excluding, `unwind_to`.
+    ///
+    /// The unwound blocks are kept in the tree as a sidechain.
+    ///
+    /// # Note
+    ///
+    /// This does not update the canonical head that is tracked outside of the tree.
+    fn unwind(&self, unwind_to: BlockNumber) -> Result<(), CanonicalError>;
 }
 
 /// Represents the kind of validation that should be performed when inserting a block.
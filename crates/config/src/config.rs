@@ -82,6 +82,10 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Index Address Appearances stage configuration.
+    pub index_address_appearances: IndexAddressAppearancesConfig,
+    /// Index Logs stage configuration.
+    pub index_logs: IndexLogsConfig,
     /// Common ETL related configuration.
     pub etl: EtlConfig,
 }
@@ -310,6 +314,42 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Address appearances index stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct IndexAddressAppearancesConfig {
+    /// Whether the stage is enabled.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexAddressAppearancesConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 10_000 }
+    }
+}
+
+/// Log index stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct IndexLogsConfig {
+    /// Whether the stage is enabled.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexLogsConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 10_000 }
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
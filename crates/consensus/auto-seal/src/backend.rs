@@ -0,0 +1,144 @@
+//! Direct state modifications of the dev chain.
+
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    database::Database,
+    models::{AccountBeforeTx, BlockNumberAddress},
+    transaction::DbTxMut,
+    DatabaseError,
+};
+use reth_primitives::{keccak256, Address, BlockNumber, Bytecode, Bytes, StorageEntry, B256, U256};
+use reth_provider::{
+    bundle_state::HashedStateChanges, providers::BlockchainProvider, AccountReader, BlockNumReader,
+    DatabaseProviderFactory, HistoryWriter, ProviderResult,
+};
+use reth_trie::{HashedPostState, HashedStorage};
+use std::collections::BTreeMap;
+use tracing::trace;
+
+/// A modification of a single account that is applied directly to the latest state, outside of
+/// block execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountUpdate {
+    /// The new balance of the account.
+    pub balance: Option<U256>,
+    /// The new nonce of the account.
+    pub nonce: Option<u64>,
+    /// The new code of the account.
+    pub code: Option<Bytes>,
+    /// Storage slots to overwrite.
+    pub storage: Vec<(B256, U256)>,
+}
+
+/// Access to the database of a dev chain that allows to modify the latest state directly.
+pub trait DevChainBackend: Send + Sync {
+    /// Applies the [`AccountUpdate`] to the latest state and returns the number of the block the
+    /// change was recorded in.
+    ///
+    /// The previous values are recorded in the changesets of the latest block, so the change is
+    /// reverted if that block is unwound. This means the state root of the latest block no longer
+    /// matches its state until a new block is built on top of it.
+    fn update_account(
+        &self,
+        address: Address,
+        update: AccountUpdate,
+    ) -> ProviderResult<BlockNumber>;
+}
+
+impl<DB> DevChainBackend for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn update_account(
+        &self,
+        address: Address,
+        update: AccountUpdate,
+    ) -> ProviderResult<BlockNumber> {
+        let provider = self.database_provider_rw()?;
+        let block = provider.best_block_number()?;
+        let tx = provider.tx_ref();
+
+        let original = provider.basic_account(address)?;
+        let mut account = original.unwrap_or_default();
+        if let Some(balance) = update.balance {
+            account.balance = balance;
+        }
+        if let Some(nonce) = update.nonce {
+            account.nonce = nonce;
+        }
+        if let Some(code) = update.code {
+            if code.is_empty() {
+                account.bytecode_hash = None;
+            } else {
+                let code_hash = keccak256(&code);
+                tx.put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code))?;
+                account.bytecode_hash = Some(code_hash);
+            }
+        }
+
+        trace!(target: "consensus::auto", ?address, ?account, block, "Updating account");
+
+        // record the previous value, unless the account was already changed in this block
+        let mut account_history = BTreeMap::new();
+        let mut account_changesets = tx.cursor_dup_write::<tables::AccountChangeSets>()?;
+        if account_changesets
+            .seek_by_key_subkey(block, address)?
+            .filter(|entry| entry.address == address)
+            .is_none()
+        {
+            account_changesets.upsert(block, AccountBeforeTx { address, info: original })?;
+            account_history.insert(address, vec![block]);
+        }
+        tx.put::<tables::PlainAccountState>(address, account)?;
+
+        let hashed_address = keccak256(address);
+        let mut hashed_storage = HashedStorage::new(false);
+        let mut storage_history = BTreeMap::new();
+        let mut storage_changesets = tx.cursor_dup_write::<tables::StorageChangeSets>()?;
+        let mut plain_storage = tx.cursor_dup_write::<tables::PlainStorageState>()?;
+        for (slot, value) in update.storage {
+            let previous = plain_storage
+                .seek_by_key_subkey(address, slot)?
+                .filter(|entry| entry.key == slot)
+                .map(|entry| entry.value);
+            if previous.is_some() {
+                plain_storage.delete_current()?;
+            }
+            if value != U256::ZERO {
+                plain_storage.upsert(address, StorageEntry { key: slot, value })?;
+            }
+
+            let changeset_key = BlockNumberAddress((block, address));
+            if storage_changesets
+                .seek_by_key_subkey(changeset_key, slot)?
+                .filter(|entry| entry.key == slot)
+                .is_none()
+            {
+                storage_changesets.upsert(
+                    changeset_key,
+                    StorageEntry { key: slot, value: previous.unwrap_or_default() },
+                )?;
+                storage_history.insert((address, slot), vec![block]);
+            }
+
+            hashed_storage.storage.insert(keccak256(slot), value);
+        }
+
+        let mut hashed_state = HashedPostState::default();
+        hashed_state.accounts.insert(hashed_address, Some(account));
+        hashed_state.storages.insert(hashed_address, hashed_storage);
+
+        // the trie updates must be computed against the hashed state before it's written
+        let (_, trie_updates) =
+            hashed_state.state_root_with_updates(tx).map_err(Into::<DatabaseError>::into)?;
+        HashedStateChanges(hashed_state).write_to_db(tx)?;
+        trie_updates.flush(tx)?;
+
+        provider.insert_account_history_index(account_history)?;
+        provider.insert_storage_history_index(storage_history)?;
+        provider.commit()?;
+
+        Ok(block)
+    }
+}
@@ -0,0 +1,460 @@
+//! A handle to control the [`MiningTask`](crate::MiningTask) of a dev chain.
+
+use crate::backend::AccountUpdate;
+use parking_lot::Mutex;
+use reth_beacon_consensus::BeaconForkChoiceUpdateError;
+use reth_blockchain_tree_api::error::{CanonicalError, InsertBlockError};
+use reth_execution_errors::BlockExecutionError;
+use reth_primitives::{
+    constants::ETHEREUM_BLOCK_GAS_LIMIT, Address, BlockNumber, Signature, Transaction,
+    TransactionSigned, TransactionSignedEcRecovered, B256, U256,
+};
+use reth_provider::ProviderError;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+/// Errors that can occur when controlling the dev chain through the [`AutoSealHandle`].
+#[derive(Debug, thiserror::Error)]
+pub enum AutoSealError {
+    /// Error while accessing the database.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Error while unwinding the canonical chain.
+    #[error(transparent)]
+    Canonical(#[from] CanonicalError),
+    /// Error while executing a new block.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// Error while inserting a new block into the blockchain tree.
+    #[error(transparent)]
+    InsertBlock(#[from] InsertBlockError),
+    /// The engine failed to process the forkchoice update for a new block.
+    #[error(transparent)]
+    ForkchoiceUpdate(#[from] BeaconForkChoiceUpdateError),
+    /// The engine considers the new block invalid.
+    #[error("engine rejected new block {0}")]
+    InvalidBlock(B256),
+    /// The mining task is no longer running.
+    #[error("auto seal mining task stopped")]
+    TaskClosed,
+}
+
+/// Commands sent by the [`AutoSealHandle`] to the [`MiningTask`](crate::MiningTask).
+#[derive(Debug)]
+pub(crate) enum AutoSealCommand {
+    /// Enables or disables mining blocks for new transactions.
+    SetAutomine { enabled: bool, tx: oneshot::Sender<()> },
+    /// Sets the interval of blocks that are mined independent of new transactions, or disables
+    /// interval mining if `None`.
+    SetIntervalMining { interval: Option<Duration>, tx: oneshot::Sender<()> },
+    /// Mines `blocks` blocks, `interval` seconds apart.
+    Mine { blocks: u64, interval: Option<u64>, tx: oneshot::Sender<Result<(), AutoSealError>> },
+    /// Applies an [`AccountUpdate`] to the latest state.
+    UpdateAccount {
+        address: Address,
+        update: AccountUpdate,
+        tx: oneshot::Sender<Result<(), AutoSealError>>,
+    },
+    /// Records the current head so it can be reverted to.
+    Snapshot { tx: oneshot::Sender<Result<U256, AutoSealError>> },
+    /// Reverts the chain to the head recorded by the given snapshot.
+    Revert { id: U256, tx: oneshot::Sender<Result<bool, AutoSealError>> },
+    /// Queues a transaction of an impersonated account for the next block.
+    SendImpersonated { transaction: TransactionSignedEcRecovered, tx: oneshot::Sender<()> },
+}
+
+/// Header fields of the next block that can be configured through the [`AutoSealHandle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NextBlock {
+    /// The timestamp of the block.
+    pub(crate) timestamp: u64,
+    /// The beneficiary of the block.
+    pub(crate) beneficiary: Address,
+    /// The gas limit of the block.
+    pub(crate) gas_limit: u64,
+    /// The base fee of the block, if it overrides the base fee derived from the parent.
+    pub(crate) base_fee_per_gas: Option<u64>,
+    /// The `prevRandao` value of the block.
+    pub(crate) prev_randao: B256,
+}
+
+/// Dev chain state shared between the [`AutoSealHandle`] and the
+/// [`MiningTask`](crate::MiningTask).
+#[derive(Debug)]
+pub(crate) struct DevState {
+    /// Whether new blocks are mined for incoming transactions.
+    pub(crate) automine: bool,
+    /// Offset in seconds that is added to the system time for new block timestamps.
+    pub(crate) time_offset: i64,
+    /// The timestamp to use for the next block, if set explicitly.
+    pub(crate) next_timestamp: Option<u64>,
+    /// Recorded snapshots by id.
+    pub(crate) snapshots: BTreeMap<U256, (BlockNumber, B256)>,
+    /// The id of the next snapshot.
+    pub(crate) next_snapshot_id: U256,
+    /// Whether the state was modified directly since the latest block was built, in which case
+    /// the state root of the latest block no longer matches the state.
+    pub(crate) dirty: bool,
+    /// Accounts that can send transactions without a valid signature.
+    pub(crate) impersonated: HashSet<Address>,
+    /// Whether every account is impersonated.
+    pub(crate) auto_impersonate: bool,
+    /// Transactions of impersonated accounts that are included in the next block.
+    ///
+    /// They don't carry a valid signature, so they never enter the pool.
+    pub(crate) impersonated_transactions: Vec<TransactionSignedEcRecovered>,
+    /// The beneficiary of new blocks.
+    pub(crate) coinbase: Address,
+    /// The gas limit of new blocks.
+    pub(crate) gas_limit: u64,
+    /// The base fee of the next block, if set explicitly.
+    pub(crate) next_base_fee: Option<u64>,
+    /// The `prevRandao` value of the next block, if set explicitly.
+    pub(crate) next_prev_randao: Option<B256>,
+    /// The number of seconds between the timestamps of consecutive blocks, if set.
+    pub(crate) timestamp_interval: Option<u64>,
+}
+
+impl DevState {
+    /// Returns the timestamp for the next block and consumes an explicitly set timestamp.
+    ///
+    /// Once consumed, the explicit timestamp becomes the new base for following blocks. Without an
+    /// explicit timestamp, a configured timestamp interval is added to the parent's timestamp.
+    pub(crate) fn next_block_timestamp(&mut self, parent_timestamp: u64) -> u64 {
+        let now = unix_now();
+        match self.next_timestamp.take() {
+            Some(timestamp) => {
+                self.time_offset = timestamp as i64 - now as i64;
+                timestamp
+            }
+            None => match self.timestamp_interval {
+                Some(interval) => parent_timestamp.saturating_add(interval),
+                None => now.saturating_add_signed(self.time_offset),
+            },
+        }
+    }
+
+    /// Returns the configured header fields of the next block and consumes the values that only
+    /// apply to a single block.
+    pub(crate) fn next_block(&mut self, parent_timestamp: u64) -> NextBlock {
+        NextBlock {
+            timestamp: self.next_block_timestamp(parent_timestamp),
+            beneficiary: self.coinbase,
+            gas_limit: self.gas_limit,
+            base_fee_per_gas: self.next_base_fee.take(),
+            prev_randao: self.next_prev_randao.take().unwrap_or_default(),
+        }
+    }
+
+    /// Returns the block the engine can consider finalized: the oldest snapshot, if any.
+    pub(crate) fn oldest_snapshot(&self) -> Option<(BlockNumber, B256)> {
+        self.snapshots.values().min_by_key(|(number, _)| *number).copied()
+    }
+}
+
+impl Default for DevState {
+    fn default() -> Self {
+        Self {
+            automine: true,
+            time_offset: 0,
+            next_timestamp: None,
+            snapshots: BTreeMap::new(),
+            next_snapshot_id: U256::from(1),
+            dirty: false,
+            impersonated: HashSet::new(),
+            auto_impersonate: false,
+            impersonated_transactions: Vec::new(),
+            coinbase: Address::ZERO,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            next_base_fee: None,
+            next_prev_randao: None,
+            timestamp_interval: None,
+        }
+    }
+}
+
+/// A handle to control the [`MiningTask`](crate::MiningTask) of a dev chain.
+///
+/// All chain modifications are processed by the mining task in order, interleaved with blocks
+/// that are mined for incoming transactions.
+#[derive(Debug, Clone)]
+pub struct AutoSealHandle {
+    to_task: UnboundedSender<AutoSealCommand>,
+    state: Arc<Mutex<DevState>>,
+}
+
+// === impl AutoSealHandle ===
+
+impl AutoSealHandle {
+    /// Creates a new handle.
+    pub(crate) const fn new(
+        to_task: UnboundedSender<AutoSealCommand>,
+        state: Arc<Mutex<DevState>>,
+    ) -> Self {
+        Self { to_task, state }
+    }
+
+    /// Returns `true` if new blocks are mined for incoming transactions.
+    pub fn automine(&self) -> bool {
+        self.state.lock().automine
+    }
+
+    /// Enables or disables mining blocks for incoming transactions.
+    pub async fn set_automine(&self, enabled: bool) -> Result<(), AutoSealError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(AutoSealCommand::SetAutomine { enabled, tx })?;
+        rx.await.map_err(|_| AutoSealError::TaskClosed)
+    }
+
+    /// Mines a block with all pending transactions every `interval`, independent of automine.
+    ///
+    /// Interval mining is disabled if `interval` is `None`.
+    pub async fn set_interval_mining(
+        &self,
+        interval: Option<Duration>,
+    ) -> Result<(), AutoSealError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(AutoSealCommand::SetIntervalMining { interval, tx })?;
+        rx.await.map_err(|_| AutoSealError::TaskClosed)
+    }
+
+    /// Mines `blocks` blocks that include all pending transactions of the pool.
+    ///
+    /// If an `interval` is given, the timestamps of consecutive blocks are `interval` seconds
+    /// apart.
+    pub async fn mine(&self, blocks: u64, interval: Option<u64>) -> Result<(), AutoSealError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(AutoSealCommand::Mine { blocks, interval, tx })?;
+        rx.await.map_err(|_| AutoSealError::TaskClosed)?
+    }
+
+    /// Applies the [`AccountUpdate`] to the latest state.
+    pub async fn update_account(
+        &self,
+        address: Address,
+        update: AccountUpdate,
+    ) -> Result<(), AutoSealError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(AutoSealCommand::UpdateAccount { address, update, tx })?;
+        rx.await.map_err(|_| AutoSealError::TaskClosed)?
+    }
+
+    /// Records the current head of the chain and returns the id of the snapshot.
+    pub async fn snapshot(&self) -> Result<U256, AutoSealError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(AutoSealCommand::Snapshot { tx })?;
+        rx.await.map_err(|_| AutoSealError::TaskClosed)?
+    }
+
+    /// Reverts the chain to the head recorded by the given snapshot.
+    ///
+    /// This removes the snapshot and all snapshots taken after it. Returns `false` if the snapshot
+    /// does not exist.
+    pub async fn revert(&self, id: U256) -> Result<bool, AutoSealError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(AutoSealCommand::Revert { id, tx })?;
+        rx.await.map_err(|_| AutoSealError::TaskClosed)?
+    }
+
+    /// Moves the clock used for new block timestamps forward by `seconds` and returns the total
+    /// offset to the system time.
+    pub fn increase_time(&self, seconds: u64) -> i64 {
+        let mut state = self.state.lock();
+        state.time_offset = state.time_offset.saturating_add(seconds as i64);
+        state.time_offset
+    }
+
+    /// Sets the timestamp of the next block. Following blocks continue from this timestamp.
+    pub fn set_next_block_timestamp(&self, timestamp: u64) {
+        self.state.lock().next_timestamp = Some(timestamp);
+    }
+
+    /// Sets the clock used for new block timestamps to `timestamp` and returns the offset to the
+    /// system time.
+    pub fn set_time(&self, timestamp: u64) -> i64 {
+        let mut state = self.state.lock();
+        state.next_timestamp = None;
+        state.time_offset = timestamp as i64 - unix_now() as i64;
+        state.time_offset
+    }
+
+    /// Sets the number of seconds between the timestamps of consecutive blocks, or removes the
+    /// interval if `None`.
+    ///
+    /// Returns `true` if an interval was set before.
+    pub fn set_block_timestamp_interval(&self, interval: Option<u64>) -> bool {
+        std::mem::replace(&mut self.state.lock().timestamp_interval, interval).is_some()
+    }
+
+    /// Sets the beneficiary of new blocks.
+    pub fn set_coinbase(&self, coinbase: Address) {
+        self.state.lock().coinbase = coinbase;
+    }
+
+    /// Sets the gas limit of new blocks.
+    pub fn set_block_gas_limit(&self, gas_limit: u64) {
+        self.state.lock().gas_limit = gas_limit;
+    }
+
+    /// Sets the base fee of the next block. Following blocks derive their base fee from it.
+    pub fn set_next_block_base_fee(&self, base_fee: u64) {
+        self.state.lock().next_base_fee = Some(base_fee);
+    }
+
+    /// Sets the `prevRandao` value of the next block.
+    pub fn set_next_block_prev_randao(&self, prev_randao: B256) {
+        self.state.lock().next_prev_randao = Some(prev_randao);
+    }
+
+    /// Allows transactions to be sent from `address` without a valid signature.
+    pub fn impersonate_account(&self, address: Address) {
+        self.state.lock().impersonated.insert(address);
+    }
+
+    /// Stops impersonating `address`.
+    pub fn stop_impersonating_account(&self, address: Address) {
+        self.state.lock().impersonated.remove(&address);
+    }
+
+    /// Enables or disables impersonation of every account.
+    pub fn set_auto_impersonate(&self, enabled: bool) {
+        self.state.lock().auto_impersonate = enabled;
+    }
+
+    /// Returns `true` if transactions can be sent from `address` without a valid signature.
+    pub fn is_impersonated(&self, address: &Address) -> bool {
+        let state = self.state.lock();
+        state.auto_impersonate || state.impersonated.contains(address)
+    }
+
+    /// Sends a transaction of the impersonated `sender` and returns its hash.
+    ///
+    /// The transaction bypasses the pool and is included in the next block of the dev chain, with
+    /// the sender carried alongside it. It's never propagated to peers. The signature of the
+    /// transaction is a placeholder that can't be recovered, so the transaction is only valid on
+    /// this dev chain, which stores the senders of its blocks.
+    pub async fn send_impersonated_transaction(
+        &self,
+        transaction: Transaction,
+        sender: Address,
+    ) -> Result<B256, AutoSealError> {
+        let transaction = TransactionSigned::from_transaction_and_signature(
+            transaction,
+            impersonated_signature(&sender),
+        );
+        let hash = transaction.hash();
+        let (tx, rx) = oneshot::channel();
+        self.send(AutoSealCommand::SendImpersonated {
+            transaction: transaction.with_signer(sender),
+            tx,
+        })?;
+        rx.await.map_err(|_| AutoSealError::TaskClosed)?;
+        Ok(hash)
+    }
+
+    fn send(&self, command: AutoSealCommand) -> Result<(), AutoSealError> {
+        self.to_task.send(command).map_err(|_| AutoSealError::TaskClosed)
+    }
+}
+
+/// Returns the placeholder signature of transactions sent by the impersonated `sender`.
+///
+/// The signature commits to the sender, so identical transactions of different impersonated
+/// accounts have different hashes. It's not a valid signature of any key.
+fn impersonated_signature(sender: &Address) -> Signature {
+    Signature { r: U256::from_be_slice(sender.as_slice()), s: U256::from(1), odd_y_parity: false }
+}
+
+/// Returns the current unix timestamp in seconds.
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_timestamp_becomes_new_base() {
+        let mut state = DevState::default();
+        let timestamp = unix_now() + 1_000;
+        state.next_timestamp = Some(timestamp);
+
+        assert_eq!(state.next_block_timestamp(0), timestamp);
+        assert!(state.next_timestamp.is_none());
+        assert!(state.next_block_timestamp(0) >= timestamp);
+    }
+
+    #[test]
+    fn timestamp_interval() {
+        let mut state = DevState { timestamp_interval: Some(10), ..Default::default() };
+        assert_eq!(state.next_block_timestamp(100), 110);
+
+        // an explicit timestamp takes precedence
+        state.next_timestamp = Some(105);
+        assert_eq!(state.next_block_timestamp(100), 105);
+    }
+
+    #[test]
+    fn next_block_consumes_single_block_values() {
+        let coinbase = Address::with_last_byte(1);
+        let mut state = DevState {
+            coinbase,
+            gas_limit: 1_000,
+            next_base_fee: Some(7),
+            next_prev_randao: Some(B256::with_last_byte(1)),
+            timestamp_interval: Some(1),
+            ..Default::default()
+        };
+
+        let next = state.next_block(0);
+        assert_eq!(
+            next,
+            NextBlock {
+                timestamp: 1,
+                beneficiary: coinbase,
+                gas_limit: 1_000,
+                base_fee_per_gas: Some(7),
+                prev_randao: B256::with_last_byte(1),
+            }
+        );
+
+        let next = state.next_block(1);
+        assert_eq!(next.beneficiary, coinbase);
+        assert_eq!(next.gas_limit, 1_000);
+        assert_eq!(next.base_fee_per_gas, None);
+        assert_eq!(next.prev_randao, B256::ZERO);
+    }
+
+    #[test]
+    fn impersonation() {
+        let (to_task, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle = AutoSealHandle::new(to_task, Default::default());
+        let (alice, bob) = (Address::with_last_byte(1), Address::with_last_byte(2));
+
+        handle.impersonate_account(alice);
+        assert!(handle.is_impersonated(&alice));
+        assert!(!handle.is_impersonated(&bob));
+
+        handle.set_auto_impersonate(true);
+        assert!(handle.is_impersonated(&bob));
+
+        handle.set_auto_impersonate(false);
+        handle.stop_impersonating_account(alice);
+        assert!(!handle.is_impersonated(&alice));
+    }
+
+    #[test]
+    fn oldest_snapshot() {
+        let mut state = DevState::default();
+        assert_eq!(state.oldest_snapshot(), None);
+
+        state.snapshots.insert(U256::from(1), (5, B256::with_last_byte(5)));
+        state.snapshots.insert(U256::from(2), (3, B256::with_last_byte(3)));
+        assert_eq!(state.oldest_snapshot(), Some((3, B256::with_last_byte(3))));
+    }
+}
@@ -21,27 +21,28 @@ use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
 use reth_engine_primitives::EngineTypes;
 use reth_execution_errors::{BlockExecutionError, BlockValidationError};
 use reth_primitives::{
-    constants::{EMPTY_TRANSACTIONS, ETHEREUM_BLOCK_GAS_LIMIT},
-    eip4844::calculate_excess_blob_gas,
-    proofs, Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Header,
-    Requests, SealedBlock, SealedHeader, TransactionSigned, Withdrawals, B256, U256,
+    constants::EMPTY_TRANSACTIONS, eip4844::calculate_excess_blob_gas, proofs, Block, BlockBody,
+    BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Header, Requests, SealedBlock,
+    SealedBlockWithSenders, SealedHeader, TransactionSigned, TransactionSignedEcRecovered,
+    Withdrawals, B256, U256,
 };
 use reth_provider::{BlockReaderIdExt, ExecutionOutcome, StateProviderFactory, StateRootProvider};
 use reth_revm::database::StateProviderDatabase;
 use reth_transaction_pool::TransactionPool;
-use std::{
-    collections::HashMap,
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{mpsc::UnboundedSender, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tracing::trace;
 
+mod backend;
 mod client;
+mod handle;
 mod mode;
 mod task;
 
 pub use crate::client::AutoSealClient;
+pub use backend::{AccountUpdate, DevChainBackend};
+use handle::NextBlock;
+pub use handle::{AutoSealError, AutoSealHandle};
 pub use mode::{FixedBlockTimeMiner, MiningMode, ReadyTransactionMiner};
 use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
 pub use task::MiningTask;
@@ -254,21 +255,47 @@ impl StorageInner {
         self.hash_to_number.insert(self.best_hash, self.best_block);
     }
 
-    /// Fills in pre-execution header fields based on the current best block and given
-    /// transactions.
+    /// Returns the sealed header of the block with the given number, if it is stored.
+    pub(crate) fn sealed_header(&self, number: BlockNumber, hash: B256) -> Option<SealedHeader> {
+        self.headers.get(&number).map(|header| header.clone().seal(hash))
+    }
+
+    /// Removes all blocks above the given block, which becomes the new best block.
+    pub(crate) fn unwind_to(&mut self, number: BlockNumber, hash: B256) {
+        for removed in number + 1..=self.best_block {
+            if let Some(header) = self.headers.remove(&removed) {
+                self.total_difficulty -= header.difficulty;
+            }
+            if let Some(removed_hash) = self.block_hash(removed) {
+                self.hash_to_number.remove(&removed_hash);
+                self.bodies.remove(&removed_hash);
+            }
+        }
+        self.best_block = number;
+        self.best_hash = hash;
+
+        trace!(target: "consensus::auto", num=self.best_block, hash=?self.best_hash, "unwound storage");
+    }
+
+    /// Fills in pre-execution header fields based on the current best block, the configured fields
+    /// of the next block and given transactions.
     pub(crate) fn build_header_template(
         &self,
-        timestamp: u64,
+        next_block: &NextBlock,
         transactions: &[TransactionSigned],
         ommers: &[Header],
         withdrawals: Option<&Withdrawals>,
         requests: Option<&Requests>,
         chain_spec: Arc<ChainSpec>,
     ) -> Header {
-        // check previous block for base fee
+        let timestamp = next_block.timestamp;
+
+        // check previous block for base fee, an explicit base fee only applies once it's active
         let base_fee_per_gas = self.headers.get(&self.best_block).and_then(|parent| {
             parent.next_block_base_fee(chain_spec.base_fee_params_at_timestamp(timestamp))
         });
+        let base_fee_per_gas =
+            base_fee_per_gas.map(|base_fee| next_block.base_fee_per_gas.unwrap_or(base_fee));
 
         let blob_gas_used = if chain_spec.is_cancun_active_at_timestamp(timestamp) {
             let mut sum_blob_gas_used = 0;
@@ -285,7 +312,7 @@ impl StorageInner {
         let mut header = Header {
             parent_hash: self.best_hash,
             ommers_hash: proofs::calculate_ommers_root(ommers),
-            beneficiary: Default::default(),
+            beneficiary: next_block.beneficiary,
             state_root: Default::default(),
             transactions_root: Default::default(),
             receipts_root: Default::default(),
@@ -293,10 +320,10 @@ impl StorageInner {
             logs_bloom: Default::default(),
             difficulty: U256::from(2),
             number: self.best_block + 1,
-            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            gas_limit: next_block.gas_limit,
             gas_used: 0,
             timestamp,
-            mix_hash: Default::default(),
+            mix_hash: next_block.prev_randao,
             nonce: 0,
             base_fee_per_gas,
             blob_gas_used,
@@ -338,21 +365,27 @@ impl StorageInner {
 
     /// Builds and executes a new block with the given transactions, on the provided executor.
     ///
-    /// This returns the header of the executed block, as well as the poststate from execution.
+    /// The transactions are executed with the given senders, their signatures are not recovered.
+    ///
+    /// This returns the executed block, as well as the poststate from execution.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn build_and_execute<Provider, Executor>(
         &mut self,
-        transactions: Vec<TransactionSigned>,
+        next_block: NextBlock,
+        transactions: Vec<TransactionSignedEcRecovered>,
         ommers: Vec<Header>,
         provider: &Provider,
         chain_spec: Arc<ChainSpec>,
         executor: &Executor,
-    ) -> Result<(SealedHeader, ExecutionOutcome), BlockExecutionError>
+    ) -> Result<(SealedBlockWithSenders, ExecutionOutcome), BlockExecutionError>
     where
         Executor: BlockExecutorProvider,
         Provider: StateProviderFactory,
     {
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let (transactions, senders): (Vec<_>, Vec<_>) =
+            transactions.into_iter().map(|tx| tx.to_components()).unzip();
+
+        let timestamp = next_block.timestamp;
 
         // if shanghai is active, include empty withdrawals
         let withdrawals =
@@ -362,7 +395,7 @@ impl StorageInner {
             chain_spec.is_prague_active_at_timestamp(timestamp).then_some(Requests::default());
 
         let header = self.build_header_template(
-            timestamp,
+            &next_block,
             &transactions,
             &ommers,
             withdrawals.as_ref(),
@@ -370,14 +403,16 @@ impl StorageInner {
             chain_spec,
         );
 
-        let block = Block {
-            header,
-            body: transactions,
-            ommers: ommers.clone(),
-            withdrawals: withdrawals.clone(),
-            requests: requests.clone(),
-        }
-        .with_recovered_senders()
+        let mut block = BlockWithSenders::new(
+            Block {
+                header,
+                body: transactions,
+                ommers: ommers.clone(),
+                withdrawals: withdrawals.clone(),
+                requests: requests.clone(),
+            },
+            senders,
+        )
         .ok_or(BlockExecutionError::Validation(BlockValidationError::SenderRecoveryError))?;
 
         trace!(target: "consensus::auto", transactions=?&block.body, "executing transactions");
@@ -400,21 +435,20 @@ impl StorageInner {
         // means we need to extract the requests from the execution output and compute the requests
         // root here
 
-        let Block { mut header, body, .. } = block.block;
-        let body = BlockBody { transactions: body, ommers, withdrawals, requests };
+        let body = BlockBody { transactions: block.body.clone(), ommers, withdrawals, requests };
 
-        trace!(target: "consensus::auto", ?execution_outcome, ?header, ?body, "executed block, calculating state root and completing header");
+        trace!(target: "consensus::auto", ?execution_outcome, header=?block.header, ?body, "executed block, calculating state root and completing header");
 
         // calculate the state root
-        header.state_root = db.state_root(execution_outcome.state())?;
-        trace!(target: "consensus::auto", root=?header.state_root, ?body, "calculated root");
+        block.header.state_root = db.state_root(execution_outcome.state())?;
+        trace!(target: "consensus::auto", root=?block.header.state_root, ?body, "calculated root");
 
         // finally insert into storage
-        self.insert_new_block(header.clone(), body);
+        self.insert_new_block(block.header.clone(), body);
 
-        // set new header with hash that should have been updated by insert_new_block
-        let new_header = header.seal(self.best_hash);
+        // seal the block with the hash that was computed by insert_new_block
+        let block = block.seal(self.best_hash);
 
-        Ok((new_header, execution_outcome))
+        Ok((block, execution_outcome))
     }
 }
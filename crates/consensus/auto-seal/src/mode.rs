@@ -92,7 +92,7 @@ impl FixedBlockTimeMiner {
         Self { interval: tokio::time::interval_at(start, duration) }
     }
 
-    fn poll<Pool>(
+    pub(crate) fn poll<Pool>(
         &mut self,
         pool: &Pool,
         cx: &mut Context<'_>,
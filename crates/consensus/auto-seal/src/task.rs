@@ -1,23 +1,35 @@
-use crate::{mode::MiningMode, Storage};
+use crate::{
+    backend::{AccountUpdate, DevChainBackend},
+    handle::{AutoSealCommand, AutoSealError, AutoSealHandle, DevState},
+    mode::{FixedBlockTimeMiner, MiningMode},
+    Storage, StorageInner,
+};
 use futures_util::{future::BoxFuture, FutureExt};
-use reth_beacon_consensus::{BeaconEngineMessage, ForkchoiceStatus};
+use parking_lot::Mutex;
+use reth_beacon_consensus::{BeaconEngineMessage, BeaconForkChoiceUpdateError, ForkchoiceStatus};
+use reth_blockchain_tree_api::{BlockValidationKind, BlockchainTreeEngine};
 use reth_chainspec::ChainSpec;
 use reth_engine_primitives::EngineTypes;
 use reth_evm::execute::BlockExecutorProvider;
-use reth_primitives::IntoRecoveredTransaction;
+use reth_primitives::{
+    Address, IntoRecoveredTransaction, SealedHeader, TransactionSignedEcRecovered, U256,
+};
 use reth_provider::{CanonChainTracker, StateProviderFactory};
 use reth_rpc_types::engine::ForkchoiceState;
 use reth_stages_api::PipelineEvent;
 use reth_tokio_util::EventStream;
-use reth_transaction_pool::{TransactionPool, ValidPoolTransaction};
+use reth_transaction_pool::{PoolTransaction, TransactionPool, ValidPoolTransaction};
 use std::{
-    collections::VecDeque,
+    collections::{HashSet, VecDeque},
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
 };
-use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
 use tracing::{debug, error, warn};
 
 /// A Future that listens for new ready transactions and puts new blocks into storage
@@ -28,20 +40,28 @@ pub struct MiningTask<Client, Pool: TransactionPool, Executor, Engine: EngineTyp
     client: Client,
     /// The active miner
     miner: MiningMode,
+    /// Miner for blocks on a fixed interval that was enabled through the [`AutoSealHandle`]
+    interval_miner: Option<FixedBlockTimeMiner>,
     /// Single active future that inserts a new block into `storage`
     insert_task: Option<BoxFuture<'static, Option<EventStream<PipelineEvent>>>>,
     /// Shared storage to insert new blocks
     storage: Storage,
     /// Pool where transactions are stored
     pool: Pool,
-    /// backlog of sets of transactions ready to be mined
-    queued: VecDeque<Vec<Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>>,
+    /// backlog of sets of transactions ready to be mined and commands to process
+    queued: VecDeque<QueuedWork<<Pool as TransactionPool>::Transaction>>,
     // TODO: ideally this would just be a sender of hashes
     to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
     /// The pipeline events to listen on
     pipe_line_events: Option<EventStream<PipelineEvent>>,
     /// The type used for block execution
     block_executor: Executor,
+    /// Dev chain state shared with the [`AutoSealHandle`]
+    dev_state: Arc<Mutex<DevState>>,
+    /// Sender half of the command channel, cloned into new handles
+    to_task: UnboundedSender<AutoSealCommand>,
+    /// Incoming commands from [`AutoSealHandle`]s
+    commands: UnboundedReceiver<AutoSealCommand>,
 }
 
 // === impl MiningTask ===
@@ -60,10 +80,15 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
         pool: Pool,
         block_executor: Executor,
     ) -> Self {
+        let (to_task, commands) = unbounded_channel();
+        // automine refers to mining blocks for incoming transactions
+        let dev_state =
+            DevState { automine: matches!(miner, MiningMode::Auto(_)), ..Default::default() };
         Self {
             chain_spec,
             client,
             miner,
+            interval_miner: None,
             insert_task: None,
             storage,
             pool,
@@ -71,6 +96,9 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
             queued: Default::default(),
             pipe_line_events: None,
             block_executor,
+            dev_state: Arc::new(Mutex::new(dev_state)),
+            to_task,
+            commands,
         }
     }
 
@@ -78,11 +106,22 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
     pub fn set_pipeline_events(&mut self, events: EventStream<PipelineEvent>) {
         self.pipe_line_events = Some(events);
     }
+
+    /// Returns a new [`AutoSealHandle`] to control the dev chain this task is building.
+    pub fn handle(&self) -> AutoSealHandle {
+        AutoSealHandle::new(self.to_task.clone(), Arc::clone(&self.dev_state))
+    }
 }
 
 impl<Executor, Client, Pool, Engine> Future for MiningTask<Client, Pool, Executor, Engine>
 where
-    Client: StateProviderFactory + CanonChainTracker + Clone + Unpin + 'static,
+    Client: StateProviderFactory
+        + CanonChainTracker
+        + BlockchainTreeEngine
+        + DevChainBackend
+        + Clone
+        + Unpin
+        + 'static,
     Pool: TransactionPool + Unpin + 'static,
     <Pool as TransactionPool>::Transaction: IntoRecoveredTransaction,
     Engine: EngineTypes + 'static,
@@ -95,9 +134,48 @@ where
 
         // this drives block production and
         loop {
-            if let Poll::Ready(transactions) = this.miner.poll(&this.pool, cx) {
-                // miner returned a set of transaction that we feed to the producer
-                this.queued.push_back(transactions);
+            while let Poll::Ready(Some(command)) = this.commands.poll_recv(cx) {
+                match command {
+                    AutoSealCommand::SetAutomine { enabled, tx } => {
+                        debug!(target: "consensus::auto", enabled, "Setting automine");
+                        this.dev_state.lock().automine = enabled;
+                        let _ = tx.send(());
+                    }
+                    AutoSealCommand::SetIntervalMining { interval, tx } => {
+                        debug!(target: "consensus::auto", ?interval, "Setting interval mining");
+                        this.interval_miner = interval.map(FixedBlockTimeMiner::new);
+                        let _ = tx.send(());
+                    }
+                    AutoSealCommand::SendImpersonated { transaction, tx } => {
+                        debug!(target: "consensus::auto", hash=?transaction.hash(), sender=?transaction.signer(), "Queued impersonated transaction");
+                        let automine = {
+                            let mut dev_state = this.dev_state.lock();
+                            dev_state.impersonated_transactions.push(transaction);
+                            dev_state.automine
+                        };
+                        // the transaction is picked up by the next block
+                        if automine {
+                            this.queued.push_back(QueuedWork::Transactions(Vec::new()));
+                        }
+                        let _ = tx.send(());
+                    }
+                    command => this.queued.push_back(QueuedWork::Command(command)),
+                }
+            }
+
+            // automine only pauses mining for incoming transactions, a configured block time is
+            // independent of it
+            if this.dev_state.lock().automine || !matches!(this.miner, MiningMode::Auto(_)) {
+                if let Poll::Ready(transactions) = this.miner.poll(&this.pool, cx) {
+                    // miner returned a set of transaction that we feed to the producer
+                    this.queued.push_back(QueuedWork::Transactions(transactions));
+                }
+            }
+
+            if let Some(miner) = &mut this.interval_miner {
+                if let Poll::Ready(transactions) = miner.poll(&this.pool, cx) {
+                    this.queued.push_back(QueuedWork::Transactions(transactions));
+                }
             }
 
             if this.insert_task.is_none() {
@@ -107,93 +185,28 @@ where
                 }
 
                 // ready to queue in new insert task
-                let storage = this.storage.clone();
-                let transactions = this.queued.pop_front().expect("not empty");
-
-                let to_engine = this.to_engine.clone();
-                let client = this.client.clone();
-                let chain_spec = Arc::clone(&this.chain_spec);
-                let pool = this.pool.clone();
+                let work = this.queued.pop_front().expect("not empty");
+                let producer = BlockProducer {
+                    chain_spec: Arc::clone(&this.chain_spec),
+                    client: this.client.clone(),
+                    pool: this.pool.clone(),
+                    storage: this.storage.clone(),
+                    to_engine: this.to_engine.clone(),
+                    executor: this.block_executor.clone(),
+                    dev_state: Arc::clone(&this.dev_state),
+                };
                 let events = this.pipe_line_events.take();
-                let executor = this.block_executor.clone();
 
                 // Create the mining future that creates a block, notifies the engine that drives
                 // the pipeline
                 this.insert_task = Some(Box::pin(async move {
-                    let mut storage = storage.write().await;
-
-                    let transactions: Vec<_> = transactions
-                        .into_iter()
-                        .map(|tx| {
-                            let recovered = tx.to_recovered_transaction();
-                            recovered.into_signed()
-                        })
-                        .collect();
-                    let ommers = vec![];
-
-                    match storage.build_and_execute(
-                        transactions.clone(),
-                        ommers.clone(),
-                        &client,
-                        chain_spec,
-                        &executor,
-                    ) {
-                        Ok((new_header, _bundle_state)) => {
-                            // clear all transactions from pool
-                            pool.remove_transactions(
-                                transactions.iter().map(|tx| tx.hash()).collect(),
-                            );
-
-                            let state = ForkchoiceState {
-                                head_block_hash: new_header.hash(),
-                                finalized_block_hash: new_header.hash(),
-                                safe_block_hash: new_header.hash(),
-                            };
-                            drop(storage);
-
-                            // TODO: make this a future
-                            // await the fcu call rx for SYNCING, then wait for a VALID response
-                            loop {
-                                // send the new update to the engine, this will trigger the engine
-                                // to download and execute the block we just inserted
-                                let (tx, rx) = oneshot::channel();
-                                let _ = to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
-                                    state,
-                                    payload_attrs: None,
-                                    tx,
-                                });
-                                debug!(target: "consensus::auto", ?state, "Sent fork choice update");
-
-                                match rx.await.unwrap() {
-                                    Ok(fcu_response) => {
-                                        match fcu_response.forkchoice_status() {
-                                            ForkchoiceStatus::Valid => break,
-                                            ForkchoiceStatus::Invalid => {
-                                                error!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned invalid response");
-                                                return None
-                                            }
-                                            ForkchoiceStatus::Syncing => {
-                                                debug!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned SYNCING, waiting for VALID");
-                                                // wait for the next fork choice update
-                                                continue
-                                            }
-                                        }
-                                    }
-                                    Err(err) => {
-                                        error!(target: "consensus::auto", %err, "Autoseal fork choice update failed");
-                                        return None
-                                    }
-                                }
+                    match work {
+                        QueuedWork::Transactions(transactions) => {
+                            if let Err(err) = producer.mine_block(transactions).await {
+                                warn!(target: "consensus::auto", %err, "failed to mine block")
                             }
-
-                            // update canon chain for rpc
-                            client.set_canonical_head(new_header.clone());
-                            client.set_safe(new_header.clone());
-                            client.set_finalized(new_header.clone());
-                        }
-                        Err(err) => {
-                            warn!(target: "consensus::auto", %err, "failed to execute block")
                         }
+                        QueuedWork::Command(command) => producer.on_command(command).await,
                     }
 
                     events
@@ -224,3 +237,289 @@ impl<Client, Pool: TransactionPool, EvmConfig: std::fmt::Debug, Engine: EngineTy
         f.debug_struct("MiningTask").finish_non_exhaustive()
     }
 }
+
+/// Work queued for the [`MiningTask`], processed in order.
+enum QueuedWork<T: PoolTransaction> {
+    /// A set of ready transactions to mine.
+    Transactions(Vec<Arc<ValidPoolTransaction<T>>>),
+    /// A command sent by an [`AutoSealHandle`].
+    Command(AutoSealCommand),
+}
+
+/// Everything needed to modify the chain from within a single insert task.
+struct BlockProducer<Client, Pool, Executor, Engine: EngineTypes> {
+    chain_spec: Arc<ChainSpec>,
+    client: Client,
+    pool: Pool,
+    storage: Storage,
+    to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
+    executor: Executor,
+    dev_state: Arc<Mutex<DevState>>,
+}
+
+impl<Client, Pool, Executor, Engine> BlockProducer<Client, Pool, Executor, Engine>
+where
+    Client: StateProviderFactory + CanonChainTracker + BlockchainTreeEngine + DevChainBackend,
+    Pool: TransactionPool,
+    <Pool as TransactionPool>::Transaction: IntoRecoveredTransaction,
+    Engine: EngineTypes,
+    Executor: BlockExecutorProvider,
+{
+    /// Processes a command and sends the result back to the handle.
+    async fn on_command(&self, command: AutoSealCommand) {
+        match command {
+            AutoSealCommand::SetAutomine { tx, .. } |
+            AutoSealCommand::SetIntervalMining { tx, .. } |
+            AutoSealCommand::SendImpersonated { tx, .. } => {
+                let _ = tx.send(());
+            }
+            AutoSealCommand::Mine { blocks, interval, tx } => {
+                let _ = tx.send(self.mine(blocks, interval).await);
+            }
+            AutoSealCommand::UpdateAccount { address, update, tx } => {
+                let _ = tx.send(self.update_account(address, update).await);
+            }
+            AutoSealCommand::Snapshot { tx } => {
+                let _ = tx.send(self.snapshot().await);
+            }
+            AutoSealCommand::Revert { id, tx } => {
+                let _ = tx.send(self.revert(id).await);
+            }
+        }
+    }
+
+    /// Mines `blocks` blocks with all pending transactions of the pool.
+    async fn mine(&self, blocks: u64, interval: Option<u64>) -> Result<(), AutoSealError> {
+        for _ in 0..blocks {
+            let transactions = self.pool.best_transactions().collect();
+            let header = self.mine_block(transactions).await?;
+
+            if let Some(interval) = interval {
+                self.dev_state.lock().next_timestamp =
+                    Some(header.timestamp.saturating_add(interval));
+            }
+        }
+
+        // don't apply the interval to blocks mined after this call
+        if interval.is_some() {
+            self.dev_state.lock().next_timestamp = None;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the recovered transactions that can be included in the block with the given number
+    /// and timestamp.
+    ///
+    /// Conditional transactions are re-checked against the latest state, if one isn't satisfied
+    /// all later transactions of its sender are skipped as well because of the nonce gap.
+    fn satisfied_transactions(
+        &self,
+        transactions: Vec<Arc<ValidPoolTransaction<Pool::Transaction>>>,
+        number: u64,
+        timestamp: u64,
+    ) -> Result<Vec<TransactionSignedEcRecovered>, AutoSealError> {
+        let mut state = None;
+        let mut skipped_senders = HashSet::new();
+        let mut satisfied = Vec::with_capacity(transactions.len());
+        for tx in transactions {
+            if skipped_senders.contains(&tx.sender()) {
+                continue
+            }
+            if let Some(conditional) = &tx.conditional {
+                if state.is_none() {
+                    state = Some(self.client.latest()?);
+                }
+                let state = state.as_ref().expect("state is loaded");
+                if !conditional.is_satisfied(number, timestamp, state)? {
+                    skipped_senders.insert(tx.sender());
+                    continue
+                }
+            }
+            satisfied.push(tx.to_recovered_transaction());
+        }
+        Ok(satisfied)
+    }
+
+    /// Returns the header the engine considers finalized and safe once `head` is canonical: the
+    /// oldest snapshot, or `head` itself if there is none.
+    fn finalized_header(&self, storage: &StorageInner, head: &SealedHeader) -> SealedHeader {
+        self.dev_state
+            .lock()
+            .oldest_snapshot()
+            .and_then(|(number, hash)| storage.sealed_header(number, hash))
+            .unwrap_or_else(|| head.clone())
+    }
+
+    /// Builds a block with the given transactions on top of the current best block and makes it
+    /// the canonical head.
+    ///
+    /// Queued transactions of impersonated accounts are appended to the pool transactions. All
+    /// transactions are executed with their given senders, since the impersonated ones don't carry
+    /// a valid signature. Conditional transactions whose conditions aren't satisfied by the new
+    /// block are skipped together with all later transactions of the same sender.
+    async fn mine_block(
+        &self,
+        transactions: Vec<Arc<ValidPoolTransaction<Pool::Transaction>>>,
+    ) -> Result<SealedHeader, AutoSealError> {
+        let mut storage = self.storage.write().await;
+
+        let parent_timestamp =
+            storage.headers.get(&storage.best_block).map_or(0, |parent| parent.timestamp);
+        let (next_block, impersonated) = {
+            let mut dev_state = self.dev_state.lock();
+            (
+                dev_state.next_block(parent_timestamp),
+                std::mem::take(&mut dev_state.impersonated_transactions),
+            )
+        };
+        let mut transactions = self.satisfied_transactions(
+            transactions,
+            storage.best_block + 1,
+            next_block.timestamp,
+        )?;
+        transactions.extend(impersonated);
+        let (block, _bundle_state) = storage.build_and_execute(
+            next_block,
+            transactions,
+            vec![],
+            &self.client,
+            Arc::clone(&self.chain_spec),
+            &self.executor,
+        )?;
+
+        let new_header = block.header.clone();
+
+        // clear all transactions from pool
+        self.pool.remove_transactions(block.body.iter().map(|tx| tx.hash()).collect());
+
+        // blocks that can still be reverted to must not be finalized, otherwise they could be
+        // moved to static files
+        let finalized_header = self.finalized_header(&storage, &new_header);
+        let finalized_block_hash = finalized_header.hash();
+        let dirty = std::mem::replace(&mut self.dev_state.lock().dirty, false);
+        if dirty {
+            debug!(target: "consensus::auto", number=new_header.number, "Mined block on top of modified state");
+        }
+
+        let state = ForkchoiceState {
+            head_block_hash: new_header.hash(),
+            finalized_block_hash,
+            safe_block_hash: finalized_block_hash,
+        };
+        drop(storage);
+
+        // insert the block with its senders before the engine is notified, otherwise the engine
+        // would download it and recover the senders from the signatures
+        self.client.insert_block(block, BlockValidationKind::Exhaustive)?;
+
+        // TODO: make this a future
+        // await the fcu call rx for SYNCING, then wait for a VALID response
+        loop {
+            // send the new update to the engine, this will trigger the engine
+            // to download and execute the block we just inserted
+            let (tx, rx) = oneshot::channel();
+            let _ = self.to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
+                state,
+                payload_attrs: None,
+                tx,
+            });
+            debug!(target: "consensus::auto", ?state, "Sent fork choice update");
+
+            let fcu_response = rx
+                .await
+                .map_err(|_| BeaconForkChoiceUpdateError::EngineUnavailable)?
+                .map_err(BeaconForkChoiceUpdateError::from)?;
+            match fcu_response.forkchoice_status() {
+                ForkchoiceStatus::Valid => break,
+                ForkchoiceStatus::Invalid => {
+                    error!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned invalid response");
+                    return Err(AutoSealError::InvalidBlock(new_header.hash()))
+                }
+                ForkchoiceStatus::Syncing => {
+                    debug!(target: "consensus::auto", ?fcu_response, "Forkchoice update returned SYNCING, waiting for VALID");
+                    // wait for the next fork choice update
+                    continue
+                }
+            }
+        }
+
+        // update canon chain for rpc, consistent with the forkchoice state sent to the engine
+        self.client.set_canonical_head(new_header.clone());
+        self.client.set_safe(finalized_header.clone());
+        self.client.set_finalized(finalized_header);
+
+        Ok(new_header)
+    }
+
+    /// Applies the [`AccountUpdate`] to the latest state.
+    async fn update_account(
+        &self,
+        address: Address,
+        update: AccountUpdate,
+    ) -> Result<(), AutoSealError> {
+        // changes are recorded in the changesets of the latest block, which are never reverted if
+        // a snapshot points to that block, or if it's the genesis block
+        let must_mine = {
+            let storage = self.storage.read().await;
+            let best_block = storage.best_block;
+            best_block == 0 ||
+                self.dev_state.lock().snapshots.values().any(|(number, _)| *number == best_block)
+        };
+        if must_mine {
+            self.mine_block(Vec::new()).await?;
+        }
+
+        let _storage = self.storage.write().await;
+        let block = self.client.update_account(address, update)?;
+        self.dev_state.lock().dirty = true;
+        debug!(target: "consensus::auto", ?address, block, "Updated account");
+
+        Ok(())
+    }
+
+    /// Records the current head and returns the id of the snapshot.
+    async fn snapshot(&self) -> Result<U256, AutoSealError> {
+        // a snapshot must point to a block that matches its state root, since it's verified when
+        // the chain is unwound to it
+        if self.dev_state.lock().dirty {
+            self.mine_block(Vec::new()).await?;
+        }
+
+        let storage = self.storage.read().await;
+        let mut dev_state = self.dev_state.lock();
+        let id = dev_state.next_snapshot_id;
+        dev_state.next_snapshot_id += U256::from(1);
+        dev_state.snapshots.insert(id, (storage.best_block, storage.best_hash));
+        debug!(target: "consensus::auto", %id, number=storage.best_block, "Created snapshot");
+
+        Ok(id)
+    }
+
+    /// Reverts the chain to the head recorded by the snapshot.
+    async fn revert(&self, id: U256) -> Result<bool, AutoSealError> {
+        let Some((number, hash)) = self.dev_state.lock().snapshots.get(&id).copied() else {
+            return Ok(false)
+        };
+
+        let mut storage = self.storage.write().await;
+        // the snapshot block must still be known before the database is unwound
+        let Some(header) = storage.sealed_header(number, hash) else { return Ok(false) };
+        self.client.unwind(number)?;
+        storage.unwind_to(number, hash);
+
+        {
+            let mut dev_state = self.dev_state.lock();
+            dev_state.snapshots.retain(|snapshot, _| *snapshot < id);
+            dev_state.dirty = false;
+        }
+        debug!(target: "consensus::auto", %id, number, ?hash, "Reverted to snapshot");
+
+        let finalized_header = self.finalized_header(&storage, &header);
+        self.client.set_canonical_head(header);
+        self.client.set_safe(finalized_header.clone());
+        self.client.set_finalized(finalized_header);
+
+        Ok(true)
+    }
+}
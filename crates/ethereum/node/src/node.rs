@@ -2,7 +2,9 @@
 
 use crate::{EthEngineTypes, EthEvmConfig};
 use reth_auto_seal_consensus::AutoSealConsensus;
-use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
+use reth_basic_payload_builder::{
+    BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig, BundleRefundSigner,
+};
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_evm_ethereum::execute::EthExecutorProvider;
 use reth_network::NetworkHandle;
@@ -186,8 +188,14 @@ where
         ctx: &BuilderContext<Node>,
         pool: Pool,
     ) -> eyre::Result<PayloadBuilderHandle<Node::Engine>> {
-        let payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default();
+        let mut payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default()
+            .with_bundle_pool(ctx.bundle_pool().clone());
         let conf = ctx.payload_builder_config();
+        if let Some(key) = conf.bundle_refund_key() {
+            let signer = BundleRefundSigner::new(key)
+                .ok_or_else(|| eyre::eyre!("invalid bundle refund key"))?;
+            payload_builder = payload_builder.with_bundle_refund_signer(signer);
+        }
 
         let payload_job_config = BasicPayloadJobGeneratorConfig::default()
             .interval(conf.interval())
@@ -10,15 +10,16 @@
 #![allow(clippy::useless_let_if_seq)]
 
 use reth_basic_payload_builder::{
-    commit_withdrawals, is_better_payload, post_block_withdrawal_requests_contract_call,
-    pre_block_beacon_root_contract_call, BuildArguments, BuildOutcome, PayloadBuilder,
-    PayloadConfig, WithdrawalsOutcome,
+    commit_withdrawals, execute_bundle, is_better_payload,
+    post_block_withdrawal_requests_contract_call, pre_block_beacon_root_contract_call,
+    BuildArguments, BuildOutcome, BundleRefundSigner, PayloadBuilder, PayloadConfig,
+    WithdrawalsOutcome,
 };
 use reth_errors::RethError;
 use reth_evm::ConfigureEvm;
 use reth_evm_ethereum::{eip6110::parse_deposits_from_receipts, EthEvmConfig};
 use reth_payload_builder::{
-    error::PayloadBuilderError, EthBuiltPayload, EthPayloadBuilderAttributes,
+    error::PayloadBuilderError, BundlePool, EthBuiltPayload, EthPayloadBuilderAttributes,
 };
 use reth_primitives::{
     constants::{
@@ -37,19 +38,37 @@ use revm::{
     primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
     DatabaseCommit, State,
 };
+use std::collections::HashSet;
 use tracing::{debug, trace, warn};
 
 /// Ethereum payload builder
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig> {
     /// The type responsible for creating the evm.
     evm_config: EvmConfig,
+    /// The pool of MEV bundles that are included in the payloads, if any.
+    bundle_pool: Option<BundlePool>,
+    /// Pays the refunds of included bundles, if any.
+    refund_signer: Option<BundleRefundSigner>,
 }
 
 impl<EvmConfig> EthereumPayloadBuilder<EvmConfig> {
     /// `EthereumPayloadBuilder` constructor.
     pub const fn new(evm_config: EvmConfig) -> Self {
-        Self { evm_config }
+        Self { evm_config, bundle_pool: None, refund_signer: None }
+    }
+
+    /// Includes the bundles of the given [`BundlePool`] that target the block of the payload.
+    pub fn with_bundle_pool(mut self, bundle_pool: BundlePool) -> Self {
+        self.bundle_pool = Some(bundle_pool);
+        self
+    }
+
+    /// Pays the refunds of bundles with the given signer, bundles with refunds are only included
+    /// if the fee recipient of the payload is the signer.
+    pub fn with_bundle_refund_signer(mut self, refund_signer: BundleRefundSigner) -> Self {
+        self.refund_signer = Some(refund_signer);
+        self
     }
 }
 
@@ -73,7 +92,12 @@ where
         &self,
         args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
     ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
-        default_ethereum_payload_builder(self.evm_config.clone(), args)
+        default_ethereum_payload_builder(
+            self.evm_config.clone(),
+            self.bundle_pool.as_ref(),
+            self.refund_signer.as_ref(),
+            args,
+        )
     }
 
     fn build_empty_payload(
@@ -244,6 +268,8 @@ where
 #[inline]
 pub fn default_ethereum_payload_builder<EvmConfig, Pool, Client>(
     evm_config: EvmConfig,
+    bundle_pool: Option<&BundlePool>,
+    refund_signer: Option<&BundleRefundSigner>,
     args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
 ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
 where
@@ -305,7 +331,61 @@ where
     .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
 
     let mut receipts = Vec::new();
+    // the transactions included by bundles, which are skipped if they are also in the pool
+    let mut included_txs = HashSet::new();
+
+    // include the bundles that target this block before any pool transactions
+    for bundle in bundle_pool.map(|pool| pool.bundles_for_block(block_number)).unwrap_or_default() {
+        if !bundle.is_allowed_builder(&initialized_block_env.coinbase) {
+            continue
+        }
+
+        // check if the job was cancelled, if so we can exit early
+        if cancel.is_cancelled() {
+            return Ok(BuildOutcome::Cancelled)
+        }
+
+        let Some(executed) = execute_bundle(
+            &evm_config,
+            &mut db,
+            &initialized_cfg,
+            &initialized_block_env,
+            &bundle,
+            block_gas_limit.saturating_sub(cumulative_gas_used),
+            refund_signer,
+            &mut included_txs,
+        )?
+        else {
+            continue
+        };
+
+        for (tx, result) in executed.transactions {
+            cumulative_gas_used += result.gas_used();
+            #[allow(clippy::needless_update)] // side-effect of optimism fields
+            receipts.push(Some(Receipt {
+                tx_type: tx.tx_type(),
+                success: result.is_success(),
+                cumulative_gas_used,
+                logs: result.into_logs().into_iter().map(Into::into).collect(),
+                ..Default::default()
+            }));
+            executed_txs.push(tx.into_signed());
+        }
+        // the profit of the bundle includes the tips and direct payments to the fee recipient
+        total_fees += executed.profit;
+    }
+
+    // the parent state the conditions of conditional transactions are checked against, only
+    // loaded once a conditional transaction is encountered
+    let mut conditional_state = None;
+
     while let Some(pool_tx) = best_txs.next() {
+        // skip transactions that are already included by a bundle, their descendants are still
+        // valid
+        if included_txs.contains(pool_tx.hash()) {
+            continue
+        }
+
         // ensure we still have capacity for this transaction
         if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
             // we can't fit this transaction into the block, so we need to mark it as invalid
@@ -315,6 +395,18 @@ where
             continue
         }
 
+        // skip conditional transactions whose conditions aren't satisfied by this block
+        if let Some(conditional) = &pool_tx.conditional {
+            if conditional_state.is_none() {
+                conditional_state = Some(client.state_by_block_hash(parent_block.hash())?);
+            }
+            let state = conditional_state.as_ref().expect("state is loaded");
+            if !conditional.is_satisfied(block_number, attributes.timestamp, state)? {
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+        }
+
         // check if the job was cancelled, if so we can exit early
         if cancel.is_cancelled() {
             return Ok(BuildOutcome::Cancelled)
@@ -0,0 +1,66 @@
+//! The Era1 accumulator, the SSZ hash tree root of `List[HeaderRecord, MAX_BLOCKS_PER_ERA1]`.
+
+use super::MAX_BLOCKS_PER_ERA1;
+use reth_primitives::{B256, U256};
+use sha2::{Digest, Sha256};
+
+/// Depth of the merkle tree over the maximum number of header records.
+const DEPTH: usize = MAX_BLOCKS_PER_ERA1.trailing_zeros() as usize;
+
+/// Computes the accumulator root over the `(block hash, total difficulty)` records of the blocks
+/// in a file.
+///
+/// # Panics
+///
+/// If there are more than [`MAX_BLOCKS_PER_ERA1`] records.
+pub fn accumulator_root(records: impl IntoIterator<Item = (B256, U256)>) -> B256 {
+    let mut layer = records
+        .into_iter()
+        .map(|(hash, total_difficulty)| {
+            sha256_pair(hash, B256::from(total_difficulty.to_le_bytes::<32>()))
+        })
+        .collect::<Vec<_>>();
+    let len = layer.len();
+    assert!(len <= MAX_BLOCKS_PER_ERA1, "too many header records");
+
+    // the list is padded with zero hashes up to its limit
+    let mut zero = B256::ZERO;
+    for _ in 0..DEPTH {
+        if layer.len() % 2 == 1 {
+            layer.push(zero);
+        }
+        layer = layer.chunks(2).map(|pair| sha256_pair(pair[0], pair[1])).collect();
+        zero = sha256_pair(zero, zero);
+    }
+    let root = layer.first().copied().unwrap_or(zero);
+
+    // mix in the length of the list
+    sha256_pair(root, B256::from(U256::from(len).to_le_bytes::<32>()))
+}
+
+fn sha256_pair(left: B256, right: B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_record() {
+        let record = (B256::repeat_byte(1), U256::from(2));
+        let mut root = sha256_pair(record.0, B256::from(record.1.to_le_bytes::<32>()));
+        let mut zero = B256::ZERO;
+        for _ in 0..DEPTH {
+            root = sha256_pair(root, zero);
+            zero = sha256_pair(zero, zero);
+        }
+        let expected = sha256_pair(root, B256::from(U256::from(1).to_le_bytes::<32>()));
+
+        assert_eq!(accumulator_root([record]), expected);
+        assert_ne!(accumulator_root([record, record]), expected);
+    }
+}
@@ -0,0 +1,107 @@
+//! The `e2store` record format Era1 archives are built on.
+//!
+//! Each record is encoded as `type (2 bytes LE) | length (4 bytes LE) | reserved (2 bytes) | data`.
+
+use super::Era1Error;
+use std::io::{self, Write};
+
+/// Length of a record header in bytes.
+pub const HEADER_LEN: usize = 8;
+
+/// Record type of the version record, which starts every file.
+pub const VERSION: u16 = 0x3265;
+/// Record type of a snappy compressed, RLP encoded header.
+pub const COMPRESSED_HEADER: u16 = 0x03;
+/// Record type of a snappy compressed, RLP encoded block body.
+pub const COMPRESSED_BODY: u16 = 0x04;
+/// Record type of snappy compressed, RLP encoded block receipts.
+pub const COMPRESSED_RECEIPTS: u16 = 0x05;
+/// Record type of a block's total difficulty, encoded as 32 bytes little endian.
+pub const TOTAL_DIFFICULTY: u16 = 0x06;
+/// Record type of the accumulator root of all blocks in the file.
+pub const ACCUMULATOR: u16 = 0x07;
+/// Record type of the block index, which terminates every file.
+pub const BLOCK_INDEX: u16 = 0x3266;
+
+/// A single `e2store` record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The record type.
+    pub ty: u16,
+    /// The record data.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Creates a new record.
+    pub const fn new(ty: u16, data: Vec<u8>) -> Self {
+        Self { ty, data }
+    }
+
+    /// Returns the encoded length of the record, including the header.
+    pub fn len(&self) -> usize {
+        HEADER_LEN + self.data.len()
+    }
+
+    /// Returns true if the record has no data.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Writes the encoded record.
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let len = u32::try_from(self.data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "e2store record too large"))?;
+        writer.write_all(&self.ty.to_le_bytes())?;
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(&[0, 0])?;
+        writer.write_all(&self.data)
+    }
+
+    /// Reads the next record from the buffer and advances it.
+    ///
+    /// Returns `None` if the buffer is empty.
+    pub fn read(buf: &mut &[u8]) -> Result<Option<Self>, Era1Error> {
+        if buf.is_empty() {
+            return Ok(None)
+        }
+        if buf.len() < HEADER_LEN {
+            return Err(Era1Error::Malformed("truncated e2store record header"))
+        }
+
+        let ty = u16::from_le_bytes([buf[0], buf[1]]);
+        let len = u32::from_le_bytes([buf[2], buf[3], buf[4], buf[5]]) as usize;
+        if buf[6..HEADER_LEN] != [0, 0] {
+            return Err(Era1Error::Malformed("non zero reserved bytes in e2store record header"))
+        }
+        if buf.len() < HEADER_LEN + len {
+            return Err(Era1Error::Malformed("truncated e2store record data"))
+        }
+
+        let data = buf[HEADER_LEN..HEADER_LEN + len].to_vec();
+        *buf = &buf[HEADER_LEN + len..];
+        Ok(Some(Self { ty, data }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let entries = [Entry::new(VERSION, vec![]), Entry::new(ACCUMULATOR, vec![1; 32])];
+        let mut encoded = Vec::new();
+        for entry in &entries {
+            entry.write(&mut encoded).unwrap();
+        }
+        assert_eq!(encoded.len(), entries.iter().map(Entry::len).sum::<usize>());
+        assert_eq!(&encoded[..HEADER_LEN], &[0x65, 0x32, 0, 0, 0, 0, 0, 0]);
+
+        let mut buf = &encoded[..];
+        for entry in entries {
+            assert_eq!(Entry::read(&mut buf).unwrap(), Some(entry));
+        }
+        assert_eq!(Entry::read(&mut buf).unwrap(), None);
+    }
+}
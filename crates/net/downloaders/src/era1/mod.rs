@@ -0,0 +1,524 @@
+//! Reading and writing [Era1](https://github.com/ethereum/go-ethereum/pull/26621) archives.
+//!
+//! An Era1 file contains up to [`MAX_BLOCKS_PER_ERA1`] consecutive blocks with their receipts and
+//! total difficulty, followed by an accumulator root over all block hashes and total
+//! difficulties, and an index of the block offsets:
+//!
+//! ```text
+//! era1 := Version | block-tuple* | other-entries* | Accumulator | BlockIndex
+//! block-tuple := CompressedHeader | CompressedBody | CompressedReceipts | TotalDifficulty
+//! ```
+
+use crate::file_client::FileClient;
+use alloy_rlp::{Decodable, Encodable};
+use reth_primitives::{
+    hex, proofs::calculate_receipt_root, Block, BlockBody, BlockNumber, Header, ReceiptWithBloom,
+    B256, U256,
+};
+use std::{
+    io::{self, Read, Write},
+    path::Path,
+};
+use thiserror::Error;
+
+mod accumulator;
+pub use accumulator::accumulator_root;
+
+pub mod e2s;
+use e2s::Entry;
+
+/// Maximum number of blocks in a single Era1 file.
+pub const MAX_BLOCKS_PER_ERA1: usize = 8192;
+
+/// File extension of Era1 files.
+pub const ERA1_EXTENSION: &str = "era1";
+
+/// Returns the conventional file name of an Era1 file, `<network>-<epoch>-<short root>.era1`.
+pub fn era1_file_name(network: &str, epoch: u64, accumulator: B256) -> String {
+    format!("{network}-{epoch:05}-{}.{ERA1_EXTENSION}", hex_prefix(accumulator))
+}
+
+/// Returns the first 4 bytes of the accumulator root as hex, as used in file names.
+fn hex_prefix(accumulator: B256) -> String {
+    hex::encode(&accumulator[..4])
+}
+
+/// An error that can occur when reading or writing Era1 files.
+#[derive(Debug, Error)]
+pub enum Era1Error {
+    /// An error occurred when reading or writing the file.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occurred when decoding a record.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The file does not follow the Era1 format.
+    #[error("malformed era1 file: {0}")]
+    Malformed(&'static str),
+    /// The file contains more than [`MAX_BLOCKS_PER_ERA1`] blocks.
+    #[error("era1 file exceeds {MAX_BLOCKS_PER_ERA1} blocks")]
+    TooManyBlocks,
+    /// The blocks in the file are not a chain of consecutive blocks.
+    #[error("block {0} does not extend the previous block")]
+    Discontinuous(BlockNumber),
+    /// The total difficulty of a block does not match its difficulty.
+    #[error("total difficulty of block {0} does not match its difficulty")]
+    TotalDifficultyMismatch(BlockNumber),
+    /// The receipts of a block do not match its receipts root.
+    #[error("receipts of block {0} do not match its receipts root")]
+    ReceiptsRootMismatch(BlockNumber),
+    /// The accumulator root does not match the blocks in the file.
+    #[error("accumulator mismatch: expected {expected}, computed {computed}")]
+    AccumulatorMismatch {
+        /// The accumulator root in the file.
+        expected: B256,
+        /// The accumulator root computed from the blocks.
+        computed: B256,
+    },
+    /// The accumulator root does not match the file name.
+    #[error("accumulator {0} does not match the file name")]
+    FileNameMismatch(B256),
+    /// The accumulator root is not the trusted root of the file's epoch.
+    #[error("accumulator {accumulator} is not the trusted accumulator of epoch {epoch}")]
+    UntrustedAccumulator {
+        /// The epoch of the first block in the file.
+        epoch: u64,
+        /// The accumulator root in the file.
+        accumulator: B256,
+    },
+}
+
+/// A block with its receipts and total difficulty, as stored in an Era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The block.
+    pub block: Block,
+    /// The receipts of the block's transactions.
+    pub receipts: Vec<ReceiptWithBloom>,
+    /// The total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+/// The receipts and total difficulties of the blocks of an Era1 file, which aren't served by its
+/// [`FileClient`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Era1Records {
+    /// Number of the first block.
+    pub first_block: BlockNumber,
+    /// The receipts of every block, in block order.
+    pub receipts: Vec<Vec<ReceiptWithBloom>>,
+    /// The total difficulty of every block, in block order.
+    pub total_difficulties: Vec<U256>,
+}
+
+impl Era1Records {
+    /// Returns the receipts of the block, if it's in the file.
+    pub fn receipts(&self, number: BlockNumber) -> Option<&[ReceiptWithBloom]> {
+        let index = usize::try_from(number.checked_sub(self.first_block)?).ok()?;
+        self.receipts.get(index).map(Vec::as_slice)
+    }
+
+    /// Returns the total difficulty of the block, if it's in the file.
+    pub fn total_difficulty(&self, number: BlockNumber) -> Option<U256> {
+        let index = usize::try_from(number.checked_sub(self.first_block)?).ok()?;
+        self.total_difficulties.get(index).copied()
+    }
+}
+
+/// Writes blocks to an Era1 file.
+#[derive(Debug)]
+pub struct Era1Writer<W> {
+    writer: W,
+    /// Number of bytes written so far.
+    position: u64,
+    /// Number of the first block in the file.
+    start_number: Option<BlockNumber>,
+    /// Positions of the header records.
+    offsets: Vec<u64>,
+    /// The hash and total difficulty of every written block, for the accumulator.
+    records: Vec<(B256, U256)>,
+}
+
+impl<W: Write> Era1Writer<W> {
+    /// Creates a new writer and writes the version record.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        let version = Entry::new(e2s::VERSION, Vec::new());
+        version.write(&mut writer)?;
+        Ok(Self {
+            writer,
+            position: version.len() as u64,
+            start_number: None,
+            offsets: Vec::new(),
+            records: Vec::new(),
+        })
+    }
+
+    /// Returns the number of blocks written so far.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if no blocks were written.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Appends the next block to the file.
+    pub fn append(&mut self, block: &Era1Block) -> Result<(), Era1Error> {
+        if self.records.len() == MAX_BLOCKS_PER_ERA1 {
+            return Err(Era1Error::TooManyBlocks)
+        }
+        let number = block.block.number;
+        let expected_number = self.start_number.map(|start| start + self.records.len() as u64);
+        if expected_number.is_some_and(|expected| expected != number) {
+            return Err(Era1Error::Discontinuous(number))
+        }
+        self.start_number.get_or_insert(number);
+
+        let Block { header, body, ommers, withdrawals, requests } = block.block.clone();
+        let hash = header.hash_slow();
+        let body = BlockBody { transactions: body, ommers, withdrawals, requests };
+
+        self.offsets.push(self.position);
+        self.write(Entry::new(e2s::COMPRESSED_HEADER, compress(&header)?))?;
+        self.write(Entry::new(e2s::COMPRESSED_BODY, compress(&body)?))?;
+        self.write(Entry::new(e2s::COMPRESSED_RECEIPTS, compress(&block.receipts)?))?;
+        self.write(Entry::new(
+            e2s::TOTAL_DIFFICULTY,
+            block.total_difficulty.to_le_bytes::<32>().to_vec(),
+        ))?;
+        self.records.push((hash, block.total_difficulty));
+
+        Ok(())
+    }
+
+    /// Writes the accumulator and block index, and returns the accumulator root.
+    pub fn finish(mut self) -> Result<B256, Era1Error> {
+        let accumulator = accumulator_root(self.records.iter().copied());
+        self.write(Entry::new(e2s::ACCUMULATOR, accumulator.to_vec()))?;
+
+        let index_position = self.position as i64;
+        let mut index = Vec::with_capacity(16 + self.offsets.len() * 8);
+        index.extend_from_slice(&self.start_number.unwrap_or_default().to_le_bytes());
+        for offset in &self.offsets {
+            index.extend_from_slice(&(*offset as i64 - index_position).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        self.write(Entry::new(e2s::BLOCK_INDEX, index))?;
+        self.writer.flush()?;
+
+        Ok(accumulator)
+    }
+
+    fn write(&mut self, entry: Entry) -> io::Result<()> {
+        entry.write(&mut self.writer)?;
+        self.position += entry.len() as u64;
+        Ok(())
+    }
+}
+
+/// The decoded contents of an Era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1File {
+    /// The blocks in the file.
+    pub blocks: Vec<Era1Block>,
+    /// The accumulator root stored in the file.
+    pub accumulator: B256,
+}
+
+impl Era1File {
+    /// Reads and decodes an Era1 file.
+    ///
+    /// If the file has the conventional Era1 file name, the accumulator root is checked against
+    /// it.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Era1Error> {
+        let path = path.as_ref();
+        let file = Self::decode(&std::fs::read(path)?)?;
+
+        let short_root = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.rsplit('-').next())
+            .filter(|short_root| short_root.len() == 8);
+        if short_root.is_some_and(|short_root| short_root != hex_prefix(file.accumulator)) {
+            return Err(Era1Error::FileNameMismatch(file.accumulator))
+        }
+
+        Ok(file)
+    }
+
+    /// Decodes the contents of an Era1 file.
+    pub fn decode(mut buf: &[u8]) -> Result<Self, Era1Error> {
+        let total_len = buf.len();
+        let position = |buf: &[u8]| (total_len - buf.len()) as i64;
+
+        match Entry::read(&mut buf)? {
+            Some(entry) if entry.ty == e2s::VERSION => {}
+            _ => return Err(Era1Error::Malformed("missing version record")),
+        }
+
+        let mut blocks = Vec::new();
+        let mut offsets = Vec::new();
+        let accumulator = loop {
+            let offset = position(buf);
+            let entry =
+                Entry::read(&mut buf)?.ok_or(Era1Error::Malformed("missing accumulator record"))?;
+            match entry.ty {
+                e2s::COMPRESSED_HEADER => {
+                    let header: Header = decompress(&entry.data)?;
+                    let body: BlockBody = decompress(&expect(&mut buf, e2s::COMPRESSED_BODY)?)?;
+                    let receipts = decompress(&expect(&mut buf, e2s::COMPRESSED_RECEIPTS)?)?;
+                    let total_difficulty = expect(&mut buf, e2s::TOTAL_DIFFICULTY)?;
+                    if total_difficulty.len() != 32 {
+                        return Err(Era1Error::Malformed("invalid total difficulty length"))
+                    }
+
+                    blocks.push(Era1Block {
+                        block: body.create_block(header),
+                        receipts,
+                        total_difficulty: U256::from_le_slice(&total_difficulty),
+                    });
+                    offsets.push(offset);
+                    if blocks.len() > MAX_BLOCKS_PER_ERA1 {
+                        return Err(Era1Error::TooManyBlocks)
+                    }
+                }
+                e2s::ACCUMULATOR if entry.data.len() == 32 => break B256::from_slice(&entry.data),
+                e2s::ACCUMULATOR => return Err(Era1Error::Malformed("invalid accumulator length")),
+                // other entries are allowed and ignored
+                _ => {}
+            }
+        };
+
+        // the block index must point to the header records
+        let index_position = position(buf);
+        let index = expect(&mut buf, e2s::BLOCK_INDEX)?;
+        if !buf.is_empty() {
+            return Err(Era1Error::Malformed("data after block index"))
+        }
+        if index.len() != 16 + offsets.len() * 8 {
+            return Err(Era1Error::Malformed("block index does not match the number of blocks"))
+        }
+        let words = index
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().expect("8 byte chunk")))
+            .collect::<Vec<_>>();
+        let start_number = blocks.first().map(|block| block.block.number).unwrap_or(words[0]);
+        if words[0] != start_number || words[words.len() - 1] != offsets.len() as u64 {
+            return Err(Era1Error::Malformed("invalid block index"))
+        }
+        for (offset, indexed) in offsets.iter().zip(&words[1..words.len() - 1]) {
+            if index_position + *indexed as i64 != *offset {
+                return Err(Era1Error::Malformed("block index offset does not point to header"))
+            }
+        }
+
+        Ok(Self { blocks, accumulator })
+    }
+
+    /// Returns the number of the first block in the file.
+    pub fn start_number(&self) -> Option<BlockNumber> {
+        self.blocks.first().map(|block| block.block.number)
+    }
+
+    /// Returns the number of the last block in the file.
+    pub fn end_number(&self) -> Option<BlockNumber> {
+        self.blocks.last().map(|block| block.block.number)
+    }
+
+    /// Verifies that the blocks form a chain, that their receipts and total difficulties match
+    /// the headers, and that the accumulator root matches the blocks.
+    pub fn verify(&self) -> Result<(), Era1Error> {
+        let mut parent: Option<(B256, &Era1Block)> = None;
+        let mut records = Vec::with_capacity(self.blocks.len());
+        for block in &self.blocks {
+            let number = block.block.number;
+            if let Some((parent_hash, parent)) = parent {
+                if number != parent.block.number + 1 || block.block.parent_hash != parent_hash {
+                    return Err(Era1Error::Discontinuous(number))
+                }
+                if block.total_difficulty != parent.total_difficulty + block.block.difficulty {
+                    return Err(Era1Error::TotalDifficultyMismatch(number))
+                }
+            }
+            if calculate_receipt_root(&block.receipts) != block.block.receipts_root {
+                return Err(Era1Error::ReceiptsRootMismatch(number))
+            }
+
+            let hash = block.block.header.hash_slow();
+            records.push((hash, block.total_difficulty));
+            parent = Some((hash, block));
+        }
+
+        let computed = accumulator_root(records);
+        if computed != self.accumulator {
+            return Err(Era1Error::AccumulatorMismatch { expected: self.accumulator, computed })
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the accumulator root is the trusted root of the file's epoch.
+    ///
+    /// `trusted` holds the accumulator root of every epoch in order, so only files that start at
+    /// an epoch boundary can be trusted.
+    pub fn verify_trusted(&self, trusted: &[B256]) -> Result<(), Era1Error> {
+        let start = self.start_number().unwrap_or_default();
+        let epoch = start / MAX_BLOCKS_PER_ERA1 as u64;
+        let is_trusted = start % MAX_BLOCKS_PER_ERA1 as u64 == 0 &&
+            usize::try_from(epoch).ok().and_then(|epoch| trusted.get(epoch)) ==
+                Some(&self.accumulator);
+        if !is_trusted {
+            return Err(Era1Error::UntrustedAccumulator { epoch, accumulator: self.accumulator })
+        }
+        Ok(())
+    }
+
+    /// Returns the total number of transactions in the file.
+    pub fn total_transactions(&self) -> usize {
+        self.blocks.iter().map(|block| block.block.body.len()).sum()
+    }
+
+    /// Converts the file into a [`FileClient`] serving its headers and bodies, and the
+    /// [`Era1Records`] of the receipts and total difficulties of its blocks.
+    pub fn into_file_client(self) -> (FileClient, Era1Records) {
+        let mut records = Era1Records {
+            first_block: self.start_number().unwrap_or_default(),
+            receipts: Vec::with_capacity(self.blocks.len()),
+            total_difficulties: Vec::with_capacity(self.blocks.len()),
+        };
+        let blocks = self.blocks.into_iter().map(|block| {
+            records.receipts.push(block.receipts);
+            records.total_difficulties.push(block.total_difficulty);
+            block.block
+        });
+        let file_client = FileClient::from_blocks(blocks);
+
+        (file_client, records)
+    }
+}
+
+/// Reads the next record and returns its data if it has the expected type.
+fn expect(buf: &mut &[u8], ty: u16) -> Result<Vec<u8>, Era1Error> {
+    match Entry::read(buf)? {
+        Some(entry) if entry.ty == ty => Ok(entry.data),
+        _ => Err(Era1Error::Malformed("unexpected record type")),
+    }
+}
+
+/// RLP encodes and snappy compresses the value.
+fn compress(value: &impl Encodable) -> io::Result<Vec<u8>> {
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(&alloy_rlp::encode(value))?;
+    encoder.into_inner().map_err(|err| err.into_error())
+}
+
+/// Snappy decompresses and RLP decodes the value.
+fn decompress<T: Decodable>(data: &[u8]) -> Result<T, Era1Error> {
+    let mut decoded = Vec::new();
+    snap::read::FrameDecoder::new(data).read_to_end(&mut decoded)?;
+    Ok(T::decode(&mut &decoded[..])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_testing_utils::generators::{self, random_block, random_receipt};
+
+    fn era1_blocks(count: u64) -> Vec<Era1Block> {
+        let mut rng = generators::rng();
+        let mut blocks = Vec::new();
+        let mut parent = B256::ZERO;
+        let mut total_difficulty = U256::ZERO;
+        for number in 0..count {
+            let mut block = random_block(&mut rng, number, Some(parent), Some(2), Some(0)).unseal();
+            let receipts = block
+                .body
+                .iter()
+                .map(|tx| random_receipt(&mut rng, tx, Some(1)).with_bloom())
+                .collect::<Vec<_>>();
+            block.header.receipts_root = calculate_receipt_root(&receipts);
+            total_difficulty += block.difficulty;
+            parent = block.header.hash_slow();
+            blocks.push(Era1Block { block, receipts, total_difficulty });
+        }
+        blocks
+    }
+
+    fn encode(blocks: &[Era1Block]) -> (Vec<u8>, B256) {
+        let mut file = Vec::new();
+        let mut writer = Era1Writer::new(&mut file).unwrap();
+        for block in blocks {
+            writer.append(block).unwrap();
+        }
+        assert_eq!(writer.len(), blocks.len());
+        let accumulator = writer.finish().unwrap();
+        (file, accumulator)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let blocks = era1_blocks(5);
+        let (file, accumulator) = encode(&blocks);
+
+        let decoded = Era1File::decode(&file).unwrap();
+        assert_eq!(decoded.blocks, blocks);
+        assert_eq!(decoded.accumulator, accumulator);
+        decoded.verify().unwrap();
+        assert_eq!(decoded.start_number(), Some(0));
+        assert_eq!(decoded.end_number(), Some(4));
+
+        let (client, records) = decoded.into_file_client();
+        assert_eq!(client.headers_len(), 5);
+        assert!(client.has_canonical_blocks());
+        for block in &blocks {
+            let number = block.block.number;
+            assert_eq!(records.receipts(number), Some(&block.receipts[..]));
+            assert_eq!(records.total_difficulty(number), Some(block.total_difficulty));
+        }
+        assert_eq!(records.receipts(5), None);
+        assert_eq!(records.total_difficulty(5), None);
+    }
+
+    #[test]
+    fn verify_trusted_accumulator() {
+        let (file, accumulator) = encode(&era1_blocks(3));
+        let decoded = Era1File::decode(&file).unwrap();
+
+        decoded.verify_trusted(&[accumulator]).unwrap();
+        assert!(matches!(
+            decoded.verify_trusted(&[B256::ZERO, accumulator]),
+            Err(Era1Error::UntrustedAccumulator { epoch: 0, .. })
+        ));
+        assert!(matches!(
+            decoded.verify_trusted(&[]),
+            Err(Era1Error::UntrustedAccumulator { epoch: 0, .. })
+        ));
+
+        // a file that doesn't start at an epoch boundary can't be trusted
+        let mut blocks = era1_blocks(4);
+        blocks.remove(0);
+        let (file, accumulator) = encode(&blocks);
+        let decoded = Era1File::decode(&file).unwrap();
+        assert!(matches!(
+            decoded.verify_trusted(&[accumulator]),
+            Err(Era1Error::UntrustedAccumulator { epoch: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_file() {
+        let mut blocks = era1_blocks(3);
+        let (file, _) = encode(&blocks);
+
+        let mut decoded = Era1File::decode(&file).unwrap();
+        decoded.blocks[1].total_difficulty += U256::from(1);
+        assert!(matches!(decoded.verify(), Err(Era1Error::TotalDifficultyMismatch(1))));
+
+        blocks[2].block.number = 5;
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        writer.append(&blocks[0]).unwrap();
+        writer.append(&blocks[1]).unwrap();
+        assert!(matches!(writer.append(&blocks[2]), Err(Era1Error::Discontinuous(5))));
+    }
+}
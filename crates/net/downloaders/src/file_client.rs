@@ -10,8 +10,8 @@ use reth_network_p2p::{
 };
 use reth_network_peers::PeerId;
 use reth_primitives::{
-    BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, BytesMut, Header, HeadersDirection,
-    SealedHeader, B256,
+    Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, BytesMut, Header,
+    HeadersDirection, SealedHeader, B256,
 };
 use std::{collections::HashMap, io, path::Path};
 use thiserror::Error;
@@ -89,6 +89,22 @@ impl FileClient {
         Ok(Self::from_reader(&reader[..], file_len).await?.0)
     }
 
+    /// Creates a new file client serving the given blocks.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = Block>) -> Self {
+        let mut headers = HashMap::new();
+        let mut hash_to_number = HashMap::new();
+        let mut bodies = HashMap::new();
+
+        for block in blocks {
+            let block_hash = block.header.hash_slow();
+            hash_to_number.insert(block_hash, block.header.number);
+            headers.insert(block.header.number, block.header.clone());
+            bodies.insert(block_hash, BlockBody::from(block));
+        }
+
+        Self { headers, hash_to_number, bodies }
+    }
+
     /// Get the tip hash of the chain.
     pub fn tip(&self) -> Option<B256> {
         self.headers.get(&self.max_block()?).map(|h| h.hash_slow())
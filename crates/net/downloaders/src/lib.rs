@@ -41,6 +41,12 @@ pub mod file_client;
 /// made for op-erigon's import needs).
 pub mod receipt_file_client;
 
+/// Module for reading and writing Era1 archives of blocks, receipts and total difficulty.
+///
+/// Contains [`Era1File`](era1::Era1File), which can be converted into a
+/// [`FileClient`](file_client::FileClient) to import the archived blocks.
+pub mod era1;
+
 /// Module with a codec for reading and encoding block bodies in files.
 ///
 /// Enables decoding and encoding `Block` types within file contexts.
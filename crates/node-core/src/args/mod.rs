@@ -12,6 +12,14 @@ pub use rpc_server::RpcServerArgs;
 mod rpc_state_cache;
 pub use rpc_state_cache::RpcStateCacheArgs;
 
+/// `RpcRateLimitArgs` struct for configuring rate limiting of the RPC servers
+mod rpc_rate_limit;
+pub use rpc_rate_limit::RpcRateLimitArgs;
+
+/// `RpcValidationArgs` struct for configuring the builder submission validation API
+mod rpc_validation;
+pub use rpc_validation::RpcValidationArgs;
+
 /// DebugArgs struct for debugging purposes
 mod debug;
 pub use debug::DebugArgs;
@@ -6,8 +6,9 @@ use clap::{
     builder::{RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
-use reth_primitives::constants::{
-    ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION,
+use reth_primitives::{
+    constants::{ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION},
+    B256,
 };
 use std::{borrow::Cow, ffi::OsStr, time::Duration};
 
@@ -34,6 +35,13 @@ pub struct PayloadBuilderArgs {
     /// Maximum number of tasks to spawn for building a payload.
     #[arg(long = "builder.max-tasks", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
+
+    /// The private key of the fee recipient that pays the refunds of included MEV bundles.
+    ///
+    /// Bundles with refunds are only included in payloads whose fee recipient is the address of
+    /// this key.
+    #[arg(long = "builder.bundle-refund-key", value_name = "PRIVATE_KEY")]
+    pub bundle_refund_key: Option<B256>,
 }
 
 impl Default for PayloadBuilderArgs {
@@ -44,6 +52,7 @@ impl Default for PayloadBuilderArgs {
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            bundle_refund_key: None,
         }
     }
 }
@@ -68,6 +77,10 @@ impl PayloadBuilderConfig for PayloadBuilderArgs {
     fn max_payload_tasks(&self) -> usize {
         self.max_payload_tasks
     }
+
+    fn bundle_refund_key(&self) -> Option<B256> {
+        self.bundle_refund_key
+    }
 }
 
 #[derive(Clone, Debug, Default)]
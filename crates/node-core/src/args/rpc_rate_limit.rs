@@ -0,0 +1,128 @@
+use clap::Args;
+use reth_rpc_layer::{RateLimitConfig, DEFAULT_API_KEY_HEADER};
+use std::{net::IpAddr, str::FromStr};
+
+/// Parameters to configure rate limiting of the HTTP and WS RPC servers
+#[derive(Debug, Clone, Args, PartialEq, Eq)]
+#[command(next_help_heading = "RPC Rate Limiting")]
+pub struct RpcRateLimitArgs {
+    /// Credits each client IP can spend per second.
+    ///
+    /// The IP is the address of the TCP peer. For requests from a trusted proxy, it's taken from
+    /// the `X-Forwarded-For` or `X-Real-IP` header instead. Requests over IPC share a single
+    /// quota.
+    #[arg(long = "rpc.rate-limit.per-ip", value_name = "CREDITS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub per_ip: Option<u32>,
+
+    /// IP of a reverse proxy whose `X-Forwarded-For` and `X-Real-IP` headers are trusted. Can be
+    /// repeated.
+    #[arg(long = "rpc.rate-limit.trusted-proxy", value_name = "IP")]
+    pub trusted_proxies: Vec<IpAddr>,
+
+    /// API key with its own quota of credits per second, as `<KEY>=<CREDITS>`. Can be repeated.
+    #[arg(long = "rpc.rate-limit.api-key", value_name = "KEY=CREDITS", value_parser = parse_key_value::<u32>)]
+    pub api_keys: Vec<(String, u32)>,
+
+    /// Header carrying the API key of a request
+    #[arg(long = "rpc.rate-limit.api-key-header", value_name = "HEADER", default_value = DEFAULT_API_KEY_HEADER)]
+    pub api_key_header: String,
+
+    /// Cost of a method in credits, as `<METHOD>=<CREDITS>`, overriding the built-in cost of
+    /// expensive methods. Other methods cost 1 credit. Can be repeated.
+    #[arg(long = "rpc.rate-limit.method-cost", value_name = "METHOD=CREDITS", value_parser = parse_key_value::<u32>)]
+    pub method_costs: Vec<(String, u32)>,
+
+    /// Maximum number of requests of a namespace that are processed concurrently, as
+    /// `<NAMESPACE>=<COUNT>`. Can be repeated.
+    #[arg(long = "rpc.rate-limit.max-concurrent", value_name = "NAMESPACE=COUNT", value_parser = parse_key_value::<usize>)]
+    pub max_concurrent: Vec<(String, usize)>,
+}
+
+impl RpcRateLimitArgs {
+    /// Returns the rate limiting config for the args, or `None` if no limits are configured.
+    pub fn rate_limit_config(&self) -> Option<RateLimitConfig> {
+        if self.per_ip.is_none() && self.api_keys.is_empty() && self.max_concurrent.is_empty() {
+            return None
+        }
+
+        let mut config = RateLimitConfig {
+            ip_quota: self.per_ip,
+            trusted_proxies: self.trusted_proxies.iter().copied().collect(),
+            api_key_header: self.api_key_header.clone(),
+            api_key_quotas: self.api_keys.iter().cloned().collect(),
+            max_concurrent: self.max_concurrent.iter().cloned().collect(),
+            ..Default::default()
+        };
+        config.method_costs.extend(self.method_costs.iter().cloned());
+        Some(config)
+    }
+}
+
+impl Default for RpcRateLimitArgs {
+    fn default() -> Self {
+        Self {
+            per_ip: None,
+            trusted_proxies: Vec::new(),
+            api_keys: Vec::new(),
+            api_key_header: DEFAULT_API_KEY_HEADER.to_string(),
+            method_costs: Vec::new(),
+            max_concurrent: Vec::new(),
+        }
+    }
+}
+
+/// Parses a `<KEY>=<VALUE>` pair.
+fn parse_key_value<T>(s: &str) -> eyre::Result<(String, T)>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let (key, value) =
+        s.split_once('=').ok_or_else(|| eyre::eyre!("expected <KEY>=<VALUE>, got `{s}`"))?;
+    Ok((key.to_string(), value.parse()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_rate_limit_args() {
+        let default_args = RpcRateLimitArgs::default();
+        let args = CommandParser::<RpcRateLimitArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+        assert_eq!(args.rate_limit_config(), None);
+
+        let args = CommandParser::<RpcRateLimitArgs>::parse_from([
+            "reth",
+            "--rpc.rate-limit.per-ip",
+            "100",
+            "--rpc.rate-limit.trusted-proxy",
+            "192.168.0.1",
+            "--rpc.rate-limit.api-key",
+            "tenant=1000",
+            "--rpc.rate-limit.method-cost",
+            "eth_getLogs=50",
+            "--rpc.rate-limit.max-concurrent",
+            "trace=4",
+            "--rpc.rate-limit.max-concurrent",
+            "debug=2",
+        ])
+        .args;
+        let config = args.rate_limit_config().unwrap();
+        assert_eq!(config.ip_quota, Some(100));
+        assert!(config.trusted_proxies.contains(&"192.168.0.1".parse::<IpAddr>().unwrap()));
+        assert_eq!(config.api_key_quotas.get("tenant"), Some(&1000));
+        assert_eq!(config.method_costs.get("eth_getLogs"), Some(&50));
+        assert_eq!(config.max_concurrent.get("trace"), Some(&4));
+        assert_eq!(config.max_concurrent.get("debug"), Some(&2));
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::args::{
     types::{MaxU32, ZeroAsNoneU64},
-    GasPriceOracleArgs, RpcStateCacheArgs,
+    GasPriceOracleArgs, RpcRateLimitArgs, RpcStateCacheArgs, RpcValidationArgs,
 };
 use alloy_rpc_types_engine::JwtSecret;
 use clap::{
@@ -11,6 +11,7 @@ use clap::{
 };
 use rand::Rng;
 use reth_rpc::eth::RPC_DEFAULT_GAS_CAP;
+use reth_rpc_layer::DEFAULT_RECORD_MAX_FILES;
 
 use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection};
 use std::{
@@ -30,6 +31,15 @@ pub(crate) const RPC_DEFAULT_MAX_REQUEST_SIZE_MB: u32 = 15;
 /// This is only relevant for very large trace responses.
 pub(crate) const RPC_DEFAULT_MAX_RESPONSE_SIZE_MB: u32 = 160;
 
+/// Default max number of calls in a batch request.
+pub(crate) const RPC_DEFAULT_MAX_BATCH_SIZE: u32 = 1000;
+
+/// Default size of a recording file in MB.
+pub(crate) const RPC_DEFAULT_RECORD_MAX_FILE_SIZE_MB: u64 = 100;
+
+/// Default filesystem permissions of the engine API IPC socket.
+pub(crate) const RPC_DEFAULT_AUTH_IPC_PERMISSIONS: u32 = 0o600;
+
 /// Default number of incoming connections.
 pub(crate) const RPC_DEFAULT_MAX_CONNECTIONS: u32 = 500;
 
@@ -110,6 +120,18 @@ pub struct RpcServerArgs {
     #[arg(long = "auth-ipc.path", default_value_t = constants::DEFAULT_ENGINE_API_IPC_ENDPOINT.to_string())]
     pub auth_ipc_path: String,
 
+    /// Path of the IPC socket serving the engine API, enables the auth IPC server.
+    ///
+    /// Calls over IPC are not JWT authenticated, access is controlled by the filesystem
+    /// permissions of the socket instead, see `--authrpc.ipc-permissions`.
+    #[arg(long = "authrpc.ipcpath", value_name = "PATH", conflicts_with = "auth_ipc_path")]
+    pub auth_ipcpath: Option<String>,
+
+    /// Filesystem permissions of the engine API IPC socket in octal notation, the default only
+    /// allows the user running reth to connect.
+    #[arg(long = "authrpc.ipc-permissions", value_name = "MODE", default_value = "600", value_parser = parse_socket_permissions)]
+    pub auth_ipc_permissions: u32,
+
     /// Hex encoded JWT secret to authenticate the regular RPC server(s), see `--http.api` and
     /// `--ws.api`.
     ///
@@ -126,6 +148,10 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.max-response-size", alias = "rpc-max-response-size", visible_alias = "rpc.returndata.limit", default_value_t = RPC_DEFAULT_MAX_RESPONSE_SIZE_MB.into())]
     pub rpc_max_response_size: MaxU32,
 
+    /// Set the maximum number of calls in a single batch request for HTTP, WS and IPC.
+    #[arg(long = "rpc.max-batch-size", value_name = "COUNT", default_value_t = RPC_DEFAULT_MAX_BATCH_SIZE.into())]
+    pub rpc_max_batch_size: MaxU32,
+
     /// Set the maximum concurrent subscriptions per connection.
     #[arg(long = "rpc.max-subscriptions-per-connection", alias = "rpc-max-subscriptions-per-connection", default_value_t = RPC_DEFAULT_MAX_SUBS_PER_CONN.into())]
     pub rpc_max_subscriptions_per_connection: MaxU32,
@@ -146,6 +172,35 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.max-logs-per-response", alias = "rpc-max-logs-per-response", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64))]
     pub rpc_max_logs_per_response: ZeroAsNoneU64,
 
+    /// Maximum number of blocks that could be scanned for matching transactions per `trace_filter`
+    /// request. Blocks covered by the address appearances index are not scanned. (0 = entire
+    /// chain)
+    #[arg(long = "rpc.max-trace-filter-blocks", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS))]
+    pub rpc_max_trace_filter_blocks: ZeroAsNoneU64,
+
+    /// Directory to cache the traces of finalized blocks in, to speed up `trace_filter` requests
+    /// over the same blocks.
+    #[arg(long = "rpc.trace-cache-dir", value_name = "PATH")]
+    pub rpc_trace_cache_dir: Option<PathBuf>,
+
+    /// Maximum number of blocks whose traces are kept in the trace cache. The least recently used
+    /// blocks are evicted from the cache directory.
+    #[arg(long = "rpc.trace-cache-max-blocks", value_name = "COUNT", default_value_t = constants::DEFAULT_TRACE_CACHE_MAX_BLOCKS)]
+    pub rpc_trace_cache_max_blocks: usize,
+
+    /// Directory to record every request and response of the http and ws servers to, for
+    /// replaying them with `reth-bench replay-rpc`. Recording is disabled if not set.
+    #[arg(long = "rpc.record-dir", value_name = "PATH")]
+    pub rpc_record_dir: Option<PathBuf>,
+
+    /// Size of a recording file in megabytes after which a new file is started.
+    #[arg(long = "rpc.record-max-file-size", value_name = "SIZE", default_value_t = RPC_DEFAULT_RECORD_MAX_FILE_SIZE_MB)]
+    pub rpc_record_max_file_size: u64,
+
+    /// Number of recording files to keep, the oldest files are removed first.
+    #[arg(long = "rpc.record-max-files", value_name = "COUNT", default_value_t = DEFAULT_RECORD_MAX_FILES, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub rpc_record_max_files: usize,
+
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     #[arg(
         long = "rpc.gascap",
@@ -156,6 +211,11 @@ pub struct RpcServerArgs {
     )]
     pub rpc_gas_cap: u64,
 
+    /// Serve the `pending` block, and calls on it, from the in-progress payload of the node's
+    /// active payload job instead of building it from the local txpool.
+    #[arg(long = "rpc.pending-block-from-payload")]
+    pub rpc_pending_block_from_payload: bool,
+
     /// State cache configuration.
     #[command(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -163,6 +223,14 @@ pub struct RpcServerArgs {
     /// Gas price oracle configuration.
     #[command(flatten)]
     pub gas_price_oracle: GasPriceOracleArgs,
+
+    /// Builder submission validation configuration.
+    #[command(flatten)]
+    pub validation: RpcValidationArgs,
+
+    /// Rate limiting configuration.
+    #[command(flatten)]
+    pub rate_limit: RpcRateLimitArgs,
 }
 
 impl RpcServerArgs {
@@ -184,6 +252,13 @@ impl RpcServerArgs {
         self
     }
 
+    /// Returns the endpoint of the auth IPC server, if enabled.
+    ///
+    /// The auth IPC server is enabled by `--authrpc.ipcpath` or `--auth-ipc`.
+    pub fn auth_ipc_endpoint(&self) -> Option<String> {
+        self.auth_ipcpath.clone().or_else(|| self.auth_ipc.then(|| self.auth_ipc_path.clone()))
+    }
+
     /// Change rpc port numbers based on the instance number.
     /// * The `auth_port` is scaled by a factor of `instance * 100`
     /// * The `http_port` is scaled by a factor of `-instance`
@@ -277,17 +352,29 @@ impl Default for RpcServerArgs {
             auth_jwtsecret: None,
             auth_ipc: false,
             auth_ipc_path: constants::DEFAULT_ENGINE_API_IPC_ENDPOINT.to_string(),
+            auth_ipcpath: None,
+            auth_ipc_permissions: RPC_DEFAULT_AUTH_IPC_PERMISSIONS,
             rpc_jwtsecret: None,
             rpc_max_request_size: RPC_DEFAULT_MAX_REQUEST_SIZE_MB.into(),
             rpc_max_response_size: RPC_DEFAULT_MAX_RESPONSE_SIZE_MB.into(),
+            rpc_max_batch_size: RPC_DEFAULT_MAX_BATCH_SIZE.into(),
             rpc_max_subscriptions_per_connection: RPC_DEFAULT_MAX_SUBS_PER_CONN.into(),
             rpc_max_connections: RPC_DEFAULT_MAX_CONNECTIONS.into(),
             rpc_max_tracing_requests: constants::default_max_tracing_requests(),
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
+            rpc_max_trace_filter_blocks: constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS.into(),
+            rpc_trace_cache_dir: None,
+            rpc_trace_cache_max_blocks: constants::DEFAULT_TRACE_CACHE_MAX_BLOCKS,
+            rpc_record_dir: None,
+            rpc_record_max_file_size: RPC_DEFAULT_RECORD_MAX_FILE_SIZE_MB,
+            rpc_record_max_files: DEFAULT_RECORD_MAX_FILES,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            rpc_pending_block_from_payload: false,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
+            validation: RpcValidationArgs::default(),
+            rate_limit: RpcRateLimitArgs::default(),
         }
     }
 }
@@ -324,6 +411,15 @@ impl TypedValueParser for RpcModuleSelectionValueParser {
     }
 }
 
+/// Parses filesystem permissions in octal notation, e.g. `600` or `0o660`.
+fn parse_socket_permissions(value: &str) -> eyre::Result<u32> {
+    let mode = u32::from_str_radix(value.trim_start_matches("0o"), 8)?;
+    if mode > 0o777 {
+        eyre::bail!("invalid permissions {value}, expected at most 777")
+    }
+    Ok(mode)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,6 +469,31 @@ mod tests {
         assert_eq!(apis, expected);
     }
 
+    #[test]
+    fn test_rpc_server_auth_ipc_args() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.auth_ipc_endpoint(), None);
+        assert_eq!(args.auth_ipc_permissions, 0o600);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--authrpc.ipcpath",
+            "/tmp/engine.ipc",
+            "--authrpc.ipc-permissions",
+            "660",
+        ])
+        .args;
+        assert_eq!(args.auth_ipc_endpoint().as_deref(), Some("/tmp/engine.ipc"));
+        assert_eq!(args.auth_ipc_permissions, 0o660);
+
+        assert!(CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--authrpc.ipc-permissions",
+            "1777"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn rpc_server_args_default_sanity_test() {
         let default_args = RpcServerArgs::default();
@@ -1,7 +1,8 @@
 use clap::Args;
 use reth_rpc_server_types::constants::cache::{
-    DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_CONCURRENT_DB_REQUESTS, DEFAULT_ENV_CACHE_MAX_LEN,
-    DEFAULT_RECEIPT_CACHE_MAX_LEN,
+    DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB, DEFAULT_CACHE_PREWARM_BLOCKS,
+    DEFAULT_CONCURRENT_DB_REQUESTS, DEFAULT_ENV_CACHE_MAX_LEN, DEFAULT_RECEIPT_CACHE_MAX_LEN,
+    DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB,
 };
 
 /// Parameters to configure RPC state cache.
@@ -15,6 +16,14 @@ pub struct RpcStateCacheArgs {
     )]
     pub max_blocks: u32,
 
+    /// Max size of all cached blocks in megabytes.
+    #[arg(
+        long = "rpc-cache.max-blocks-size",
+        value_name = "MB",
+        default_value_t = DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB,
+    )]
+    pub max_blocks_size: usize,
+
     /// Max number receipts in cache.
     #[arg(
         long = "rpc-cache.max-receipts",
@@ -22,6 +31,14 @@ pub struct RpcStateCacheArgs {
     )]
     pub max_receipts: u32,
 
+    /// Max size of all cached receipts in megabytes.
+    #[arg(
+        long = "rpc-cache.max-receipts-size",
+        value_name = "MB",
+        default_value_t = DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB,
+    )]
+    pub max_receipts_size: usize,
+
     /// Max number of bytes for cached env data.
     #[arg(
         long = "rpc-cache.max-envs",
@@ -35,15 +52,28 @@ pub struct RpcStateCacheArgs {
         default_value_t = DEFAULT_CONCURRENT_DB_REQUESTS,
     )]
     pub max_concurrent_db_requests: usize,
+
+    /// Number of recent canonical blocks whose blocks and receipts are loaded into the cache on
+    /// startup.
+    ///
+    /// Set to 0 to start with an empty cache.
+    #[arg(
+        long = "rpc-cache.prewarm-blocks",
+        default_value_t = DEFAULT_CACHE_PREWARM_BLOCKS,
+    )]
+    pub prewarm_blocks: u64,
 }
 
 impl Default for RpcStateCacheArgs {
     fn default() -> Self {
         Self {
             max_blocks: DEFAULT_BLOCK_CACHE_MAX_LEN,
+            max_blocks_size: DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB,
             max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
+            max_receipts_size: DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            prewarm_blocks: DEFAULT_CACHE_PREWARM_BLOCKS,
         }
     }
 }
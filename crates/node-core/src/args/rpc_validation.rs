@@ -0,0 +1,88 @@
+use crate::{args::utils::parse_duration_from_secs, primitives::Address};
+use clap::Args;
+use reth_rpc::ValidationApiConfig;
+use reth_rpc_server_types::constants::validation::{
+    DEFAULT_MAX_CONCURRENT_VALIDATIONS, DEFAULT_VALIDATION_TIMEOUT, DEFAULT_VALIDATION_WINDOW,
+};
+use std::{collections::HashSet, path::Path, time::Duration};
+
+/// Parameters to configure the `flashbots` builder submission validation API
+#[derive(Debug, Clone, Args, PartialEq, Eq)]
+#[command(next_help_heading = "Builder Submission Validation")]
+pub struct RpcValidationArgs {
+    /// Path to a JSON file with a list of addresses that must not appear in validated blocks
+    #[arg(long = "validation.disallow", value_name = "PATH", value_parser = parse_disallow_file)]
+    pub disallow: Option<HashSet<Address>>,
+
+    /// Maximum number of blocks a validated block may be behind the latest block
+    #[arg(long = "validation.window", value_name = "BLOCKS", default_value_t = DEFAULT_VALIDATION_WINDOW)]
+    pub window: u64,
+
+    /// Maximum number of builder submissions that are validated concurrently
+    #[arg(long = "validation.max-concurrent", value_name = "COUNT", default_value_t = DEFAULT_MAX_CONCURRENT_VALIDATIONS)]
+    pub max_concurrent: usize,
+
+    /// Maximum time a validation may take, including the time waiting for a free validation slot
+    #[arg(long = "validation.timeout", value_name = "SECONDS", value_parser = parse_duration_from_secs, default_value = "2")]
+    pub timeout: Duration,
+}
+
+impl RpcValidationArgs {
+    /// Returns the validation API config for the args.
+    pub fn validation_api_config(&self) -> ValidationApiConfig {
+        ValidationApiConfig {
+            disallow: self.disallow.clone().unwrap_or_default(),
+            validation_window: self.window,
+            max_concurrent_validations: self.max_concurrent,
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl Default for RpcValidationArgs {
+    fn default() -> Self {
+        Self {
+            disallow: None,
+            window: DEFAULT_VALIDATION_WINDOW,
+            max_concurrent: DEFAULT_MAX_CONCURRENT_VALIDATIONS,
+            timeout: DEFAULT_VALIDATION_TIMEOUT,
+        }
+    }
+}
+
+/// Reads the list of disallowed addresses from a JSON file.
+fn parse_disallow_file(path: &str) -> eyre::Result<HashSet<Address>> {
+    let contents = reth_fs_util::read_to_string(Path::new(path))?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_validation_args() {
+        let default_args = RpcValidationArgs::default();
+        let args = CommandParser::<RpcValidationArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+
+        let args = CommandParser::<RpcValidationArgs>::parse_from([
+            "reth",
+            "--validation.timeout",
+            "5",
+            "--validation.max-concurrent",
+            "2",
+        ])
+        .args;
+        assert_eq!(args.timeout, Duration::from_secs(5));
+        assert_eq!(args.max_concurrent, 2);
+    }
+}
@@ -1,7 +1,7 @@
 //! Config traits for various node components.
 
 use reth_network::protocol::IntoRlpxSubProtocol;
-use reth_primitives::Bytes;
+use reth_primitives::{Bytes, B256};
 use reth_transaction_pool::PoolConfig;
 use std::{borrow::Cow, time::Duration};
 
@@ -29,6 +29,9 @@ pub trait PayloadBuilderConfig {
 
     /// Maximum number of tasks to spawn for building a payload.
     fn max_payload_tasks(&self) -> usize;
+
+    /// The private key of the fee recipient that pays the refunds of included bundles.
+    fn bundle_refund_key(&self) -> Option<B256>;
 }
 
 /// A trait that represents the configured network and can be used to apply additional configuration
@@ -33,6 +33,7 @@ use reth_node_core::{
     primitives::{kzg::KzgSettings, Head},
     utils::write_peers_to_file,
 };
+use reth_payload_builder::BundlePool;
 use reth_primitives::constants::eip4844::MAINNET_KZG_TRUSTED_SETUP;
 use reth_provider::{providers::BlockchainProvider, ChainSpecProvider};
 use reth_tasks::TaskExecutor;
@@ -414,6 +415,8 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs,
+    /// The pool of MEV bundles shared between the RPC and the payload builder.
+    pub(crate) bundle_pool: BundlePool,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
@@ -423,8 +426,9 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         provider: Node::Provider,
         executor: TaskExecutor,
         config_container: WithConfigs,
+        bundle_pool: BundlePool,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self { head, provider, executor, config_container, bundle_pool }
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -474,6 +478,12 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         Ok(Arc::clone(&MAINNET_KZG_TRUSTED_SETUP))
     }
 
+    /// Returns the pool of bundles submitted via `mev_sendBundle`, which payload builders can
+    /// include in their payloads.
+    pub const fn bundle_pool(&self) -> &BundlePool {
+        &self.bundle_pool
+    }
+
     /// Returns the config for payload building.
     pub fn payload_builder_config(&self) -> impl PayloadBuilderConfig {
         self.config().builder.clone()
@@ -604,6 +614,7 @@ impl<Node: FullNodeTypes> std::fmt::Debug for BuilderContext<Node> {
             .field("provider", &std::any::type_name::<Node::Provider>())
             .field("executor", &self.executor)
             .field("config", &self.config())
+            .field("bundle_pool", &self.bundle_pool)
             .finish()
     }
 }
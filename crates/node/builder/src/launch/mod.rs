@@ -28,11 +28,14 @@ use reth_node_core::{
     version::{CARGO_PKG_VERSION, CLIENT_CODE, NAME_CLIENT, VERGEN_GIT_SHA},
 };
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
-
+use reth_payload_builder::BundlePool;
 use reth_primitives::format_ether;
-use reth_provider::providers::BlockchainProvider;
+use reth_provider::{providers::BlockchainProvider, CanonStateSubscriptions};
 use reth_rpc_engine_api::EngineApi;
 use reth_rpc_types::engine::ClientVersionV1;
+use reth_stages::stages::{
+    maintain_address_appearances, maintain_log_index, IndexAddressAppearancesStage, IndexLogsStage,
+};
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::TransactionPool;
@@ -152,11 +155,15 @@ where
             )),
         )?;
 
+        // bundles submitted via `mev_sendBundle` are shared with the payload builder
+        let bundle_pool = BundlePool::default();
+
         let builder_ctx = BuilderContext::new(
             head,
             blockchain_db.clone(),
             ctx.task_executor().clone(),
             ctx.configs().clone(),
+            bundle_pool.clone(),
         );
 
         debug!(target: "reth::cli", "creating components");
@@ -194,6 +201,36 @@ where
         debug!(target: "reth::cli", "calling on_component_initialized hook");
         on_component_initialized.on_event(node_adapter.clone())?;
 
+        // keep the address appearances index in sync with blocks committed by the tree
+        let address_appearances_config = ctx.toml_config().stages.index_address_appearances;
+        if address_appearances_config.enabled {
+            let stage = IndexAddressAppearancesStage::new(
+                node_adapter.components.evm_config().clone(),
+                address_appearances_config,
+            );
+            ctx.task_executor().spawn_critical_blocking(
+                "address appearances index task",
+                maintain_address_appearances(
+                    ctx.provider_factory().clone(),
+                    stage,
+                    blockchain_db.canonical_state_stream(),
+                ),
+            );
+        }
+
+        // keep the log index in sync with blocks committed by the tree
+        let log_index_config = ctx.toml_config().stages.index_logs;
+        if log_index_config.enabled {
+            ctx.task_executor().spawn_critical_blocking(
+                "log index task",
+                maintain_log_index(
+                    ctx.provider_factory().clone(),
+                    IndexLogsStage::new(log_index_config),
+                    blockchain_db.canonical_state_stream(),
+                ),
+            );
+        }
+
         // spawn exexs
         let exex_manager_handle =
             ExExLauncher::new(head, node_adapter.clone(), installed_exex, ctx.configs().clone())
@@ -227,7 +264,7 @@ where
         // Configure the pipeline
         let pipeline_exex_handle =
             exex_manager_handle.clone().unwrap_or_else(ExExManagerHandle::empty);
-        let (pipeline, client) = if ctx.is_dev() {
+        let (pipeline, client, dev_handle) = if ctx.is_dev() {
             info!(target: "reth::cli", "Starting Reth in dev mode");
 
             for (idx, (address, alloc)) in ctx.chain_spec().genesis.alloc.iter().enumerate() {
@@ -260,16 +297,18 @@ where
                 max_block,
                 static_file_producer,
                 node_adapter.components.block_executor().clone(),
+                node_adapter.components.evm_config().clone(),
                 pipeline_exex_handle,
             )
             .await?;
 
             let pipeline_events = pipeline.events();
             task.set_pipeline_events(pipeline_events);
+            let dev_handle = task.handle();
             debug!(target: "reth::cli", "Spawning auto mine task");
             ctx.task_executor().spawn(Box::pin(task));
 
-            (pipeline, Either::Left(client))
+            (pipeline, Either::Left(client), Some(dev_handle))
         } else {
             let pipeline = crate::setup::build_networked_pipeline(
                 &ctx.toml_config().stages,
@@ -282,11 +321,12 @@ where
                 max_block,
                 static_file_producer,
                 node_adapter.components.block_executor().clone(),
+                node_adapter.components.evm_config().clone(),
                 pipeline_exex_handle,
             )
             .await?;
 
-            (pipeline, Either::Right(network_client.clone()))
+            (pipeline, Either::Right(network_client.clone()), None)
         };
 
         let pipeline_events = pipeline.events();
@@ -373,6 +413,9 @@ where
             engine_api,
             ctx.node_config(),
             jwt_secret,
+            consensus,
+            dev_handle.clone(),
+            bundle_pool,
             rpc,
         )
         .await?;
@@ -381,6 +424,10 @@ where
         if ctx.is_dev() {
             rpc_registry.eth_api().with_dev_accounts();
         }
+        // transactions of impersonated accounts are handed to the dev chain instead of the pool
+        if let Some(handle) = dev_handle {
+            rpc_registry.eth_api().with_impersonation(handle);
+        }
 
         // Run consensus engine to completion
         let (tx, rx) = oneshot::channel();
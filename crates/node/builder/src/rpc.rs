@@ -1,21 +1,29 @@
 //! Builder support for rpc components.
 
-use futures::TryFutureExt;
+use futures::{StreamExt, TryFutureExt};
+use reth_auto_seal_consensus::AutoSealHandle;
+use reth_consensus::Consensus;
 use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
-use reth_node_core::{node_config::NodeConfig, rpc::api::EngineApiServer};
-use reth_payload_builder::PayloadBuilderHandle;
+use reth_node_core::{
+    node_config::NodeConfig,
+    rpc::api::{BlockSubmissionValidationApiServer, EngineApiServer},
+};
+use reth_payload_builder::{BundlePool, PayloadBuilderHandle, PayloadStore};
+use reth_provider::{BlockNumReader, CanonStateSubscriptions};
+use reth_rpc::{DevApi, ValidationApi};
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
     config::RethRpcServerConfig,
-    RethModuleRegistry, RpcModuleBuilder, RpcServerHandle, TransportRpcModules,
+    RethModuleRegistry, RethRpcModule, RpcModuleBuilder, RpcServerHandle, TransportRpcModules,
 };
-use reth_rpc_layer::JwtSecret;
+use reth_rpc_layer::{JwtSecret, RpcRecorder};
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
 use std::{
     fmt,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 /// Contains the handles to the spawned RPC servers.
@@ -252,6 +260,9 @@ pub(crate) async fn launch_rpc_servers<Node, Engine>(
     engine_api: Engine,
     config: &NodeConfig,
     jwt_secret: JwtSecret,
+    consensus: Arc<dyn Consensus>,
+    dev_handle: Option<AutoSealHandle>,
+    bundle_pool: BundlePool,
     hooks: RpcHooks<Node>,
 ) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node>)>
 where
@@ -264,16 +275,46 @@ where
     let module_config = config.rpc.transport_rpc_module_config();
     debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
-    let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
+    let mut registry = RpcModuleBuilder::default()
         .with_provider(node.provider().clone())
         .with_pool(node.pool().clone())
         .with_network(node.network().clone())
         .with_events(node.provider().clone())
         .with_executor(node.task_executor().clone())
         .with_evm_config(node.evm_config().clone())
-        .build_with_auth_server(module_config, engine_api);
+        .into_registry(module_config.config.clone().unwrap_or_default());
+    // bundles are added to the pool the payload builder includes them from
+    registry.set_bundle_pool(bundle_pool);
+    let mut modules = registry.create_transport_rpc_modules(module_config);
+    let mut auth_module = registry.create_auth_module(engine_api);
+
+    let validation_api = ValidationApi::new(
+        node.provider().clone(),
+        consensus,
+        node.block_executor().clone(),
+        config.rpc.validation_api_config(),
+        Box::new(node.task_executor().clone()),
+    );
+    modules.merge_if_module_configured(
+        RethRpcModule::Flashbots,
+        BlockSubmissionValidationApiServer::into_rpc(validation_api),
+    )?;
+
+    // the dev namespaces are only available if the node builds its own chain
+    if let Some(handle) = dev_handle {
+        let dev_api = DevApi::new(handle, node.pool().clone());
+        modules
+            .merge_if_module_configured(RethRpcModule::Anvil, dev_api.clone().into_anvil_rpc())?;
+        modules.merge_if_module_configured(RethRpcModule::Hardhat, dev_api.into_hardhat_rpc())?;
+    }
 
     let mut registry = RpcRegistry { registry };
+    if config.rpc.rpc_pending_block_from_payload {
+        registry.set_eth_pending_block_source(Arc::new(PayloadStore::from(
+            node.payload_builder().clone(),
+        )));
+    }
+
     let ctx = RpcContext {
         node: node.clone(),
         config,
@@ -284,7 +325,20 @@ where
 
     extend_rpc_modules.extend_rpc_modules(ctx)?;
 
-    let server_config = config.rpc.rpc_server_config();
+    let recorder = config.rpc.record_config().map(RpcRecorder::new).transpose()?;
+    if let Some(recorder) = recorder.clone() {
+        // keep the best block number of the records up to date with the canonical chain
+        if let Ok(best_block_number) = node.provider().best_block_number() {
+            recorder.set_block_number(best_block_number);
+        }
+        let mut canon_state_stream = node.provider().canonical_state_stream();
+        node.task_executor().spawn(Box::pin(async move {
+            while let Some(notification) = canon_state_stream.next().await {
+                recorder.set_block_number(notification.tip().number);
+            }
+        }));
+    }
+    let server_config = config.rpc.rpc_server_config().with_recorder(recorder);
     let launch_rpc = modules.clone().start_server(server_config).map_ok(|handle| {
         if let Some(path) = handle.ipc_endpoint() {
             info!(target: "reth::cli", %path, "RPC IPC server started");
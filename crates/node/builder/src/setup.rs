@@ -7,7 +7,7 @@ use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
-use reth_evm::execute::BlockExecutorProvider;
+use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
 use reth_exex::ExExManagerHandle;
 use reth_network_p2p::{
     bodies::{client::BodiesClient, downloader::BodyDownloader},
@@ -15,7 +15,11 @@ use reth_network_p2p::{
 };
 use reth_node_core::primitives::{BlockNumber, B256};
 use reth_provider::ProviderFactory;
-use reth_stages::{prelude::DefaultStages, stages::ExecutionStage, Pipeline, StageSet};
+use reth_stages::{
+    prelude::DefaultStages,
+    stages::{ExecutionStage, IndexAddressAppearancesStage, IndexLogsStage},
+    Pipeline, StageId, StageSet,
+};
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::debug;
@@ -24,7 +28,7 @@ use tokio::sync::watch;
 
 /// Constructs a [Pipeline] that's wired to the network
 #[allow(clippy::too_many_arguments)]
-pub async fn build_networked_pipeline<DB, Client, Executor>(
+pub async fn build_networked_pipeline<DB, Client, Executor, EvmConfig>(
     config: &StageConfig,
     client: Client,
     consensus: Arc<dyn Consensus>,
@@ -35,12 +39,14 @@ pub async fn build_networked_pipeline<DB, Client, Executor>(
     max_block: Option<BlockNumber>,
     static_file_producer: StaticFileProducer<DB>,
     executor: Executor,
+    evm_config: EvmConfig,
     exex_manager_handle: ExExManagerHandle,
 ) -> eyre::Result<Pipeline<DB>>
 where
     DB: Database + Unpin + Clone + 'static,
     Client: HeadersClient + BodiesClient + Clone + 'static,
     Executor: BlockExecutorProvider,
+    EvmConfig: ConfigureEvm,
 {
     // building network downloaders using the fetch client
     let header_downloader = ReverseHeadersDownloaderBuilder::new(config.headers)
@@ -62,6 +68,7 @@ where
         prune_config,
         static_file_producer,
         executor,
+        evm_config,
         exex_manager_handle,
     )
     .await?;
@@ -71,7 +78,7 @@ where
 
 /// Builds the [Pipeline] with the given [`ProviderFactory`] and downloaders.
 #[allow(clippy::too_many_arguments)]
-pub async fn build_pipeline<DB, H, B, Executor>(
+pub async fn build_pipeline<DB, H, B, Executor, EvmConfig>(
     provider_factory: ProviderFactory<DB>,
     stage_config: &StageConfig,
     header_downloader: H,
@@ -82,6 +89,7 @@ pub async fn build_pipeline<DB, H, B, Executor>(
     prune_config: Option<PruneConfig>,
     static_file_producer: StaticFileProducer<DB>,
     executor: Executor,
+    evm_config: EvmConfig,
     exex_manager_handle: ExExManagerHandle,
 ) -> eyre::Result<Pipeline<DB>>
 where
@@ -89,6 +97,7 @@ where
     H: HeaderDownloader + 'static,
     B: BodyDownloader + 'static,
     Executor: BlockExecutorProvider,
+    EvmConfig: ConfigureEvm,
 {
     let mut builder = Pipeline::builder();
 
@@ -124,7 +133,22 @@ where
                     exex_manager_handle,
                 )
                 .with_metrics_tx(metrics_tx),
-            ),
+            )
+            .add_after(
+                IndexAddressAppearancesStage::new(
+                    evm_config,
+                    stage_config.index_address_appearances,
+                ),
+                StageId::IndexAccountHistory,
+            )
+            .disable_if(StageId::IndexAddressAppearances, || {
+                !stage_config.index_address_appearances.enabled
+            })
+            .add_after(
+                IndexLogsStage::new(stage_config.index_logs),
+                StageId::IndexAddressAppearances,
+            )
+            .disable_if(StageId::IndexLogs, || !stage_config.index_logs.enabled),
         )
         .build(provider_factory, static_file_producer);
 
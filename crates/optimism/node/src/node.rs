@@ -5,7 +5,9 @@ use crate::{
     txpool::{OpTransactionPool, OpTransactionValidator},
     OptimismEngineTypes,
 };
-use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
+use reth_basic_payload_builder::{
+    BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig, BundleRefundSigner,
+};
 use reth_evm::ConfigureEvm;
 use reth_evm_optimism::{OpExecutorProvider, OptimismEvmConfig};
 use reth_network::{NetworkHandle, NetworkManager};
@@ -225,12 +227,18 @@ where
         ctx: &BuilderContext<Node>,
         pool: Pool,
     ) -> eyre::Result<PayloadBuilderHandle<Node::Engine>> {
-        let payload_builder = reth_optimism_payload_builder::OptimismPayloadBuilder::new(
+        let mut payload_builder = reth_optimism_payload_builder::OptimismPayloadBuilder::new(
             ctx.chain_spec(),
             self.evm_config,
         )
-        .set_compute_pending_block(self.compute_pending_block);
+        .set_compute_pending_block(self.compute_pending_block)
+        .with_bundle_pool(ctx.bundle_pool().clone());
         let conf = ctx.payload_builder_config();
+        if let Some(key) = conf.bundle_refund_key() {
+            let signer = BundleRefundSigner::new(key)
+                .ok_or_else(|| eyre::eyre!("invalid bundle refund key"))?;
+            payload_builder = payload_builder.with_bundle_refund_signer(signer);
+        }
 
         let payload_job_config = BasicPayloadJobGeneratorConfig::default()
             .interval(conf.interval())
@@ -7,7 +7,7 @@ use crate::{
 use reth_basic_payload_builder::*;
 use reth_chainspec::ChainSpec;
 use reth_evm::ConfigureEvm;
-use reth_payload_builder::error::PayloadBuilderError;
+use reth_payload_builder::{error::PayloadBuilderError, BundlePool};
 use reth_primitives::{
     constants::{BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS},
     eip4844::calculate_excess_blob_gas,
@@ -24,11 +24,11 @@ use revm::{
     primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
     DatabaseCommit, State,
 };
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use tracing::{debug, trace, warn};
 
 /// Optimism's payload builder
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct OptimismPayloadBuilder<EvmConfig> {
     /// The rollup's compute pending block configuration option.
     // TODO(clabby): Implement this feature.
@@ -37,12 +37,35 @@ pub struct OptimismPayloadBuilder<EvmConfig> {
     chain_spec: Arc<ChainSpec>,
     /// The type responsible for creating the evm.
     evm_config: EvmConfig,
+    /// The pool of MEV bundles that are included in the payloads, if any.
+    bundle_pool: Option<BundlePool>,
+    /// Pays the refunds of included bundles, if any.
+    refund_signer: Option<BundleRefundSigner>,
 }
 
 impl<EvmConfig> OptimismPayloadBuilder<EvmConfig> {
     /// `OptimismPayloadBuilder` constructor.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
-        Self { compute_pending_block: true, chain_spec, evm_config }
+        Self {
+            compute_pending_block: true,
+            chain_spec,
+            evm_config,
+            bundle_pool: None,
+            refund_signer: None,
+        }
+    }
+
+    /// Includes the bundles of the given [`BundlePool`] that target the block of the payload.
+    pub fn with_bundle_pool(mut self, bundle_pool: BundlePool) -> Self {
+        self.bundle_pool = Some(bundle_pool);
+        self
+    }
+
+    /// Pays the refunds of bundles with the given signer, bundles with refunds are only included
+    /// if the fee recipient of the payload is the signer.
+    pub fn with_bundle_refund_signer(mut self, refund_signer: BundleRefundSigner) -> Self {
+        self.refund_signer = Some(refund_signer);
+        self
     }
 
     /// Sets the rollup's compute pending block configuration option.
@@ -82,7 +105,13 @@ where
         &self,
         args: BuildArguments<Pool, Client, OptimismPayloadBuilderAttributes, OptimismBuiltPayload>,
     ) -> Result<BuildOutcome<OptimismBuiltPayload>, PayloadBuilderError> {
-        optimism_payload_builder(self.evm_config.clone(), args, self.compute_pending_block)
+        optimism_payload_builder(
+            self.evm_config.clone(),
+            self.bundle_pool.as_ref(),
+            self.refund_signer.as_ref(),
+            args,
+            self.compute_pending_block,
+        )
     }
 
     fn on_missing_payload(
@@ -237,6 +266,8 @@ where
 #[inline]
 pub(crate) fn optimism_payload_builder<EvmConfig, Pool, Client>(
     evm_config: EvmConfig,
+    bundle_pool: Option<&BundlePool>,
+    refund_signer: Option<&BundleRefundSigner>,
     args: BuildArguments<Pool, Client, OptimismPayloadBuilderAttributes, OptimismBuiltPayload>,
     _compute_pending_block: bool,
 ) -> Result<BuildOutcome<OptimismBuiltPayload>, PayloadBuilderError>
@@ -308,6 +339,8 @@ where
     })?;
 
     let mut receipts = Vec::with_capacity(attributes.transactions.len());
+    // the transactions included by bundles, which are skipped if they are also in the pool
+    let mut included_txs = HashSet::new();
     for sequencer_tx in &attributes.transactions {
         // Check if the job was cancelled, if so we can exit early.
         if cancel.is_cancelled() {
@@ -403,7 +436,61 @@ where
     }
 
     if !attributes.no_tx_pool {
+        // include the bundles that target this block after the sequencer transactions, before
+        // any pool transactions
+        for bundle in
+            bundle_pool.map(|pool| pool.bundles_for_block(block_number)).unwrap_or_default()
+        {
+            if !bundle.is_allowed_builder(&initialized_block_env.coinbase) {
+                continue
+            }
+
+            // check if the job was cancelled, if so we can exit early
+            if cancel.is_cancelled() {
+                return Ok(BuildOutcome::Cancelled)
+            }
+
+            let Some(executed) = execute_bundle(
+                &evm_config,
+                &mut db,
+                &initialized_cfg,
+                &initialized_block_env,
+                &bundle,
+                block_gas_limit.saturating_sub(cumulative_gas_used),
+                refund_signer,
+                &mut included_txs,
+            )?
+            else {
+                continue
+            };
+
+            for (tx, result) in executed.transactions {
+                cumulative_gas_used += result.gas_used();
+                receipts.push(Some(Receipt {
+                    tx_type: tx.tx_type(),
+                    success: result.is_success(),
+                    cumulative_gas_used,
+                    logs: result.into_logs().into_iter().map(Into::into).collect(),
+                    deposit_nonce: None,
+                    deposit_receipt_version: None,
+                }));
+                executed_txs.push(tx.into_signed());
+            }
+            // the profit of the bundle includes the tips and direct payments to the fee recipient
+            total_fees += executed.profit;
+        }
+
+        // the parent state the conditions of conditional transactions are checked against, only
+        // loaded once a conditional transaction is encountered
+        let mut conditional_state = None;
+
         while let Some(pool_tx) = best_txs.next() {
+            // skip transactions that are already included by a bundle, their descendants are
+            // still valid
+            if included_txs.contains(pool_tx.hash()) {
+                continue
+            }
+
             // ensure we still have capacity for this transaction
             if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
                 // we can't fit this transaction into the block, so we need to mark it as
@@ -418,6 +505,22 @@ where
                 best_txs.mark_invalid(&pool_tx)
             }
 
+            // skip conditional transactions whose conditions aren't satisfied by this block
+            if let Some(conditional) = &pool_tx.conditional {
+                if conditional_state.is_none() {
+                    conditional_state = Some(client.state_by_block_hash(parent_block.hash())?);
+                }
+                let state = conditional_state.as_ref().expect("state is loaded");
+                if !conditional.is_satisfied(
+                    block_number,
+                    attributes.payload_attributes.timestamp,
+                    state,
+                )? {
+                    best_txs.mark_invalid(&pool_tx);
+                    continue
+                }
+            }
+
             // check if the job was cancelled, if so we can exit early
             if cancel.is_cancelled() {
                 return Ok(BuildOutcome::Cancelled)
@@ -0,0 +1,438 @@
+//! Execution of MEV bundles from the [`BundlePool`](reth_payload_builder::BundlePool) on top of a
+//! payload.
+
+use reth_evm::ConfigureEvm;
+use reth_payload_builder::{
+    bundle_pool::{MevBundle, MevBundleItem},
+    error::PayloadBuilderError,
+};
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, sign_message, Address, Transaction, TransactionSigned,
+    TransactionSignedEcRecovered, TxEip1559, TxKind, B256, U256,
+};
+use reth_provider::ProviderError;
+use revm::{
+    primitives::{BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, ExecutionResult},
+    Database, DatabaseCommit, State,
+};
+use std::collections::HashSet;
+use tracing::trace;
+
+/// The gas limit of a refund payment, a plain value transfer.
+const REFUND_GAS_LIMIT: u64 = 21_000;
+
+/// A bundle that was executed and committed to the state of the payload.
+#[derive(Debug)]
+pub struct ExecutedBundle {
+    /// The transactions of the bundle and all nested bundles with their results, in execution
+    /// order, followed by the transactions that pay the refunds of the bundle.
+    pub transactions: Vec<(TransactionSignedEcRecovered, ExecutionResult)>,
+    /// The gas used by all transactions of the bundle, including the refund payments.
+    pub gas_used: u64,
+    /// The increase of the fee recipient's balance, including direct payments to the fee
+    /// recipient and after paying the refunds.
+    pub profit: U256,
+}
+
+/// Signs the transactions that pay the refunds of bundles from the fee recipient of the payload.
+///
+/// Bundles with refunds can only be included in payloads whose fee recipient is the address of
+/// this signer.
+#[derive(Clone)]
+pub struct BundleRefundSigner {
+    secret: B256,
+    address: Address,
+}
+
+impl BundleRefundSigner {
+    /// Creates a new signer for the given private key, returns `None` if the key is invalid.
+    pub fn new(secret: B256) -> Option<Self> {
+        // the address of the key is recovered from a signature of an arbitrary message
+        let signature = sign_message(secret, B256::ZERO).ok()?;
+        let address = signature.recover_signer_unchecked(B256::ZERO)?;
+        Some(Self { secret, address })
+    }
+
+    /// Returns the address that pays the refunds.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Signs the transaction with the key of this signer.
+    fn sign(&self, transaction: Transaction) -> Option<TransactionSignedEcRecovered> {
+        let signature = sign_message(self.secret, transaction.signature_hash()).ok()?;
+        Some(
+            TransactionSigned::from_transaction_and_signature(transaction, signature)
+                .with_signer(self.address),
+        )
+    }
+}
+
+impl std::fmt::Debug for BundleRefundSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BundleRefundSigner").field("address", &self.address).finish_non_exhaustive()
+    }
+}
+
+/// Executes all transactions of the bundle, including the transactions of nested bundles, pays
+/// the refunds of the bundle and commits them to `db`.
+///
+/// Bundles are all-or-nothing: if a transaction that may not revert reverts or is invalid, a
+/// transaction is already `included` in the payload, the refunds can't be paid by the fee
+/// recipient, or the bundle does not fit into the remaining `gas_limit`, the state is restored and
+/// `None` is returned. Otherwise the hashes of all executed transactions are added to `included`.
+///
+/// Refunds are paid with value transfers from the fee recipient, signed by the `refund_signer`,
+/// after all transactions of the bundle. The gas of each transfer is deducted from the refund.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_bundle<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: &mut State<DB>,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    bundle: &MevBundle,
+    gas_limit: u64,
+    refund_signer: Option<&BundleRefundSigner>,
+    included: &mut HashSet<B256>,
+) -> Result<Option<ExecutedBundle>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database<Error = ProviderError>,
+{
+    let mut transactions = Vec::new();
+    flatten_bundle(bundle, &mut transactions);
+
+    if transactions.iter().map(|(tx, _)| tx.gas_limit()).sum::<u64>() > gas_limit {
+        trace!(target: "payload_builder", bundle=?bundle.hash, "skipping bundle that exceeds the remaining gas");
+        return Ok(None)
+    }
+    // blob transactions are subject to their own limits and never part of bundles
+    if transactions.iter().any(|(tx, _)| tx.is_eip4844()) {
+        trace!(target: "payload_builder", bundle=?bundle.hash, "skipping bundle with blob transactions");
+        return Ok(None)
+    }
+    // a transaction can only be included once, either by another bundle, the pool or twice by
+    // this bundle
+    let mut hashes = HashSet::with_capacity(transactions.len());
+    if transactions.iter().any(|(tx, _)| included.contains(&tx.hash()) || !hashes.insert(tx.hash()))
+    {
+        trace!(target: "payload_builder", bundle=?bundle.hash, "skipping bundle with already included transactions");
+        return Ok(None)
+    }
+
+    let coinbase = initialized_block_env.coinbase;
+    let refund_signer = refund_signer.filter(|signer| signer.address() == coinbase);
+    if has_refunds(bundle) && refund_signer.is_none() {
+        trace!(target: "payload_builder", bundle=?bundle.hash, ?coinbase, "skipping bundle with refunds that the fee recipient can't pay");
+        return Ok(None)
+    }
+
+    let balance_before = balance(db, coinbase)?;
+
+    // the state before the bundle, restored if the bundle can't be included
+    let cache = db.cache.clone();
+    let transition_state = db.transition_state.clone();
+
+    let mut execution = BundleExecution {
+        evm_config,
+        initialized_cfg,
+        initialized_block_env,
+        bundle_hash: bundle.hash,
+        executed: Vec::with_capacity(transactions.len()),
+        gas_used: 0,
+        refunds: Vec::new(),
+    };
+    let mut included_bundle = execution.execute_items(db, bundle)?.is_some();
+
+    if included_bundle && !execution.refunds.is_empty() {
+        let signer = refund_signer.expect("bundles with refunds require a signer");
+        included_bundle = execution.pay_refunds(db, signer, gas_limit)?;
+    }
+
+    if !included_bundle {
+        db.cache = cache;
+        db.transition_state = transition_state;
+        return Ok(None)
+    }
+
+    let BundleExecution { executed, gas_used, .. } = execution;
+    included.extend(executed.iter().map(|(tx, _)| tx.hash()));
+    let profit = balance(db, coinbase)?.saturating_sub(balance_before);
+    Ok(Some(ExecutedBundle { transactions: executed, gas_used, profit }))
+}
+
+/// The state of the execution of a single bundle.
+struct BundleExecution<'a, EvmConfig> {
+    evm_config: &'a EvmConfig,
+    initialized_cfg: &'a CfgEnvWithHandlerCfg,
+    initialized_block_env: &'a BlockEnv,
+    /// The hash of the executed top level bundle.
+    bundle_hash: B256,
+    /// The executed transactions with their results.
+    executed: Vec<(TransactionSignedEcRecovered, ExecutionResult)>,
+    /// The gas used by all executed transactions.
+    gas_used: u64,
+    /// The refunds owed by the bundle and its nested bundles, as `(recipient, value)`.
+    refunds: Vec<(Address, U256)>,
+}
+
+impl<EvmConfig: ConfigureEvm> BundleExecution<'_, EvmConfig> {
+    /// Executes a single transaction and commits it, returns `false` if the transaction is invalid
+    /// or reverted even though it may not revert.
+    fn execute_transaction<DB>(
+        &mut self,
+        db: &mut State<DB>,
+        tx: &TransactionSignedEcRecovered,
+        can_revert: bool,
+    ) -> Result<bool, PayloadBuilderError>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        let env = EnvWithHandlerCfg::new_with_cfg_env(
+            self.initialized_cfg.clone(),
+            self.initialized_block_env.clone(),
+            tx_env_with_recovered(tx),
+        );
+        let mut evm = self.evm_config.evm_with_env(&mut *db, env);
+        let res = evm.transact();
+        // drop evm so db is released.
+        drop(evm);
+
+        match res {
+            Ok(res) if res.result.is_success() || can_revert => {
+                db.commit(res.state);
+                self.gas_used += res.result.gas_used();
+                self.executed.push((tx.clone(), res.result));
+                Ok(true)
+            }
+            Ok(_) | Err(EVMError::Transaction(_)) => {
+                trace!(target: "payload_builder", bundle=?self.bundle_hash, tx=?tx.hash(), "skipping bundle with reverted or invalid transaction");
+                Ok(false)
+            }
+            // this is an error that we should treat as fatal for this attempt
+            Err(err) => Err(PayloadBuilderError::EvmExecutionError(err)),
+        }
+    }
+
+    /// Executes the body of the bundle and records its refunds.
+    ///
+    /// Returns the profit of the fee recipient that remains after the refunds of the bundle, or
+    /// `None` if the bundle can't be included.
+    fn execute_items<DB>(
+        &mut self,
+        db: &mut State<DB>,
+        bundle: &MevBundle,
+    ) -> Result<Option<U256>, PayloadBuilderError>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        let coinbase = self.initialized_block_env.coinbase;
+        // the profit of the fee recipient of each body item, after the refunds of nested bundles
+        let mut item_profits = Vec::with_capacity(bundle.body.len());
+        for item in &bundle.body {
+            let profit = match item {
+                // bundles from the pool are fully matched
+                MevBundleItem::Hash(_) => return Ok(None),
+                MevBundleItem::Tx { transaction, can_revert } => {
+                    let balance_before = balance(db, coinbase)?;
+                    if !self.execute_transaction(db, transaction, *can_revert)? {
+                        return Ok(None)
+                    }
+                    balance(db, coinbase)?.saturating_sub(balance_before)
+                }
+                MevBundleItem::Bundle(nested) => {
+                    let Some(profit) = self.execute_items(db, nested)? else { return Ok(None) };
+                    profit
+                }
+            };
+            item_profits.push(profit);
+        }
+
+        // refunds are paid from the profit of the body items that are not refunded themselves
+        let refundable_profit = item_profits
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !bundle.refunds.iter().any(|refund| refund.body_idx == *idx))
+            .fold(U256::ZERO, |acc, (_, profit)| acc + profit);
+        let mut total_refund = U256::ZERO;
+        for refund in &bundle.refunds {
+            let value = refundable_profit * U256::from(refund.percent) / U256::from(100);
+            total_refund += value;
+            for (recipient, percent) in refund_recipients(&bundle.body[refund.body_idx]) {
+                self.refunds.push((recipient, value * U256::from(percent) / U256::from(100)));
+            }
+        }
+
+        let total_profit = item_profits.iter().fold(U256::ZERO, |acc, profit| acc + profit);
+        Ok(Some(total_profit.saturating_sub(total_refund)))
+    }
+
+    /// Pays the recorded refunds from the fee recipient, returns `false` if a payment fails.
+    fn pay_refunds<DB>(
+        &mut self,
+        db: &mut State<DB>,
+        signer: &BundleRefundSigner,
+        gas_limit: u64,
+    ) -> Result<bool, PayloadBuilderError>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        // a single payment per recipient
+        let mut payments: Vec<(Address, U256)> = Vec::new();
+        for (recipient, value) in std::mem::take(&mut self.refunds) {
+            match payments.iter_mut().find(|(address, _)| *address == recipient) {
+                Some((_, total)) => *total += value,
+                None => payments.push((recipient, value)),
+            }
+        }
+
+        let base_fee = self.initialized_block_env.basefee.saturating_to::<u128>();
+        let gas_cost = U256::from(base_fee) * U256::from(REFUND_GAS_LIMIT);
+        for (recipient, value) in payments {
+            // refunds that don't cover the gas of the transfer are not paid
+            if value <= gas_cost {
+                continue
+            }
+            if self.gas_used + REFUND_GAS_LIMIT > gas_limit {
+                trace!(target: "payload_builder", bundle=?self.bundle_hash, "skipping bundle whose refunds exceed the remaining gas");
+                return Ok(false)
+            }
+
+            let nonce =
+                db.basic(signer.address())?.map(|account| account.nonce).unwrap_or_default();
+            let transaction = Transaction::Eip1559(TxEip1559 {
+                chain_id: self.initialized_cfg.chain_id,
+                nonce,
+                gas_limit: REFUND_GAS_LIMIT,
+                max_fee_per_gas: base_fee,
+                max_priority_fee_per_gas: 0,
+                to: TxKind::Call(recipient),
+                value: value - gas_cost,
+                ..Default::default()
+            });
+            let Some(transaction) = signer.sign(transaction) else { return Ok(false) };
+            if !self.execute_transaction(db, &transaction, false)? {
+                return Ok(false)
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Returns the recipients of a refund for the body item, as `(recipient, percent)`.
+///
+/// Refunds of transactions are paid to their sender, refunds of bundles are paid according to
+/// their refund config, or to the sender of their first transaction.
+fn refund_recipients(item: &MevBundleItem) -> Vec<(Address, u64)> {
+    match item {
+        MevBundleItem::Hash(_) => Vec::new(),
+        MevBundleItem::Tx { transaction, .. } => vec![(transaction.signer(), 100)],
+        MevBundleItem::Bundle(bundle) if !bundle.refund_config.is_empty() => {
+            bundle.refund_config.clone()
+        }
+        MevBundleItem::Bundle(bundle) => {
+            bundle.body.first().map(refund_recipients).unwrap_or_default()
+        }
+    }
+}
+
+/// Returns `true` if the bundle or any nested bundle refunds a share of its profit.
+fn has_refunds(bundle: &MevBundle) -> bool {
+    !bundle.refunds.is_empty() ||
+        bundle.body.iter().any(|item| match item {
+            MevBundleItem::Bundle(nested) => has_refunds(nested),
+            MevBundleItem::Hash(_) | MevBundleItem::Tx { .. } => false,
+        })
+}
+
+/// Collects the transactions of the bundle and all nested bundles in execution order, with
+/// whether they may revert.
+fn flatten_bundle<'a>(
+    bundle: &'a MevBundle,
+    out: &mut Vec<(&'a TransactionSignedEcRecovered, bool)>,
+) {
+    for item in &bundle.body {
+        match item {
+            // bundles from the pool are fully matched
+            MevBundleItem::Hash(_) => {}
+            MevBundleItem::Tx { transaction, can_revert } => out.push((transaction, *can_revert)),
+            MevBundleItem::Bundle(nested) => flatten_bundle(nested, out),
+        }
+    }
+}
+
+/// Returns the balance of `address` in the current state.
+fn balance<DB: Database<Error = ProviderError>>(
+    db: &mut State<DB>,
+    address: Address,
+) -> Result<U256, ProviderError> {
+    Ok(db.basic(address)?.map(|account| account.balance).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_payload_builder::bundle_pool::MevBundleRefund;
+    use std::sync::Arc;
+
+    fn tx_item(signer: Address) -> MevBundleItem {
+        MevBundleItem::Tx {
+            transaction: TransactionSigned::default().with_signer(signer),
+            can_revert: false,
+        }
+    }
+
+    fn bundle(body: Vec<MevBundleItem>) -> MevBundle {
+        MevBundle {
+            hash: MevBundle::compute_hash(&body),
+            block: 1,
+            max_block: 1,
+            body,
+            refunds: Vec::new(),
+            refund_config: Vec::new(),
+            privacy: None,
+        }
+    }
+
+    #[test]
+    fn refund_signer_address() {
+        let signer = BundleRefundSigner::new(B256::with_last_byte(1)).unwrap();
+        let transaction = signer.sign(Transaction::Eip1559(TxEip1559::default())).unwrap();
+        assert_eq!(transaction.recover_signer(), Some(signer.address()));
+
+        assert!(BundleRefundSigner::new(B256::ZERO).is_none());
+    }
+
+    #[test]
+    fn refunds_of_bundles_follow_their_refund_config() {
+        let (alice, bob, carol) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        assert_eq!(refund_recipients(&tx_item(alice)), vec![(alice, 100)]);
+
+        let nested = bundle(vec![tx_item(bob), tx_item(alice)]);
+        assert_eq!(
+            refund_recipients(&MevBundleItem::Bundle(Arc::new(nested.clone()))),
+            vec![(bob, 100)]
+        );
+
+        let nested = MevBundle { refund_config: vec![(alice, 60), (carol, 40)], ..nested };
+        assert_eq!(
+            refund_recipients(&MevBundleItem::Bundle(Arc::new(nested))),
+            vec![(alice, 60), (carol, 40)]
+        );
+    }
+
+    #[test]
+    fn nested_refunds() {
+        let nested = MevBundle {
+            refunds: vec![MevBundleRefund { body_idx: 0, percent: 10 }],
+            ..bundle(vec![tx_item(Address::with_last_byte(1)), tx_item(Address::with_last_byte(2))])
+        };
+        assert!(has_refunds(&nested));
+
+        let outer = bundle(vec![MevBundleItem::Bundle(Arc::new(nested))]);
+        assert!(has_refunds(&outer));
+        assert!(!has_refunds(&bundle(vec![tx_item(Address::with_last_byte(1))])));
+    }
+}
@@ -49,6 +49,9 @@ use tokio::{
 };
 use tracing::{debug, trace, warn};
 
+mod bundle;
+pub use bundle::{execute_bundle, BundleRefundSigner, ExecutedBundle};
+
 mod metrics;
 
 /// The [`PayloadJobGenerator`] that creates [`BasicPayloadJob`]s.
@@ -0,0 +1,330 @@
+//! A pool of MEV-Share bundles that payload builders can include in their blocks.
+//!
+//! Bundles are submitted via `mev_sendBundle` and stay in the pool until the last block they are
+//! valid for is canonical.
+
+use futures_util::{Stream, StreamExt};
+use parking_lot::RwLock;
+use reth_primitives::{keccak256, Address, BlockNumber, TransactionSignedEcRecovered, B256};
+use reth_provider::CanonStateNotification;
+use reth_rpc_types::Privacy;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+use tracing::trace;
+
+/// The default maximum number of bundles in the [`BundlePool`].
+pub const DEFAULT_MAX_BUNDLES: usize = 10_000;
+
+/// An item of the body of a [`MevBundle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MevBundleItem {
+    /// The hash of a transaction or bundle that is backrun by this bundle.
+    Hash(B256),
+    /// A signed transaction.
+    Tx {
+        /// The transaction with its recovered signer.
+        transaction: TransactionSignedEcRecovered,
+        /// Whether the transaction may revert without invalidating the bundle.
+        can_revert: bool,
+    },
+    /// A nested bundle.
+    Bundle(Arc<MevBundle>),
+}
+
+impl MevBundleItem {
+    /// Returns the hash that identifies this item in the bundle hash.
+    pub fn hash(&self) -> B256 {
+        match self {
+            Self::Hash(hash) => *hash,
+            Self::Tx { transaction, .. } => transaction.hash(),
+            Self::Bundle(bundle) => bundle.hash,
+        }
+    }
+}
+
+/// A refund of a share of the bundle's profit to the sender of one of its body items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MevBundleRefund {
+    /// The index of the body item the refund is paid for.
+    pub body_idx: usize,
+    /// The share of the profit in percent.
+    pub percent: u64,
+}
+
+/// A validated MEV-Share bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MevBundle {
+    /// The hash of the bundle, see [`MevBundle::compute_hash`].
+    pub hash: B256,
+    /// The first block the bundle is valid for.
+    pub block: BlockNumber,
+    /// The last block the bundle is valid for.
+    pub max_block: BlockNumber,
+    /// The transactions and bundles of the bundle, in execution order.
+    pub body: Vec<MevBundleItem>,
+    /// Shares of the profit that are refunded.
+    pub refunds: Vec<MevBundleRefund>,
+    /// Who receives the refund of this bundle if it's backrun, as `(recipient, percent)`.
+    pub refund_config: Vec<(Address, u64)>,
+    /// What may be shared about the bundle, and with which builders.
+    pub privacy: Option<Privacy>,
+}
+
+impl MevBundle {
+    /// Computes the hash of a bundle body: the keccak256 hash of the concatenated hashes of all
+    /// body items.
+    pub fn compute_hash(body: &[MevBundleItem]) -> B256 {
+        let mut hashes = Vec::with_capacity(body.len() * 32);
+        for item in body {
+            hashes.extend_from_slice(item.hash().as_slice());
+        }
+        keccak256(hashes)
+    }
+
+    /// Returns `true` if the bundle can be included in the given block.
+    pub const fn is_valid_at(&self, block: BlockNumber) -> bool {
+        self.block <= block && block <= self.max_block
+    }
+
+    /// Returns `true` if the body of this bundle, and all nested bundles, contain no backrun
+    /// hashes, which means all transactions are known and the bundle can be executed.
+    pub fn is_fully_matched(&self) -> bool {
+        self.body.iter().all(|item| match item {
+            MevBundleItem::Hash(_) => false,
+            MevBundleItem::Tx { .. } => true,
+            MevBundleItem::Bundle(bundle) => bundle.is_fully_matched(),
+        })
+    }
+
+    /// Returns how deep bundles are nested in the body of this bundle, `0` if it contains no
+    /// bundles.
+    pub fn nesting_depth(&self) -> usize {
+        self.body
+            .iter()
+            .filter_map(|item| match item {
+                MevBundleItem::Bundle(bundle) => Some(bundle.nesting_depth() + 1),
+                MevBundleItem::Hash(_) | MevBundleItem::Tx { .. } => None,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the given builder may include this bundle.
+    pub fn is_allowed_builder(&self, builder: &Address) -> bool {
+        self.privacy
+            .as_ref()
+            .and_then(|privacy| privacy.builders.as_ref())
+            .map_or(true, |builders| builders.contains(builder))
+    }
+}
+
+/// Errors that can occur when adding a bundle to the [`BundlePool`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BundlePoolError {
+    /// The bundle is already in the pool.
+    #[error("bundle {0} already known")]
+    AlreadyKnown(B256),
+    /// The last block the bundle is valid for is already canonical.
+    #[error("bundle {hash} expired at block {max_block}")]
+    Expired {
+        /// The hash of the bundle.
+        hash: B256,
+        /// The last block the bundle was valid for.
+        max_block: BlockNumber,
+    },
+    /// The pool reached its maximum number of bundles.
+    #[error("bundle pool is full")]
+    PoolFull,
+}
+
+/// A pool of [`MevBundle`]s, shared between the RPC that receives them and payload builders that
+/// include them.
+#[derive(Debug, Clone)]
+pub struct BundlePool {
+    inner: Arc<RwLock<BundlePoolInner>>,
+}
+
+impl BundlePool {
+    /// Creates a new pool that holds at most `max_bundles` bundles.
+    pub fn new(max_bundles: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(BundlePoolInner {
+                max_bundles,
+                last_block: 0,
+                bundles: HashMap::new(),
+                by_max_block: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// Adds a new bundle to the pool.
+    pub fn add_bundle(&self, bundle: MevBundle) -> Result<Arc<MevBundle>, BundlePoolError> {
+        let mut inner = self.inner.write();
+        if inner.bundles.contains_key(&bundle.hash) {
+            return Err(BundlePoolError::AlreadyKnown(bundle.hash))
+        }
+        if bundle.max_block <= inner.last_block {
+            return Err(BundlePoolError::Expired { hash: bundle.hash, max_block: bundle.max_block })
+        }
+        if inner.bundles.len() >= inner.max_bundles {
+            return Err(BundlePoolError::PoolFull)
+        }
+
+        let bundle = Arc::new(bundle);
+        inner.by_max_block.entry(bundle.max_block).or_default().insert(bundle.hash);
+        inner.bundles.insert(bundle.hash, Arc::clone(&bundle));
+        Ok(bundle)
+    }
+
+    /// Returns the bundle with the given hash.
+    pub fn get(&self, hash: &B256) -> Option<Arc<MevBundle>> {
+        self.inner.read().bundles.get(hash).cloned()
+    }
+
+    /// Removes the bundle with the given hash.
+    pub fn remove(&self, hash: &B256) -> Option<Arc<MevBundle>> {
+        let mut inner = self.inner.write();
+        let bundle = inner.bundles.remove(hash)?;
+        if let Some(hashes) = inner.by_max_block.get_mut(&bundle.max_block) {
+            hashes.remove(hash);
+            if hashes.is_empty() {
+                inner.by_max_block.remove(&bundle.max_block);
+            }
+        }
+        Some(bundle)
+    }
+
+    /// Returns all fully matched bundles that can be included in the given block.
+    ///
+    /// Bundles that are not fully matched backrun transactions or bundles that are not known to
+    /// the pool, and can't be executed on their own.
+    pub fn bundles_for_block(&self, block: BlockNumber) -> Vec<Arc<MevBundle>> {
+        self.inner
+            .read()
+            .bundles
+            .values()
+            .filter(|bundle| bundle.is_valid_at(block) && bundle.is_fully_matched())
+            .cloned()
+            .collect()
+    }
+
+    /// Removes all bundles that can't be included after the given canonical block.
+    pub fn on_canonical_block(&self, block: BlockNumber) {
+        let mut inner = self.inner.write();
+        inner.last_block = inner.last_block.max(block);
+
+        let valid = inner.by_max_block.split_off(&(block + 1));
+        let expired = std::mem::replace(&mut inner.by_max_block, valid);
+        for hash in expired.into_values().flatten() {
+            inner.bundles.remove(&hash);
+        }
+    }
+
+    /// Returns the number of bundles in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.read().bundles.len()
+    }
+
+    /// Returns `true` if the pool contains no bundles.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().bundles.is_empty()
+    }
+}
+
+impl Default for BundlePool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BUNDLES)
+    }
+}
+
+/// Evicts expired bundles from the [`BundlePool`] whenever a new block is canonical.
+pub async fn maintain_bundle_pool<St>(pool: BundlePool, mut events: St)
+where
+    St: Stream<Item = CanonStateNotification> + Unpin,
+{
+    while let Some(notification) = events.next().await {
+        let tip = notification.tip().number;
+        pool.on_canonical_block(tip);
+        trace!(target: "payload_builder::bundle_pool", tip, bundles = pool.len(), "Evicted expired bundles");
+    }
+}
+
+#[derive(Debug)]
+struct BundlePoolInner {
+    /// The maximum number of bundles in the pool.
+    max_bundles: usize,
+    /// The latest canonical block the pool was updated with.
+    last_block: BlockNumber,
+    /// All bundles by hash.
+    bundles: HashMap<B256, Arc<MevBundle>>,
+    /// Bundle hashes by the last block they are valid for.
+    by_max_block: BTreeMap<BlockNumber, HashSet<B256>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(hash: u8, block: BlockNumber, max_block: BlockNumber) -> MevBundle {
+        MevBundle {
+            hash: B256::with_last_byte(hash),
+            block,
+            max_block,
+            body: vec![MevBundleItem::Hash(B256::with_last_byte(hash))],
+            refunds: Vec::new(),
+            refund_config: Vec::new(),
+            privacy: None,
+        }
+    }
+
+    #[test]
+    fn evicts_expired_bundles() {
+        let pool = BundlePool::default();
+        pool.add_bundle(bundle(1, 1, 2)).unwrap();
+        pool.add_bundle(bundle(2, 1, 5)).unwrap();
+        assert_eq!(
+            pool.add_bundle(bundle(1, 1, 2)),
+            Err(BundlePoolError::AlreadyKnown(B256::with_last_byte(1)))
+        );
+
+        pool.on_canonical_block(2);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.get(&B256::with_last_byte(1)).is_none());
+        assert_eq!(
+            pool.add_bundle(bundle(3, 1, 2)),
+            Err(BundlePoolError::Expired { hash: B256::with_last_byte(3), max_block: 2 })
+        );
+
+        pool.on_canonical_block(5);
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn only_returns_matched_bundles() {
+        let pool = BundlePool::default();
+        let backrun = pool.add_bundle(bundle(1, 1, 3)).unwrap();
+        assert!(pool.bundles_for_block(2).is_empty());
+
+        let mut nested = bundle(2, 2, 3);
+        nested.body = vec![MevBundleItem::Bundle(backrun)];
+        pool.add_bundle(nested).unwrap();
+        assert!(pool.bundles_for_block(2).is_empty());
+        assert_eq!(pool.remove(&B256::with_last_byte(2)).map(|b| b.block), Some(2));
+    }
+
+    #[test]
+    fn nesting_depth() {
+        let inner = bundle(1, 1, 1);
+        assert_eq!(inner.nesting_depth(), 0);
+
+        let mut nested = bundle(2, 1, 1);
+        nested.body.push(MevBundleItem::Bundle(Arc::new(inner)));
+        assert_eq!(nested.nesting_depth(), 1);
+
+        let mut outer = bundle(3, 1, 1);
+        outer.body = vec![MevBundleItem::Bundle(Arc::new(nested))];
+        assert_eq!(outer.nesting_depth(), 2);
+    }
+}
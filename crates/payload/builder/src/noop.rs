@@ -50,6 +50,7 @@ where
                     tx.send(Ok(id)).ok()
                 }
                 PayloadServiceCommand::BestPayload(_, tx) => tx.send(None).ok(),
+                PayloadServiceCommand::BestPendingPayload(tx) => tx.send(None).ok(),
                 PayloadServiceCommand::PayloadAttributes(_, tx) => tx.send(None).ok(),
                 PayloadServiceCommand::Resolve(_, tx) => tx.send(None).ok(),
                 PayloadServiceCommand::Subscribe(_) => None,
@@ -62,6 +62,15 @@ where
         self.inner.best_payload(id).await
     }
 
+    /// Returns the best payload of the most recently started payload job, if it's still active.
+    ///
+    /// Note: this merely returns the best payload so far and does not resolve the job.
+    pub async fn best_pending_payload(
+        &self,
+    ) -> Option<Result<Engine::BuiltPayload, PayloadBuilderError>> {
+        self.inner.best_pending_payload().await
+    }
+
     /// Returns the payload attributes associated with the given identifier.
     ///
     /// Note: this returns the attributes of the payload and does not resolve the job.
@@ -142,6 +151,17 @@ where
         rx.await.ok()?
     }
 
+    /// Returns the best payload of the most recently started payload job, if it's still active.
+    ///
+    /// Note: this does not resolve the job if it's still in progress.
+    pub async fn best_pending_payload(
+        &self,
+    ) -> Option<Result<Engine::BuiltPayload, PayloadBuilderError>> {
+        let (tx, rx) = oneshot::channel();
+        self.to_service.send(PayloadServiceCommand::BestPendingPayload(tx)).ok()?;
+        rx.await.ok()?
+    }
+
     /// Returns the payload attributes associated with the given identifier.
     ///
     /// Note: this returns the attributes of the payload and does not resolve the job.
@@ -217,6 +237,8 @@ where
     generator: Gen,
     /// All active payload jobs.
     payload_jobs: Vec<(Gen::Job, PayloadId)>,
+    /// The identifier of the most recently started payload job.
+    latest_payload_id: Option<PayloadId>,
     /// Copy of the sender half, so new [`PayloadBuilderHandle`] can be created on demand.
     service_tx: mpsc::UnboundedSender<PayloadServiceCommand<Engine>>,
     /// Receiver half of the command channel.
@@ -253,6 +275,7 @@ where
         let service = Self {
             generator,
             payload_jobs: Vec::new(),
+            latest_payload_id: None,
             service_tx,
             command_rx: UnboundedReceiverStream::new(command_rx),
             metrics: Default::default(),
@@ -414,6 +437,7 @@ where
                                     this.metrics.inc_initiated_jobs();
                                     new_job = true;
                                     this.payload_jobs.push((job, id));
+                                    this.latest_payload_id = Some(id);
                                     this.payload_events.send(Events::Attributes(attr.clone())).ok();
                                 }
                                 Err(err) => {
@@ -430,6 +454,10 @@ where
                     PayloadServiceCommand::BestPayload(id, tx) => {
                         let _ = tx.send(this.best_payload(id));
                     }
+                    PayloadServiceCommand::BestPendingPayload(tx) => {
+                        let best = this.latest_payload_id.and_then(|id| this.best_payload(id));
+                        let _ = tx.send(best);
+                    }
                     PayloadServiceCommand::PayloadAttributes(id, tx) => {
                         let attributes = this.payload_attributes(id);
                         let _ = tx.send(attributes);
@@ -463,6 +491,8 @@ pub enum PayloadServiceCommand<Engine: PayloadTypes> {
         PayloadId,
         oneshot::Sender<Option<Result<Engine::BuiltPayload, PayloadBuilderError>>>,
     ),
+    /// Get the best payload so far of the most recently started payload job
+    BestPendingPayload(oneshot::Sender<Option<Result<Engine::BuiltPayload, PayloadBuilderError>>>),
     /// Get the payload attributes for the given payload
     PayloadAttributes(
         PayloadId,
@@ -486,6 +516,7 @@ where
             Self::BestPayload(f0, f1) => {
                 f.debug_tuple("BestPayload").field(&f0).field(&f1).finish()
             }
+            Self::BestPendingPayload(f0) => f.debug_tuple("BestPendingPayload").field(&f0).finish(),
             Self::PayloadAttributes(f0, f1) => {
                 f.debug_tuple("PayloadAttributes").field(&f0).field(&f1).finish()
             }
@@ -1,6 +1,7 @@
 use crate::{
     segments::{
-        history::prune_history_indices, PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
+        history::{prune_changeset_static_files, prune_history_indices},
+        PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
     },
     PrunerError,
 };
@@ -8,6 +9,7 @@ use reth_db::tables;
 use reth_db_api::{database::Database, models::ShardedKey};
 use reth_provider::DatabaseProviderRW;
 use reth_prune_types::{PruneInterruptReason, PruneMode, PruneProgress, PruneSegment};
+use reth_static_file_types::StaticFileSegment;
 use tracing::{instrument, trace};
 
 /// Number of account history tables to prune in one step.
@@ -87,11 +89,18 @@ impl<DB: Database> Segment<DB> for AccountHistory {
         )?;
         trace!(target: "pruner", %processed, pruned = %pruned_indices, %done, "Pruned account history (history)");
 
+        let pruned_static_files = prune_changeset_static_files(
+            provider,
+            StaticFileSegment::AccountChangeSets,
+            last_changeset_pruned_block,
+        )?;
+        trace!(target: "pruner", pruned = %pruned_static_files, "Pruned account history (static files)");
+
         let progress = PruneProgress::new(done, &limiter);
 
         Ok(PruneOutput {
             progress,
-            pruned: pruned_changesets + pruned_indices,
+            pruned: pruned_changesets + pruned_indices + pruned_static_files,
             checkpoint: Some(PruneOutputCheckpoint {
                 block_number: Some(last_changeset_pruned_block),
                 tx_number: None,
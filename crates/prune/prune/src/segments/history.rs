@@ -8,7 +8,26 @@ use reth_db_api::{
     transaction::DbTxMut,
     DatabaseError,
 };
-use reth_provider::DatabaseProviderRW;
+use reth_provider::{DatabaseProviderRW, ProviderResult};
+use reth_prune_types::PruneLimiter;
+use reth_static_file_types::StaticFileSegment;
+
+/// Deletes the static files of a changeset segment whose blocks are all at or below the provided
+/// block.
+///
+/// Returns total number of blocks in the deleted static files.
+pub(crate) fn prune_changeset_static_files<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    segment: StaticFileSegment,
+    to_block: BlockNumber,
+) -> ProviderResult<usize> {
+    Ok(provider
+        .static_file_provider()
+        .delete_segment_below_block(segment, to_block)?
+        .iter()
+        .map(|header| header.block_len().unwrap_or_default() as usize)
+        .sum())
+}
 
 /// Prune history indices up to the provided block, inclusive.
 ///
@@ -19,6 +38,80 @@ pub(crate) fn prune_history_indices<DB, T, SK>(
     key_matches: impl Fn(&T::Key, &T::Key) -> bool,
     last_key: impl Fn(&T::Key) -> T::Key,
 ) -> Result<(usize, usize), DatabaseError>
+where
+    DB: Database,
+    T: Table<Value = BlockNumberList>,
+    T::Key: AsRef<ShardedKey<SK>>,
+{
+    prune_history_indices_filtered::<DB, T, SK>(provider, to_block, |_| true, key_matches, last_key)
+}
+
+/// Prune history indices up to the provided block, inclusive, until the limiter is reached.
+///
+/// Returns total number of processed (walked) and deleted entities, and whether all shards were
+/// pruned.
+pub(crate) fn prune_history_indices_with_limiter<DB, T, SK>(
+    provider: &DatabaseProviderRW<DB>,
+    to_block: BlockNumber,
+    limiter: &mut PruneLimiter,
+    key_matches: impl Fn(&T::Key, &T::Key) -> bool,
+    last_key: impl Fn(&T::Key) -> T::Key,
+) -> Result<(usize, usize, bool), DatabaseError>
+where
+    DB: Database,
+    T: Table<Value = BlockNumberList>,
+    T::Key: AsRef<ShardedKey<SK>>,
+{
+    prune_history_indices_limited::<DB, T, SK>(
+        provider,
+        to_block,
+        limiter,
+        |_| true,
+        key_matches,
+        last_key,
+    )
+}
+
+/// Prune history indices of the keys accepted by `key_filter` up to the provided block, inclusive.
+/// Shards of the other keys are left untouched.
+///
+/// Returns total number of processed (walked) and deleted entities.
+pub(crate) fn prune_history_indices_filtered<DB, T, SK>(
+    provider: &DatabaseProviderRW<DB>,
+    to_block: BlockNumber,
+    key_filter: impl Fn(&T::Key) -> bool,
+    key_matches: impl Fn(&T::Key, &T::Key) -> bool,
+    last_key: impl Fn(&T::Key) -> T::Key,
+) -> Result<(usize, usize), DatabaseError>
+where
+    DB: Database,
+    T: Table<Value = BlockNumberList>,
+    T::Key: AsRef<ShardedKey<SK>>,
+{
+    let (processed, deleted, _) = prune_history_indices_limited::<DB, T, SK>(
+        provider,
+        to_block,
+        &mut PruneLimiter::default(),
+        key_filter,
+        key_matches,
+        last_key,
+    )?;
+    Ok((processed, deleted))
+}
+
+/// Prune history indices of the keys accepted by `key_filter` up to the provided block,
+/// inclusive, until the limiter is reached.
+///
+/// Returns total number of processed (walked) and deleted entities, and whether all shards were
+/// pruned.
+fn prune_history_indices_limited<DB, T, SK>(
+    provider: &DatabaseProviderRW<DB>,
+    to_block: BlockNumber,
+    limiter: &mut PruneLimiter,
+    key_filter: impl Fn(&T::Key) -> bool,
+    key_matches: impl Fn(&T::Key, &T::Key) -> bool,
+    last_key: impl Fn(&T::Key) -> T::Key,
+) -> Result<(usize, usize, bool), DatabaseError>
 where
     DB: Database,
     T: Table<Value = BlockNumberList>,
@@ -35,13 +128,26 @@ where
     // pruning, filter block numbers inside the shard which are less than the target
     // block number for pruning.
     while let Some(result) = cursor.next()? {
+        if limiter.is_limit_reached() {
+            return Ok((processed, deleted, false))
+        }
+
         let (key, blocks): (T::Key, BlockNumberList) = result;
 
+        // Skip all shards of the keys that shouldn't be pruned.
+        if !key_filter(&key) {
+            if key.as_ref().highest_block_number != u64::MAX {
+                cursor.seek_exact(last_key(&key))?;
+            }
+            continue
+        }
+
         // If shard consists only of block numbers less than the target one, delete shard
         // completely.
         if key.as_ref().highest_block_number <= to_block {
             cursor.delete_current()?;
             deleted += 1;
+            limiter.increment_deleted_entries_count();
             if key.as_ref().highest_block_number == to_block {
                 // Shard contains only block numbers up to the target one, so we can skip to
                 // the last shard for this key. It is guaranteed that further shards for this
@@ -70,6 +176,7 @@ where
                             Some((prev_key, prev_value)) if key_matches(&prev_key, &key) => {
                                 cursor.delete_current()?;
                                 deleted += 1;
+                                limiter.increment_deleted_entries_count();
                                 // Upsert will replace the last shard for this sharded key with
                                 // the previous value.
                                 cursor.upsert(key.clone(), prev_value)?;
@@ -85,6 +192,7 @@ where
                                 // Delete shard.
                                 cursor.delete_current()?;
                                 deleted += 1;
+                                limiter.increment_deleted_entries_count();
                             }
                         }
                     }
@@ -93,6 +201,7 @@ where
                     else {
                         cursor.delete_current()?;
                         deleted += 1;
+                        limiter.increment_deleted_entries_count();
                     }
                 } else {
                     cursor.upsert(key.clone(), BlockNumberList::new_pre_sorted(higher_blocks))?;
@@ -108,5 +217,5 @@ where
         processed += 1;
     }
 
-    Ok((processed, deleted))
+    Ok((processed, deleted, true))
 }
@@ -0,0 +1,223 @@
+use crate::{
+    segments::{
+        history::prune_history_indices_with_limiter, PruneInput, PruneOutput,
+        PruneOutputCheckpoint, Segment,
+    },
+    PrunerError,
+};
+use reth_db::tables;
+use reth_db_api::{database::Database, models::ShardedKey};
+use reth_provider::DatabaseProviderRW;
+use reth_prune_types::{PruneInterruptReason, PruneMode, PruneProgress, PruneSegment};
+use tracing::{instrument, trace};
+
+/// Prunes the [`tables::LogAddressHistory`] and [`tables::LogTopicHistory`] tables.
+///
+/// The log index points to blocks whose receipts contain the logs, so it's pruned with the same
+/// mode as the receipts.
+#[derive(Debug)]
+pub struct LogIndex {
+    mode: PruneMode,
+}
+
+impl LogIndex {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<DB: Database> Segment<DB> for LogIndex {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::LogIndex
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No log index to prune");
+                return Ok(PruneOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        let mut limiter = input.limiter;
+        if limiter.is_limit_reached() {
+            return Ok(PruneOutput::not_done(
+                PruneInterruptReason::new(&limiter),
+                input.previous_checkpoint.map(|checkpoint| checkpoint.into()),
+            ))
+        }
+
+        // Both tables are walked from the start on every run, so an interrupted run is continued
+        // by the next one with the same range.
+        let (processed_addresses, pruned_addresses, mut done) =
+            prune_history_indices_with_limiter::<DB, tables::LogAddressHistory, _>(
+                provider,
+                range_end,
+                &mut limiter,
+                |a, b| a.key == b.key,
+                |key| ShardedKey::last(key.key),
+            )?;
+        trace!(target: "pruner", processed = %processed_addresses, pruned = %pruned_addresses, %done, "Pruned log address history");
+
+        let mut pruned_topics = 0;
+        if done {
+            let (processed_topics, pruned, topics_done) =
+                prune_history_indices_with_limiter::<DB, tables::LogTopicHistory, _>(
+                    provider,
+                    range_end,
+                    &mut limiter,
+                    |a, b| a.key == b.key,
+                    |key| ShardedKey::last(key.key),
+                )?;
+            pruned_topics = pruned;
+            done = topics_done;
+            trace!(target: "pruner", processed = %processed_topics, pruned = %pruned_topics, %done, "Pruned log topic history");
+        }
+
+        // The checkpoint only moves once both tables are pruned up to the end of the range.
+        let checkpoint = if done {
+            Some(PruneOutputCheckpoint { block_number: Some(range_end), tx_number: None })
+        } else {
+            input.previous_checkpoint.map(|checkpoint| checkpoint.into())
+        };
+
+        Ok(PruneOutput {
+            progress: PruneProgress::new(done, &limiter),
+            pruned: pruned_addresses + pruned_topics,
+            checkpoint,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{LogIndex, PruneInput, Segment};
+    use alloy_primitives::{Address, B256};
+    use reth_db::{models::ShardedKey, tables, BlockNumberList};
+    use reth_provider::PruneCheckpointReader;
+    use reth_prune_types::{PruneLimiter, PruneMode, PruneProgress, PruneSegment};
+    use reth_stages::test_utils::TestStageDB;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+
+        let address = Address::with_last_byte(1);
+        let topic = B256::with_last_byte(2);
+        db.commit(|tx| {
+            tx.put::<tables::LogAddressHistory>(
+                ShardedKey::last(address),
+                BlockNumberList::new([2, 5, 8]).unwrap(),
+            )?;
+            tx.put::<tables::LogTopicHistory>(
+                ShardedKey::last(topic),
+                BlockNumberList::new([3, 5]).unwrap(),
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let prune_mode = PruneMode::Before(6);
+        let segment = LogIndex::new(prune_mode);
+        let input =
+            PruneInput { previous_checkpoint: None, to_block: 5, limiter: PruneLimiter::default() };
+        let provider = db.factory.provider_rw().unwrap();
+        let output = segment.prune(&provider, input).unwrap();
+        segment
+            .save_checkpoint(&provider, output.checkpoint.unwrap().as_prune_checkpoint(prune_mode))
+            .unwrap();
+        provider.commit().expect("commit");
+
+        let addresses = db
+            .table::<tables::LogAddressHistory>()
+            .unwrap()
+            .into_iter()
+            .map(|(key, blocks)| (key.key, blocks.iter().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        assert_eq!(addresses, vec![(address, vec![8])]);
+        assert!(db.table::<tables::LogTopicHistory>().unwrap().is_empty());
+
+        assert_eq!(
+            db.factory
+                .provider()
+                .unwrap()
+                .get_prune_checkpoint(PruneSegment::LogIndex)
+                .unwrap()
+                .and_then(|checkpoint| checkpoint.block_number),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn prune_with_limiter() {
+        let db = TestStageDB::default();
+
+        let addresses = [Address::with_last_byte(1), Address::with_last_byte(2)];
+        let topic = B256::with_last_byte(3);
+        db.commit(|tx| {
+            for address in addresses {
+                tx.put::<tables::LogAddressHistory>(
+                    ShardedKey::last(address),
+                    BlockNumberList::new([2, 4]).unwrap(),
+                )?;
+            }
+            tx.put::<tables::LogTopicHistory>(
+                ShardedKey::last(topic),
+                BlockNumberList::new([3]).unwrap(),
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let prune_mode = PruneMode::Before(6);
+        let segment = LogIndex::new(prune_mode);
+        let run = |previous_checkpoint| {
+            let input = PruneInput {
+                previous_checkpoint,
+                to_block: 5,
+                limiter: PruneLimiter::default().set_deleted_entries_limit(1),
+            };
+            let provider = db.factory.provider_rw().unwrap();
+            let output = segment.prune(&provider, input).unwrap();
+            if let Some(checkpoint) = output.checkpoint {
+                segment
+                    .save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))
+                    .unwrap();
+            }
+            provider.commit().expect("commit");
+            output
+        };
+        let checkpoint =
+            || db.factory.provider().unwrap().get_prune_checkpoint(PruneSegment::LogIndex).unwrap();
+
+        // Every run deletes a single shard and keeps the checkpoint until all shards are pruned
+        for remaining_shards in [2, 1] {
+            let output = run(checkpoint());
+            assert!(matches!(output.progress, PruneProgress::HasMoreData(_)));
+            assert_eq!(output.pruned, 1);
+            assert_eq!(checkpoint().and_then(|checkpoint| checkpoint.block_number), None);
+            assert_eq!(
+                db.table::<tables::LogAddressHistory>().unwrap().len() +
+                    db.table::<tables::LogTopicHistory>().unwrap().len(),
+                remaining_shards
+            );
+        }
+
+        let output = run(checkpoint());
+        assert!(output.progress.is_finished());
+        assert_eq!(output.pruned, 1);
+        assert!(db.table::<tables::LogTopicHistory>().unwrap().is_empty());
+        assert_eq!(checkpoint().and_then(|checkpoint| checkpoint.block_number), Some(5));
+    }
+}
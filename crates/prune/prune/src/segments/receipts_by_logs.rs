@@ -1,9 +1,12 @@
 use crate::{
-    segments::{PruneInput, PruneOutput, Segment},
+    segments::{
+        history::{prune_history_indices, prune_history_indices_filtered},
+        PruneInput, PruneOutput, Segment,
+    },
     PrunerError,
 };
 use reth_db::tables;
-use reth_db_api::database::Database;
+use reth_db_api::{database::Database, models::ShardedKey};
 use reth_provider::{BlockReader, DatabaseProviderRW, PruneCheckpointWriter, TransactionsProvider};
 use reth_prune_types::{
     PruneCheckpoint, PruneMode, PruneProgress, PrunePurpose, PruneSegment, ReceiptsLogPruneConfig,
@@ -184,6 +187,37 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
             from_tx_number = last_pruned_transaction + 1;
         }
 
+        // Receipts of the pruned blocks can't be returned by log queries anymore, so their entries
+        // in the log index are pruned too. Topics can't be attributed to the retained addresses,
+        // so the topic index is only pruned up to the first block with retained receipts.
+        if let Some(last_pruned_block) = last_pruned_block {
+            let (processed, deleted) =
+                prune_history_indices_filtered::<DB, tables::LogAddressHistory, _>(
+                    provider,
+                    last_pruned_block,
+                    |key| !filtered_addresses.contains(&&key.key),
+                    |a, b| a.key == b.key,
+                    |key| ShardedKey::last(key.key),
+                )?;
+            trace!(target: "pruner", %processed, %deleted, "Pruned log address history");
+
+            let topics_to_block = match address_filter.keys().next() {
+                Some(start_block) => {
+                    start_block.checked_sub(1).map(|block| block.min(last_pruned_block))
+                }
+                None => Some(last_pruned_block),
+            };
+            if let Some(topics_to_block) = topics_to_block {
+                let (processed, deleted) = prune_history_indices::<DB, tables::LogTopicHistory, _>(
+                    provider,
+                    topics_to_block,
+                    |a, b| a.key == b.key,
+                    |key| ShardedKey::last(key.key),
+                )?;
+                trace!(target: "pruner", %processed, %deleted, "Pruned log topic history");
+            }
+        }
+
         // If there are contracts using `PruneMode::Distance(_)` there will be receipts before
         // `to_block` that become eligible to be pruned in future runs. Therefore, our checkpoint is
         // not actually `to_block`, but the `lowest_block_with_distance` from any contract.
@@ -1,5 +1,5 @@
 use crate::segments::{
-    AccountHistory, Receipts, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory,
+    AccountHistory, LogIndex, Receipts, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory,
     TransactionLookup,
 };
 use reth_db_api::database::Database;
@@ -55,6 +55,8 @@ impl<DB: Database> SegmentSet<DB> {
                 (!receipts_log_filter.is_empty())
                     .then(|| ReceiptsByLogs::new(receipts_log_filter.clone())),
             )
+            // Log index, pointing to the blocks of the receipts
+            .segment_opt(receipts.map(LogIndex::new))
             // Transaction lookup
             .segment_opt(transaction_lookup.map(TransactionLookup::new))
             // Sender recovery
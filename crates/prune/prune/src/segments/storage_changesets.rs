@@ -0,0 +1,75 @@
+use crate::{
+    segments::{PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
+    PrunerError,
+};
+use reth_db::tables;
+use reth_db_api::{database::Database, models::BlockNumberAddress};
+use reth_provider::DatabaseProviderRW;
+use reth_prune_types::{PruneMode, PruneProgress, PruneSegment};
+use tracing::{instrument, trace};
+
+/// Prunes the [`tables::StorageChangeSets`] table, leaving the [`tables::StoragesHistory`] table
+/// untouched. Used to delete the changesets that were moved to static files.
+#[derive(Debug)]
+pub struct StorageChangeSets {
+    mode: PruneMode,
+}
+
+impl StorageChangeSets {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<DB: Database> Segment<DB> for StorageChangeSets {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::StorageChangeSets
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No storage changesets to prune");
+                return Ok(PruneOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        let mut limiter = input.limiter;
+        let mut last_pruned_block = None;
+        let (pruned, done) = provider.prune_table_with_range::<tables::StorageChangeSets>(
+            BlockNumberAddress::range(range),
+            &mut limiter,
+            |_| false,
+            |row| last_pruned_block = Some(row.0.block_number()),
+        )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned storage changesets");
+
+        let last_pruned_block = last_pruned_block
+            // If there's more storage changesets to prune, set the checkpoint block number to
+            // previous, so we could finish pruning its storage changesets on the next run.
+            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .unwrap_or(range_end);
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(PruneOutput {
+            progress,
+            pruned,
+            checkpoint: Some(PruneOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     segments::{
-        history::prune_history_indices, PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
+        history::{prune_changeset_static_files, prune_history_indices},
+        PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
     },
     PrunerError,
 };
@@ -11,6 +12,7 @@ use reth_db_api::{
 };
 use reth_provider::DatabaseProviderRW;
 use reth_prune_types::{PruneInterruptReason, PruneMode, PruneProgress, PruneSegment};
+use reth_static_file_types::StaticFileSegment;
 use tracing::{instrument, trace};
 
 /// Number of storage history tables to prune in one step
@@ -90,11 +92,18 @@ impl<DB: Database> Segment<DB> for StorageHistory {
         )?;
         trace!(target: "pruner", %processed, deleted = %pruned_indices, %done, "Pruned storage history (history)");
 
+        let pruned_static_files = prune_changeset_static_files(
+            provider,
+            StaticFileSegment::StorageChangeSets,
+            last_changeset_pruned_block,
+        )?;
+        trace!(target: "pruner", deleted = %pruned_static_files, "Pruned storage history (static files)");
+
         let progress = PruneProgress::new(done, &limiter);
 
         Ok(PruneOutput {
             progress,
-            pruned: pruned_changesets + pruned_indices,
+            pruned: pruned_changesets + pruned_indices + pruned_static_files,
             checkpoint: Some(PruneOutputCheckpoint {
                 block_number: Some(last_changeset_pruned_block),
                 tx_number: None,
@@ -24,17 +24,28 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the `AccountChangeSets` table, without the `AccountsHistory`
+    /// table. Only used to delete the changesets moved to static files.
+    AccountChangeSets,
+    /// Prune segment responsible for the `StorageChangeSets` table, without the `StoragesHistory`
+    /// table. Only used to delete the changesets moved to static files.
+    StorageChangeSets,
+    /// Prune segment responsible for the `LogAddressHistory` and `LogTopicHistory` tables.
+    LogIndex,
 }
 
 impl PruneSegment {
     /// Returns minimum number of blocks to left in the database for this segment.
     pub const fn min_blocks(&self, purpose: PrunePurpose) -> u64 {
         match self {
-            Self::SenderRecovery | Self::TransactionLookup | Self::Headers | Self::Transactions => {
-                0
-            }
+            Self::SenderRecovery |
+            Self::TransactionLookup |
+            Self::Headers |
+            Self::Transactions |
+            Self::AccountChangeSets |
+            Self::StorageChangeSets => 0,
             Self::Receipts if purpose.is_static_file() => 0,
-            Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
+            Self::ContractLogs | Self::AccountHistory | Self::StorageHistory | Self::LogIndex => {
                 MINIMUM_PRUNING_DISTANCE
             }
             Self::Receipts => MINIMUM_PRUNING_DISTANCE,
@@ -11,7 +11,7 @@ use jsonrpsee::{
     core::TEN_MB_SIZE_BYTES,
     server::{
         middleware::rpc::{RpcLoggerLayer, RpcServiceT},
-        AlreadyStoppedError, ConnectionGuard, ConnectionPermit, IdProvider,
+        AlreadyStoppedError, BatchRequestConfig, ConnectionGuard, ConnectionPermit, IdProvider,
         RandomIntegerIdProvider,
     },
     BoundedSubscriptions, MethodSink, Methods,
@@ -137,7 +137,7 @@ where
             }
         }
 
-        let listener = match LocalSocketListener::bind(self.endpoint.clone()) {
+        let listener = match bind(&self.endpoint, self.cfg.socket_permissions) {
             Err(err) => {
                 on_ready
                     .send(Err(IpcServerStartError { endpoint: self.endpoint.clone(), source: err }))
@@ -212,6 +212,47 @@ where
     }
 }
 
+/// Binds the listener to the endpoint, with the given permissions of the socket file.
+///
+/// On unix, the socket is bound inside a private directory next to the endpoint and only moved to
+/// the endpoint once its permissions are set, so it's never reachable with the default
+/// permissions.
+#[cfg(unix)]
+fn bind(endpoint: &str, permissions: Option<u32>) -> io::Result<LocalSocketListener> {
+    use std::{
+        fs::{DirBuilder, Permissions},
+        os::unix::fs::{DirBuilderExt, PermissionsExt},
+        path::Path,
+    };
+
+    let Some(mode) = permissions else { return LocalSocketListener::bind(endpoint) };
+
+    let endpoint = Path::new(endpoint);
+    let file_name = endpoint
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "IPC endpoint is not a file"))?;
+    let private_dir =
+        endpoint.with_file_name(format!(".{}.{}", file_name.to_string_lossy(), std::process::id()));
+    // leftover of a previous run that didn't clean up
+    let _ = std::fs::remove_dir_all(&private_dir);
+    DirBuilder::new().mode(0o700).create(&private_dir)?;
+
+    let socket_path = private_dir.join(file_name);
+    let listener = LocalSocketListener::bind(socket_path.as_path()).and_then(|listener| {
+        std::fs::set_permissions(&socket_path, Permissions::from_mode(mode))?;
+        std::fs::rename(&socket_path, endpoint)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_dir_all(&private_dir);
+    listener
+}
+
+/// Binds the listener to the endpoint. Permissions only apply to unix sockets.
+#[cfg(not(unix))]
+fn bind(endpoint: &str, _permissions: Option<u32>) -> io::Result<LocalSocketListener> {
+    LocalSocketListener::bind(endpoint)
+}
+
 enum AcceptConnection<S> {
     Shutdown,
     Established { local_socket_stream: LocalSocketStream, stop: S },
@@ -386,6 +427,7 @@ where
 
         let max_response_body_size = self.inner.server_cfg.max_response_body_size as usize;
         let max_request_body_size = self.inner.server_cfg.max_request_body_size as usize;
+        let batch_config = self.inner.server_cfg.batch_config;
         let conn = self.inner.conn_permit.clone();
         let rpc_service = self.rpc_middleware.service(RpcService::new(
             self.inner.methods.clone(),
@@ -405,6 +447,7 @@ where
                 rpc_service,
                 max_response_body_size,
                 max_request_body_size,
+                batch_config,
                 conn,
             )
             .await
@@ -544,10 +587,14 @@ pub struct Settings {
     max_connections: u32,
     /// Maximum number of subscriptions per connection.
     max_subscriptions_per_connection: u32,
+    /// Whether batch requests are supported and the maximum number of calls per batch.
+    batch_config: BatchRequestConfig,
     /// Number of messages that server is allowed `buffer` until backpressure kicks in.
     message_buffer_capacity: u32,
     /// Custom tokio runtime to run the server on.
     tokio_runtime: Option<tokio::runtime::Handle>,
+    /// Filesystem permissions of the socket file, only applied on unix.
+    socket_permissions: Option<u32>,
 }
 
 impl Default for Settings {
@@ -558,8 +605,10 @@ impl Default for Settings {
             max_log_length: 4096,
             max_connections: 100,
             max_subscriptions_per_connection: 1024,
+            batch_config: BatchRequestConfig::Unlimited,
             message_buffer_capacity: 1024,
             tokio_runtime: None,
+            socket_permissions: None,
         }
     }
 }
@@ -616,6 +665,13 @@ impl<HttpMiddleware, RpcMiddleware> Builder<HttpMiddleware, RpcMiddleware> {
         self
     }
 
+    /// Configure how batch requests are handled, e.g. the maximum number of calls per batch.
+    /// Default is unlimited.
+    pub const fn set_batch_request_config(mut self, cfg: BatchRequestConfig) -> Self {
+        self.settings.batch_config = cfg;
+        self
+    }
+
     /// The server enforces backpressure which means that
     /// `n` messages can be buffered and if the client
     /// can't keep with up the server.
@@ -638,6 +694,15 @@ impl<HttpMiddleware, RpcMiddleware> Builder<HttpMiddleware, RpcMiddleware> {
         self
     }
 
+    /// Set the filesystem permissions of the socket file, e.g. `0o600` to only allow the owner to
+    /// connect. Default is the permissions derived from the process umask.
+    ///
+    /// Note: this is only applied on unix.
+    pub const fn socket_permissions(mut self, mode: u32) -> Self {
+        self.settings.socket_permissions = Some(mode);
+        self
+    }
+
     /// Configure a custom [`tokio::runtime::Handle`] to run the server on.
     ///
     /// Default: [`tokio::spawn`]
@@ -905,6 +970,34 @@ mod tests {
         assert!(response.unwrap_err().to_string().contains("Exceeded max limit of"));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn can_set_the_socket_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let endpoint = dummy_endpoint();
+        let server = Builder::default().socket_permissions(0o600).build(&endpoint);
+        let mut module = RpcModule::new(());
+        module.register_method("anything", |_, _, _| "succeed").unwrap();
+        let handle = server.start(module).await.unwrap();
+        tokio::spawn(handle.stopped());
+
+        let mode = std::fs::metadata(&endpoint).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // the socket moved out of the private directory is still served
+        let client = IpcClientBuilder::default().build(&endpoint).await.unwrap();
+        let response: String = client.request("anything", rpc_params![]).await.unwrap();
+        assert_eq!(response, "succeed");
+        let endpoint = std::path::Path::new(&endpoint);
+        let private_dir = endpoint.with_file_name(format!(
+            ".{}.{}",
+            endpoint.file_name().unwrap().to_string_lossy(),
+            std::process::id()
+        ));
+        assert!(!private_dir.exists());
+    }
+
     #[tokio::test]
     async fn can_set_the_max_request_body_size() {
         init_test_tracing();
@@ -1006,6 +1099,32 @@ mod tests {
         assert_eq!(result, vec!["ok", "ok", "ok"]);
     }
 
+    #[tokio::test]
+    async fn can_set_the_batch_request_limit() {
+        init_test_tracing();
+        let endpoint = dummy_endpoint();
+        let server = Builder::default()
+            .set_batch_request_config(BatchRequestConfig::Limit(2))
+            .build(&endpoint);
+        let mut module = RpcModule::new(());
+        module.register_method("anything", |_, _, _| "ok").unwrap();
+        let handle = server.start(module).await.unwrap();
+        tokio::spawn(handle.stopped());
+
+        let client = IpcClientBuilder::default().build(endpoint).await.unwrap();
+        let mut batch_request_builder = BatchRequestBuilder::new();
+        let _ = batch_request_builder.insert("anything", rpc_params![]);
+        let _ = batch_request_builder.insert("anything", rpc_params![]);
+        let response: Result<client::BatchResponse<'_, String>, Error> =
+            client.batch_request(batch_request_builder.clone()).await;
+        assert!(response.is_ok());
+
+        let _ = batch_request_builder.insert("anything", rpc_params![]);
+        let response: Result<client::BatchResponse<'_, String>, Error> =
+            client.batch_request(batch_request_builder).await;
+        assert!(response.is_err());
+    }
+
     #[tokio::test]
     async fn test_ipc_modules() {
         reth_tracing::init_test_tracing();
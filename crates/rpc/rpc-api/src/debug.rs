@@ -162,6 +162,9 @@ pub trait DebugApi {
 
     /// Flattens the entire key-value database into a single level, removing all unused slots and
     /// merging all keys.
+    ///
+    /// Not supported while the node is running, the database is compacted offline with
+    /// `reth db compact`.
     #[method(name = "chaindbCompact")]
     async fn debug_chaindb_compact(&self) -> RpcResult<()>;
 
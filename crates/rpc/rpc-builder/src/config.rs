@@ -2,10 +2,13 @@ use crate::{
     auth::AuthServerConfig, error::RpcError, EthConfig, IpcServerBuilder, RpcModuleConfig,
     RpcServerConfig, TransportRpcModuleConfig,
 };
-use jsonrpsee::server::ServerBuilder;
+use jsonrpsee::server::{BatchRequestConfig, ServerBuilder};
 use reth_node_core::{args::RpcServerArgs, utils::get_or_create_jwt_secret_from_path};
-use reth_rpc::eth::{cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig};
-use reth_rpc_layer::{JwtError, JwtSecret};
+use reth_rpc::{
+    eth::{cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig},
+    ValidationApiConfig,
+};
+use reth_rpc_layer::{JwtError, JwtSecret, RateLimitConfig, RpcRecordConfig};
 use reth_rpc_server_types::RpcModuleSelection;
 use std::{net::SocketAddr, path::PathBuf};
 use tower::layer::util::Identity;
@@ -34,9 +37,21 @@ pub trait RethRpcServerConfig {
     /// Returns the max response size in bytes.
     fn rpc_max_response_size_bytes(&self) -> u32;
 
+    /// Returns the batch request configuration for all transports.
+    fn rpc_batch_request_config(&self) -> BatchRequestConfig;
+
     /// Extracts the gas price oracle config from the args.
     fn gas_price_oracle_config(&self) -> GasPriceOracleConfig;
 
+    /// Returns the builder submission validation configuration.
+    fn validation_api_config(&self) -> ValidationApiConfig;
+
+    /// Returns the rate limiting configuration, if any limits are configured.
+    fn rate_limit_config(&self) -> Option<RateLimitConfig>;
+
+    /// Returns the configuration of the call recording, if enabled.
+    fn record_config(&self) -> Option<RpcRecordConfig>;
+
     /// Creates the [`TransportRpcModuleConfig`] from cli args.
     ///
     /// This sets all the api modules, and configures additional settings like gas price oracle
@@ -91,7 +106,11 @@ impl RethRpcServerConfig for RpcServerArgs {
             .max_tracing_requests(self.rpc_max_tracing_requests)
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
+            .max_trace_filter_blocks(self.rpc_max_trace_filter_blocks.unwrap_or_max())
+            .trace_cache_dir(self.rpc_trace_cache_dir.clone())
+            .trace_cache_max_blocks(self.rpc_trace_cache_max_blocks)
             .rpc_gas_cap(self.rpc_gas_cap)
+            .max_response_size(self.rpc_max_response_size_bytes() as usize)
             .state_cache(self.state_cache_config())
             .gpo_config(self.gas_price_oracle_config())
     }
@@ -99,9 +118,12 @@ impl RethRpcServerConfig for RpcServerArgs {
     fn state_cache_config(&self) -> EthStateCacheConfig {
         EthStateCacheConfig {
             max_blocks: self.rpc_state_cache.max_blocks,
+            max_blocks_bytes: self.rpc_state_cache.max_blocks_size.saturating_mul(1024 * 1024),
             max_receipts: self.rpc_state_cache.max_receipts,
+            max_receipts_bytes: self.rpc_state_cache.max_receipts_size.saturating_mul(1024 * 1024),
             max_envs: self.rpc_state_cache.max_envs,
             max_concurrent_db_requests: self.rpc_state_cache.max_concurrent_db_requests,
+            prewarm_blocks: self.rpc_state_cache.prewarm_blocks,
         }
     }
 
@@ -113,10 +135,30 @@ impl RethRpcServerConfig for RpcServerArgs {
         self.rpc_max_response_size.get().saturating_mul(1024 * 1024)
     }
 
+    fn rpc_batch_request_config(&self) -> BatchRequestConfig {
+        BatchRequestConfig::Limit(self.rpc_max_batch_size.get())
+    }
+
     fn gas_price_oracle_config(&self) -> GasPriceOracleConfig {
         self.gas_price_oracle.gas_price_oracle_config()
     }
 
+    fn validation_api_config(&self) -> ValidationApiConfig {
+        self.validation.validation_api_config()
+    }
+
+    fn rate_limit_config(&self) -> Option<RateLimitConfig> {
+        self.rate_limit.rate_limit_config()
+    }
+
+    fn record_config(&self) -> Option<RpcRecordConfig> {
+        self.rpc_record_dir.clone().map(|dir| {
+            RpcRecordConfig::new(dir)
+                .with_max_file_size(self.rpc_record_max_file_size.saturating_mul(1024 * 1024))
+                .with_max_files(self.rpc_record_max_files)
+        })
+    }
+
     fn transport_rpc_module_config(&self) -> TransportRpcModuleConfig {
         let mut config = TransportRpcModuleConfig::default()
             .with_config(RpcModuleConfig::new(self.eth_config()));
@@ -150,6 +192,7 @@ impl RethRpcServerConfig for RpcServerArgs {
             .max_request_body_size(self.rpc_max_request_size_bytes())
             .max_response_body_size(self.rpc_max_response_size_bytes())
             .max_subscriptions_per_connection(self.rpc_max_subscriptions_per_connection.get())
+            .set_batch_request_config(self.rpc_batch_request_config())
     }
 
     fn ipc_server_builder(&self) -> IpcServerBuilder<Identity, Identity> {
@@ -158,10 +201,14 @@ impl RethRpcServerConfig for RpcServerArgs {
             .max_request_body_size(self.rpc_max_request_size_bytes())
             .max_response_body_size(self.rpc_max_response_size_bytes())
             .max_connections(self.rpc_max_connections.get())
+            .set_batch_request_config(self.rpc_batch_request_config())
     }
 
     fn rpc_server_config(&self) -> RpcServerConfig {
-        let mut config = RpcServerConfig::default().with_jwt_secret(self.rpc_secret_key());
+        let mut config = RpcServerConfig::default()
+            .with_jwt_secret(self.rpc_secret_key())
+            .with_max_connections(self.rpc_max_connections.get())
+            .with_rate_limit(self.rate_limit_config());
 
         if self.http {
             let socket_address = SocketAddr::new(self.http_addr, self.http_port);
@@ -189,10 +236,10 @@ impl RethRpcServerConfig for RpcServerArgs {
         let address = SocketAddr::new(self.auth_addr, self.auth_port);
 
         let mut builder = AuthServerConfig::builder(jwt_secret).socket_addr(address);
-        if self.auth_ipc {
-            builder = builder
-                .ipc_endpoint(self.auth_ipc_path.clone())
-                .with_ipc_config(self.ipc_server_builder());
+        if let Some(endpoint) = self.auth_ipc_endpoint() {
+            builder = builder.ipc_endpoint(endpoint).with_ipc_config(
+                self.ipc_server_builder().socket_permissions(self.auth_ipc_permissions),
+            );
         }
         Ok(builder.build())
     }
@@ -4,13 +4,15 @@ use reth_rpc::{
         gas_oracle::GasPriceOracleConfig,
         EthFilterConfig, FeeHistoryCacheConfig, RPC_DEFAULT_GAS_CAP,
     },
-    EthApi, EthFilter, EthPubSub,
+    EthApi, EthFilter, EthPubSub, TraceFilterConfig,
 };
 use reth_rpc_server_types::constants::{
     default_max_tracing_requests, DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE,
+    DEFAULT_MAX_TRACE_FILTER_BLOCKS, DEFAULT_TRACE_CACHE_MAX_BLOCKS,
 };
 use reth_tasks::pool::BlockingTaskPool;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// All handlers for the `eth` namespace
 #[derive(Debug, Clone)]
@@ -40,10 +42,20 @@ pub struct EthConfig {
     pub max_blocks_per_filter: u64,
     /// Maximum number of logs that can be returned in a single response in `eth_getLogs` calls.
     pub max_logs_per_response: usize,
+    /// Maximum number of blocks that could be scanned for matching transactions in
+    /// `trace_filter` calls.
+    pub max_trace_filter_blocks: u64,
+    /// Directory to cache the traces of finalized blocks in for `trace_filter` calls.
+    pub trace_cache_dir: Option<PathBuf>,
+    /// Maximum number of blocks whose traces are kept in the trace cache.
+    pub trace_cache_max_blocks: usize,
     /// Gas limit for `eth_call` and call tracing RPC methods.
     ///
     /// Defaults to [`RPC_DEFAULT_GAS_CAP`]
     pub rpc_gas_cap: u64,
+    /// Maximum size in bytes of the streamed responses of heavy methods like `debug_traceBlock`
+    /// and `txpool_content`, these abort as soon as the limit is exceeded.
+    pub max_response_size: usize,
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: std::time::Duration,
@@ -59,6 +71,14 @@ impl EthConfig {
             .max_logs_per_response(self.max_logs_per_response)
             .stale_filter_ttl(self.stale_filter_ttl)
     }
+
+    /// Returns the config for the `trace_filter` handler.
+    pub fn trace_filter_config(&self) -> TraceFilterConfig {
+        TraceFilterConfig::default()
+            .max_blocks_per_filter(self.max_trace_filter_blocks)
+            .cache_dir(self.trace_cache_dir.clone())
+            .cache_max_blocks(self.trace_cache_max_blocks)
+    }
 }
 
 /// Default value for stale filter ttl
@@ -72,7 +92,11 @@ impl Default for EthConfig {
             max_tracing_requests: default_max_tracing_requests(),
             max_blocks_per_filter: DEFAULT_MAX_BLOCKS_PER_FILTER,
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
+            max_trace_filter_blocks: DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+            trace_cache_dir: None,
+            trace_cache_max_blocks: DEFAULT_TRACE_CACHE_MAX_BLOCKS,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            max_response_size: usize::MAX,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
         }
@@ -110,9 +134,33 @@ impl EthConfig {
         self
     }
 
+    /// Configures the maximum block length to scan per `trace_filter` request
+    pub const fn max_trace_filter_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_trace_filter_blocks = max_blocks;
+        self
+    }
+
+    /// Configures the directory to cache the traces of finalized blocks in
+    pub fn trace_cache_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.trace_cache_dir = dir;
+        self
+    }
+
+    /// Configures the maximum number of blocks whose traces are kept in the trace cache
+    pub const fn trace_cache_max_blocks(mut self, max_blocks: usize) -> Self {
+        self.trace_cache_max_blocks = max_blocks;
+        self
+    }
+
     /// Configures the maximum gas limit for `eth_call` and call tracing RPC methods
     pub const fn rpc_gas_cap(mut self, rpc_gas_cap: u64) -> Self {
         self.rpc_gas_cap = rpc_gas_cap;
         self
     }
+
+    /// Configures the maximum size of streamed responses in bytes
+    pub const fn max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
 }
@@ -20,8 +20,9 @@
 //! use reth_evm::ConfigureEvm;
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{
-//!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     AccountReader, AddressAppearancesReader, BlockReaderIdExt, CanonStateSubscriptions,
+//!     ChainSpecProvider, ChangeSetReader, EvmEnvProvider, ForkChoiceSubscriptions,
+//!     LogIndexReader, StateProviderFactory,
 //! };
 //! use reth_rpc_builder::{
 //!     RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
@@ -39,6 +40,8 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + AddressAppearancesReader
+//!         + LogIndexReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
 //!         + Clone
@@ -46,7 +49,7 @@
 //!         + 'static,
 //!     Pool: TransactionPool + Clone + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
-//!     Events: CanonStateSubscriptions + Clone + 'static,
+//!     Events: CanonStateSubscriptions + ForkChoiceSubscriptions + Clone + 'static,
 //!     EvmConfig: ConfigureEvm + 'static,
 //! {
 //!     // configure the rpc module per transport
@@ -81,8 +84,9 @@
 //! use reth_evm::ConfigureEvm;
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{
-//!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     AccountReader, AddressAppearancesReader, BlockReaderIdExt, CanonStateSubscriptions,
+//!     ChainSpecProvider, ChangeSetReader, EvmEnvProvider, ForkChoiceSubscriptions,
+//!     LogIndexReader, StateProviderFactory,
 //! };
 //! use reth_rpc_api::EngineApiServer;
 //! use reth_rpc_builder::{
@@ -105,6 +109,8 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + AddressAppearancesReader
+//!         + LogIndexReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
 //!         + Clone
@@ -112,7 +118,7 @@
 //!         + 'static,
 //!     Pool: TransactionPool + Clone + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
-//!     Events: CanonStateSubscriptions + Clone + 'static,
+//!     Events: CanonStateSubscriptions + ForkChoiceSubscriptions + Clone + 'static,
 //!     EngineApi: EngineApiServer<EngineT>,
 //!     EngineT: EngineTypes + 'static,
 //!     EvmConfig: ConfigureEvm + 'static,
@@ -161,32 +167,41 @@ use crate::{
 };
 use error::{ConflictingModules, RpcError, ServerKind};
 use http::{header::AUTHORIZATION, HeaderMap};
+use hyper::body::Incoming;
 use jsonrpsee::{
     core::RegisterMethodError,
-    server::{AlreadyStoppedError, IdProvider, RpcServiceBuilder, Server, ServerHandle},
+    server::{
+        serve_with_graceful_shutdown, stop_channel, AlreadyStoppedError, ConnectionGuard,
+        IdProvider, RpcServiceBuilder, Server, ServerHandle, TowerServiceBuilder,
+    },
     Methods, RpcModule,
 };
 use reth_engine_primitives::EngineTypes;
 use reth_evm::ConfigureEvm;
 use reth_ipc::server::IpcServer;
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
+use reth_payload_builder::bundle_pool::{maintain_bundle_pool, BundlePool};
 use reth_provider::{
-    AccountReader, BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-    ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+    AccountReader, AddressAppearancesReader, BlockReader, BlockReaderIdExt,
+    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
+    ForkChoiceSubscriptions, LogIndexReader, StateProviderFactory,
 };
 use reth_rpc::{
     eth::{
         cache::{cache_new_blocks_task, EthStateCache},
         fee_history_cache_new_blocks_task,
         gas_oracle::GasPriceOracle,
-        traits::RawTransactionForwarder,
+        traits::{PendingBlockSource, RawTransactionForwarder},
         EthBundle, FeeHistoryCache,
     },
     AdminApi, DebugApi, EngineEthApi, EthApi, EthFilter, EthPubSub, EthSubscriptionIdProvider,
-    NetApi, OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, Web3Api,
+    MevApi, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::servers::*;
-use reth_rpc_layer::{AuthLayer, Claims, JwtAuthValidator, JwtSecret};
+use reth_rpc_layer::{
+    AuthLayer, Claims, JwtAuthValidator, JwtSecret, PeerAddr, RateLimitClientLayer,
+    RateLimitConfig, RateLimitLayer, RateLimiter, RpcRecorder,
+};
 use reth_tasks::{
     pool::{BlockingTaskGuard, BlockingTaskPool},
     TaskSpawner, TokioTaskExecutor,
@@ -200,8 +215,10 @@ use std::{
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
+use tokio::net::TcpListener;
+use tower::Service;
 use tower_http::cors::CorsLayer;
-use tracing::{instrument, trace};
+use tracing::{debug, instrument, trace};
 
 // re-export for convenience
 pub use jsonrpsee::server::ServerBuilder;
@@ -249,13 +266,15 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + AddressAppearancesReader
+        + LogIndexReader
         + Clone
         + Unpin
         + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
-    Events: CanonStateSubscriptions + Clone + 'static,
+    Events: CanonStateSubscriptions + ForkChoiceSubscriptions + Clone + 'static,
     EvmConfig: ConfigureEvm + 'static,
 {
     let module_config = module_config.into();
@@ -440,13 +459,15 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + AddressAppearancesReader
+        + LogIndexReader
         + Clone
         + Unpin
         + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
-    Events: CanonStateSubscriptions + Clone + 'static,
+    Events: CanonStateSubscriptions + ForkChoiceSubscriptions + Clone + 'static,
     EvmConfig: ConfigureEvm + 'static,
 {
     /// Configures all [`RpcModule`]s specific to the given [`TransportRpcModuleConfig`] which can
@@ -645,6 +666,10 @@ pub struct RethModuleRegistry<Provider, Pool, Network, Tasks, Events, EvmConfig>
     /// Optional forwarder for `eth_sendRawTransaction`
     // TODO(mattsse): find a more ergonomic way to configure eth/rpc customizations
     eth_raw_transaction_forwarder: Option<Arc<dyn RawTransactionForwarder>>,
+    /// Optional source for the `pending` block
+    eth_pending_block_source: Option<Arc<dyn PendingBlockSource>>,
+    /// The pool `mev_sendBundle` adds bundles to
+    bundle_pool: BundlePool,
 }
 
 // === impl RethModuleRegistry ===
@@ -674,9 +699,23 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
             config,
             events,
             eth_raw_transaction_forwarder: None,
+            eth_pending_block_source: None,
+            bundle_pool: BundlePool::default(),
         }
     }
 
+    /// Sets the [`BundlePool`] that bundles submitted via `mev_sendBundle` are added to.
+    ///
+    /// This allows a payload builder to include the bundles in its payloads.
+    pub fn set_bundle_pool(&mut self, bundle_pool: BundlePool) {
+        self.bundle_pool = bundle_pool;
+    }
+
+    /// Returns a reference to the bundle pool
+    pub const fn bundle_pool(&self) -> &BundlePool {
+        &self.bundle_pool
+    }
+
     /// Sets a forwarder for `eth_sendRawTransaction`
     ///
     /// Note: this might be removed in the future in favor of a more generic approach.
@@ -691,6 +730,16 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
         self.eth_raw_transaction_forwarder = Some(forwarder);
     }
 
+    /// Sets a source for the `pending` block of the `eth` namespace, for example the node's
+    /// payload builder.
+    pub fn set_eth_pending_block_source(&mut self, source: Arc<dyn PendingBlockSource>) {
+        if let Some(eth) = self.eth.as_ref() {
+            // in case the eth api has been created before the source was set
+            eth.api.set_pending_block_source(source.clone());
+        }
+        self.eth_pending_block_source = Some(source);
+    }
+
     /// Returns a reference to the pool
     pub const fn pool(&self) -> &Pool {
         &self.pool
@@ -765,13 +814,15 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + AddressAppearancesReader
+        + LogIndexReader
         + Clone
         + Unpin
         + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
-    Events: CanonStateSubscriptions + Clone + 'static,
+    Events: CanonStateSubscriptions + ForkChoiceSubscriptions + Clone + 'static,
     EvmConfig: ConfigureEvm + 'static,
 {
     /// Register Eth Namespace
@@ -935,16 +986,19 @@ where
                                 .into_rpc()
                                 .into()
                         }
-                        RethRpcModule::Debug => DebugApi::new(
+                        RethRpcModule::Debug => DebugApi::with_max_response_size(
                             self.provider.clone(),
                             eth_api.clone(),
                             self.blocking_pool_guard.clone(),
+                            self.config.eth.max_response_size,
                         )
-                        .into_rpc()
+                        .into_streamed_rpc()
                         .into(),
                         RethRpcModule::Eth => {
                             // merge all eth handlers
-                            let mut module = eth_api.clone().into_rpc();
+                            let mut module = eth_api
+                                .clone()
+                                .into_streamed_rpc(self.config.eth.max_response_size);
                             module.merge(eth_filter.clone().into_rpc()).expect("No conflicts");
                             module.merge(eth_pubsub.clone().into_rpc()).expect("No conflicts");
 
@@ -953,17 +1007,21 @@ where
                         RethRpcModule::Net => {
                             NetApi::new(self.network.clone(), eth_api.clone()).into_rpc().into()
                         }
-                        RethRpcModule::Trace => TraceApi::new(
+                        RethRpcModule::Trace => TraceApi::with_filter_config(
                             self.provider.clone(),
                             eth_api.clone(),
                             self.blocking_pool_guard.clone(),
+                            self.config.eth.trace_filter_config(),
                         )
                         .into_rpc()
                         .into(),
                         RethRpcModule::Web3 => Web3Api::new(self.network.clone()).into_rpc().into(),
-                        RethRpcModule::Txpool => {
-                            TxPoolApi::new(self.pool.clone()).into_rpc().into()
-                        }
+                        RethRpcModule::Txpool => TxPoolApi::with_max_response_size(
+                            self.pool.clone(),
+                            self.config.eth.max_response_size,
+                        )
+                        .into_streamed_rpc()
+                        .into(),
                         RethRpcModule::Rpc => RPCApi::new(
                             namespaces
                                 .iter()
@@ -972,7 +1030,11 @@ where
                         )
                         .into_rpc()
                         .into(),
-                        RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
+                        RethRpcModule::Ots => {
+                            OtterscanApi::new(self.provider.clone(), eth_api.clone())
+                                .into_rpc()
+                                .into()
+                        }
                         RethRpcModule::Reth => {
                             RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
                                 .into_rpc()
@@ -983,6 +1045,33 @@ where
                                 .into_rpc()
                                 .into()
                         }
+                        // the dev namespaces need the auto seal miner and are installed by the
+                        // node in `--dev` mode, the flashbots namespace needs the consensus and
+                        // block executor of the node, see
+                        // `TransportRpcModules::merge_if_module_configured`
+                        RethRpcModule::Anvil |
+                        RethRpcModule::Hardhat |
+                        RethRpcModule::Flashbots => Methods::new(),
+                        RethRpcModule::Mev => {
+                            let new_canonical_blocks = self.events.canonical_state_stream();
+                            let bundle_pool = self.bundle_pool.clone();
+                            self.executor.spawn_critical(
+                                "bundle pool maintenance task",
+                                Box::pin(async move {
+                                    maintain_bundle_pool(bundle_pool, new_canonical_blocks).await;
+                                }),
+                            );
+
+                            MevApi::new(
+                                eth_api.clone(),
+                                self.pool.clone(),
+                                self.bundle_pool.clone(),
+                                self.provider.chain_spec(),
+                                self.blocking_pool_guard.clone(),
+                            )
+                            .into_rpc()
+                            .into()
+                        }
                     })
                     .clone()
             })
@@ -1061,6 +1150,9 @@ where
             self.evm_config.clone(),
             self.eth_raw_transaction_forwarder.clone(),
         );
+        if let Some(source) = self.eth_pending_block_source.clone() {
+            api.set_pending_block_source(source);
+        }
         let filter = EthFilter::new(
             self.provider.clone(),
             self.pool.clone(),
@@ -1107,7 +1199,12 @@ where
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
     pub fn trace_api(&mut self) -> TraceApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>> {
         let eth = self.eth_handlers();
-        TraceApi::new(self.provider.clone(), eth.api, self.blocking_pool_guard.clone())
+        TraceApi::with_filter_config(
+            self.provider.clone(),
+            eth.api,
+            self.blocking_pool_guard.clone(),
+            self.config.eth.trace_filter_config(),
+        )
     }
 
     /// Instantiates [`EthBundle`] Api
@@ -1125,9 +1222,11 @@ where
     /// # Panics
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
-    pub fn otterscan_api(&mut self) -> OtterscanApi<EthApi<Provider, Pool, Network, EvmConfig>> {
+    pub fn otterscan_api(
+        &mut self,
+    ) -> OtterscanApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>> {
         let eth_api = self.eth_api();
-        OtterscanApi::new(eth_api)
+        OtterscanApi::new(self.provider.clone(), eth_api)
     }
 
     /// Instantiates `DebugApi`
@@ -1137,7 +1236,12 @@ where
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
     pub fn debug_api(&mut self) -> DebugApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>> {
         let eth_api = self.eth_api();
-        DebugApi::new(self.provider.clone(), eth_api, self.blocking_pool_guard.clone())
+        DebugApi::with_max_response_size(
+            self.provider.clone(),
+            eth_api,
+            self.blocking_pool_guard.clone(),
+            self.config.eth.max_response_size,
+        )
     }
 
     /// Instantiates `NetApi`
@@ -1187,6 +1291,13 @@ pub struct RpcServerConfig {
     ipc_endpoint: Option<String>,
     /// JWT secret for authentication
     jwt_secret: Option<JwtSecret>,
+    /// Maximum number of concurrent connections of the http and ws servers, overriding the
+    /// limit of their [`ServerBuilder`]s
+    max_connections: Option<u32>,
+    /// Rate limiter shared by the http and ws servers
+    rate_limiter: Option<RateLimiter>,
+    /// Recorder of the calls to the http and ws servers
+    recorder: Option<RpcRecorder>,
 }
 
 // === impl RpcServerConfig ===
@@ -1304,6 +1415,33 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures the maximum number of concurrent connections of the http and ws servers.
+    ///
+    /// This overrides the limit configured on their [`ServerBuilder`]s. Defaults to
+    /// [`DEFAULT_MAX_CONNECTIONS`](constants::DEFAULT_MAX_CONNECTIONS) if the servers are rate
+    /// limited.
+    pub const fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = Some(max_connections);
+        self
+    }
+
+    /// Configures rate limiting of the http, ws and ipc servers.
+    ///
+    /// Clients of the http and ws servers are identified by their IP, while all ipc clients share
+    /// a single quota.
+    pub fn with_rate_limit(mut self, config: Option<RateLimitConfig>) -> Self {
+        self.rate_limiter = config.map(RateLimiter::new);
+        self
+    }
+
+    /// Configures the [`RpcRecorder`] that records all calls to the http and ws servers.
+    ///
+    /// Note: calls to the ipc server are not recorded.
+    pub fn with_recorder(mut self, recorder: Option<RpcRecorder>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
     /// Returns true if any server is configured.
     ///
     /// If no server is configured, no server will be launched on [`RpcServerConfig::start`].
@@ -1343,6 +1481,19 @@ impl RpcServerConfig {
         self.jwt_secret.map(|secret| AuthLayer::new(JwtAuthValidator::new(secret)))
     }
 
+    /// Creates the [`RateLimitClientLayer`] if rate limiting is configured
+    fn maybe_rate_limit_client_layer(&self) -> Option<RateLimitClientLayer> {
+        self.rate_limiter.as_ref().map(RateLimiter::client_layer)
+    }
+
+    /// Creates the [`RateLimitLayer`] for the methods of the module if rate limiting is
+    /// configured
+    fn maybe_rate_limit_layer(&self, module: Option<&RpcModule<()>>) -> Option<RateLimitLayer> {
+        self.rate_limiter
+            .as_ref()
+            .map(|limiter| limiter.rpc_layer(module.into_iter().flat_map(RpcModule::method_names)))
+    }
+
     /// Builds the ws and http server(s).
     ///
     /// If both are on the same port, they are combined into one server.
@@ -1386,28 +1537,39 @@ impl RpcServerConfig {
             modules.config.ensure_ws_http_identical()?;
 
             let builder = self.http_server_config.take().expect("http_server_config is Some");
-            let server = builder
-                .set_http_middleware(
-                    tower::ServiceBuilder::new()
-                        .option_layer(Self::maybe_cors_layer(cors)?)
-                        .option_layer(self.maybe_jwt_layer()),
-                )
-                .set_rpc_middleware(
-                    RpcServiceBuilder::new().layer(
-                        modules
-                            .http
-                            .as_ref()
-                            .or(modules.ws.as_ref())
-                            .map(RpcRequestMetrics::same_port)
-                            .unwrap_or_default(),
-                    ),
-                )
-                .build(http_socket_addr)
-                .await
-                .map_err(|err| RpcError::server_error(err, ServerKind::WsHttp(http_socket_addr)))?;
-            let addr = server
-                .local_addr()
-                .map_err(|err| RpcError::server_error(err, ServerKind::WsHttp(http_socket_addr)))?;
+            let builder =
+                builder
+                    .set_http_middleware(
+                        tower::ServiceBuilder::new()
+                            .option_layer(Self::maybe_cors_layer(cors)?)
+                            .option_layer(self.maybe_jwt_layer())
+                            .option_layer(self.maybe_rate_limit_client_layer()),
+                    )
+                    .set_rpc_middleware(
+                        RpcServiceBuilder::new()
+                            .layer(
+                                modules
+                                    .http
+                                    .as_ref()
+                                    .or(modules.ws.as_ref())
+                                    .map(RpcRequestMetrics::same_port)
+                                    .unwrap_or_default(),
+                            )
+                            .option_layer(self.recorder.clone())
+                            .option_layer(self.maybe_rate_limit_layer(
+                                modules.http.as_ref().or(modules.ws.as_ref()),
+                            )),
+                    );
+            let kind = ServerKind::WsHttp(http_socket_addr);
+            let server = WsHttpServerKind::bind(
+                builder,
+                http_socket_addr,
+                kind,
+                self.max_connections,
+                self.rate_limiter.is_some(),
+            )
+            .await?;
+            let addr = server.local_addr(kind)?;
             return Ok(WsHttpServer {
                 http_local_addr: Some(addr),
                 ws_local_addr: Some(addr),
@@ -1422,48 +1584,61 @@ impl RpcServerConfig {
         let mut ws_local_addr = None;
         let mut ws_server = None;
         if let Some(builder) = self.ws_server_config.take() {
-            let server = builder
+            let builder = builder
                 .ws_only()
                 .set_http_middleware(
                     tower::ServiceBuilder::new()
                         .option_layer(Self::maybe_cors_layer(self.ws_cors_domains.clone())?)
-                        .option_layer(self.maybe_jwt_layer()),
+                        .option_layer(self.maybe_jwt_layer())
+                        .option_layer(self.maybe_rate_limit_client_layer()),
                 )
                 .set_rpc_middleware(
                     RpcServiceBuilder::new()
-                        .layer(modules.ws.as_ref().map(RpcRequestMetrics::ws).unwrap_or_default()),
-                )
-                .build(ws_socket_addr)
-                .await
-                .map_err(|err| RpcError::server_error(err, ServerKind::WS(ws_socket_addr)))?;
-            let addr = server
-                .local_addr()
-                .map_err(|err| RpcError::server_error(err, ServerKind::WS(ws_socket_addr)))?;
-
-            ws_local_addr = Some(addr);
+                        .layer(modules.ws.as_ref().map(RpcRequestMetrics::ws).unwrap_or_default())
+                        .option_layer(self.recorder.clone())
+                        .option_layer(self.maybe_rate_limit_layer(modules.ws.as_ref())),
+                );
+            let kind = ServerKind::WS(ws_socket_addr);
+            let server = WsHttpServerKind::bind(
+                builder,
+                ws_socket_addr,
+                kind,
+                self.max_connections,
+                self.rate_limiter.is_some(),
+            )
+            .await?;
+
+            ws_local_addr = Some(server.local_addr(kind)?);
             ws_server = Some(server);
         }
 
         if let Some(builder) = self.http_server_config.take() {
-            let server = builder
+            let builder = builder
                 .http_only()
                 .set_http_middleware(
                     tower::ServiceBuilder::new()
                         .option_layer(Self::maybe_cors_layer(self.http_cors_domains.clone())?)
-                        .option_layer(self.maybe_jwt_layer()),
+                        .option_layer(self.maybe_jwt_layer())
+                        .option_layer(self.maybe_rate_limit_client_layer()),
                 )
                 .set_rpc_middleware(
-                    RpcServiceBuilder::new().layer(
-                        modules.http.as_ref().map(RpcRequestMetrics::http).unwrap_or_default(),
-                    ),
-                )
-                .build(http_socket_addr)
-                .await
-                .map_err(|err| RpcError::server_error(err, ServerKind::Http(http_socket_addr)))?;
-            let local_addr = server
-                .local_addr()
-                .map_err(|err| RpcError::server_error(err, ServerKind::Http(http_socket_addr)))?;
-            http_local_addr = Some(local_addr);
+                    RpcServiceBuilder::new()
+                        .layer(
+                            modules.http.as_ref().map(RpcRequestMetrics::http).unwrap_or_default(),
+                        )
+                        .option_layer(self.recorder.clone())
+                        .option_layer(self.maybe_rate_limit_layer(modules.http.as_ref())),
+                );
+            let kind = ServerKind::Http(http_socket_addr);
+            let server = WsHttpServerKind::bind(
+                builder,
+                http_socket_addr,
+                kind,
+                self.max_connections,
+                self.rate_limiter.is_some(),
+            )
+            .await?;
+            http_local_addr = Some(server.local_addr(kind)?);
             http_server = Some(server);
         }
 
@@ -1485,12 +1660,15 @@ impl RpcServerConfig {
         let mut server = RpcServer::empty();
         server.ws_http = self.build_ws_http(modules).await?;
 
+        let ipc_rate_limit = self.maybe_rate_limit_layer(modules.ipc.as_ref());
         if let Some(builder) = self.ipc_server_config {
             let metrics = modules.ipc.as_ref().map(RpcRequestMetrics::ipc).unwrap_or_default();
             let ipc_path =
                 self.ipc_endpoint.unwrap_or_else(|| constants::DEFAULT_IPC_ENDPOINT.into());
             let ipc = builder
-                .set_rpc_middleware(IpcRpcServiceBuilder::new().layer(metrics))
+                .set_rpc_middleware(
+                    IpcRpcServiceBuilder::new().layer(metrics).option_layer(ipc_rate_limit),
+                )
                 .build(ipc_path);
             server.ipc = Some(ipc);
         }
@@ -1690,6 +1868,31 @@ impl TransportRpcModules {
         Ok(false)
     }
 
+    /// Merge the given [Methods] in all transports that have the given [`RethRpcModule`]
+    /// configured.
+    ///
+    /// Fails if any of the methods in other is present already.
+    ///
+    /// Returns [Ok(false)] if the module is not configured for any transport.
+    pub fn merge_if_module_configured(
+        &mut self,
+        module: RethRpcModule,
+        other: impl Into<Methods>,
+    ) -> Result<bool, RegisterMethodError> {
+        let other = other.into();
+        let mut merged = false;
+        if self.config.http.as_ref().is_some_and(|http| http.contains(&module)) {
+            merged |= self.merge_http(other.clone())?;
+        }
+        if self.config.ws.as_ref().is_some_and(|ws| ws.contains(&module)) {
+            merged |= self.merge_ws(other.clone())?;
+        }
+        if self.config.ipc.as_ref().is_some_and(|ipc| ipc.contains(&module)) {
+            merged |= self.merge_ipc(other)?;
+        }
+        Ok(merged)
+    }
+
     /// Merge the given [Methods] in all configured methods.
     ///
     /// Fails if any of the methods in other is present already.
@@ -1723,15 +1926,150 @@ struct WsHttpServer {
     jwt_secret: Option<JwtSecret>,
 }
 
-// Define the type alias with detailed type complexity
-type WsHttpServerKind = Server<
+/// The http middleware of the ws and http servers.
+type WsHttpMiddleware = Stack<
+    tower::util::Either<RateLimitClientLayer, Identity>,
     Stack<
         tower::util::Either<AuthLayer<JwtAuthValidator>, Identity>,
         Stack<tower::util::Either<CorsLayer, Identity>, Identity>,
     >,
-    Stack<RpcRequestMetrics, Identity>,
 >;
 
+/// The rpc middleware of the ws and http servers.
+type WsHttpRpcMiddleware = Stack<
+    tower::util::Either<RateLimitLayer, Identity>,
+    Stack<tower::util::Either<RpcRecorder, Identity>, Stack<RpcRequestMetrics, Identity>>,
+>;
+
+/// A ws and/or http server, bound to its address.
+enum WsHttpServerKind {
+    /// The jsonrpsee server, used if requests are not rate limited.
+    Server(Server<WsHttpMiddleware, WsHttpRpcMiddleware>),
+    /// A server that passes the address of the TCP peer to the http middleware as [`PeerAddr`],
+    /// used to rate limit requests by IP.
+    PeerAware(PeerAwareServer),
+}
+
+impl WsHttpServerKind {
+    /// Binds the server to the address. The [`PeerAwareServer`] is used if `peer_aware` is set.
+    async fn bind(
+        mut builder: ServerBuilder<WsHttpMiddleware, WsHttpRpcMiddleware>,
+        addr: SocketAddr,
+        kind: ServerKind,
+        max_connections: Option<u32>,
+        peer_aware: bool,
+    ) -> Result<Self, RpcError> {
+        if let Some(max_connections) = max_connections {
+            builder = builder.max_connections(max_connections);
+        }
+        if peer_aware {
+            let max_connections = max_connections.unwrap_or(constants::DEFAULT_MAX_CONNECTIONS);
+            return Ok(Self::PeerAware(
+                PeerAwareServer::bind(builder, addr, kind, max_connections).await?,
+            ))
+        }
+
+        let server = builder.build(addr).await.map_err(|err| RpcError::server_error(err, kind))?;
+        Ok(Self::Server(server))
+    }
+
+    /// Returns the address the server is bound to.
+    fn local_addr(&self, kind: ServerKind) -> Result<SocketAddr, RpcError> {
+        match self {
+            Self::Server(server) => {
+                server.local_addr().map_err(|err| RpcError::server_error(err, kind))
+            }
+            Self::PeerAware(server) => Ok(server.local_addr),
+        }
+    }
+
+    /// Spawns the server, serving the methods of the module.
+    fn start(self, module: RpcModule<()>) -> ServerHandle {
+        match self {
+            Self::Server(server) => server.start(module),
+            Self::PeerAware(server) => server.start(module),
+        }
+    }
+}
+
+/// A ws and/or http server that accepts the connections itself instead of using [`Server`], so
+/// the address of the TCP peer can be passed to the http middleware as [`PeerAddr`].
+///
+/// Like [`Server`], connections exceeding the maximum number of connections are closed right
+/// away.
+struct PeerAwareServer {
+    listener: TcpListener,
+    local_addr: SocketAddr,
+    connection_guard: ConnectionGuard,
+    builder: TowerServiceBuilder<WsHttpRpcMiddleware, WsHttpMiddleware>,
+}
+
+impl PeerAwareServer {
+    /// Binds the server to the address.
+    async fn bind(
+        builder: ServerBuilder<WsHttpMiddleware, WsHttpRpcMiddleware>,
+        addr: SocketAddr,
+        kind: ServerKind,
+        max_connections: u32,
+    ) -> Result<Self, RpcError> {
+        let listener =
+            TcpListener::bind(addr).await.map_err(|err| RpcError::server_error(err, kind))?;
+        let local_addr = listener.local_addr().map_err(|err| RpcError::server_error(err, kind))?;
+        Ok(Self {
+            listener,
+            local_addr,
+            connection_guard: ConnectionGuard::new(max_connections as usize),
+            builder: builder.to_service_builder(),
+        })
+    }
+
+    /// Spawns the server, serving the methods of the module on every accepted connection.
+    fn start(self, module: RpcModule<()>) -> ServerHandle {
+        let Self { listener, connection_guard, builder, .. } = self;
+        let methods = Methods::from(module);
+        let (stop_handle, server_handle) = stop_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let (socket, peer_addr) = tokio::select! {
+                    res = listener.accept() => match res {
+                        Ok(conn) => conn,
+                        Err(err) => {
+                            debug!(target: "rpc", %err, "Failed to accept connection");
+                            continue
+                        }
+                    },
+                    _ = stop_handle.clone().shutdown() => break,
+                };
+
+                let Some(permit) = connection_guard.try_acquire() else {
+                    debug!(
+                        target: "rpc",
+                        %peer_addr,
+                        max_connections = connection_guard.max_connections(),
+                        "Too many connections, closing connection"
+                    );
+                    continue
+                };
+
+                let service = builder.clone().build(methods.clone(), stop_handle.clone());
+                let service = tower::service_fn(move |mut req: http::Request<Incoming>| {
+                    req.extensions_mut().insert(PeerAddr(peer_addr));
+                    let mut service = service.clone();
+                    async move { service.call(req).await }
+                });
+                let stopped = stop_handle.clone().shutdown();
+                tokio::spawn(async move {
+                    let _ = serve_with_graceful_shutdown(socket, service, stopped).await;
+                    drop(permit);
+                });
+            }
+        });
+
+        server_handle
+    }
+}
+
 /// Enum for holding the http and ws servers in all possible combinations.
 enum WsHttpServers {
     /// Both servers are on the same port
@@ -1793,7 +2131,15 @@ pub struct RpcServer {
     /// Configured ws,http servers
     ws_http: WsHttpServer,
     /// ipc server
-    ipc: Option<IpcServer<Identity, Stack<RpcRequestMetrics, Identity>>>,
+    ipc: Option<
+        IpcServer<
+            Identity,
+            Stack<
+                tower::util::Either<RateLimitLayer, Identity>,
+                Stack<RpcRequestMetrics, Identity>,
+            >,
+        >,
+    >,
 }
 
 // === impl RpcServer ===
@@ -2077,6 +2423,10 @@ mod tests {
                 "rpc" => RethRpcModule::Rpc,
                 "ots" => RethRpcModule::Ots,
                 "reth" => RethRpcModule::Reth,
+                "anvil" => RethRpcModule::Anvil,
+                "hardhat" => RethRpcModule::Hardhat,
+                "mev" => RethRpcModule::Mev,
+                "flashbots" => RethRpcModule::Flashbots,
             );
     }
 
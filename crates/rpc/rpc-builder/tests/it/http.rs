@@ -1,7 +1,7 @@
 #![allow(unreachable_pub)]
 //! Standalone http tests
 
-use crate::utils::{launch_http, launch_http_ws, launch_ws};
+use crate::utils::{launch_http, launch_http_ws, launch_ws, test_address, test_rpc_builder};
 use jsonrpsee::{
     core::{
         client::{ClientT, SubscriptionClientT},
@@ -9,17 +9,19 @@ use jsonrpsee::{
     },
     http_client::HttpClient,
     rpc_params,
-    types::error::ErrorCode,
+    server::ServerBuilder,
+    types::error::{ErrorCode, OVERSIZED_RESPONSE_CODE},
 };
 use reth_chainspec::net::NodeRecord;
 use reth_primitives::{
     hex_literal::hex, Address, BlockId, BlockNumberOrTag, Bytes, TxHash, B256, B64, U256, U64,
 };
 use reth_rpc_api::{
-    clients::{AdminApiClient, EthApiClient},
+    clients::{AdminApiClient, EthApiClient, TxPoolApiClient},
     DebugApiClient, EthFilterApiClient, NetApiClient, OtterscanClient, TraceApiClient,
     Web3ApiClient,
 };
+use reth_rpc_builder::{EthConfig, RpcModuleConfig, RpcServerConfig, TransportRpcModuleConfig};
 use reth_rpc_server_types::RethRpcModule;
 use reth_rpc_types::{
     trace::filter::TraceFilter, FeeHistory, Filter, Index, Log, PendingTransactionFilterKind,
@@ -238,6 +240,7 @@ where
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
     assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
+    assert!(DebugApiClient::debug_chaindb_compact(client).await.is_err());
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -309,9 +312,7 @@ where
 
     OtterscanClient::get_transaction_error(client, tx_hash).await.unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::trace_transaction(client, tx_hash).await.err().unwrap()
-    ));
+    OtterscanClient::trace_transaction(client, tx_hash).await.unwrap();
 
     OtterscanClient::get_block_details(client, block_number).await.unwrap();
 
@@ -322,27 +323,17 @@ where
         .err()
         .unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_before(client, address, block_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_after(client, address, block_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::get_transaction_by_sender_and_nonce(client, sender, nonce,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::get_contract_creator(client, address).await.err().unwrap()
-    ));
+    OtterscanClient::search_transactions_before(client, address, block_number, page_size)
+        .await
+        .unwrap();
+
+    OtterscanClient::search_transactions_after(client, address, block_number, page_size)
+        .await
+        .unwrap();
+
+    OtterscanClient::get_transaction_by_sender_and_nonce(client, sender, nonce).await.unwrap();
+
+    OtterscanClient::get_contract_creator(client, address).await.unwrap();
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -1442,3 +1433,51 @@ async fn test_eth_fee_history_raw() {
     )
     .await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_response_exceeding_max_response_size_is_rejected() {
+    reth_tracing::init_test_tracing();
+
+    let server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Txpool]));
+    let handle = server
+        .start_server(
+            RpcServerConfig::http(ServerBuilder::default().max_response_body_size(16))
+                .with_http_address(test_address()),
+        )
+        .await
+        .unwrap();
+    let client = handle.http_client().unwrap();
+
+    let err = TxPoolApiClient::txpool_content(&client).await.unwrap_err();
+    assert!(
+        matches!(err, jsonrpsee::core::client::Error::Call(err) if err.code() == OVERSIZED_RESPONSE_CODE)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_streamed_response_exceeding_max_response_size_is_rejected() {
+    reth_tracing::init_test_tracing();
+
+    // the streamed responses decode into the typed client signatures
+    let handle = launch_http(vec![RethRpcModule::Txpool]).await;
+    let client = handle.http_client().unwrap();
+    let content = TxPoolApiClient::txpool_content(&client).await.unwrap();
+    assert!(content.pending.is_empty() && content.queued.is_empty());
+
+    // the streamed response aborts at the configured limit, before the server's own limit
+    let server = test_rpc_builder().build(
+        TransportRpcModuleConfig::set_http(vec![RethRpcModule::Txpool])
+            .with_config(RpcModuleConfig::new(EthConfig::default().max_response_size(16))),
+    );
+    let handle = server
+        .start_server(RpcServerConfig::http(Default::default()).with_http_address(test_address()))
+        .await
+        .unwrap();
+    let client = handle.http_client().unwrap();
+
+    let err = TxPoolApiClient::txpool_content(&client).await.unwrap_err();
+    assert!(
+        matches!(err, jsonrpsee::core::client::Error::Call(err) if err.code() == OVERSIZED_RESPONSE_CODE)
+    );
+}
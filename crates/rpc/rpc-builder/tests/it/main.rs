@@ -1,5 +1,6 @@
 mod auth;
 mod http;
+mod rate_limit;
 mod serde;
 mod startup;
 pub mod utils;
@@ -0,0 +1,42 @@
+//! Rate limiting tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use http::{HeaderMap, HeaderValue};
+use jsonrpsee::{core::client::Error, http_client::HttpClientBuilder};
+use reth_rpc_api::clients::Web3ApiClient;
+use reth_rpc_builder::{RpcServerConfig, TransportRpcModuleConfig};
+use reth_rpc_layer::{RateLimitConfig, LIMIT_EXCEEDED_ERROR_CODE};
+use reth_rpc_server_types::RethRpcModule;
+
+fn is_limit_exceeded(err: Error) -> bool {
+    matches!(err, Error::Call(err) if err.code() == LIMIT_EXCEEDED_ERROR_CODE)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limit_by_peer_address() {
+    let server =
+        test_rpc_builder().build(TransportRpcModuleConfig::set_http(vec![RethRpcModule::Web3]));
+    let handle = server
+        .start_server(
+            RpcServerConfig::http(Default::default())
+                .with_http_address(test_address())
+                .with_rate_limit(Some(RateLimitConfig { ip_quota: Some(1), ..Default::default() })),
+        )
+        .await
+        .unwrap();
+
+    let client = handle.http_client().unwrap();
+    Web3ApiClient::client_version(&client).await.unwrap();
+    assert!(is_limit_exceeded(Web3ApiClient::client_version(&client).await.unwrap_err()));
+
+    // the peer isn't a trusted proxy, so it can't escape its quota with forwarded headers
+    let headers = HeaderMap::from_iter([(
+        http::header::HeaderName::from_static("x-forwarded-for"),
+        HeaderValue::from_static("10.0.0.1"),
+    )]);
+    let client = HttpClientBuilder::default()
+        .set_headers(headers)
+        .build(handle.http_url().unwrap())
+        .unwrap();
+    assert!(is_limit_exceeded(Web3ApiClient::client_version(&client).await.unwrap_err()));
+}
@@ -14,6 +14,8 @@ use jsonrpsee_http_client::HttpResponse;
 mod auth_client_layer;
 mod auth_layer;
 mod jwt_validator;
+mod rate_limit;
+mod recorder;
 
 // Export alloy JWT types
 pub use alloy_rpc_types_engine::{Claims, JwtError, JwtSecret};
@@ -21,6 +23,15 @@ pub use alloy_rpc_types_engine::{Claims, JwtError, JwtSecret};
 pub use auth_client_layer::{secret_to_bearer_header, AuthClientLayer, AuthClientService};
 pub use auth_layer::AuthLayer;
 pub use jwt_validator::JwtAuthValidator;
+pub use rate_limit::{
+    PeerAddr, RateLimitClient, RateLimitClientLayer, RateLimitClientService, RateLimitConfig,
+    RateLimitError, RateLimitFuture, RateLimitLayer, RateLimitPermit, RateLimitService,
+    RateLimiter, DEFAULT_API_KEY_HEADER, DEFAULT_METHOD_COSTS, LIMIT_EXCEEDED_ERROR_CODE,
+};
+pub use recorder::{
+    read_rpc_records, rpc_record_files, RecordedCallFuture, RpcRecord, RpcRecordConfig,
+    RpcRecorder, RpcRecorderService, DEFAULT_RECORD_MAX_FILES, DEFAULT_RECORD_MAX_FILE_SIZE,
+};
 
 /// General purpose trait to validate Http Authorization headers. It's supposed to be integrated as
 /// a validator trait into an [`AuthLayer`].
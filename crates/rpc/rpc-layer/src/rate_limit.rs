@@ -0,0 +1,638 @@
+use http::HeaderMap;
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObject, Request},
+    MethodResponse,
+};
+use parking_lot::Mutex;
+use pin_project::pin_project;
+use reth_metrics::{metrics::Counter, Metrics};
+use schnellru::{ByLength, LruMap};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tower::{Layer, Service};
+
+/// JSON-RPC error code of requests rejected by the [`RateLimiter`], the `Limit exceeded` code of
+/// [EIP-1474](https://eips.ethereum.org/EIPS/eip-1474).
+pub const LIMIT_EXCEEDED_ERROR_CODE: i32 = -32005;
+
+/// The default header carrying the API key of a request.
+pub const DEFAULT_API_KEY_HEADER: &str = "x-api-key";
+
+/// The default cost of expensive methods in credits. Methods that are not listed cost 1 credit.
+pub const DEFAULT_METHOD_COSTS: &[(&str, u32)] = &[
+    ("eth_call", 5),
+    ("eth_estimateGas", 5),
+    ("eth_createAccessList", 5),
+    ("eth_getLogs", 20),
+    ("eth_simulateV1", 20),
+    ("debug_traceCall", 50),
+    ("debug_traceTransaction", 50),
+    ("debug_traceBlock", 200),
+    ("debug_traceBlockByHash", 200),
+    ("debug_traceBlockByNumber", 200),
+    ("trace_call", 50),
+    ("trace_callMany", 100),
+    ("trace_transaction", 50),
+    ("trace_replayTransaction", 50),
+    ("trace_block", 200),
+    ("trace_replayBlockTransactions", 200),
+    ("trace_filter", 500),
+];
+
+/// Maximum number of clients whose quota is tracked. Once reached, the least recently active
+/// clients are forgotten.
+const MAX_TRACKED_CLIENTS: u32 = 10_000;
+
+/// Length of the prefix of the IPv6 addresses that share a quota. A single host is usually
+/// assigned a whole /64 subnet.
+const IPV6_CLIENT_PREFIX_LEN: u32 = 64;
+
+/// Configuration of the [`RateLimiter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Credits each client IP can spend per second, or `None` if they are not limited.
+    pub ip_quota: Option<u32>,
+    /// Reverse proxies whose `X-Forwarded-For` and `X-Real-IP` headers are trusted. The headers
+    /// of all other peers are ignored.
+    pub trusted_proxies: HashSet<IpAddr>,
+    /// The header carrying the API key of a request.
+    pub api_key_header: String,
+    /// Credits each API key can spend per second. Requests with unknown API keys are limited by
+    /// their IP.
+    pub api_key_quotas: HashMap<String, u32>,
+    /// Cost of each method in credits. Methods that are not listed cost 1 credit.
+    pub method_costs: HashMap<String, u32>,
+    /// Maximum number of requests of each namespace that are processed concurrently.
+    pub max_concurrent: HashMap<String, usize>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            ip_quota: None,
+            trusted_proxies: HashSet::new(),
+            api_key_header: DEFAULT_API_KEY_HEADER.to_string(),
+            api_key_quotas: HashMap::new(),
+            method_costs: DEFAULT_METHOD_COSTS
+                .iter()
+                .map(|(method, cost)| (method.to_string(), *cost))
+                .collect(),
+            max_concurrent: HashMap::new(),
+        }
+    }
+}
+
+/// Errors returned for requests that are rejected by the [`RateLimiter`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RateLimitError {
+    /// The client doesn't have enough credits left for the method.
+    #[error("rate limit exceeded: method costs {cost} credits, {available} available")]
+    QuotaExceeded {
+        /// Cost of the method.
+        cost: u32,
+        /// Credits the client has left.
+        available: u32,
+    },
+    /// Too many requests of the namespace are already being processed.
+    #[error("too many concurrent {namespace} requests")]
+    ConcurrencyLimited {
+        /// Namespace of the method.
+        namespace: String,
+    },
+}
+
+impl From<RateLimitError> for ErrorObject<'static> {
+    fn from(err: RateLimitError) -> Self {
+        ErrorObject::owned(LIMIT_EXCEEDED_ERROR_CODE, err.to_string(), None::<()>)
+    }
+}
+
+/// The address of the TCP peer a request was received from.
+///
+/// The server inserts it into the extensions of every HTTP request, for the
+/// [`RateLimitClientLayer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeerAddr(pub SocketAddr);
+
+/// The client a request is accounted to.
+///
+/// This is determined from the [`PeerAddr`] and the HTTP headers of the request by the
+/// [`RateLimitClientLayer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitClient {
+    /// A request with a known API key.
+    ApiKey(String),
+    /// A request from the IP of the TCP peer, or the IP reported by a trusted reverse proxy in the
+    /// `X-Forwarded-For` or `X-Real-IP` header.
+    Ip(IpAddr),
+    /// A request of unknown origin, like the requests over IPC. These requests share a single
+    /// quota.
+    Unknown,
+}
+
+/// Limits the requests of each client to its quota of credits per second, and the number of
+/// concurrently processed requests of expensive namespaces.
+///
+/// The client of a request is determined by the HTTP middleware created with
+/// [`RateLimiter::client_layer`], while the limits are enforced by the RPC middleware created with
+/// [`RateLimiter::rpc_layer`]. Requests that exceed a limit are rejected with the
+/// [`LIMIT_EXCEEDED_ERROR_CODE`].
+///
+/// Quotas are replenished continuously, so a client can spend at most its quota in a burst.
+/// Calls that cost more than the quota are accepted once the quota is fully replenished, leaving
+/// the client in debt.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    inner: Arc<RateLimiterInner>,
+}
+
+#[derive(Debug)]
+struct RateLimiterInner {
+    config: RateLimitConfig,
+    /// Remaining credits of each client, IPv6 clients are grouped by their prefix
+    buckets: Mutex<LruMap<RateLimitClient, TokenBucket, ByLength>>,
+    /// Concurrency limits of each namespace
+    semaphores: HashMap<String, Arc<Semaphore>>,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter with the given config.
+    pub fn new(config: RateLimitConfig) -> Self {
+        let semaphores = config
+            .max_concurrent
+            .iter()
+            .map(|(namespace, limit)| (namespace.clone(), Arc::new(Semaphore::new(*limit))))
+            .collect();
+        Self {
+            inner: Arc::new(RateLimiterInner {
+                config,
+                buckets: Mutex::new(LruMap::new(ByLength::new(MAX_TRACKED_CLIENTS))),
+                semaphores,
+            }),
+        }
+    }
+
+    /// Returns the config of the rate limiter.
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.inner.config
+    }
+
+    /// Returns the HTTP middleware that determines the [`RateLimitClient`] of each request.
+    pub fn client_layer(&self) -> RateLimitClientLayer {
+        RateLimitClientLayer { limiter: self.clone() }
+    }
+
+    /// Returns the RPC middleware that enforces the limits. Metrics are recorded for the given
+    /// methods.
+    pub fn rpc_layer(&self, methods: impl IntoIterator<Item = &'static str>) -> RateLimitLayer {
+        RateLimitLayer {
+            limiter: self.clone(),
+            metrics: Arc::new(
+                methods
+                    .into_iter()
+                    .map(|method| {
+                        (method, RateLimitMetrics::new_with_labels(&[("method", method)]))
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the client of a request with the given headers, received from the given peer.
+    pub fn client(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> RateLimitClient {
+        let api_key = headers
+            .get(self.inner.config.api_key_header.as_str())
+            .and_then(|value| value.to_str().ok());
+        if let Some(api_key) = api_key {
+            if self.inner.config.api_key_quotas.contains_key(api_key) {
+                return RateLimitClient::ApiKey(api_key.to_string())
+            }
+        }
+
+        let Some(peer) = peer else { return RateLimitClient::Unknown };
+        let trusted_proxies = &self.inner.config.trusted_proxies;
+        if !trusted_proxies.contains(&peer) {
+            return RateLimitClient::Ip(peer)
+        }
+        RateLimitClient::Ip(forwarded_ip(headers, trusted_proxies).unwrap_or(peer))
+    }
+
+    /// Checks whether the client may call the method and charges the cost of the call.
+    ///
+    /// The returned permit has to be held until the call is processed.
+    pub fn acquire(
+        &self,
+        client: &RateLimitClient,
+        method: &str,
+    ) -> Result<RateLimitPermit, RateLimitError> {
+        // the concurrency limit is checked first, so rejected calls are not charged
+        let namespace = method.split('_').next().unwrap_or_default();
+        let permit = match self.inner.semaphores.get(namespace) {
+            Some(semaphore) => Some(semaphore.clone().try_acquire_owned().map_err(|_| {
+                RateLimitError::ConcurrencyLimited { namespace: namespace.to_string() }
+            })?),
+            None => None,
+        };
+
+        let cost = self.inner.config.method_costs.get(method).copied().unwrap_or(1);
+        let quota = match client {
+            RateLimitClient::ApiKey(api_key) => {
+                self.inner.config.api_key_quotas.get(api_key).copied()
+            }
+            RateLimitClient::Ip(_) | RateLimitClient::Unknown => self.inner.config.ip_quota,
+        };
+        if let Some(quota) = quota {
+            let now = Instant::now();
+            let mut buckets = self.inner.buckets.lock();
+            buckets
+                .get_or_insert(client.bucket_key(), || TokenBucket::new(quota, now))
+                .expect("buckets are only limited by length")
+                .charge(cost, now)?;
+        }
+
+        Ok(RateLimitPermit { cost, _permit: permit })
+    }
+}
+
+impl RateLimitClient {
+    /// Returns the key of the client's quota. IPv6 clients share the quota of their prefix, so a
+    /// single host can't get more quota by rotating through its addresses.
+    fn bucket_key(&self) -> Self {
+        match self {
+            Self::Ip(IpAddr::V6(ip)) => {
+                if let Some(ip) = ip.to_ipv4_mapped() {
+                    return Self::Ip(IpAddr::V4(ip))
+                }
+                let mask = u128::MAX << (128 - IPV6_CLIENT_PREFIX_LEN);
+                Self::Ip(IpAddr::V6(Ipv6Addr::from(u128::from(*ip) & mask)))
+            }
+            client => client.clone(),
+        }
+    }
+}
+
+/// A call accepted by the [`RateLimiter`].
+#[derive(Debug)]
+pub struct RateLimitPermit {
+    /// Credits charged for the call
+    pub cost: u32,
+    /// Concurrency permit of the namespace, if it's limited
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+/// Credits of a client, replenished continuously up to the quota.
+#[derive(Debug)]
+struct TokenBucket {
+    quota: f64,
+    credits: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(quota: u32, now: Instant) -> Self {
+        Self { quota: quota as f64, credits: quota as f64, updated_at: now }
+    }
+
+    fn replenish(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.credits = (self.credits + elapsed * self.quota).min(self.quota);
+        self.updated_at = now;
+    }
+
+    /// Charges the cost of a call, if enough credits are left.
+    fn charge(&mut self, cost: u32, now: Instant) -> Result<(), RateLimitError> {
+        self.replenish(now);
+        let required = (cost as f64).min(self.quota);
+        if self.credits <= 0.0 || self.credits < required {
+            return Err(RateLimitError::QuotaExceeded {
+                cost,
+                available: self.credits.max(0.0) as u32,
+            })
+        }
+        self.credits -= cost as f64;
+        Ok(())
+    }
+}
+
+/// Returns the client IP reported by a trusted reverse proxy in the `X-Forwarded-For` or
+/// `X-Real-IP` header.
+///
+/// Every proxy appends the address it received the request from to `X-Forwarded-For`, so the
+/// entries are walked from the right, skipping the trusted proxies. Entries left of the first
+/// untrusted one could be forged by the client.
+fn forwarded_ip(headers: &HeaderMap, trusted_proxies: &HashSet<IpAddr>) -> Option<IpAddr> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    if let Some(forwarded_for) = header("x-forwarded-for") {
+        let mut client = None;
+        for entry in forwarded_for.rsplit(',') {
+            let ip = entry.trim().parse::<IpAddr>().ok()?;
+            client = Some(ip);
+            if !trusted_proxies.contains(&ip) {
+                break
+            }
+        }
+        return client
+    }
+    header("x-real-ip").and_then(|ip| ip.trim().parse().ok())
+}
+
+/// Metrics of the [`RateLimiter`] per RPC method.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "rpc_server.rate_limit")]
+struct RateLimitMetrics {
+    /// The number of calls rejected because the client's quota was exhausted
+    quota_exceeded_total: Counter,
+    /// The number of calls rejected because of the namespace's concurrency limit
+    concurrency_limited_total: Counter,
+    /// The number of credits charged for accepted calls
+    credits_charged_total: Counter,
+}
+
+/// HTTP middleware that determines the [`RateLimitClient`] of a request and stores it in the
+/// request extensions, for the [`RateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct RateLimitClientLayer {
+    limiter: RateLimiter,
+}
+
+impl<S> Layer<S> for RateLimitClientLayer {
+    type Service = RateLimitClientService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitClientService { limiter: self.limiter.clone(), inner }
+    }
+}
+
+/// Service created by the [`RateLimitClientLayer`].
+#[derive(Debug, Clone)]
+pub struct RateLimitClientService<S> {
+    limiter: RateLimiter,
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for RateLimitClientService<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        let peer = req.extensions().get::<PeerAddr>().map(|peer| peer.0.ip());
+        let client = self.limiter.client(peer, req.headers());
+        req.extensions_mut().insert(client);
+        self.inner.call(req)
+    }
+}
+
+/// RPC middleware that rejects the calls exceeding the limits of the [`RateLimiter`].
+///
+/// Calls without a [`RateLimitClient`] in their extensions are accounted to
+/// [`RateLimitClient::Unknown`].
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: RateLimiter,
+    metrics: Arc<HashMap<&'static str, RateLimitMetrics>>,
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService { layer: self.clone(), inner }
+    }
+}
+
+/// Service created by the [`RateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    layer: RateLimitLayer,
+    inner: S,
+}
+
+impl<'a, S> RpcServiceT<'a> for RateLimitService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = RateLimitFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let client =
+            req.extensions().get::<RateLimitClient>().cloned().unwrap_or(RateLimitClient::Unknown);
+        let metrics = self.layer.metrics.get(req.method.as_ref());
+
+        match self.layer.limiter.acquire(&client, req.method.as_ref()) {
+            Ok(permit) => {
+                if let Some(metrics) = metrics {
+                    metrics.credits_charged_total.increment(permit.cost as u64);
+                }
+                RateLimitFuture::Call { fut: self.inner.call(req), _permit: permit }
+            }
+            Err(err) => {
+                if let Some(metrics) = metrics {
+                    match err {
+                        RateLimitError::QuotaExceeded { .. } => {
+                            metrics.quota_exceeded_total.increment(1)
+                        }
+                        RateLimitError::ConcurrencyLimited { .. } => {
+                            metrics.concurrency_limited_total.increment(1)
+                        }
+                    }
+                }
+                RateLimitFuture::Rejected { response: Some(MethodResponse::error(req.id, err)) }
+            }
+        }
+    }
+}
+
+/// Response future of the [`RateLimitService`].
+#[pin_project(project = RateLimitFutureProj)]
+#[allow(missing_debug_implementations)]
+pub enum RateLimitFuture<F> {
+    /// The call was accepted and is processed by the inner service.
+    Call {
+        /// The inner service future
+        #[pin]
+        fut: F,
+        /// Released once the call is processed
+        _permit: RateLimitPermit,
+    },
+    /// The call was rejected.
+    Rejected {
+        /// The error response
+        response: Option<MethodResponse>,
+    },
+}
+
+impl<F> Future for RateLimitFuture<F>
+where
+    F: Future<Output = MethodResponse>,
+{
+    type Output = MethodResponse;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            RateLimitFutureProj::Call { fut, .. } => fut.poll(cx),
+            RateLimitFutureProj::Rejected { response } => {
+                Poll::Ready(response.take().expect("polled after completion"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use http::HeaderValue;
+
+    fn ip_client() -> RateLimitClient {
+        RateLimitClient::Ip("10.0.0.1".parse().unwrap())
+    }
+
+    #[test]
+    fn charges_method_costs() {
+        let mut config = RateLimitConfig { ip_quota: Some(10), ..Default::default() };
+        config.method_costs.insert("eth_getLogs".to_string(), 6);
+        let limiter = RateLimiter::new(config);
+
+        let permit = limiter.acquire(&ip_client(), "eth_getLogs").unwrap();
+        assert_eq!(permit.cost, 6);
+        assert_matches!(
+            limiter.acquire(&ip_client(), "eth_getLogs"),
+            Err(RateLimitError::QuotaExceeded { cost: 6, available: 4 })
+        );
+        // cheap calls still fit into the remaining quota
+        assert_eq!(limiter.acquire(&ip_client(), "eth_blockNumber").unwrap().cost, 1);
+
+        // other clients have their own quota
+        assert!(limiter.acquire(&RateLimitClient::Unknown, "eth_getLogs").is_ok());
+    }
+
+    #[test]
+    fn limits_concurrent_calls() {
+        let config = RateLimitConfig {
+            max_concurrent: HashMap::from([("trace".to_string(), 1)]),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config);
+
+        let permit = limiter.acquire(&ip_client(), "trace_block").unwrap();
+        assert_matches!(
+            limiter.acquire(&ip_client(), "trace_filter"),
+            Err(RateLimitError::ConcurrencyLimited { namespace }) if namespace == "trace"
+        );
+        assert!(limiter.acquire(&ip_client(), "eth_call").is_ok());
+
+        drop(permit);
+        assert!(limiter.acquire(&ip_client(), "trace_filter").is_ok());
+    }
+
+    #[test]
+    fn ipv6_clients_share_the_quota_of_their_prefix() {
+        let config = RateLimitConfig { ip_quota: Some(1), ..Default::default() };
+        let limiter = RateLimiter::new(config);
+        let client = |ip: &str| RateLimitClient::Ip(ip.parse().unwrap());
+
+        assert!(limiter.acquire(&client("2001:db8::1"), "eth_blockNumber").is_ok());
+        assert_matches!(
+            limiter.acquire(&client("2001:db8::ffff:1"), "eth_blockNumber"),
+            Err(RateLimitError::QuotaExceeded { .. })
+        );
+        assert!(limiter.acquire(&client("2001:db8:0:1::1"), "eth_blockNumber").is_ok());
+
+        // IPv4-mapped addresses are accounted to the IPv4 address
+        assert!(limiter.acquire(&client("::ffff:10.0.0.1"), "eth_blockNumber").is_ok());
+        assert_matches!(
+            limiter.acquire(&ip_client(), "eth_blockNumber"),
+            Err(RateLimitError::QuotaExceeded { .. })
+        );
+    }
+
+    #[test]
+    fn bounds_tracked_clients() {
+        let config = RateLimitConfig { ip_quota: Some(1), ..Default::default() };
+        let limiter = RateLimiter::new(config);
+
+        for ip in 0..MAX_TRACKED_CLIENTS + 10 {
+            let client = RateLimitClient::Ip(IpAddr::V4(ip.into()));
+            assert!(limiter.acquire(&client, "eth_blockNumber").is_ok());
+        }
+        assert_eq!(limiter.inner.buckets.lock().len(), MAX_TRACKED_CLIENTS as usize);
+
+        // the least recently active client was forgotten, so its quota is replenished
+        let client = RateLimitClient::Ip(IpAddr::V4(0u32.into()));
+        assert!(limiter.acquire(&client, "eth_blockNumber").is_ok());
+    }
+
+    #[test]
+    fn determines_client() {
+        let proxy: IpAddr = "192.168.0.1".parse().unwrap();
+        let config = RateLimitConfig {
+            api_key_quotas: HashMap::from([("tenant".to_string(), 100)]),
+            trusted_proxies: HashSet::from([proxy]),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config);
+        let peer: IpAddr = "10.0.0.1".parse().unwrap();
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(limiter.client(None, &headers), RateLimitClient::Unknown);
+        assert_eq!(limiter.client(Some(peer), &headers), ip_client());
+
+        // forwarded headers of untrusted peers are ignored
+        headers.insert("x-forwarded-for", HeaderValue::from_static("10.0.0.2"));
+        assert_eq!(limiter.client(Some(peer), &headers), ip_client());
+
+        // unknown API keys are limited by IP
+        headers.insert(DEFAULT_API_KEY_HEADER, HeaderValue::from_static("unknown"));
+        assert_eq!(limiter.client(Some(peer), &headers), ip_client());
+
+        headers.insert(DEFAULT_API_KEY_HEADER, HeaderValue::from_static("tenant"));
+        assert_eq!(
+            limiter.client(Some(peer), &headers),
+            RateLimitClient::ApiKey("tenant".to_string())
+        );
+    }
+
+    #[test]
+    fn trusts_forwarded_headers_of_trusted_proxies() {
+        let proxies: [IpAddr; 2] = ["192.168.0.1".parse().unwrap(), "192.168.0.2".parse().unwrap()];
+        let config =
+            RateLimitConfig { trusted_proxies: HashSet::from(proxies), ..Default::default() };
+        let limiter = RateLimiter::new(config);
+        let client = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-forwarded-for", HeaderValue::from_static(value));
+            limiter.client(Some(proxies[0]), &headers)
+        };
+
+        assert_eq!(client("10.0.0.1"), ip_client());
+        // the trusted proxies are skipped, addresses forged by the client are ignored
+        assert_eq!(client("1.1.1.1, 10.0.0.1, 192.168.0.2"), ip_client());
+        // requests without a valid forwarded address are accounted to the proxy
+        assert_eq!(client("garbage"), RateLimitClient::Ip(proxies[0]));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", HeaderValue::from_static("10.0.0.1"));
+        assert_eq!(limiter.client(Some(proxies[0]), &headers), ip_client());
+        assert_eq!(
+            limiter.client(Some(proxies[0]), &HeaderMap::new()),
+            RateLimitClient::Ip(proxies[0])
+        );
+    }
+}
@@ -0,0 +1,446 @@
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request, MethodResponse};
+use pin_project::pin_project;
+use reth_metrics::{metrics::Counter, Metrics};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions},
+    future::Future,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+use tower::Layer;
+use tracing::{debug, warn};
+
+/// The default maximum size of a recording file in bytes, 100 MiB.
+pub const DEFAULT_RECORD_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// The default number of recording files that are kept.
+pub const DEFAULT_RECORD_MAX_FILES: usize = 10;
+
+/// Prefix of the recording file names.
+const RECORD_FILE_PREFIX: &str = "rpc-";
+
+/// The block number of the [`RpcRecorder`] until the best block number is set.
+const UNKNOWN_BLOCK_NUMBER: u64 = u64::MAX;
+
+/// Extension of the recording file names.
+const RECORD_FILE_EXTENSION: &str = "jsonl";
+
+/// Number of calls that can be queued for the writer before calls are dropped from the recording.
+const RECORD_CHANNEL_SIZE: usize = 10_000;
+
+/// Configuration of the [`RpcRecorder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcRecordConfig {
+    /// Directory the recording files are written to.
+    pub dir: PathBuf,
+    /// Size in bytes after which a new recording file is started.
+    pub max_file_size: u64,
+    /// Number of recording files that are kept, the oldest files are removed first.
+    pub max_files: usize,
+}
+
+impl RpcRecordConfig {
+    /// Creates a new config that writes recordings to the given directory.
+    pub const fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_file_size: DEFAULT_RECORD_MAX_FILE_SIZE,
+            max_files: DEFAULT_RECORD_MAX_FILES,
+        }
+    }
+
+    /// Sets the size in bytes after which a new recording file is started.
+    pub const fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Sets the number of recording files that are kept.
+    pub const fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+}
+
+/// A recorded RPC call, stored as one JSON line in the recording files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRecord {
+    /// Unix timestamp in milliseconds at which the call was received.
+    pub timestamp: u64,
+    /// The best block number of the node at the time of the call.
+    pub block_number: Option<u64>,
+    /// Time it took to process the call in microseconds.
+    pub duration_micros: u64,
+    /// The JSON-RPC request.
+    pub request: serde_json::Value,
+    /// The JSON-RPC response.
+    pub response: serde_json::Value,
+}
+
+impl RpcRecord {
+    /// Returns the method of the recorded request.
+    pub fn method(&self) -> Option<&str> {
+        self.request.get("method").and_then(serde_json::Value::as_str)
+    }
+}
+
+/// Returns the recording files in the given directory, ordered from oldest to newest.
+pub fn rpc_record_files(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if let Some(timestamp) = record_file_timestamp(&path) {
+            files.push((timestamp, path));
+        }
+    }
+    files.sort_unstable();
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Returns an iterator over the calls recorded in the given file.
+pub fn read_rpc_records(
+    path: impl AsRef<Path>,
+) -> io::Result<impl Iterator<Item = io::Result<RpcRecord>>> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(reader.lines().filter(|line| line.as_ref().map_or(true, |line| !line.is_empty())).map(
+        |line| {
+            let line = line?;
+            serde_json::from_str(&line).map_err(io::Error::from)
+        },
+    ))
+}
+
+/// Returns the timestamp of the recording file, or `None` if the path is not a recording file.
+fn record_file_timestamp(path: &Path) -> Option<u64> {
+    if path.extension()? != RECORD_FILE_EXTENSION {
+        return None
+    }
+    path.file_stem()?.to_str()?.strip_prefix(RECORD_FILE_PREFIX)?.parse().ok()
+}
+
+/// Returns the current unix timestamp in milliseconds.
+fn unix_timestamp_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Records every request and response of the RPC server, with timing and the block number at the
+/// time of the call, to rotating files.
+///
+/// The records are handed to a dedicated writer thread, so recording never blocks the server. If
+/// the writer can't keep up, calls are dropped from the recording.
+#[derive(Clone)]
+pub struct RpcRecorder {
+    inner: Arc<RpcRecorderInner>,
+}
+
+struct RpcRecorderInner {
+    /// Sends the finished calls to the writer thread.
+    sender: SyncSender<PendingRecord>,
+    /// The current best block number, [`UNKNOWN_BLOCK_NUMBER`] until it's set.
+    block_number: AtomicU64,
+    /// Metrics of the recorder.
+    metrics: RpcRecorderMetrics,
+}
+
+impl RpcRecorder {
+    /// Creates a new recorder and spawns the thread writing the recording files.
+    ///
+    /// The best block number attached to the records has to be kept up to date with
+    /// [`RpcRecorder::set_block_number`].
+    pub fn new(config: RpcRecordConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        let (sender, receiver) = mpsc::sync_channel(RECORD_CHANNEL_SIZE);
+        let metrics = RpcRecorderMetrics::default();
+        let writer = RecordWriter { config, file: None, metrics: metrics.clone() };
+        std::thread::Builder::new()
+            .name("rpc-recorder".to_string())
+            .spawn(move || writer.run(receiver))?;
+
+        Ok(Self {
+            inner: Arc::new(RpcRecorderInner {
+                sender,
+                block_number: AtomicU64::new(UNKNOWN_BLOCK_NUMBER),
+                metrics,
+            }),
+        })
+    }
+
+    /// Sets the best block number that is attached to the records of the following calls.
+    pub fn set_block_number(&self, block_number: u64) {
+        self.inner.block_number.store(block_number, Ordering::Relaxed);
+    }
+
+    /// Returns the current best block number, if it's set.
+    fn block_number(&self) -> Option<u64> {
+        let block_number = self.inner.block_number.load(Ordering::Relaxed);
+        (block_number != UNKNOWN_BLOCK_NUMBER).then_some(block_number)
+    }
+
+    /// Queues the finished call for the writer.
+    fn record(&self, record: PendingRecord) {
+        match self.inner.sender.try_send(record) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => self.inner.metrics.dropped_total.increment(1),
+            Err(TrySendError::Disconnected(_)) => {
+                debug!(target: "rpc::recorder", "recording writer stopped");
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RpcRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcRecorder").finish_non_exhaustive()
+    }
+}
+
+impl<S> Layer<S> for RpcRecorder {
+    type Service = RpcRecorderService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcRecorderService { recorder: self.clone(), inner }
+    }
+}
+
+/// RPC middleware created by the [`RpcRecorder`] that records every call.
+#[derive(Debug, Clone)]
+pub struct RpcRecorderService<S> {
+    recorder: RpcRecorder,
+    inner: S,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcRecorderService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = RecordedCallFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let request = serde_json::to_string(&req).unwrap_or_default();
+        RecordedCallFuture {
+            fut: self.inner.call(req),
+            recorder: self.recorder.clone(),
+            call: Some(CallInfo {
+                timestamp: unix_timestamp_millis(),
+                block_number: self.recorder.block_number(),
+                started_at: Instant::now(),
+                request,
+            }),
+        }
+    }
+}
+
+/// Response future of the [`RpcRecorderService`] that records the call once it's processed.
+#[pin_project]
+#[allow(missing_debug_implementations)]
+pub struct RecordedCallFuture<F> {
+    #[pin]
+    fut: F,
+    recorder: RpcRecorder,
+    call: Option<CallInfo>,
+}
+
+impl<F> Future for RecordedCallFuture<F>
+where
+    F: Future<Output = MethodResponse>,
+{
+    type Output = MethodResponse;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response = std::task::ready!(this.fut.poll(cx));
+        if let Some(call) = this.call.take() {
+            this.recorder.record(PendingRecord {
+                timestamp: call.timestamp,
+                block_number: call.block_number,
+                duration_micros: call.started_at.elapsed().as_micros() as u64,
+                request: call.request,
+                response: response.as_result().to_string(),
+            });
+        }
+        Poll::Ready(response)
+    }
+}
+
+/// Information about a call that is being processed.
+struct CallInfo {
+    timestamp: u64,
+    block_number: Option<u64>,
+    started_at: Instant,
+    request: String,
+}
+
+/// A processed call that is not yet written, the request and response are serialized JSON.
+struct PendingRecord {
+    timestamp: u64,
+    block_number: Option<u64>,
+    duration_micros: u64,
+    request: String,
+    response: String,
+}
+
+impl PendingRecord {
+    fn into_record(self) -> RpcRecord {
+        let parse =
+            |json: String| serde_json::from_str(&json).unwrap_or(serde_json::Value::String(json));
+        RpcRecord {
+            timestamp: self.timestamp,
+            block_number: self.block_number,
+            duration_micros: self.duration_micros,
+            request: parse(self.request),
+            response: parse(self.response),
+        }
+    }
+}
+
+/// Writes the records to the rotating recording files.
+struct RecordWriter {
+    config: RpcRecordConfig,
+    /// The current file and the number of bytes written to it.
+    file: Option<(BufWriter<File>, u64)>,
+    metrics: RpcRecorderMetrics,
+}
+
+impl RecordWriter {
+    /// Writes the received records until all senders are dropped.
+    fn run(mut self, receiver: Receiver<PendingRecord>) {
+        while let Ok(record) = receiver.recv() {
+            self.write_or_log(record);
+            // flush once the queue is drained, so the files are usable while the node runs
+            while let Ok(record) = receiver.try_recv() {
+                self.write_or_log(record);
+            }
+            if let Some((file, _)) = self.file.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+
+    fn write_or_log(&mut self, record: PendingRecord) {
+        if let Err(err) = self.write(record) {
+            warn!(target: "rpc::recorder", %err, "Failed to write RPC recording");
+            self.metrics.write_errors_total.increment(1);
+            self.file = None;
+        }
+    }
+
+    fn write(&mut self, record: PendingRecord) -> io::Result<()> {
+        let mut line = serde_json::to_vec(&record.into_record())?;
+        line.push(b'\n');
+
+        let rotate = self.file.as_ref().map_or(true, |(_, size)| {
+            *size > 0 && size + line.len() as u64 > self.config.max_file_size
+        });
+        if rotate {
+            self.rotate()?;
+        }
+
+        let (file, size) = self.file.as_mut().expect("file is opened");
+        file.write_all(&line)?;
+        *size += line.len() as u64;
+        self.metrics.recorded_total.increment(1);
+        Ok(())
+    }
+
+    /// Starts a new recording file and removes the oldest files exceeding the limit.
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some((mut file, _)) = self.file.take() {
+            file.flush()?;
+        }
+
+        // make sure the new file sorts after the previous ones
+        let mut timestamp = unix_timestamp_millis();
+        let existing = rpc_record_files(&self.config.dir)?;
+        if let Some(last) = existing.last().and_then(|path| record_file_timestamp(path)) {
+            timestamp = timestamp.max(last + 1);
+        }
+
+        let path = self
+            .config
+            .dir
+            .join(format!("{RECORD_FILE_PREFIX}{timestamp}.{RECORD_FILE_EXTENSION}"));
+        let file = OpenOptions::new().create_new(true).append(true).open(&path)?;
+        debug!(target: "rpc::recorder", path=%path.display(), "Started RPC recording file");
+        self.file = Some((BufWriter::new(file), 0));
+
+        let stale = (existing.len() + 1).saturating_sub(self.config.max_files.max(1));
+        for path in existing.into_iter().take(stale) {
+            if let Err(err) = fs::remove_file(&path) {
+                warn!(target: "rpc::recorder", %err, path=%path.display(), "Failed to remove RPC recording file");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Metrics of the [`RpcRecorder`].
+#[derive(Metrics, Clone)]
+#[metrics(scope = "rpc_server.recorder")]
+struct RpcRecorderMetrics {
+    /// The number of calls written to the recording
+    recorded_total: Counter,
+    /// The number of calls dropped because the writer couldn't keep up
+    dropped_total: Counter,
+    /// The number of failed writes
+    write_errors_total: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(id: u64) -> PendingRecord {
+        PendingRecord {
+            timestamp: id,
+            block_number: Some(id),
+            duration_micros: 1,
+            request: format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"eth_blockNumber"}}"#),
+            response: format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"0x{id:x}"}}"#),
+        }
+    }
+
+    #[test]
+    fn attaches_the_best_block_number() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = RpcRecorder::new(RpcRecordConfig::new(dir.path().to_path_buf())).unwrap();
+        assert_eq!(recorder.block_number(), None);
+
+        recorder.set_block_number(7);
+        assert_eq!(recorder.block_number(), Some(7));
+    }
+
+    #[test]
+    fn rotates_record_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config =
+            RpcRecordConfig::new(dir.path().to_path_buf()).with_max_file_size(1).with_max_files(2);
+        let mut writer = RecordWriter { config, file: None, metrics: Default::default() };
+        for id in 0..5 {
+            writer.write(pending(id)).unwrap();
+        }
+        writer.file.take().unwrap().0.flush().unwrap();
+
+        // every record exceeds the file size, so only the last two records are kept
+        let files = rpc_record_files(dir.path()).unwrap();
+        assert_eq!(files.len(), 2);
+        let records = files
+            .iter()
+            .flat_map(|path| read_rpc_records(path).unwrap())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(records.iter().map(|record| record.timestamp).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(records[0].method(), Some("eth_blockNumber"));
+        assert_eq!(records[1].response["result"], "0x4");
+    }
+}
@@ -12,6 +12,16 @@ pub const DEFAULT_AUTH_PORT: u16 = 8551;
 /// The default maximum block range allowed to filter
 pub const DEFAULT_MAX_BLOCKS_PER_FILTER: u64 = 100_000;
 
+/// The default maximum number of blocks `trace_filter` scans for matching transactions
+pub const DEFAULT_MAX_TRACE_FILTER_BLOCKS: u64 = 100;
+
+/// The default maximum number of blocks whose traces are cached for `trace_filter`
+pub const DEFAULT_TRACE_CACHE_MAX_BLOCKS: usize = 10_000;
+
+/// The default maximum number of concurrent connections of the http and ws servers, the default of
+/// jsonrpsee's server.
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 100;
+
 /// The default maximum of logs in a single response.
 pub const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
 
@@ -68,7 +78,6 @@ pub mod gas_oracle {
 
 /// Cache specific constants
 pub mod cache {
-    // TODO: memory based limiter is currently disabled pending <https://github.com/paradigmxyz/reth/issues/3503>
     /// Default cache size for the block cache: 500MB
     ///
     /// With an average block size of ~100kb this should be able to cache ~5000 blocks.
@@ -91,4 +100,21 @@ pub mod cache {
 
     /// Default number of concurrent database requests.
     pub const DEFAULT_CONCURRENT_DB_REQUESTS: usize = 512;
+
+    /// Default number of recent canonical blocks loaded into the cache on startup: 128 blocks.
+    pub const DEFAULT_CACHE_PREWARM_BLOCKS: u64 = 128;
+}
+
+/// Builder submission validation specific constants
+pub mod validation {
+    use std::time::Duration;
+
+    /// The default maximum number of blocks a validated block may be behind the latest block.
+    pub const DEFAULT_VALIDATION_WINDOW: u64 = 3;
+
+    /// The default maximum number of concurrent builder submission validations.
+    pub const DEFAULT_MAX_CONCURRENT_VALIDATIONS: usize = 8;
+
+    /// The default maximum time a builder submission validation may take.
+    pub const DEFAULT_VALIDATION_TIMEOUT: Duration = Duration::from_secs(2);
 }
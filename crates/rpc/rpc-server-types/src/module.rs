@@ -98,6 +98,15 @@ impl RpcModuleSelection {
         }
     }
 
+    /// Returns true if the given module is part of the selection.
+    pub fn contains(&self, module: &RethRpcModule) -> bool {
+        match self {
+            Self::All => true,
+            Self::Standard => Self::STANDARD_MODULES.contains(module),
+            Self::Selection(s) => s.contains(module),
+        }
+    }
+
     /// Returns an iterator over all configured [`RethRpcModule`]
     pub fn iter_selection(&self) -> Box<dyn Iterator<Item = RethRpcModule> + '_> {
         match self {
@@ -260,6 +269,20 @@ pub enum RethRpcModule {
     /// This is separate from [`RethRpcModule::Eth`] because it is a non standardized call that
     /// should be opt-in.
     EthCallBundle,
+    /// `anvil_` module
+    ///
+    /// This is only installed if the node runs in `--dev` mode.
+    Anvil,
+    /// `hardhat_` module
+    ///
+    /// This is only installed if the node runs in `--dev` mode.
+    Hardhat,
+    /// `mev_` module
+    Mev,
+    /// `flashbots_` module
+    ///
+    /// This is installed by the node, because it needs the node's consensus and block executor.
+    Flashbots,
 }
 
 // === impl RethRpcModule ===
@@ -309,6 +332,10 @@ impl FromStr for RethRpcModule {
             "reth" => Self::Reth,
             "ots" => Self::Ots,
             "eth-call-bundle" | "eth_callBundle" => Self::EthCallBundle,
+            "anvil" => Self::Anvil,
+            "hardhat" => Self::Hardhat,
+            "mev" => Self::Mev,
+            "flashbots" => Self::Flashbots,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
@@ -0,0 +1,150 @@
+//! Types for the `eth_simulateV1` endpoint.
+//!
+//! See also <https://github.com/ethereum/execution-apis/pull/484>
+
+use crate::{state::StateOverride, Block, BlockOverrides, Log, TransactionRequest};
+use alloy_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of blocks that can be simulated in a single `eth_simulateV1` request.
+pub const MAX_SIMULATE_BLOCKS: u64 = 256;
+
+/// The request payload of `eth_simulateV1`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, in order.
+    #[serde(default)]
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to add synthetic logs for every ETH transfer.
+    #[serde(default)]
+    pub trace_transfers: bool,
+    /// Whether to run the calls with the full transaction validation rules (nonce, balance,
+    /// base fee).
+    #[serde(default)]
+    pub validation: bool,
+    /// Whether to return full transaction objects in the simulated blocks instead of hashes.
+    #[serde(default)]
+    pub return_full_transactions: bool,
+}
+
+/// A single block of calls to simulate, with optional overrides.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBlock {
+    /// Overrides for the block header fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Overrides of the state, applied before the calls of this block are executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    /// The calls to execute in this block.
+    #[serde(default)]
+    pub calls: Vec<TransactionRequest>,
+}
+
+impl SimBlock {
+    /// Returns a new, empty block with the given block overrides.
+    pub fn with_block_overrides(block_overrides: BlockOverrides) -> Self {
+        Self { block_overrides: Some(block_overrides), ..Default::default() }
+    }
+
+    /// Appends a call to the block.
+    pub fn call(mut self, call: TransactionRequest) -> Self {
+        self.calls.push(call);
+        self
+    }
+}
+
+/// A simulated block, returned by `eth_simulateV1`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock<B = Block> {
+    /// The simulated block.
+    #[serde(flatten)]
+    pub inner: B,
+    /// The results of the calls included in the block.
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The result of a single call in a simulated block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallResult {
+    /// The return data of the call.
+    pub return_data: Bytes,
+    /// The logs emitted by the call, including synthetic transfer logs if enabled.
+    #[serde(default)]
+    pub logs: Vec<Log>,
+    /// The amount of gas used by the call.
+    #[serde(with = "alloy_serde::quantity")]
+    pub gas_used: u64,
+    /// Whether the call succeeded.
+    #[serde(with = "alloy_serde::quantity")]
+    pub status: bool,
+    /// The error, if the call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+}
+
+/// The error of a failed call in a simulated block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateError {
+    /// The error code.
+    pub code: i32,
+    /// The error message.
+    pub message: String,
+    /// Optional additional data, for example the revert data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_simulate_payload() {
+        let s = r#"{
+            "blockStateCalls": [
+                {
+                    "blockOverrides": { "number": "0x10", "baseFee": "0x0" },
+                    "stateOverrides": {
+                        "0xc000000000000000000000000000000000000000": { "balance": "0x3e8" }
+                    },
+                    "calls": [
+                        {
+                            "from": "0xc000000000000000000000000000000000000000",
+                            "to": "0xc100000000000000000000000000000000000000",
+                            "value": "0x3e8"
+                        }
+                    ]
+                },
+                {}
+            ],
+            "traceTransfers": true,
+            "validation": false
+        }"#;
+        let payload: SimulatePayload = serde_json::from_str(s).unwrap();
+        assert_eq!(payload.block_state_calls.len(), 2);
+        assert!(payload.trace_transfers);
+        assert!(!payload.validation);
+        assert!(!payload.return_full_transactions);
+        assert_eq!(payload.block_state_calls[0].calls.len(), 1);
+        assert!(payload.block_state_calls[1].calls.is_empty());
+    }
+
+    #[test]
+    fn serialize_call_result() {
+        let res = SimCallResult {
+            return_data: Bytes::from_static(&[1]),
+            logs: vec![],
+            gas_used: 21000,
+            status: true,
+            error: None,
+        };
+        let s = serde_json::to_string(&res).unwrap();
+        assert_eq!(s, r#"{"returnData":"0x01","logs":[],"gasUsed":"0x5208","status":"0x1"}"#);
+    }
+}
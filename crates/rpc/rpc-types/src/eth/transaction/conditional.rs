@@ -0,0 +1,65 @@
+//! Types for `eth_sendRawTransactionConditional`.
+
+use alloy_primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The expected storage of an account, either its storage root or the values of individual slots.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KnownAccountState {
+    /// The storage root of the account.
+    StorageRoot(B256),
+    /// The values of storage slots of the account.
+    Slots(HashMap<B256, B256>),
+}
+
+/// Conditions a transaction submitted via `eth_sendRawTransactionConditional` must satisfy to be
+/// included in a block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionConditional {
+    /// The expected storage of accounts the transaction depends on.
+    #[serde(default)]
+    pub known_accounts: HashMap<Address, KnownAccountState>,
+    /// The minimum block number the transaction can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "alloy_serde::quantity::opt")]
+    pub block_number_min: Option<u64>,
+    /// The maximum block number the transaction can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "alloy_serde::quantity::opt")]
+    pub block_number_max: Option<u64>,
+    /// The minimum block timestamp the transaction can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "alloy_serde::quantity::opt")]
+    pub timestamp_min: Option<u64>,
+    /// The maximum block timestamp the transaction can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "alloy_serde::quantity::opt")]
+    pub timestamp_max: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn deserialize_conditional() {
+        let s = r#"{
+            "knownAccounts": {
+                "0x000000000000000000000000000000000000aaaa": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "0x000000000000000000000000000000000000bbbb": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000002": "0x0000000000000000000000000000000000000000000000000000000000000003"
+                }
+            },
+            "blockNumberMax": "0x10",
+            "timestampMin": "0x64"
+        }"#;
+        let conditional: TransactionConditional = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            conditional.known_accounts[&address!("000000000000000000000000000000000000aaaa")],
+            KnownAccountState::StorageRoot(B256::with_last_byte(1))
+        );
+        assert_eq!(conditional.block_number_max, Some(16));
+        assert_eq!(conditional.timestamp_min, Some(100));
+        assert_eq!(conditional.block_number_min, None);
+    }
+}
@@ -1,5 +1,7 @@
 //! RPC types for transactions
+mod conditional;
 mod request;
 mod typed;
+pub use conditional::{KnownAccountState, TransactionConditional};
 pub use request::TransactionRequest;
 pub use typed::*;
@@ -14,7 +14,9 @@ mod eth;
 mod mev;
 mod net;
 mod peer;
+pub mod pubsub;
 mod rpc;
+pub mod streaming;
 
 // re-export for convenience
 pub use alloy_rpc_types::serde_helpers;
@@ -49,7 +51,11 @@ pub use eth::{
         ExecutionPayload, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadError,
     },
     error::ToRpcError,
-    transaction::{self, TransactionRequest, TypedTransactionRequest},
+    simulate,
+    transaction::{
+        self, KnownAccountState, TransactionConditional, TransactionRequest,
+        TypedTransactionRequest,
+    },
 };
 
 pub use mev::*;
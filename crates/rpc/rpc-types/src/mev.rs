@@ -82,6 +82,11 @@ pub enum BundleItem {
         /// If true, the transaction can revert without the bundle being considered invalid.
         can_revert: bool,
     },
+    /// A nested bundle.
+    Bundle {
+        /// The nested bundle.
+        bundle: Box<SendBundleRequest>,
+    },
 }
 
 /// Requirements for the bundle to be included in the block.
@@ -829,6 +834,37 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn can_deserialize_nested() {
+        let str = r#"
+        {
+            "version": "v0.1",
+            "inclusion": {
+                "block": "0x1",
+                "maxBlock": "0x3"
+            },
+            "body": [{
+                "hash": "0xa463985c616dd8ee17d7ef9112af4e6e06a27b071525b42182fe7b0b5c8b4925"
+            }, {
+                "bundle": {
+                    "version": "v0.1",
+                    "inclusion": {
+                        "block": "0x1"
+                    },
+                    "body": [{
+                        "tx": "0x02f86b0180843b9aca00852ecc889a0082520894c87037874aed04e51c29f582394217a0a2b89d808080c080a0a463985c616dd8ee17d7ef9112af4e6e06a27b071525b42182fe7b0b5c8b4925a00af5ca177ffef2ff28449292505d41be578bebb77110dfc09361d2fb56998260",
+                        "canRevert": true
+                    }]
+                }
+            }]
+        }
+        "#;
+        let res: SendBundleRequest = serde_json::from_str(str).unwrap();
+        assert!(matches!(res.bundle_body[0], BundleItem::Hash { .. }));
+        let BundleItem::Bundle { bundle } = &res.bundle_body[1] else { panic!("expected bundle") };
+        assert!(matches!(bundle.bundle_body[0], BundleItem::Tx { can_revert: true, .. }));
+    }
+
     #[test]
     fn can_serialize_complex() {
         let str = r#"
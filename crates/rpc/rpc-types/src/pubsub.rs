@@ -0,0 +1,160 @@
+//! Ethereum types for pub-sub.
+//!
+//! Extends the [`alloy_rpc_types::pubsub`] types with the subscriptions for canonical chain
+//! updates, safe and finalized heads and dropped pool transactions.
+
+use alloy_primitives::B256;
+use alloy_rpc_types::{Header, Log, RichHeader, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub use alloy_rpc_types::pubsub::{Params, PubSubSyncStatus, SyncStatusMetadata};
+
+/// Subscription result.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubscriptionResult {
+    /// New block header.
+    Header(Box<RichHeader>),
+    /// Log
+    Log(Box<Log>),
+    /// Transaction hash
+    TransactionHash(B256),
+    /// Full Transaction
+    FullTransaction(Box<Transaction>),
+    /// SyncStatus
+    SyncState(PubSubSyncStatus),
+    /// Canonical chain update
+    CanonicalChainUpdate(Box<CanonicalChainUpdate>),
+    /// Transaction dropped from the pool
+    DroppedTransaction(DroppedTransaction),
+}
+
+/// Subscription kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    /// New block headers subscription.
+    ///
+    /// Fires a notification each time a new header is appended to the chain, including chain
+    /// reorganizations. In case of a chain reorganization the subscription will emit all new
+    /// headers for the new chain. Therefore the subscription can emit multiple headers on the same
+    /// height.
+    NewHeads,
+    /// Logs subscription.
+    ///
+    /// Returns logs that are included in new imported blocks and match the given filter criteria.
+    /// In case of a chain reorganization previous sent logs that are on the old chain will be
+    /// resent with the removed property set to true. Logs from transactions that ended up in the
+    /// new chain are emitted. Therefore, a subscription can emit logs for the same transaction
+    /// multiple times.
+    Logs,
+    /// New Pending Transactions subscription.
+    ///
+    /// Returns the hash or full tx for all transactions that are added to the pending state and
+    /// are signed with a key that is available in the node. When a transaction that was
+    /// previously part of the canonical chain isn't part of the new canonical chain after a
+    /// reorganization its again emitted.
+    NewPendingTransactions,
+    /// Node syncing status subscription.
+    ///
+    /// Indicates when the node starts or stops synchronizing. The result can either be a boolean
+    /// indicating that the synchronization has started (true), finished (false) or an object with
+    /// various progress indicators.
+    Syncing,
+    /// Canonical chain updates subscription.
+    ///
+    /// Fires a [`CanonicalChainUpdate`] for every change of the canonical chain. A chain
+    /// reorganization is a single notification carrying the headers of both the reverted and the
+    /// committed blocks.
+    CanonicalChainUpdates,
+    /// Safe head subscription.
+    ///
+    /// Fires a notification with the header each time the consensus layer selects a new safe
+    /// block.
+    SafeHeads,
+    /// Finalized head subscription.
+    ///
+    /// Fires a notification with the header each time the consensus layer selects a new finalized
+    /// block.
+    FinalizedHeads,
+    /// Dropped transactions subscription.
+    ///
+    /// Fires a [`DroppedTransaction`] for every transaction that is replaced, discarded or
+    /// invalidated in the transaction pool.
+    DroppedTransactions,
+}
+
+/// A change of the canonical chain, sent by the
+/// [`SubscriptionKind::CanonicalChainUpdates`] subscription.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanonicalChainUpdate {
+    /// Headers of the blocks that were removed from the canonical chain, ordered by block number.
+    pub reverted: Vec<Header>,
+    /// Headers of the blocks that were added to the canonical chain, ordered by block number.
+    pub committed: Vec<Header>,
+}
+
+impl CanonicalChainUpdate {
+    /// Returns true if the update reverted blocks of the canonical chain.
+    pub fn is_reorg(&self) -> bool {
+        !self.reverted.is_empty()
+    }
+}
+
+/// A transaction dropped from the pool, sent by the [`SubscriptionKind::DroppedTransactions`]
+/// subscription.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedTransaction {
+    /// Hash of the dropped transaction.
+    pub hash: B256,
+    /// Why the transaction was dropped.
+    pub reason: DroppedTransactionReason,
+    /// Hash of the transaction that replaced the dropped transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<B256>,
+}
+
+/// The reason a transaction was dropped from the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DroppedTransactionReason {
+    /// The transaction was replaced by a transaction with the same sender and nonce.
+    Replaced,
+    /// The transaction was discarded due to the pool limits.
+    Discarded,
+    /// The transaction became invalid.
+    Invalid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscription_kind_serde() {
+        let kind: SubscriptionKind = serde_json::from_str(r#""canonicalChainUpdates""#).unwrap();
+        assert_eq!(kind, SubscriptionKind::CanonicalChainUpdates);
+        let kind: SubscriptionKind = serde_json::from_str(r#""newHeads""#).unwrap();
+        assert_eq!(kind, SubscriptionKind::NewHeads);
+        assert_eq!(
+            serde_json::to_string(&SubscriptionKind::DroppedTransactions).unwrap(),
+            r#""droppedTransactions""#
+        );
+    }
+
+    #[test]
+    fn dropped_transaction_serde() {
+        let dropped = SubscriptionResult::DroppedTransaction(DroppedTransaction {
+            hash: B256::with_last_byte(1),
+            reason: DroppedTransactionReason::Replaced,
+            replaced_by: Some(B256::with_last_byte(2)),
+        });
+        let json = serde_json::to_value(&dropped).unwrap();
+        assert_eq!(json["reason"], "replaced");
+        assert_eq!(json["replacedBy"], B256::with_last_byte(2).to_string());
+        assert_eq!(serde_json::from_value::<SubscriptionResult>(json).unwrap(), dropped);
+    }
+}
@@ -0,0 +1,338 @@
+//! Incrementally serialized JSON responses.
+//!
+//! Large responses, like the traces of all transactions in a block, are serialized item by item
+//! into a size bounded buffer as they are produced, instead of first materializing the entire
+//! response and serializing it afterwards.
+//!
+//! The RPC method signatures stay typed, the servers register the streamed handlers in place of
+//! the typed ones. A [`StreamedJson`] serializes exactly like the value it wraps.
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use std::{
+    fmt,
+    io::{self, Write},
+    marker::PhantomData,
+};
+
+/// Errors that can occur when writing a [`StreamedJson`] response.
+#[derive(Debug, thiserror::Error)]
+pub enum StreamingJsonError {
+    /// The serialized response exceeds the size limit.
+    #[error("response exceeds the maximum size of {0} bytes")]
+    ResponseTooLarge(usize),
+    /// A value failed to serialize.
+    #[error(transparent)]
+    Serialization(serde_json::Error),
+}
+
+/// A JSON response that is either already serialized or a plain value.
+///
+/// Serializes to the JSON of `T` and deserializes from it, so it can be used in place of `T` in
+/// RPC method signatures.
+#[derive(Clone)]
+pub struct StreamedJson<T> {
+    repr: Repr<T>,
+}
+
+#[derive(Clone)]
+enum Repr<T> {
+    Serialized(Box<RawValue>, PhantomData<fn() -> T>),
+    Value(T),
+}
+
+impl<T> StreamedJson<T> {
+    /// Creates a new instance from an unserialized value.
+    pub const fn new(value: T) -> Self {
+        Self { repr: Repr::Value(value) }
+    }
+
+    /// Returns the serialized JSON if this was written by a [`JsonArrayWriter`] or
+    /// [`JsonMapWriter`].
+    pub fn as_raw(&self) -> Option<&str> {
+        match &self.repr {
+            Repr::Serialized(raw, _) => Some(raw.get()),
+            Repr::Value(_) => None,
+        }
+    }
+
+    /// Returns the value, deserializing it if it was already serialized.
+    pub fn into_value(self) -> serde_json::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        match self.repr {
+            Repr::Serialized(raw, _) => serde_json::from_str(raw.get()),
+            Repr::Value(value) => Ok(value),
+        }
+    }
+
+    fn from_buf(buf: Vec<u8>) -> Self {
+        let json = String::from_utf8(buf).expect("serde_json writes valid utf8");
+        let raw = RawValue::from_string(json).expect("writer produces valid json");
+        Self { repr: Repr::Serialized(raw, PhantomData) }
+    }
+}
+
+impl<T> From<T> for StreamedJson<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for StreamedJson<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.repr {
+            Repr::Serialized(raw, _) => {
+                f.debug_tuple("StreamedJson").field(&raw.get().len()).finish()
+            }
+            Repr::Value(value) => f.debug_tuple("StreamedJson").field(value).finish(),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for StreamedJson<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.repr {
+            Repr::Serialized(raw, _) => raw.serialize(serializer),
+            Repr::Value(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for StreamedJson<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// Writes a JSON array item by item into a buffer of at most `limit` bytes.
+#[derive(Debug)]
+pub struct JsonArrayWriter<T> {
+    buf: Vec<u8>,
+    limit: usize,
+    len: usize,
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T: Serialize> JsonArrayWriter<T> {
+    /// Creates a new writer for a response of at most `limit` bytes.
+    pub fn new(limit: usize) -> Self {
+        Self { buf: vec![b'['], limit, len: 0, _marker: PhantomData }
+    }
+
+    /// Serializes the item and appends it to the array.
+    ///
+    /// Returns [`StreamingJsonError::ResponseTooLarge`] as soon as the array exceeds the limit.
+    pub fn push(&mut self, item: &T) -> Result<(), StreamingJsonError> {
+        if self.len > 0 {
+            self.buf.push(b',');
+        }
+        // reserve one byte for the closing bracket
+        write_bounded(&mut self.buf, self.limit.saturating_sub(1), item)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the number of items in the array.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no items were written.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Closes the array and returns the serialized response.
+    pub fn finish(mut self) -> StreamedJson<Vec<T>> {
+        self.buf.push(b']');
+        StreamedJson::from_buf(self.buf)
+    }
+}
+
+/// Collects the items of a JSON array response.
+///
+/// Implemented by [`Vec`], which keeps the values, and by [`JsonArrayWriter`], which serializes
+/// them right away, so the same code can produce either a typed or a streamed response.
+pub trait JsonArraySink<T> {
+    /// Appends the item to the array.
+    fn append(&mut self, item: T) -> Result<(), StreamingJsonError>;
+}
+
+impl<T> JsonArraySink<T> for Vec<T> {
+    fn append(&mut self, item: T) -> Result<(), StreamingJsonError> {
+        self.push(item);
+        Ok(())
+    }
+}
+
+impl<T: Serialize> JsonArraySink<T> for JsonArrayWriter<T> {
+    fn append(&mut self, item: T) -> Result<(), StreamingJsonError> {
+        self.push(&item)
+    }
+}
+
+/// Writes a JSON object entry by entry into a buffer of at most `limit` bytes.
+#[derive(Debug)]
+pub struct JsonMapWriter {
+    buf: Vec<u8>,
+    limit: usize,
+}
+
+impl JsonMapWriter {
+    /// Creates a new writer for a response of at most `limit` bytes.
+    pub const fn new(limit: usize) -> Self {
+        Self { buf: Vec::new(), limit }
+    }
+
+    /// Serializes the entry and appends it to the object.
+    ///
+    /// Returns [`StreamingJsonError::ResponseTooLarge`] as soon as the object exceeds the limit.
+    pub fn insert<K, V>(&mut self, key: &K, value: &V) -> Result<(), StreamingJsonError>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        // the entry is written as a single entry object `{"key":value}`, which is how serde_json
+        // ensures the key is a valid object key, the braces are then merged into this object
+        let start = self.buf.len();
+        write_bounded(&mut self.buf, self.limit, &Entry(key, value))?;
+        self.buf.pop();
+        if start > 0 {
+            self.buf[start] = b',';
+        }
+        Ok(())
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn written(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Closes the object and returns the serialized response.
+    ///
+    /// The written entries must be the serialized fields of `T`.
+    pub fn finish<T>(mut self) -> StreamedJson<T> {
+        if self.buf.is_empty() {
+            self.buf.push(b'{');
+        }
+        self.buf.push(b'}');
+        StreamedJson::from_buf(self.buf)
+    }
+}
+
+/// A single map entry that serializes as `{"key":value}`.
+struct Entry<'a, K, V>(&'a K, &'a V);
+
+impl<K: Serialize, V: Serialize> Serialize for Entry<'_, K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(self.0, self.1)?;
+        map.end()
+    }
+}
+
+/// Serializes the value into the buffer, failing as soon as the buffer exceeds `limit` bytes.
+fn write_bounded<T: Serialize + ?Sized>(
+    buf: &mut Vec<u8>,
+    limit: usize,
+    value: &T,
+) -> Result<(), StreamingJsonError> {
+    serde_json::to_writer(BoundedWriter { buf, limit }, value).map_err(|err| {
+        if err.is_io() {
+            StreamingJsonError::ResponseTooLarge(limit)
+        } else {
+            StreamingJsonError::Serialization(err)
+        }
+    })
+}
+
+/// A writer that rejects writes beyond `limit` bytes.
+struct BoundedWriter<'a> {
+    buf: &'a mut Vec<u8>,
+    limit: usize,
+}
+
+impl Write for BoundedWriter<'_> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.buf.len() + data.len() > self.limit {
+            return Err(io::Error::new(io::ErrorKind::OutOfMemory, "response too large"))
+        }
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn array_writer_matches_serde() {
+        let items = vec![vec![1u64, 2], vec![], vec![3]];
+        let mut writer = JsonArrayWriter::new(usize::MAX);
+        for item in &items {
+            writer.push(item).unwrap();
+        }
+        let streamed = writer.finish();
+        assert_eq!(streamed.as_raw().unwrap(), serde_json::to_string(&items).unwrap());
+        assert_eq!(
+            serde_json::to_string(&streamed).unwrap(),
+            serde_json::to_string(&items).unwrap()
+        );
+        assert_eq!(streamed.into_value().unwrap(), items);
+
+        let empty = JsonArrayWriter::<u64>::new(usize::MAX).finish();
+        assert_eq!(empty.as_raw().unwrap(), "[]");
+    }
+
+    #[test]
+    fn map_writer_matches_serde() {
+        let map = BTreeMap::from([("a".to_string(), vec![1u64]), ("b".to_string(), vec![2, 3])]);
+        let mut writer = JsonMapWriter::new(usize::MAX);
+        for (key, value) in &map {
+            writer.insert(key, value).unwrap();
+        }
+        let streamed = writer.finish::<BTreeMap<String, Vec<u64>>>();
+        assert_eq!(streamed.as_raw().unwrap(), serde_json::to_string(&map).unwrap());
+        assert_eq!(streamed.into_value().unwrap(), map);
+
+        let empty = JsonMapWriter::new(usize::MAX).finish::<BTreeMap<String, u64>>();
+        assert_eq!(empty.as_raw().unwrap(), "{}");
+    }
+
+    #[test]
+    fn writer_respects_limit() {
+        let mut writer = JsonArrayWriter::new(8);
+        writer.push(&"abc").unwrap();
+        assert!(matches!(writer.push(&"abc"), Err(StreamingJsonError::ResponseTooLarge(_))));
+    }
+
+    #[test]
+    fn sinks_produce_the_same_json() {
+        fn fill<S: JsonArraySink<u64>>(mut sink: S) -> S {
+            for item in [1, 2, 3] {
+                sink.append(item).unwrap();
+            }
+            sink
+        }
+
+        let values = fill(Vec::new());
+        let streamed = fill(JsonArrayWriter::new(usize::MAX)).finish();
+        assert_eq!(streamed.as_raw().unwrap(), serde_json::to_string(&values).unwrap());
+    }
+
+    #[test]
+    fn deserializes_from_value() {
+        let streamed: StreamedJson<Vec<u64>> = serde_json::from_str("[1,2]").unwrap();
+        assert!(streamed.as_raw().is_none());
+        assert_eq!(streamed.into_value().unwrap(), vec![1, 2]);
+    }
+}
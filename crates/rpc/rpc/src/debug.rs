@@ -9,7 +9,7 @@ use crate::{
 };
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::{core::RpcResult, types::ErrorObjectOwned, RpcModule};
 use reth_primitives::{
     revm::env::tx_env_with_recovered, Address, Block, BlockId, BlockNumberOrTag, Bytes,
     TransactionSignedEcRecovered, Withdrawals, B256, U256,
@@ -21,6 +21,7 @@ use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_types::{
     state::EvmOverrides,
+    streaming::{JsonArraySink, JsonArrayWriter},
     trace::geth::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
         GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
@@ -51,7 +52,23 @@ pub struct DebugApi<Provider, Eth> {
 impl<Provider, Eth> DebugApi<Provider, Eth> {
     /// Create a new instance of the [`DebugApi`]
     pub fn new(provider: Provider, eth: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(DebugApiInner { provider, eth_api: eth, blocking_task_guard });
+        Self::with_max_response_size(provider, eth, blocking_task_guard, usize::MAX)
+    }
+
+    /// Create a new instance of the [`DebugApi`] whose streamed block traces abort once the
+    /// serialized response exceeds `max_response_size` bytes, see [`Self::into_streamed_rpc`].
+    pub fn with_max_response_size(
+        provider: Provider,
+        eth: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        max_response_size: usize,
+    ) -> Self {
+        let inner = Arc::new(DebugApiInner {
+            provider,
+            eth_api: eth,
+            blocking_task_guard,
+            max_response_size,
+        });
         Self { inner }
     }
 
@@ -73,18 +90,30 @@ where
         self.inner.blocking_task_guard.clone().acquire_owned().await
     }
 
+    /// Returns a writer for a streamed block trace response.
+    fn trace_writer(&self) -> JsonArrayWriter<TraceResult> {
+        JsonArrayWriter::new(self.inner.max_response_size)
+    }
+
     /// Trace the entire block asynchronously
-    async fn trace_block_with(
+    ///
+    /// Each trace is appended to `results` as soon as the transaction is traced, so a
+    /// [`JsonArrayWriter`] serializes the traces one by one and fails once they exceed its limit.
+    async fn trace_block_with<S>(
         &self,
         at: BlockId,
         transactions: Vec<TransactionSignedEcRecovered>,
         cfg: CfgEnvWithHandlerCfg,
         block_env: BlockEnv,
         opts: GethDebugTracingOptions,
-    ) -> EthResult<Vec<TraceResult>> {
+        mut results: S,
+    ) -> EthResult<S>
+    where
+        S: JsonArraySink<TraceResult> + Send + 'static,
+    {
         if transactions.is_empty() {
             // nothing to trace
-            return Ok(Vec::new())
+            return Ok(results)
         }
 
         // replay all transactions of the block
@@ -92,7 +121,6 @@ where
         self.eth_api()
             .spawn_with_state_at_block(at, move |state| {
                 let block_hash = at.as_block_hash();
-                let mut results = Vec::with_capacity(transactions.len());
                 let mut db = CacheDB::new(StateProviderDatabase::new(state));
                 let mut transactions = transactions.into_iter().enumerate().peekable();
                 while let Some((index, tx)) = transactions.next() {
@@ -113,7 +141,7 @@ where
                         }),
                     )?;
 
-                    results.push(TraceResult::Success { result, tx_hash: Some(tx_hash) });
+                    results.append(TraceResult::Success { result, tx_hash: Some(tx_hash) })?;
                     if transactions.peek().is_some() {
                         // need to apply the state changes of this transaction before executing the
                         // next transaction
@@ -136,6 +164,19 @@ where
         rlp_block: Bytes,
         opts: GethDebugTracingOptions,
     ) -> EthResult<Vec<TraceResult>> {
+        self.trace_raw_block_into(rlp_block, opts, Vec::new()).await
+    }
+
+    /// Replays the given rlp encoded block and appends the trace of each transaction to `results`.
+    async fn trace_raw_block_into<S>(
+        &self,
+        rlp_block: Bytes,
+        opts: GethDebugTracingOptions,
+        results: S,
+    ) -> EthResult<S>
+    where
+        S: JsonArraySink<TraceResult> + Send + 'static,
+    {
         let block =
             Block::decode(&mut rlp_block.as_ref()).map_err(BlockError::RlpDecodeRawBlock)?;
 
@@ -165,7 +206,7 @@ where
                     .collect::<EthResult<Vec<_>>>()?
             };
 
-        self.trace_block_with(parent.into(), transactions, cfg, block_env, opts).await
+        self.trace_block_with(parent.into(), transactions, cfg, block_env, opts, results).await
     }
 
     /// Replays a block and returns the trace of each transaction.
@@ -174,6 +215,19 @@ where
         block_id: BlockId,
         opts: GethDebugTracingOptions,
     ) -> EthResult<Vec<TraceResult>> {
+        self.trace_block_into(block_id, opts, Vec::new()).await
+    }
+
+    /// Replays a block and appends the trace of each transaction to `results`.
+    async fn trace_block_into<S>(
+        &self,
+        block_id: BlockId,
+        opts: GethDebugTracingOptions,
+        results: S,
+    ) -> EthResult<S>
+    where
+        S: JsonArraySink<TraceResult> + Send + 'static,
+    {
         let block_hash = self
             .inner
             .provider
@@ -196,6 +250,7 @@ where
             cfg,
             block_env,
             opts,
+            results,
         )
         .await
     }
@@ -611,6 +666,66 @@ where
     }
 }
 
+impl<Provider, Eth> DebugApi<Provider, Eth>
+where
+    Provider: BlockReaderIdExt + HeaderProvider + ChainSpecProvider + 'static,
+    Eth: EthApiSpec + 'static,
+{
+    /// Returns the `debug` namespace with the block trace methods replaced by handlers that
+    /// serialize each trace as soon as the transaction is traced.
+    ///
+    /// The responses are the same as the ones of the typed [`DebugApiServer`] methods, but the
+    /// traces of the entire block are never materialized at once and the call aborts as soon as
+    /// they exceed the max response size.
+    pub fn into_streamed_rpc(self) -> RpcModule<Self> {
+        let mut module = DebugApiServer::into_rpc(self);
+
+        module.remove_method("debug_traceBlock");
+        module
+            .register_async_method("debug_traceBlock", |params, this, _| async move {
+                let mut params = params.sequence();
+                let rlp_block: Bytes = params.next()?;
+                let opts: Option<GethDebugTracingOptions> = params.optional_next()?;
+                let _permit = this.acquire_trace_permit().await;
+                let results = this
+                    .trace_raw_block_into(rlp_block, opts.unwrap_or_default(), this.trace_writer())
+                    .await?;
+                Ok::<_, ErrorObjectOwned>(results.finish())
+            })
+            .expect("method was removed");
+
+        module.remove_method("debug_traceBlockByHash");
+        module
+            .register_async_method("debug_traceBlockByHash", |params, this, _| async move {
+                let mut params = params.sequence();
+                let block: B256 = params.next()?;
+                let opts: Option<GethDebugTracingOptions> = params.optional_next()?;
+                let _permit = this.acquire_trace_permit().await;
+                let results = this
+                    .trace_block_into(block.into(), opts.unwrap_or_default(), this.trace_writer())
+                    .await?;
+                Ok::<_, ErrorObjectOwned>(results.finish())
+            })
+            .expect("method was removed");
+
+        module.remove_method("debug_traceBlockByNumber");
+        module
+            .register_async_method("debug_traceBlockByNumber", |params, this, _| async move {
+                let mut params = params.sequence();
+                let block: BlockNumberOrTag = params.next()?;
+                let opts: Option<GethDebugTracingOptions> = params.optional_next()?;
+                let _permit = this.acquire_trace_permit().await;
+                let results = this
+                    .trace_block_into(block.into(), opts.unwrap_or_default(), this.trace_writer())
+                    .await?;
+                Ok::<_, ErrorObjectOwned>(results.finish())
+            })
+            .expect("method was removed");
+
+        module
+    }
+}
+
 #[async_trait]
 impl<Provider, Eth> DebugApiServer for DebugApi<Provider, Eth>
 where
@@ -785,8 +900,15 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_chaindbCompact`
+    ///
+    /// Always fails: copying the database without its free pages has to block writers to include
+    /// the latest state, so compaction only runs offline with `reth db compact`.
     async fn debug_chaindb_compact(&self) -> RpcResult<()> {
-        Ok(())
+        Err(internal_rpc_err(
+            "the database can't be compacted while the node is running, stop the node and run \
+             `reth db compact`",
+        ))
     }
 
     async fn debug_chaindb_property(&self, _property: String) -> RpcResult<()> {
@@ -996,4 +1118,6 @@ struct DebugApiInner<Provider, Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
+    /// Maximum size of a streamed block trace response in bytes.
+    max_response_size: usize,
 }
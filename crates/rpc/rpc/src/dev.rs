@@ -0,0 +1,494 @@
+use crate::result::{invalid_params_rpc_err, ToRpcResult};
+use async_trait::async_trait;
+use jsonrpsee::{
+    core::RpcResult,
+    types::{error::ErrorCode, ErrorObjectOwned},
+    RpcModule,
+};
+use reth_auto_seal_consensus::{AccountUpdate, AutoSealHandle};
+use reth_primitives::{Address, Bytes, B256, U256};
+use reth_rpc_api::{AnvilApiServer, HardhatApiServer};
+use reth_rpc_types::{
+    anvil::{Forking, Metadata, MineOptions, NodeInfo},
+    Block,
+};
+use reth_transaction_pool::TransactionPool;
+use std::{sync::Arc, time::Duration};
+use tracing::trace;
+
+/// The maximum number of blocks that can be mined with a single `mine` call.
+const MAX_MINE_BLOCKS: u64 = 10_000;
+
+/// `anvil` methods the dev chain doesn't support, they are not registered.
+const UNSUPPORTED_ANVIL_METHODS: &[&str] = &[
+    "anvil_reset",
+    "anvil_setRpcUrl",
+    "anvil_setChainId",
+    "anvil_setLoggingEnabled",
+    "anvil_setMinGasPrice",
+    "anvil_dumpState",
+    "anvil_loadState",
+    "anvil_nodeInfo",
+    "anvil_metadata",
+    "anvil_mine_detailed",
+    "anvil_enableTraces",
+];
+
+/// `hardhat` methods the dev chain doesn't support, they are not registered.
+const UNSUPPORTED_HARDHAT_METHODS: &[&str] =
+    &["hardhat_metadata", "hardhat_reset", "hardhat_setLoggingEnabled", "hardhat_setMinGasPrice"];
+
+/// `anvil` and `hardhat` API implementation for dev nodes.
+///
+/// State modifications, mining, snapshots and time control are processed in order by the auto
+/// seal mining task that builds the dev chain.
+pub struct DevApi<Pool> {
+    inner: Arc<DevApiInner<Pool>>,
+}
+
+impl<Pool> DevApi<Pool> {
+    /// Creates a new instance of `DevApi`.
+    pub fn new(handle: AutoSealHandle, pool: Pool) -> Self {
+        Self { inner: Arc::new(DevApiInner { handle, pool }) }
+    }
+}
+
+impl<Pool> DevApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Returns the `anvil` namespace without the methods the dev chain doesn't support.
+    pub fn into_anvil_rpc(self) -> RpcModule<Self> {
+        let mut module = AnvilApiServer::into_rpc(self);
+        for &method in UNSUPPORTED_ANVIL_METHODS {
+            module.remove_method(method);
+        }
+        module
+    }
+
+    /// Returns the `hardhat` namespace without the methods the dev chain doesn't support.
+    pub fn into_hardhat_rpc(self) -> RpcModule<Self> {
+        let mut module = HardhatApiServer::into_rpc(self);
+        for &method in UNSUPPORTED_HARDHAT_METHODS {
+            module.remove_method(method);
+        }
+        module
+    }
+}
+
+impl<Pool> DevApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Mines `blocks` blocks, one by default, that are `interval` seconds apart.
+    async fn mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        let blocks = blocks.map_or(1, |blocks| blocks.saturating_to());
+        if blocks > MAX_MINE_BLOCKS {
+            return Err(invalid_params_rpc_err(format!(
+                "cannot mine more than {MAX_MINE_BLOCKS} blocks at once"
+            )))
+        }
+        let interval = interval.map(|interval| interval.saturating_to());
+        self.inner.handle.mine(blocks, interval).await.to_rpc_result()
+    }
+
+    /// Applies the [`AccountUpdate`] to the latest state.
+    async fn update_account(&self, address: Address, update: AccountUpdate) -> RpcResult<()> {
+        self.inner.handle.update_account(address, update).await.to_rpc_result()
+    }
+
+    /// Overwrites the balance of the account.
+    async fn set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        self.update_account(address, AccountUpdate { balance: Some(balance), ..Default::default() })
+            .await
+    }
+
+    /// Overwrites the code of the account.
+    async fn set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        self.update_account(address, AccountUpdate { code: Some(code), ..Default::default() }).await
+    }
+
+    /// Overwrites the nonce of the account.
+    async fn set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        let nonce = u64::try_from(nonce).map_err(|_| invalid_params_rpc_err("nonce too high"))?;
+        self.update_account(address, AccountUpdate { nonce: Some(nonce), ..Default::default() })
+            .await
+    }
+
+    /// Overwrites a single storage slot of the account.
+    async fn set_storage_at(&self, address: Address, slot: U256, value: B256) -> RpcResult<()> {
+        let storage = vec![(B256::from(slot), U256::from_be_bytes(value.0))];
+        self.update_account(address, AccountUpdate { storage, ..Default::default() }).await
+    }
+
+    /// Sets the base fee of the next block.
+    fn set_next_block_base_fee(&self, base_fee: U256) -> RpcResult<()> {
+        let base_fee =
+            u64::try_from(base_fee).map_err(|_| invalid_params_rpc_err("base fee too high"))?;
+        self.inner.handle.set_next_block_base_fee(base_fee);
+        Ok(())
+    }
+
+    /// Removes the transaction from the pool and returns its hash if it existed.
+    fn drop_transaction(&self, tx_hash: B256) -> Option<B256> {
+        self.inner.pool.remove_transactions(vec![tx_hash]).pop().map(|tx| *tx.hash())
+    }
+}
+
+#[async_trait]
+impl<Pool> AnvilApiServer for DevApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Handler for `anvil_impersonateAccount`
+    async fn anvil_impersonate_account(&self, address: Address) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?address, "Serving anvil_impersonateAccount");
+        self.inner.handle.impersonate_account(address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_stopImpersonatingAccount`
+    async fn anvil_stop_impersonating_account(&self, address: Address) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?address, "Serving anvil_stopImpersonatingAccount");
+        self.inner.handle.stop_impersonating_account(address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_autoImpersonateAccount`
+    async fn anvil_auto_impersonate_account(&self, enabled: bool) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", enabled, "Serving anvil_autoImpersonateAccount");
+        self.inner.handle.set_auto_impersonate(enabled);
+        Ok(())
+    }
+
+    /// Handler for `anvil_getAutomine`
+    async fn anvil_get_automine(&self) -> RpcResult<bool> {
+        Ok(self.inner.handle.automine())
+    }
+
+    /// Handler for `anvil_mine`
+    async fn anvil_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?blocks, ?interval, "Serving anvil_mine");
+        self.mine(blocks, interval).await
+    }
+
+    /// Handler for `anvil_setAutomine`
+    async fn anvil_set_automine(&self, enabled: bool) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", enabled, "Serving anvil_setAutomine");
+        self.inner.handle.set_automine(enabled).await.to_rpc_result()
+    }
+
+    /// Handler for `anvil_setIntervalMining`
+    async fn anvil_set_interval_mining(&self, interval: u64) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", interval, "Serving anvil_setIntervalMining");
+        // an interval of 0 disables interval mining
+        let interval = (interval > 0).then(|| Duration::from_secs(interval));
+        self.inner.handle.set_interval_mining(interval).await.to_rpc_result()
+    }
+
+    /// Handler for `anvil_dropTransaction`
+    async fn anvil_drop_transaction(&self, tx_hash: B256) -> RpcResult<Option<B256>> {
+        trace!(target: "rpc::anvil", ?tx_hash, "Serving anvil_dropTransaction");
+        Ok(self.drop_transaction(tx_hash))
+    }
+
+    /// Handler for `anvil_reset`
+    async fn anvil_reset(&self, _fork: Option<Forking>) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setRpcUrl`
+    async fn anvil_set_rpc_url(&self, _url: String) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setBalance`
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?address, ?balance, "Serving anvil_setBalance");
+        self.set_balance(address, balance).await
+    }
+
+    /// Handler for `anvil_setCode`
+    async fn anvil_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?address, "Serving anvil_setCode");
+        self.set_code(address, code).await
+    }
+
+    /// Handler for `anvil_setNonce`
+    async fn anvil_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?address, ?nonce, "Serving anvil_setNonce");
+        self.set_nonce(address, nonce).await
+    }
+
+    /// Handler for `anvil_setStorageAt`
+    async fn anvil_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<bool> {
+        trace!(target: "rpc::anvil", ?address, ?slot, ?value, "Serving anvil_setStorageAt");
+        self.set_storage_at(address, slot, value).await?;
+        Ok(true)
+    }
+
+    /// Handler for `anvil_setCoinbase`
+    async fn anvil_set_coinbase(&self, address: Address) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?address, "Serving anvil_setCoinbase");
+        self.inner.handle.set_coinbase(address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_setChainId`
+    async fn anvil_set_chain_id(&self, _chain_id: u64) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setLoggingEnabled`
+    async fn anvil_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setMinGasPrice`
+    async fn anvil_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setNextBlockBaseFeePerGas`
+    async fn anvil_set_next_block_base_fee_per_gas(&self, base_fee: U256) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?base_fee, "Serving anvil_setNextBlockBaseFeePerGas");
+        self.set_next_block_base_fee(base_fee)
+    }
+
+    /// Handler for `anvil_setTime`
+    async fn anvil_set_time(&self, timestamp: u64) -> RpcResult<u64> {
+        trace!(target: "rpc::anvil", timestamp, "Serving anvil_setTime");
+        let offset = self.inner.handle.set_time(timestamp);
+        Ok(offset.max(0) as u64)
+    }
+
+    /// Handler for `anvil_dumpState`
+    async fn anvil_dump_state(&self) -> RpcResult<Bytes> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_loadState`
+    async fn anvil_load_state(&self, _state: Bytes) -> RpcResult<bool> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_nodeInfo`
+    async fn anvil_node_info(&self) -> RpcResult<NodeInfo> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_metadata`
+    async fn anvil_metadata(&self) -> RpcResult<Metadata> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_snapshot`
+    async fn anvil_snapshot(&self) -> RpcResult<U256> {
+        trace!(target: "rpc::anvil", "Serving anvil_snapshot");
+        self.inner.handle.snapshot().await.to_rpc_result()
+    }
+
+    /// Handler for `anvil_revert`
+    async fn anvil_revert(&self, id: U256) -> RpcResult<bool> {
+        trace!(target: "rpc::anvil", ?id, "Serving anvil_revert");
+        self.inner.handle.revert(id).await.to_rpc_result()
+    }
+
+    /// Handler for `anvil_increaseTime`
+    async fn anvil_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        trace!(target: "rpc::anvil", ?seconds, "Serving anvil_increaseTime");
+        Ok(self.inner.handle.increase_time(seconds.saturating_to()))
+    }
+
+    /// Handler for `anvil_setNextBlockTimestamp`
+    async fn anvil_set_next_block_timestamp(&self, seconds: u64) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", seconds, "Serving anvil_setNextBlockTimestamp");
+        self.inner.handle.set_next_block_timestamp(seconds);
+        Ok(())
+    }
+
+    /// Handler for `anvil_setBlockGasLimit`
+    async fn anvil_set_block_gas_limit(&self, gas_limit: U256) -> RpcResult<bool> {
+        trace!(target: "rpc::anvil", ?gas_limit, "Serving anvil_setBlockGasLimit");
+        let gas_limit =
+            u64::try_from(gas_limit).map_err(|_| invalid_params_rpc_err("gas limit too high"))?;
+        self.inner.handle.set_block_gas_limit(gas_limit);
+        Ok(true)
+    }
+
+    /// Handler for `anvil_setBlockTimestampInterval`
+    async fn anvil_set_block_timestamp_interval(&self, seconds: u64) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", seconds, "Serving anvil_setBlockTimestampInterval");
+        self.inner.handle.set_block_timestamp_interval(Some(seconds));
+        Ok(())
+    }
+
+    /// Handler for `anvil_removeBlockTimestampInterval`
+    async fn anvil_remove_block_timestamp_interval(&self) -> RpcResult<bool> {
+        trace!(target: "rpc::anvil", "Serving anvil_removeBlockTimestampInterval");
+        Ok(self.inner.handle.set_block_timestamp_interval(None))
+    }
+
+    /// Handler for `anvil_mine_detailed`
+    async fn anvil_mine_detailed(&self, _opts: Option<MineOptions>) -> RpcResult<Vec<Block>> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_enableTraces`
+    async fn anvil_enable_traces(&self) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_removePoolTransactions`
+    async fn anvil_remove_pool_transactions(&self, address: Address) -> RpcResult<()> {
+        trace!(target: "rpc::anvil", ?address, "Serving anvil_removePoolTransactions");
+        let hashes = self
+            .inner
+            .pool
+            .get_transactions_by_sender(address)
+            .into_iter()
+            .map(|tx| *tx.hash())
+            .collect();
+        self.inner.pool.remove_transactions(hashes);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Pool> HardhatApiServer for DevApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Handler for `hardhat_dropTransaction`
+    async fn hardhat_drop_transaction(&self, tx_hash: B256) -> RpcResult<bool> {
+        trace!(target: "rpc::hardhat", ?tx_hash, "Serving hardhat_dropTransaction");
+        Ok(self.drop_transaction(tx_hash).is_some())
+    }
+
+    /// Handler for `hardhat_impersonateAccount`
+    async fn hardhat_impersonate_account(&self, address: Address) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?address, "Serving hardhat_impersonateAccount");
+        self.inner.handle.impersonate_account(address);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_getAutomine`
+    async fn hardhat_get_automine(&self) -> RpcResult<bool> {
+        Ok(self.inner.handle.automine())
+    }
+
+    /// Handler for `hardhat_metadata`
+    async fn hardhat_metadata(&self) -> RpcResult<Metadata> {
+        Err(unsupported())
+    }
+
+    /// Handler for `hardhat_mine`
+    async fn hardhat_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?blocks, ?interval, "Serving hardhat_mine");
+        self.mine(blocks, interval).await
+    }
+
+    /// Handler for `hardhat_reset`
+    async fn hardhat_reset(&self, _fork: Option<Forking>) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `hardhat_setBalance`
+    async fn hardhat_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?address, ?balance, "Serving hardhat_setBalance");
+        self.set_balance(address, balance).await
+    }
+
+    /// Handler for `hardhat_setCode`
+    async fn hardhat_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?address, "Serving hardhat_setCode");
+        self.set_code(address, code).await
+    }
+
+    /// Handler for `hardhat_setCoinbase`
+    async fn hardhat_set_coinbase(&self, address: Address) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?address, "Serving hardhat_setCoinbase");
+        self.inner.handle.set_coinbase(address);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_setLoggingEnabled`
+    async fn hardhat_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `hardhat_setMinGasPrice`
+    async fn hardhat_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `hardhat_setNextBlockBaseFeePerGas`
+    async fn hardhat_set_next_block_base_fee_per_gas(
+        &self,
+        base_fee_per_gas: U256,
+    ) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?base_fee_per_gas, "Serving hardhat_setNextBlockBaseFeePerGas");
+        self.set_next_block_base_fee(base_fee_per_gas)
+    }
+
+    /// Handler for `hardhat_setPrevRandao`
+    async fn hardhat_set_prev_randao(&self, prev_randao: B256) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?prev_randao, "Serving hardhat_setPrevRandao");
+        self.inner.handle.set_next_block_prev_randao(prev_randao);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_setNonce`
+    async fn hardhat_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?address, ?nonce, "Serving hardhat_setNonce");
+        self.set_nonce(address, nonce).await
+    }
+
+    /// Handler for `hardhat_setStorageAt`
+    async fn hardhat_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?address, ?slot, ?value, "Serving hardhat_setStorageAt");
+        self.set_storage_at(address, slot, value).await
+    }
+
+    /// Handler for `hardhat_stopImpersonatingAccount`
+    async fn hardhat_stop_impersonating_account(&self, address: Address) -> RpcResult<()> {
+        trace!(target: "rpc::hardhat", ?address, "Serving hardhat_stopImpersonatingAccount");
+        self.inner.handle.stop_impersonating_account(address);
+        Ok(())
+    }
+}
+
+impl<Pool> Clone for DevApi<Pool> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<Pool> std::fmt::Debug for DevApi<Pool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DevApi").finish_non_exhaustive()
+    }
+}
+
+/// The error of methods that are not registered, see [`DevApi::into_anvil_rpc`] and
+/// [`DevApi::into_hardhat_rpc`].
+fn unsupported() -> ErrorObjectOwned {
+    ErrorCode::MethodNotFound.into()
+}
+
+struct DevApiInner<Pool> {
+    /// Handle to the mining task of the dev chain.
+    handle: AutoSealHandle,
+    /// The transaction pool of the node.
+    pool: Pool,
+}
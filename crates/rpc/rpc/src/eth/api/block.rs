@@ -11,7 +11,7 @@ use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{BlockId, TransactionMeta};
 use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
-use reth_rpc_types::{AnyTransactionReceipt, Header, Index, RichBlock};
+use reth_rpc_types::{streaming::JsonArraySink, AnyTransactionReceipt, Header, Index, RichBlock};
 use reth_rpc_types_compat::block::{from_block, uncle_block_from_header};
 use reth_transaction_pool::TransactionPool;
 use std::sync::Arc;
@@ -63,11 +63,34 @@ where
         &self,
         block_id: BlockId,
     ) -> EthResult<Option<Vec<AnyTransactionReceipt>>> {
+        self.block_receipts_into(block_id, Vec::new()).await
+    }
+
+    /// Appends all transaction receipts in the block to `rpc_receipts`.
+    ///
+    /// Each receipt is appended as soon as it is built, so a
+    /// [`JsonArrayWriter`](reth_rpc_types::streaming::JsonArrayWriter) never materializes the
+    /// receipts of the entire block at once.
+    ///
+    /// Returns `None` if the block wasn't found.
+    pub(crate) async fn block_receipts_into<S>(
+        &self,
+        block_id: BlockId,
+        mut rpc_receipts: S,
+    ) -> EthResult<Option<S>>
+    where
+        S: JsonArraySink<AnyTransactionReceipt>,
+    {
         // Fetch block and receipts based on block_id
         let block_and_receipts = if block_id.is_pending() {
-            self.provider()
-                .pending_block_and_receipts()?
-                .map(|(sb, receipts)| (sb, Arc::new(receipts)))
+            // the payload of the node's payload job takes precedence, if configured
+            if let Some(pending) = self.payload_pending_block().await? {
+                Some((pending.block.block.clone(), Arc::new(pending.receipts())))
+            } else {
+                self.provider()
+                    .pending_block_and_receipts()?
+                    .map(|(sb, receipts)| (sb, Arc::new(receipts)))
+            }
         } else if let Some(block_hash) = self.provider().block_hash_for_id(block_id)? {
             self.cache().get_block_and_receipts(block_hash).await?
         } else {
@@ -94,37 +117,32 @@ where
         };
 
         // Build transaction receipts
-        block
-            .body
-            .into_iter()
-            .zip(receipts.iter())
-            .enumerate()
-            .map(|(idx, (tx, receipt))| {
-                let meta = TransactionMeta {
-                    tx_hash: tx.hash,
-                    index: idx as u64,
-                    block_hash,
-                    block_number,
-                    base_fee,
-                    excess_blob_gas,
-                    timestamp,
-                };
-
+        for (idx, (tx, receipt)) in block.body.into_iter().zip(receipts.iter()).enumerate() {
+            let meta = TransactionMeta {
+                tx_hash: tx.hash,
+                index: idx as u64,
+                block_hash,
+                block_number,
+                base_fee,
+                excess_blob_gas,
+                timestamp,
+            };
+
+            #[cfg(feature = "optimism")]
+            let op_tx_meta = self.build_op_tx_meta(&tx, l1_block_info.clone(), block_timestamp)?;
+
+            let rpc_receipt = build_transaction_receipt_with_block_receipts(
+                tx,
+                meta,
+                receipt.clone(),
+                &receipts,
                 #[cfg(feature = "optimism")]
-                let op_tx_meta =
-                    self.build_op_tx_meta(&tx, l1_block_info.clone(), block_timestamp)?;
-
-                build_transaction_receipt_with_block_receipts(
-                    tx,
-                    meta,
-                    receipt.clone(),
-                    &receipts,
-                    #[cfg(feature = "optimism")]
-                    op_tx_meta,
-                )
-            })
-            .collect::<EthResult<Vec<_>>>()
-            .map(Some)
+                op_tx_meta,
+            )?;
+            rpc_receipts.append(rpc_receipt)?;
+        }
+
+        Ok(Some(rpc_receipts))
     }
 
     /// Returns the number transactions in the given block.
@@ -167,6 +185,11 @@ where
         let block_id = block_id.into();
 
         if block_id.is_pending() {
+            // the payload of the node's payload job takes precedence, if configured
+            if let Some(pending) = self.payload_pending_block().await? {
+                return Ok(Some(pending.block.clone()))
+            }
+
             // Pending block can be fetched directly without need for caching
             let maybe_pending = self.provider().pending_block_with_senders()?;
             return if maybe_pending.is_some() {
@@ -5,28 +5,43 @@ use crate::{
         error::{ensure_success, EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
         revm_utils::{
             apply_state_overrides, build_call_evm_env, caller_gas_allowance,
-            cap_tx_gas_limit_with_caller_allowance, get_precompiles, prepare_call_env,
+            cap_tx_gas_limit_with_caller_allowance, create_txn_env, get_precompiles,
+            prepare_call_env,
+        },
+        simulate::{
+            cached_bundle_state, ensure_block_gas, resolve_sim_blocks, sim_call_result,
+            simulated_header, simulated_transaction, EthSimulateError, ResolvedSimBlock,
+            TransferLogInspector,
         },
         EthTransactions,
     },
     EthApi,
 };
-use reth_evm::ConfigureEvm;
+use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
 use reth_network_api::NetworkInfo;
-use reth_primitives::{revm::env::tx_env_with_recovered, BlockId, Bytes, TxKind, U256};
+use reth_primitives::{
+    logs_bloom, proofs,
+    revm::env::{fill_block_env_with_coinbase, tx_env_with_recovered},
+    Block, BlockId, BlockNumberOrTag, BlockWithSenders, Bytes, Receipt, Requests, TxKind,
+    Withdrawals, U256,
+};
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProvider,
+    StateProviderFactory, StateRootProvider,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_types::{
+    simulate::{SimBlock, SimulatePayload, SimulatedBlock, MAX_SIMULATE_BLOCKS},
     state::{EvmOverrides, StateOverride},
-    AccessListWithGasUsed, Bundle, EthCallResponse, StateContext, TransactionRequest,
+    AccessListWithGasUsed, Bundle, EthCallResponse, Log, StateContext, TransactionRequest,
 };
+use reth_rpc_types_compat::block::from_block;
 use reth_transaction_pool::TransactionPool;
 use revm::{
     db::{CacheDB, DatabaseRef},
     primitives::{
-        BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, HaltReason, TransactTo,
+        BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, HaltReason,
+        ResultAndState, SpecId, TransactTo,
     },
     DatabaseCommit,
 };
@@ -174,6 +189,230 @@ where
         .await
     }
 
+    /// Simulates the blocks of the [`SimulatePayload`] on top of the given block
+    /// (`eth_simulateV1`).
+    ///
+    /// Every simulated block is built on top of the previous one, so the state changes of all
+    /// calls carry over to the following calls and blocks.
+    pub async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> EthResult<Vec<SimulatedBlock>> {
+        let SimulatePayload {
+            block_state_calls,
+            trace_transfers,
+            validation,
+            return_full_transactions,
+        } = payload;
+        if block_state_calls.is_empty() {
+            return Err(EthApiError::InvalidParams(String::from("calls are empty.")))
+        }
+        if block_state_calls.len() as u64 > MAX_SIMULATE_BLOCKS {
+            return Err(EthSimulateError::TooManyBlocks.into())
+        }
+
+        // the pending block can't be used as parent, so we simulate on top of the latest block
+        let mut block_id = block_number.unwrap_or_default();
+        if block_id.is_pending() {
+            block_id = BlockNumberOrTag::Latest.into();
+        }
+
+        let parent = self
+            .provider()
+            .sealed_header_by_id(block_id)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let mut total_difficulty =
+            self.provider().header_td_by_number(parent.number)?.unwrap_or_default();
+        let (cfg, _, at) = self.evm_env_at(parent.hash().into()).await?;
+        let blocks = resolve_sim_blocks(block_state_calls, &parent)?;
+        let chain_spec = self.provider().chain_spec();
+
+        let this = self.clone();
+        self.spawn_with_state_at_block(at, move |state| {
+            let mut db = CacheDB::new(StateProviderDatabase::new(state));
+            let mut parent = parent;
+            let mut simulated = Vec::with_capacity(blocks.len());
+            // index of the call in the whole request, used to tell identical calls apart
+            let mut call_index = 0u64;
+
+            for ResolvedSimBlock { number, timestamp, block } in blocks {
+                let SimBlock { block_overrides, state_overrides, calls } = block;
+
+                let mut header = simulated_header(
+                    &parent,
+                    number,
+                    timestamp,
+                    block_overrides.as_ref(),
+                    &chain_spec,
+                    validation,
+                );
+
+                // the total difficulty includes the (possibly overridden) difficulty of this block
+                total_difficulty += header.difficulty;
+
+                let mut cfg = cfg.clone();
+                EvmConfig::fill_cfg_env(&mut cfg, &chain_spec, &header, total_difficulty);
+                if !validation {
+                    // without validation the calls behave like `eth_call`
+                    cfg.disable_eip3607 = true;
+                    cfg.disable_base_fee = true;
+                }
+                let after_merge = cfg.handler_cfg.spec_id >= SpecId::MERGE;
+                let mut block_env = BlockEnv::default();
+                fill_block_env_with_coinbase(
+                    &mut block_env,
+                    &header,
+                    after_merge,
+                    header.beneficiary,
+                );
+
+                if let Some(block_hashes) =
+                    block_overrides.and_then(|overrides| overrides.block_hash)
+                {
+                    db.block_hashes
+                        .extend(block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)))
+                }
+                if let Some(state_overrides) = state_overrides {
+                    apply_state_overrides(state_overrides, &mut db)?;
+                }
+
+                let mut transactions = Vec::with_capacity(calls.len());
+                let mut senders = Vec::with_capacity(calls.len());
+                let mut receipts = Vec::with_capacity(calls.len());
+                let mut call_results = Vec::with_capacity(calls.len());
+                let mut call_logs = Vec::with_capacity(calls.len());
+                let mut cumulative_gas_used = 0u64;
+
+                for request in calls {
+                    let from = request.from.unwrap_or_default();
+                    let gas_limit = ensure_block_gas(
+                        request.gas.map(|gas| gas.try_into().unwrap_or(u64::MAX)),
+                        header.gas_limit.saturating_sub(cumulative_gas_used),
+                    )?;
+                    let nonce = match request.nonce {
+                        Some(nonce) => nonce,
+                        None => db.basic_ref(from)?.unwrap_or_default().nonce,
+                    };
+
+                    let mut tx_env = create_txn_env(&block_env, request.clone())?;
+                    tx_env.gas_limit = gas_limit;
+                    // without validation the nonce check is skipped
+                    tx_env.nonce = validation.then_some(nonce);
+                    let tx = simulated_transaction(
+                        &request,
+                        &tx_env,
+                        nonce,
+                        cfg.chain_id,
+                        from,
+                        call_index,
+                    );
+                    call_index += 1;
+                    let env =
+                        EnvWithHandlerCfg::new_with_cfg_env(cfg.clone(), block_env.clone(), tx_env);
+
+                    let (ResultAndState { result, state }, logs) = if trace_transfers {
+                        let mut inspector = TransferLogInspector::default();
+                        let (res, _) = this.inspect(&mut db, env, &mut inspector)?;
+                        (res, inspector.into_logs())
+                    } else {
+                        let (res, _) = this.transact(&mut db, env)?;
+                        let logs = res.result.logs().to_vec();
+                        (res, logs)
+                    };
+                    db.commit(state);
+
+                    cumulative_gas_used += result.gas_used();
+                    receipts.push(Receipt {
+                        tx_type: tx.tx_type(),
+                        success: result.is_success(),
+                        cumulative_gas_used,
+                        logs: result.logs().to_vec(),
+                        #[cfg(feature = "optimism")]
+                        deposit_nonce: None,
+                        #[cfg(feature = "optimism")]
+                        deposit_receipt_version: None,
+                    });
+                    call_results.push(sim_call_result(result, gas_limit));
+                    call_logs.push(logs);
+                    transactions.push(tx);
+                    senders.push(from);
+                }
+
+                let receipt_refs = receipts.iter().collect::<Vec<_>>();
+                #[cfg(feature = "optimism")]
+                let receipts_root = proofs::calculate_receipt_root_no_memo_optimism(
+                    &receipt_refs,
+                    &chain_spec,
+                    timestamp,
+                );
+                #[cfg(not(feature = "optimism"))]
+                let receipts_root = proofs::calculate_receipt_root_no_memo(&receipt_refs);
+
+                header.transactions_root = proofs::calculate_transaction_root(&transactions);
+                header.receipts_root = receipts_root;
+                header.logs_bloom = logs_bloom(receipts.iter().flat_map(|receipt| &receipt.logs));
+                header.gas_used = cumulative_gas_used;
+                header.state_root = db.db.state_root(&cached_bundle_state(&db))?;
+
+                let sealed = header.clone().seal_slow();
+                let block_hash = sealed.hash();
+                let tx_hashes = transactions.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+
+                let block = Block {
+                    withdrawals: header.withdrawals_root.map(|_| Withdrawals::default()),
+                    requests: header.requests_root.map(|_| Requests::default()),
+                    header,
+                    body: transactions,
+                    ommers: Vec::new(),
+                };
+                let block =
+                    BlockWithSenders::new(block, senders).ok_or(EthApiError::InternalEthError)?;
+                let inner = from_block(
+                    block,
+                    total_difficulty,
+                    return_full_transactions.into(),
+                    Some(block_hash),
+                )?;
+
+                let mut log_index = 0u64;
+                let calls = call_results
+                    .into_iter()
+                    .zip(call_logs)
+                    .enumerate()
+                    .map(|(tx_index, (mut call, logs))| {
+                        call.logs = logs
+                            .into_iter()
+                            .map(|log| {
+                                let log = Log {
+                                    inner: log,
+                                    block_hash: Some(block_hash),
+                                    block_number: Some(number),
+                                    block_timestamp: Some(timestamp),
+                                    transaction_hash: Some(tx_hashes[tx_index]),
+                                    transaction_index: Some(tx_index as u64),
+                                    log_index: Some(log_index),
+                                    removed: false,
+                                };
+                                log_index += 1;
+                                log
+                            })
+                            .collect();
+                        call
+                    })
+                    .collect();
+
+                // make the hash of the simulated block available to `BLOCKHASH`
+                db.block_hashes.insert(U256::from(number), block_hash);
+                parent = sealed;
+                simulated.push(SimulatedBlock { inner, calls });
+            }
+
+            Ok(simulated)
+        })
+        .await
+    }
+
     /// Estimates the gas usage of the `request` with the state.
     ///
     /// This will execute the [`TransactionRequest`] and find the best gas limit via binary search
@@ -530,3 +769,129 @@ fn update_estimated_gas_range(
     };
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{
+        cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig,
+    };
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, Address, Header};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::BlockOverrides;
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_transaction_pool::test_utils::testing_pool;
+    use std::sync::Arc;
+
+    fn transfer(from: Address, to: Address, value: u64) -> TransactionRequest {
+        TransactionRequest {
+            from: Some(from),
+            to: Some(TxKind::Call(to)),
+            value: Some(U256::from(value)),
+            ..Default::default()
+        }
+    }
+
+    fn sim_block(difficulty: u64, calls: Vec<TransactionRequest>) -> SimBlock {
+        SimBlock {
+            block_overrides: Some(BlockOverrides {
+                difficulty: Some(U256::from(difficulty)),
+                ..Default::default()
+            }),
+            state_overrides: None,
+            calls,
+        }
+    }
+
+    #[tokio::test]
+    async fn simulate_v1_carries_state_across_blocks() {
+        let provider = MockEthProvider {
+            chain_spec: Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build()),
+            ..Default::default()
+        };
+        let parent = Header {
+            number: 1,
+            timestamp: 1_000,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            base_fee_per_gas: Some(7),
+            excess_blob_gas: Some(0),
+            blob_gas_used: Some(0),
+            ..Default::default()
+        };
+        let parent_hash = parent.hash_slow();
+        provider.add_block(parent_hash, Block { header: parent.clone(), ..Default::default() });
+        provider.add_header(parent_hash, parent);
+
+        let (alice, bob, carol) = (Address::random(), Address::random(), Address::random());
+        provider.add_account(alice, ExtendedAccount::new(0, U256::from(1_000_000)));
+
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        );
+
+        // bob can only pay carol with the funds he received in the first simulated block
+        let payload = SimulatePayload {
+            block_state_calls: vec![
+                sim_block(10, vec![transfer(alice, bob, 1_000)]),
+                sim_block(20, vec![transfer(bob, carol, 600)]),
+            ],
+            trace_transfers: false,
+            validation: false,
+            return_full_transactions: false,
+        };
+        let blocks =
+            eth_api.simulate_v1(payload, Some(BlockNumberOrTag::Latest.into())).await.unwrap();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].inner.header.number, Some(2));
+        assert_eq!(blocks[1].inner.header.number, Some(3));
+        assert_eq!(blocks[0].inner.header.parent_hash, parent_hash);
+        assert_eq!(Some(blocks[1].inner.header.parent_hash), blocks[0].inner.header.hash);
+        assert!(blocks.iter().flat_map(|block| &block.calls).all(|call| call.status));
+
+        // the total difficulty accumulates the difficulty of every simulated block
+        assert_eq!(blocks[0].inner.header.total_difficulty, Some(U256::from(10)));
+        assert_eq!(blocks[1].inner.header.total_difficulty, Some(U256::from(30)));
+    }
+
+    #[tokio::test]
+    async fn simulate_v1_rejects_empty_payload() {
+        let provider = MockEthProvider::default();
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        );
+
+        let payload = SimulatePayload {
+            block_state_calls: Vec::new(),
+            trace_transfers: false,
+            validation: false,
+            return_full_transactions: false,
+        };
+        let err = eth_api.simulate_v1(payload, None).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+    }
+}
@@ -4,15 +4,18 @@
 use crate::eth::{
     api::{
         fee_history::FeeHistoryCache,
-        pending_block::{PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin},
+        pending_block::{
+            PayloadPendingBlock, PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin,
+        },
     },
     cache::EthStateCache,
     error::{EthApiError, EthResult},
     gas_oracle::GasPriceOracle,
     signer::EthSigner,
-    traits::RawTransactionForwarder,
+    traits::{PendingBlockSource, RawTransactionForwarder},
 };
 use async_trait::async_trait;
+use reth_auto_seal_consensus::AutoSealHandle;
 use reth_chainspec::ChainInfo;
 use reth_errors::{RethError, RethResult};
 use reth_evm::ConfigureEvm;
@@ -95,6 +98,12 @@ impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConf
     pub fn set_eth_raw_transaction_forwarder(&self, forwarder: Arc<dyn RawTransactionForwarder>) {
         self.inner.raw_transaction_forwarder.write().replace(forwarder);
     }
+
+    /// Sets a source for the `pending` block that takes precedence over the block built from the
+    /// local txpool, for example the node's payload builder.
+    pub fn set_pending_block_source(&self, source: Arc<dyn PendingBlockSource>) {
+        self.inner.pending_block_source.write().replace(source);
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
@@ -168,6 +177,9 @@ where
             fee_history_cache,
             evm_config,
             raw_transaction_forwarder: parking_lot::RwLock::new(raw_transaction_forwarder),
+            pending_block_source: Default::default(),
+            payload_pending_block: Default::default(),
+            dev_handle: Default::default(),
         };
 
         Self { inner: Arc::new(inner) }
@@ -328,8 +340,68 @@ where
         Ok(PendingBlockEnv { cfg, block_env, origin })
     }
 
+    /// Returns the in-progress payload of the configured [`PendingBlockSource`], executed on top
+    /// of the latest block.
+    ///
+    /// Returns `None` if no source is configured or if it has no payload on top of the latest
+    /// block.
+    pub(crate) async fn payload_pending_block(
+        &self,
+    ) -> EthResult<Option<Arc<PayloadPendingBlock>>> {
+        let Some(source) = self.inner.pending_block_source.read().clone() else { return Ok(None) };
+        let Some(block) = source.pending_block().await else { return Ok(None) };
+
+        // payloads of jobs for an outdated head are not pending anymore
+        let latest =
+            self.provider().latest_header()?.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        if block.parent_hash != latest.hash() {
+            return Ok(None)
+        }
+
+        // the payload is only re-executed, and its senders recovered, if it changed
+        if let Some(pending) = self.inner.payload_pending_block.read().as_ref() {
+            if pending.block.hash() == block.hash() {
+                return Ok(Some(Arc::clone(pending)))
+            }
+        }
+
+        let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::LATEST);
+        let mut block_env = BlockEnv::default();
+        self.provider().fill_env_with_header(
+            &mut cfg,
+            &mut block_env,
+            &block.header,
+            self.inner.evm_config.clone(),
+        )?;
+
+        let pending = self
+            .on_blocking_task(|this| async move {
+                let block =
+                    block.seal_with_senders().ok_or(EthApiError::InvalidTransactionSignature)?;
+                PayloadPendingBlock::execute(this.provider(), block, cfg, block_env)
+            })
+            .await?;
+        let pending = Arc::new(pending);
+        *self.inner.payload_pending_block.write() = Some(Arc::clone(&pending));
+
+        Ok(Some(pending))
+    }
+
+    /// Returns the state after the cached payload pending block if it has the given hash.
+    pub(crate) fn payload_pending_state(
+        &self,
+        block_hash: B256,
+    ) -> Option<EthResult<StateProviderBox>> {
+        let pending = self.inner.payload_pending_block.read().clone()?;
+        (pending.block.hash() == block_hash).then(|| pending.state_provider(self.provider()))
+    }
+
     /// Returns the locally built pending block
     pub(crate) async fn local_pending_block(&self) -> EthResult<Option<SealedBlockWithSenders>> {
+        if let Some(pending) = self.payload_pending_block().await? {
+            return Ok(Some(pending.block.clone()))
+        }
+
         let pending = self.pending_block_env_and_cfg()?;
         if pending.origin.is_actual_pending() {
             return Ok(pending.origin.into_actual_pending())
@@ -500,4 +572,134 @@ struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     evm_config: EvmConfig,
     /// Allows forwarding received raw transactions
     raw_transaction_forwarder: parking_lot::RwLock<Option<Arc<dyn RawTransactionForwarder>>>,
+    /// Provides the pending block instead of the local txpool, if set
+    pending_block_source: parking_lot::RwLock<Option<Arc<dyn PendingBlockSource>>>,
+    /// Cached pending block of the [`PendingBlockSource`]
+    payload_pending_block: parking_lot::RwLock<Option<Arc<PayloadPendingBlock>>>,
+    /// Handle to the dev chain that receives transactions of impersonated accounts, if set
+    dev_handle: parking_lot::RwLock<Option<AutoSealHandle>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        constants::ETHEREUM_BLOCK_GAS_LIMIT, Block, Header, SealedBlock, Transaction, TxKind,
+        TxLegacy,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_testing_utils::generators::{self, sign_tx_with_random_key_pair};
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+    /// A [`PendingBlockSource`] that returns a fixed block.
+    #[derive(Debug, Default)]
+    struct TestPendingBlockSource(parking_lot::Mutex<Option<SealedBlock>>);
+
+    #[async_trait]
+    impl PendingBlockSource for TestPendingBlockSource {
+        async fn pending_block(&self) -> Option<SealedBlock> {
+            self.0.lock().clone()
+        }
+    }
+
+    fn eth_api(provider: &MockEthProvider) -> EthApi<MockEthProvider, TestPool, (), EthEvmConfig> {
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider.clone(), Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        )
+    }
+
+    /// Adds a latest block to the provider and returns a payload on top of it with a funded
+    /// transfer.
+    fn payload_on_latest(provider: &MockEthProvider) -> SealedBlock {
+        let parent =
+            Header { number: 1, gas_limit: ETHEREUM_BLOCK_GAS_LIMIT, ..Default::default() };
+        let parent_hash = parent.hash_slow();
+        provider.add_block(parent_hash, Block { header: parent.clone(), ..Default::default() });
+        provider.add_header(parent_hash, parent);
+
+        let tx = sign_tx_with_random_key_pair(
+            &mut generators::rng(),
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                nonce: 0,
+                gas_price: 10,
+                gas_limit: 21_000,
+                to: TxKind::Call(Address::random()),
+                value: U256::from(1),
+                input: Default::default(),
+            }),
+        );
+        let sender = tx.recover_signer().unwrap();
+        provider.add_account(sender, ExtendedAccount::new(0, U256::from(1_000_000_000_000u64)));
+
+        Block {
+            header: Header {
+                number: 2,
+                parent_hash,
+                gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+                gas_used: 21_000,
+                ..Default::default()
+            },
+            body: vec![tx],
+            ..Default::default()
+        }
+        .seal_slow()
+    }
+
+    #[tokio::test]
+    async fn payload_pending_block_is_cached_by_hash() {
+        let provider = MockEthProvider::default();
+        let payload = payload_on_latest(&provider);
+        let eth_api = eth_api(&provider);
+        let source = Arc::new(TestPendingBlockSource::default());
+        eth_api.set_pending_block_source(source.clone());
+
+        // no payload yet
+        assert!(eth_api.payload_pending_block().await.unwrap().is_none());
+
+        *source.0.lock() = Some(payload.clone());
+        let pending = eth_api.payload_pending_block().await.unwrap().unwrap();
+        assert_eq!(pending.block.hash(), payload.hash());
+        assert_eq!(pending.block.senders.len(), 1);
+
+        // the same payload isn't executed again
+        let cached = eth_api.payload_pending_block().await.unwrap().unwrap();
+        assert!(Arc::ptr_eq(&pending, &cached));
+
+        // payloads that aren't on top of the latest block aren't pending
+        let mut stale = payload.unseal();
+        stale.header.parent_hash = B256::random();
+        *source.0.lock() = Some(stale.seal_slow());
+        assert!(eth_api.payload_pending_block().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn pending_block_receipts_are_served_from_the_payload() {
+        let provider = MockEthProvider::default();
+        let payload = payload_on_latest(&provider);
+        let eth_api = eth_api(&provider);
+        eth_api.set_pending_block_source(Arc::new(TestPendingBlockSource(
+            parking_lot::Mutex::new(Some(payload.clone())),
+        )));
+
+        let receipts =
+            eth_api.block_receipts(BlockNumberOrTag::Pending.into()).await.unwrap().unwrap();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].transaction_hash, payload.body[0].hash());
+        assert_eq!(receipts[0].block_hash, Some(payload.hash()));
+        assert_eq!(receipts[0].gas_used, 21_000);
+    }
 }
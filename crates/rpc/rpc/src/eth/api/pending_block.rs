@@ -10,10 +10,13 @@ use reth_primitives::{
     revm_primitives::{
         BlockEnv, CfgEnvWithHandlerCfg, EVMError, Env, InvalidTransaction, ResultAndState, SpecId,
     },
-    Block, BlockId, BlockNumberOrTag, Header, IntoRecoveredTransaction, Receipt, Requests,
-    SealedBlockWithSenders, SealedHeader, B256, EMPTY_OMMER_ROOT_HASH, U256,
+    Block, BlockHash, BlockId, BlockNumber, BlockNumberOrTag, Header, IntoRecoveredTransaction,
+    Receipt, Requests, SealedBlockWithSenders, SealedHeader, B256, EMPTY_OMMER_ROOT_HASH, U256,
+};
+use reth_provider::{
+    providers::BundleStateProvider, ChainSpecProvider, ExecutionDataProvider, ExecutionOutcome,
+    StateProviderBox, StateProviderFactory,
 };
-use reth_provider::{ChainSpecProvider, ExecutionOutcome, StateProviderFactory};
 use reth_revm::{
     database::StateProviderDatabase,
     state_change::{
@@ -24,7 +27,7 @@ use reth_revm::{
 use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
 use revm::{db::states::bundle_state::BundleRetention, Database, DatabaseCommit, State};
 use revm_primitives::EnvWithHandlerCfg;
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 /// Configured [`BlockEnv`] and [`CfgEnvWithHandlerCfg`] for a pending block
 #[derive(Debug, Clone)]
@@ -419,6 +422,134 @@ impl PendingBlockEnvOrigin {
     }
 }
 
+/// The in-progress payload of the node's active payload job, used as the pending block.
+///
+/// The payload is executed on top of its parent so that calls on the `pending` tag see the state
+/// after the payload's transactions.
+#[derive(Debug, Clone)]
+pub(crate) struct PayloadPendingBlock {
+    /// The payload block.
+    pub(crate) block: SealedBlockWithSenders,
+    /// Configured [`CfgEnvWithHandlerCfg`] for the payload block.
+    pub(crate) cfg: CfgEnvWithHandlerCfg,
+    /// Configured [`BlockEnv`] for the payload block.
+    pub(crate) block_env: BlockEnv,
+    /// The state changes of the payload block.
+    execution_outcome: Arc<ExecutionOutcome>,
+}
+
+impl PayloadPendingBlock {
+    /// Executes the payload block on top of its parent's state.
+    pub(crate) fn execute<Client>(
+        client: &Client,
+        block: SealedBlockWithSenders,
+        cfg: CfgEnvWithHandlerCfg,
+        block_env: BlockEnv,
+    ) -> EthResult<Self>
+    where
+        Client: StateProviderFactory + ChainSpecProvider,
+    {
+        let state_provider = client.history_by_block_hash(block.parent_hash)?;
+        let state = StateProviderDatabase::new(state_provider);
+        let mut db = State::builder().with_database(state).with_bundle_update().build();
+        let chain_spec = client.chain_spec();
+
+        pre_block_beacon_root_contract_call(
+            &mut db,
+            chain_spec.as_ref(),
+            block.number,
+            &cfg,
+            &block_env,
+            block.parent_beacon_block_root,
+        )?;
+        pre_block_blockhashes_update(
+            &mut db,
+            chain_spec.as_ref(),
+            &block_env,
+            block.number,
+            block.parent_hash,
+        )?;
+
+        let mut cumulative_gas_used = 0;
+        let mut receipts = Vec::with_capacity(block.body.len());
+        for tx in block.clone().into_transactions_ecrecovered() {
+            let env =
+                Env::boxed(cfg.cfg_env.clone(), block_env.clone(), tx_env_with_recovered(&tx));
+            let mut evm = revm::Evm::builder().with_env(env).with_db(&mut db).build();
+            let ResultAndState { result, state } = evm.transact()?;
+            // drop evm to release db reference.
+            drop(evm);
+            db.commit(state);
+
+            cumulative_gas_used += result.gas_used();
+            receipts.push(Some(Receipt {
+                tx_type: tx.tx_type(),
+                success: result.is_success(),
+                cumulative_gas_used,
+                logs: result.into_logs().into_iter().map(Into::into).collect(),
+                #[cfg(feature = "optimism")]
+                deposit_nonce: None,
+                #[cfg(feature = "optimism")]
+                deposit_receipt_version: None,
+            }));
+        }
+
+        let balance_increments = post_block_withdrawals_balance_increments(
+            &chain_spec,
+            block.timestamp,
+            &block.withdrawals.clone().unwrap_or_default(),
+        );
+        db.increment_balances(balance_increments)?;
+        db.merge_transitions(BundleRetention::PlainState);
+
+        let execution_outcome = ExecutionOutcome::new(
+            db.take_bundle(),
+            vec![receipts].into(),
+            block.number,
+            Vec::new(),
+        );
+
+        Ok(Self { block, cfg, block_env, execution_outcome: Arc::new(execution_outcome) })
+    }
+
+    /// Returns the receipts of the payload block's transactions.
+    pub(crate) fn receipts(&self) -> Vec<Receipt> {
+        self.execution_outcome
+            .receipts_by_block(self.block.number)
+            .iter()
+            .map(|receipt| receipt.clone().expect("payload receipts are never pruned"))
+            .collect()
+    }
+
+    /// Returns the state after the payload block.
+    pub(crate) fn state_provider<Client>(&self, client: &Client) -> EthResult<StateProviderBox>
+    where
+        Client: StateProviderFactory,
+    {
+        let parent = client.history_by_block_hash(self.block.parent_hash)?;
+        Ok(Box::new(BundleStateProvider::new(
+            parent,
+            PayloadExecutionData(Arc::clone(&self.execution_outcome)),
+        )))
+    }
+}
+
+/// Provides the [`ExecutionOutcome`] of a [`PayloadPendingBlock`] to a [`BundleStateProvider`].
+#[derive(Debug)]
+struct PayloadExecutionData(Arc<ExecutionOutcome>);
+
+impl ExecutionDataProvider for PayloadExecutionData {
+    fn execution_outcome(&self) -> &ExecutionOutcome {
+        &self.0
+    }
+
+    /// The payload block is not yet part of any chain, so all block hashes are looked up from the
+    /// parent's state.
+    fn block_hash(&self, _block_number: BlockNumber) -> Option<BlockHash> {
+        None
+    }
+}
+
 /// In memory pending block for `pending` tag
 #[derive(Debug)]
 pub(crate) struct PendingBlock {
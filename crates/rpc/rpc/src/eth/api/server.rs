@@ -10,7 +10,7 @@ use crate::{
     result::{internal_rpc_err, ToRpcResult},
 };
 use alloy_dyn_abi::TypedData;
-use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::{core::RpcResult as Result, types::ErrorObjectOwned, RpcModule};
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64};
@@ -21,14 +21,53 @@ use reth_provider::{
 use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
     serde_helpers::JsonStorageKey,
+    simulate::{SimulatePayload, SimulatedBlock},
     state::{EvmOverrides, StateOverride},
+    streaming::JsonArrayWriter,
     AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
     EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index, RichBlock,
-    StateContext, SyncStatus, TransactionRequest, Work,
+    StateContext, SyncStatus, TransactionConditional, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 use tracing::trace;
 
+impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Self: EthApiSpec + EthTransactions,
+    Pool: TransactionPool + 'static,
+    Provider: BlockReader
+        + BlockIdReader
+        + BlockReaderIdExt
+        + ChainSpecProvider
+        + HeaderProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+    EvmConfig: ConfigureEvm + 'static,
+{
+    /// Returns the `eth` namespace with `eth_getBlockReceipts` replaced by a handler that
+    /// serializes each receipt as soon as it is built.
+    ///
+    /// The response is the same as the one of the typed [`EthApiServer::block_receipts`], but it
+    /// aborts as soon as it exceeds `max_response_size` bytes.
+    pub fn into_streamed_rpc(self, max_response_size: usize) -> RpcModule<Self> {
+        let mut module = EthApiServer::into_rpc(self);
+        module.remove_method("eth_getBlockReceipts");
+        module
+            .register_async_method("eth_getBlockReceipts", move |params, this, _| async move {
+                let block_id: BlockId = params.sequence().next()?;
+                trace!(target: "rpc::eth", ?block_id, "Serving eth_getBlockReceipts");
+                let receipts = this
+                    .block_receipts_into(block_id, JsonArrayWriter::new(max_response_size))
+                    .await?;
+                Ok::<_, ErrorObjectOwned>(receipts.map(JsonArrayWriter::finish))
+            })
+            .expect("method was removed");
+        module
+    }
+}
+
 #[async_trait::async_trait]
 impl<Provider, Pool, Network, EvmConfig> EthApiServer for EthApi<Provider, Pool, Network, EvmConfig>
 where
@@ -265,6 +304,16 @@ where
         Ok(Self::rpc_block_header(self, hash).await?)
     }
 
+    /// Handler for: `eth_simulateV1`
+    async fn simulate_v1(
+        &self,
+        opts: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<SimulatedBlock>> {
+        trace!(target: "rpc::eth", ?opts, ?block_number, "Serving eth_simulateV1");
+        Ok(Self::simulate_v1(self, opts, block_number).await?)
+    }
+
     /// Handler for: `eth_call`
     async fn call(
         &self,
@@ -387,6 +436,16 @@ where
         Ok(EthTransactions::send_raw_transaction(self, tx).await?)
     }
 
+    /// Handler for: `eth_sendRawTransactionConditional`
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> Result<B256> {
+        trace!(target: "rpc::eth", ?tx, ?conditional, "Serving eth_sendRawTransactionConditional");
+        Ok(EthTransactions::send_raw_transaction_conditional(self, tx, conditional).await?)
+    }
+
     /// Handler for: `eth_sign`
     async fn sign(&self, address: Address, message: Bytes) -> Result<Bytes> {
         trace!(target: "rpc::eth", ?address, ?message, "Serving eth_sign");
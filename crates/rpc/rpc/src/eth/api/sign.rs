@@ -8,6 +8,7 @@ use crate::{
     EthApi,
 };
 use alloy_dyn_abi::TypedData;
+use reth_auto_seal_consensus::AutoSealHandle;
 use reth_primitives::{Address, Bytes};
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig> {
@@ -38,4 +39,11 @@ impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConf
         let mut signers = self.inner.signers.write();
         *signers = DevSigner::random_signers(20);
     }
+
+    /// Hands transactions of accounts impersonated through the [`AutoSealHandle`] to the dev chain
+    /// directly, bypassing the pool, since they can't be signed.
+    /// Used in DEV mode.
+    pub fn with_impersonation(&self, handle: AutoSealHandle) {
+        self.inner.dev_handle.write().replace(handle);
+    }
 }
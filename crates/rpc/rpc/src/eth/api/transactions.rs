@@ -10,6 +10,7 @@ use crate::{
 };
 use alloy_primitives::TxKind as RpcTransactionKind;
 use async_trait::async_trait;
+use reth_errors::RethError;
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
@@ -29,14 +30,19 @@ use reth_rpc_types::{
     state::EvmOverrides,
     transaction::{
         EIP1559TransactionRequest, EIP2930TransactionRequest, EIP4844TransactionRequest,
-        LegacyTransactionRequest,
+        KnownAccountState, LegacyTransactionRequest, TransactionConditional,
     },
     AnyReceiptEnvelope, AnyTransactionReceipt, Index, Log, ReceiptWithBloom, Transaction,
     TransactionInfo, TransactionReceipt, TransactionRequest, TypedTransactionRequest,
     WithOtherFields,
 };
-use reth_rpc_types_compat::transaction::from_recovered_with_block_context;
-use reth_transaction_pool::{TransactionOrigin, TransactionPool};
+use reth_rpc_types_compat::transaction::{
+    from_recovered_with_block_context, to_primitive_transaction,
+};
+use reth_transaction_pool::{
+    KnownAccountState as PoolKnownAccountState,
+    TransactionConditional as PoolTransactionConditional, TransactionOrigin, TransactionPool,
+};
 use revm::{
     db::CacheDB,
     primitives::{
@@ -53,6 +59,9 @@ use crate::eth::revm_utils::FillableTransaction;
 use reth_rpc_types::OptimismTransactionReceiptFields;
 use revm_primitives::db::{Database, DatabaseRef};
 
+/// The maximum number of storage roots and slots a conditional transaction can depend on.
+const MAX_CONDITIONAL_KNOWN_SLOTS: usize = 1000;
+
 /// Helper alias type for the state's [`CacheDB`]
 pub(crate) type StateCacheDB = CacheDB<StateProviderDatabase<StateProviderBox>>;
 
@@ -261,6 +270,18 @@ pub trait EthTransactions: Send + Sync {
     /// Returns the hash of the transaction.
     async fn send_raw_transaction(&self, tx: Bytes) -> EthResult<B256>;
 
+    /// Decodes and recovers the transaction and submits it to the pool with the given conditions.
+    ///
+    /// The conditions are checked against the latest block and state first, the pool evicts the
+    /// transaction once a new canonical block violates them.
+    ///
+    /// Returns the hash of the transaction.
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> EthResult<B256>;
+
     /// Signs transaction with a matching signer, if any and submits the transaction to the pool.
     /// Returns the hash of the signed transaction.
     async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256>;
@@ -648,6 +669,12 @@ where
     }
 
     fn state_at(&self, at: BlockId) -> EthResult<StateProviderBox> {
+        // the payload pending block is identified by its hash, but not yet part of the chain
+        if let BlockId::Hash(hash) = at {
+            if let Some(state) = self.payload_pending_state(hash.block_hash) {
+                return state
+            }
+        }
         self.state_at_block_id(at)
     }
 
@@ -676,6 +703,14 @@ where
         at: BlockId,
     ) -> EthResult<(CfgEnvWithHandlerCfg, BlockEnv, BlockId)> {
         if at.is_pending() {
+            if let Some(pending) = self.payload_pending_block().await? {
+                return Ok((
+                    pending.cfg.clone(),
+                    pending.block_env.clone(),
+                    pending.block.hash().into(),
+                ))
+            }
+
             let PendingBlockEnv { cfg, block_env, origin } = self.pending_block_env_and_cfg()?;
             Ok((cfg, block_env, origin.state_block_id()))
         } else {
@@ -865,6 +900,54 @@ where
         Ok(hash)
     }
 
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> EthResult<B256> {
+        let known_slots = conditional
+            .known_accounts
+            .values()
+            .map(|state| match state {
+                KnownAccountState::StorageRoot(_) => 1,
+                KnownAccountState::Slots(slots) => slots.len(),
+            })
+            .sum::<usize>();
+        if known_slots > MAX_CONDITIONAL_KNOWN_SLOTS {
+            return Err(EthApiError::InvalidParams(format!(
+                "conditional exceeds the maximum of {MAX_CONDITIONAL_KNOWN_SLOTS} known slots"
+            )))
+        }
+
+        let recovered = recover_raw_transaction(tx)?;
+        let pool_transaction = <Pool::Transaction>::from_recovered_pooled_transaction(recovered);
+
+        // reject the transaction if the latest block already violates its conditions
+        let conditional = into_pool_conditional(conditional);
+        let conditional = self
+            .on_blocking_task(|this| async move {
+                let header =
+                    this.provider().latest_header()?.ok_or(EthApiError::UnknownBlockNumber)?;
+                if conditional.is_expired(header.number, header.timestamp) {
+                    return Err(EthApiError::ConditionalNotMet("block number or timestamp exceeded"))
+                }
+                if !conditional.known_accounts_match(&this.provider().latest()?)? {
+                    return Err(EthApiError::ConditionalNotMet("known accounts mismatch"))
+                }
+                Ok(conditional)
+            })
+            .await?;
+
+        // submit the transaction to the pool with an `External` origin, the pool never
+        // propagates conditional transactions
+        let hash = self
+            .pool()
+            .add_conditional_transaction(TransactionOrigin::External, pool_transaction, conditional)
+            .await?;
+
+        Ok(hash)
+    }
+
     async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<B256> {
         let from = match request.from {
             Some(from) => from,
@@ -1044,6 +1127,19 @@ where
             None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
         };
 
+        // transactions of impersonated accounts can't be signed, they are handed to the dev chain
+        // with their sender instead of entering the pool
+        let dev_handle = self.inner.dev_handle.read().clone();
+        if let Some(handle) = dev_handle.filter(|handle| handle.is_impersonated(&from)) {
+            let transaction = to_primitive_transaction(transaction)
+                .ok_or(SignError::InvalidTransactionRequest)?;
+            let hash = handle
+                .send_impersonated_transaction(transaction, from)
+                .await
+                .map_err(RethError::other)?;
+            return Ok(hash)
+        }
+
         let signed_tx = self.sign_request(&from, transaction)?;
 
         let recovered =
@@ -1592,6 +1688,28 @@ where
     }
 }
 
+/// Converts the RPC representation of transaction conditions into the pool's.
+fn into_pool_conditional(conditional: TransactionConditional) -> PoolTransactionConditional {
+    let known_accounts = conditional
+        .known_accounts
+        .into_iter()
+        .map(|(address, state)| {
+            let state = match state {
+                KnownAccountState::StorageRoot(root) => PoolKnownAccountState::StorageRoot(root),
+                KnownAccountState::Slots(slots) => PoolKnownAccountState::Slots(slots),
+            };
+            (address, state)
+        })
+        .collect();
+    PoolTransactionConditional {
+        known_accounts,
+        block_number_min: conditional.block_number_min,
+        block_number_max: conditional.block_number_max,
+        timestamp_min: conditional.timestamp_min,
+        timestamp_max: conditional.timestamp_max,
+    }
+}
+
 /// Represents from where a transaction was fetched.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TransactionSource {
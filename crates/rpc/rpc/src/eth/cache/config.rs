@@ -9,10 +9,18 @@ pub struct EthStateCacheConfig {
     ///
     /// Default is 5000.
     pub max_blocks: u32,
+    /// Max size of all cached blocks in bytes.
+    ///
+    /// Default is 500MB.
+    pub max_blocks_bytes: usize,
     /// Max number receipts in cache.
     ///
     /// Default is 2000.
     pub max_receipts: u32,
+    /// Max size of all cached receipts in bytes.
+    ///
+    /// Default is 500MB.
+    pub max_receipts_bytes: usize,
     /// Max number of bytes for cached env data.
     ///
     /// Default is 1000.
@@ -21,15 +29,23 @@ pub struct EthStateCacheConfig {
     ///
     /// Default is 512.
     pub max_concurrent_db_requests: usize,
+    /// Number of recent canonical blocks whose blocks and receipts are loaded into the cache on
+    /// startup.
+    ///
+    /// Default is 128.
+    pub prewarm_blocks: u64,
 }
 
 impl Default for EthStateCacheConfig {
     fn default() -> Self {
         Self {
             max_blocks: DEFAULT_BLOCK_CACHE_MAX_LEN,
+            max_blocks_bytes: DEFAULT_BLOCK_CACHE_SIZE_BYTES_MB * 1024 * 1024,
             max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
+            max_receipts_bytes: DEFAULT_RECEIPT_CACHE_SIZE_BYTES_MB * 1024 * 1024,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            prewarm_blocks: DEFAULT_CACHE_PREWARM_BLOCKS,
         }
     }
 }
@@ -0,0 +1,150 @@
+use reth_primitives::{Address, BlockWithSenders, Log, Receipt, B256};
+use schnellru::Limiter;
+use std::{mem::size_of, sync::Arc};
+
+/// Types whose in-memory size can be estimated, used to limit caches by bytes.
+pub trait CacheSize {
+    /// Returns a heuristic for the in-memory size of the value in bytes.
+    fn cache_size(&self) -> usize;
+}
+
+impl CacheSize for BlockWithSenders {
+    fn cache_size(&self) -> usize {
+        self.block.size() + self.senders.capacity() * size_of::<Address>()
+    }
+}
+
+impl CacheSize for Receipt {
+    fn cache_size(&self) -> usize {
+        size_of::<Self>() +
+            self.logs
+                .iter()
+                .map(|log| log.data.topics().len() * size_of::<B256>() + log.data.data.len())
+                .sum::<usize>() +
+            self.logs.capacity() * size_of::<Log>()
+    }
+}
+
+impl<T: CacheSize> CacheSize for Arc<Vec<T>> {
+    fn cache_size(&self) -> usize {
+        self.iter().map(CacheSize::cache_size).sum::<usize>() +
+            (self.capacity() - self.len()) * size_of::<T>()
+    }
+}
+
+/// A [`Limiter`] that limits the cache by both the number of entries and the estimated
+/// [`CacheSize`] of all cached values in bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct ByLengthAndSize {
+    /// Max number of entries.
+    max_length: u32,
+    /// Max size of all cached values in bytes.
+    max_size: usize,
+    /// Current size of all cached values in bytes.
+    size: usize,
+}
+
+impl ByLengthAndSize {
+    /// Creates a new limiter with the given max number of entries and max size in bytes.
+    pub const fn new(max_length: u32, max_size: usize) -> Self {
+        Self { max_length, max_size, size: 0 }
+    }
+
+    /// Returns the current size of all cached values in bytes.
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<K, V: CacheSize> Limiter<K, V> for ByLengthAndSize {
+    type KeyToInsert<'a> = K;
+    type LinkType = u32;
+
+    fn is_over_the_limit(&self, length: usize) -> bool {
+        length > self.max_length as usize || self.size > self.max_size
+    }
+
+    fn on_insert(&mut self, _length: usize, key: K, value: V) -> Option<(K, V)> {
+        let size = value.cache_size();
+        // reject values that could never fit
+        if self.max_length == 0 || size > self.max_size {
+            return None
+        }
+        self.size += size;
+        Some((key, value))
+    }
+
+    fn on_replace(
+        &mut self,
+        _length: usize,
+        _old_key: &mut K,
+        _new_key: K,
+        old_value: &mut V,
+        new_value: &mut V,
+    ) -> bool {
+        // the cache evicts entries until it's within the limit again
+        self.size = self.size.saturating_sub(old_value.cache_size()) + new_value.cache_size();
+        true
+    }
+
+    fn on_removed(&mut self, _key: &mut K, value: &mut V) {
+        self.size = self.size.saturating_sub(value.cache_size());
+    }
+
+    fn on_cleared(&mut self) {
+        self.size = 0;
+    }
+
+    fn on_grow(&mut self, _new_memory_usage: usize) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Bytes;
+    use schnellru::LruMap;
+
+    fn receipts(data_len: usize) -> Arc<Vec<Receipt>> {
+        Arc::new(vec![Receipt {
+            logs: vec![Log::new_unchecked(
+                Address::ZERO,
+                vec![B256::ZERO],
+                Bytes::from(vec![0; data_len]),
+            )],
+            ..Default::default()
+        }])
+    }
+
+    #[test]
+    fn evicts_by_size() {
+        let entry_size = receipts(1000).cache_size();
+        let mut cache = LruMap::new(ByLengthAndSize::new(100, entry_size * 2));
+
+        for key in 0..3u64 {
+            assert!(cache.insert(key, receipts(1000)));
+        }
+
+        // only the two most recent entries fit
+        assert_eq!(cache.len(), 2);
+        assert!(cache.peek(&0).is_none());
+        assert_eq!(cache.limiter().size(), entry_size * 2);
+
+        // values larger than the limit are rejected
+        assert!(!cache.insert(3, receipts(entry_size * 2)));
+
+        cache.remove(&1);
+        assert_eq!(cache.limiter().size(), entry_size);
+    }
+
+    #[test]
+    fn evicts_by_length() {
+        let mut cache = LruMap::new(ByLengthAndSize::new(2, usize::MAX));
+        for key in 0..3u64 {
+            assert!(cache.insert(key, receipts(10)));
+        }
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.limiter().size(), receipts(10).cache_size() * 2);
+    }
+}
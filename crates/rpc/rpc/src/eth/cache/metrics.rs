@@ -6,6 +6,8 @@ use reth_metrics::{metrics::Gauge, Metrics};
 pub(crate) struct CacheMetrics {
     /// The number of entities in the cache.
     pub(crate) cached_count: Gauge,
+    /// The estimated size of all cached entities in bytes, only tracked by size limited caches.
+    pub(crate) cached_bytes: Gauge,
     /// The number of queued consumers.
     pub(crate) queued_consumers_count: Gauge,
     /// The number of cache hits.
@@ -25,10 +25,14 @@ use tokio::sync::{
     oneshot, Semaphore,
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{debug, warn};
 
 mod config;
 pub use config::*;
 
+mod limiter;
+pub use limiter::{ByLengthAndSize, CacheSize};
+
 mod metrics;
 
 mod multi_consumer;
@@ -71,20 +75,27 @@ pub struct EthStateCache {
 
 impl EthStateCache {
     /// Creates and returns both [`EthStateCache`] frontend and the memory bound service.
+    #[allow(clippy::too_many_arguments)]
     fn create<Provider, Tasks, EvmConfig>(
         provider: Provider,
         action_task_spawner: Tasks,
         evm_config: EvmConfig,
         max_blocks: u32,
+        max_blocks_bytes: usize,
         max_receipts: u32,
+        max_receipts_bytes: usize,
         max_envs: u32,
         max_concurrent_db_operations: usize,
     ) -> (Self, EthStateCacheService<Provider, Tasks, EvmConfig>) {
         let (to_service, rx) = unbounded_channel();
         let service = EthStateCacheService {
             provider,
-            full_block_cache: BlockLruCache::new(max_blocks, "blocks"),
-            receipts_cache: ReceiptsLruCache::new(max_receipts, "receipts"),
+            full_block_cache: BlockLruCache::new_with_size(max_blocks, max_blocks_bytes, "blocks"),
+            receipts_cache: ReceiptsLruCache::new_with_size(
+                max_receipts,
+                max_receipts_bytes,
+                "receipts",
+            ),
             evm_env_cache: EnvLruCache::new(max_envs, "evm_env"),
             action_tx: to_service.clone(),
             action_rx: UnboundedReceiverStream::new(rx),
@@ -115,7 +126,8 @@ impl EthStateCache {
     /// Creates a new async LRU backed cache service task and spawns it to a new task via the given
     /// spawner.
     ///
-    /// The cache is memory limited by the given max bytes values.
+    /// The cache is memory limited by the given max bytes values. If configured, the most recent
+    /// canonical blocks and their receipts are loaded into the cache on a blocking task.
     pub fn spawn_with<Provider, Tasks, EvmConfig>(
         provider: Provider,
         config: EthStateCacheConfig,
@@ -127,21 +139,86 @@ impl EthStateCache {
         Tasks: TaskSpawner + Clone + 'static,
         EvmConfig: ConfigureEvm + 'static,
     {
-        let EthStateCacheConfig { max_blocks, max_receipts, max_envs, max_concurrent_db_requests } =
-            config;
+        let EthStateCacheConfig {
+            max_blocks,
+            max_blocks_bytes,
+            max_receipts,
+            max_receipts_bytes,
+            max_envs,
+            max_concurrent_db_requests,
+            prewarm_blocks,
+        } = config;
         let (this, service) = Self::create(
-            provider,
+            provider.clone(),
             executor.clone(),
             evm_config,
             max_blocks,
+            max_blocks_bytes,
             max_receipts,
+            max_receipts_bytes,
             max_envs,
             max_concurrent_db_requests,
         );
+        let rate_limiter = service.rate_limiter.clone();
         executor.spawn_critical("eth state cache", Box::pin(service));
+
+        if prewarm_blocks > 0 {
+            let cache = this.clone();
+            executor.spawn_blocking(Box::pin(async move {
+                if let Err(err) = cache.prewarm(provider, rate_limiter, prewarm_blocks).await {
+                    warn!(target: "rpc::eth_cache", %err, "Failed to prewarm eth state cache");
+                }
+            }));
+        }
+
         this
     }
 
+    /// Loads the blocks and receipts of the last `num_blocks` canonical blocks from the provider
+    /// and inserts them into the cache.
+    ///
+    /// Blocks are inserted oldest first, so the most recent blocks are evicted last. Every block
+    /// is loaded with a permit of the `rate_limiter` of the cache service, so prewarming doesn't
+    /// exceed the limit of concurrent database requests.
+    async fn prewarm<Provider>(
+        &self,
+        provider: Provider,
+        rate_limiter: Arc<Semaphore>,
+        num_blocks: u64,
+    ) -> ProviderResult<()>
+    where
+        Provider: BlockReader,
+    {
+        let best_block = provider.best_block_number()?;
+        let start = best_block.saturating_sub(num_blocks - 1);
+        let mut prewarmed = 0;
+
+        for number in start..=best_block {
+            let _permit = rate_limiter.acquire().await;
+            let Some(block_hash) = provider.block_hash(number)? else { continue };
+            let Some(block) =
+                provider.block_with_senders(number.into(), TransactionVariant::WithHash)?
+            else {
+                continue
+            };
+            let receipts = provider.receipts_by_block(number.into())?;
+
+            let _ = self
+                .to_service
+                .send(CacheAction::BlockWithSendersResult { block_hash, res: Ok(Some(block)) });
+            if let Some(receipts) = receipts {
+                let _ = self.to_service.send(CacheAction::ReceiptsResult {
+                    block_hash,
+                    res: Ok(Some(Arc::new(receipts))),
+                });
+            }
+            prewarmed += 1;
+        }
+
+        debug!(target: "rpc::eth_cache", prewarmed, best_block, "Prewarmed eth state cache");
+        Ok(())
+    }
+
     /// Requests the [Block] for the block hash
     ///
     /// Returns `None` if the block does not exist.
@@ -284,8 +361,8 @@ pub(crate) struct EthStateCacheService<
     Provider,
     Tasks,
     EvmConfig,
-    LimitBlocks = ByLength,
-    LimitReceipts = ByLength,
+    LimitBlocks = ByLengthAndSize,
+    LimitReceipts = ByLengthAndSize,
     LimitEnvs = ByLength,
 > where
     LimitBlocks: Limiter<B256, BlockWithSenders>,
@@ -394,7 +471,9 @@ where
 
     fn update_cached_metrics(&self) {
         self.full_block_cache.update_cached_metrics();
+        self.full_block_cache.update_cached_size_metrics();
         self.receipts_cache.update_cached_metrics();
+        self.receipts_cache.update_cached_size_metrics();
         self.evm_env_cache.update_cached_metrics();
     }
 }
@@ -657,3 +736,38 @@ where
             eth_state_cache.to_service.send(CacheAction::CacheNewCanonicalChain { chain_change });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::Header;
+    use reth_provider::test_utils::MockEthProvider;
+
+    #[tokio::test]
+    async fn prewarm_populates_cache() {
+        let provider = MockEthProvider::default();
+        let mut block_hashes = Vec::new();
+        for number in 0..3 {
+            let block =
+                Block { header: Header { number, ..Default::default() }, ..Default::default() };
+            let block_hash = block.header.hash_slow();
+            provider.add_header(block_hash, block.header.clone());
+            provider.add_block(block_hash, block);
+            block_hashes.push(block_hash);
+        }
+
+        // the cache service reads from an empty provider, so it can only serve cached blocks
+        let cache = EthStateCache::spawn(
+            MockEthProvider::default(),
+            EthStateCacheConfig { prewarm_blocks: 0, ..Default::default() },
+            EthEvmConfig::default(),
+        );
+        cache.prewarm(provider, Arc::new(Semaphore::new(1)), 2).await.unwrap();
+
+        assert!(cache.get_block(block_hashes[0]).await.unwrap().is_none());
+        for block_hash in &block_hashes[1..] {
+            assert!(cache.get_block(*block_hash).await.unwrap().is_some());
+        }
+    }
+}
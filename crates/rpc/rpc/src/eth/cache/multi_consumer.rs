@@ -1,4 +1,7 @@
-use super::metrics::CacheMetrics;
+use super::{
+    limiter::{ByLengthAndSize, CacheSize},
+    metrics::CacheMetrics,
+};
 use schnellru::{ByLength, Limiter, LruMap};
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -111,3 +114,24 @@ where
         }
     }
 }
+
+impl<K, V, S> MultiConsumerLruCache<K, V, ByLengthAndSize, S>
+where
+    K: Hash + Eq,
+    V: CacheSize,
+{
+    /// Creates a new empty map with a given `max_len`, `max_bytes` and metric label.
+    pub fn new_with_size(max_len: u32, max_bytes: usize, cache_id: &str) -> Self {
+        Self {
+            cache: LruMap::new(ByLengthAndSize::new(max_len, max_bytes)),
+            queued: Default::default(),
+            metrics: CacheMetrics::new_with_labels(&[("cache", cache_id.to_string())]),
+        }
+    }
+
+    /// Update the size metric for the inner cache.
+    #[inline]
+    pub fn update_cached_size_metrics(&self) {
+        self.metrics.cached_bytes.set(self.cache.limiter().size() as f64);
+    }
+}
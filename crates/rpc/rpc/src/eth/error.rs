@@ -2,11 +2,15 @@
 
 use crate::result::{internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error_with_code};
 use alloy_sol_types::decode_revert_reason;
-use jsonrpsee::types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObject};
+use jsonrpsee::types::{
+    error::{CALL_EXECUTION_FAILED_CODE, OVERSIZED_RESPONSE_CODE},
+    ErrorObject,
+};
 use reth_errors::RethError;
 use reth_primitives::{revm_primitives::InvalidHeader, Address, Bytes};
 use reth_rpc_types::{
-    error::EthRpcErrorCode, request::TransactionInputError, BlockError, ToRpcError,
+    error::EthRpcErrorCode, request::TransactionInputError, streaming::StreamingJsonError,
+    BlockError, ToRpcError,
 };
 use reth_transaction_pool::error::{
     Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolError, PoolErrorKind,
@@ -122,6 +126,13 @@ pub enum EthApiError {
     /// Error thrown when tracing with a muxTracer fails
     #[error(transparent)]
     MuxTracerError(#[from] MuxError),
+    /// Error thrown when writing a streamed response fails, e.g. because it exceeds the max
+    /// response size
+    #[error(transparent)]
+    StreamingJson(#[from] StreamingJsonError),
+    /// Error thrown when the conditions of a conditional transaction are not met
+    #[error("transaction conditional not met: {0}")]
+    ConditionalNotMet(&'static str),
     /// Any other error
     #[error("{0}")]
     Other(Box<dyn ToRpcError>),
@@ -174,6 +185,13 @@ impl From<EthApiError> for ErrorObject<'static> {
             err @ EthApiError::TransactionInputError(_) => invalid_params_rpc_err(err.to_string()),
             EthApiError::Other(err) => err.to_rpc_error(),
             EthApiError::MuxTracerError(msg) => internal_rpc_err(msg.to_string()),
+            EthApiError::StreamingJson(err @ StreamingJsonError::ResponseTooLarge(_)) => {
+                rpc_error_with_code(OVERSIZED_RESPONSE_CODE, err.to_string())
+            }
+            EthApiError::StreamingJson(err) => internal_rpc_err(err.to_string()),
+            err @ EthApiError::ConditionalNotMet(_) => {
+                rpc_error_with_code(EthRpcErrorCode::TransactionRejected.code(), err.to_string())
+            }
         }
     }
 }
@@ -12,8 +12,8 @@ use core::fmt;
 use async_trait::async_trait;
 use jsonrpsee::{core::RpcResult, server::IdProvider};
 use reth_chainspec::ChainInfo;
-use reth_primitives::{IntoRecoveredTransaction, TxHash};
-use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, ProviderError};
+use reth_primitives::{BlockNumber, IntoRecoveredTransaction, TxHash};
+use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, LogIndexReader, ProviderError};
 use reth_rpc_api::EthFilterApiServer;
 use reth_rpc_types::{
     BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log,
@@ -23,7 +23,7 @@ use reth_rpc_types::{
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::{NewSubpoolTransactionStream, PoolTransaction, TransactionPool};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     iter::StepBy,
     ops::RangeInclusive,
     sync::Arc,
@@ -127,7 +127,7 @@ where
 
 impl<Provider, Pool> EthFilter<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader + BlockIdReader + EvmEnvProvider + LogIndexReader + 'static,
     Pool: TransactionPool + 'static,
     <Pool as TransactionPool>::Transaction: 'static,
 {
@@ -224,7 +224,7 @@ where
 #[async_trait]
 impl<Provider, Pool> EthFilterApiServer for EthFilter<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader + BlockIdReader + EvmEnvProvider + LogIndexReader + 'static,
     Pool: TransactionPool + 'static,
 {
     /// Handler for `eth_newFilter`
@@ -343,7 +343,7 @@ struct EthFilterInner<Provider, Pool> {
 
 impl<Provider, Pool> EthFilterInner<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader + BlockIdReader + EvmEnvProvider + LogIndexReader + 'static,
     Pool: TransactionPool + 'static,
 {
     /// Returns logs matching given filter object.
@@ -436,14 +436,42 @@ where
             return Err(FilterError::InvalidBlockRangeParams)
         }
 
-        if to_block - from_block > self.max_blocks_per_filter {
-            return Err(FilterError::QueryExceedsMaxBlocks(self.max_blocks_per_filter))
-        }
-
         let mut all_logs = Vec::new();
         let filter_params = FilteredParams::new(Some(filter.clone()));
+        let is_multi_block_range = from_block != to_block;
+
+        // look up the matching blocks in the log index for the part of the range it covers, the
+        // remaining blocks are scanned below
+        let mut scan_from_block = from_block;
+        if let Some(indexed_to_block) = self.log_index_range_end(filter, to_block)? {
+            if indexed_to_block >= from_block {
+                for block_number in self.indexed_blocks(filter, from_block..=indexed_to_block)? {
+                    let header = self
+                        .provider
+                        .sealed_header(block_number)?
+                        .ok_or(ProviderError::HeaderNotFound(block_number.into()))?;
+                    self.append_block_logs(
+                        &mut all_logs,
+                        &filter_params,
+                        BlockNumHash::new(block_number, header.hash()),
+                        header.timestamp,
+                        is_multi_block_range,
+                    )
+                    .await?;
+                }
+                scan_from_block = indexed_to_block + 1;
+            }
+        }
+
+        if scan_from_block > to_block {
+            return Ok(all_logs)
+        }
 
-        if (to_block == best_number) && (from_block == best_number) {
+        if to_block - scan_from_block > self.max_blocks_per_filter {
+            return Err(FilterError::QueryExceedsMaxBlocks(self.max_blocks_per_filter))
+        }
+
+        if (to_block == best_number) && (scan_from_block == best_number) {
             // only one block to check and it's the current best block which we can fetch directly
             // Note: In case of a reorg, the best block's hash might have changed, hence we only
             // return early of we were able to fetch the best block's receipts
@@ -471,7 +499,7 @@ where
         // loop over the range of new blocks and check logs if the filter matches the log's bloom
         // filter
         for (from, to) in
-            BlockRangeInclusiveIter::new(from_block..=to_block, self.max_headers_range)
+            BlockRangeInclusiveIter::new(scan_from_block..=to_block, self.max_headers_range)
         {
             let headers = self.provider.headers_range(from..=to)?;
 
@@ -490,32 +518,100 @@ where
                             .ok_or(ProviderError::HeaderNotFound(header.number.into()))?,
                     };
 
-                    if let Some(receipts) = self.eth_cache.get_receipts(block_hash).await? {
-                        append_matching_block_logs(
-                            &mut all_logs,
-                            &self.provider,
-                            &filter_params,
-                            BlockNumHash::new(header.number, block_hash),
-                            &receipts,
-                            false,
-                            header.timestamp,
-                        )?;
-
-                        // size check but only if range is multiple blocks, so we always return all
-                        // logs of a single block
-                        let is_multi_block_range = from_block != to_block;
-                        if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
-                            return Err(FilterError::QueryExceedsMaxResults(
-                                self.max_logs_per_response,
-                            ))
-                        }
-                    }
+                    self.append_block_logs(
+                        &mut all_logs,
+                        &filter_params,
+                        BlockNumHash::new(header.number, block_hash),
+                        header.timestamp,
+                        is_multi_block_range,
+                    )
+                    .await?;
                 }
             }
         }
 
         Ok(all_logs)
     }
+
+    /// Returns the last block up to `to_block` whose logs can be looked up in the log index, or
+    /// `None` if logs are not indexed or the filter matches logs of any address and topic.
+    fn log_index_range_end(
+        &self,
+        filter: &Filter,
+        to_block: u64,
+    ) -> Result<Option<BlockNumber>, FilterError> {
+        if filter.address.is_empty() && filter.topics.iter().all(|topic| topic.is_empty()) {
+            return Ok(None)
+        }
+        Ok(self.provider.log_index_tip()?.map(|tip| tip.min(to_block)))
+    }
+
+    /// Returns the blocks in the range that may contain logs matching the filter, according to
+    /// the log index.
+    ///
+    /// A block matches if it contains logs of any of the filter's addresses and logs with any of
+    /// the topics of each position, like the header bloom check does.
+    fn indexed_blocks(
+        &self,
+        filter: &Filter,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<BTreeSet<BlockNumber>, FilterError> {
+        let mut blocks: Option<BTreeSet<BlockNumber>> = None;
+
+        if !filter.address.is_empty() {
+            let mut matching = BTreeSet::new();
+            for address in filter.address.iter() {
+                matching.extend(self.provider.log_address_blocks(*address, range.clone())?);
+            }
+            blocks = Some(matching);
+        }
+
+        for topic in filter.topics.iter().filter(|topic| !topic.is_empty()) {
+            let mut matching = BTreeSet::new();
+            for topic in topic.iter() {
+                matching.extend(self.provider.log_topic_blocks(*topic, range.clone())?);
+            }
+            blocks = Some(match blocks {
+                Some(blocks) => blocks.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        }
+
+        Ok(blocks.unwrap_or_default())
+    }
+
+    /// Appends the logs of the block that match the filter, if its receipts are available.
+    ///
+    /// Returns an error if the range spans multiple blocks and the number of logs exceeds the
+    /// configured limit.
+    async fn append_block_logs(
+        &self,
+        all_logs: &mut Vec<Log>,
+        filter_params: &FilteredParams,
+        block_num_hash: BlockNumHash,
+        timestamp: u64,
+        is_multi_block_range: bool,
+    ) -> Result<(), FilterError> {
+        if let Some(receipts) = self.eth_cache.get_receipts(block_num_hash.hash).await? {
+            append_matching_block_logs(
+                all_logs,
+                &self.provider,
+                filter_params,
+                block_num_hash,
+                &receipts,
+                false,
+                timestamp,
+            )?;
+
+            // size check but only if range is multiple blocks, so we always return all
+            // logs of a single block
+            if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
+                return Err(FilterError::QueryExceedsMaxResults(self.max_logs_per_response))
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Config for the filter
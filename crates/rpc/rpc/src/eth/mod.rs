@@ -11,6 +11,7 @@ mod logs_utils;
 mod pubsub;
 pub mod revm_utils;
 mod signer;
+pub mod simulate;
 pub mod traits;
 pub(crate) mod utils;
 
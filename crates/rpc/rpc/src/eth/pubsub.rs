@@ -10,17 +10,20 @@ use jsonrpsee::{
 };
 use reth_network_api::NetworkInfo;
 use reth_primitives::{IntoRecoveredTransaction, TxHash};
-use reth_provider::{BlockReader, CanonStateSubscriptions, EvmEnvProvider};
+use reth_provider::{
+    BlockReader, CanonStateSubscriptions, EvmEnvProvider, ForkChoiceStream, ForkChoiceSubscriptions,
+};
 use reth_rpc_api::EthPubSubApiServer;
 use reth_rpc_types::{
     pubsub::{
-        Params, PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
+        CanonicalChainUpdate, DroppedTransaction, DroppedTransactionReason, Params,
+        PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
         SyncStatusMetadata,
     },
     FilteredParams, Header, Log,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::{NewTransactionEvent, TransactionPool};
+use reth_transaction_pool::{FullTransactionEvent, NewTransactionEvent, TransactionPool};
 use serde::Serialize;
 use std::sync::Arc;
 use tokio_stream::{
@@ -74,7 +77,7 @@ impl<Provider, Pool, Events, Network> EthPubSubApiServer
 where
     Provider: BlockReader + EvmEnvProvider + Clone + 'static,
     Pool: TransactionPool + 'static,
-    Events: CanonStateSubscriptions + Clone + 'static,
+    Events: CanonStateSubscriptions + ForkChoiceSubscriptions + Clone + 'static,
     Network: NetworkInfo + Clone + 'static,
 {
     /// Handler for `eth_subscribe`
@@ -104,7 +107,7 @@ async fn handle_accepted<Provider, Pool, Events, Network>(
 where
     Provider: BlockReader + EvmEnvProvider + Clone + 'static,
     Pool: TransactionPool + 'static,
-    Events: CanonStateSubscriptions + Clone + 'static,
+    Events: CanonStateSubscriptions + ForkChoiceSubscriptions + Clone + 'static,
     Network: NetworkInfo + Clone + 'static,
 {
     match kind {
@@ -191,9 +194,37 @@ where
 
             Ok(())
         }
+        SubscriptionKind::CanonicalChainUpdates => {
+            let stream = pubsub
+                .canonical_chain_update_stream()
+                .map(|update| EthSubscriptionResult::CanonicalChainUpdate(Box::new(update)));
+            pipe_from_stream(accepted_sink, stream).await
+        }
+        SubscriptionKind::SafeHeads => {
+            let stream = header_results(pubsub.chain_events.safe_block_stream());
+            pipe_from_stream(accepted_sink, stream).await
+        }
+        SubscriptionKind::FinalizedHeads => {
+            let stream = header_results(pubsub.chain_events.finalized_block_stream());
+            pipe_from_stream(accepted_sink, stream).await
+        }
+        SubscriptionKind::DroppedTransactions => {
+            let stream =
+                pubsub.dropped_transaction_stream().map(EthSubscriptionResult::DroppedTransaction);
+            pipe_from_stream(accepted_sink, stream).await
+        }
     }
 }
 
+/// Maps the headers of a safe or finalized block stream to subscription results.
+fn header_results(stream: ForkChoiceStream) -> impl Stream<Item = EthSubscriptionResult> {
+    stream.map(|header| {
+        EthSubscriptionResult::Header(Box::new(
+            reth_rpc_types_compat::block::from_primitive_with_hash(header).into(),
+        ))
+    })
+}
+
 /// Helper to convert a serde error into an [`ErrorObject`]
 #[derive(Debug, thiserror::Error)]
 #[error("Failed to serialize subscription item: {0}")]
@@ -302,6 +333,33 @@ where
     ) -> impl Stream<Item = NewTransactionEvent<<Pool as TransactionPool>::Transaction>> {
         self.pool.new_pending_pool_transactions_listener()
     }
+
+    /// Returns a stream that yields all transactions that are dropped from the txpool.
+    fn dropped_transaction_stream(&self) -> impl Stream<Item = DroppedTransaction> {
+        self.pool.all_transactions_event_listener().filter_map(|event| {
+            let dropped = match event {
+                FullTransactionEvent::Replaced { transaction, replaced_by } => {
+                    Some(DroppedTransaction {
+                        hash: *transaction.hash(),
+                        reason: DroppedTransactionReason::Replaced,
+                        replaced_by: Some(replaced_by),
+                    })
+                }
+                FullTransactionEvent::Discarded(hash) => Some(DroppedTransaction {
+                    hash,
+                    reason: DroppedTransactionReason::Discarded,
+                    replaced_by: None,
+                }),
+                FullTransactionEvent::Invalid(hash) => Some(DroppedTransaction {
+                    hash,
+                    reason: DroppedTransactionReason::Invalid,
+                    replaced_by: None,
+                }),
+                _ => None,
+            };
+            futures::future::ready(dropped)
+        })
+    }
 }
 
 impl<Provider, Pool, Events, Network> EthPubSubInner<Provider, Pool, Events, Network>
@@ -321,6 +379,28 @@ where
         })
     }
 
+    /// Returns a stream that yields the reverted and committed headers of all canonical chain
+    /// changes.
+    fn canonical_chain_update_stream(&self) -> impl Stream<Item = CanonicalChainUpdate> {
+        self.chain_events.canonical_state_stream().map(|notification| {
+            let reverted = notification
+                .reverted()
+                .map(|chain| {
+                    chain
+                        .headers()
+                        .map(reth_rpc_types_compat::block::from_primitive_with_hash)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let committed = notification
+                .committed()
+                .headers()
+                .map(reth_rpc_types_compat::block::from_primitive_with_hash)
+                .collect();
+            CanonicalChainUpdate { reverted, committed }
+        })
+    }
+
     /// Returns a stream that yields all logs that match the given filter.
     fn log_stream(&self, filter: FilteredParams) -> impl Stream<Item = Log> {
         BroadcastStream::new(self.chain_events.subscribe_to_canonical_state())
@@ -339,3 +419,76 @@ where
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::SealedBlockWithSenders;
+    use reth_provider::{
+        test_utils::{NoopProvider, TestCanonStateSubscriptions},
+        Chain, ExecutionOutcome,
+    };
+    use reth_testing_utils::generators::{self, random_block, Rng};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        PoolTransaction,
+    };
+
+    fn chain<R: Rng>(rng: &mut R, number: u64) -> Arc<Chain> {
+        let block = random_block(rng, number, None, Some(0), Some(0));
+        let block = SealedBlockWithSenders { block, senders: vec![] };
+        Arc::new(Chain::from_block(block, ExecutionOutcome::default(), None))
+    }
+
+    #[tokio::test]
+    async fn canonical_chain_update_stream_yields_reverted_and_committed_headers() {
+        let chain_events = TestCanonStateSubscriptions::default();
+        let pubsub = EthPubSubInner {
+            pool: testing_pool(),
+            provider: NoopProvider::default(),
+            chain_events: chain_events.clone(),
+            network: NoopNetwork::default(),
+        };
+        let mut stream = Box::pin(pubsub.canonical_chain_update_stream());
+        let mut rng = generators::rng();
+
+        let committed = chain(&mut rng, 1);
+        chain_events.add_next_commit(committed.clone());
+        let update = stream.next().await.unwrap();
+        assert!(update.reverted.is_empty());
+        assert_eq!(update.committed.len(), 1);
+        assert_eq!(update.committed[0].hash, Some(committed.tip().hash()));
+
+        let old = chain(&mut rng, 2);
+        let new = chain(&mut rng, 2);
+        chain_events.add_next_reorg(old.clone(), new.clone());
+        let update = stream.next().await.unwrap();
+        assert_eq!(update.reverted.len(), 1);
+        assert_eq!(update.reverted[0].hash, Some(old.tip().hash()));
+        assert_eq!(update.committed.len(), 1);
+        assert_eq!(update.committed[0].hash, Some(new.tip().hash()));
+    }
+
+    #[tokio::test]
+    async fn dropped_transaction_stream_yields_discarded_transactions() {
+        let pool = testing_pool();
+        let pubsub = EthPubSubInner {
+            pool: pool.clone(),
+            provider: NoopProvider::default(),
+            chain_events: TestCanonStateSubscriptions::default(),
+            network: NoopNetwork::default(),
+        };
+        let mut stream = Box::pin(pubsub.dropped_transaction_stream());
+
+        let tx = MockTransaction::eip1559();
+        let hash = *tx.hash();
+        pool.add_external_transaction(tx).await.unwrap();
+        assert_eq!(pool.remove_transactions(vec![hash]).len(), 1);
+
+        let dropped = stream.next().await.unwrap();
+        assert_eq!(dropped.hash, hash);
+        assert_eq!(dropped.reason, DroppedTransactionReason::Discarded);
+        assert_eq!(dropped.replaced_by, None);
+    }
+}
@@ -0,0 +1,560 @@
+//! Helpers for `eth_simulateV1`.
+//!
+//! See also <https://github.com/ethereum/execution-apis/pull/484>
+
+use crate::{
+    eth::error::{EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
+    result::rpc_error_with_code,
+};
+use jsonrpsee::types::ErrorObject;
+use reth_chainspec::ChainSpec;
+use reth_primitives::{
+    address, b256,
+    constants::{BEACON_NONCE, EMPTY_ROOT_HASH},
+    Address, Bytes, Header, SealedHeader, Signature, Transaction, TransactionSigned, TxEip1559,
+    TxKind, TxLegacy, B256, EMPTY_OMMER_ROOT_HASH, U256,
+};
+use reth_rpc_types::{
+    simulate::{SimBlock, SimCallResult, SimulateError, MAX_SIMULATE_BLOCKS},
+    BlockOverrides, ToRpcError, TransactionRequest,
+};
+use revm::{
+    db::{AccountStatus, BundleState, CacheDB},
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+    primitives::{AccountInfo, Bytecode, ExecutionResult, HashMap, Log, TxEnv},
+    Database, EvmContext, Inspector,
+};
+
+/// The address that emits the synthetic ETH transfer logs if `traceTransfers` is enabled.
+pub const TRANSFER_LOG_EMITTER: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+
+/// The topic of the synthetic ETH transfer logs: `keccak256("Transfer(address,address,uint256)")`
+pub const TRANSFER_EVENT_SIGNATURE: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// Error code for a call that was halted by the EVM.
+const SIMULATE_VM_ERROR_CODE: i32 = -32015;
+
+/// Error code for a reverted call.
+const SIMULATE_REVERT_CODE: i32 = 3;
+
+/// The default time between two simulated blocks, if the timestamp is not overridden.
+const SIMULATE_BLOCK_TIME: u64 = 12;
+
+/// Errors specific to `eth_simulateV1`.
+#[derive(Debug, thiserror::Error)]
+pub enum EthSimulateError {
+    /// Thrown if the calls of a simulated block exceed its gas limit.
+    #[error("block gas limit reached")]
+    BlockGasLimitExceeded,
+    /// Thrown if the block numbers of the simulated blocks are not strictly increasing.
+    #[error("block numbers must be in order: {got} <= {parent}")]
+    BlockNumberInvalid {
+        /// The requested block number.
+        got: u64,
+        /// The number of the previous block.
+        parent: u64,
+    },
+    /// Thrown if the timestamps of the simulated blocks are not strictly increasing.
+    #[error("block timestamps must be in order: {got} <= {parent}")]
+    BlockTimestampInvalid {
+        /// The requested timestamp.
+        got: u64,
+        /// The timestamp of the previous block.
+        parent: u64,
+    },
+    /// Thrown if more than [`MAX_SIMULATE_BLOCKS`] blocks would be simulated.
+    #[error("too many blocks, at most {MAX_SIMULATE_BLOCKS} blocks can be simulated")]
+    TooManyBlocks,
+}
+
+impl EthSimulateError {
+    /// Returns the rpc error code for this error.
+    const fn error_code(&self) -> i32 {
+        match self {
+            Self::BlockGasLimitExceeded => -38015,
+            Self::BlockNumberInvalid { .. } => -38020,
+            Self::BlockTimestampInvalid { .. } => -38021,
+            Self::TooManyBlocks => -38026,
+        }
+    }
+}
+
+impl ToRpcError for EthSimulateError {
+    fn to_rpc_error(&self) -> ErrorObject<'static> {
+        rpc_error_with_code(self.error_code(), self.to_string())
+    }
+}
+
+impl From<EthSimulateError> for EthApiError {
+    fn from(err: EthSimulateError) -> Self {
+        Self::other(err)
+    }
+}
+
+/// A [`SimBlock`] with its resolved block number and timestamp.
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedSimBlock {
+    /// The number of the simulated block.
+    pub(crate) number: u64,
+    /// The timestamp of the simulated block.
+    pub(crate) timestamp: u64,
+    /// The requested block.
+    pub(crate) block: SimBlock,
+}
+
+/// Resolves the block number and timestamp of all requested blocks on top of the given parent.
+///
+/// Blocks without a number override are placed right after their predecessor. If a number
+/// override skips block numbers, empty blocks are inserted for the gap.
+pub(crate) fn resolve_sim_blocks(
+    blocks: Vec<SimBlock>,
+    parent: &Header,
+) -> Result<Vec<ResolvedSimBlock>, EthSimulateError> {
+    let mut resolved = Vec::with_capacity(blocks.len());
+    let mut prev_number = parent.number;
+    let mut prev_timestamp = parent.timestamp;
+
+    for block in blocks {
+        let overrides = block.block_overrides.as_ref();
+        let number = match overrides.and_then(|o| o.number) {
+            Some(number) => {
+                let number = number.saturating_to::<u64>();
+                if number <= prev_number {
+                    return Err(EthSimulateError::BlockNumberInvalid {
+                        got: number,
+                        parent: prev_number,
+                    })
+                }
+                number
+            }
+            None => prev_number + 1,
+        };
+
+        if number - parent.number > MAX_SIMULATE_BLOCKS {
+            return Err(EthSimulateError::TooManyBlocks)
+        }
+
+        // fill the gap with empty blocks
+        for gap in prev_number + 1..number {
+            prev_timestamp += SIMULATE_BLOCK_TIME;
+            resolved.push(ResolvedSimBlock {
+                number: gap,
+                timestamp: prev_timestamp,
+                block: SimBlock::default(),
+            });
+        }
+
+        let timestamp = match overrides.and_then(|o| o.time) {
+            Some(time) => {
+                let time = U256::from(time).saturating_to::<u64>();
+                if time <= prev_timestamp {
+                    return Err(EthSimulateError::BlockTimestampInvalid {
+                        got: time,
+                        parent: prev_timestamp,
+                    })
+                }
+                time
+            }
+            None => prev_timestamp + SIMULATE_BLOCK_TIME,
+        };
+
+        resolved.push(ResolvedSimBlock { number, timestamp, block });
+        prev_number = number;
+        prev_timestamp = timestamp;
+    }
+
+    Ok(resolved)
+}
+
+/// Creates the header of a simulated block on top of the given parent.
+///
+/// The roots, the gas used and the bloom are not set and must be filled in once the calls of the
+/// block have been executed.
+///
+/// If `validation` is disabled, the base fee defaults to zero unless it is overridden.
+pub(crate) fn simulated_header(
+    parent: &SealedHeader,
+    number: u64,
+    timestamp: u64,
+    overrides: Option<&BlockOverrides>,
+    chain_spec: &ChainSpec,
+    validation: bool,
+) -> Header {
+    let base_fee_per_gas = parent
+        .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(timestamp))
+        .map(|base_fee| if validation { base_fee } else { 0 });
+
+    let excess_blob_gas = if chain_spec.is_cancun_active_at_timestamp(timestamp) {
+        Some(parent.next_block_excess_blob_gas().unwrap_or_default())
+    } else {
+        None
+    };
+
+    let mut header = Header {
+        parent_hash: parent.hash(),
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        beneficiary: parent.beneficiary,
+        withdrawals_root: chain_spec
+            .is_shanghai_active_at_timestamp(timestamp)
+            .then_some(EMPTY_ROOT_HASH),
+        difficulty: U256::ZERO,
+        number,
+        gas_limit: parent.gas_limit,
+        timestamp,
+        mix_hash: parent.mix_hash,
+        nonce: BEACON_NONCE,
+        base_fee_per_gas,
+        excess_blob_gas,
+        blob_gas_used: excess_blob_gas.map(|_| 0),
+        parent_beacon_block_root: excess_blob_gas.map(|_| B256::ZERO),
+        requests_root: chain_spec
+            .is_prague_active_at_timestamp(timestamp)
+            .then_some(EMPTY_ROOT_HASH),
+        ..Default::default()
+    };
+
+    if let Some(overrides) = overrides {
+        let BlockOverrides {
+            number: _,
+            difficulty,
+            time: _,
+            gas_limit,
+            coinbase,
+            random,
+            base_fee,
+            block_hash: _,
+        } = overrides;
+
+        if let Some(difficulty) = difficulty {
+            header.difficulty = *difficulty;
+        }
+        if let Some(gas_limit) = gas_limit {
+            header.gas_limit = U256::from(*gas_limit).saturating_to();
+        }
+        if let Some(coinbase) = coinbase {
+            header.beneficiary = *coinbase;
+        }
+        if let Some(random) = random {
+            header.mix_hash = *random;
+        }
+        if let Some(base_fee) = base_fee {
+            header.base_fee_per_gas = Some(base_fee.saturating_to());
+        }
+    }
+
+    header
+}
+
+/// Creates the transaction that represents the simulated call in the simulated block.
+///
+/// Simulated calls are not signed, so the sender must be tracked separately. The transaction
+/// carries a placeholder signature made of the sender and the `index` of the call in the request,
+/// so that identical calls still get distinct transaction hashes.
+pub(crate) fn simulated_transaction(
+    request: &TransactionRequest,
+    tx_env: &TxEnv,
+    nonce: u64,
+    chain_id: u64,
+    from: Address,
+    index: u64,
+) -> TransactionSigned {
+    let to = request.to.unwrap_or(TxKind::Create);
+    let transaction = if request.gas_price.is_some() {
+        Transaction::Legacy(TxLegacy {
+            chain_id: Some(chain_id),
+            nonce,
+            gas_price: tx_env.gas_price.saturating_to(),
+            gas_limit: tx_env.gas_limit,
+            to,
+            value: tx_env.value,
+            input: tx_env.data.clone(),
+        })
+    } else {
+        Transaction::Eip1559(TxEip1559 {
+            chain_id,
+            nonce,
+            gas_limit: tx_env.gas_limit,
+            max_fee_per_gas: request
+                .max_fee_per_gas
+                .unwrap_or_else(|| tx_env.gas_price.saturating_to()),
+            max_priority_fee_per_gas: request.max_priority_fee_per_gas.unwrap_or_default(),
+            to,
+            value: tx_env.value,
+            access_list: request.access_list.clone().unwrap_or_default(),
+            input: tx_env.data.clone(),
+        })
+    };
+
+    let signature = Signature {
+        r: U256::from_be_slice(from.as_slice()),
+        s: U256::from(index),
+        odd_y_parity: false,
+    };
+    TransactionSigned::from_transaction_and_signature(transaction, signature)
+}
+
+/// Converts the result of a simulated call into a [`SimCallResult`].
+///
+/// The logs are not set, since they need the block context.
+pub(crate) fn sim_call_result(result: ExecutionResult, gas_limit: u64) -> SimCallResult {
+    let gas_used = result.gas_used();
+    match result {
+        ExecutionResult::Success { output, .. } => SimCallResult {
+            return_data: output.into_data(),
+            gas_used,
+            status: true,
+            ..Default::default()
+        },
+        ExecutionResult::Revert { output, .. } => SimCallResult {
+            return_data: output.clone(),
+            gas_used,
+            status: false,
+            error: Some(SimulateError {
+                code: SIMULATE_REVERT_CODE,
+                message: RevertError::new(output.clone()).to_string(),
+                data: (!output.is_empty()).then_some(output),
+            }),
+            ..Default::default()
+        },
+        ExecutionResult::Halt { reason, .. } => SimCallResult {
+            return_data: Bytes::new(),
+            gas_used,
+            status: false,
+            error: Some(SimulateError {
+                code: SIMULATE_VM_ERROR_CODE,
+                message: RpcInvalidTransactionError::halt(reason, gas_limit).to_string(),
+                data: None,
+            }),
+            ..Default::default()
+        },
+    }
+}
+
+/// Creates a [`BundleState`] from all accounts that are cached in the [`CacheDB`].
+///
+/// The [`CacheDB`] keeps every account that was loaded or modified during the simulation, which
+/// is enough to compute the state root of the simulated blocks on top of the underlying state.
+/// Accounts with cleared storage are marked as destroyed, so their storage is wiped.
+pub(crate) fn cached_bundle_state<DB>(db: &CacheDB<DB>) -> BundleState {
+    let state = db.accounts.iter().map(|(address, account)| {
+        let storage = account
+            .storage
+            .iter()
+            .map(|(slot, value)| (*slot, (U256::ZERO, *value)))
+            .collect::<HashMap<_, _>>();
+        (*address, None::<AccountInfo>, account.info(), storage)
+    });
+    let reverts: Vec<Vec<(Address, Option<Option<AccountInfo>>, Vec<(U256, U256)>)>> = Vec::new();
+    let contracts: Vec<(B256, Bytecode)> = Vec::new();
+    let mut bundle = BundleState::new(state, reverts, contracts);
+
+    for (address, account) in &db.accounts {
+        if account.account_state.is_storage_cleared() {
+            if let Some(bundle_account) = bundle.state.get_mut(address) {
+                bundle_account.status = AccountStatus::DestroyedChanged;
+            }
+        }
+    }
+
+    bundle
+}
+
+/// An [Inspector] that records all logs of a call in execution order, together with synthetic
+/// logs for every ETH transfer.
+///
+/// The synthetic transfer logs are emitted by [`TRANSFER_LOG_EMITTER`] with the ERC-20 `Transfer`
+/// event signature. Logs of reverted call frames are discarded.
+#[derive(Debug, Default)]
+pub struct TransferLogInspector {
+    /// All recorded logs, in execution order.
+    logs: Vec<Log>,
+    /// The number of logs at the start of each active call frame.
+    checkpoints: Vec<usize>,
+}
+
+impl TransferLogInspector {
+    /// Consumes the inspector and returns the recorded logs.
+    pub fn into_logs(self) -> Vec<Log> {
+        self.logs
+    }
+
+    /// Creates the synthetic log for an ETH transfer.
+    fn transfer_log(from: Address, to: Address, value: U256) -> Log {
+        Log::new_unchecked(
+            TRANSFER_LOG_EMITTER,
+            vec![TRANSFER_EVENT_SIGNATURE, from.into_word(), to.into_word()],
+            Bytes::from(value.to_be_bytes::<32>()),
+        )
+    }
+
+    /// Closes the current call frame and discards its logs if it failed.
+    fn end_frame(&mut self, success: bool) -> usize {
+        let checkpoint = self.checkpoints.pop().unwrap_or_default();
+        if !success {
+            self.logs.truncate(checkpoint);
+        }
+        checkpoint
+    }
+}
+
+impl<DB> Inspector<DB> for TransferLogInspector
+where
+    DB: Database,
+{
+    fn log(&mut self, _context: &mut EvmContext<DB>, log: &Log) {
+        self.logs.push(log.clone());
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.checkpoints.push(self.logs.len());
+        if inputs.transfers_value() && !inputs.call_value().is_zero() {
+            self.logs.push(Self::transfer_log(
+                inputs.caller,
+                inputs.target_address,
+                inputs.call_value(),
+            ));
+        }
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end_frame(outcome.result.is_ok());
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        // the address of the created contract is only known once the frame ends
+        self.checkpoints.push(self.logs.len());
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        let checkpoint = self.end_frame(outcome.result.is_ok());
+        if let Some(address) = outcome.address.filter(|_| outcome.result.is_ok()) {
+            if !inputs.value.is_zero() {
+                // the value is transferred before any logs of the init code are emitted
+                self.logs
+                    .insert(checkpoint, Self::transfer_log(inputs.caller, address, inputs.value));
+            }
+        }
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if !value.is_zero() {
+            self.logs.push(Self::transfer_log(contract, target, value));
+        }
+    }
+}
+
+/// Returns the gas limit for a simulated call.
+///
+/// Defaults to the remaining gas of the simulated block and errors if the requested gas limit
+/// exceeds it.
+pub(crate) fn ensure_block_gas(request_gas: Option<u64>, remaining: u64) -> EthResult<u64> {
+    match request_gas {
+        Some(gas) if gas > remaining => Err(EthSimulateError::BlockGasLimitExceeded.into()),
+        Some(gas) => Ok(gas),
+        None => Ok(remaining),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent() -> Header {
+        Header { number: 10, timestamp: 100, ..Default::default() }
+    }
+
+    fn block_at(number: Option<u64>, time: Option<u64>) -> SimBlock {
+        let overrides = BlockOverrides {
+            number: number.map(U256::from),
+            time: time.map(Into::into),
+            ..Default::default()
+        };
+        SimBlock::with_block_overrides(overrides)
+    }
+
+    #[test]
+    fn resolve_sequential_blocks() {
+        let blocks = vec![SimBlock::default(), SimBlock::default()];
+        let resolved = resolve_sim_blocks(blocks, &parent()).unwrap();
+        assert_eq!(resolved.len(), 2);
+        assert_eq!((resolved[0].number, resolved[0].timestamp), (11, 112));
+        assert_eq!((resolved[1].number, resolved[1].timestamp), (12, 124));
+    }
+
+    #[test]
+    fn resolve_fills_gaps() {
+        let blocks = vec![block_at(Some(14), None)];
+        let resolved = resolve_sim_blocks(blocks, &parent()).unwrap();
+        let numbers = resolved.iter().map(|b| b.number).collect::<Vec<_>>();
+        assert_eq!(numbers, vec![11, 12, 13, 14]);
+        assert!(resolved[..3].iter().all(|b| b.block.calls.is_empty()));
+    }
+
+    #[test]
+    fn resolve_rejects_invalid_order() {
+        let blocks = vec![block_at(Some(12), None), block_at(Some(12), None)];
+        assert!(matches!(
+            resolve_sim_blocks(blocks, &parent()),
+            Err(EthSimulateError::BlockNumberInvalid { got: 12, parent: 12 })
+        ));
+
+        let blocks = vec![block_at(None, Some(100))];
+        assert!(matches!(
+            resolve_sim_blocks(blocks, &parent()),
+            Err(EthSimulateError::BlockTimestampInvalid { got: 100, parent: 100 })
+        ));
+
+        let blocks = vec![block_at(Some(10 + MAX_SIMULATE_BLOCKS + 1), None)];
+        assert!(matches!(
+            resolve_sim_blocks(blocks, &parent()),
+            Err(EthSimulateError::TooManyBlocks)
+        ));
+    }
+
+    #[test]
+    fn simulated_transactions_have_distinct_hashes() {
+        let request = TransactionRequest::default();
+        let tx_env = TxEnv::default();
+        let from = Address::with_last_byte(1);
+
+        let first = simulated_transaction(&request, &tx_env, 0, 1, from, 0);
+        let second = simulated_transaction(&request, &tx_env, 0, 1, from, 1);
+        let other_sender =
+            simulated_transaction(&request, &tx_env, 0, 1, Address::with_last_byte(2), 0);
+
+        assert_eq!(first.transaction, second.transaction);
+        assert_ne!(first.hash(), second.hash());
+        assert_ne!(first.hash(), other_sender.hash());
+        assert_eq!(first.hash(), simulated_transaction(&request, &tx_env, 0, 1, from, 0).hash());
+    }
+
+    #[test]
+    fn block_gas_check() {
+        assert_eq!(ensure_block_gas(None, 100).unwrap(), 100);
+        assert_eq!(ensure_block_gas(Some(50), 100).unwrap(), 50);
+        assert!(ensure_block_gas(Some(101), 100).is_err());
+    }
+}
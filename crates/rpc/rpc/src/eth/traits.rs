@@ -1,7 +1,11 @@
 //! Additional helper traits that allow for more customization.
 
 use crate::eth::error::EthResult;
+use reth_payload_builder::PayloadStore;
+use reth_payload_primitives::{BuiltPayload, PayloadTypes};
+use reth_primitives::SealedBlock;
 use std::fmt;
+use tracing::debug;
 
 /// A trait that allows for forwarding raw transactions.
 ///
@@ -11,3 +15,31 @@ pub trait RawTransactionForwarder: fmt::Debug + Send + Sync + 'static {
     /// Forwards raw transaction bytes for `eth_sendRawTransaction`
     async fn forward_raw_transaction(&self, raw: &[u8]) -> EthResult<()>;
 }
+
+/// A trait that provides the pending block instead of building it from the local txpool.
+///
+/// For example the in-progress payload of the node's payload builder.
+#[async_trait::async_trait]
+pub trait PendingBlockSource: fmt::Debug + Send + Sync + 'static {
+    /// Returns the current pending block, if any.
+    ///
+    /// The senders are only recovered by the caller once a block isn't cached already.
+    async fn pending_block(&self) -> Option<SealedBlock>;
+}
+
+#[async_trait::async_trait]
+impl<Engine> PendingBlockSource for PayloadStore<Engine>
+where
+    Engine: PayloadTypes + 'static,
+{
+    async fn pending_block(&self) -> Option<SealedBlock> {
+        let payload = match self.best_pending_payload().await? {
+            Ok(payload) => payload,
+            Err(err) => {
+                debug!(target: "rpc::eth", %err, "failed to get best pending payload");
+                return None
+            }
+        };
+        Some(payload.block().clone())
+    }
+}
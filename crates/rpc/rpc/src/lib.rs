@@ -34,24 +34,30 @@ use tower as _;
 
 mod admin;
 mod debug;
+mod dev;
 mod engine;
 pub mod eth;
+pub mod mev;
 mod net;
 mod otterscan;
 mod reth;
 mod rpc;
 mod trace;
 mod txpool;
+mod validation;
 mod web3;
 pub use admin::AdminApi;
 pub use debug::DebugApi;
+pub use dev::DevApi;
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
+pub use mev::MevApi;
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
 pub use rpc::RPCApi;
-pub use trace::TraceApi;
+pub use trace::{TraceApi, TraceFilterConfig};
 pub use txpool::TxPoolApi;
+pub use validation::{ValidationApi, ValidationApiConfig, ValidationApiError};
 pub use web3::Web3Api;
 pub mod result;
@@ -0,0 +1,566 @@
+//! `mev` namespace implementation: MEV-Share bundle submission and simulation.
+
+use crate::eth::{
+    error::{EthApiError, EthResult, RpcInvalidTransactionError},
+    revm_utils::FillableTransaction,
+    utils::recover_raw_transaction,
+    EthTransactions,
+};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_chainspec::ChainSpec;
+use reth_payload_builder::bundle_pool::{BundlePool, MevBundle, MevBundleItem, MevBundleRefund};
+use reth_primitives::{Address, U256};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_api::MevApiServer;
+use reth_rpc_types::{
+    BlockId, BlockNumberOrTag, BundleItem, Log, SendBundleRequest, SendBundleResponse,
+    SimBundleLogs, SimBundleOverrides, SimBundleResponse, Validity,
+};
+use reth_tasks::pool::BlockingTaskGuard;
+use reth_transaction_pool::{PoolTransaction, TransactionPool};
+use revm::{
+    db::CacheDB,
+    primitives::{EVMError, ResultAndState, TxEnv},
+    Database, DatabaseCommit, Evm,
+};
+use revm_primitives::EnvWithHandlerCfg;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::trace;
+
+/// Maximum number of items in the body of a bundle.
+pub const MAX_BUNDLE_BODY_SIZE: usize = 50;
+
+/// Maximum nesting depth of bundles, a bundle may contain bundles that contain no bundles.
+pub const MAX_BUNDLE_NESTING_DEPTH: usize = 1;
+
+/// Maximum number of blocks a bundle can be valid for.
+pub const MAX_BUNDLE_BLOCK_RANGE: u64 = 30;
+
+/// Default timeout of `mev_simBundle` in seconds.
+pub const DEFAULT_SIM_BUNDLE_TIMEOUT_SECS: u64 = 5;
+
+/// `mev` API implementation.
+///
+/// Bundles submitted via `mev_sendBundle` are added to a [`BundlePool`] that payload builders can
+/// include bundles from.
+pub struct MevApi<Eth, Pool> {
+    /// All nested fields bundled together.
+    inner: Arc<MevApiInner<Eth, Pool>>,
+}
+
+impl<Eth, Pool> MevApi<Eth, Pool> {
+    /// Create a new `MevApi` instance.
+    pub fn new(
+        eth_api: Eth,
+        pool: Pool,
+        bundle_pool: BundlePool,
+        chain_spec: Arc<ChainSpec>,
+        blocking_task_guard: BlockingTaskGuard,
+    ) -> Self {
+        Self {
+            inner: Arc::new(MevApiInner {
+                eth_api,
+                pool,
+                bundle_pool,
+                chain_spec,
+                blocking_task_guard,
+            }),
+        }
+    }
+
+    /// Returns the pool the bundles are added to.
+    pub fn bundle_pool(&self) -> &BundlePool {
+        &self.inner.bundle_pool
+    }
+}
+
+impl<Eth, Pool> MevApi<Eth, Pool>
+where
+    Eth: EthTransactions + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Validates the bundle and adds it to the bundle pool.
+    pub fn send_bundle(&self, request: SendBundleRequest) -> EthResult<SendBundleResponse> {
+        let bundle = self.parse_bundle(request, 0)?;
+        let bundle_hash = bundle.hash;
+        self.inner
+            .bundle_pool
+            .add_bundle(bundle)
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+        Ok(SendBundleResponse { bundle_hash })
+    }
+
+    /// Simulates the bundle on top of the parent block of the [`SimBundleOverrides`].
+    ///
+    /// The bundle is executed even if it's not valid for the simulated block number, but it must
+    /// be fully matched: all backrun hashes must be known to the bundle pool or transaction pool.
+    pub async fn sim_bundle(
+        &self,
+        request: SendBundleRequest,
+        overrides: SimBundleOverrides,
+    ) -> EthResult<SimBundleResponse> {
+        let bundle = self.parse_bundle(request, 0)?;
+        if !bundle.is_fully_matched() {
+            return Err(EthApiError::InvalidParams(MevBundleError::NotFullyMatched.to_string()))
+        }
+
+        let SimBundleOverrides {
+            parent_block,
+            block_number,
+            coinbase,
+            timestamp,
+            gas_limit,
+            base_fee,
+            timeout,
+        } = overrides;
+        let parent_block = parent_block.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let (cfg, mut block_env, at) = self.inner.eth_api.evm_env_at(parent_block).await?;
+
+        let state_block = block_env.number.to::<u64>();
+        let block = block_number.unwrap_or(state_block + 1);
+        block_env.number = U256::from(block);
+        if let Some(timestamp) = timestamp {
+            block_env.timestamp = U256::from(timestamp);
+        } else {
+            block_env.timestamp += U256::from(12);
+        }
+        if let Some(coinbase) = coinbase {
+            block_env.coinbase = coinbase;
+        }
+        if let Some(gas_limit) = gas_limit {
+            block_env.gas_limit = U256::from(gas_limit);
+        }
+        if let Some(base_fee) = base_fee {
+            block_env.basefee = U256::from(base_fee);
+        } else {
+            // the env is the one of the parent block, the simulated block pays the base fee that
+            // follows from the parent
+            let parent =
+                self.inner.eth_api.block_by_id(at).await?.ok_or(EthApiError::UnknownBlockNumber)?;
+            let base_fee_params =
+                self.inner.chain_spec.base_fee_params_at_timestamp(block_env.timestamp.to());
+            if let Some(base_fee) = parent.header.next_block_base_fee(base_fee_params) {
+                block_env.basefee = U256::from(base_fee);
+            }
+        }
+
+        let timeout = Duration::from_secs(timeout.unwrap_or(DEFAULT_SIM_BUNDLE_TIMEOUT_SECS));
+        let ctx = SimContext {
+            coinbase: block_env.coinbase,
+            block,
+            deadline: Instant::now() + timeout,
+            timeout,
+        };
+
+        trace!(target: "rpc::mev", hash = ?bundle.hash, block, state_block, "Simulating bundle");
+
+        let _permit = self.inner.blocking_task_guard.clone().acquire_owned().await;
+        self.inner
+            .eth_api
+            .spawn_with_state_at_block(at, move |state| {
+                let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default());
+                let db = CacheDB::new(StateProviderDatabase::new(state));
+                let mut evm = Evm::builder().with_db(db).with_env_with_handler_cfg(env).build();
+
+                let response = match execute_bundle(&mut evm, &bundle, &ctx) {
+                    Ok(execution) => SimBundleResponse {
+                        success: true,
+                        error: None,
+                        state_block,
+                        mev_gas_price: execution
+                            .profit
+                            .checked_div(U256::from(execution.gas_used))
+                            .unwrap_or_default()
+                            .saturating_to(),
+                        profit: execution.profit.saturating_to(),
+                        refundable_value: execution.refundable_value.saturating_to(),
+                        gas_used: execution.gas_used,
+                        logs: Some(execution.logs),
+                    },
+                    Err(SimBundleError::Invalid(error)) => SimBundleResponse {
+                        success: false,
+                        error: Some(error),
+                        state_block,
+                        mev_gas_price: 0,
+                        profit: 0,
+                        refundable_value: 0,
+                        gas_used: 0,
+                        logs: None,
+                    },
+                    Err(SimBundleError::Eth(err)) => return Err(err),
+                };
+
+                Ok(response)
+            })
+            .await
+    }
+
+    /// Validates the request and converts it into a [`MevBundle`].
+    ///
+    /// Backrun hashes are resolved against the bundle pool and the transaction pool, hashes that
+    /// are unknown to both are kept as they are. Bundles from the pool are nested like inline
+    /// bundles and are subject to the same nesting limit.
+    fn parse_bundle(&self, request: SendBundleRequest, depth: usize) -> EthResult<MevBundle> {
+        let SendBundleRequest { protocol_version: _, inclusion, bundle_body, validity, privacy } =
+            request;
+
+        let invalid = |err: MevBundleError| EthApiError::InvalidParams(err.to_string());
+        if bundle_body.is_empty() {
+            return Err(invalid(MevBundleError::EmptyBody))
+        }
+        if bundle_body.len() > MAX_BUNDLE_BODY_SIZE {
+            return Err(invalid(MevBundleError::BodyTooLarge(bundle_body.len())))
+        }
+        if depth > MAX_BUNDLE_NESTING_DEPTH {
+            return Err(invalid(MevBundleError::NestingTooDeep))
+        }
+
+        let block = inclusion.block_number();
+        let max_block = inclusion.max_block_number().unwrap_or(block);
+        if max_block < block {
+            return Err(invalid(MevBundleError::InvalidInclusion { block, max_block }))
+        }
+        if max_block - block > MAX_BUNDLE_BLOCK_RANGE {
+            return Err(invalid(MevBundleError::InclusionRangeTooLarge { block, max_block }))
+        }
+
+        let mut body = Vec::with_capacity(bundle_body.len());
+        for item in bundle_body {
+            let item = match item {
+                BundleItem::Hash { hash } => {
+                    if let Some(bundle) = self.inner.bundle_pool.get(&hash) {
+                        if depth + 1 + bundle.nesting_depth() > MAX_BUNDLE_NESTING_DEPTH {
+                            return Err(invalid(MevBundleError::NestingTooDeep))
+                        }
+                        MevBundleItem::Bundle(bundle)
+                    } else if let Some(tx) = self.inner.pool.get(&hash) {
+                        MevBundleItem::Tx {
+                            transaction: tx.transaction.to_recovered_transaction(),
+                            can_revert: false,
+                        }
+                    } else {
+                        MevBundleItem::Hash(hash)
+                    }
+                }
+                BundleItem::Tx { tx, can_revert } => MevBundleItem::Tx {
+                    transaction: recover_raw_transaction(tx)?.into_ecrecovered_transaction(),
+                    can_revert,
+                },
+                BundleItem::Bundle { bundle } => {
+                    MevBundleItem::Bundle(Arc::new(self.parse_bundle(*bundle, depth + 1)?))
+                }
+            };
+            body.push(item);
+        }
+
+        let Validity { refund, refund_config } = validity.unwrap_or_default();
+        let refunds = refund
+            .unwrap_or_default()
+            .into_iter()
+            .map(|refund| MevBundleRefund {
+                body_idx: refund.body_idx as usize,
+                percent: refund.percent,
+            })
+            .collect::<Vec<_>>();
+        if let Some(refund) = refunds.iter().find(|refund| refund.body_idx >= body.len()) {
+            return Err(invalid(MevBundleError::InvalidRefundIndex(refund.body_idx)))
+        }
+        if refunds.iter().map(|refund| refund.percent).sum::<u64>() > 100 {
+            return Err(invalid(MevBundleError::InvalidRefundPercent))
+        }
+
+        let refund_config = refund_config
+            .unwrap_or_default()
+            .into_iter()
+            .map(|config| (config.address, config.percent))
+            .collect::<Vec<_>>();
+        if !refund_config.is_empty() &&
+            refund_config.iter().map(|(_, percent)| percent).sum::<u64>() != 100
+        {
+            return Err(invalid(MevBundleError::InvalidRefundConfig))
+        }
+
+        Ok(MevBundle {
+            hash: MevBundle::compute_hash(&body),
+            block,
+            max_block,
+            body,
+            refunds,
+            refund_config,
+            privacy,
+        })
+    }
+}
+
+#[async_trait]
+impl<Eth, Pool> MevApiServer for MevApi<Eth, Pool>
+where
+    Eth: EthTransactions + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Handler for `mev_sendBundle`
+    async fn send_bundle(&self, request: SendBundleRequest) -> RpcResult<SendBundleResponse> {
+        trace!(target: "rpc::mev", "Serving mev_sendBundle");
+        Ok(Self::send_bundle(self, request)?)
+    }
+
+    /// Handler for `mev_simBundle`
+    async fn sim_bundle(
+        &self,
+        bundle: SendBundleRequest,
+        sim_overrides: SimBundleOverrides,
+    ) -> RpcResult<SimBundleResponse> {
+        trace!(target: "rpc::mev", ?sim_overrides, "Serving mev_simBundle");
+        Ok(Self::sim_bundle(self, bundle, sim_overrides).await?)
+    }
+}
+
+/// Container type for `MevApi` internals
+#[derive(Debug)]
+struct MevApiInner<Eth, Pool> {
+    /// Access to commonly used code of the `eth` namespace
+    eth_api: Eth,
+    /// The transaction pool backrun hashes are resolved against.
+    pool: Pool,
+    /// The pool submitted bundles are added to.
+    bundle_pool: BundlePool,
+    /// The chain spec, used to derive the base fee of simulated blocks.
+    chain_spec: Arc<ChainSpec>,
+    /// Restrict the number of concurrent simulations.
+    blocking_task_guard: BlockingTaskGuard,
+}
+
+impl<Eth, Pool> std::fmt::Debug for MevApi<Eth, Pool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MevApi").finish_non_exhaustive()
+    }
+}
+
+impl<Eth, Pool> Clone for MevApi<Eth, Pool> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+/// Settings shared by all (nested) bundles of a simulation.
+#[derive(Debug)]
+struct SimContext {
+    /// The beneficiary of the simulated block.
+    coinbase: Address,
+    /// The number of the simulated block.
+    block: u64,
+    /// The simulation is aborted once the deadline is reached.
+    deadline: Instant,
+    /// The configured timeout, used for the error message.
+    timeout: Duration,
+}
+
+/// The outcome of a successful bundle execution.
+#[derive(Debug)]
+struct BundleExecution {
+    /// Gas used by all transactions of the bundle, including nested bundles.
+    gas_used: u64,
+    /// The increase of the coinbase balance.
+    profit: U256,
+    /// The share of the profit that is refunded.
+    refundable_value: U256,
+    /// Logs of all body items, mirroring the nesting of the bundle.
+    logs: Vec<SimBundleLogs>,
+}
+
+/// Errors that abort the execution of a bundle.
+#[derive(Debug)]
+enum SimBundleError {
+    /// The bundle can't be included in the simulated block, this is reported in the response.
+    Invalid(String),
+    /// Any other error.
+    Eth(EthApiError),
+}
+
+impl From<EthApiError> for SimBundleError {
+    fn from(err: EthApiError) -> Self {
+        Self::Eth(err)
+    }
+}
+
+/// Executes the bundle on top of the state of the `evm` and commits all state changes.
+fn execute_bundle<DB>(
+    evm: &mut Evm<'_, (), DB>,
+    bundle: &MevBundle,
+    ctx: &SimContext,
+) -> Result<BundleExecution, SimBundleError>
+where
+    DB: Database + DatabaseCommit,
+    DB::Error: Into<EthApiError>,
+{
+    let mut gas_used = 0;
+    let mut profit = U256::ZERO;
+    // the coinbase profit of each body item
+    let mut item_profits = Vec::with_capacity(bundle.body.len());
+    let mut logs = Vec::with_capacity(bundle.body.len());
+
+    for item in &bundle.body {
+        if Instant::now() > ctx.deadline {
+            return Err(EthApiError::ExecutionTimedOut(ctx.timeout).into())
+        }
+
+        let balance_before = coinbase_balance(evm, ctx.coinbase)?;
+
+        match item {
+            MevBundleItem::Hash(hash) => {
+                return Err(SimBundleError::Invalid(format!("unmatched backrun hash {hash}")))
+            }
+            MevBundleItem::Tx { transaction, can_revert } => {
+                let hash = transaction.hash();
+                transaction.try_fill_tx_env(evm.tx_mut())?;
+                let ResultAndState { result, state } = match evm.transact() {
+                    Ok(res) => res,
+                    Err(EVMError::Transaction(err)) => {
+                        return Err(SimBundleError::Invalid(format!(
+                            "transaction {hash} is invalid: {}",
+                            RpcInvalidTransactionError::from(err)
+                        )))
+                    }
+                    Err(err) => return Err(EthApiError::from(err).into()),
+                };
+                if !result.is_success() && !can_revert {
+                    return Err(SimBundleError::Invalid(format!("transaction {hash} reverted")))
+                }
+
+                gas_used += result.gas_used();
+                let tx_logs = result
+                    .into_logs()
+                    .into_iter()
+                    .map(|log| Log {
+                        inner: log,
+                        block_hash: None,
+                        block_number: Some(ctx.block),
+                        block_timestamp: None,
+                        transaction_hash: Some(hash),
+                        transaction_index: None,
+                        log_index: None,
+                        removed: false,
+                    })
+                    .collect();
+                logs.push(SimBundleLogs { tx_logs: Some(tx_logs), bundle_logs: None });
+                evm.context.evm.db.commit(state);
+            }
+            MevBundleItem::Bundle(nested) => {
+                let execution = execute_bundle(evm, nested, ctx)?;
+                gas_used += execution.gas_used;
+                logs.push(SimBundleLogs { tx_logs: None, bundle_logs: Some(execution.logs) });
+            }
+        }
+
+        let item_profit = coinbase_balance(evm, ctx.coinbase)?.saturating_sub(balance_before);
+        profit += item_profit;
+        item_profits.push(item_profit);
+    }
+
+    let refundable_value = refundable_value(&bundle.refunds, &item_profits);
+
+    Ok(BundleExecution { gas_used, profit, refundable_value, logs })
+}
+
+/// Returns the share of the profit that is refunded to the senders of the refunded body items.
+///
+/// Refunds are paid from the profit of the body items that are not refunded themselves, the
+/// transactions that are backrun don't pay for their own refund.
+fn refundable_value(refunds: &[MevBundleRefund], item_profits: &[U256]) -> U256 {
+    let refundable_profit = item_profits
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| !refunds.iter().any(|refund| refund.body_idx == *idx))
+        .fold(U256::ZERO, |acc, (_, profit)| acc + profit);
+    refunds.iter().fold(U256::ZERO, |acc, refund| {
+        acc + refundable_profit * U256::from(refund.percent) / U256::from(100)
+    })
+}
+
+/// Returns the balance of the coinbase in the current state of the `evm`.
+fn coinbase_balance<DB>(evm: &mut Evm<'_, (), DB>, coinbase: Address) -> EthResult<U256>
+where
+    DB: Database,
+    DB::Error: Into<EthApiError>,
+{
+    Ok(evm
+        .context
+        .evm
+        .db
+        .basic(coinbase)
+        .map_err(Into::into)?
+        .map(|acc| acc.balance)
+        .unwrap_or_default())
+}
+
+/// [`MevApi`] specific errors.
+#[derive(Debug, thiserror::Error)]
+pub enum MevBundleError {
+    /// Thrown if the bundle body is empty.
+    #[error("bundle body is empty")]
+    EmptyBody,
+    /// Thrown if the bundle body has more than [`MAX_BUNDLE_BODY_SIZE`] items.
+    #[error("bundle body has {0} items, the limit is {MAX_BUNDLE_BODY_SIZE}")]
+    BodyTooLarge(usize),
+    /// Thrown if bundles are nested deeper than [`MAX_BUNDLE_NESTING_DEPTH`].
+    #[error("bundles can only be nested {MAX_BUNDLE_NESTING_DEPTH} level deep")]
+    NestingTooDeep,
+    /// Thrown if the last block of the inclusion range is before the first block.
+    #[error("invalid inclusion: maxBlock {max_block} is before block {block}")]
+    InvalidInclusion {
+        /// The first block the bundle is valid for.
+        block: u64,
+        /// The last block the bundle is valid for.
+        max_block: u64,
+    },
+    /// Thrown if the inclusion range spans more than [`MAX_BUNDLE_BLOCK_RANGE`] blocks.
+    #[error(
+        "inclusion range {block}..={max_block} exceeds the limit of {MAX_BUNDLE_BLOCK_RANGE} blocks"
+    )]
+    InclusionRangeTooLarge {
+        /// The first block the bundle is valid for.
+        block: u64,
+        /// The last block the bundle is valid for.
+        max_block: u64,
+    },
+    /// Thrown if a refund refers to a body item that does not exist.
+    #[error("refund for body index {0} which does not exist")]
+    InvalidRefundIndex(usize),
+    /// Thrown if the refunds sum up to more than 100 percent.
+    #[error("refunds exceed 100 percent")]
+    InvalidRefundPercent,
+    /// Thrown if the refund config does not sum up to 100 percent.
+    #[error("refund config must sum up to 100 percent")]
+    InvalidRefundConfig,
+    /// Thrown if a bundle with backrun hashes that are unknown is simulated.
+    #[error("bundle is not fully matched")]
+    NotFullyMatched,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refunds_are_paid_from_the_profit_of_other_items() {
+        let item_profits = [U256::from(10), U256::from(1_000), U256::from(90)];
+
+        // nothing is refunded without refunds
+        assert_eq!(refundable_value(&[], &item_profits), U256::ZERO);
+
+        // the backrun transaction pays 50% of its profit to the sender of the first item, the
+        // profit of the refunded item itself is not shared
+        let refunds = [MevBundleRefund { body_idx: 0, percent: 50 }];
+        assert_eq!(refundable_value(&refunds, &item_profits), U256::from(545));
+
+        let refunds = [
+            MevBundleRefund { body_idx: 0, percent: 50 },
+            MevBundleRefund { body_idx: 2, percent: 40 },
+        ];
+        assert_eq!(refundable_value(&refunds, &item_profits), U256::from(900));
+    }
+}
@@ -1,39 +1,174 @@
 use alloy_primitives::Bytes;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use revm_inspectors::transfer::{TransferInspector, TransferKind};
+use revm_inspectors::{
+    tracing::{types::CallTraceNode, TracingInspector, TracingInspectorConfig},
+    transfer::{TransferInspector, TransferKind},
+};
 use revm_primitives::ExecutionResult;
+use std::ops::RangeInclusive;
 
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, TxHash, B256};
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, TxHash, TxNumber, B256, U256};
+use reth_provider::{AddressAppearancesReader, BlockReaderIdExt, ProviderError, ProviderResult};
 use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_types::{
     trace::otterscan::{
         BlockDetails, ContractCreator, InternalOperation, OperationType, OtsBlockTransactions,
         OtsReceipt, OtsTransactionReceipt, TraceEntry, TransactionsWithReceipts,
     },
-    BlockTransactions, Transaction,
+    AnyTransactionReceipt, BlockTransactions, Transaction,
 };
 
-use crate::{eth::EthTransactions, result::internal_rpc_err};
+use crate::{
+    eth::EthTransactions,
+    result::{internal_rpc_err, ToRpcResult},
+};
 
 const API_LEVEL: u64 = 8;
 
 /// Otterscan API.
 #[derive(Debug)]
-pub struct OtterscanApi<Eth> {
+pub struct OtterscanApi<Provider, Eth> {
+    provider: Provider,
     eth: Eth,
 }
 
-impl<Eth> OtterscanApi<Eth> {
+impl<Provider, Eth> OtterscanApi<Provider, Eth> {
     /// Creates a new instance of `Otterscan`.
-    pub const fn new(eth: Eth) -> Self {
-        Self { eth }
+    pub const fn new(provider: Provider, eth: Eth) -> Self {
+        Self { provider, eth }
+    }
+}
+
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
+where
+    Provider: BlockReaderIdExt + AddressAppearancesReader + 'static,
+    Eth: EthApiServer + EthTransactions,
+{
+    /// Resolves the block number of the given block tag.
+    fn block_number(&self, block_number: BlockNumberOrTag) -> RpcResult<u64> {
+        self.provider
+            .convert_block_number(block_number)
+            .to_rpc_result()?
+            .ok_or_else(|| internal_rpc_err("block not found"))
+    }
+
+    /// Returns an error if the address appearances index was never built, which is the case if
+    /// the index stage is disabled.
+    fn ensure_address_appearances_index(&self) -> RpcResult<()> {
+        if self.provider.address_appearances_tip().to_rpc_result()?.is_none() {
+            return Err(internal_rpc_err(
+                "address appearances index is disabled, enable `stages.index_address_appearances`",
+            ))
+        }
+        Ok(())
+    }
+
+    /// Returns the next transactions the address appeared in, in the given range, and whether
+    /// there are more transactions after them.
+    ///
+    /// A page contains at least `page_size` transactions if there are enough of them, and always
+    /// contains all the transactions of the blocks it touches.
+    fn search_transactions(
+        &self,
+        address: Address,
+        range: RangeInclusive<TxNumber>,
+        reverse: bool,
+        page_size: usize,
+    ) -> ProviderResult<(Vec<TxNumber>, bool)> {
+        let mut tx_numbers =
+            self.provider.address_appearances(address, range.clone(), reverse, page_size)?;
+        let Some(&last) = tx_numbers.last().filter(|_| tx_numbers.len() == page_size) else {
+            return Ok((tx_numbers, false))
+        };
+
+        // Complete the block of the last transaction
+        let block_number = self
+            .provider
+            .transaction_block(last)?
+            .ok_or(ProviderError::TransactionNotFound(last.into()))?;
+        let body = self
+            .provider
+            .block_body_indices(block_number)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(block_number))?;
+        let (rest, remaining) = if reverse {
+            (
+                last.checked_sub(1).map(|prev| body.first_tx_num.max(*range.start())..=prev),
+                body.first_tx_num.checked_sub(1).map(|prev| *range.start()..=prev),
+            )
+        } else {
+            (
+                Some(last + 1..=body.last_tx_num().min(*range.end())),
+                Some(body.next_tx_num()..=*range.end()),
+            )
+        };
+        if let Some(rest) = rest {
+            tx_numbers.extend(self.provider.address_appearances(
+                address,
+                rest,
+                reverse,
+                usize::MAX,
+            )?);
+        }
+
+        let has_more = match remaining {
+            Some(remaining) => {
+                !self.provider.address_appearances(address, remaining, reverse, 1)?.is_empty()
+            }
+            None => false,
+        };
+
+        Ok((tx_numbers, has_more))
+    }
+
+    /// Returns the transactions with the given numbers, and their receipts.
+    async fn transactions_with_receipts(
+        &self,
+        tx_numbers: Vec<TxNumber>,
+    ) -> RpcResult<(Vec<Transaction>, Vec<OtsTransactionReceipt>)> {
+        let mut txs = Vec::with_capacity(tx_numbers.len());
+        let mut receipts = Vec::with_capacity(tx_numbers.len());
+        let mut timestamp = None;
+
+        for tx_number in tx_numbers {
+            let hash = self
+                .provider
+                .transaction_by_id(tx_number)
+                .to_rpc_result()?
+                .ok_or_else(|| internal_rpc_err("transaction not found"))?
+                .hash();
+            let tx = EthApiServer::transaction_by_hash(&self.eth, hash)
+                .await?
+                .ok_or_else(|| internal_rpc_err("transaction not found"))?;
+            let receipt = EthApiServer::transaction_receipt(&self.eth, hash)
+                .await?
+                .ok_or_else(|| internal_rpc_err("receipt not found"))?;
+
+            let block_number = tx.block_number.unwrap_or_default();
+            let block_timestamp = match timestamp {
+                Some((number, timestamp)) if number == block_number => timestamp,
+                _ => {
+                    self.provider
+                        .header_by_number(block_number)
+                        .to_rpc_result()?
+                        .ok_or_else(|| internal_rpc_err("header not found"))?
+                        .timestamp
+                }
+            };
+            timestamp = Some((block_number, block_timestamp));
+
+            txs.push(tx);
+            receipts.push(ots_transaction_receipt(receipt, Some(block_timestamp)));
+        }
+
+        Ok((txs, receipts))
     }
 }
 
 #[async_trait]
-impl<Eth> OtterscanServer for OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanServer for OtterscanApi<Provider, Eth>
 where
+    Provider: BlockReaderIdExt + AddressAppearancesReader + 'static,
     Eth: EthApiServer + EthTransactions,
 {
     /// Handler for `ots_hasCode`
@@ -90,8 +225,34 @@ where
     }
 
     /// Handler for `ots_traceTransaction`
-    async fn trace_transaction(&self, _tx_hash: TxHash) -> RpcResult<TraceEntry> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn trace_transaction(&self, tx_hash: TxHash) -> RpcResult<Option<Vec<TraceEntry>>> {
+        let traces = self
+            .eth
+            .spawn_trace_transaction_in_block_with_inspector(
+                tx_hash,
+                TracingInspector::new(TracingInspectorConfig::default_parity()),
+                |_tx_info, inspector, _, _| Ok(inspector.into_traces().into_nodes()),
+            )
+            .await?
+            .map(|traces| {
+                traces
+                    .into_iter()
+                    .map(|CallTraceNode { trace, .. }| TraceEntry {
+                        r#type: if trace.is_selfdestruct() {
+                            "SELFDESTRUCT".to_string()
+                        } else {
+                            trace.kind.to_string()
+                        },
+                        depth: trace.depth as u32,
+                        from: trace.caller,
+                        to: trace.address,
+                        value: trace.value,
+                        input: trace.data,
+                        output: trace.output,
+                    })
+                    .collect::<Vec<_>>()
+            });
+        Ok(traces)
     }
 
     /// Handler for `ots_getBlockDetails`
@@ -156,22 +317,7 @@ where
         let timestamp = Some(block.header.timestamp);
         let receipts = receipts
             .drain(page_start..page_end)
-            .map(|receipt| {
-                let receipt = receipt.inner.map_inner(|receipt| OtsReceipt {
-                    status: receipt
-                        .inner
-                        .receipt
-                        .status
-                        .as_eip658()
-                        .expect("ETH API returned pre-EIP-658 status"),
-                    cumulative_gas_used: receipt.inner.receipt.cumulative_gas_used as u64,
-                    logs: None,
-                    logs_bloom: None,
-                    r#type: receipt.r#type,
-                });
-
-                OtsTransactionReceipt { receipt, timestamp }
-            })
+            .map(|receipt| ots_transaction_receipt(receipt, timestamp))
             .collect();
         Ok(OtsBlockTransactions { fullblock: block.inner.into(), receipts })
     }
@@ -179,34 +325,208 @@ where
     /// Handler for `searchTransactionsBefore`
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: BlockNumberOrTag,
-        _page_size: usize,
+        address: Address,
+        block_number: BlockNumberOrTag,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        self.ensure_address_appearances_index()?;
+
+        // Block 0 means the search starts from the latest block
+        let block_number = self.block_number(block_number)?;
+        let end = if block_number == 0 {
+            Some(TxNumber::MAX)
+        } else {
+            self.provider
+                .block_body_indices(block_number)
+                .to_rpc_result()?
+                .ok_or_else(|| internal_rpc_err("block not found"))?
+                .first_tx_num
+                .checked_sub(1)
+        };
+
+        let (tx_numbers, has_more) = match end {
+            Some(end) => {
+                self.search_transactions(address, 0..=end, true, page_size).to_rpc_result()?
+            }
+            None => (Vec::new(), false),
+        };
+        let (txs, receipts) = self.transactions_with_receipts(tx_numbers).await?;
+
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page: block_number == 0,
+            last_page: !has_more,
+        })
     }
 
     /// Handler for `searchTransactionsAfter`
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: BlockNumberOrTag,
-        _page_size: usize,
+        address: Address,
+        block_number: BlockNumberOrTag,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        self.ensure_address_appearances_index()?;
+
+        // Block 0 means the search starts from genesis
+        let block_number = self.block_number(block_number)?;
+        let start = if block_number == 0 {
+            0
+        } else {
+            self.provider
+                .block_body_indices(block_number)
+                .to_rpc_result()?
+                .ok_or_else(|| internal_rpc_err("block not found"))?
+                .next_tx_num()
+        };
+
+        let (tx_numbers, has_more) = self
+            .search_transactions(address, start..=TxNumber::MAX, false, page_size)
+            .to_rpc_result()?;
+        let (mut txs, mut receipts) = self.transactions_with_receipts(tx_numbers).await?;
+
+        // Pages are always sorted from the newest to the oldest transaction
+        txs.reverse();
+        receipts.reverse();
+
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page: !has_more,
+            last_page: block_number == 0,
+        })
     }
 
     /// Handler for `getTransactionBySenderAndNonce`
     async fn get_transaction_by_sender_and_nonce(
         &self,
-        _sender: Address,
-        _nonce: u64,
+        sender: Address,
+        nonce: u64,
     ) -> RpcResult<Option<Transaction>> {
-        Err(internal_rpc_err("unimplemented"))
+        let nonce_after = |block_number: u64| {
+            EthApiServer::transaction_count(&self.eth, sender, Some(block_number.into()))
+        };
+
+        // The sender's nonce is only increased by its transactions
+        let latest = self.provider.best_block_number().to_rpc_result()?;
+        if nonce_after(latest).await? <= U256::from(nonce) {
+            return Ok(None)
+        }
+
+        // Find the first block after which the sender's nonce is greater than the given nonce
+        let (mut low, mut high) = (0, latest);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if nonce_after(mid).await? > U256::from(nonce) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        let Some(block) = self.eth.block_by_number(low.into(), true).await? else {
+            return Ok(None)
+        };
+        let BlockTransactions::Full(transactions) = block.inner.transactions else {
+            return Err(internal_rpc_err("block is not full"));
+        };
+
+        Ok(transactions.into_iter().find(|tx| tx.from == sender && tx.nonce == nonce))
     }
 
     /// Handler for `getContractCreator`
-    async fn get_contract_creator(&self, _address: Address) -> RpcResult<Option<ContractCreator>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn get_contract_creator(&self, address: Address) -> RpcResult<Option<ContractCreator>> {
+        self.ensure_address_appearances_index()?;
+
+        let Some(creation) = self.provider.contract_creation(address).to_rpc_result()? else {
+            return Ok(None)
+        };
+
+        let hash = self
+            .provider
+            .transaction_by_id(creation.tx_number)
+            .to_rpc_result()?
+            .ok_or_else(|| internal_rpc_err("transaction not found"))?
+            .hash();
+
+        Ok(Some(ContractCreator { hash, creator: creation.creator }))
+    }
+}
+
+/// Converts a transaction receipt into an [`OtsTransactionReceipt`], without the logs.
+fn ots_transaction_receipt(
+    receipt: AnyTransactionReceipt,
+    timestamp: Option<u64>,
+) -> OtsTransactionReceipt {
+    let receipt = receipt.inner.map_inner(|receipt| OtsReceipt {
+        status: receipt
+            .inner
+            .receipt
+            .status
+            .as_eip658()
+            .expect("ETH API returned pre-EIP-658 status"),
+        cumulative_gas_used: receipt.inner.receipt.cumulative_gas_used as u64,
+        logs: None,
+        logs_bloom: None,
+        r#type: receipt.r#type,
+    });
+
+    OtsTransactionReceipt { receipt, timestamp }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{
+        cache::EthStateCache, gas_oracle::GasPriceOracle, EthApi, FeeHistoryCache,
+        FeeHistoryCacheConfig,
+    };
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+    type TestOtterscanApi =
+        OtterscanApi<MockEthProvider, EthApi<MockEthProvider, TestPool, (), EthEvmConfig>>;
+
+    fn otterscan_api(provider: MockEthProvider) -> TestOtterscanApi {
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider.clone(), Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        );
+        OtterscanApi::new(provider, eth_api)
+    }
+
+    #[tokio::test]
+    async fn searches_require_address_appearances_index() {
+        let provider = MockEthProvider::default();
+        let api = otterscan_api(provider.clone());
+        let address = Address::random();
+        // block 0 searches the whole chain
+        let block = BlockNumberOrTag::Number(0);
+
+        // the index was never built
+        assert!(api.search_transactions_before(address, block, 10).await.is_err());
+        assert!(api.search_transactions_after(address, block, 10).await.is_err());
+        assert!(api.get_contract_creator(address).await.is_err());
+
+        provider.set_address_appearances_tip(0);
+        let before = api.search_transactions_before(address, block, 10).await.unwrap();
+        assert!(before.txs.is_empty() && before.last_page);
+        let after = api.search_transactions_after(address, block, 10).await.unwrap();
+        assert!(after.txs.is_empty() && after.last_page);
+        assert!(api.get_contract_creator(address).await.unwrap().is_none());
     }
 }
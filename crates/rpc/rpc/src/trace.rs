@@ -6,15 +6,25 @@ use crate::eth::{
 };
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult as Result;
+use parking_lot::Mutex;
 use reth_consensus_common::calc::{base_block_reward, block_reward, ommer_reward};
-use reth_primitives::{revm::env::tx_env_with_recovered, BlockId, Bytes, SealedHeader, B256, U256};
-use reth_provider::{BlockReader, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, Address, BlockId, BlockNumber, Bytes, SealedHeader, TxNumber,
+    B256, U256,
+};
+use reth_provider::{
+    AddressAppearancesReader, BlockIdReader, BlockReader, ChainSpecProvider, EvmEnvProvider,
+    StateProviderFactory,
+};
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::TraceApiServer;
+use reth_rpc_server_types::constants::{
+    DEFAULT_MAX_TRACE_FILTER_BLOCKS, DEFAULT_TRACE_CACHE_MAX_BLOCKS,
+};
 use reth_rpc_types::{
     state::{EvmOverrides, StateOverride},
     trace::{
-        filter::TraceFilter,
+        filter::{TraceFilter, TraceFilterMatcher, TraceFilterMode},
         opcode::{BlockOpcodeGas, TransactionOpcodeGas},
         parity::*,
         tracerequest::TraceCallRequest,
@@ -30,8 +40,21 @@ use revm_inspectors::{
     opcode::OpcodeGasInspector,
     tracing::{parity::populate_state_diff, TracingInspector, TracingInspectorConfig},
 };
-use std::{collections::HashSet, sync::Arc};
+use schnellru::{LruMap, Unlimited};
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::RangeInclusive,
+    path::PathBuf,
+    sync::Arc,
+};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
+use tracing::warn;
+
+/// Number of blocks that are traced concurrently by `trace_filter`.
+const TRACE_FILTER_BATCH_SIZE: usize = 100;
+
+/// Number of address appearances that are read from the index at once by `trace_filter`.
+const APPEARANCES_BATCH_SIZE: usize = 1000;
 
 /// `trace` API implementation.
 ///
@@ -50,7 +73,32 @@ impl<Provider, Eth> TraceApi<Provider, Eth> {
 
     /// Create a new instance of the [`TraceApi`]
     pub fn new(provider: Provider, eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(TraceApiInner { provider, eth_api, blocking_task_guard });
+        Self::with_filter_config(
+            provider,
+            eth_api,
+            blocking_task_guard,
+            TraceFilterConfig::default(),
+        )
+    }
+
+    /// Create a new instance of the [`TraceApi`] with the given config for `trace_filter`.
+    pub fn with_filter_config(
+        provider: Provider,
+        eth_api: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        filter_config: TraceFilterConfig,
+    ) -> Self {
+        let trace_cache = filter_config
+            .cache_dir
+            .clone()
+            .map(|dir| TraceCache::new(dir, filter_config.cache_max_blocks));
+        let inner = Arc::new(TraceApiInner {
+            provider,
+            eth_api,
+            blocking_task_guard,
+            filter_config,
+            trace_cache,
+        });
         Self { inner }
     }
 
@@ -71,7 +119,13 @@ impl<Provider, Eth> TraceApi<Provider, Eth> {
 
 impl<Provider, Eth> TraceApi<Provider, Eth>
 where
-    Provider: BlockReader + StateProviderFactory + EvmEnvProvider + ChainSpecProvider + 'static,
+    Provider: BlockReader
+        + BlockIdReader
+        + AddressAppearancesReader
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + 'static,
     Eth: EthTransactions + 'static,
 {
     /// Executes the given call and returns a number of possible traces for it.
@@ -230,18 +284,21 @@ where
         Ok(self.trace_transaction(hash).await?.and_then(|traces| traces.into_iter().nth(index)))
     }
 
-    /// Returns all transaction traces that match the given filter.
+    /// Returns all transaction traces that match the given filter, in chain order.
+    ///
+    /// If the address appearances index is available, only the transactions the filtered
+    /// addresses appeared in are traced, so the number of indexed blocks is not limited. The
+    /// remaining blocks are scanned for matching transactions, which is limited to the configured
+    /// number of blocks.
     ///
-    /// This is similar to [`Self::trace_block`] but only returns traces for transactions that match
-    /// the filter.
+    /// `after` skips the first matching traces and `count` limits the number of returned traces.
     pub async fn trace_filter(
         &self,
         filter: TraceFilter,
     ) -> EthResult<Vec<LocalizedTransactionTrace>> {
         let matcher = filter.matcher();
-        let TraceFilter { from_block, to_block, .. } = filter;
-        let start = from_block.unwrap_or(0);
-        let end = if let Some(to_block) = to_block {
+        let start = filter.from_block.unwrap_or(0);
+        let end = if let Some(to_block) = filter.to_block {
             to_block
         } else {
             self.provider().best_block_number()?
@@ -253,42 +310,235 @@ where
             ))
         }
 
-        // ensure that the range is not too large, since we need to fetch all blocks in the range
-        let distance = end.saturating_sub(start);
-        if distance > 100 {
-            return Err(EthApiError::InvalidParams(
-                "Block range too large; currently limited to 100 blocks".to_string(),
-            ))
+        // every matching transaction has at least one trace, so no more than `after + count`
+        // transactions have to be found
+        let max_matches = filter
+            .after
+            .unwrap_or_default()
+            .checked_add(filter.count.unwrap_or(u64::MAX))
+            .and_then(|max| usize::try_from(max).ok())
+            .unwrap_or(usize::MAX);
+
+        // find relevant blocks to trace, looking up the blocks covered by the address appearances
+        // index first
+        let mut target_blocks = Vec::new();
+        let mut scan_from_block = Some(start);
+        if let Some(indexed_to_block) = self.appearance_index_range_end(&filter, end)? {
+            if indexed_to_block >= start {
+                let (blocks, matches) = self.indexed_target_blocks(
+                    &filter,
+                    &matcher,
+                    start..=indexed_to_block,
+                    max_matches,
+                )?;
+                target_blocks = blocks;
+                // the blocks after the index only have to be scanned if more matches are needed
+                scan_from_block = (matches < max_matches)
+                    .then(|| indexed_to_block.checked_add(1))
+                    .flatten()
+                    .filter(|block| *block <= end);
+            }
+        }
+
+        if let Some(scan_from_block) = scan_from_block {
+            // ensure that the range is not too large, since we need to fetch all blocks in the
+            // range
+            if let Some(max_blocks) = self.inner.filter_config.max_blocks_per_filter {
+                if end - scan_from_block > max_blocks {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "Block range too large; currently limited to {max_blocks} blocks"
+                    )))
+                }
+            }
+            target_blocks.extend(self.scanned_target_blocks(&matcher, scan_from_block..=end)?);
+        }
+
+        // trace the relevant blocks in batches, until enough traces are collected
+        let finalized_block = self.provider().finalized_block_number()?;
+        let mut skip = filter.after.unwrap_or_default() as usize;
+        let count = filter.count.map_or(usize::MAX, |count| count as usize);
+        let mut all_traces = Vec::new();
+        for blocks in target_blocks.chunks(TRACE_FILTER_BATCH_SIZE) {
+            let block_traces = futures::future::try_join_all(
+                blocks.iter().map(|block| self.trace_target_block(block, finalized_block)),
+            )
+            .await?;
+
+            for trace in block_traces.into_iter().flatten() {
+                if skip > 0 {
+                    skip -= 1;
+                } else if all_traces.len() < count {
+                    all_traces.push(trace);
+                }
+            }
+
+            if all_traces.len() >= count {
+                break
+            }
+        }
+
+        Ok(all_traces)
+    }
+
+    /// Returns the last block up to `to_block` whose address appearances are indexed, or `None`
+    /// if the index is not available or the filter matches transactions of any address.
+    fn appearance_index_range_end(
+        &self,
+        filter: &TraceFilter,
+        to_block: BlockNumber,
+    ) -> EthResult<Option<BlockNumber>> {
+        if filter.from_address.is_empty() && filter.to_address.is_empty() {
+            return Ok(None)
+        }
+        Ok(self.provider().address_appearances_tip()?.map(|tip| tip.min(to_block)))
+    }
+
+    /// Returns the blocks in the range with transactions matching the filter, using the address
+    /// appearances index to find the candidate transactions, and the number of matching
+    /// transactions.
+    ///
+    /// The index is walked in transaction order and the walk stops after `max_matches` matching
+    /// transactions.
+    fn indexed_target_blocks(
+        &self,
+        filter: &TraceFilter,
+        matcher: &TraceFilterMatcher,
+        range: RangeInclusive<BlockNumber>,
+        max_matches: usize,
+    ) -> EthResult<(Vec<TraceFilterBlock>, usize)> {
+        let (Some(first_body), Some(last_body)) = (
+            self.provider().block_body_indices(*range.start())?,
+            self.provider().block_body_indices(*range.end())?,
+        ) else {
+            return Ok((Vec::new(), 0))
+        };
+        let tx_range = first_body.first_tx_num()..=last_body.last_tx_num();
+
+        // the sender of a transaction always appears in the index, so in intersection mode it's
+        // enough to walk the appearances of the senders
+        let addresses = match (filter.from_address.is_empty(), filter.to_address.is_empty()) {
+            (false, false) if filter.mode == TraceFilterMode::Intersection => {
+                filter.from_address.clone()
+            }
+            _ => filter.from_address.iter().chain(&filter.to_address).copied().collect(),
+        };
+        let mut candidates = AppearancesWalker::new(self.provider(), addresses, tx_range);
+
+        // an address can appear anywhere in a transaction, so the candidates are matched against
+        // the sender and recipient of the transaction
+        let mut target_blocks: Vec<TraceFilterBlock> = Vec::new();
+        let mut current_body: Option<(TxNumber, TxNumber)> = None;
+        let mut matches = 0;
+        while matches < max_matches {
+            let Some(tx_number) = candidates.next()? else { break };
+            let Some(tx) = self.provider().transaction_by_id(tx_number)? else { continue };
+            let from = tx.recover_signer_unchecked().ok_or(BlockError::InvalidSignature)?;
+            if !matcher.matches(from, tx.to()) {
+                continue
+            }
+
+            // candidates are sorted, so the transaction belongs to a new block once it's past the
+            // last transaction of the current one
+            let first_tx_num = match current_body {
+                Some((first_tx_num, last_tx_num)) if tx_number <= last_tx_num => first_tx_num,
+                _ => {
+                    let Some(block_number) = self.provider().transaction_block(tx_number)? else {
+                        continue
+                    };
+                    let Some(body) = self.provider().block_body_indices(block_number)? else {
+                        continue
+                    };
+                    current_body = Some((body.first_tx_num(), body.last_tx_num()));
+                    target_blocks.push(TraceFilterBlock::new(block_number));
+                    body.first_tx_num()
+                }
+            };
+            target_blocks.last_mut().expect("block is pushed").insert(tx_number - first_tx_num);
+            matches += 1;
         }
 
-        // fetch all blocks in that range
-        let blocks = self.provider().block_range(start..=end)?;
+        Ok((target_blocks, matches))
+    }
 
-        // find relevant blocks to trace
+    /// Returns the blocks in the range with transactions matching the filter, by fetching and
+    /// scanning all blocks in the range.
+    fn scanned_target_blocks(
+        &self,
+        matcher: &TraceFilterMatcher,
+        range: RangeInclusive<BlockNumber>,
+    ) -> EthResult<Vec<TraceFilterBlock>> {
         let mut target_blocks = Vec::new();
-        for block in blocks {
-            let mut transaction_indices = HashSet::new();
-            let mut highest_matching_index = 0;
+        for block in self.provider().block_range(range)? {
+            let mut target_block = TraceFilterBlock::new(block.number);
             for (tx_idx, tx) in block.body.iter().enumerate() {
                 let from = tx.recover_signer_unchecked().ok_or(BlockError::InvalidSignature)?;
                 let to = tx.to();
                 if matcher.matches(from, to) {
-                    let idx = tx_idx as u64;
-                    transaction_indices.insert(idx);
-                    highest_matching_index = idx;
+                    target_block.insert(tx_idx as u64);
                 }
             }
-            if !transaction_indices.is_empty() {
-                target_blocks.push((block.number, transaction_indices, highest_matching_index));
+            if !target_block.indices.is_empty() {
+                target_blocks.push(target_block);
             }
         }
+        Ok(target_blocks)
+    }
 
-        // trace all relevant blocks
-        let mut block_traces = Vec::with_capacity(target_blocks.len());
-        for (num, indices, highest_idx) in target_blocks {
-            let traces = self.inner.eth_api.trace_block_until(
-                num.into(),
-                Some(highest_idx),
+    /// Returns the traces of the relevant transactions of the block.
+    ///
+    /// If the block is finalized and the trace cache is configured, all transactions of the block
+    /// are traced once and their traces are served from the cache afterwards.
+    async fn trace_target_block(
+        &self,
+        block: &TraceFilterBlock,
+        finalized_block: Option<BlockNumber>,
+    ) -> EthResult<Vec<LocalizedTransactionTrace>> {
+        // traces of finalized blocks can't change anymore, so they can be cached
+        let is_finalized = finalized_block.map_or(false, |finalized| block.number <= finalized);
+        if let Some(cache) = self.inner.trace_cache.as_ref().filter(|_| is_finalized) {
+            if let Some(block_hash) = self.provider().block_hash(block.number)? {
+                let traces = match cache.get(block_hash) {
+                    Some(traces) => traces,
+                    None => {
+                        let traces = self
+                            .inner
+                            .eth_api
+                            .trace_block_with(
+                                block_hash.into(),
+                                TracingInspectorConfig::default_parity(),
+                                |tx_info, inspector, res, _, _| {
+                                    Ok(inspector
+                                        .with_transaction_gas_used(res.gas_used())
+                                        .into_parity_builder()
+                                        .into_localized_transaction_traces(tx_info))
+                                },
+                            )
+                            .await?
+                            .unwrap_or_default()
+                            .into_iter()
+                            .flatten()
+                            .collect::<Vec<_>>();
+                        cache.insert(block_hash, &traces);
+                        traces
+                    }
+                };
+
+                return Ok(traces
+                    .into_iter()
+                    .filter(|trace| {
+                        trace.transaction_position.map_or(false, |idx| block.indices.contains(&idx))
+                    })
+                    .collect())
+            }
+        }
+
+        let indices = block.indices.clone();
+        let traces = self
+            .inner
+            .eth_api
+            .trace_block_until(
+                block.number.into(),
+                Some(block.highest_index),
                 TracingInspectorConfig::default_parity(),
                 move |tx_info, inspector, res, _, _| {
                     if let Some(idx) = tx_info.index {
@@ -303,18 +553,10 @@ where
                         .into_localized_transaction_traces(tx_info);
                     Ok(Some(traces))
                 },
-            );
-            block_traces.push(traces);
-        }
-
-        let block_traces = futures::future::try_join_all(block_traces).await?;
-        let all_traces = block_traces
-            .into_iter()
-            .flatten()
-            .flat_map(|traces| traces.into_iter().flatten().flat_map(|traces| traces.into_iter()))
-            .collect();
+            )
+            .await?;
 
-        Ok(all_traces)
+        Ok(traces.into_iter().flatten().flatten().flatten().collect())
     }
 
     /// Returns all traces for the given transaction hash
@@ -490,7 +732,13 @@ where
 #[async_trait]
 impl<Provider, Eth> TraceApiServer for TraceApi<Provider, Eth>
 where
-    Provider: BlockReader + StateProviderFactory + EvmEnvProvider + ChainSpecProvider + 'static,
+    Provider: BlockReader
+        + BlockIdReader
+        + AddressAppearancesReader
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + 'static,
     Eth: EthTransactions + 'static,
 {
     /// Executes the given call and returns a number of possible traces for it.
@@ -563,9 +811,6 @@ where
     /// Handler for `trace_filter`
     ///
     /// This is similar to `eth_getLogs` but for traces.
-    ///
-    /// # Limitations
-    /// This currently requires block filter fields, since reth does not have address indices yet.
     async fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<LocalizedTransactionTrace>> {
         Ok(Self::trace_filter(self, filter).await?)
     }
@@ -624,6 +869,234 @@ struct TraceApiInner<Provider, Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to `trace_*`
     blocking_task_guard: BlockingTaskGuard,
+    /// Config for `trace_filter`
+    filter_config: TraceFilterConfig,
+    /// Cache of the traces of finalized blocks, if configured
+    trace_cache: Option<TraceCache>,
+}
+
+/// Config for the `trace_filter` handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceFilterConfig {
+    /// Maximum number of blocks that are scanned for matching transactions.
+    ///
+    /// Blocks covered by the address appearances index are not scanned, so they don't count
+    /// towards this limit. If `None` then no limit is enforced.
+    pub max_blocks_per_filter: Option<u64>,
+    /// Directory the traces of finalized blocks are cached in.
+    ///
+    /// If `None` then traces are not cached.
+    pub cache_dir: Option<PathBuf>,
+    /// Maximum number of blocks whose traces are cached, the least recently used blocks are
+    /// evicted from the cache directory.
+    pub cache_max_blocks: usize,
+}
+
+impl TraceFilterConfig {
+    /// Sets the maximum number of blocks that are scanned for matching transactions.
+    pub const fn max_blocks_per_filter(mut self, num: u64) -> Self {
+        self.max_blocks_per_filter = Some(num);
+        self
+    }
+
+    /// Sets the directory the traces of finalized blocks are cached in.
+    pub fn cache_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.cache_dir = dir;
+        self
+    }
+
+    /// Sets the maximum number of blocks whose traces are cached.
+    pub const fn cache_max_blocks(mut self, max_blocks: usize) -> Self {
+        self.cache_max_blocks = max_blocks;
+        self
+    }
+}
+
+impl Default for TraceFilterConfig {
+    fn default() -> Self {
+        Self {
+            max_blocks_per_filter: Some(DEFAULT_MAX_TRACE_FILTER_BLOCKS),
+            cache_dir: None,
+            cache_max_blocks: DEFAULT_TRACE_CACHE_MAX_BLOCKS,
+        }
+    }
+}
+
+/// Walks the address appearances index of multiple addresses in ascending transaction order,
+/// without duplicates. The appearances are read from the index in batches, as they're needed.
+struct AppearancesWalker<'a, Provider> {
+    provider: &'a Provider,
+    addresses: Vec<AddressAppearances>,
+    range_end: TxNumber,
+}
+
+/// The appearances of an address that are read from the index, but not walked yet.
+struct AddressAppearances {
+    address: Address,
+    /// The first transaction number that is not read yet, or `None` if all appearances are read.
+    next_tx_number: Option<TxNumber>,
+    buffered: VecDeque<TxNumber>,
+}
+
+impl<'a, Provider: AddressAppearancesReader> AppearancesWalker<'a, Provider> {
+    fn new(
+        provider: &'a Provider,
+        addresses: Vec<Address>,
+        tx_range: RangeInclusive<TxNumber>,
+    ) -> Self {
+        let addresses = addresses
+            .into_iter()
+            .map(|address| AddressAppearances {
+                address,
+                next_tx_number: Some(*tx_range.start()),
+                buffered: VecDeque::new(),
+            })
+            .collect();
+        Self { provider, addresses, range_end: *tx_range.end() }
+    }
+
+    /// Returns the next transaction number any of the addresses appears in.
+    fn next(&mut self) -> EthResult<Option<TxNumber>> {
+        for appearances in &mut self.addresses {
+            if !appearances.buffered.is_empty() {
+                continue
+            }
+            let Some(start) = appearances.next_tx_number.filter(|start| *start <= self.range_end)
+            else {
+                continue
+            };
+
+            let batch = self.provider.address_appearances(
+                appearances.address,
+                start..=self.range_end,
+                false,
+                APPEARANCES_BATCH_SIZE,
+            )?;
+            appearances.next_tx_number = if batch.len() < APPEARANCES_BATCH_SIZE {
+                None
+            } else {
+                batch.last().and_then(|last| last.checked_add(1))
+            };
+            appearances.buffered.extend(batch);
+        }
+
+        let Some(next) =
+            self.addresses.iter().filter_map(|appearances| appearances.buffered.front()).min()
+        else {
+            return Ok(None)
+        };
+        let next = *next;
+        for appearances in &mut self.addresses {
+            if appearances.buffered.front() == Some(&next) {
+                appearances.buffered.pop_front();
+            }
+        }
+        Ok(Some(next))
+    }
+}
+
+/// A block with the indices of its transactions that match a `trace_filter` request.
+#[derive(Debug)]
+struct TraceFilterBlock {
+    number: BlockNumber,
+    indices: HashSet<u64>,
+    highest_index: u64,
+}
+
+impl TraceFilterBlock {
+    fn new(number: BlockNumber) -> Self {
+        Self { number, indices: HashSet::new(), highest_index: 0 }
+    }
+
+    fn insert(&mut self, index: u64) {
+        self.indices.insert(index);
+        self.highest_index = self.highest_index.max(index);
+    }
+}
+
+/// On-disk cache of the transaction traces of finalized blocks.
+///
+/// The traces of a block are stored as a JSON file named after the block hash, so entries never
+/// have to be invalidated. At most `max_blocks` blocks are cached, the files of the least recently
+/// used blocks are removed.
+#[derive(Debug)]
+struct TraceCache {
+    dir: PathBuf,
+    max_blocks: usize,
+    /// The cached blocks, in order of their last use.
+    entries: Mutex<LruMap<B256, (), Unlimited>>,
+}
+
+impl TraceCache {
+    /// Creates the cache, picking up the entries of previous runs in the order they were written.
+    fn new(dir: PathBuf, max_blocks: usize) -> Self {
+        let mut existing = std::fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let block_hash =
+                    entry.file_name().to_str()?.strip_suffix(".json")?.parse::<B256>().ok()?;
+                Some((entry.metadata().and_then(|meta| meta.modified()).ok()?, block_hash))
+            })
+            .collect::<Vec<_>>();
+        existing.sort_unstable();
+
+        let cache = Self { dir, max_blocks, entries: Mutex::new(LruMap::new(Unlimited)) };
+        for (_, block_hash) in existing {
+            cache.track(block_hash);
+        }
+        cache
+    }
+
+    /// Returns the cached traces of the block, if any.
+    fn get(&self, block_hash: B256) -> Option<Vec<LocalizedTransactionTrace>> {
+        let data = std::fs::read(self.path(block_hash)).ok()?;
+        let traces = serde_json::from_slice(&data).ok()?;
+        self.entries.lock().get(&block_hash);
+        Some(traces)
+    }
+
+    /// Caches the traces of the block. Failures are only logged, since the traces can always be
+    /// recomputed.
+    fn insert(&self, block_hash: B256, traces: &[LocalizedTransactionTrace]) {
+        if self.max_blocks == 0 {
+            return
+        }
+        if let Err(err) = self.write(block_hash, traces) {
+            warn!(target: "rpc::trace", %err, %block_hash, "Failed to cache block traces");
+            return
+        }
+        self.track(block_hash);
+    }
+
+    /// Marks the block as most recently used, evicting the least recently used blocks if the cache
+    /// is full.
+    fn track(&self, block_hash: B256) {
+        let mut entries = self.entries.lock();
+        if entries.get(&block_hash).is_some() {
+            return
+        }
+        while entries.len() >= self.max_blocks {
+            let Some((evicted, _)) = entries.pop_oldest() else { break };
+            if let Err(err) = std::fs::remove_file(self.path(evicted)) {
+                warn!(target: "rpc::trace", %err, block_hash=%evicted, "Failed to evict block traces");
+            }
+        }
+        entries.insert(block_hash, ());
+    }
+
+    fn write(&self, block_hash: B256, traces: &[LocalizedTransactionTrace]) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        // write to a temporary file first, so concurrent readers never see a partial entry
+        let tmp_path = self.dir.join(format!("{block_hash:x}.{:x}.tmp", rand::random::<u64>()));
+        std::fs::write(&tmp_path, serde_json::to_vec(traces)?)?;
+        std::fs::rename(tmp_path, self.path(block_hash))
+    }
+
+    fn path(&self, block_hash: B256) -> PathBuf {
+        self.dir.join(format!("{block_hash:x}.json"))
+    }
 }
 
 /// Helper to construct a [`LocalizedTransactionTrace`] that describes a reward to the block
@@ -643,3 +1116,278 @@ fn reward_trace(header: &SealedHeader, reward: RewardAction) -> LocalizedTransac
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{
+        cache::EthStateCache, gas_oracle::GasPriceOracle, EthApi, FeeHistoryCache,
+        FeeHistoryCacheConfig,
+    };
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        constants::ETHEREUM_BLOCK_GAS_LIMIT, Block, BlockNumHash, Header, Transaction,
+        TransactionSigned, TxKind, TxLegacy,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_testing_utils::generators::{self, generate_keys, sign_tx_with_key_pair};
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+    type TestTraceApi =
+        TraceApi<MockEthProvider, EthApi<MockEthProvider, TestPool, (), EthEvmConfig>>;
+
+    /// Test chain with three blocks:
+    /// 1. alice -> carol, bob -> dave
+    /// 2. alice -> carol, alice -> dave
+    /// 3. bob -> carol
+    struct TestChain {
+        provider: MockEthProvider,
+        alice: Address,
+        carol: Address,
+        dave: Address,
+        /// Hashes of the blocks by number
+        block_hashes: Vec<B256>,
+        /// Hashes of the transactions of alice, in chain order
+        alice_txs: Vec<B256>,
+    }
+
+    fn test_chain() -> TestChain {
+        let mut rng = generators::rng();
+        let keys = generate_keys(&mut rng, 2);
+        let (carol, dave) = (Address::random(), Address::random());
+
+        let transfer = |key: usize, nonce: u64, to: Address| -> TransactionSigned {
+            sign_tx_with_key_pair(
+                keys[key],
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce,
+                    gas_price: 1,
+                    gas_limit: 21_000,
+                    to: TxKind::Call(to),
+                    value: U256::from(1),
+                    input: Bytes::default(),
+                }),
+            )
+        };
+        // the mock serves the same state for every block, so nonces restart in every block
+        let bodies = vec![
+            vec![transfer(0, 0, carol), transfer(1, 0, dave)],
+            vec![transfer(0, 0, carol), transfer(0, 1, dave)],
+            vec![transfer(1, 0, carol)],
+        ];
+        let alice = bodies[0][0].recover_signer().unwrap();
+        let bob = bodies[0][1].recover_signer().unwrap();
+
+        let provider = MockEthProvider::default();
+        provider.add_account(alice, ExtendedAccount::new(0, U256::from(u64::MAX)));
+        provider.add_account(bob, ExtendedAccount::new(0, U256::from(u64::MAX)));
+
+        let mut block_hashes = Vec::new();
+        let mut parent_hash = B256::ZERO;
+        for (number, body) in (1..).zip(bodies.clone()) {
+            let header = Header {
+                number,
+                parent_hash,
+                gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+                ..Default::default()
+            };
+            parent_hash = header.hash_slow();
+            provider.add_block(parent_hash, Block { header, body, ..Default::default() });
+            block_hashes.push(parent_hash);
+        }
+
+        let alice_txs = bodies
+            .iter()
+            .flatten()
+            .filter(|tx| tx.recover_signer() == Some(alice))
+            .map(|tx| tx.hash())
+            .collect();
+        TestChain { provider, alice, carol, dave, block_hashes, alice_txs }
+    }
+
+    fn trace_api(provider: MockEthProvider, filter_config: TraceFilterConfig) -> TestTraceApi {
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider.clone(), Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        );
+        TraceApi::with_filter_config(provider, eth_api, BlockingTaskGuard::new(10), filter_config)
+    }
+
+    fn tx_hashes(traces: &[LocalizedTransactionTrace]) -> Vec<B256> {
+        traces.iter().filter_map(|trace| trace.transaction_hash).collect()
+    }
+
+    #[tokio::test]
+    async fn trace_filter_uses_address_appearances_index() {
+        let chain = test_chain();
+        let filter = TraceFilter {
+            from_block: Some(1),
+            to_block: Some(3),
+            from_address: vec![chain.alice],
+            ..Default::default()
+        };
+
+        // without the index, the blocks are scanned
+        let scanned = trace_api(chain.provider.clone(), TraceFilterConfig::default())
+            .trace_filter(filter.clone())
+            .await
+            .unwrap();
+        assert_eq!(tx_hashes(&scanned), chain.alice_txs);
+
+        // with the index covering the range, no block has to be scanned
+        chain.provider.set_address_appearances_tip(3);
+        let api = trace_api(
+            chain.provider.clone(),
+            TraceFilterConfig::default().max_blocks_per_filter(0),
+        );
+        let indexed = api.trace_filter(filter.clone()).await.unwrap();
+        assert_eq!(indexed, scanned);
+
+        // the indexed candidates are matched against the sender and recipient
+        let intersection = api
+            .trace_filter(TraceFilter {
+                to_address: vec![chain.dave],
+                mode: TraceFilterMode::Intersection,
+                ..filter.clone()
+            })
+            .await
+            .unwrap();
+        assert_eq!(tx_hashes(&intersection), vec![chain.alice_txs[2]]);
+
+        let union = api
+            .trace_filter(TraceFilter {
+                from_address: vec![],
+                to_address: vec![chain.carol],
+                ..filter
+            })
+            .await
+            .unwrap();
+        assert_eq!(union.len(), 3);
+        assert!(union.iter().all(|trace| matches!(
+            &trace.trace.action,
+            Action::Call(call) if call.to == chain.carol
+        )));
+    }
+
+    #[tokio::test]
+    async fn trace_filter_pages_with_after_and_count() {
+        let chain = test_chain();
+        chain.provider.set_address_appearances_tip(1);
+        let api = trace_api(chain.provider.clone(), TraceFilterConfig::default());
+        let filter = TraceFilter {
+            from_block: Some(1),
+            to_block: Some(3),
+            from_address: vec![chain.alice],
+            ..Default::default()
+        };
+
+        // the pages span the indexed and the scanned blocks
+        let mut paged = Vec::new();
+        for after in 0..4 {
+            let page = api
+                .trace_filter(TraceFilter { after: Some(after), count: Some(1), ..filter.clone() })
+                .await
+                .unwrap();
+            paged.extend(tx_hashes(&page));
+        }
+        assert_eq!(paged, chain.alice_txs);
+
+        let page = api
+            .trace_filter(TraceFilter { after: Some(1), count: Some(5), ..filter })
+            .await
+            .unwrap();
+        assert_eq!(tx_hashes(&page), chain.alice_txs[1..]);
+    }
+
+    #[tokio::test]
+    async fn trace_filter_stops_walking_the_index_after_enough_matches() {
+        let chain = test_chain();
+        chain.provider.set_address_appearances_tip(1);
+        let api = trace_api(
+            chain.provider.clone(),
+            TraceFilterConfig::default().max_blocks_per_filter(0),
+        );
+        let filter = TraceFilter {
+            from_block: Some(1),
+            to_block: Some(3),
+            from_address: vec![chain.alice],
+            ..Default::default()
+        };
+
+        // the blocks after the index would have to be scanned, which exceeds the block limit
+        assert!(api.trace_filter(filter.clone()).await.is_err());
+
+        // the indexed blocks already contain enough matches, so no block is scanned
+        let page = api.trace_filter(TraceFilter { count: Some(1), ..filter }).await.unwrap();
+        assert_eq!(tx_hashes(&page), vec![chain.alice_txs[0]]);
+    }
+
+    #[tokio::test]
+    async fn trace_filter_caches_finalized_blocks() {
+        let chain = test_chain();
+        chain.provider.set_finalized(BlockNumHash::new(1, chain.block_hashes[0]));
+        let dir = tempfile::tempdir().unwrap();
+        let api = trace_api(
+            chain.provider.clone(),
+            TraceFilterConfig::default().cache_dir(Some(dir.path().to_path_buf())),
+        );
+        let filter = TraceFilter {
+            from_block: Some(1),
+            to_block: Some(3),
+            from_address: vec![chain.alice],
+            ..Default::default()
+        };
+
+        let traces = api.trace_filter(filter.clone()).await.unwrap();
+        assert_eq!(tx_hashes(&traces), chain.alice_txs);
+
+        // only the finalized block is cached, with the traces of all its transactions
+        let cache = api.inner.trace_cache.as_ref().unwrap();
+        assert!(cache.path(chain.block_hashes[0]).exists());
+        assert!(!cache.path(chain.block_hashes[1]).exists());
+        assert_eq!(cache.get(chain.block_hashes[0]).unwrap().len(), 2);
+
+        // the cached traces are served again
+        assert_eq!(api.trace_filter(filter).await.unwrap(), traces);
+    }
+
+    #[test]
+    fn trace_cache_evicts_least_recently_used_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let [a, b, c] = [B256::with_last_byte(1), B256::with_last_byte(2), B256::with_last_byte(3)];
+
+        let cache = TraceCache::new(dir.path().to_path_buf(), 2);
+        cache.insert(a, &[]);
+        cache.insert(b, &[]);
+        // using `a` makes `b` the least recently used block
+        assert_eq!(cache.get(a), Some(Vec::new()));
+        cache.insert(c, &[]);
+
+        assert!(cache.get(b).is_none());
+        assert!(!cache.path(b).exists());
+        assert!(cache.get(a).is_some());
+        assert!(cache.get(c).is_some());
+
+        // the entries of previous runs count towards the limit
+        let cache = TraceCache::new(dir.path().to_path_buf(), 1);
+        assert_eq!(cache.entries.lock().len(), 1);
+        assert_eq!([a, c].into_iter().filter(|hash| cache.path(*hash).exists()).count(), 1);
+
+        // nothing is cached without capacity
+        let cache = TraceCache::new(dir.path().to_path_buf(), 0);
+        cache.insert(b, &[]);
+        assert!(cache.get(b).is_none());
+    }
+}
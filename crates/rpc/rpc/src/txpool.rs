@@ -1,13 +1,17 @@
+use crate::eth::error::EthApiError;
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::{core::RpcResult as Result, types::ErrorObjectOwned, RpcModule};
 use reth_primitives::Address;
 use reth_rpc_api::TxPoolApiServer;
 use reth_rpc_types::{
+    streaming::{JsonMapWriter, StreamedJson, StreamingJsonError},
     txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
     Transaction,
 };
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
-use std::collections::BTreeMap;
+use reth_transaction_pool::{
+    AllPoolTransactions, PoolTransaction, TransactionPool, ValidPoolTransaction,
+};
+use std::{collections::BTreeMap, sync::Arc};
 use tracing::trace;
 
 /// `txpool` API implementation.
@@ -17,12 +21,20 @@ use tracing::trace;
 pub struct TxPoolApi<Pool> {
     /// An interface to interact with the pool
     pool: Pool,
+    /// Maximum size of a streamed `txpool_content` response in bytes.
+    max_response_size: usize,
 }
 
 impl<Pool> TxPoolApi<Pool> {
     /// Creates a new instance of `TxpoolApi`.
     pub const fn new(pool: Pool) -> Self {
-        Self { pool }
+        Self::with_max_response_size(pool, usize::MAX)
+    }
+
+    /// Creates a new instance of `TxpoolApi` whose streamed `txpool_content` responses are
+    /// rejected once they exceed `max_response_size` bytes, see [`Self::into_streamed_rpc`].
+    pub const fn with_max_response_size(pool: Pool, max_response_size: usize) -> Self {
+        Self { pool, max_response_size }
     }
 }
 
@@ -54,6 +66,70 @@ where
 
         content
     }
+
+    /// Returns the serialized content of the pool.
+    ///
+    /// The transactions are converted and serialized one sender at a time, so the converted
+    /// transactions of the entire pool are never materialized at once.
+    fn streamed_content(
+        &self,
+    ) -> std::result::Result<StreamedJson<TxpoolContent>, StreamingJsonError> {
+        fn write_by_sender<T: PoolTransaction>(
+            mut txs: Vec<Arc<ValidPoolTransaction<T>>>,
+            limit: usize,
+        ) -> std::result::Result<
+            StreamedJson<BTreeMap<Address, BTreeMap<String, Transaction>>>,
+            StreamingJsonError,
+        > {
+            txs.sort_unstable_by_key(|tx| (tx.sender(), tx.nonce()));
+            let mut writer = JsonMapWriter::new(limit);
+            for sender_txs in txs.chunk_by(|a, b| a.sender() == b.sender()) {
+                let by_nonce = sender_txs
+                    .iter()
+                    .map(|tx| {
+                        (
+                            tx.nonce().to_string(),
+                            reth_rpc_types_compat::transaction::from_recovered(
+                                tx.transaction.to_recovered_transaction(),
+                            ),
+                        )
+                    })
+                    .collect::<BTreeMap<_, _>>();
+                writer.insert(&sender_txs[0].sender(), &by_nonce)?;
+            }
+            Ok(writer.finish())
+        }
+
+        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+
+        let mut content = JsonMapWriter::new(self.max_response_size);
+        let pending = write_by_sender(pending, self.max_response_size)?;
+        content.insert(&"pending", &pending)?;
+        drop(pending);
+        let queued =
+            write_by_sender(queued, self.max_response_size.saturating_sub(content.written()))?;
+        content.insert(&"queued", &queued)?;
+
+        Ok(content.finish())
+    }
+
+    /// Returns the `txpool` namespace with `txpool_content` replaced by a handler that serializes
+    /// the content one sender at a time.
+    ///
+    /// The response is the same as the one of the typed [`TxPoolApiServer::txpool_content`], but
+    /// it aborts as soon as it exceeds the max response size.
+    pub fn into_streamed_rpc(self) -> RpcModule<Self> {
+        let mut module = TxPoolApiServer::into_rpc(self);
+        module.remove_method("txpool_content");
+        module
+            .register_method("txpool_content", |_, this, _| {
+                trace!(target: "rpc::eth", "Serving txpool_content");
+                this.streamed_content()
+                    .map_err(|err| ErrorObjectOwned::from(EthApiError::from(err)))
+            })
+            .expect("method was removed");
+        module
+    }
 }
 
 #[async_trait]
@@ -139,3 +215,28 @@ impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
         f.debug_struct("TxpoolApi").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionOrigin,
+    };
+
+    #[tokio::test]
+    async fn streamed_content_matches_content() {
+        let pool = testing_pool();
+        let tx = MockTransaction::eip1559();
+        for tx in [tx.clone(), tx.next(), MockTransaction::eip1559().with_nonce(5)] {
+            pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+        }
+
+        let api = TxPoolApi::new(pool.clone());
+        let streamed = api.streamed_content().unwrap();
+        assert_eq!(streamed.as_raw().unwrap(), serde_json::to_string(&api.content()).unwrap());
+
+        let api = TxPoolApi::with_max_response_size(pool, 64);
+        assert!(matches!(api.streamed_content(), Err(StreamingJsonError::ResponseTooLarge(_))));
+    }
+}
@@ -0,0 +1,637 @@
+//! `flashbots` namespace implementation: validation of block builder submissions.
+
+use crate::{
+    eth::error::EthApiError,
+    result::{internal_rpc_err, invalid_params_rpc_err},
+};
+use async_trait::async_trait;
+use jsonrpsee::{core::RpcResult, types::ErrorObject};
+use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_errors::ProviderError;
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use reth_primitives::{Address, Block, BlockId, GotExpected, SealedBlock, B256, U256};
+use reth_provider::{BlockReaderIdExt, StateProviderFactory};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_api::BlockSubmissionValidationApiServer;
+use reth_rpc_server_types::constants::validation::{
+    DEFAULT_MAX_CONCURRENT_VALIDATIONS, DEFAULT_VALIDATION_TIMEOUT, DEFAULT_VALIDATION_WINDOW,
+};
+use reth_rpc_types::{
+    beacon::relay::{BidTrace, BuilderBlockValidationRequest, BuilderBlockValidationRequestV2},
+    engine::PayloadError,
+};
+use reth_rpc_types_compat::engine::payload::{try_payload_v1_to_block, try_payload_v2_to_block};
+use reth_tasks::{pool::BlockingTaskGuard, TaskSpawner};
+use std::{collections::HashSet, future::Future, sync::Arc, time::Duration};
+use tokio::sync::oneshot;
+use tracing::trace;
+
+/// Configuration for the [`ValidationApi`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationApiConfig {
+    /// Addresses that must not be the sender or recipient of a transaction, or be touched by the
+    /// execution of a submitted block.
+    pub disallow: HashSet<Address>,
+    /// The maximum number of blocks the submitted block may be behind the latest block.
+    pub validation_window: u64,
+    /// The maximum number of submissions that are validated concurrently.
+    pub max_concurrent_validations: usize,
+    /// The maximum time a validation may take, including the time waiting for a free validation
+    /// slot.
+    pub timeout: Duration,
+}
+
+impl Default for ValidationApiConfig {
+    fn default() -> Self {
+        Self {
+            disallow: HashSet::new(),
+            validation_window: DEFAULT_VALIDATION_WINDOW,
+            max_concurrent_validations: DEFAULT_MAX_CONCURRENT_VALIDATIONS,
+            timeout: DEFAULT_VALIDATION_TIMEOUT,
+        }
+    }
+}
+
+/// `flashbots` block submission validation API implementation.
+///
+/// Relays use this to validate the blocks builders submit: the payload is executed on top of its
+/// parent and checked against the bid.
+pub struct ValidationApi<Provider, E> {
+    inner: Arc<ValidationApiInner<Provider, E>>,
+}
+
+// === impl ValidationApi ===
+
+impl<Provider, E> ValidationApi<Provider, E> {
+    /// Create a new instance of the [`ValidationApi`]
+    pub fn new(
+        provider: Provider,
+        consensus: Arc<dyn Consensus>,
+        executor_provider: E,
+        config: ValidationApiConfig,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let validation_guard = BlockingTaskGuard::new(config.max_concurrent_validations);
+        let inner = Arc::new(ValidationApiInner {
+            provider,
+            consensus,
+            executor_provider,
+            config,
+            task_spawner,
+            validation_guard,
+        });
+        Self { inner }
+    }
+}
+
+impl<Provider, E> ValidationApi<Provider, E>
+where
+    Provider: BlockReaderIdExt + StateProviderFactory + Clone + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Validates a `flashbots_validateBuilderSubmissionV1` request.
+    pub async fn validate_builder_submission_v1(
+        &self,
+        request: BuilderBlockValidationRequest,
+    ) -> Result<(), ValidationApiError> {
+        let BuilderBlockValidationRequest { request, registered_gas_limit } = request;
+        let block = try_payload_v1_to_block(request.execution_payload)?;
+        self.validate_with_budget(block, request.message, registered_gas_limit, None).await
+    }
+
+    /// Validates a `flashbots_validateBuilderSubmissionV2` request.
+    pub async fn validate_builder_submission_v2(
+        &self,
+        request: BuilderBlockValidationRequestV2,
+    ) -> Result<(), ValidationApiError> {
+        let BuilderBlockValidationRequestV2 { request, registered_gas_limit, withdrawals_root } =
+            request;
+        let block = try_payload_v2_to_block(request.execution_payload)?;
+        self.validate_with_budget(
+            block,
+            request.message,
+            registered_gas_limit,
+            Some(withdrawals_root),
+        )
+        .await
+    }
+
+    /// Validates the block on a blocking task, within the configured validation budget.
+    async fn validate_with_budget(
+        &self,
+        block: Block,
+        message: BidTrace,
+        registered_gas_limit: u64,
+        withdrawals_root: Option<B256>,
+    ) -> Result<(), ValidationApiError> {
+        let timeout = self.inner.config.timeout;
+        let validation = async {
+            // the permit is held until the blocking validation finishes, even if it times out
+            let permit = self.inner.validation_guard.clone().acquire_owned().await;
+            self.on_blocking_task(move |this| async move {
+                let _permit = permit;
+                this.validate_block(block, message, registered_gas_limit, withdrawals_root)
+            })
+            .await
+        };
+        tokio::time::timeout(timeout, validation)
+            .await
+            .map_err(|_| ValidationApiError::Timeout(timeout))?
+    }
+
+    /// Executes the future on a new blocking task.
+    async fn on_blocking_task<C, F, R>(&self, c: C) -> Result<R, ValidationApiError>
+    where
+        C: FnOnce(Self) -> F,
+        F: Future<Output = Result<R, ValidationApiError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let this = self.clone();
+        let f = c(this);
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let res = f.await;
+            let _ = tx.send(res);
+        }));
+        rx.await.map_err(|_| ValidationApiError::Eth(EthApiError::InternalEthError))?
+    }
+
+    /// Validates the submitted block against the bid and executes it on top of its parent.
+    fn validate_block(
+        &self,
+        block: Block,
+        message: BidTrace,
+        registered_gas_limit: u64,
+        withdrawals_root: Option<B256>,
+    ) -> Result<(), ValidationApiError> {
+        let block = block.seal_slow();
+        trace!(target: "rpc::flashbots", hash = %block.hash(), number = block.number, "Validating builder submission");
+
+        validate_message_against_block(&message, &block)?;
+        if let Some(withdrawals_root) = withdrawals_root {
+            if block.withdrawals_root != Some(withdrawals_root) {
+                return Err(ValidationApiError::WithdrawalsRootMismatch(GotExpected {
+                    got: block.withdrawals_root.unwrap_or_default(),
+                    expected: withdrawals_root,
+                }))
+            }
+        }
+
+        let block =
+            block.try_seal_with_senders().map_err(|_| ValidationApiError::InvalidSignature)?;
+        self.validate_blacklist(
+            [block.beneficiary, message.proposer_fee_recipient]
+                .into_iter()
+                .chain(block.senders.iter().copied())
+                .chain(block.body.iter().filter_map(|tx| tx.to())),
+        )?;
+
+        let latest =
+            self.inner.provider.latest_header()?.ok_or(ValidationApiError::MissingLatestBlock)?;
+        if latest.number.saturating_sub(block.number) > self.inner.config.validation_window {
+            return Err(ValidationApiError::BlockTooOld {
+                number: block.number,
+                latest: latest.number,
+            })
+        }
+
+        let parent = self
+            .inner
+            .provider
+            .sealed_header_by_id(BlockId::from(block.parent_hash))?
+            .ok_or(ValidationApiError::MissingParentBlock(block.parent_hash))?;
+
+        let consensus = &self.inner.consensus;
+        consensus.validate_header(&block.header)?;
+        consensus.validate_header_against_parent(&block.header, &parent)?;
+        consensus.validate_block_pre_execution(&block)?;
+        validate_gas_limit(registered_gas_limit, parent.gas_limit, block.gas_limit)?;
+
+        let state_provider = self.inner.provider.history_by_block_hash(parent.hash())?;
+        let balance_before =
+            state_provider.account_balance(message.proposer_fee_recipient)?.unwrap_or_default();
+
+        let executor =
+            self.inner.executor_provider.executor(StateProviderDatabase::new(state_provider));
+        let block = block.unseal();
+        let BlockExecutionOutput { state, receipts, requests, db, .. } =
+            executor.execute((&block, U256::MAX).into())?;
+        consensus
+            .validate_block_post_execution(&block, PostExecutionInput::new(&receipts, &requests))?;
+
+        self.validate_blacklist(state.state.keys().copied())?;
+
+        // the proposer is either paid by a balance increase of the fee recipient over the whole
+        // block, or by the last transaction of the block
+        let balance_after = state
+            .account(&message.proposer_fee_recipient)
+            .and_then(|account| account.info.as_ref())
+            .map(|info| info.balance)
+            .unwrap_or(balance_before);
+        if balance_after < balance_before.saturating_add(message.value) {
+            let (Some(receipt), Some(tx)) = (receipts.last(), block.body.last()) else {
+                return Err(ValidationApiError::ProposerPayment)
+            };
+            if !receipt.success ||
+                tx.to() != Some(message.proposer_fee_recipient) ||
+                tx.value() != message.value ||
+                !tx.input().is_empty() ||
+                tx.effective_tip_per_gas(block.base_fee_per_gas).unwrap_or_default() != 0
+            {
+                return Err(ValidationApiError::ProposerPayment)
+            }
+        }
+
+        let state_root = db.database.state_root(&state)?;
+        if state_root != block.state_root {
+            return Err(ValidationApiError::StateRootMismatch(GotExpected {
+                got: state_root,
+                expected: block.state_root,
+            }))
+        }
+
+        Ok(())
+    }
+
+    /// Returns an error if any of the addresses is disallowed.
+    fn validate_blacklist(
+        &self,
+        mut addresses: impl Iterator<Item = Address>,
+    ) -> Result<(), ValidationApiError> {
+        match addresses.find(|address| self.inner.config.disallow.contains(address)) {
+            Some(address) => Err(ValidationApiError::Blacklist(address)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl<Provider, E> BlockSubmissionValidationApiServer for ValidationApi<Provider, E>
+where
+    Provider: BlockReaderIdExt + StateProviderFactory + Clone + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Handler for `flashbots_validateBuilderSubmissionV1`
+    async fn validate_builder_submission_v1(
+        &self,
+        request: BuilderBlockValidationRequest,
+    ) -> RpcResult<()> {
+        Ok(Self::validate_builder_submission_v1(self, request).await?)
+    }
+
+    /// Handler for `flashbots_validateBuilderSubmissionV2`
+    async fn validate_builder_submission_v2(
+        &self,
+        request: BuilderBlockValidationRequestV2,
+    ) -> RpcResult<()> {
+        Ok(Self::validate_builder_submission_v2(self, request).await?)
+    }
+}
+
+impl<Provider, E> std::fmt::Debug for ValidationApi<Provider, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidationApi").finish_non_exhaustive()
+    }
+}
+
+impl<Provider, E> Clone for ValidationApi<Provider, E> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct ValidationApiInner<Provider, E> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// Consensus rules the submitted blocks are checked against.
+    consensus: Arc<dyn Consensus>,
+    /// Executes the submitted blocks.
+    executor_provider: E,
+    /// Validation settings.
+    config: ValidationApiConfig,
+    /// The type that can spawn tasks which would otherwise block.
+    task_spawner: Box<dyn TaskSpawner>,
+    /// Restricts the number of concurrent validations.
+    validation_guard: BlockingTaskGuard,
+}
+
+/// Checks that the bid matches the submitted block.
+fn validate_message_against_block(
+    message: &BidTrace,
+    block: &SealedBlock,
+) -> Result<(), ValidationApiError> {
+    if message.parent_hash != block.parent_hash {
+        return Err(ValidationApiError::ParentHashMismatch(GotExpected {
+            got: message.parent_hash,
+            expected: block.parent_hash,
+        }))
+    }
+    if message.block_hash != block.hash() {
+        return Err(ValidationApiError::BlockHashMismatch(GotExpected {
+            got: message.block_hash,
+            expected: block.hash(),
+        }))
+    }
+    if message.gas_limit != block.gas_limit {
+        return Err(ValidationApiError::GasLimitMismatch(GotExpected {
+            got: message.gas_limit,
+            expected: block.gas_limit,
+        }))
+    }
+    if message.gas_used != block.gas_used {
+        return Err(ValidationApiError::GasUsedMismatch(GotExpected {
+            got: message.gas_used,
+            expected: block.gas_used,
+        }))
+    }
+    Ok(())
+}
+
+/// Checks that the gas limit of the block moves from the parent gas limit towards the gas limit
+/// the proposer registered, as far as the maximum adjustment of 1/1024 allows.
+fn validate_gas_limit(
+    registered_gas_limit: u64,
+    parent_gas_limit: u64,
+    gas_limit: u64,
+) -> Result<(), ValidationApiError> {
+    // the gas limit may change by less than 1/1024 of the parent gas limit, parents with a gas
+    // limit below 1024 can't change at all
+    let max_change = (parent_gas_limit / 1024).saturating_sub(1);
+    let max_gas_limit = parent_gas_limit.saturating_add(max_change);
+    let min_gas_limit = parent_gas_limit.saturating_sub(max_change);
+    let expected = registered_gas_limit.clamp(min_gas_limit, max_gas_limit);
+    if gas_limit != expected {
+        return Err(ValidationApiError::GasLimitTarget(GotExpected { got: gas_limit, expected }))
+    }
+    Ok(())
+}
+
+/// [`ValidationApi`] specific errors.
+#[derive(Debug, thiserror::Error)]
+pub enum ValidationApiError {
+    /// The parent hash of the bid does not match the block.
+    #[error("parent hash mismatch: {0}")]
+    ParentHashMismatch(GotExpected<B256>),
+    /// The block hash of the bid does not match the block.
+    #[error("block hash mismatch: {0}")]
+    BlockHashMismatch(GotExpected<B256>),
+    /// The gas limit of the bid does not match the block.
+    #[error("gas limit mismatch: {0}")]
+    GasLimitMismatch(GotExpected<u64>),
+    /// The gas used of the bid does not match the block.
+    #[error("gas used mismatch: {0}")]
+    GasUsedMismatch(GotExpected<u64>),
+    /// The withdrawals root of the request does not match the block.
+    #[error("withdrawals root mismatch: {0}")]
+    WithdrawalsRootMismatch(GotExpected<B256>),
+    /// The gas limit of the block does not move towards the registered gas limit.
+    #[error("incorrect gas limit: {0}")]
+    GasLimitTarget(GotExpected<u64>),
+    /// The block is too far behind the latest block.
+    #[error("block {number} is too old, latest block is {latest}")]
+    BlockTooOld {
+        /// The number of the submitted block.
+        number: u64,
+        /// The number of the latest block.
+        latest: u64,
+    },
+    /// The latest block is not available.
+    #[error("missing latest block")]
+    MissingLatestBlock,
+    /// The parent of the block is unknown.
+    #[error("missing parent block {0}")]
+    MissingParentBlock(B256),
+    /// A transaction signature is invalid.
+    #[error("invalid transaction signature")]
+    InvalidSignature,
+    /// A disallowed address is part of the block.
+    #[error("block contains blacklisted address {0}")]
+    Blacklist(Address),
+    /// The proposer is not paid the value of the bid.
+    #[error("proposer payment not found")]
+    ProposerPayment,
+    /// The state root of the block does not match the executed state.
+    #[error("state root mismatch: {0}")]
+    StateRootMismatch(GotExpected<B256>),
+    /// The validation did not finish within the timeout.
+    #[error("validation exceeded the timeout of {0:?}")]
+    Timeout(Duration),
+    /// The payload could not be converted into a block.
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+    /// The block violates a consensus rule.
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    /// The block failed to execute.
+    #[error(transparent)]
+    Execution(#[from] reth_evm::execute::BlockExecutionError),
+    /// Error while accessing the database.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Any other error.
+    #[error(transparent)]
+    Eth(#[from] EthApiError),
+}
+
+impl From<ValidationApiError> for ErrorObject<'static> {
+    fn from(error: ValidationApiError) -> Self {
+        match error {
+            ValidationApiError::Provider(_) | ValidationApiError::Timeout(_) => {
+                internal_rpc_err(error.to_string())
+            }
+            ValidationApiError::Eth(err) => err.into(),
+            err => invalid_params_rpc_err(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_consensus::noop::NoopConsensus;
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{Header, SealedHeader};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_tasks::TokioTaskExecutor;
+
+    const GAS_LIMIT: u64 = 30_000_000;
+
+    /// Returns an api that validates against a chain with a parent block at height 1.
+    fn setup(
+        config: ValidationApiConfig,
+    ) -> (ValidationApi<MockEthProvider, EthExecutorProvider>, SealedHeader) {
+        let provider = MockEthProvider::default();
+        let parent = Header {
+            number: 1,
+            gas_limit: GAS_LIMIT,
+            base_fee_per_gas: Some(7),
+            ..Default::default()
+        }
+        .seal_slow();
+        provider.add_header(parent.hash(), parent.header().clone());
+        provider.add_block(
+            parent.hash(),
+            Block { header: parent.header().clone(), ..Default::default() },
+        );
+
+        let api = ValidationApi::new(
+            provider,
+            Arc::new(NoopConsensus::default()),
+            EthExecutorProvider::mainnet(),
+            config,
+            Box::<TokioTaskExecutor>::default(),
+        );
+        (api, parent)
+    }
+
+    /// Returns an empty child block of `parent` and the matching bid.
+    fn child_block(parent: &SealedHeader) -> (Block, BidTrace) {
+        let block = Block {
+            header: Header {
+                parent_hash: parent.hash(),
+                number: parent.number + 1,
+                gas_limit: GAS_LIMIT,
+                beneficiary: Address::with_last_byte(0xbb),
+                state_root: B256::with_last_byte(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let message = BidTrace {
+            parent_hash: parent.hash(),
+            block_hash: block.header.hash_slow(),
+            gas_limit: GAS_LIMIT,
+            proposer_fee_recipient: Address::with_last_byte(0xfe),
+            ..Default::default()
+        };
+        (block, message)
+    }
+
+    #[test]
+    fn rejects_bid_that_does_not_match_block() {
+        let (api, parent) = setup(Default::default());
+
+        let (block, mut message) = child_block(&parent);
+        message.parent_hash = B256::with_last_byte(1);
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT, None),
+            Err(ValidationApiError::ParentHashMismatch(_))
+        ));
+
+        let (block, mut message) = child_block(&parent);
+        message.gas_limit += 1;
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT, None),
+            Err(ValidationApiError::GasLimitMismatch(_))
+        ));
+
+        let (block, message) = child_block(&parent);
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT, Some(B256::with_last_byte(1))),
+            Err(ValidationApiError::WithdrawalsRootMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_blacklisted_addresses() {
+        let config = ValidationApiConfig {
+            disallow: HashSet::from([Address::with_last_byte(0xbb)]),
+            ..Default::default()
+        };
+        let (api, parent) = setup(config);
+
+        let (block, message) = child_block(&parent);
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT, None),
+            Err(ValidationApiError::Blacklist(address)) if address == Address::with_last_byte(0xbb)
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_and_old_parents() {
+        let config = ValidationApiConfig { validation_window: 0, ..Default::default() };
+        let (api, parent) = setup(config);
+
+        let unknown = Header { number: 1, gas_limit: 1, ..Default::default() }.seal_slow();
+        let (block, message) = child_block(&unknown);
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT, None),
+            Err(ValidationApiError::MissingParentBlock(hash)) if hash == unknown.hash()
+        ));
+
+        // the block is behind the latest block by more than the validation window
+        let (mut block, _) = child_block(&parent);
+        block.header.number = 0;
+        let message = BidTrace {
+            parent_hash: parent.hash(),
+            block_hash: block.header.hash_slow(),
+            gas_limit: GAS_LIMIT,
+            ..Default::default()
+        };
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT, None),
+            Err(ValidationApiError::BlockTooOld { number: 0, latest: 1 })
+        ));
+    }
+
+    #[test]
+    fn rejects_wrong_gas_limit_target() {
+        let (api, parent) = setup(Default::default());
+
+        let (block, message) = child_block(&parent);
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT * 2, None),
+            Err(ValidationApiError::GasLimitTarget(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_proposer_payment_and_state_root_mismatch() {
+        let (api, parent) = setup(Default::default());
+
+        let (block, mut message) = child_block(&parent);
+        message.value = U256::from(1);
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT, None),
+            Err(ValidationApiError::ProposerPayment)
+        ));
+
+        // the state root of the mock provider is always zero
+        let (block, message) = child_block(&parent);
+        assert!(matches!(
+            api.validate_block(block, message, GAS_LIMIT, None),
+            Err(ValidationApiError::StateRootMismatch(GotExpected { got, .. })) if got == B256::ZERO
+        ));
+    }
+
+    #[test]
+    fn gas_limit_of_tiny_parents() {
+        // parents with a gas limit below 1024 can't change their gas limit
+        assert!(validate_gas_limit(GAS_LIMIT, 0, 0).is_ok());
+        assert!(validate_gas_limit(GAS_LIMIT, 1_000, 1_000).is_ok());
+        assert!(matches!(
+            validate_gas_limit(GAS_LIMIT, 1_000, 1_001),
+            Err(ValidationApiError::GasLimitTarget(GotExpected { expected: 1_000, .. }))
+        ));
+        assert!(validate_gas_limit(u64::MAX, u64::MAX, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn gas_limit_moves_towards_registered_gas_limit() {
+        let parent = 30_000_000;
+        let step = parent / 1024 - 1;
+
+        // the registered gas limit is reached
+        assert!(validate_gas_limit(parent, parent, parent).is_ok());
+        assert!(validate_gas_limit(parent + 1_000, parent, parent + 1_000).is_ok());
+
+        // the gas limit moves as far as possible
+        assert!(validate_gas_limit(36_000_000, parent, parent + step).is_ok());
+        assert!(validate_gas_limit(20_000_000, parent, parent - step).is_ok());
+        assert!(matches!(
+            validate_gas_limit(36_000_000, parent, parent),
+            Err(ValidationApiError::GasLimitTarget(GotExpected { got, expected }))
+                if got == parent && expected == parent + step
+        ));
+    }
+}
@@ -244,6 +244,9 @@ where
     ///   [`StageId::Execution`]
     /// - [`StaticFileSegment::Transactions`](reth_static_file_types::StaticFileSegment::Transactions)
     ///   -> [`StageId::Bodies`]
+    /// - [`StaticFileSegment::AccountChangeSets`](reth_static_file_types::StaticFileSegment::AccountChangeSets)
+    ///   and [`StaticFileSegment::StorageChangeSets`](reth_static_file_types::StaticFileSegment::StorageChangeSets)
+    ///   -> [`StageId::Execution`]
     ///
     /// CAUTION: This method locks the static file producer Mutex, hence can block the thread if the
     /// lock is occupied.
@@ -264,6 +267,8 @@ where
                 headers: stages_checkpoints[0],
                 receipts: stages_checkpoints[1],
                 transactions: stages_checkpoints[2],
+                account_changesets: stages_checkpoints[1],
+                storage_changesets: stages_checkpoints[1],
             })?;
             static_file_producer.run(targets)?;
             stages_checkpoints.into_iter().min().expect("exists")
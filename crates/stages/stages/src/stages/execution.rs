@@ -390,6 +390,25 @@ where
         // This also updates `PlainStorageState` and `PlainAccountState`.
         let bundle_state_with_receipts = provider.unwind_or_peek_state::<true>(range.clone())?;
 
+        // Unwind account and storage changesets that were moved to static files.
+        let static_file_provider = provider.static_file_provider();
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            let Some(highest_block) = static_file_provider.get_highest_static_file_block(segment)
+            else {
+                continue
+            };
+            if highest_block >= *range.start() {
+                let to_delete = highest_block - *range.start() + 1;
+                let mut writer = static_file_provider.latest_writer(segment)?;
+                if segment == StaticFileSegment::AccountChangeSets {
+                    writer.prune_account_changesets(to_delete)?;
+                } else {
+                    writer.prune_storage_changesets(to_delete)?;
+                }
+            }
+        }
+
         // Prepare the input for post unwind commit hook, where an `ExExNotification` will be sent.
         if self.exex_manager_handle.has_exexs() {
             // Get the blocks for the unwound range.
@@ -708,7 +727,14 @@ mod tests {
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
     use reth_chainspec::ChainSpecBuilder;
-    use reth_db_api::{models::AccountBeforeTx, transaction::DbTxMut};
+    use reth_db_api::{
+        cursor::DbDupCursorRO,
+        models::{
+            AccountBeforeTx, AccountChangeSet, BlockNumberAddress, StorageBeforeTx,
+            StorageChangeSet,
+        },
+        transaction::DbTxMut,
+    };
     use reth_evm_ethereum::execute::EthExecutorProvider;
     use reth_execution_errors::BlockValidationError;
     use reth_primitives::{
@@ -1181,6 +1207,132 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn unwind_changesets_from_static_files() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let input = ExecInput { target: Some(1), checkpoint: None };
+        let mut genesis_rlp = hex!("f901faf901f5a00000000000000000000000000000000000000000000000000000000000000000a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa045571b40ae66ca7480791bbb2887286e4e4c4b1b298b191c889d6959023a32eda056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000808502540be400808000a00000000000000000000000000000000000000000000000000000000000000000880000000000000000c0c0").as_slice();
+        let genesis = SealedBlock::decode(&mut genesis_rlp).unwrap();
+        let mut block_rlp = hex!("f90262f901f9a075c371ba45999d87f4542326910a11af515897aebce5265d3f6acd1f1161f82fa01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa098f2dcd87c8ae4083e7017a05456c14eea4b1db2032126e27b3b1563d57d7cc0a08151d548273f6683169524b66ca9fe338b9ce42bc3540046c828fd939ae23bcba03f4e5c2ec5b2170b711d97ee755c160457bb58d8daa338e835ec02ae6860bbabb901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000018502540be40082a8798203e800a00000000000000000000000000000000000000000000000000000000000000000880000000000000000f863f861800a8405f5e10094100000000000000000000000000000000000000080801ba07e09e26678ed4fac08a249ebe8ed680bf9051a5e14ad223e4b2b9d26e0208f37a05f6e3f188e3e6eab7d7d3b6568f5eac7d687b08d307d3154ccd8c87b4630509bc0").as_slice();
+        let block = SealedBlock::decode(&mut block_rlp).unwrap();
+        provider.insert_historical_block(genesis.try_seal_with_senders().unwrap(), None).unwrap();
+        provider.insert_historical_block(block.try_seal_with_senders().unwrap(), None).unwrap();
+        provider
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        {
+            let mut receipts_writer =
+                provider.static_file_provider().latest_writer(StaticFileSegment::Receipts).unwrap();
+            receipts_writer.increment_block(StaticFileSegment::Receipts, 0).unwrap();
+            receipts_writer.commit().unwrap();
+        }
+        provider.commit().unwrap();
+
+        // variables
+        let code = hex!("5a465a905090036002900360015500");
+        let balance = U256::from(0x3635c9adc5dea00000u128);
+        let code_hash = keccak256(code);
+        // pre state
+        let provider = factory.provider_rw().unwrap();
+
+        let db_tx = provider.tx_ref();
+        let acc1 = address!("1000000000000000000000000000000000000000");
+        let acc1_info = Account { nonce: 0, balance: U256::ZERO, bytecode_hash: Some(code_hash) };
+        let acc2 = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+        let acc2_info = Account { nonce: 0, balance, bytecode_hash: None };
+
+        db_tx.put::<tables::PlainAccountState>(acc1, acc1_info).unwrap();
+        db_tx.put::<tables::PlainAccountState>(acc2, acc2_info).unwrap();
+        db_tx.put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code.to_vec().into())).unwrap();
+        provider.commit().unwrap();
+
+        // execute
+        let provider = factory.provider_rw().unwrap();
+        let result = stage().execute(&provider, input).unwrap();
+        provider.commit().unwrap();
+
+        // move the changesets of both blocks to static files
+        let provider = factory.provider_rw().unwrap();
+        let static_file_provider = factory.static_file_provider();
+        {
+            let mut writer =
+                static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+            let mut cursor =
+                provider.tx_ref().cursor_dup_read::<tables::AccountChangeSets>().unwrap();
+            for block in 0..=1 {
+                let accounts = cursor
+                    .walk_dup(Some(block), None)
+                    .unwrap()
+                    .map(|entry| entry.map(|(_, account)| account))
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                writer.append_account_changeset(block, AccountChangeSet { accounts }).unwrap();
+            }
+            writer.commit().unwrap();
+        }
+        {
+            let mut writer =
+                static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+            let mut cursor = provider.tx_ref().cursor_read::<tables::StorageChangeSets>().unwrap();
+            for block in 0..=1 {
+                let storages = cursor
+                    .walk_range(BlockNumberAddress::range(block..=block))
+                    .unwrap()
+                    .map(|entry| {
+                        entry.map(|(key, storage)| StorageBeforeTx {
+                            address: key.address(),
+                            key: storage.key,
+                            value: storage.value,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                writer.append_storage_changeset(block, StorageChangeSet { storages }).unwrap();
+            }
+            writer.commit().unwrap();
+        }
+        assert!(static_file_provider
+            .storage_changeset(1)
+            .unwrap()
+            .is_some_and(|changeset| !changeset.storages.is_empty()));
+        provider.tx_ref().clear::<tables::AccountChangeSets>().unwrap();
+        provider.tx_ref().clear::<tables::StorageChangeSets>().unwrap();
+        provider.commit().unwrap();
+
+        // unwind
+        let provider = factory.provider_rw().unwrap();
+        stage()
+            .unwind(
+                &provider,
+                UnwindInput { checkpoint: result.checkpoint, unwind_to: 0, bad_block: None },
+            )
+            .unwrap();
+        static_file_provider.commit().unwrap();
+        provider.commit().unwrap();
+
+        // assert the unwound block is pruned from the static files
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(0));
+        }
+        assert_eq!(static_file_provider.account_changeset(1), Ok(None));
+        assert_eq!(static_file_provider.storage_changeset(1), Ok(None));
+
+        // assert the state is unwound with the static file changesets
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.basic_account(acc1), Ok(Some(acc1_info)));
+        assert_eq!(provider.basic_account(acc2), Ok(Some(acc2_info)));
+        assert_eq!(
+            provider.basic_account(address!("2adc25665018aa1fe0e6bc666dac8fc2697ff9ba")),
+            Ok(None)
+        );
+        assert_eq!(provider.tx_ref().entries::<tables::PlainStorageState>().unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn test_selfdestruct() {
         let test_db = TestStageDB::default();
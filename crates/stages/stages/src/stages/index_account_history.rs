@@ -2,7 +2,7 @@ use super::{collect_history_indices, load_history_indices};
 use reth_config::config::{EtlConfig, IndexHistoryConfig};
 use reth_db::tables;
 use reth_db_api::{database::Database, models::ShardedKey, table::Decode, transaction::DbTxMut};
-use reth_primitives::Address;
+use reth_primitives::{Address, StaticFileSegment};
 use reth_provider::{
     DatabaseProviderRW, HistoryWriter, PruneCheckpointReader, PruneCheckpointWriter,
 };
@@ -101,10 +101,13 @@ impl<DB: Database> Stage<DB> for IndexAccountHistoryStage {
         }
 
         info!(target: "sync::stages::index_account_history::exec", ?first_sync, "Collecting indices");
+        let first_database_block =
+            provider.first_database_changeset(StaticFileSegment::AccountChangeSets);
         let collector =
             collect_history_indices::<_, tables::AccountChangeSets, tables::AccountsHistory, _>(
                 provider.tx_ref(),
-                range.clone(),
+                provider.static_file_provider().account_changesets_iter(range.clone())?,
+                first_database_block.max(*range.start())..=*range.end(),
                 ShardedKey::new,
                 |(index, value)| (index, value.address),
                 &self.etl_config,
@@ -151,13 +154,13 @@ mod tests {
     use reth_db_api::{
         cursor::DbCursorRO,
         models::{
-            sharded_key, sharded_key::NUM_OF_INDICES_IN_SHARD, AccountBeforeTx,
+            sharded_key, sharded_key::NUM_OF_INDICES_IN_SHARD, AccountBeforeTx, AccountChangeSet,
             StoredBlockBodyIndices,
         },
         transaction::DbTx,
     };
     use reth_primitives::{address, BlockNumber, B256};
-    use reth_provider::providers::StaticFileWriter;
+    use reth_provider::{providers::StaticFileWriter, StaticFileProviderFactory};
     use reth_testing_utils::{
         generators,
         generators::{random_block_range, random_changeset_range, random_contract_account_range},
@@ -494,6 +497,53 @@ mod tests {
         assert!(table.is_empty());
     }
 
+    #[tokio::test]
+    async fn insert_index_from_static_files() {
+        // init
+        let db = TestStageDB::default();
+
+        // setup changesets of blocks 0..=2 in static files
+        let static_file_provider = db.factory.static_file_provider();
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        for block in 0..=2 {
+            let accounts = if block == 1 { Vec::new() } else { vec![acc()] };
+            writer.append_account_changeset(block, AccountChangeSet { accounts }).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        // setup changesets of blocks 3..=5 in the database, and a leftover of block 2 that was
+        // already moved to static files
+        db.commit(|tx| {
+            for block in 0..=5 {
+                tx.put::<tables::BlockBodyIndices>(
+                    block,
+                    StoredBlockBodyIndices { tx_count: 3, ..Default::default() },
+                )?;
+            }
+            for block in [2, 3, 5] {
+                tx.put::<tables::AccountChangeSets>(block, acc())?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        // run
+        run(&db, 5, None);
+
+        // verify
+        let table = cast(db.table::<tables::AccountsHistory>().unwrap());
+        assert_eq!(table, BTreeMap::from([(shard(u64::MAX), vec![0, 2, 3, 5])]));
+
+        // unwind
+        unwind(&db, 5, 1);
+
+        // verify initial state
+        let table = cast(db.table::<tables::AccountsHistory>().unwrap());
+        assert_eq!(table, BTreeMap::from([(shard(u64::MAX), vec![0])]));
+    }
+
     stage_test_suite_ext!(IndexAccountHistoryTestRunner, index_account_history);
 
     struct IndexAccountHistoryTestRunner {
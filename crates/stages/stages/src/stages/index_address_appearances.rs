@@ -0,0 +1,370 @@
+use futures_util::{Stream, StreamExt};
+use reth_config::config::IndexAddressAppearancesConfig;
+use reth_db::tables;
+use reth_db_api::{
+    database::Database,
+    models::ContractCreation,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_evm::ConfigureEvm;
+use reth_primitives::{Address, BlockNumber, TxNumber};
+use reth_provider::{
+    providers::HistoricalStateProviderRef, BlockReader, CanonStateNotification, DatabaseProviderRW,
+    EvmEnvProvider, HeaderProvider, HistoryWriter, ProviderError, ProviderFactory,
+    StageCheckpointReader, StageCheckpointWriter, TransactionsProvider,
+};
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::CacheDB,
+    primitives::{
+        BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, ResultAndState, SpecId,
+    },
+    DatabaseCommit,
+};
+use reth_stages_api::{
+    ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{debug, info, warn};
+
+/// The maximum number of blocks that are indexed or unwound in one database transaction when the
+/// index is kept in sync with the canonical chain.
+const LIVE_SYNC_COMMIT_THRESHOLD: u64 = 100;
+
+/// The address appearances index stage.
+///
+/// This stage re-executes the transactions of each block with a call tracer, and records every
+/// address that appeared in a transaction in [`tables::AddressAppearances`]: the sender and
+/// recipient of the transaction, and the caller and callee of every internal call. Contracts
+/// created by successful `CREATE` and `CREATE2` calls are recorded in
+/// [`tables::ContractCreators`].
+///
+/// Transactions are executed on historical state, so this stage has to run after
+/// [`IndexAccountHistoryStage`](crate::stages::IndexAccountHistoryStage) and
+/// [`IndexStorageHistoryStage`](crate::stages::IndexStorageHistoryStage). It's not part of the
+/// default stage sets, since it's only needed by the Otterscan RPC namespace.
+#[derive(Debug, Clone)]
+pub struct IndexAddressAppearancesStage<EvmConfig> {
+    /// The EVM configuration used to re-execute transactions.
+    evm_config: EvmConfig,
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    commit_threshold: u64,
+}
+
+impl<EvmConfig> IndexAddressAppearancesStage<EvmConfig> {
+    /// Create new instance of [`IndexAddressAppearancesStage`].
+    pub const fn new(evm_config: EvmConfig, config: IndexAddressAppearancesConfig) -> Self {
+        Self { evm_config, commit_threshold: config.commit_threshold }
+    }
+}
+
+impl<EvmConfig: ConfigureEvm> IndexAddressAppearancesStage<EvmConfig> {
+    /// Re-executes the transactions of the given block on the state before the block, and returns
+    /// the addresses that appeared in each transaction and the contracts created by the block.
+    fn trace_block<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        block_number: BlockNumber,
+    ) -> Result<BlockAppearances, StageError> {
+        let mut appearances = BlockAppearances::default();
+
+        let body = provider
+            .block_body_indices(block_number)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(block_number))?;
+        if body.is_empty() {
+            return Ok(appearances)
+        }
+
+        let header = provider
+            .header_by_number(block_number)?
+            .ok_or(ProviderError::HeaderNotFound(block_number.into()))?;
+        let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::LATEST);
+        let mut block_env = BlockEnv::default();
+        provider.fill_env_with_header(
+            &mut cfg,
+            &mut block_env,
+            &header,
+            self.evm_config.clone(),
+        )?;
+        let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, Default::default());
+
+        let transactions = provider.transactions_by_tx_range(body.tx_num_range())?;
+        let senders = provider.senders_by_tx_range(body.tx_num_range())?;
+        let is_taiko = provider.chain_spec().is_taiko();
+
+        let state = HistoricalStateProviderRef::new(
+            provider.tx_ref(),
+            block_number,
+            provider.static_file_provider().clone(),
+        );
+        let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+        for (idx, ((tx_number, transaction), sender)) in
+            body.tx_num_range().zip(transactions).zip(senders).enumerate()
+        {
+            let transaction = transaction.with_hash();
+            let mut addresses = BTreeSet::from([sender]);
+            addresses.extend(transaction.to());
+
+            let mut inspector = TracingInspector::new(TracingInspectorConfig::none());
+            let res = {
+                let mut evm = self.evm_config.evm_with_env_and_inspector(
+                    &mut db,
+                    env.clone(),
+                    &mut inspector,
+                );
+                EvmConfig::fill_tx_env(evm.tx_mut(), &transaction, sender);
+                evm.tx_mut().taiko.is_anchor = is_taiko && idx == 0;
+                evm.transact()
+            };
+
+            match res {
+                Ok(ResultAndState { state, .. }) => {
+                    db.commit(state);
+                    for node in inspector.into_traces().into_nodes() {
+                        let trace = node.trace;
+                        addresses.insert(trace.caller);
+                        addresses.insert(trace.address);
+                        if trace.kind.is_any_create() && trace.success {
+                            appearances.creations.push((
+                                trace.address,
+                                ContractCreation { tx_number, creator: trace.caller },
+                            ));
+                        }
+                    }
+                }
+                Err(EVMError::Database(err)) => return Err(err.into()),
+                Err(err) => {
+                    // Blocks can contain transactions that were skipped during execution, they
+                    // only appear with their sender and recipient.
+                    debug!(target: "sync::stages::index_address_appearances", tx_number, %err, "Skipping invalid transaction");
+                }
+            }
+
+            appearances.transactions.push((tx_number, addresses));
+        }
+
+        Ok(appearances)
+    }
+
+    /// Indexes the blocks up to the given canonical block, after unwinding the index to
+    /// `unwind_to` if it's set.
+    ///
+    /// This runs next to the engine, so progress is committed after every
+    /// [`LIVE_SYNC_COMMIT_THRESHOLD`] blocks to not hold the database write lock for long.
+    fn sync_to<DB: Database>(
+        &mut self,
+        provider_factory: &ProviderFactory<DB>,
+        unwind_to: Option<BlockNumber>,
+        target: BlockNumber,
+    ) -> Result<(), StageError> {
+        let stage_id = <Self as Stage<DB>>::id(self);
+
+        if let Some(unwind_to) = unwind_to {
+            loop {
+                let provider_rw = provider_factory.provider_rw()?;
+                let checkpoint = provider_rw.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+                if checkpoint.block_number <= unwind_to {
+                    break
+                }
+
+                let unwind_to = unwind_to
+                    .max(checkpoint.block_number.saturating_sub(LIVE_SYNC_COMMIT_THRESHOLD));
+                let input = UnwindInput { checkpoint, unwind_to, bad_block: None };
+                let output = self.unwind(&provider_rw, input)?;
+                provider_rw.save_stage_checkpoint(stage_id, output.checkpoint)?;
+                provider_rw.commit()?;
+            }
+        }
+
+        loop {
+            let provider_rw = provider_factory.provider_rw()?;
+            let checkpoint = provider_rw.get_stage_checkpoint(stage_id)?;
+            if (ExecInput { target: Some(target), checkpoint }).target_reached() {
+                break
+            }
+
+            let batch_target = checkpoint
+                .unwrap_or_default()
+                .block_number
+                .saturating_add(LIVE_SYNC_COMMIT_THRESHOLD)
+                .min(target);
+            let input = ExecInput { target: Some(batch_target), checkpoint };
+
+            let output = self.execute(&provider_rw, input)?;
+            provider_rw.save_stage_checkpoint(stage_id, output.checkpoint)?;
+            provider_rw.commit()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<EvmConfig, DB> Stage<DB> for IndexAddressAppearancesStage<EvmConfig>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database,
+{
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::IndexAddressAppearances
+    }
+
+    /// Execute the stage.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+        info!(target: "sync::stages::index_address_appearances::exec", ?range, "Indexing address appearances");
+
+        let tx = provider.tx_ref();
+        let mut index = BTreeMap::<Address, Vec<TxNumber>>::new();
+        for block_number in range.clone() {
+            let BlockAppearances { transactions, creations } =
+                self.trace_block(provider, block_number)?;
+
+            let mut block_addresses = BTreeSet::new();
+            for (tx_number, addresses) in transactions {
+                for address in addresses {
+                    index.entry(address).or_default().push(tx_number);
+                    block_addresses.insert(address);
+                }
+            }
+            for address in block_addresses {
+                tx.put::<tables::BlockAppearances>(block_number, address)?;
+            }
+
+            for (address, creation) in creations {
+                // If a contract was destroyed and created again at the same address, we keep the
+                // first creation.
+                if tx.get::<tables::ContractCreators>(address)?.is_none() {
+                    tx.put::<tables::ContractCreators>(address, creation)?;
+                }
+            }
+        }
+
+        info!(target: "sync::stages::index_address_appearances::exec", addresses = index.len(), "Loading indices into database");
+        provider.insert_address_appearances_index(index)?;
+
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(*range.end()), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_progress, _) =
+            input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        provider.unwind_address_appearances(range)?;
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_progress) })
+    }
+}
+
+/// The addresses that appeared in the transactions of a block, and the contracts it created.
+#[derive(Debug, Default)]
+struct BlockAppearances {
+    /// The addresses that appeared in each transaction.
+    transactions: Vec<(TxNumber, BTreeSet<Address>)>,
+    /// The created contracts.
+    creations: Vec<(Address, ContractCreation)>,
+}
+
+/// Keeps the address appearances index in sync with the canonical chain.
+///
+/// Blocks that are made canonical by the blockchain tree are committed to the database outside of
+/// the pipeline, so the [`IndexAddressAppearancesStage`] is run for them whenever the canonical
+/// chain changes. Reverted blocks are unwound from the index first.
+pub async fn maintain_address_appearances<DB, EvmConfig, St>(
+    provider_factory: ProviderFactory<DB>,
+    mut stage: IndexAddressAppearancesStage<EvmConfig>,
+    mut events: St,
+) where
+    DB: Database,
+    EvmConfig: ConfigureEvm,
+    St: Stream<Item = CanonStateNotification> + Unpin,
+{
+    while let Some(notification) = events.next().await {
+        let unwind_to = notification.reverted().map(|chain| chain.first().number.saturating_sub(1));
+        let tip = notification.tip().number;
+
+        if let Err(err) = stage.sync_to(&provider_factory, unwind_to, tip) {
+            warn!(target: "sync::stages::index_address_appearances", %err, tip, "Failed to index address appearances");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestStageDB;
+    use reth_db_api::models::StoredBlockBodyIndices;
+    use reth_primitives::address;
+    use reth_provider::AddressAppearancesReader;
+
+    const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
+    const CONTRACT: Address = address!("0000000000000000000000000000000000000002");
+
+    #[test]
+    fn unwind_address_appearances() {
+        let db = TestStageDB::default();
+
+        db.commit(|tx| {
+            // Blocks 1 to 3 contain transactions 0 to 5.
+            for block in 0..=3 {
+                let first_tx_num = block.saturating_sub(1) * 2;
+                let tx_count = if block == 0 { 0 } else { 2 };
+                tx.put::<tables::BlockBodyIndices>(
+                    block,
+                    StoredBlockBodyIndices { first_tx_num, tx_count },
+                )?;
+                if block > 0 {
+                    tx.put::<tables::BlockAppearances>(block, ADDRESS)?;
+                }
+            }
+            tx.put::<tables::BlockAppearances>(3, CONTRACT)?;
+            tx.put::<tables::ContractCreators>(
+                CONTRACT,
+                ContractCreation { tx_number: 4, creator: ADDRESS },
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let provider = db.factory.provider_rw().unwrap();
+        let mut index = BTreeMap::new();
+        index.insert(ADDRESS, vec![0, 1, 3, 4, 5]);
+        index.insert(CONTRACT, vec![4]);
+        provider.insert_address_appearances_index(index).unwrap();
+
+        assert_eq!(provider.address_appearances(ADDRESS, 0..=5, false, 3).unwrap(), vec![0, 1, 3]);
+        assert_eq!(
+            provider.address_appearances(ADDRESS, 0..=4, true, 10).unwrap(),
+            vec![4, 3, 1, 0]
+        );
+        assert_eq!(provider.address_appearances(ADDRESS, 2..=3, true, 10).unwrap(), vec![3]);
+
+        // Unwind block 3.
+        assert_eq!(provider.unwind_address_appearances(3..=3).unwrap(), 2);
+        assert_eq!(
+            provider.address_appearances(ADDRESS, 0..=u64::MAX, false, 10).unwrap(),
+            vec![0, 1, 3]
+        );
+        assert!(provider
+            .address_appearances(CONTRACT, 0..=u64::MAX, false, 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(provider.contract_creation(CONTRACT).unwrap(), None);
+    }
+}
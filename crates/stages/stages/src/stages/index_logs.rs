@@ -0,0 +1,288 @@
+use futures_util::{Stream, StreamExt};
+use reth_config::config::IndexLogsConfig;
+use reth_db_api::database::Database;
+use reth_primitives::{Address, BlockNumber, Log, B256};
+use reth_provider::{
+    BlockReader, CanonStateNotification, Chain, DatabaseProviderRW, HistoryWriter, ProviderError,
+    ProviderFactory, ReceiptProvider, StageCheckpointReader, StageCheckpointWriter,
+};
+use reth_stages_api::{
+    ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use std::{collections::BTreeMap, ops::RangeInclusive};
+use tracing::{info, warn};
+
+/// The maximum number of blocks that are indexed in one database transaction when the index is
+/// kept in sync with the canonical chain.
+const LIVE_SYNC_COMMIT_THRESHOLD: u64 = 100;
+
+/// Stage is indexing the blocks containing logs of each address and topic, and inserting them in
+/// [`LogAddressHistory`](reth_db::tables::LogAddressHistory) and
+/// [`LogTopicHistory`](reth_db::tables::LogTopicHistory) tables.
+///
+/// The logs are read from the receipts, so this stage has to run after the
+/// [`ExecutionStage`](crate::stages::ExecutionStage). It's not part of the default stage sets,
+/// since the index is only used to speed up log queries.
+#[derive(Debug, Clone, Copy)]
+pub struct IndexLogsStage {
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+}
+
+impl IndexLogsStage {
+    /// Create new instance of [`IndexLogsStage`].
+    pub const fn new(config: IndexLogsConfig) -> Self {
+        Self { commit_threshold: config.commit_threshold }
+    }
+
+    /// Indexes the blocks up to the given canonical block, after unwinding the index to the block
+    /// before the given reverted chain, if it's set.
+    ///
+    /// This runs next to the engine, so progress is committed after every
+    /// [`LIVE_SYNC_COMMIT_THRESHOLD`] blocks to not hold the database write lock for long.
+    fn sync_to<DB: Database>(
+        &mut self,
+        provider_factory: &ProviderFactory<DB>,
+        reverted: Option<&Chain>,
+        target: BlockNumber,
+    ) -> Result<(), StageError> {
+        let stage_id = <Self as Stage<DB>>::id(self);
+
+        if let Some(reverted) = reverted {
+            let provider_rw = provider_factory.provider_rw()?;
+            let checkpoint = provider_rw.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+            let first_block = reverted.first().number;
+            if checkpoint.block_number >= first_block {
+                // The reverted blocks are already removed from the database, so the logs to
+                // unwind are taken from the receipts of the reverted chain.
+                let mut index = LogIndex::default();
+                for (block, receipts) in reverted.blocks_iter().zip(reverted.block_receipts_iter())
+                {
+                    index.insert_block(
+                        block.number,
+                        receipts.iter().flatten().flat_map(|receipt| &receipt.logs),
+                    );
+                }
+                provider_rw.unwind_log_index(
+                    index.addresses.into_keys().collect(),
+                    index.topics.into_keys().collect(),
+                    first_block,
+                )?;
+                provider_rw.save_stage_checkpoint(
+                    stage_id,
+                    StageCheckpoint::new(first_block.saturating_sub(1)),
+                )?;
+                provider_rw.commit()?;
+            }
+        }
+
+        loop {
+            let provider_rw = provider_factory.provider_rw()?;
+            let checkpoint = provider_rw.get_stage_checkpoint(stage_id)?;
+            if (ExecInput { target: Some(target), checkpoint }).target_reached() {
+                break
+            }
+
+            let batch_target = checkpoint
+                .unwrap_or_default()
+                .block_number
+                .saturating_add(LIVE_SYNC_COMMIT_THRESHOLD)
+                .min(target);
+            let input = ExecInput { target: Some(batch_target), checkpoint };
+
+            let output = self.execute(&provider_rw, input)?;
+            provider_rw.save_stage_checkpoint(stage_id, output.checkpoint)?;
+            provider_rw.commit()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for IndexLogsStage {
+    fn default() -> Self {
+        Self { commit_threshold: 10_000 }
+    }
+}
+
+impl<DB: Database> Stage<DB> for IndexLogsStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::IndexLogs
+    }
+
+    /// Execute the stage.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+        info!(target: "sync::stages::index_logs::exec", ?range, "Indexing logs");
+
+        let LogIndex { addresses, topics } = read_log_index(provider, range.clone())?;
+
+        info!(target: "sync::stages::index_logs::exec", addresses = addresses.len(), topics = topics.len(), "Loading indices into database");
+        provider.insert_log_index(addresses, topics)?;
+
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(*range.end()), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_progress, _) =
+            input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        let first_block = *range.start();
+        let LogIndex { addresses, topics } = read_log_index(provider, range)?;
+        provider.unwind_log_index(
+            addresses.into_keys().collect(),
+            topics.into_keys().collect(),
+            first_block,
+        )?;
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_progress) })
+    }
+}
+
+/// The blocks containing logs of each address and topic, in ascending order.
+#[derive(Debug, Default)]
+struct LogIndex {
+    addresses: BTreeMap<Address, Vec<BlockNumber>>,
+    topics: BTreeMap<B256, Vec<BlockNumber>>,
+}
+
+impl LogIndex {
+    /// Adds the logs of a block. Blocks have to be inserted in ascending order.
+    fn insert_block<'a>(&mut self, block_number: BlockNumber, logs: impl Iterator<Item = &'a Log>) {
+        for log in logs {
+            let blocks = self.addresses.entry(log.address).or_default();
+            if blocks.last() != Some(&block_number) {
+                blocks.push(block_number);
+            }
+
+            for topic in log.topics() {
+                let blocks = self.topics.entry(*topic).or_default();
+                if blocks.last() != Some(&block_number) {
+                    blocks.push(block_number);
+                }
+            }
+        }
+    }
+}
+
+/// Reads the logs of the given blocks from their receipts.
+///
+/// Receipts that were pruned are skipped.
+fn read_log_index<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    range: RangeInclusive<BlockNumber>,
+) -> Result<LogIndex, StageError> {
+    let mut index = LogIndex::default();
+    for block_number in range {
+        let body = provider
+            .block_body_indices(block_number)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(block_number))?;
+        if body.is_empty() {
+            continue
+        }
+
+        let receipts = provider.receipts_by_tx_range(body.tx_num_range())?;
+        index.insert_block(block_number, receipts.iter().flat_map(|receipt| &receipt.logs));
+    }
+
+    Ok(index)
+}
+
+/// Keeps the log index in sync with the canonical chain.
+///
+/// Blocks that are made canonical by the blockchain tree are committed to the database outside of
+/// the pipeline, so the [`IndexLogsStage`] is run for them whenever the canonical chain changes.
+/// Reverted blocks are unwound from the index first.
+pub async fn maintain_log_index<DB, St>(
+    provider_factory: ProviderFactory<DB>,
+    mut stage: IndexLogsStage,
+    mut events: St,
+) where
+    DB: Database,
+    St: Stream<Item = CanonStateNotification> + Unpin,
+{
+    while let Some(notification) = events.next().await {
+        let reverted = notification.reverted();
+        let tip = notification.tip().number;
+
+        if let Err(err) = stage.sync_to(&provider_factory, reverted.as_deref(), tip) {
+            warn!(target: "sync::stages::index_logs", %err, tip, "Failed to index logs");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use reth_provider::LogIndexReader;
+    use reth_testing_utils::generators::{self, random_block_range, random_log, random_receipt};
+
+    #[test]
+    fn execute_and_unwind() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 0..=10, B256::ZERO, 1..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        // Blocks 2, 5 and 8 contain logs of the same contract.
+        let contract = Address::with_last_byte(1);
+        let mut receipts = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                let mut receipt = random_receipt(&mut rng, transaction, Some(1));
+                if [2, 5, 8].contains(&block.number) {
+                    receipt.logs.push(random_log(&mut rng, Some(contract), Some(1)));
+                }
+                receipts.push((receipts.len() as u64, receipt));
+            }
+        }
+        db.insert_receipts(receipts).expect("insert receipts");
+
+        let mut stage = IndexLogsStage { commit_threshold: 4 };
+        let provider = db.factory.provider_rw().unwrap();
+        let mut input = ExecInput { target: Some(10), checkpoint: None };
+        loop {
+            let output = stage.execute(&provider, input).unwrap();
+            input.checkpoint = Some(output.checkpoint);
+            if output.done {
+                break
+            }
+        }
+        assert_eq!(input.checkpoint(), StageCheckpoint::new(10));
+        assert_eq!(provider.log_address_blocks(contract, 0..=10).unwrap(), vec![2, 5, 8]);
+        assert_eq!(provider.log_address_blocks(contract, 3..=7).unwrap(), vec![5]);
+
+        let output = stage
+            .unwind(
+                &provider,
+                UnwindInput { checkpoint: StageCheckpoint::new(10), unwind_to: 4, bad_block: None },
+            )
+            .unwrap();
+        assert_eq!(output.checkpoint, StageCheckpoint::new(5));
+        let output = stage
+            .unwind(
+                &provider,
+                UnwindInput { checkpoint: output.checkpoint, unwind_to: 4, bad_block: None },
+            )
+            .unwrap();
+        assert_eq!(output.checkpoint, StageCheckpoint::new(4));
+        assert_eq!(provider.log_address_blocks(contract, 0..=10).unwrap(), vec![2]);
+    }
+}
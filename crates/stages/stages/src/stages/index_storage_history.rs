@@ -8,6 +8,7 @@ use reth_db_api::{
     table::Decode,
     transaction::DbTxMut,
 };
+use reth_primitives::StaticFileSegment;
 use reth_provider::{
     DatabaseProviderRW, HistoryWriter, PruneCheckpointReader, PruneCheckpointWriter,
 };
@@ -104,10 +105,13 @@ impl<DB: Database> Stage<DB> for IndexStorageHistoryStage {
         }
 
         info!(target: "sync::stages::index_storage_history::exec", ?first_sync, "Collecting indices");
+        let first_database_block =
+            provider.first_database_changeset(StaticFileSegment::StorageChangeSets);
         let collector =
             collect_history_indices::<_, tables::StorageChangeSets, tables::StoragesHistory, _>(
                 provider.tx_ref(),
-                BlockNumberAddress::range(range.clone()),
+                provider.static_file_provider().storage_changesets_iter(range.clone())?,
+                BlockNumberAddress::range(first_database_block.max(*range.start())..=*range.end()),
                 |AddressStorageKey((address, storage_key)), highest_block_number| {
                     StorageShardedKey::new(address, storage_key, highest_block_number)
                 },
@@ -157,13 +161,13 @@ mod tests {
     use reth_db_api::{
         cursor::DbCursorRO,
         models::{
-            sharded_key, storage_sharded_key::NUM_OF_INDICES_IN_SHARD, ShardedKey,
-            StoredBlockBodyIndices,
+            sharded_key, storage_sharded_key::NUM_OF_INDICES_IN_SHARD, ShardedKey, StorageBeforeTx,
+            StorageChangeSet, StoredBlockBodyIndices,
         },
         transaction::DbTx,
     };
     use reth_primitives::{address, b256, Address, BlockNumber, StorageEntry, B256, U256};
-    use reth_provider::providers::StaticFileWriter;
+    use reth_provider::{providers::StaticFileWriter, StaticFileProviderFactory};
     use reth_testing_utils::{
         generators,
         generators::{random_block_range, random_changeset_range, random_contract_account_range},
@@ -516,6 +520,60 @@ mod tests {
         assert!(table.is_empty());
     }
 
+    #[tokio::test]
+    async fn insert_index_from_static_files() {
+        // init
+        let db = TestStageDB::default();
+
+        // setup changesets of blocks 0..=2 in static files
+        let static_file_provider = db.factory.static_file_provider();
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        for block in 0..=2 {
+            let storages = if block == 1 {
+                Vec::new()
+            } else {
+                vec![StorageBeforeTx { address: ADDRESS, key: STORAGE_KEY, value: U256::ZERO }]
+            };
+            writer.append_storage_changeset(block, StorageChangeSet { storages }).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        // setup changesets of blocks 3..=5 in the database, and a leftover of block 2 that was
+        // already moved to static files
+        db.commit(|tx| {
+            for block in 0..=5 {
+                tx.put::<tables::BlockBodyIndices>(
+                    block,
+                    StoredBlockBodyIndices { tx_count: 3, ..Default::default() },
+                )?;
+            }
+            for block in [2, 3, 5] {
+                tx.put::<tables::StorageChangeSets>(
+                    block_number_address(block),
+                    storage(STORAGE_KEY),
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        // run
+        run(&db, 5, None);
+
+        // verify
+        let table = cast(db.table::<tables::StoragesHistory>().unwrap());
+        assert_eq!(table, BTreeMap::from([(shard(u64::MAX), vec![0, 2, 3, 5])]));
+
+        // unwind
+        unwind(&db, 5, 1);
+
+        // verify initial state
+        let table = cast(db.table::<tables::StoragesHistory>().unwrap());
+        assert_eq!(table, BTreeMap::from([(shard(u64::MAX), vec![0])]));
+    }
+
     stage_test_suite_ext!(IndexStorageHistoryTestRunner, index_storage_history);
 
     struct IndexStorageHistoryTestRunner {
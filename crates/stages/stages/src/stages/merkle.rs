@@ -3,6 +3,7 @@ use reth_consensus::ConsensusError;
 use reth_db::tables;
 use reth_db_api::{
     database::Database,
+    models::BlockNumberAddress,
     transaction::{DbTx, DbTxMut},
 };
 use reth_primitives::{BlockNumber, GotExpected, SealedHeader, B256};
@@ -14,8 +15,11 @@ use reth_stages_api::{
     BlockErrorKind, EntitiesCheckpoint, ExecInput, ExecOutput, MerkleCheckpoint, Stage,
     StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
 };
-use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress, StoredSubNode};
-use std::fmt::Debug;
+use reth_trie::{
+    prefix_set::{PrefixSetLoader, TriePrefixSets},
+    IntermediateStateRootState, StateRoot, StateRootProgress, StoredSubNode,
+};
+use std::{fmt::Debug, ops::RangeInclusive};
 use tracing::*;
 
 // TODO: automate the process outlined below so the user can just send in a debugging package
@@ -246,9 +250,10 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             }
         } else {
             debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
-            let (root, updates) =
-                StateRoot::incremental_root_with_updates(provider.tx_ref(), range)
-                    .map_err(|e| {
+            let (root, updates) = StateRoot::from_tx(provider.tx_ref())
+                .with_prefix_sets(load_prefix_sets(provider, range)?)
+                .root_with_updates()
+                .map_err(|e| {
                         error!(target: "sync::stages::merkle", %e, ?current_block_number, ?to_block, "Incremental state root failed! {INVALID_STATE_ROOT_ERROR_MESSAGE}");
                         StageError::Fatal(Box::new(e))
                     })?;
@@ -315,7 +320,9 @@ impl<DB: Database> Stage<DB> for MerkleStage {
 
         // Unwind trie only if there are transitions
         if !range.is_empty() {
-            let (block_root, updates) = StateRoot::incremental_root_with_updates(tx, range)
+            let (block_root, updates) = StateRoot::from_tx(tx)
+                .with_prefix_sets(load_prefix_sets(provider, range)?)
+                .root_with_updates()
                 .map_err(|e| StageError::Fatal(Box::new(e)))?;
 
             // Validate the calculated state root
@@ -336,6 +343,21 @@ impl<DB: Database> Stage<DB> for MerkleStage {
     }
 }
 
+/// Loads the prefix sets of the accounts and storage slots changed in the block range, including
+/// the changesets that were already moved to static files.
+fn load_prefix_sets<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    range: RangeInclusive<BlockNumber>,
+) -> Result<TriePrefixSets, StageError> {
+    let changed_accounts = provider.account_changesets_range(range.clone())?;
+    let changed_storages = provider.storage_changesets_range(BlockNumberAddress::range(range))?;
+
+    Ok(PrefixSetLoader::new(provider.tx_ref()).load_changes(
+        changed_accounts.map(|entry| entry.map(|(_, account)| account.address)),
+        changed_storages.map(|entry| entry.map(|(key, entry)| (key.address(), entry.key))),
+    )?)
+}
+
 /// Check that the computed state root matches the root in the expected header.
 #[inline]
 fn validate_state_root(
@@ -364,8 +386,11 @@ mod tests {
         TestRunnerError, TestStageDB, UnwindStageTestRunner,
     };
     use assert_matches::assert_matches;
-    use reth_db_api::cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO};
-    use reth_primitives::{keccak256, SealedBlock, StaticFileSegment, StorageEntry, U256};
+    use reth_db_api::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+        models::{AccountBeforeTx, AccountChangeSet, StorageBeforeTx, StorageChangeSet},
+    };
+    use reth_primitives::{keccak256, Address, SealedBlock, StaticFileSegment, StorageEntry, U256};
     use reth_provider::{providers::StaticFileWriter, StaticFileProviderFactory};
     use reth_stages_api::StageUnitCheckpoint;
     use reth_testing_utils::{
@@ -374,8 +399,90 @@ mod tests {
             random_block, random_block_range, random_changeset_range, random_contract_account_range,
         },
     };
-    use reth_trie::test_utils::{state_root, state_root_prehashed};
-    use std::collections::BTreeMap;
+    use reth_trie::{
+        test_utils::{state_root, state_root_prehashed},
+        Nibbles,
+    };
+    use std::collections::{BTreeMap, HashSet};
+
+    #[test]
+    fn load_prefix_sets_from_static_files() {
+        let db = TestStageDB::default();
+
+        let static_file_account = Address::with_last_byte(1);
+        let static_file_storage = Address::with_last_byte(2);
+        let database_account = Address::with_last_byte(3);
+        let database_storage = Address::with_last_byte(4);
+        let moved_account = Address::with_last_byte(5);
+        let key = B256::with_last_byte(1);
+
+        // Changesets of blocks 0..=1 were moved to static files.
+        let static_file_provider = db.factory.static_file_provider();
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        writer.append_account_changeset(0, AccountChangeSet::default()).unwrap();
+        writer
+            .append_account_changeset(
+                1,
+                AccountChangeSet {
+                    accounts: vec![AccountBeforeTx { address: static_file_account, info: None }],
+                },
+            )
+            .unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        writer.append_storage_changeset(0, StorageChangeSet::default()).unwrap();
+        writer
+            .append_storage_changeset(
+                1,
+                StorageChangeSet {
+                    storages: vec![StorageBeforeTx {
+                        address: static_file_storage,
+                        key,
+                        value: U256::ZERO,
+                    }],
+                },
+            )
+            .unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+
+        // The changeset of block 1 left in the database was already moved to static files, so it
+        // must be ignored.
+        db.commit(|tx| {
+            tx.put::<tables::AccountChangeSets>(
+                1,
+                AccountBeforeTx { address: moved_account, info: None },
+            )?;
+            tx.put::<tables::AccountChangeSets>(
+                2,
+                AccountBeforeTx { address: database_account, info: None },
+            )?;
+            tx.put::<tables::StorageChangeSets>(
+                (3, database_storage).into(),
+                StorageEntry { key, value: U256::ZERO },
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let provider = db.factory.provider_rw().unwrap();
+        let mut prefix_sets = load_prefix_sets(&provider, 1..=3).unwrap();
+
+        assert_eq!(prefix_sets.account_prefix_set.len(), 4);
+        for address in
+            [static_file_account, static_file_storage, database_account, database_storage]
+        {
+            assert!(prefix_sets.account_prefix_set.contains(&Nibbles::unpack(keccak256(address))));
+        }
+        assert_eq!(
+            prefix_sets.storage_prefix_sets.keys().copied().collect::<HashSet<_>>(),
+            HashSet::from([keccak256(static_file_storage), keccak256(database_storage)])
+        );
+    }
 
     stage_test_suite_ext!(MerkleTestRunner, merkle);
 
@@ -10,6 +10,7 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives::BlockNumber;
+use reth_provider::ProviderResult;
 use reth_stages_api::StageError;
 use std::{collections::HashMap, hash::Hash, ops::RangeBounds};
 use tracing::info;
@@ -20,6 +21,9 @@ const DEFAULT_CACHE_THRESHOLD: u64 = 100_000;
 /// Collects all history (`H`) indices for a range of changesets (`CS`) and stores them in a
 /// [`Collector`].
 ///
+/// Changesets of blocks that were moved to static files are passed as `static_file_changesets`,
+/// and come before the database `range`.
+///
 /// ## Process
 /// The function utilizes a `HashMap` cache with a structure of `PartialKey` (`P`) (Address or
 /// Address.StorageKey) to `BlockNumberList`. When the cache exceeds its capacity, its contents are
@@ -36,6 +40,7 @@ const DEFAULT_CACHE_THRESHOLD: u64 = 100_000;
 /// `(Address1.300, [100,300])`. The entries may be stored across one or more files.
 pub(crate) fn collect_history_indices<TX, CS, H, P>(
     tx: &TX,
+    static_file_changesets: impl Iterator<Item = ProviderResult<(CS::Key, CS::Value)>>,
     range: impl RangeBounds<CS::Key>,
     sharded_key_factory: impl Fn(P, BlockNumber) -> H::Key,
     partial_key_factory: impl Fn((CS::Key, CS::Value)) -> (u64, P),
@@ -69,7 +74,10 @@ where
 
     let mut flush_counter = 0;
     let mut current_block_number = u64::MAX;
-    for (idx, entry) in changeset_cursor.walk_range(range)?.enumerate() {
+    let changesets = static_file_changesets
+        .map(|entry| entry.map_err(StageError::from))
+        .chain(changeset_cursor.walk_range(range)?.map(|entry| entry.map_err(StageError::from)));
+    for (idx, entry) in changesets.enumerate() {
         let (block_number, key) = partial_key_factory(entry?);
         cache.entry(key).or_default().push(block_number);
 
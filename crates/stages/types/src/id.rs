@@ -30,6 +30,14 @@ pub enum StageId {
     IndexStorageHistory,
     /// Index account history stage in the process.
     IndexAccountHistory,
+    /// Optional stage that indexes address appearances and contract creators.
+    ///
+    /// Not part of [`StageId::ALL`], since it's only run if enabled.
+    IndexAddressAppearances,
+    /// Index the blocks containing logs of each address and topic.
+    ///
+    /// Not part of [`StageId::ALL`], since it's only run if enabled.
+    IndexLogs,
     /// Finish stage in the process.
     Finish,
     /// Other custom stage with a provided string identifier.
@@ -80,6 +88,8 @@ impl StageId {
             Self::TransactionLookup => "TransactionLookup",
             Self::IndexAccountHistory => "IndexAccountHistory",
             Self::IndexStorageHistory => "IndexStorageHistory",
+            Self::IndexAddressAppearances => "IndexAddressAppearances",
+            Self::IndexLogs => "IndexLogs",
             Self::Finish => "Finish",
             Self::Other(s) => s,
         }
@@ -124,6 +134,8 @@ mod tests {
         assert_eq!(StageId::IndexAccountHistory.to_string(), "IndexAccountHistory");
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
+        assert_eq!(StageId::IndexAddressAppearances.to_string(), "IndexAddressAppearances");
+        assert_eq!(StageId::IndexLogs.to_string(), "IndexLogs");
         assert_eq!(StageId::Finish.to_string(), "Finish");
 
         assert_eq!(StageId::Other("Foo").to_string(), "Foo");
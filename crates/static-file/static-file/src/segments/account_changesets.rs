@@ -0,0 +1,93 @@
+use crate::segments::{create_changeset_static_file, Segment};
+use alloy_primitives::BlockNumber;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::DbCursorRO, database::Database, models::AccountChangeSet, table::Compress,
+    transaction::DbTx,
+};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    DatabaseProviderRO,
+};
+use reth_static_file_types::{SegmentConfig, StaticFileSegment};
+use reth_storage_errors::provider::ProviderResult;
+use std::{ops::RangeInclusive, path::Path};
+
+/// Static File segment responsible for [`StaticFileSegment::AccountChangeSets`] part of data.
+#[derive(Debug, Default)]
+pub struct AccountChangeSets;
+
+impl AccountChangeSets {
+    /// Returns an iterator over the account changeset of every block in the range, including
+    /// empty ones, which walks the changeset table as it advances.
+    fn changesets<DB: Database>(
+        provider: &DatabaseProviderRO<DB>,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumber, AccountChangeSet)>>> {
+        let mut cursor = provider.tx_ref().cursor_read::<tables::AccountChangeSets>()?;
+        let mut next = cursor.seek(*block_range.start()).transpose();
+
+        Ok(block_range.map(move |block| {
+            let mut accounts = Vec::new();
+            loop {
+                match next.take() {
+                    Some(Ok((number, account))) if number == block => {
+                        accounts.push(account);
+                        next = cursor.next().transpose();
+                    }
+                    Some(Err(err)) => return Err(err.into()),
+                    entry => {
+                        next = entry;
+                        break
+                    }
+                }
+            }
+            Ok((block, AccountChangeSet { accounts }))
+        }))
+    }
+}
+
+impl<DB: Database> Segment<DB> for AccountChangeSets {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::AccountChangeSets
+    }
+
+    fn copy_to_static_files(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        static_file_provider: StaticFileProvider,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer = static_file_provider
+            .get_writer(*block_range.start(), StaticFileSegment::AccountChangeSets)?;
+
+        for changeset in Self::changesets(&provider, block_range)? {
+            let (block, changeset) = changeset?;
+            let _static_file_block =
+                static_file_writer.append_account_changeset(block, changeset)?;
+            debug_assert_eq!(_static_file_block, block);
+        }
+
+        Ok(())
+    }
+
+    fn create_static_file_file(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        directory: &Path,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        create_changeset_static_file(
+            provider,
+            directory,
+            StaticFileSegment::AccountChangeSets,
+            config,
+            block_range,
+            |range| {
+                Ok(Self::changesets(provider, range)?
+                    .map(|changeset| changeset.map(|(_, changeset)| changeset.compress())))
+            },
+        )
+    }
+}
@@ -9,8 +9,14 @@ pub use headers::Headers;
 mod receipts;
 pub use receipts::Receipts;
 
+mod account_changesets;
+pub use account_changesets::AccountChangeSets;
+
+mod storage_changesets;
+pub use storage_changesets::StorageChangeSets;
+
 use alloy_primitives::BlockNumber;
-use reth_db::{RawKey, RawTable};
+use reth_db::{tables, RawKey, RawTable};
 use reth_db_api::{cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx};
 use reth_nippy_jar::NippyJar;
 use reth_provider::{
@@ -61,11 +67,10 @@ pub(crate) fn prepare_jar<DB: Database, const COLUMNS: usize>(
     total_rows: usize,
     prepare_compression: impl Fn() -> ProviderResult<Rows<COLUMNS>>,
 ) -> ProviderResult<NippyJar<SegmentHeader>> {
-    let tx_range = match segment {
-        StaticFileSegment::Headers => None,
-        StaticFileSegment::Receipts | StaticFileSegment::Transactions => {
-            Some(provider.transaction_range_by_block_range(block_range.clone())?.into())
-        }
+    let tx_range = if segment.is_block_based() {
+        None
+    } else {
+        Some(provider.transaction_range_by_block_range(block_range.clone())?.into())
     };
 
     let mut nippy_jar = NippyJar::new(
@@ -102,6 +107,56 @@ pub(crate) fn prepare_jar<DB: Database, const COLUMNS: usize>(
     Ok(nippy_jar)
 }
 
+/// Creates a static file of a changeset segment out of the compressed changeset of each block in
+/// the range. Changesets have no database table with one row per block, so `changesets` has to
+/// walk the changeset table and yield one row for every block of the range it's given, which is
+/// streamed into the static file.
+pub(crate) fn create_changeset_static_file<DB, I>(
+    provider: &DatabaseProviderRO<DB>,
+    directory: &Path,
+    segment: StaticFileSegment,
+    config: SegmentConfig,
+    block_range: RangeInclusive<BlockNumber>,
+    changesets: impl Fn(RangeInclusive<BlockNumber>) -> ProviderResult<I>,
+) -> ProviderResult<()>
+where
+    DB: Database,
+    I: Iterator<Item = ProviderResult<Vec<u8>>>,
+{
+    let range_len = block_range.clone().count();
+    let mut jar = prepare_jar::<DB, 1>(
+        provider,
+        directory,
+        segment,
+        config,
+        block_range.clone(),
+        range_len,
+        || {
+            // The dictionary is trained on the changesets of the most recent blocks.
+            let dataset_start = block_range.end().saturating_sub(999).max(*block_range.start());
+            let mut dataset = changesets(dataset_start..=*block_range.end())?
+                .collect::<ProviderResult<Vec<_>>>()?;
+            dataset.reverse();
+            Ok([dataset])
+        },
+    )?;
+
+    // Generate list of hashes for filters & PHF
+    if config.filters.has_filters() {
+        let mut cursor = provider.tx_ref().cursor_read::<RawTable<tables::CanonicalHeaders>>()?;
+        let hashes = cursor
+            .walk(Some(RawKey::from(*block_range.start())))?
+            .take(range_len)
+            .map(|row| row.map(|(_key, value)| value.into_value()).map_err(|e| e.into()));
+        jar.prepare_index(hashes, range_len).map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+    }
+
+    let rows = changesets(block_range)?.map(|row| row.map_err(|e| e.into()));
+    jar.freeze(vec![rows], range_len as u64).map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+
+    Ok(())
+}
+
 /// Generates the dataset to train a zstd dictionary with the most recent rows (at most 1000).
 pub(crate) fn dataset_for_compression<DB: Database, T: Table<Key = u64>>(
     provider: &DatabaseProviderRO<DB>,
@@ -0,0 +1,101 @@
+use crate::segments::{create_changeset_static_file, Segment};
+use alloy_primitives::{Address, BlockNumber};
+use reth_db::tables;
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    models::{BlockNumberAddress, StorageBeforeTx, StorageChangeSet},
+    table::Compress,
+    transaction::DbTx,
+};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    DatabaseProviderRO,
+};
+use reth_static_file_types::{SegmentConfig, StaticFileSegment};
+use reth_storage_errors::provider::ProviderResult;
+use std::{ops::RangeInclusive, path::Path};
+
+/// Static File segment responsible for [`StaticFileSegment::StorageChangeSets`] part of data.
+#[derive(Debug, Default)]
+pub struct StorageChangeSets;
+
+impl StorageChangeSets {
+    /// Returns an iterator over the storage changeset of every block in the range, including
+    /// empty ones, which walks the changeset table as it advances.
+    fn changesets<DB: Database>(
+        provider: &DatabaseProviderRO<DB>,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumber, StorageChangeSet)>>> {
+        let mut cursor = provider.tx_ref().cursor_read::<tables::StorageChangeSets>()?;
+        let mut next =
+            cursor.seek(BlockNumberAddress((*block_range.start(), Address::ZERO))).transpose();
+
+        Ok(block_range.map(move |block| {
+            let mut storages = Vec::new();
+            loop {
+                match next.take() {
+                    Some(Ok((key, entry))) if key.block_number() == block => {
+                        storages.push(StorageBeforeTx {
+                            address: key.address(),
+                            key: entry.key,
+                            value: entry.value,
+                        });
+                        next = cursor.next().transpose();
+                    }
+                    Some(Err(err)) => return Err(err.into()),
+                    entry => {
+                        next = entry;
+                        break
+                    }
+                }
+            }
+            Ok((block, StorageChangeSet { storages }))
+        }))
+    }
+}
+
+impl<DB: Database> Segment<DB> for StorageChangeSets {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::StorageChangeSets
+    }
+
+    fn copy_to_static_files(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        static_file_provider: StaticFileProvider,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer = static_file_provider
+            .get_writer(*block_range.start(), StaticFileSegment::StorageChangeSets)?;
+
+        for changeset in Self::changesets(&provider, block_range)? {
+            let (block, changeset) = changeset?;
+            let _static_file_block =
+                static_file_writer.append_storage_changeset(block, changeset)?;
+            debug_assert_eq!(_static_file_block, block);
+        }
+
+        Ok(())
+    }
+
+    fn create_static_file_file(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        directory: &Path,
+        config: SegmentConfig,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        create_changeset_static_file(
+            provider,
+            directory,
+            StaticFileSegment::StorageChangeSets,
+            config,
+            block_range,
+            |range| {
+                Ok(Self::changesets(provider, range)?
+                    .map(|changeset| changeset.map(|(_, changeset)| changeset.compress())))
+            },
+        )
+    }
+}
@@ -57,17 +57,23 @@ pub struct StaticFileProducerInner<DB> {
 }
 
 /// Static File targets, per data part, measured in [`BlockNumber`].
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct StaticFileTargets {
     headers: Option<RangeInclusive<BlockNumber>>,
     receipts: Option<RangeInclusive<BlockNumber>>,
     transactions: Option<RangeInclusive<BlockNumber>>,
+    account_changesets: Option<RangeInclusive<BlockNumber>>,
+    storage_changesets: Option<RangeInclusive<BlockNumber>>,
 }
 
 impl StaticFileTargets {
     /// Returns `true` if any of the targets are [Some].
     pub const fn any(&self) -> bool {
-        self.headers.is_some() || self.receipts.is_some() || self.transactions.is_some()
+        self.headers.is_some() ||
+            self.receipts.is_some() ||
+            self.transactions.is_some() ||
+            self.account_changesets.is_some() ||
+            self.storage_changesets.is_some()
     }
 
     // Returns `true` if all targets are either [`None`] or has beginning of the range equal to the
@@ -77,6 +83,8 @@ impl StaticFileTargets {
             (self.headers.as_ref(), static_files.headers),
             (self.receipts.as_ref(), static_files.receipts),
             (self.transactions.as_ref(), static_files.transactions),
+            (self.account_changesets.as_ref(), static_files.account_changesets),
+            (self.storage_changesets.as_ref(), static_files.storage_changesets),
         ]
         .iter()
         .all(|(target_block_range, highest_static_fileted_block)| {
@@ -135,6 +143,12 @@ impl<DB: Database> StaticFileProducerInner<DB> {
         if let Some(block_range) = targets.receipts.clone() {
             segments.push((Box::new(segments::Receipts), block_range));
         }
+        if let Some(block_range) = targets.account_changesets.clone() {
+            segments.push((Box::new(segments::AccountChangeSets), block_range));
+        }
+        if let Some(block_range) = targets.storage_changesets.clone() {
+            segments.push((Box::new(segments::StorageChangeSets), block_range));
+        }
 
         segments.par_iter().try_for_each(|(segment, block_range)| -> ProviderResult<()> {
             debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
@@ -200,6 +214,28 @@ impl<DB: Database> StaticFileProducerInner<DB> {
                     finalized_block_number,
                 )
             }),
+            // StaticFile changesets only if the history is not pruned according to the user
+            // configuration
+            account_changesets: if self.prune_modes.account_history.is_none() {
+                finalized_block_numbers.account_changesets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.account_changesets,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
+            storage_changesets: if self.prune_modes.storage_history.is_none() {
+                finalized_block_numbers.storage_changesets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.storage_changesets,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
         };
 
         trace!(
@@ -296,6 +332,8 @@ mod tests {
                 headers: Some(1),
                 receipts: Some(1),
                 transactions: Some(1),
+                account_changesets: Some(1),
+                storage_changesets: Some(1),
             })
             .expect("get static file targets");
         assert_eq!(
@@ -303,13 +341,21 @@ mod tests {
             StaticFileTargets {
                 headers: Some(0..=1),
                 receipts: Some(0..=1),
-                transactions: Some(0..=1)
+                transactions: Some(0..=1),
+                account_changesets: Some(0..=1),
+                storage_changesets: Some(0..=1),
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(1), receipts: Some(1), transactions: Some(1) }
+            HighestStaticFiles {
+                headers: Some(1),
+                receipts: Some(1),
+                transactions: Some(1),
+                account_changesets: Some(1),
+                storage_changesets: Some(1),
+            }
         );
 
         let targets = static_file_producer
@@ -317,6 +363,8 @@ mod tests {
                 headers: Some(3),
                 receipts: Some(3),
                 transactions: Some(3),
+                account_changesets: Some(3),
+                storage_changesets: Some(3),
             })
             .expect("get static file targets");
         assert_eq!(
@@ -324,13 +372,21 @@ mod tests {
             StaticFileTargets {
                 headers: Some(2..=3),
                 receipts: Some(2..=3),
-                transactions: Some(2..=3)
+                transactions: Some(2..=3),
+                account_changesets: Some(2..=3),
+                storage_changesets: Some(2..=3),
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                account_changesets: Some(3),
+                storage_changesets: Some(3),
+            }
         );
 
         let targets = static_file_producer
@@ -338,6 +394,7 @@ mod tests {
                 headers: Some(4),
                 receipts: Some(4),
                 transactions: Some(4),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -345,7 +402,8 @@ mod tests {
             StaticFileTargets {
                 headers: Some(4..=4),
                 receipts: Some(4..=4),
-                transactions: Some(4..=4)
+                transactions: Some(4..=4),
+                ..Default::default()
             }
         );
         assert_matches!(
@@ -354,7 +412,13 @@ mod tests {
         );
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                account_changesets: Some(3),
+                storage_changesets: Some(3),
+            }
         );
     }
 
@@ -382,6 +446,7 @@ mod tests {
                         headers: Some(1),
                         receipts: Some(1),
                         transactions: Some(1),
+                        ..Default::default()
                     })
                     .expect("get static file targets");
                 assert_matches!(locked_producer.run(targets.clone()), Ok(_));
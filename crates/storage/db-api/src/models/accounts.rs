@@ -7,15 +7,15 @@ use crate::{
     table::{Decode, Encode},
     DatabaseError,
 };
-use reth_codecs::{derive_arbitrary, Compact};
-use reth_primitives::{Account, Address, BlockNumber, Buf, StorageKey};
+use reth_codecs::{derive_arbitrary, main_codec, Compact};
+use reth_primitives::{Account, Address, BlockNumber, Buf, StorageKey, TxNumber, U256};
 use serde::{Deserialize, Serialize};
 
 /// Account as it is saved in the database.
 ///
 /// [`Address`] is the subkey.
 #[derive_arbitrary(compact)]
-#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize)]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct AccountBeforeTx {
     /// Address for the account. Acts as `DupSort::SubKey`.
     pub address: Address,
@@ -57,6 +57,72 @@ impl Compact for AccountBeforeTx {
     }
 }
 
+/// The account changes of a block, as stored in the `AccountChangeSets` static files.
+///
+/// Holds one [`AccountBeforeTx`] for each account changed in the block, sorted by address.
+#[main_codec]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountChangeSet {
+    /// Accounts changed in the block, with their state before the block.
+    pub accounts: Vec<AccountBeforeTx>,
+}
+
+impl AccountChangeSet {
+    /// Returns the state of `address` before the block, if the account changed in the block.
+    pub fn get(&self, address: &Address) -> Option<&AccountBeforeTx> {
+        self.accounts
+            .binary_search_by(|account| account.address.cmp(address))
+            .ok()
+            .map(|index| &self.accounts[index])
+    }
+}
+
+/// Storage slot as it is saved in the `StorageChangeSets` static files.
+#[main_codec]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct StorageBeforeTx {
+    /// Address of the account owning the storage slot.
+    pub address: Address,
+    /// Storage slot key.
+    pub key: StorageKey,
+    /// Storage slot value before the block.
+    pub value: U256,
+}
+
+/// The storage changes of a block, as stored in the `StorageChangeSets` static files.
+///
+/// Holds one [`StorageBeforeTx`] for each storage slot changed in the block, sorted by address
+/// and key.
+#[main_codec]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StorageChangeSet {
+    /// Storage slots changed in the block, with their value before the block.
+    pub storages: Vec<StorageBeforeTx>,
+}
+
+impl StorageChangeSet {
+    /// Returns the storage slot `key` of `address` before the block, if it changed in the block.
+    pub fn get(&self, address: &Address, key: &StorageKey) -> Option<&StorageBeforeTx> {
+        self.storages
+            .binary_search_by(|storage| (&storage.address, &storage.key).cmp(&(address, key)))
+            .ok()
+            .map(|index| &self.storages[index])
+    }
+}
+
+/// The transaction that created a contract, and the account that created it.
+///
+/// The creator is the sender of the transaction, or the contract that executed the `CREATE` or
+/// `CREATE2` opcode.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[main_codec]
+pub struct ContractCreation {
+    /// The number of the transaction that created the contract.
+    pub tx_number: TxNumber,
+    /// The account that created the contract.
+    pub creator: Address,
+}
+
 /// [`BlockNumber`] concatenated with [`Address`].
 ///
 /// Since it's used as a key, it isn't compressed when encoding it.
@@ -186,6 +252,35 @@ mod tests {
         assert_eq!(bytes, Encode::encode(key));
     }
 
+    #[test]
+    fn test_changesets() {
+        use crate::table::{Compress, Decompress};
+
+        let address = Address::with_last_byte(2);
+        let accounts = AccountChangeSet {
+            accounts: vec![
+                AccountBeforeTx { address: Address::with_last_byte(1), info: None },
+                AccountBeforeTx { address, info: Some(Account { nonce: 1, ..Default::default() }) },
+            ],
+        };
+        let decoded = AccountChangeSet::decompress(accounts.clone().compress()).unwrap();
+        assert_eq!(decoded, accounts);
+        assert_eq!(decoded.get(&address), Some(&accounts.accounts[1]));
+        assert_eq!(decoded.get(&Address::ZERO), None);
+
+        let key = StorageKey::with_last_byte(1);
+        let storages = StorageChangeSet {
+            storages: vec![
+                StorageBeforeTx { address, key: StorageKey::ZERO, value: U256::from(1) },
+                StorageBeforeTx { address, key, value: U256::ZERO },
+            ],
+        };
+        let decoded = StorageChangeSet::decompress(storages.clone().compress()).unwrap();
+        assert_eq!(decoded, storages);
+        assert_eq!(decoded.get(&address, &key), Some(&storages.storages[1]));
+        assert_eq!(decoded.get(&Address::ZERO, &key), None);
+    }
+
     #[test]
     fn test_address_storage_key() {
         let storage_key = StorageKey::random();
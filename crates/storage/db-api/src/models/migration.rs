@@ -0,0 +1,27 @@
+//! Database migration models.
+
+use reth_codecs::{main_codec, Compact};
+use reth_primitives::Bytes;
+
+/// Progress of a database migration, saved by the database version it migrates to.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[main_codec]
+pub struct MigrationCheckpoint {
+    /// Whether the migration has finished.
+    pub finished: bool,
+    /// Migration-specific progress of an unfinished migration. Empty if the migration hasn't
+    /// committed any progress yet.
+    pub progress: Bytes,
+}
+
+impl MigrationCheckpoint {
+    /// Creates a checkpoint of an unfinished migration.
+    pub const fn new(progress: Bytes) -> Self {
+        Self { finished: false, progress }
+    }
+
+    /// Creates a checkpoint of a finished migration.
+    pub const fn finished() -> Self {
+        Self { finished: true, progress: Bytes::new() }
+    }
+}
@@ -14,12 +14,14 @@ pub mod accounts;
 pub mod blocks;
 pub mod client_version;
 pub mod integer_list;
+pub mod migration;
 pub mod sharded_key;
 pub mod storage_sharded_key;
 
 pub use accounts::*;
 pub use blocks::*;
 pub use client_version::ClientVersion;
+pub use migration::MigrationCheckpoint;
 pub use sharded_key::ShardedKey;
 
 /// Macro that implements [`Encode`] and [`Decode`] for uint types.
@@ -216,11 +218,15 @@ impl_compression_for_compact!(
     StoredBlockWithdrawals,
     Bytecode,
     AccountBeforeTx,
+    AccountChangeSet,
+    StorageChangeSet,
+    ContractCreation,
     TransactionSignedNoHash,
     CompactU256,
     StageCheckpoint,
     PruneCheckpoint,
     ClientVersion,
+    MigrationCheckpoint,
     Requests,
     // Non-DB
     GenesisAccount
@@ -319,6 +325,7 @@ mod tests {
         #[cfg(not(feature = "optimism"))]
         {
             assert_eq!(Account::bitflag_encoded_bytes(), 2);
+            assert_eq!(AccountChangeSet::bitflag_encoded_bytes(), 0);
             assert_eq!(AccountHashingCheckpoint::bitflag_encoded_bytes(), 1);
             assert_eq!(CheckpointBlockRange::bitflag_encoded_bytes(), 1);
             assert_eq!(CompactClientVersion::bitflag_encoded_bytes(), 0);
@@ -340,6 +347,8 @@ mod tests {
             assert_eq!(StoredBlockBodyIndices::bitflag_encoded_bytes(), 1);
             assert_eq!(StoredBlockOmmers::bitflag_encoded_bytes(), 0);
             assert_eq!(StoredBlockWithdrawals::bitflag_encoded_bytes(), 0);
+            assert_eq!(StorageBeforeTx::bitflag_encoded_bytes(), 1);
+            assert_eq!(StorageChangeSet::bitflag_encoded_bytes(), 0);
             assert_eq!(StorageHashingCheckpoint::bitflag_encoded_bytes(), 1);
             assert_eq!(TxEip1559::bitflag_encoded_bytes(), 4);
             assert_eq!(TxEip2930::bitflag_encoded_bytes(), 3);
@@ -351,6 +360,7 @@ mod tests {
         #[cfg(feature = "optimism")]
         {
             assert_eq!(Account::bitflag_encoded_bytes(), 2);
+            assert_eq!(AccountChangeSet::bitflag_encoded_bytes(), 0);
             assert_eq!(AccountHashingCheckpoint::bitflag_encoded_bytes(), 1);
             assert_eq!(CheckpointBlockRange::bitflag_encoded_bytes(), 1);
             assert_eq!(CompactClientVersion::bitflag_encoded_bytes(), 0);
@@ -372,6 +382,8 @@ mod tests {
             assert_eq!(StoredBlockBodyIndices::bitflag_encoded_bytes(), 1);
             assert_eq!(StoredBlockOmmers::bitflag_encoded_bytes(), 0);
             assert_eq!(StoredBlockWithdrawals::bitflag_encoded_bytes(), 0);
+            assert_eq!(StorageBeforeTx::bitflag_encoded_bytes(), 1);
+            assert_eq!(StorageChangeSet::bitflag_encoded_bytes(), 0);
             assert_eq!(StorageHashingCheckpoint::bitflag_encoded_bytes(), 1);
             assert_eq!(TxEip1559::bitflag_encoded_bytes(), 4);
             assert_eq!(TxEip2930::bitflag_encoded_bytes(), 3);
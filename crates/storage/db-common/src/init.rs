@@ -505,21 +505,22 @@ fn compute_state_root<DB: Database>(provider: &DatabaseProviderRW<DB>) -> eyre::
     }
 }
 
-/// Type to deserialize state root from state dump file.
+/// Type to (de)serialize the state root, the first line of the state dump file.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
-struct StateRoot {
-    root: B256,
+pub struct StateRoot {
+    /// The state root of the dumped state.
+    pub root: B256,
 }
 
 /// An account as in the state dump file. This contains a [`GenesisAccount`] and the account's
 /// address.
 #[derive(Debug, Serialize, Deserialize)]
-struct GenesisAccountWithAddress {
+pub struct GenesisAccountWithAddress {
     /// The account's balance, nonce, code, and storage.
     #[serde(flatten)]
-    genesis_account: GenesisAccount,
+    pub genesis_account: GenesisAccount,
     /// The account's address.
-    address: Address,
+    pub address: Address,
 }
 
 #[cfg(test)]
@@ -0,0 +1,270 @@
+//! Cursor of the in-memory database.
+
+use super::{
+    table, table_mut,
+    tx::{TransactionKind, RW},
+    MemoryDatabaseError, MemoryTable, MemoryTables, Row,
+};
+use crate::{tables::utils::decoder, DatabaseError};
+use parking_lot::Mutex;
+use reth_db_api::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    table::{Compress, DupSort, Encode, Table},
+};
+use reth_storage_errors::db::{DatabaseWriteError, DatabaseWriteOperation};
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+/// Cursor over table `T` of an in-memory transaction.
+///
+/// The cursor remembers the row it is positioned at, so it keeps working when rows are inserted or
+/// deleted through other cursors of the same transaction. A move that finds no row leaves the
+/// position unchanged.
+#[derive(Debug)]
+pub struct MemoryCursor<K: TransactionKind, T: Table> {
+    /// Tables of the transaction.
+    tables: Arc<Mutex<MemoryTables>>,
+    /// Encoded row the cursor is positioned at.
+    position: Option<Row>,
+    _kind: PhantomData<(K, T)>,
+}
+
+impl<K: TransactionKind, T: Table> MemoryCursor<K, T> {
+    pub(crate) const fn new(tables: Arc<Mutex<MemoryTables>>) -> Self {
+        Self { tables, position: None, _kind: PhantomData }
+    }
+
+    /// Runs `f` on the table of the cursor.
+    fn read<R>(&self, f: impl FnOnce(&MemoryTable) -> R) -> Result<R, DatabaseError> {
+        Ok(f(table(&self.tables.lock(), T::NAME)?))
+    }
+
+    /// Positions the cursor at `row`, if any, and decodes it.
+    fn position_at(&mut self, row: Option<Row>) -> PairResult<T> {
+        let Some((key, value)) = row else { return Ok(None) };
+        let decoded =
+            decoder::<T>((Cow::Borrowed(key.as_slice()), Cow::Borrowed(value.as_slice())))?;
+        self.position = Some((key, value));
+        Ok(Some(decoded))
+    }
+}
+
+impl<K: TransactionKind, T: Table> DbCursorRO<T> for MemoryCursor<K, T> {
+    fn first(&mut self) -> PairResult<T> {
+        let row = self.read(MemoryTable::first)?;
+        self.position_at(row)
+    }
+
+    fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        let row = self.read(|table| table.seek_exact(key.encode().as_ref()))?;
+        self.position_at(row)
+    }
+
+    fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        let row = self.read(|table| table.seek(key.encode().as_ref()))?;
+        self.position_at(row)
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        let row = match &self.position {
+            Some((key, value)) => self.read(|table| table.next(key, value))?,
+            None => self.read(MemoryTable::first)?,
+        };
+        self.position_at(row)
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        let row = match &self.position {
+            Some((key, value)) => self.read(|table| table.prev(key, value))?,
+            None => self.read(MemoryTable::last)?,
+        };
+        self.position_at(row)
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        let row = self.read(MemoryTable::last)?;
+        self.position_at(row)
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        let Some((key, value)) = &self.position else { return Ok(None) };
+        // the row after a deleted one takes its place, like in MDBX
+        self.read(|table| table.current(key, value))?
+            .map(|(key, value)| decoder::<T>((Cow::Owned(key), Cow::Owned(value))))
+            .transpose()
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = match start_key {
+            Some(start_key) => self.seek(start_key),
+            None => self.first(),
+        }
+        .transpose();
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start = match start_key {
+            Some(start_key) => self.seek(start_key),
+            None => self.last(),
+        }
+        .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for MemoryCursor<K, T> {
+    fn next_dup(&mut self) -> PairResult<T> {
+        let Some((key, value)) = &self.position else { return Ok(None) };
+        let row = self.read(|table| table.next_dup(key, value))?;
+        self.position_at(row)
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let row = match &self.position {
+            Some((key, _)) => self.read(|table| table.next_no_dup(key))?,
+            None => self.read(MemoryTable::first)?,
+        };
+        self.position_at(row)
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(self.next_dup()?.map(|(_, value)| value))
+    }
+
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        let row = self.read(|table| {
+            table.seek_by_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())
+        })?;
+        Ok(self.position_at(row)?.map(|(_, value)| value))
+    }
+
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let start = match (key, subkey) {
+            (Some(key), Some(subkey)) => {
+                let row = self.read(|table| {
+                    table.seek_by_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())
+                })?;
+                self.position_at(row)
+            }
+            (Some(key), None) => self.seek_exact(key),
+            (None, Some(subkey)) => {
+                let row = self.read(|table| {
+                    table.first().and_then(|(key, _)| {
+                        table.seek_by_key_subkey(&key, subkey.encode().as_ref())
+                    })
+                })?;
+                self.position_at(row)
+            }
+            (None, None) => self.first(),
+        }
+        .transpose();
+
+        Ok(DupWalker::<'_, T, Self> { cursor: self, start })
+    }
+}
+
+impl<T: Table> MemoryCursor<RW, T> {
+    /// Runs `f` on the table of the cursor, copying it first if it is shared with other
+    /// transactions.
+    fn write<R>(&self, f: impl FnOnce(&mut MemoryTable) -> R) -> Result<R, DatabaseError> {
+        Ok(f(table_mut(&mut self.tables.lock(), T::NAME)?))
+    }
+
+    /// Runs the write `f` of `(key, value)` and positions the cursor at the new row.
+    fn write_row(
+        &mut self,
+        operation: DatabaseWriteOperation,
+        key: T::Key,
+        value: T::Value,
+        f: impl FnOnce(&mut MemoryTable, Vec<u8>, Vec<u8>) -> Result<(), MemoryDatabaseError>,
+    ) -> Result<(), DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let value: Vec<u8> = value.compress().into();
+        self.write(|table| f(table, key.clone(), value.clone()))?.map_err(|e| {
+            DatabaseWriteError { info: e.into(), operation, table_name: T::NAME, key: key.clone() }
+        })?;
+        self.position = Some((key, value));
+        Ok(())
+    }
+}
+
+impl<T: Table> DbCursorRW<T> for MemoryCursor<RW, T> {
+    /// For a DUPSORT table, `upsert` adds the value to the values of the key, even if a value with
+    /// the same subkey exists, like MDBX does.
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.write_row(DatabaseWriteOperation::CursorUpsert, key, value, |table, key, value| {
+            table.put(key, value);
+            Ok(())
+        })
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.write_row(DatabaseWriteOperation::CursorInsert, key, value, |table, key, value| {
+            if table.contains_key(&key) {
+                return Err(MemoryDatabaseError::KeyExist)
+            }
+            table.put(key, value);
+            Ok(())
+        })
+    }
+
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.write_row(DatabaseWriteOperation::CursorAppend, key, value, MemoryTable::append)
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        let Some((key, value)) = &self.position else {
+            return Err(DatabaseError::Delete(MemoryDatabaseError::NotPositioned.into()))
+        };
+        self.write(|table| table.remove(key, value))?;
+        Ok(())
+    }
+}
+
+impl<T: DupSort> DbDupCursorRW<T> for MemoryCursor<RW, T> {
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        let Some((key, _)) = &self.position else {
+            return Err(DatabaseError::Delete(MemoryDatabaseError::NotPositioned.into()))
+        };
+        self.write(|table| table.remove_key(key))?;
+        Ok(())
+    }
+
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.write_row(DatabaseWriteOperation::CursorAppendDup, key, value, MemoryTable::append_dup)
+    }
+}
@@ -0,0 +1,540 @@
+//! Module that keeps the database in memory.
+
+use crate::{tables::Tables, DatabaseError};
+use im::{OrdMap, OrdSet};
+use metrics::Label;
+use parking_lot::{Condvar, Mutex, RwLock};
+use reth_db_api::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+};
+use reth_storage_errors::db::DatabaseErrorInfo;
+use std::{cmp::Ordering, collections::HashMap, ops::Bound, sync::Arc};
+use tx::{MemoryTx, RO, RW};
+
+pub mod cursor;
+pub mod tx;
+
+/// Encoded key and compressed value of a table row.
+pub(crate) type Row = (Vec<u8>, Vec<u8>);
+
+/// Tables of a database snapshot, keyed by table name.
+pub(crate) type MemoryTables = HashMap<&'static str, MemoryTable>;
+
+/// Errors of the in-memory database. The error codes are the ones MDBX uses for the same
+/// conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum MemoryDatabaseError {
+    /// The table does not exist.
+    #[error("table does not exist")]
+    TableNotFound,
+    /// The key already exists and overwriting was not allowed.
+    #[error("key already exists")]
+    KeyExist,
+    /// The key or value is not in order for an append.
+    #[error("appended key is not in order")]
+    KeyMismatch,
+    /// The cursor is not positioned at a row.
+    #[error("cursor is not positioned")]
+    NotPositioned,
+}
+
+impl From<MemoryDatabaseError> for i32 {
+    fn from(value: MemoryDatabaseError) -> Self {
+        match value {
+            MemoryDatabaseError::TableNotFound => -30780,
+            MemoryDatabaseError::KeyExist => -30799,
+            MemoryDatabaseError::KeyMismatch => -30418,
+            MemoryDatabaseError::NotPositioned => 22,
+        }
+    }
+}
+
+/// Rows of a single table.
+///
+/// Every encoded key maps to the sorted set of its compressed values. Tables that are not
+/// `DUPSORT` hold exactly one value per key, and no key is kept with an empty set.
+///
+/// The rows are kept in persistent maps, so cloning a table is cheap and a write only copies the
+/// nodes on the path to the written row, while the clones keep sharing all other nodes.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MemoryTable {
+    /// Whether the table allows multiple values per key.
+    pub(crate) dupsort: bool,
+    /// Values of the table by key.
+    pub(crate) rows: OrdMap<Vec<u8>, OrdSet<Vec<u8>>>,
+}
+
+impl MemoryTable {
+    fn new(dupsort: bool) -> Self {
+        Self { dupsort, rows: OrdMap::new() }
+    }
+
+    /// Returns the number of rows in the table.
+    pub(crate) fn entries(&self) -> usize {
+        self.rows.values().map(OrdSet::len).sum()
+    }
+
+    /// Returns the first row of the table.
+    pub(crate) fn first(&self) -> Option<Row> {
+        self.rows.get_min().map(|(key, values)| first_row(key, values))
+    }
+
+    /// Returns the last row of the table.
+    pub(crate) fn last(&self) -> Option<Row> {
+        self.rows.get_max().map(|(key, values)| last_row(key, values))
+    }
+
+    /// Returns the first row of `key`.
+    pub(crate) fn seek_exact(&self, key: &[u8]) -> Option<Row> {
+        self.rows.get(key).map(|values| first_row(key, values))
+    }
+
+    /// Returns the first row whose key is greater than or equal to `key`.
+    pub(crate) fn seek(&self, key: &[u8]) -> Option<Row> {
+        self.rows
+            .range::<_, [u8]>((Bound::Included(key), Bound::Unbounded))
+            .next()
+            .map(|(key, values)| first_row(key, values))
+    }
+
+    /// Returns the first row of `key` whose value is greater than or equal to `subkey`.
+    pub(crate) fn seek_by_key_subkey(&self, key: &[u8], subkey: &[u8]) -> Option<Row> {
+        self.rows.get(key).and_then(|values| {
+            values
+                .range::<_, [u8]>((Bound::Included(subkey), Bound::Unbounded))
+                .next()
+                .map(|value| (key.to_vec(), value.clone()))
+        })
+    }
+
+    /// Returns the row at `(key, value)`, or the row after it if it no longer exists.
+    pub(crate) fn current(&self, key: &[u8], value: &[u8]) -> Option<Row> {
+        if self.rows.get(key).is_some_and(|values| values.contains(value)) {
+            return Some((key.to_vec(), value.to_vec()))
+        }
+        self.next(key, value)
+    }
+
+    /// Returns the row after `(key, value)`.
+    pub(crate) fn next(&self, key: &[u8], value: &[u8]) -> Option<Row> {
+        self.next_dup(key, value).or_else(|| self.next_no_dup(key))
+    }
+
+    /// Returns the row before `(key, value)`.
+    pub(crate) fn prev(&self, key: &[u8], value: &[u8]) -> Option<Row> {
+        self.rows
+            .get(key)
+            .and_then(|values| {
+                values
+                    .range::<_, [u8]>((Bound::Unbounded, Bound::Excluded(value)))
+                    .next_back()
+                    .map(|value| (key.to_vec(), value.clone()))
+            })
+            .or_else(|| {
+                self.rows
+                    .range::<_, [u8]>((Bound::Unbounded, Bound::Excluded(key)))
+                    .next_back()
+                    .map(|(key, values)| last_row(key, values))
+            })
+    }
+
+    /// Returns the row of `key` after `value`.
+    pub(crate) fn next_dup(&self, key: &[u8], value: &[u8]) -> Option<Row> {
+        self.rows.get(key).and_then(|values| {
+            values
+                .range::<_, [u8]>((Bound::Excluded(value), Bound::Unbounded))
+                .next()
+                .map(|value| (key.to_vec(), value.clone()))
+        })
+    }
+
+    /// Returns the first row whose key is greater than `key`.
+    pub(crate) fn next_no_dup(&self, key: &[u8]) -> Option<Row> {
+        self.rows
+            .range::<_, [u8]>((Bound::Excluded(key), Bound::Unbounded))
+            .next()
+            .map(|(key, values)| first_row(key, values))
+    }
+
+    /// Returns `true` if the table has a row with `key`.
+    pub(crate) fn contains_key(&self, key: &[u8]) -> bool {
+        self.rows.contains_key(key)
+    }
+
+    /// Inserts a row. Replaces the value of `key` unless the table is `DUPSORT`, in which case the
+    /// value is added to the values of `key`.
+    pub(crate) fn put(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        let values = self.rows.entry(key).or_default();
+        if !self.dupsort {
+            values.clear();
+        }
+        values.insert(value);
+    }
+
+    /// Inserts a row that must sort after all rows of the table.
+    pub(crate) fn append(
+        &mut self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), MemoryDatabaseError> {
+        if let Some((last_key, last_values)) = self.rows.get_max() {
+            let in_order = match key.cmp(last_key) {
+                Ordering::Greater => true,
+                Ordering::Equal => {
+                    self.dupsort && last_values.get_max().is_some_and(|last| value > *last)
+                }
+                Ordering::Less => false,
+            };
+            if !in_order {
+                return Err(MemoryDatabaseError::KeyMismatch)
+            }
+        }
+        self.put(key, value);
+        Ok(())
+    }
+
+    /// Inserts a row that must sort after all rows of its key.
+    pub(crate) fn append_dup(
+        &mut self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), MemoryDatabaseError> {
+        if self.rows.get(&key).and_then(OrdSet::get_max).is_some_and(|last| value <= *last) {
+            return Err(MemoryDatabaseError::KeyMismatch)
+        }
+        self.put(key, value);
+        Ok(())
+    }
+
+    /// Removes all rows of `key`, returning `true` if there were any.
+    pub(crate) fn remove_key(&mut self, key: &[u8]) -> bool {
+        self.rows.remove(key).is_some()
+    }
+
+    /// Removes the row at `(key, value)`, returning `true` if it existed.
+    pub(crate) fn remove(&mut self, key: &[u8], value: &[u8]) -> bool {
+        let Some(values) = self.rows.get_mut(key) else { return false };
+        let removed = values.remove(value).is_some();
+        if values.is_empty() {
+            self.rows.remove(key);
+        }
+        removed
+    }
+}
+
+fn first_row(key: &[u8], values: &OrdSet<Vec<u8>>) -> Row {
+    (key.to_vec(), values.get_min().cloned().unwrap_or_default())
+}
+
+fn last_row(key: &[u8], values: &OrdSet<Vec<u8>>) -> Row {
+    (key.to_vec(), values.get_max().cloned().unwrap_or_default())
+}
+
+/// Returns the table of `name`, or an error if the database has no such table.
+pub(crate) fn table<'a>(
+    tables: &'a MemoryTables,
+    name: &str,
+) -> Result<&'a MemoryTable, DatabaseError> {
+    tables.get(name).ok_or_else(|| DatabaseError::Open(MemoryDatabaseError::TableNotFound.into()))
+}
+
+/// Returns the table of `name` for writing.
+pub(crate) fn table_mut<'a>(
+    tables: &'a mut MemoryTables,
+    name: &str,
+) -> Result<&'a mut MemoryTable, DatabaseError> {
+    tables
+        .get_mut(name)
+        .ok_or_else(|| DatabaseError::Open(MemoryDatabaseError::TableNotFound.into()))
+}
+
+/// State shared between the database and its read-write transaction.
+#[derive(Debug, Default)]
+pub(crate) struct DatabaseState {
+    /// Latest committed tables.
+    pub(crate) tables: RwLock<MemoryTables>,
+    /// Whether a read-write transaction is open.
+    writer: Mutex<bool>,
+    /// Notified when the read-write transaction is closed.
+    writer_closed: Condvar,
+}
+
+/// Exclusive write access to the database, released on drop.
+#[derive(Debug)]
+pub(crate) struct WriteLock {
+    state: Arc<DatabaseState>,
+}
+
+impl WriteLock {
+    /// Waits until no other read-write transaction is open and takes its place.
+    fn acquire(state: Arc<DatabaseState>) -> Self {
+        let mut writer = state.writer.lock();
+        while *writer {
+            state.writer_closed.wait(&mut writer);
+        }
+        *writer = true;
+        drop(writer);
+        Self { state }
+    }
+
+    /// Makes `tables` the latest committed tables.
+    pub(crate) fn commit(&self, tables: MemoryTables) {
+        *self.state.tables.write() = tables;
+    }
+}
+
+impl Drop for WriteLock {
+    fn drop(&mut self) {
+        *self.state.writer.lock() = false;
+        self.state.writer_closed.notify_one();
+    }
+}
+
+/// Database that keeps all tables in memory.
+///
+/// Read-only transactions work on the snapshot of the tables that was committed when they were
+/// opened. Like with MDBX, only one read-write transaction can be open at a time; opening another
+/// one blocks until it is committed or aborted. Transactions share the unchanged parts of the
+/// tables with the snapshot they were opened on, so the cost of a write grows only with the
+/// logarithm of the size of its table.
+#[derive(Clone, Debug)]
+pub struct MemoryDatabase {
+    state: Arc<DatabaseState>,
+}
+
+impl MemoryDatabase {
+    /// Creates an empty database with all [`Tables`].
+    pub fn new() -> Self {
+        let tables = Tables::ALL
+            .iter()
+            .map(|table| (table.name(), MemoryTable::new(table.is_dupsort())))
+            .collect();
+        Self {
+            state: Arc::new(DatabaseState { tables: RwLock::new(tables), ..Default::default() }),
+        }
+    }
+}
+
+impl Default for MemoryDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Database for MemoryDatabase {
+    type TX = MemoryTx<RO>;
+    type TXMut = MemoryTx<RW>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        Ok(MemoryTx::new(self.state.tables.read().clone(), None))
+    }
+
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        let lock = WriteLock::acquire(self.state.clone());
+        Ok(MemoryTx::new(self.state.tables.read().clone(), Some(lock)))
+    }
+}
+
+impl DatabaseMetrics for MemoryDatabase {
+    fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
+        self.state
+            .tables
+            .read()
+            .iter()
+            .map(|(name, table)| {
+                ("db.table_entries", table.entries() as f64, vec![Label::new("table", *name)])
+            })
+            .collect()
+    }
+}
+
+impl DatabaseMetadata for MemoryDatabase {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        DatabaseMetadataValue::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables;
+    use reth_db_api::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Account, Address, StorageEntry, B256, U256};
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn commit_and_abort() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::CanonicalHeaders>(1, B256::repeat_byte(1)).unwrap();
+        tx.abort();
+        assert_eq!(db.tx().unwrap().get::<tables::CanonicalHeaders>(1), Ok(None));
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::CanonicalHeaders>(1, B256::repeat_byte(1)).unwrap();
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(1), Ok(Some(B256::repeat_byte(1))));
+        tx.commit().unwrap();
+        assert_eq!(
+            db.tx().unwrap().get::<tables::CanonicalHeaders>(1),
+            Ok(Some(B256::repeat_byte(1)))
+        );
+    }
+
+    #[test]
+    fn read_transaction_isolation() {
+        let db = MemoryDatabase::new();
+        db.update(|tx| tx.put::<tables::CanonicalHeaders>(1, B256::repeat_byte(1)))
+            .unwrap()
+            .unwrap();
+
+        let reader = db.tx().unwrap();
+        let writer = db.tx_mut().unwrap();
+        writer.put::<tables::CanonicalHeaders>(1, B256::repeat_byte(2)).unwrap();
+        writer.put::<tables::CanonicalHeaders>(2, B256::repeat_byte(2)).unwrap();
+        writer.commit().unwrap();
+
+        assert_eq!(reader.get::<tables::CanonicalHeaders>(1), Ok(Some(B256::repeat_byte(1))));
+        assert_eq!(reader.entries::<tables::CanonicalHeaders>(), Ok(1));
+        assert_eq!(db.tx().unwrap().entries::<tables::CanonicalHeaders>(), Ok(2));
+    }
+
+    #[test]
+    fn snapshots_keep_their_rows() {
+        let db = MemoryDatabase::new();
+        db.update(|tx| {
+            for number in 0..1_000 {
+                tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8))
+                    .unwrap();
+            }
+        })
+        .unwrap();
+
+        let reader = db.tx().unwrap();
+        let writer = db.tx_mut().unwrap();
+        for number in (0..1_000).step_by(2) {
+            writer.delete::<tables::CanonicalHeaders>(number, None).unwrap();
+        }
+        writer.put::<tables::CanonicalHeaders>(1, B256::ZERO).unwrap();
+        assert_eq!(writer.entries::<tables::CanonicalHeaders>(), Ok(500));
+        writer.commit().unwrap();
+
+        assert_eq!(reader.entries::<tables::CanonicalHeaders>(), Ok(1_000));
+        assert_eq!(reader.get::<tables::CanonicalHeaders>(1), Ok(Some(B256::with_last_byte(1))));
+        let mut cursor = reader.cursor_read::<tables::CanonicalHeaders>().unwrap();
+        assert_eq!(cursor.walk(None).unwrap().count(), 1_000);
+
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.entries::<tables::CanonicalHeaders>(), Ok(500));
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(0), Ok(None));
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(1), Ok(Some(B256::ZERO)));
+    }
+
+    #[test]
+    fn single_writer() {
+        let db = MemoryDatabase::new();
+        let writer = db.tx_mut().unwrap();
+
+        let handle = {
+            let db = db.clone();
+            thread::spawn(move || {
+                let tx = db.tx_mut().unwrap();
+                tx.get::<tables::CanonicalHeaders>(1).unwrap()
+            })
+        };
+
+        thread::sleep(Duration::from_millis(50));
+        writer.put::<tables::CanonicalHeaders>(1, B256::repeat_byte(1)).unwrap();
+        writer.commit().unwrap();
+
+        assert_eq!(handle.join().unwrap(), Some(B256::repeat_byte(1)));
+    }
+
+    #[test]
+    fn cursor_walk_and_delete() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().unwrap();
+
+        let mut cursor = tx.cursor_write::<tables::CanonicalHeaders>().unwrap();
+        for number in 0..5 {
+            cursor.append(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        assert!(cursor.append(2, B256::ZERO).is_err());
+        assert!(cursor.insert(2, B256::ZERO).is_err());
+
+        let mut walker = cursor.walk(Some(1)).unwrap();
+        assert_eq!(walker.next().unwrap().unwrap().0, 1);
+        walker.delete_current().unwrap();
+        assert_eq!(walker.next().unwrap().unwrap().0, 2);
+
+        assert_eq!(
+            cursor.walk_range(1..4).unwrap().map(|row| row.unwrap().0).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(
+            cursor.walk_back(None).unwrap().map(|row| row.unwrap().0).collect::<Vec<_>>(),
+            vec![4, 3, 2, 0]
+        );
+        assert_eq!(cursor.seek(1).unwrap().map(|row| row.0), Some(2));
+        assert_eq!(cursor.prev().unwrap().map(|row| row.0), Some(0));
+        assert_eq!(cursor.seek_exact(1), Ok(None));
+
+        cursor.upsert(2, B256::ZERO).unwrap();
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(2), Ok(Some(B256::ZERO)));
+        assert_eq!(tx.entries::<tables::CanonicalHeaders>(), Ok(4));
+    }
+
+    #[test]
+    fn dup_cursor() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().unwrap();
+        let address = Address::with_last_byte(1);
+        let entry = |key: u8, value: u64| StorageEntry {
+            key: B256::with_last_byte(key),
+            value: U256::from(value),
+        };
+
+        let mut cursor = tx.cursor_dup_write::<tables::PlainStorageState>().unwrap();
+        cursor.upsert(address, entry(3, 3)).unwrap();
+        cursor.upsert(address, entry(1, 1)).unwrap();
+        cursor.append_dup(address, entry(5, 5)).unwrap();
+        assert!(cursor.append_dup(address, entry(4, 4)).is_err());
+        tx.put::<tables::PlainAccountState>(address, Account::default()).unwrap();
+        tx.put::<tables::PlainStorageState>(Address::with_last_byte(2), entry(1, 1)).unwrap();
+
+        assert_eq!(cursor.seek_exact(address).unwrap(), Some((address, entry(1, 1))));
+        assert_eq!(cursor.next_dup_val().unwrap(), Some(entry(3, 3)));
+        assert_eq!(
+            cursor.seek_by_key_subkey(address, B256::with_last_byte(4)).unwrap(),
+            Some(entry(5, 5))
+        );
+        assert_eq!(cursor.next_dup().unwrap(), None);
+        assert_eq!(
+            cursor.next_no_dup().unwrap().map(|row| row.0),
+            Some(Address::with_last_byte(2))
+        );
+
+        assert_eq!(
+            cursor
+                .walk_dup(Some(address), Some(B256::with_last_byte(2)))
+                .unwrap()
+                .map(|row| row.unwrap().1)
+                .collect::<Vec<_>>(),
+            vec![entry(3, 3), entry(5, 5)]
+        );
+
+        cursor.seek_by_key_subkey(address, B256::with_last_byte(3)).unwrap();
+        cursor.delete_current().unwrap();
+        assert_eq!(cursor.next_dup_val().unwrap(), Some(entry(5, 5)));
+        assert!(tx.delete::<tables::PlainStorageState>(address, Some(entry(5, 5))).unwrap());
+        assert_eq!(tx.entries::<tables::PlainStorageState>(), Ok(2));
+
+        cursor.seek_exact(address).unwrap();
+        cursor.delete_current_duplicates().unwrap();
+        assert_eq!(cursor.first().unwrap(), Some((Address::with_last_byte(2), entry(1, 1))));
+    }
+}
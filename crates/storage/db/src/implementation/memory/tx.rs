@@ -0,0 +1,131 @@
+//! Transaction of the in-memory database.
+
+use super::{cursor::MemoryCursor, table, table_mut, MemoryTables, WriteLock};
+use crate::{tables::utils::decode_one, DatabaseError};
+use parking_lot::Mutex;
+use reth_db_api::{
+    table::{Compress, DupSort, Encode, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+};
+use std::{borrow::Cow, fmt::Debug, marker::PhantomData, sync::Arc};
+
+/// Kind of an in-memory transaction, either [`RO`] or [`RW`].
+pub trait TransactionKind: private::Sealed + Send + Sync + Debug + 'static {}
+
+/// Marker for read-only transactions.
+#[derive(Debug)]
+pub struct RO;
+
+/// Marker for read-write transactions.
+#[derive(Debug)]
+pub struct RW;
+
+impl TransactionKind for RO {}
+impl TransactionKind for RW {}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::RO {}
+    impl Sealed for super::RW {}
+}
+
+/// Transaction of a [`MemoryDatabase`](super::MemoryDatabase).
+#[derive(Debug)]
+pub struct MemoryTx<K: TransactionKind> {
+    /// Tables as seen by the transaction, shared with its cursors.
+    tables: Arc<Mutex<MemoryTables>>,
+    /// Exclusive write access to the database, held by read-write transactions.
+    lock: Option<WriteLock>,
+    _kind: PhantomData<K>,
+}
+
+impl<K: TransactionKind> MemoryTx<K> {
+    /// Creates a transaction over a snapshot of the tables.
+    pub(crate) fn new(tables: MemoryTables, lock: Option<WriteLock>) -> Self {
+        Self { tables: Arc::new(Mutex::new(tables)), lock, _kind: PhantomData }
+    }
+
+    /// Creates a cursor over table `T`.
+    fn new_cursor<T: Table>(&self) -> Result<MemoryCursor<K, T>, DatabaseError> {
+        table(&self.tables.lock(), T::NAME)?;
+        Ok(MemoryCursor::new(self.tables.clone()))
+    }
+}
+
+impl<K: TransactionKind> DbTx for MemoryTx<K> {
+    type Cursor<T: Table> = MemoryCursor<K, T>;
+    type DupCursor<T: DupSort> = MemoryCursor<K, T>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        table(&self.tables.lock(), T::NAME)?
+            .seek_exact(key.encode().as_ref())
+            .map(|(_, value)| decode_one::<T>(Cow::Owned(value)))
+            .transpose()
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        if let Some(lock) = &self.lock {
+            lock.commit(std::mem::take(&mut *self.tables.lock()));
+        }
+        Ok(false)
+    }
+
+    fn abort(self) {}
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        self.new_cursor()
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        self.new_cursor()
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        Ok(table(&self.tables.lock(), T::NAME)?.entries())
+    }
+
+    fn disable_long_read_transaction_safety(&mut self) {}
+}
+
+impl DbTxMut for MemoryTx<RW> {
+    type CursorMut<T: Table> = MemoryCursor<RW, T>;
+    type DupCursorMut<T: DupSort> = MemoryCursor<RW, T>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        table_mut(&mut self.tables.lock(), T::NAME)?
+            .put(key.encode().into(), value.compress().into());
+        Ok(())
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let key = key.encode();
+        let mut tables = self.tables.lock();
+        let table = table_mut(&mut tables, T::NAME)?;
+
+        // like MDBX, the value is only taken into account for `DUPSORT` tables
+        Ok(match value {
+            Some(value) if table.dupsort => table.remove(key.as_ref(), value.compress().as_ref()),
+            _ => table.remove_key(key.as_ref()),
+        })
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        table_mut(&mut self.tables.lock(), T::NAME)?.rows.clear();
+        Ok(())
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        self.new_cursor()
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        self.new_cursor()
+    }
+}
+
+impl TableImporter for MemoryTx<RW> {}
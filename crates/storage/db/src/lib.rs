@@ -17,8 +17,10 @@
 
 mod implementation;
 pub mod lockfile;
+pub mod memory;
 #[cfg(feature = "not-zkvm")]
 mod metrics;
+pub mod migration;
 #[cfg(feature = "not-zkvm")]
 pub mod static_file;
 pub mod tables;
@@ -28,12 +30,15 @@ pub mod version;
 #[cfg(feature = "mdbx")]
 pub mod mdbx;
 
+pub use memory::MemoryDatabase;
 pub use reth_storage_errors::db::{DatabaseError, DatabaseWriteOperation};
 pub use tables::*;
 pub use utils::is_database_empty;
 
 #[cfg(feature = "mdbx")]
-pub use mdbx::{create_db, init_db, open_db, open_db_read_only, DatabaseEnv, DatabaseEnvKind};
+pub use mdbx::{
+    compact_db, create_db, init_db, open_db, open_db_read_only, DatabaseEnv, DatabaseEnvKind,
+};
 
 pub use reth_db_api::*;
 
@@ -180,16 +185,20 @@ pub mod test_utils {
 #[cfg(test)]
 mod tests {
     use crate::{
-        init_db,
+        compact_db, init_db,
         mdbx::DatabaseArguments,
         open_db, tables,
         version::{db_version_file_path, DatabaseVersionError},
     };
     use assert_matches::assert_matches;
     use reth_db_api::{
-        cursor::DbCursorRO, database::Database, models::ClientVersion, transaction::DbTx,
+        cursor::DbCursorRO,
+        database::Database,
+        models::ClientVersion,
+        transaction::{DbTx, DbTxMut},
     };
     use reth_libmdbx::MaxReadTransactionDuration;
+    use reth_primitives::B256;
     use std::time::Duration;
     use tempfile::tempdir;
 
@@ -317,4 +326,39 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn db_compaction() {
+        let path = tempdir().unwrap();
+        let args = DatabaseArguments::new(ClientVersion::default());
+
+        // Most of the written entries are deleted, leaving free pages behind
+        {
+            let db = init_db(&path, args.clone()).unwrap();
+            let tx = db.tx_mut().unwrap();
+            for number in 0..10_000 {
+                tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8))
+                    .unwrap();
+            }
+            tx.commit().unwrap();
+
+            let tx = db.tx_mut().unwrap();
+            for number in 1..10_000 {
+                tx.delete::<tables::CanonicalHeaders>(number, None).unwrap();
+            }
+            tx.commit().unwrap();
+            assert!(db.freelist().unwrap() > 0);
+        }
+
+        let (before, after) = compact_db(path.path(), args.clone()).unwrap();
+        assert!(after <= before);
+        assert!(!path.path().with_extension("compact").exists());
+
+        // The compacted database has no free pages, and keeps the remaining entries
+        let db = init_db(&path, args).unwrap();
+        assert_eq!(db.freelist().unwrap(), 0);
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.entries::<tables::CanonicalHeaders>().unwrap(), 1);
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(0).unwrap(), Some(B256::ZERO));
+    }
 }
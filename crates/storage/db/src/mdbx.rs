@@ -5,7 +5,13 @@ pub use reth_libmdbx::*;
 
 use crate::is_database_empty;
 use eyre::Context;
-use std::path::Path;
+use std::{fs::File, path::Path};
+
+/// The name of the MDBX data file in the database directory.
+const MDBX_DATA_FILE_NAME: &str = "mdbx.dat";
+
+/// The extension of the sibling directory a database is compacted into.
+const COMPACTION_DIRECTORY_EXTENSION: &str = "compact";
 
 /// Creates a new database at the specified path if it doesn't exist. Does NOT create tables. Check
 /// [`init_db`].
@@ -56,3 +62,37 @@ pub fn open_db(path: &Path, args: DatabaseArguments) -> eyre::Result<DatabaseEnv
     db.record_client_version(args.client_version().clone())?;
     Ok(db)
 }
+
+/// Compacts an existing database, and returns the size of its data file before and after.
+///
+/// The database is copied without its free pages into a sibling directory, and the copy then
+/// replaces the data file with an atomic rename, so an interrupted compaction leaves the database
+/// untouched. The database is opened in Read/Write mode to make sure no other process uses it, and
+/// the copy needs as much free disk space as the pages in use.
+pub fn compact_db(path: &Path, args: DatabaseArguments) -> eyre::Result<(u64, u64)> {
+    let data_file = path.join(MDBX_DATA_FILE_NAME);
+    let before = reth_fs_util::metadata(&data_file)?.len();
+
+    // Leftovers of an interrupted compaction are discarded.
+    let compaction_dir = path.with_extension(COMPACTION_DIRECTORY_EXTENSION);
+    if compaction_dir.exists() {
+        reth_fs_util::remove_dir_all(&compaction_dir)?;
+    }
+    reth_fs_util::create_dir_all(&compaction_dir)?;
+
+    let compacted_file = compaction_dir.join(MDBX_DATA_FILE_NAME);
+    {
+        let db = DatabaseEnv::open(path, DatabaseEnvKind::RW, args)
+            .with_context(|| format!("Could not open database at path: {}", path.display()))?;
+        db.copy(&compacted_file, true)
+            .with_context(|| format!("Could not copy database to {}", compacted_file.display()))?;
+    }
+
+    File::open(&compacted_file)?.sync_all()?;
+    reth_fs_util::rename(&compacted_file, &data_file)?;
+    File::open(path)?.sync_all()?;
+    reth_fs_util::remove_dir_all(&compaction_dir)?;
+
+    let after = reth_fs_util::metadata(&data_file)?.len();
+    Ok((before, after))
+}
@@ -0,0 +1,7 @@
+//! Database that keeps all tables in memory.
+//!
+//! [`MemoryDatabase`] implements the same traits as the MDBX database and can be used wherever a
+//! database is needed without touching the disk, e.g. in tests, stateless provers or ephemeral dev
+//! nodes.
+
+pub use crate::implementation::memory::*;
@@ -0,0 +1,590 @@
+//! Database migrations.
+//!
+//! A [`Migration`] upgrades the table layout of the database from the previous
+//! [database version](DB_VERSION) to its own. The [`Migrator`] runs the migrations a database is
+//! missing in order, and writes the new version to the database version file after each of them.
+//!
+//! Migrations are resumable: a migration can commit its progress through the
+//! [`MigrationContext`], which is saved as a [`MigrationCheckpoint`] in the [`tables::Migrations`]
+//! table in the same transaction, and is handed back to the migration if it's interrupted and run
+//! again.
+
+use crate::{
+    tables::{self, RawKey, RawTable, RawValue},
+    version::{get_db_version, write_db_version_file, DatabaseVersionError, DB_VERSION},
+};
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    models::MigrationCheckpoint,
+    table::{Decode, Decompress, Key, Table, Value},
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_etl::Collector;
+use reth_primitives::Bytes;
+use reth_tracing::tracing::info;
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// The oldest database version that the [registered migrations](migrations) can migrate from.
+///
+/// Databases older than this version need to be resynced.
+pub const MIN_MIGRATABLE_DB_VERSION: u64 = DB_VERSION;
+
+/// Returns the registered migrations, ordered by version.
+///
+/// The migrations must upgrade the database from [`MIN_MIGRATABLE_DB_VERSION`] to [`DB_VERSION`],
+/// one version at a time.
+pub fn migrations<DB: Database>() -> Vec<Box<dyn Migration<DB>>> {
+    Vec::new()
+}
+
+/// A migration of the database from the previous database version to [`Migration::version`].
+pub trait Migration<DB: Database>: Send + Sync {
+    /// The database version the migration upgrades to.
+    fn version(&self) -> u64;
+
+    /// A short description of what the migration changes.
+    fn description(&self) -> &'static str;
+
+    /// Runs the migration, resuming from `progress` if it's not empty.
+    ///
+    /// Changes are made through [`MigrationContext::tx`], and can be committed along with the
+    /// progress to resume from with [`MigrationContext::commit`]. The last transaction is committed
+    /// by the [`Migrator`] once the migration returns.
+    fn migrate(
+        &self,
+        ctx: &mut MigrationContext<'_, DB>,
+        progress: Bytes,
+    ) -> Result<(), MigrationError>;
+}
+
+/// Error while migrating the database.
+#[derive(thiserror::Error, Debug)]
+pub enum MigrationError {
+    /// Database error.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    /// Database version error.
+    #[error(transparent)]
+    Version(#[from] DatabaseVersionError),
+    /// IO error, either from the ETL collector or while writing the database version file.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// No migration upgrades the database to the given version.
+    #[error("no migration to database version v{version}, the database needs to be resynced")]
+    MissingMigration {
+        /// The database version without a migration.
+        version: u64,
+    },
+}
+
+/// The state a [`Migration`] runs with.
+pub struct MigrationContext<'a, DB: Database> {
+    db: &'a DB,
+    /// Always `Some`, except while committing.
+    tx: Option<DB::TXMut>,
+    version: u64,
+    etl_file_size: usize,
+    etl_dir: Option<PathBuf>,
+    commit_threshold: u64,
+}
+
+impl<'a, DB: Database> MigrationContext<'a, DB> {
+    fn new(migrator: &Migrator<'a, DB>, version: u64) -> Result<Self, DatabaseError> {
+        Ok(Self {
+            db: migrator.db,
+            tx: Some(migrator.db.tx_mut()?),
+            version,
+            etl_file_size: migrator.etl_file_size,
+            etl_dir: migrator.etl_dir.clone(),
+            commit_threshold: migrator.commit_threshold,
+        })
+    }
+
+    /// Returns the current read-write transaction.
+    pub fn tx(&self) -> &DB::TXMut {
+        self.tx.as_ref().expect("transaction is open")
+    }
+
+    /// Returns the number of entries a migration should change before committing its progress.
+    pub const fn commit_threshold(&self) -> u64 {
+        self.commit_threshold
+    }
+
+    /// Returns a new ETL collector configured by the [`Migrator`].
+    pub fn collector<K: Key, V: Value>(&self) -> Collector<K, V> {
+        Collector::new(self.etl_file_size, self.etl_dir.clone())
+    }
+
+    /// Saves the progress to resume the migration from, commits the current transaction and opens
+    /// a new one.
+    pub fn commit(&mut self, progress: Bytes) -> Result<(), DatabaseError> {
+        self.commit_checkpoint(MigrationCheckpoint::new(progress))?;
+        self.tx = Some(self.db.tx_mut()?);
+        Ok(())
+    }
+
+    /// Marks the migration as finished and commits the current transaction.
+    fn finish(mut self) -> Result<(), DatabaseError> {
+        self.commit_checkpoint(MigrationCheckpoint::finished())
+    }
+
+    fn commit_checkpoint(&mut self, checkpoint: MigrationCheckpoint) -> Result<(), DatabaseError> {
+        let tx = self.tx.take().expect("transaction is open");
+        tx.put::<tables::Migrations>(self.version, checkpoint)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl<DB: Database> fmt::Debug for MigrationContext<'_, DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MigrationContext")
+            .field("version", &self.version)
+            .field("etl_file_size", &self.etl_file_size)
+            .field("etl_dir", &self.etl_dir)
+            .field("commit_threshold", &self.commit_threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs the pending [`Migration`]s of a database.
+pub struct Migrator<'a, DB: Database> {
+    db: &'a DB,
+    path: PathBuf,
+    migrations: Vec<Box<dyn Migration<DB>>>,
+    etl_file_size: usize,
+    etl_dir: Option<PathBuf>,
+    commit_threshold: u64,
+}
+
+impl<'a, DB: Database> Migrator<'a, DB> {
+    /// Creates a new migrator of the database at `path` with the [registered
+    /// migrations](migrations).
+    pub fn new(db: &'a DB, path: impl AsRef<Path>) -> Self {
+        Self {
+            db,
+            path: path.as_ref().to_path_buf(),
+            migrations: migrations(),
+            etl_file_size: 500 * 1024 * 1024,
+            etl_dir: None,
+            commit_threshold: 100_000,
+        }
+    }
+
+    /// Sets the migrations to run, ordered by version.
+    pub fn with_migrations(mut self, migrations: Vec<Box<dyn Migration<DB>>>) -> Self {
+        self.migrations = migrations;
+        self
+    }
+
+    /// Sets the maximum size of the ETL collector buffers and their parent directory.
+    pub fn with_etl(mut self, file_size: usize, dir: Option<PathBuf>) -> Self {
+        self.etl_file_size = file_size;
+        self.etl_dir = dir;
+        self
+    }
+
+    /// Sets the number of entries a migration should change before committing its progress.
+    pub const fn with_commit_threshold(mut self, commit_threshold: u64) -> Self {
+        self.commit_threshold = commit_threshold;
+        self
+    }
+
+    /// Returns the migrations the database is missing, ordered by version.
+    pub fn pending(&self) -> Result<Vec<&dyn Migration<DB>>, MigrationError> {
+        let mut version = get_db_version(&self.path)?;
+        let mut pending = Vec::new();
+        for migration in &self.migrations {
+            if migration.version() <= version {
+                continue
+            }
+            if migration.version() != version + 1 {
+                return Err(MigrationError::MissingMigration { version: version + 1 })
+            }
+            pending.push(migration.as_ref());
+            version += 1;
+        }
+        Ok(pending)
+    }
+
+    /// Runs the pending migrations, and returns the number of migrations that were run.
+    ///
+    /// A migration that was interrupted resumes from its last committed progress.
+    pub fn run(&self) -> Result<usize, MigrationError> {
+        let pending = self.pending()?;
+        for migration in &pending {
+            let version = migration.version();
+            let checkpoint =
+                self.db.view(|tx| tx.get::<tables::Migrations>(version))??.unwrap_or_default();
+
+            if checkpoint.finished {
+                info!(target: "reth::db", version, "Migration already finished");
+            } else {
+                info!(
+                    target: "reth::db",
+                    version,
+                    description = migration.description(),
+                    resumed = !checkpoint.progress.is_empty(),
+                    "Running migration"
+                );
+                let mut ctx = MigrationContext::new(self, version)?;
+                migration.migrate(&mut ctx, checkpoint.progress)?;
+                ctx.finish()?;
+            }
+
+            write_db_version_file(&self.path, version)?;
+            info!(target: "reth::db", version, "Migrated database");
+        }
+
+        Ok(pending.len())
+    }
+}
+
+impl<DB: Database> fmt::Debug for Migrator<'_, DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Migrator")
+            .field("path", &self.path)
+            .field(
+                "migrations",
+                &self.migrations.iter().map(|migration| migration.version()).collect::<Vec<_>>(),
+            )
+            .field("etl_file_size", &self.etl_file_size)
+            .field("etl_dir", &self.etl_dir)
+            .field("commit_threshold", &self.commit_threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Rewrites the entries of table `T` into table `U`, and clears `T` once done.
+///
+/// `f` maps each entry of `T` to an entry of `U`, or skips it. The entries are sorted by the keys
+/// of `U` with an ETL [`Collector`] and inserted in order, committing the encoded key of the last
+/// inserted entry as progress every [`MigrationContext::commit_threshold`] entries. `T` is only
+/// cleared in the last transaction, so an interrupted rewrite collects the entries again and
+/// inserts the ones after that key.
+///
+/// `T` and `U` must be different tables, use [`rewrite_table_in_place`] to change the value format
+/// of a table. `U` is cleared before the rewrite starts, and may be a
+/// [`DupSort`](reth_db_api::table::DupSort) table, in which case progress is only committed once
+/// all values of a key are inserted.
+pub fn rewrite_table<DB, T, U>(
+    ctx: &mut MigrationContext<'_, DB>,
+    progress: Bytes,
+    mut f: impl FnMut(T::Key, T::Value) -> Result<Option<(U::Key, U::Value)>, MigrationError>,
+) -> Result<(), MigrationError>
+where
+    DB: Database,
+    T: Table,
+    U: Table,
+{
+    debug_assert_ne!(T::NAME, U::NAME, "rewriting a table into itself");
+
+    let mut collector = ctx.collector::<U::Key, U::Value>();
+    for entry in ctx.tx().cursor_read::<T>()?.walk(None)? {
+        let (key, value) = entry?;
+        if let Some((key, value)) = f(key, value)? {
+            collector.insert(key, value)?;
+        }
+    }
+
+    let resume_after = (!progress.is_empty()).then(|| progress.to_vec());
+    if resume_after.is_none() {
+        ctx.tx().clear::<U>()?;
+    }
+
+    let mut last_key: Option<Vec<u8>> = None;
+    let mut uncommitted = 0;
+    for entry in collector.iter()? {
+        let (key, value) = entry?;
+        if resume_after.as_ref().is_some_and(|resume_after| key <= *resume_after) {
+            continue
+        }
+
+        if uncommitted >= ctx.commit_threshold() && last_key.as_ref() != Some(&key) {
+            ctx.commit(last_key.clone().unwrap_or_default().into())?;
+            uncommitted = 0;
+        }
+
+        ctx.tx().put::<U>(U::Key::decode(&key)?, U::Value::decompress(&value)?)?;
+        last_key = Some(key);
+        uncommitted += 1;
+    }
+
+    ctx.tx().clear::<T>()?;
+
+    Ok(())
+}
+
+/// Rewrites the values of table `T` in place, for when the format of its values changes.
+///
+/// The entries are read as [`RawTable<T>`], since their values are still in the old format, and
+/// `decode` maps the key and the raw value of each entry to its value in the new format, or `None`
+/// to delete the entry. The keys are unchanged. Every [`MigrationContext::commit_threshold`]
+/// entries, the raw key of the last rewritten entry is committed as progress, and an interrupted
+/// rewrite continues after it, so `decode` never sees a value it already rewrote.
+///
+/// `T` must not be a [`DupSort`](reth_db_api::table::DupSort) table, since putting a value of a
+/// key adds a duplicate instead of replacing it.
+pub fn rewrite_table_in_place<DB, T>(
+    ctx: &mut MigrationContext<'_, DB>,
+    progress: Bytes,
+    mut decode: impl FnMut(T::Key, &[u8]) -> Result<Option<T::Value>, MigrationError>,
+) -> Result<(), MigrationError>
+where
+    DB: Database,
+    T: Table,
+{
+    let mut resume_after =
+        (!progress.is_empty()).then(|| RawKey::<T::Key>::from_vec(progress.to_vec()));
+
+    loop {
+        // the batch is read before it's rewritten, so the cursor doesn't see the new values
+        let batch = ctx
+            .tx()
+            .cursor_read::<RawTable<T>>()?
+            .walk(resume_after.clone())?
+            .skip_while(|entry| {
+                entry.as_ref().is_ok_and(|(key, _)| Some(key) == resume_after.as_ref())
+            })
+            .take(ctx.commit_threshold() as usize)
+            .collect::<Result<Vec<_>, _>>()?;
+        let Some((last_key, _)) = batch.last() else { break };
+        let last_key = last_key.clone();
+        let is_last_batch = (batch.len() as u64) < ctx.commit_threshold();
+
+        for (key, value) in batch {
+            match decode(key.key()?, value.raw_value())? {
+                Some(value) => ctx.tx().put::<RawTable<T>>(key, RawValue::new(value))?,
+                None => {
+                    ctx.tx().delete::<RawTable<T>>(key, None)?;
+                }
+            }
+        }
+
+        if is_last_batch {
+            break
+        }
+        ctx.commit(last_key.raw_key().clone().into())?;
+        resume_after = Some(last_key);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::create_test_rw_db, DatabaseEnv};
+    use assert_matches::assert_matches;
+    use reth_db_api::table::Encode;
+    use reth_primitives::{keccak256, BlockNumber, B256};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Moves the canonical hashes into the hash to number index, failing once after rewriting them
+    /// if `interrupt` is set.
+    #[derive(Default)]
+    struct IndexCanonicalHashes {
+        version: u64,
+        interrupt: AtomicBool,
+    }
+
+    impl<DB: Database> Migration<DB> for IndexCanonicalHashes {
+        fn version(&self) -> u64 {
+            self.version
+        }
+
+        fn description(&self) -> &'static str {
+            "Moves the canonical hashes into the hash to number index"
+        }
+
+        fn migrate(
+            &self,
+            ctx: &mut MigrationContext<'_, DB>,
+            progress: Bytes,
+        ) -> Result<(), MigrationError> {
+            rewrite_table::<_, tables::CanonicalHeaders, tables::HeaderNumbers>(
+                ctx,
+                progress,
+                |number, hash| Ok(Some((hash, number))),
+            )?;
+            if self.interrupt.swap(false, Ordering::Relaxed) {
+                return Err(DatabaseError::Other("interrupted".to_string()).into())
+            }
+            Ok(())
+        }
+    }
+
+    /// Reverses the bytes of the canonical hashes, failing once after the first batch if
+    /// `interrupt` is set. Zero hashes are dropped.
+    #[derive(Default)]
+    struct ReverseCanonicalHashes {
+        version: u64,
+        interrupt: AtomicBool,
+    }
+
+    impl<DB: Database> Migration<DB> for ReverseCanonicalHashes {
+        fn version(&self) -> u64 {
+            self.version
+        }
+
+        fn description(&self) -> &'static str {
+            "Reverses the bytes of the canonical hashes"
+        }
+
+        fn migrate(
+            &self,
+            ctx: &mut MigrationContext<'_, DB>,
+            progress: Bytes,
+        ) -> Result<(), MigrationError> {
+            let mut decoded = 0;
+            rewrite_table_in_place::<_, tables::CanonicalHeaders>(ctx, progress, |_, raw| {
+                if decoded == 3 && self.interrupt.swap(false, Ordering::Relaxed) {
+                    return Err(DatabaseError::Other("interrupted".to_string()).into())
+                }
+                decoded += 1;
+                let mut hash = B256::from_slice(raw);
+                hash.reverse();
+                Ok((hash != B256::ZERO).then_some(hash))
+            })
+        }
+    }
+
+    fn hash(number: BlockNumber) -> B256 {
+        keccak256(number.to_be_bytes())
+    }
+
+    #[test]
+    fn registered_migrations() {
+        let versions = migrations::<DatabaseEnv>()
+            .iter()
+            .map(|migration| migration.version())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, (MIN_MIGRATABLE_DB_VERSION + 1..=DB_VERSION).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn missing_migration() {
+        let db = create_test_rw_db();
+        let migrator = Migrator::new(db.as_ref(), db.path()).with_migrations(vec![Box::new(
+            IndexCanonicalHashes { version: DB_VERSION + 2, ..Default::default() },
+        )]);
+
+        assert_matches!(
+            migrator.run(),
+            Err(MigrationError::MissingMigration { version }) if version == DB_VERSION + 1
+        );
+        assert_eq!(get_db_version(db.path()).unwrap(), DB_VERSION);
+    }
+
+    #[test]
+    fn resume_interrupted_migration() {
+        let db = create_test_rw_db();
+        let version = DB_VERSION + 1;
+        let tx = db.tx_mut().unwrap();
+        for number in 0..10 {
+            tx.put::<tables::CanonicalHeaders>(number, hash(number)).unwrap();
+        }
+        // stale entry, cleared when the migration starts
+        tx.put::<tables::HeaderNumbers>(B256::ZERO, 100).unwrap();
+        tx.commit().unwrap();
+
+        let migrator = Migrator::new(db.as_ref(), db.path())
+            .with_migrations(vec![Box::new(IndexCanonicalHashes {
+                version,
+                interrupt: AtomicBool::new(true),
+            })])
+            .with_commit_threshold(3);
+
+        // progress is committed after every 3 entries, the last entry is lost
+        let mut sorted = (0..10).map(|number| (hash(number), number)).collect::<Vec<_>>();
+        sorted.sort();
+        assert_matches!(migrator.run(), Err(MigrationError::Database(DatabaseError::Other(_))));
+        assert_eq!(get_db_version(db.path()).unwrap(), DB_VERSION);
+        let tx = db.tx().unwrap();
+        assert_eq!(
+            tx.get::<tables::Migrations>(version).unwrap(),
+            Some(MigrationCheckpoint::new(sorted[8].0.encode().to_vec().into()))
+        );
+        assert_eq!(tx.entries::<tables::HeaderNumbers>().unwrap(), 9);
+        assert_eq!(tx.entries::<tables::CanonicalHeaders>().unwrap(), 10);
+        drop(tx);
+
+        // the migration resumes after the last committed entry
+        assert_matches!(migrator.run(), Ok(1));
+        assert_eq!(get_db_version(db.path()).unwrap(), version);
+        let tx = db.tx().unwrap();
+        assert_eq!(
+            tx.get::<tables::Migrations>(version).unwrap(),
+            Some(MigrationCheckpoint::finished())
+        );
+        assert_eq!(
+            tx.cursor_read::<tables::HeaderNumbers>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            sorted
+        );
+        assert_eq!(tx.entries::<tables::CanonicalHeaders>().unwrap(), 0);
+        drop(tx);
+
+        // nothing left to migrate
+        assert_matches!(migrator.run(), Ok(0));
+    }
+
+    #[test]
+    fn resume_interrupted_in_place_rewrite() {
+        let db = create_test_rw_db();
+        let version = DB_VERSION + 1;
+        let old_hash = |number: BlockNumber| {
+            let mut hash = if number == 5 { B256::ZERO } else { hash(number) };
+            hash.reverse();
+            hash
+        };
+        let tx = db.tx_mut().unwrap();
+        for number in 0..10 {
+            tx.put::<tables::CanonicalHeaders>(number, old_hash(number)).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let migrator = Migrator::new(db.as_ref(), db.path())
+            .with_migrations(vec![Box::new(ReverseCanonicalHashes {
+                version,
+                interrupt: AtomicBool::new(true),
+            })])
+            .with_commit_threshold(3);
+
+        // the first batch is committed, the second one is lost
+        assert_matches!(migrator.run(), Err(MigrationError::Database(DatabaseError::Other(_))));
+        let tx = db.tx().unwrap();
+        assert_eq!(
+            tx.get::<tables::Migrations>(version).unwrap(),
+            Some(MigrationCheckpoint::new(2u64.encode().to_vec().into()))
+        );
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(2).unwrap(), Some(hash(2)));
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(3).unwrap(), Some(old_hash(3)));
+        drop(tx);
+
+        // the rewritten values aren't decoded again
+        assert_matches!(migrator.run(), Ok(1));
+        let tx = db.tx().unwrap();
+        assert_eq!(
+            tx.cursor_read::<tables::CanonicalHeaders>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            (0..10)
+                .filter(|number| *number != 5)
+                .map(|number| (number, hash(number)))
+                .collect::<Vec<_>>()
+        );
+    }
+}
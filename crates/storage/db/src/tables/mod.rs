@@ -24,9 +24,10 @@ pub(crate) mod utils;
 
 use reth_db_api::{
     models::{
-        accounts::{AccountBeforeTx, BlockNumberAddress},
+        accounts::{AccountBeforeTx, BlockNumberAddress, ContractCreation},
         blocks::{HeaderHash, StoredBlockOmmers},
         client_version::ClientVersion,
+        migration::MigrationCheckpoint,
         storage_sharded_key::StorageShardedKey,
         CompactU256, ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
     },
@@ -411,6 +412,36 @@ tables! {
 
     /// Stores generic chain state info, like the last finalized block.
     table ChainState<Key = ChainStateKey, Value = BlockNumber>;
+
+    /// Stores the numbers of all transactions an address appeared in, either as the sender or
+    /// recipient of the transaction or as the caller or callee of an internal call.
+    ///
+    /// Sharded the same way as [`AccountsHistory`], with transaction numbers instead of block
+    /// numbers. Only written if the `IndexAddressAppearances` stage is enabled.
+    table AddressAppearances<Key = ShardedKey<Address>, Value = BlockNumberList>;
+
+    /// Stores the addresses that appeared in the transactions of each block. Used to unwind
+    /// [`AddressAppearances`].
+    table BlockAppearances<Key = BlockNumber, Value = Address, SubKey = Address>;
+
+    /// Stores the transaction that created a contract and the creator, indexed by the address of
+    /// the contract. Only written if the `IndexAddressAppearances` stage is enabled.
+    table ContractCreators<Key = Address, Value = ContractCreation>;
+
+    /// Stores pointers to the blocks containing logs emitted by an address.
+    ///
+    /// Sharded the same way as [`AccountsHistory`]. Only written if the `IndexLogs` stage is
+    /// enabled.
+    table LogAddressHistory<Key = ShardedKey<Address>, Value = BlockNumberList>;
+
+    /// Stores pointers to the blocks containing logs with a topic, at any position.
+    ///
+    /// Sharded the same way as [`AccountsHistory`]. Only written if the `IndexLogs` stage is
+    /// enabled.
+    table LogTopicHistory<Key = ShardedKey<B256>, Value = BlockNumberList>;
+
+    /// Stores the progress of each database migration, by the database version it migrates to.
+    table Migrations<Key = u64, Value = MigrationCheckpoint>;
 }
 
 /// Keys for the `ChainState` table.
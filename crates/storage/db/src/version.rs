@@ -1,5 +1,6 @@
 //! Database version utils.
 
+use crate::migration::MIN_MIGRATABLE_DB_VERSION;
 use std::{
     fs, io,
     path::{Path, PathBuf},
@@ -31,6 +32,15 @@ pub enum DatabaseVersionError {
         /// The detected version in the database.
         version: u64,
     },
+    /// The database needs to be migrated to the latest database version.
+    #[error(
+        "your database version (v{version}) needs to be migrated to the latest database version \
+         (v{DB_VERSION}), run `reth db migrate`"
+    )]
+    MigrationRequired {
+        /// The detected version in the database.
+        version: u64,
+    },
     /// IO error occurred while reading the database version file.
     #[error("IO error occurred while reading {path}: {err}")]
     IORead {
@@ -44,9 +54,13 @@ pub enum DatabaseVersionError {
 /// Checks the database version file with [`DB_VERSION_FILE_NAME`] name.
 ///
 /// Returns [Ok] if file is found and has one line which equals to [`DB_VERSION`].
-/// Otherwise, returns different [`DatabaseVersionError`] error variants.
+/// Otherwise, returns different [`DatabaseVersionError`] error variants, with
+/// [`DatabaseVersionError::MigrationRequired`] if the database can be migrated to [`DB_VERSION`].
 pub fn check_db_version_file<P: AsRef<Path>>(db_path: P) -> Result<(), DatabaseVersionError> {
     let version = get_db_version(db_path)?;
+    if (MIN_MIGRATABLE_DB_VERSION..DB_VERSION).contains(&version) {
+        return Err(DatabaseVersionError::MigrationRequired { version })
+    }
     if version != DB_VERSION {
         return Err(DatabaseVersionError::VersionMismatch { version })
     }
@@ -75,7 +89,15 @@ pub fn get_db_version<P: AsRef<Path>>(db_path: P) -> Result<u64, DatabaseVersion
 /// This function will create a file if it does not exist,
 /// and will entirely replace its contents if it does.
 pub fn create_db_version_file<P: AsRef<Path>>(db_path: P) -> io::Result<()> {
-    fs::write(db_version_file_path(db_path), DB_VERSION.to_string())
+    write_db_version_file(db_path, DB_VERSION)
+}
+
+/// Writes the given version to the database version file with [`DB_VERSION_FILE_NAME`] name.
+///
+/// This function will create a file if it does not exist,
+/// and will entirely replace its contents if it does.
+pub fn write_db_version_file<P: AsRef<Path>>(db_path: P, version: u64) -> io::Result<()> {
+    fs::write(db_version_file_path(db_path), version.to_string())
 }
 
 /// Returns a database version file path.
@@ -223,6 +223,39 @@ impl Environment {
 
         Ok(freelist)
     }
+
+    /// Copies the environment into a new data file at `dest`, which must not exist yet.
+    ///
+    /// If `compact` is set, free pages are omitted from the copy and all pages are renumbered
+    /// sequentially, so the data file of the copy is only as large as the pages in use. The copy
+    /// is made within a read transaction, so the environment can be used while it's running.
+    ///
+    /// The path may not contain the null character.
+    pub fn copy(&self, dest: &Path, compact: bool) -> Result<()> {
+        let dest = CString::new(path_to_bytes(dest)).map_err(|_| Error::Invalid)?;
+        let flags = if compact {
+            ffi::MDBX_CP_COMPACT | ffi::MDBX_CP_FORCE_DYNAMIC_SIZE
+        } else {
+            ffi::MDBX_CP_DEFAULTS
+        };
+        mdbx_result(unsafe { ffi::mdbx_env_copy(self.env_ptr(), dest.as_ptr(), flags) })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_ref().as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(windows)]
+fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+    // On Windows, could use std::os::windows::ffi::OsStrExt to encode_wide(),
+    // but we end up with a Vec<u16> instead of a Vec<u8>, so that doesn't
+    // really help.
+    path.as_ref().to_string_lossy().to_string().into_bytes()
 }
 
 /// Container type for Environment internals.
@@ -361,6 +394,11 @@ impl GeometryInfo {
     pub const fn min(&self) -> u64 {
         self.0.lower
     }
+
+    /// Current size of the data file.
+    pub const fn current(&self) -> u64 {
+        self.0.current
+    }
 }
 
 /// Environment information.
@@ -667,20 +705,6 @@ impl EnvironmentBuilder {
                     ))?;
                 }
 
-                #[cfg(unix)]
-                fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
-                    use std::os::unix::ffi::OsStrExt;
-                    path.as_ref().as_os_str().as_bytes().to_vec()
-                }
-
-                #[cfg(windows)]
-                fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
-                    // On Windows, could use std::os::windows::ffi::OsStrExt to encode_wide(),
-                    // but we end up with a Vec<u16> instead of a Vec<u8>, so that doesn't
-                    // really help.
-                    path.as_ref().to_string_lossy().to_string().into_bytes()
-                }
-
                 let path = match CString::new(path_to_bytes(path)) {
                     Ok(path) => path,
                     Err(_) => return Err(Error::Invalid),
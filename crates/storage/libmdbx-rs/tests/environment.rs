@@ -154,3 +154,45 @@ fn test_freelist() {
     freelist = env.freelist().unwrap();
     assert!(freelist > 0);
 }
+
+#[test]
+fn test_copy_compact() {
+    let dir = tempdir().unwrap();
+    let env = Environment::builder().open(dir.path()).unwrap();
+
+    // Write a few values, and delete most of them.
+    for i in 0..1024 {
+        let mut value = [0u8; 8];
+        LittleEndian::write_u64(&mut value, i);
+        let tx = env.begin_rw_txn().expect("begin_rw_txn");
+        tx.put(tx.open_db(None).unwrap().dbi(), value, [0u8; 512], WriteFlags::default())
+            .expect("tx.put");
+        tx.commit().expect("tx.commit");
+    }
+    let tx = env.begin_rw_txn().expect("begin_rw_txn");
+    let dbi = tx.open_db(None).unwrap().dbi();
+    for i in 1..1024 {
+        let mut value = [0u8; 8];
+        LittleEndian::write_u64(&mut value, i);
+        tx.del(dbi, value, None).expect("tx.del");
+    }
+    tx.commit().expect("tx.commit");
+    assert!(env.freelist().unwrap() > 0);
+
+    // The copy must not exist yet.
+    let copy_dir = tempdir().unwrap();
+    let copy_path = copy_dir.path().join("mdbx.dat");
+    env.copy(&copy_path, true).unwrap();
+    assert!(env.copy(&copy_path, true).is_err());
+
+    // The compacted copy has no free pages, and keeps the data.
+    let copy = Environment::builder().open(copy_dir.path()).unwrap();
+    assert_eq!(copy.freelist().unwrap(), 0);
+    assert!(copy.info().unwrap().last_pgno() < env.info().unwrap().last_pgno());
+    assert_eq!(copy.stat().unwrap().entries(), 1);
+    let tx = copy.begin_ro_txn().unwrap();
+    assert_eq!(
+        tx.get::<[u8; 512]>(tx.open_db(None).unwrap().dbi(), &[0u8; 8]).unwrap(),
+        Some([0u8; 512])
+    );
+}
@@ -12,7 +12,7 @@ pub use zstd::{bulk::Decompressor, dict::DecoderDictionary};
 
 type RawDictionary = Vec<u8>;
 
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ZstdState {
     #[default]
     PendingDictionary,
@@ -20,7 +20,7 @@ pub enum ZstdState {
 }
 
 #[cfg_attr(test, derive(PartialEq))]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Zstd compression structure. Supports a compression dictionary per column.
 pub struct Zstd {
     /// State. Should be ready before compressing.
@@ -56,8 +56,16 @@ impl Zstd {
         self
     }
 
+    /// Returns the size of the dictionary of each column, if using dictionaries.
+    pub fn dictionary_sizes(&self) -> Vec<usize> {
+        self.dictionaries
+            .as_ref()
+            .map(|dictionaries| dictionaries.iter().map(|dict| dict.raw().len()).collect())
+            .unwrap_or_default()
+    }
+
     /// Creates a list of [`Decompressor`] if using dictionaries.
-    pub fn decompressors(&self) -> Result<Vec<Decompressor<'_>>, NippyJarError> {
+    pub fn decompressors(&self) -> Result<Vec<Decompressor<'static>>, NippyJarError> {
         if let Some(dictionaries) = &self.dictionaries {
             debug_assert!(dictionaries.len() == self.columns);
             return dictionaries.decompressors()
@@ -67,7 +75,7 @@ impl Zstd {
     }
 
     /// If using dictionaries, creates a list of [`Compressor`].
-    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'_>>>, NippyJarError> {
+    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'static>>>, NippyJarError> {
         match self.state {
             ZstdState::PendingDictionary => Err(NippyJarError::CompressorNotReady),
             ZstdState::Ready => {
@@ -280,51 +288,45 @@ impl<'a> ZstdDictionaries<'a> {
 
     /// Loads a list [`RawDictionary`] into a list of [`ZstdDictionary::Loaded`].
     pub(crate) fn load(raw: Vec<RawDictionary>) -> Self {
-        Self(
-            raw.into_iter()
-                .map(|dict| ZstdDictionary::Loaded(DecoderDictionary::copy(&dict)))
-                .collect(),
-        )
+        Self(raw.into_iter().map(ZstdDictionary::load).collect())
     }
 
-    /// Creates a list of decompressors from a list of [`ZstdDictionary::Loaded`].
-    pub(crate) fn decompressors(&self) -> Result<Vec<Decompressor<'_>>, NippyJarError> {
+    /// Creates a list of decompressors from the raw dictionaries.
+    pub(crate) fn decompressors(&self) -> Result<Vec<Decompressor<'static>>, NippyJarError> {
         Ok(self
             .iter()
-            .flat_map(|dict| {
-                dict.loaded()
-                    .ok_or(NippyJarError::DictionaryNotLoaded)
-                    .map(Decompressor::with_prepared_dictionary)
-            })
+            .map(|dict| Decompressor::with_dictionary(dict.raw()))
             .collect::<Result<Vec<_>, _>>()?)
     }
 
-    /// Creates a list of compressors from a list of [`ZstdDictionary::Raw`].
-    pub(crate) fn compressors(&self) -> Result<Vec<Compressor<'_>>, NippyJarError> {
+    /// Creates a list of compressors from the raw dictionaries.
+    pub(crate) fn compressors(&self) -> Result<Vec<Compressor<'static>>, NippyJarError> {
         Ok(self
             .iter()
-            .flat_map(|dict| {
-                dict.raw()
-                    .ok_or(NippyJarError::CompressorNotAllowed)
-                    .map(|dict| Compressor::with_dictionary(0, dict))
-            })
+            .map(|dict| Compressor::with_dictionary(0, dict.raw()))
             .collect::<Result<Vec<_>, _>>()?)
     }
 }
 
-/// A Zstd dictionary. It's created and serialized with [`ZstdDictionary::Raw`], and deserialized as
-/// [`ZstdDictionary::Loaded`].
+/// A Zstd dictionary. It's created as [`ZstdDictionary::Raw`], and deserialized as
+/// [`ZstdDictionary::Loaded`], which keeps the raw dictionary so values can still be compressed
+/// when appending to a loaded jar.
 pub(crate) enum ZstdDictionary<'a> {
     Raw(RawDictionary),
-    Loaded(DecoderDictionary<'a>),
+    Loaded(RawDictionary, DecoderDictionary<'a>),
 }
 
 impl<'a> ZstdDictionary<'a> {
-    /// Returns a reference to the expected `RawDictionary`
-    pub(crate) const fn raw(&self) -> Option<&RawDictionary> {
+    /// Prepares the `DecoderDictionary` of a `RawDictionary`.
+    fn load(raw: RawDictionary) -> Self {
+        let decoder = DecoderDictionary::copy(&raw);
+        Self::Loaded(raw, decoder)
+    }
+
+    /// Returns a reference to the `RawDictionary`
+    pub(crate) const fn raw(&self) -> &RawDictionary {
         match self {
-            ZstdDictionary::Raw(dict) => Some(dict),
-            ZstdDictionary::Loaded(_) => None,
+            ZstdDictionary::Raw(dict) | ZstdDictionary::Loaded(dict, _) => dict,
         }
     }
 
@@ -332,7 +334,7 @@ impl<'a> ZstdDictionary<'a> {
     pub(crate) const fn loaded(&self) -> Option<&DecoderDictionary<'_>> {
         match self {
             ZstdDictionary::Raw(_) => None,
-            ZstdDictionary::Loaded(dict) => Some(dict),
+            ZstdDictionary::Loaded(_, dict) => Some(dict),
         }
     }
 }
@@ -342,8 +344,7 @@ impl<'de, 'a> Deserialize<'de> for ZstdDictionary<'a> {
     where
         D: Deserializer<'de>,
     {
-        let dict = RawDictionary::deserialize(deserializer)?;
-        Ok(Self::Loaded(DecoderDictionary::copy(&dict)))
+        Ok(Self::load(RawDictionary::deserialize(deserializer)?))
     }
 }
 
@@ -352,19 +353,13 @@ impl<'a> Serialize for ZstdDictionary<'a> {
     where
         S: Serializer,
     {
-        match self {
-            ZstdDictionary::Raw(r) => r.serialize(serializer),
-            ZstdDictionary::Loaded(_) => unreachable!(),
-        }
+        self.raw().serialize(serializer)
     }
 }
 
 #[cfg(test)]
 impl<'a> PartialEq for ZstdDictionary<'a> {
     fn eq(&self, other: &Self) -> bool {
-        if let (Self::Raw(a), Self::Raw(b)) = (self, &other) {
-            return a == b
-        }
-        unimplemented!("`DecoderDictionary` can't be compared. So comparison should be done after decompressing a value.");
+        self.raw() == other.raw()
     }
 }
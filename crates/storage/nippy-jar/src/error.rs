@@ -64,4 +64,34 @@ pub enum NippyJarError {
     FrozenJar,
     #[error("File is in an inconsistent state.")]
     InconsistentState,
+    #[error("jars with filters or a perfect hashing function can't be recompressed")]
+    RecompressionWithFilters,
+    #[error("expected {expected} offsets, got {got}")]
+    OffsetsCountMismatch {
+        /// The number of offsets expected from the rows and columns of the jar.
+        expected: usize,
+        /// The number of offsets in the offsets file.
+        got: usize,
+    },
+    #[error("offset {index} is out of order")]
+    OffsetOutOfOrder {
+        /// The index of the offset.
+        index: usize,
+    },
+    #[error("last offset is {expected}, but the data file size is {got}")]
+    DataSizeMismatch {
+        /// The last offset.
+        expected: u64,
+        /// The size of the data file.
+        got: u64,
+    },
+    #[error("checksum mismatch on column {column}: expected {expected}, got {got}")]
+    ChecksumMismatch {
+        /// The column whose values don't match the checksum.
+        column: usize,
+        /// The recorded checksum.
+        expected: u64,
+        /// The checksum of the stored values.
+        got: u64,
+    },
 }
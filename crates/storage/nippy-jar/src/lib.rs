@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     error::Error as StdError,
     fs::{File, OpenOptions},
+    io::Read,
     ops::Range,
     path::{Path, PathBuf},
 };
@@ -43,11 +44,12 @@ pub use cursor::NippyJarCursor;
 mod writer;
 pub use writer::{ConsistencyFailStrategy, NippyJarWriter};
 
-const NIPPY_JAR_VERSION: usize = 1;
+const NIPPY_JAR_VERSION: usize = 2;
 
 const INDEX_FILE_EXTENSION: &str = "idx";
 const OFFSETS_FILE_EXTENSION: &str = "off";
 const CONFIG_FILE_EXTENSION: &str = "conf";
+const STAGED_FILE_EXTENSION: &str = "staged";
 
 /// A [`RefRow`] is a list of column value slices pointing to either an internal buffer or a
 /// memory-mapped file.
@@ -118,11 +120,45 @@ pub struct NippyJar<H = ()> {
     /// Maximum uncompressed row size of the set. This will enable decompression without any
     /// resizing of the output buffer.
     max_row_size: usize,
+    /// Checksum of each column, as the wrapping sum of the checksums of its stored values.
+    ///
+    /// `None` for jars created before checksums were introduced.
+    checksums: Option<Vec<u64>>,
     /// Data path for file. Supporting files will have a format `{path}.{extension}`.
     #[serde(skip)]
     path: PathBuf,
 }
 
+/// Configuration of a [`NippyJar`] of version 1, which has no column checksums.
+#[derive(Deserialize)]
+struct NippyJarV1<H> {
+    version: usize,
+    user_header: H,
+    columns: usize,
+    rows: usize,
+    compressor: Option<Compressors>,
+    max_row_size: usize,
+}
+
+impl<H> From<NippyJarV1<H>> for NippyJar<H> {
+    fn from(jar: NippyJarV1<H>) -> Self {
+        debug_assert_eq!(jar.version, 1);
+        Self {
+            version: NIPPY_JAR_VERSION,
+            user_header: jar.user_header,
+            columns: jar.columns,
+            rows: jar.rows,
+            compressor: jar.compressor,
+            filter: None,
+            phf: None,
+            offsets_index: PrefixSummedEliasFano::default(),
+            max_row_size: jar.max_row_size,
+            checksums: None,
+            path: PathBuf::new(),
+        }
+    }
+}
+
 impl<H: NippyJarHeader> std::fmt::Debug for NippyJar<H> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("NippyJar")
@@ -137,6 +173,7 @@ impl<H: NippyJarHeader> std::fmt::Debug for NippyJar<H> {
             .field("offsets_index (size in bytes)", &self.offsets_index.size_in_bytes())
             .field("path", &self.path)
             .field("max_row_size", &self.max_row_size)
+            .field("checksums", &self.checksums)
             .finish_non_exhaustive()
     }
 }
@@ -167,6 +204,7 @@ impl<H: NippyJarHeader> NippyJar<H> {
             columns,
             rows: 0,
             max_row_size: 0,
+            checksums: Some(vec![0; columns]),
             compressor: None,
             filter: None,
             phf: None,
@@ -221,6 +259,11 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self.rows
     }
 
+    /// Gets the checksum of each column, if the jar records them.
+    pub fn checksums(&self) -> Option<&[u64]> {
+        self.checksums.as_deref()
+    }
+
     /// Returns the size of inclusion filter
     pub fn filter_size(&self) -> usize {
         self.size()
@@ -248,10 +291,18 @@ impl<H: NippyJarHeader> NippyJar<H> {
     pub fn load(path: &Path) -> Result<Self, NippyJarError> {
         // Read [`Self`] located at the data file.
         let config_path = path.with_extension(CONFIG_FILE_EXTENSION);
-        let config_file = File::open(&config_path)
+        let mut config_file = File::open(&config_path)
             .map_err(|err| reth_fs_util::FsPathError::open(err, config_path))?;
+        let mut config = Vec::new();
+        config_file.read_to_end(&mut config)?;
 
-        let mut obj: Self = bincode::deserialize_from(&config_file)?;
+        // The version is the first field, so it can be read before knowing the layout.
+        let version: usize = bincode::deserialize(&config)?;
+        let mut obj: Self = if version < 2 {
+            bincode::deserialize::<NippyJarV1<H>>(&config)?.into()
+        } else {
+            bincode::deserialize(&config)?
+        };
         obj.path = path.to_path_buf();
         Ok(obj)
     }
@@ -306,6 +357,55 @@ impl<H: NippyJarHeader> NippyJar<H> {
         DataReader::new(self.data_path())
     }
 
+    /// Checks that the data and offsets files are consistent with the configuration:
+    /// * there is one offset per value, plus the one representing the size of the data file.
+    /// * offsets start at zero, never decrease, and the last one is the size of the data file.
+    /// * stored values match the column checksums, if the jar records them.
+    pub fn check_integrity(&self) -> Result<(), NippyJarError> {
+        let reader = self.open_data_reader()?;
+
+        let values = self.rows * self.columns;
+        let offsets = reader.offsets_count()?;
+        // A jar whose rows were all pruned has no offsets at all
+        if offsets != values + 1 && !(values == 0 && offsets == 0) {
+            return Err(NippyJarError::OffsetsCountMismatch { expected: values + 1, got: offsets })
+        }
+
+        let mut previous = 0;
+        for index in 0..offsets {
+            let offset = reader.offset(index)?;
+            if offset < previous || (index == 0 && offset != 0) {
+                return Err(NippyJarError::OffsetOutOfOrder { index })
+            }
+            previous = offset;
+        }
+        if previous != reader.size() as u64 {
+            return Err(NippyJarError::DataSizeMismatch {
+                expected: previous,
+                got: reader.size() as u64,
+            })
+        }
+
+        if let Some(expected) = &self.checksums {
+            let got = checksums_of_rows(&reader, self.columns, 0..self.rows, |index| {
+                reader.offset(index)
+            })?;
+            for (column, (expected, got)) in expected.iter().zip(got).enumerate() {
+                if *expected != got {
+                    return Err(NippyJarError::ChecksumMismatch { column, expected: *expected, got })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Computes the checksum of each column from the data and offsets files.
+    fn compute_checksums(&self) -> Result<Vec<u64>, NippyJarError> {
+        let reader = self.open_data_reader()?;
+        checksums_of_rows(&reader, self.columns, 0..self.rows, |index| reader.offset(index))
+    }
+
     /// If required, prepares any compression algorithm to an early pass of the data.
     pub fn prepare_compression(
         &mut self,
@@ -454,6 +554,68 @@ impl<H: NippyJarHeader> NippyJar<H> {
     }
 }
 
+impl<H: NippyJarHeader + Clone> NippyJar<H> {
+    /// Rewrites the jar with `compressor` and replaces its files with the rewritten ones, returning
+    /// the rewritten jar.
+    ///
+    /// The rewritten jar is staged in `staging_dir`, and marked as complete before any file of the
+    /// jar is replaced. If the replacement is interrupted, [`commit_staged_jars`] finishes it.
+    pub fn recompress(
+        &self,
+        compressor: Option<Compressors>,
+        staging_dir: &Path,
+    ) -> Result<Self, NippyJarError> {
+        if self.filter.is_some() || self.phf.is_some() {
+            return Err(NippyJarError::RecompressionWithFilters)
+        }
+
+        let target_dir = self.path.parent().unwrap_or(Path::new(""));
+        let staged_path = staging_dir.join(self.path.file_name().unwrap_or_default());
+
+        // Removes the leftovers of an interrupted rewrite, since the writer would append to them
+        reth_fs_util::create_dir_all(staging_dir)?;
+        for path in [
+            staged_path.clone(),
+            staged_path.with_extension(INDEX_FILE_EXTENSION),
+            staged_path.with_extension(OFFSETS_FILE_EXTENSION),
+            staged_path.with_extension(CONFIG_FILE_EXTENSION),
+            staged_path.with_extension(STAGED_FILE_EXTENSION),
+        ] {
+            if path.exists() {
+                reth_fs_util::remove_file(path)?;
+            }
+        }
+
+        let mut staged = Self::new(self.columns, &staged_path, self.user_header.clone());
+        staged.compressor = compressor;
+        staged.freeze_filters()?;
+
+        debug!(target: "nippy-jar", path=?self.data_path(), "Rewriting jar.");
+        let mut writer = NippyJarWriter::new(staged, ConsistencyFailStrategy::Heal)?;
+        let mut cursor = NippyJarCursor::new(self)?;
+        while let Some(row) = cursor.next_row()? {
+            for value in row {
+                writer.append_column(Some(Ok(value)))?;
+            }
+        }
+        writer.commit()?;
+
+        let staged = writer.into_jar();
+        if staged.rows != self.rows {
+            return Err(NippyJarError::InconsistentState)
+        }
+        staged.check_integrity()?;
+
+        File::open(staged.index_path())?.sync_all()?;
+        File::create(staged_path.with_extension(STAGED_FILE_EXTENSION))?.sync_all()?;
+        OpenOptions::new().read(true).open(staging_dir)?.sync_all()?;
+
+        commit_staged_jar(&staged_path, target_dir)?;
+
+        Self::load(&self.path)
+    }
+}
+
 impl<H: NippyJarHeader> InclusionFilter for NippyJar<H> {
     fn add(&mut self, element: &[u8]) -> Result<(), NippyJarError> {
         self.filter.as_mut().ok_or(NippyJarError::FilterMissing)?.add(element)
@@ -478,6 +640,98 @@ impl<H: NippyJarHeader> PerfectHashingFunction for NippyJar<H> {
     }
 }
 
+/// Returns the checksum of a stored value. The row is part of it, so that values moved to another
+/// row don't go unnoticed.
+fn value_checksum(row: usize, value: &[u8]) -> u64 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&(row as u64).to_le_bytes());
+    hasher.update(value);
+    u64::from(hasher.finalize())
+}
+
+/// Returns the checksum of each column over `rows`, reading values from `reader` at the offsets
+/// returned by `offset` for a value index.
+fn checksums_of_rows(
+    reader: &DataReader,
+    columns: usize,
+    rows: Range<usize>,
+    offset: impl Fn(usize) -> Result<u64, NippyJarError>,
+) -> Result<Vec<u64>, NippyJarError> {
+    let mut checksums = vec![0u64; columns];
+    for row in rows {
+        for (column, checksum) in checksums.iter_mut().enumerate() {
+            let index = row * columns + column;
+            let (start, end) = (offset(index)?, offset(index + 1)?);
+            if start > end || end > reader.size() as u64 {
+                return Err(NippyJarError::OffsetOutOfOrder { index })
+            }
+            *checksum = checksum
+                .wrapping_add(value_checksum(row, reader.data(start as usize..end as usize)));
+        }
+    }
+    Ok(checksums)
+}
+
+/// Moves the jars staged in `staging_dir` by [`NippyJar::recompress`] to `target_dir`, replacing
+/// the jars of the same name. Returns the data paths of the replaced jars.
+///
+/// Staged jars that weren't marked as complete were interrupted while being written, and are
+/// removed instead.
+pub fn commit_staged_jars(
+    staging_dir: &Path,
+    target_dir: &Path,
+) -> Result<Vec<PathBuf>, NippyJarError> {
+    let mut committed = Vec::new();
+    if !staging_dir.exists() {
+        return Ok(committed)
+    }
+
+    for entry in reth_fs_util::read_dir(staging_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == STAGED_FILE_EXTENSION) {
+            let staged_path = path.with_extension("");
+            commit_staged_jar(&staged_path, target_dir)?;
+            committed.push(target_dir.join(staged_path.file_name().unwrap_or_default()));
+        }
+    }
+
+    reth_fs_util::remove_dir_all(staging_dir)?;
+
+    Ok(committed)
+}
+
+/// Moves the files of a staged jar marked as complete to `target_dir`, and removes the mark.
+///
+/// The configuration is moved last, and files that were already moved are skipped, so that an
+/// interrupted call can be repeated.
+fn commit_staged_jar(staged_path: &Path, target_dir: &Path) -> Result<(), NippyJarError> {
+    let target_path = target_dir.join(staged_path.file_name().unwrap_or_default());
+    for (from, to) in [
+        (staged_path.to_path_buf(), target_path.clone()),
+        (
+            staged_path.with_extension(OFFSETS_FILE_EXTENSION),
+            target_path.with_extension(OFFSETS_FILE_EXTENSION),
+        ),
+        (
+            staged_path.with_extension(INDEX_FILE_EXTENSION),
+            target_path.with_extension(INDEX_FILE_EXTENSION),
+        ),
+        (
+            staged_path.with_extension(CONFIG_FILE_EXTENSION),
+            target_path.with_extension(CONFIG_FILE_EXTENSION),
+        ),
+    ] {
+        if from.exists() {
+            reth_fs_util::rename(from, to)?;
+        }
+    }
+    OpenOptions::new().read(true).open(target_dir)?.sync_all()?;
+
+    reth_fs_util::remove_file(staged_path.with_extension(STAGED_FILE_EXTENSION))?;
+
+    Ok(())
+}
+
 /// Manages the reading of static file data using memory-mapped files.
 ///
 /// Holds file and mmap descriptors of the data and offsets files of a `static_file`.
@@ -1117,6 +1371,93 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_checksums() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let num_columns = 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = NippyJar::new_without_header(num_columns, file_path.path())
+            .with_lz4()
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        assert!(nippy.checksums().is_some());
+        nippy.check_integrity().unwrap();
+
+        // Appends two rows, and a third one whose offsets are kept in memory, so pruning two rows
+        // prunes from the on-memory and on-disk offset lists
+        let mut writer = NippyJarWriter::new(nippy, ConsistencyFailStrategy::Heal).unwrap();
+        writer.append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], 2).unwrap();
+        writer.commit().unwrap();
+        writer.append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], 1).unwrap();
+        writer.prune_rows(2).unwrap();
+        assert_eq!(writer.rows(), num_rows as usize + 1);
+
+        let nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        nippy.check_integrity().unwrap();
+
+        // Corrupts a value without changing its size
+        let mut data = std::fs::read(nippy.data_path()).unwrap();
+        data[0] ^= 0xff;
+        std::fs::write(nippy.data_path(), data).unwrap();
+        assert!(matches!(
+            nippy.check_integrity(),
+            Err(NippyJarError::ChecksumMismatch { column: 0, .. })
+        ));
+
+        // Pruning all rows resets the checksums
+        let mut writer = NippyJarWriter::new(nippy, ConsistencyFailStrategy::Heal).unwrap();
+        writer.prune_rows(num_rows as usize + 1).unwrap();
+        writer.commit().unwrap();
+        let nippy = writer.into_jar();
+        assert_eq!(nippy.checksums(), Some([0, 0].as_slice()));
+        nippy.check_integrity().unwrap();
+    }
+
+    #[test]
+    fn test_recompress() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let num_columns = 2;
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("jar");
+        let staging_dir = dir.path().join("staging");
+
+        NippyJar::new_without_header(num_columns, &file_path)
+            .with_lz4()
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        let nippy = NippyJar::load_without_header(&file_path).unwrap();
+
+        let mut zstd = compression::Zstd::new(true, 5000, num_columns);
+        zstd.prepare_compression(vec![col1.clone(), col2.clone()]).unwrap();
+        let nippy = nippy.recompress(Some(Compressors::Zstd(zstd)), &staging_dir).unwrap();
+        assert!(matches!(nippy.compressor(), Some(Compressors::Zstd(zstd)) if zstd.use_dict));
+        assert_eq!(nippy.rows(), num_rows as usize);
+        nippy.check_integrity().unwrap();
+
+        // Appending to the loaded jar compresses the new values with its dictionaries
+        let mut writer = NippyJarWriter::new(nippy, ConsistencyFailStrategy::Heal).unwrap();
+        writer.append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], 1).unwrap();
+        writer.commit().unwrap();
+
+        let nippy = NippyJar::load_without_header(&file_path).unwrap();
+        nippy.check_integrity().unwrap();
+        let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+        let mut row_index = 0usize;
+        while let Some(row) = cursor.next_row().unwrap() {
+            let index = row_index % num_rows as usize;
+            assert_eq!((row[0], row[1]), (col1[index].as_slice(), col2[index].as_slice()));
+            row_index += 1;
+        }
+        assert_eq!(row_index, num_rows as usize + 1);
+
+        // Nothing is left to commit, and the staging directory is removed
+        assert!(commit_staged_jars(&staging_dir, dir.path()).unwrap().is_empty());
+        assert!(!staging_dir.exists());
+    }
+
     fn test_append_consistency_partial_commit(
         file_path: &Path,
         col1: &[Vec<u8>],
@@ -1,10 +1,16 @@
-use crate::{compression::Compression, ColumnResult, NippyJar, NippyJarError, NippyJarHeader};
+use crate::{
+    checksums_of_rows,
+    compression::{Compression, Compressors},
+    value_checksum, ColumnResult, NippyJar, NippyJarError, NippyJarHeader,
+};
 use std::{
     cmp::Ordering,
     fs::{File, OpenOptions},
     io::{BufWriter, Read, Seek, SeekFrom, Write},
     path::Path,
 };
+use tracing::warn;
+use zstd::{bulk::Compressor, zstd_safe};
 
 /// Size of one offset in bytes.
 const OFFSET_SIZE_BYTES: u8 = 8;
@@ -22,7 +28,6 @@ const OFFSET_SIZE_BYTES: u8 = 8;
 ///
 /// ## Data file layout
 /// The data file is represented just as a sequence of bytes of data without any delimiters
-#[derive(Debug)]
 pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     /// Associated [`NippyJar`], containing all necessary configurations for data
     /// handling.
@@ -33,6 +38,8 @@ pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     offsets_file: BufWriter<File>,
     /// Temporary buffer to reuse when compressing data.
     tmp_buf: Vec<u8>,
+    /// Compressor of each column, if the jar is compressed with zstd dictionaries.
+    dictionary_compressors: Option<Vec<Compressor<'static>>>,
     /// Used to find the maximum uncompressed size of a row in a jar.
     uncompressed_row_size: usize,
     /// Partial offset list which hasn't been flushed to disk.
@@ -43,6 +50,16 @@ pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     dirty: bool,
 }
 
+impl<H: NippyJarHeader> std::fmt::Debug for NippyJarWriter<H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NippyJarWriter")
+            .field("jar", &self.jar)
+            .field("column", &self.column)
+            .field("dirty", &self.dirty)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<H: NippyJarHeader> NippyJarWriter<H> {
     /// Creates a [`NippyJarWriter`] from [`NippyJar`].
     ///
@@ -58,11 +75,17 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         // Makes sure we don't have dangling data and offset files
         jar.freeze_config()?;
 
+        let dictionary_compressors = match &jar.compressor {
+            Some(Compressors::Zstd(zstd)) => zstd.compressors()?,
+            _ => None,
+        };
+
         let mut writer = Self {
             jar,
             data_file: BufWriter::new(data_file),
             offsets_file: BufWriter::new(offsets_file),
             tmp_buf: Vec::with_capacity(1_000_000),
+            dictionary_compressors,
             uncompressed_row_size: 0,
             offsets: Vec::with_capacity(1_000_000),
             column: 0,
@@ -158,6 +181,28 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         &mut self,
         check_mode: ConsistencyFailStrategy,
     ) -> Result<(), NippyJarError> {
+        let rows = self.jar.rows;
+
+        self.heal_files(check_mode)?;
+
+        // Healing an interrupted pruning job drops rows, so the checksums no longer match
+        if self.jar.rows != rows && self.jar.checksums.is_some() {
+            self.jar.checksums = match self.jar.compute_checksums() {
+                Ok(checksums) => Some(checksums),
+                Err(err) => {
+                    warn!(target: "nippy-jar", %err, path = ?self.jar.data_path(), "Dropping checksums of a corrupted jar.");
+                    None
+                }
+            };
+            self.jar.freeze_config()?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the offsets and data files sizes, and heals them according to
+    /// [`ConsistencyFailStrategy`]. See [`Self::ensure_file_consistency`].
+    fn heal_files(&mut self, check_mode: ConsistencyFailStrategy) -> Result<(), NippyJarError> {
         let reader = self.jar.open_data_reader()?;
 
         // When an offset size is smaller than the initial (8), we are dealing with immutable
@@ -234,7 +279,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
 
                         // Since we decrease the offset list, we need to check the consistency of
                         // `self.jar.rows` again
-                        self.ensure_file_consistency(ConsistencyFailStrategy::Heal)?;
+                        self.heal_files(ConsistencyFailStrategy::Heal)?;
                         break
                     }
                 }
@@ -315,7 +360,15 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     /// Writes column to data file. If it's the last column of the row, call `finalize_row()`
     fn write_column(&mut self, value: &[u8]) -> Result<usize, NippyJarError> {
         self.uncompressed_row_size += value.len();
-        let len = if let Some(compression) = &self.jar.compressor {
+        let len = if let Some(compressors) = &mut self.dictionary_compressors {
+            let before = self.tmp_buf.len();
+            self.tmp_buf.resize(before + zstd_safe::compress_bound(value.len()), 0);
+            let len =
+                compressors[self.column].compress_to_buffer(value, &mut self.tmp_buf[before..])?;
+            self.tmp_buf.truncate(before + len);
+            self.data_file.write_all(&self.tmp_buf[before..])?;
+            len
+        } else if let Some(compression) = &self.jar.compressor {
             let before = self.tmp_buf.len();
             let len = compression.compress_to(value, &mut self.tmp_buf)?;
             self.data_file.write_all(&self.tmp_buf[before..before + len])?;
@@ -325,6 +378,15 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
             value.len()
         };
 
+        if let Some(checksums) = &mut self.jar.checksums {
+            let written = match &self.jar.compressor {
+                Some(_) => &self.tmp_buf[self.tmp_buf.len() - len..],
+                None => value,
+            };
+            checksums[self.column] =
+                checksums[self.column].wrapping_add(value_checksum(self.jar.rows, written));
+        }
+
         self.column += 1;
 
         if self.jar.columns == self.column {
@@ -341,6 +403,23 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.offsets_file.flush()?;
         self.data_file.flush()?;
 
+        // Values of the pruned rows no longer count towards the checksums
+        if self.jar.checksums.is_some() {
+            match self.checksums_of_last_rows(num_rows) {
+                Ok(pruned) => {
+                    if let Some(checksums) = &mut self.jar.checksums {
+                        for (checksum, pruned) in checksums.iter_mut().zip(pruned) {
+                            *checksum = checksum.wrapping_sub(pruned);
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!(target: "nippy-jar", %err, path = ?self.jar.data_path(), "Dropping checksums of a corrupted jar.");
+                    self.jar.checksums = None;
+                }
+            }
+        }
+
         // Each column of a row is one offset
         let num_offsets = num_rows * self.jar.columns;
 
@@ -411,12 +490,46 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.jar.rows = self.jar.rows.saturating_sub(num_rows);
         if self.jar.rows == 0 {
             self.jar.max_row_size = 0;
+            self.jar.checksums = Some(vec![0; self.jar.columns]);
         }
         self.jar.freeze_config()?;
 
         Ok(())
     }
 
+    /// Returns the checksum of each column over the last `num_rows` rows. Their offsets can be
+    /// both on disk and in memory.
+    fn checksums_of_last_rows(&self, num_rows: usize) -> Result<Vec<u64>, NippyJarError> {
+        let first_row = self.jar.rows.saturating_sub(num_rows);
+        if first_row == self.jar.rows {
+            return Ok(vec![0; self.jar.columns])
+        }
+
+        let reader = self.jar.open_data_reader()?;
+
+        // The first in-memory offset repeats the last on-disk one, if there's any.
+        let in_memory_start = reader.offsets_count()?.saturating_sub(1);
+
+        checksums_of_rows(&reader, self.jar.columns, first_row..self.jar.rows, |index| match index
+            .checked_sub(in_memory_start)
+            .and_then(|index| self.offsets.get(index))
+        {
+            Some(offset) => Ok(*offset),
+            None => reader.offset(index),
+        })
+    }
+
+    /// Commits any pending changes, and recomputes the checksum of each column from the data on
+    /// disk.
+    ///
+    /// Jars created before checksums were introduced start recording them.
+    pub fn rebuild_checksums(&mut self) -> Result<(), NippyJarError> {
+        self.commit()?;
+
+        self.jar.checksums = Some(self.jar.compute_checksums()?);
+        self.jar.freeze_config()
+    }
+
     /// Updates [`NippyJar`] with the new row count and maximum uncompressed row size, while
     /// resetting internal fields.
     fn finalize_row(&mut self) {
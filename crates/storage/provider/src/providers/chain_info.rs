@@ -8,6 +8,7 @@ use std::{
     },
     time::Instant,
 };
+use tokio::sync::watch;
 
 /// Tracks the chain info: canonical head, safe block, finalized block.
 #[derive(Debug, Clone)]
@@ -24,8 +25,8 @@ impl ChainInfoTracker {
                 last_transition_configuration_exchange: RwLock::new(None),
                 canonical_head_number: AtomicU64::new(head.number),
                 canonical_head: RwLock::new(head),
-                safe_block: RwLock::new(None),
-                finalized_block: RwLock::new(None),
+                safe_block: watch::channel(None).0,
+                finalized_block: watch::channel(None).0,
             }),
         }
     }
@@ -63,12 +64,12 @@ impl ChainInfoTracker {
 
     /// Returns the safe header of the chain.
     pub(crate) fn get_safe_header(&self) -> Option<SealedHeader> {
-        self.inner.safe_block.read().clone()
+        self.inner.safe_block.borrow().clone()
     }
 
     /// Returns the finalized header of the chain.
     pub(crate) fn get_finalized_header(&self) -> Option<SealedHeader> {
-        self.inner.finalized_block.read().clone()
+        self.inner.finalized_block.borrow().clone()
     }
 
     /// Returns the canonical head of the chain.
@@ -85,15 +86,13 @@ impl ChainInfoTracker {
     /// Returns the safe header of the chain.
     #[allow(dead_code)]
     pub(crate) fn get_safe_num_hash(&self) -> Option<BlockNumHash> {
-        let h = self.inner.safe_block.read();
-        h.as_ref().map(|h| h.num_hash())
+        self.inner.safe_block.borrow().as_ref().map(|h| h.num_hash())
     }
 
     /// Returns the finalized header of the chain.
     #[allow(dead_code)]
     pub(crate) fn get_finalized_num_hash(&self) -> Option<BlockNumHash> {
-        let h = self.inner.finalized_block.read();
-        h.as_ref().map(|h| h.num_hash())
+        self.inner.finalized_block.borrow().as_ref().map(|h| h.num_hash())
     }
 
     /// Sets the canonical head of the chain.
@@ -106,13 +105,39 @@ impl ChainInfoTracker {
     }
 
     /// Sets the safe header of the chain.
+    ///
+    /// Subscribers are only notified if the safe block changed.
     pub(crate) fn set_safe(&self, header: SealedHeader) {
-        self.inner.safe_block.write().replace(header);
+        self.inner.safe_block.send_if_modified(|current| {
+            if current.as_ref().map(SealedHeader::hash) == Some(header.hash()) {
+                return false
+            }
+            current.replace(header);
+            true
+        });
     }
 
     /// Sets the finalized header of the chain.
+    ///
+    /// Subscribers are only notified if the finalized block changed.
     pub(crate) fn set_finalized(&self, header: SealedHeader) {
-        self.inner.finalized_block.write().replace(header);
+        self.inner.finalized_block.send_if_modified(|current| {
+            if current.as_ref().map(SealedHeader::hash) == Some(header.hash()) {
+                return false
+            }
+            current.replace(header);
+            true
+        });
+    }
+
+    /// Subscribes to the safe header of the chain.
+    pub(crate) fn subscribe_safe_block(&self) -> watch::Receiver<Option<SealedHeader>> {
+        self.inner.safe_block.subscribe()
+    }
+
+    /// Subscribes to the finalized header of the chain.
+    pub(crate) fn subscribe_finalized_block(&self) -> watch::Receiver<Option<SealedHeader>> {
+        self.inner.finalized_block.subscribe()
     }
 }
 
@@ -132,7 +157,7 @@ struct ChainInfoInner {
     /// The canonical head of the chain.
     canonical_head: RwLock<SealedHeader>,
     /// The block that the beacon node considers safe.
-    safe_block: RwLock<Option<SealedHeader>>,
+    safe_block: watch::Sender<Option<SealedHeader>>,
     /// The block that the beacon node considers finalized.
-    finalized_block: RwLock<Option<SealedHeader>>,
+    finalized_block: watch::Sender<Option<SealedHeader>>,
 }
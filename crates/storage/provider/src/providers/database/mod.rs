@@ -154,6 +154,10 @@ impl<DB: Database> DatabaseProviderFactory<DB> for ProviderFactory<DB> {
     fn database_provider_ro(&self) -> ProviderResult<DatabaseProviderRO<DB>> {
         self.provider()
     }
+
+    fn database_provider_rw(&self) -> ProviderResult<DatabaseProviderRW<DB>> {
+        self.provider_rw()
+    }
 }
 
 impl<DB> StaticFileProviderFactory for ProviderFactory<DB> {
@@ -592,7 +596,7 @@ mod tests {
     use super::*;
     use crate::{
         providers::{StaticFileProvider, StaticFileWriter},
-        test_utils::create_test_provider_factory,
+        test_utils::{create_test_memory_provider_factory, create_test_provider_factory},
         BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider, TransactionsProvider,
     };
     use alloy_rlp::Decodable;
@@ -697,6 +701,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn insert_block_with_memory_database() {
+        let factory = create_test_memory_provider_factory();
+
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 0, None, Some(3), None);
+
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(block.clone().try_seal_with_senders().unwrap(), None).unwrap();
+        provider.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.block_hash(0).unwrap(), Some(block.hash()));
+        assert_eq!(provider.transaction_id(block.body[1].hash).unwrap(), Some(1));
+        assert_eq!(provider.transaction_sender(2).unwrap(), block.body[2].recover_signer());
+    }
+
     #[test]
     fn get_take_block_transaction_range_recover_senders() {
         let factory = create_test_provider_factory();
@@ -5,13 +5,13 @@ use crate::{
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
-    AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
-    Chain, EvmEnvProvider, FinalizedBlockReader, FinalizedBlockWriter, HashingWriter,
-    HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoricalStateProvider, HistoryWriter,
-    LatestStateProvider, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
-    PruneCheckpointWriter, RequestsProvider, StageCheckpointReader, StateProviderBox, StateWriter,
-    StatsReader, StorageReader, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
-    WithdrawalsProvider,
+    AccountReader, AddressAppearancesReader, BlockExecutionWriter, BlockHashReader, BlockNumReader,
+    BlockReader, BlockWriter, Chain, EvmEnvProvider, FinalizedBlockReader, FinalizedBlockWriter,
+    HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoricalStateProvider,
+    HistoryWriter, LatestStateProvider, LogIndexReader, OriginalValuesKnown, ProviderError,
+    PruneCheckpointReader, PruneCheckpointWriter, RequestsProvider, StageCheckpointReader,
+    StateProviderBox, StateWriter, StatsReader, StorageReader, TransactionVariant,
+    TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
 use itertools::{izip, Itertools};
 use reth_chainspec::{ChainInfo, ChainSpec};
@@ -22,7 +22,8 @@ use reth_db_api::{
     database::Database,
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        ContractCreation, ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers,
+        StoredBlockWithdrawals,
     },
     table::{Table, TableRow},
     transaction::{DbTx, DbTxMut},
@@ -251,6 +252,30 @@ where
     Ok(Vec::new())
 }
 
+/// Returns an iterator over the entries of the table in `range`.
+///
+/// Unlike [`DbCursorRO::walk_range`], the iterator owns the cursor, so it can be returned along
+/// with other iterators.
+fn walk_range_owned<T, C>(
+    mut cursor: C,
+    range: Range<T::Key>,
+) -> impl Iterator<Item = ProviderResult<(T::Key, T::Value)>>
+where
+    T: Table,
+    C: DbCursorRO<T>,
+{
+    let mut next =
+        if range.start < range.end { cursor.seek(range.start.clone()).transpose() } else { None };
+    std::iter::from_fn(move || match next.take()? {
+        Ok((key, _)) if key >= range.end => None,
+        Ok(entry) => {
+            next = cursor.next().transpose();
+            Some(Ok(entry))
+        }
+        Err(err) => Some(Err(err.into())),
+    })
+}
+
 impl<TX: DbTx> DatabaseProvider<TX> {
     /// Creates a provider with an inner read-only transaction.
     pub const fn new(
@@ -515,6 +540,120 @@ impl<TX: DbTx> DatabaseProvider<TX> {
             assemble_block(header, body, ommers, withdrawals, requests, senders)
         })
     }
+
+    /// Returns at most `limit` indices of the given key that are in the range, from a history
+    /// table sharded by [`ShardedKey`], in ascending order, or in descending order if `reverse` is
+    /// set.
+    fn sharded_history_range<T, K>(
+        &self,
+        key: K,
+        range: RangeInclusive<u64>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<u64>>
+    where
+        T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+        K: Copy + PartialEq,
+    {
+        let mut indices = Vec::new();
+        if range.is_empty() || limit == 0 {
+            return Ok(indices)
+        }
+
+        // The first shard that can contain the first index we're looking for. Shards are keyed by
+        // their highest index, and the last shard of a key is keyed by `u64::MAX`.
+        let start = if reverse { *range.end() } else { *range.start() };
+        let mut cursor = self.tx.cursor_read::<T>()?;
+        let mut shard = cursor.seek(ShardedKey::new(key, start))?;
+        while let Some((sharded_key, list)) = shard {
+            if sharded_key.key != key {
+                break
+            }
+
+            let mut list = list.iter().collect::<Vec<_>>();
+            if reverse {
+                list.reverse();
+            }
+            for index in list {
+                if range.contains(&index) {
+                    indices.push(index);
+                    if indices.len() == limit {
+                        return Ok(indices)
+                    }
+                } else if (reverse && index < *range.start()) || (!reverse && index > *range.end())
+                {
+                    return Ok(indices)
+                }
+            }
+
+            shard = if reverse { cursor.prev()? } else { cursor.next()? };
+        }
+
+        Ok(indices)
+    }
+
+    /// Returns an iterator over the account changes of the blocks in `range`, in block and
+    /// address order.
+    ///
+    /// Changesets of blocks that were moved to static files are read from there, and the rest from
+    /// the [`tables::AccountChangeSets`] table. The changes are read as the iterator advances, a
+    /// block at a time from the static files.
+    pub fn account_changesets_range(
+        &self,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumber, AccountBeforeTx)>> + '_>
+    {
+        let range = to_range(range);
+        let static_file_changesets =
+            self.static_file_provider.account_changesets_iter(range.clone())?;
+
+        let database_start =
+            range.start.max(self.first_database_changeset(StaticFileSegment::AccountChangeSets));
+        let database_changesets = walk_range_owned(
+            self.tx.cursor_read::<tables::AccountChangeSets>()?,
+            database_start..range.end,
+        );
+
+        Ok(static_file_changesets.chain(database_changesets))
+    }
+
+    /// Returns an iterator over the storage changes of the `range` of [`BlockNumberAddress`] keys,
+    /// in key order.
+    ///
+    /// Changesets of blocks that were moved to static files are read from there, and the rest from
+    /// the [`tables::StorageChangeSets`] table. The changes are read as the iterator advances, a
+    /// block at a time from the static files.
+    pub fn storage_changesets_range(
+        &self,
+        range: Range<BlockNumberAddress>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumberAddress, StorageEntry)>> + '_>
+    {
+        let static_file_range = range.clone();
+        let static_file_changesets = self
+            .static_file_provider
+            .storage_changesets_iter(range.start.block_number()..=range.end.block_number())?
+            .filter(move |entry| {
+                entry.as_ref().map_or(true, |(key, _)| static_file_range.contains(key))
+            });
+
+        let database_start = range.start.max(BlockNumberAddress((
+            self.first_database_changeset(StaticFileSegment::StorageChangeSets),
+            Address::ZERO,
+        )));
+        let database_changesets = walk_range_owned(
+            self.tx.cursor_read::<tables::StorageChangeSets>()?,
+            database_start..range.end,
+        );
+
+        Ok(static_file_changesets.chain(database_changesets))
+    }
+
+    /// Returns the first block whose changesets are not in the static files of `segment`.
+    pub fn first_database_changeset(&self, segment: StaticFileSegment) -> BlockNumber {
+        self.static_file_provider
+            .get_highest_static_file_block(segment)
+            .map_or(0, |block| block + 1)
+    }
 }
 
 impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
@@ -568,9 +707,31 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
 
         let storage_range = BlockNumberAddress::range(range.clone());
 
-        let storage_changeset =
-            self.get_or_take::<tables::StorageChangeSets, TAKE>(storage_range)?;
-        let account_changeset = self.get_or_take::<tables::AccountChangeSets, TAKE>(range)?;
+        // Changesets of blocks that were moved to static files are read from there. They are not
+        // removed from the static files here, the execution stage prunes them on unwind.
+        let first_database_storage_changeset =
+            self.first_database_changeset(StaticFileSegment::StorageChangeSets);
+        let mut storage_changeset = self
+            .static_file_provider
+            .storage_changesets_iter(range.clone())?
+            .collect::<ProviderResult<Vec<_>>>()?;
+        storage_changeset.extend(
+            self.get_or_take::<tables::StorageChangeSets, TAKE>(storage_range)?
+                .into_iter()
+                .filter(|(key, _)| key.block_number() >= first_database_storage_changeset),
+        );
+
+        let first_database_account_changeset =
+            self.first_database_changeset(StaticFileSegment::AccountChangeSets);
+        let mut account_changeset = self
+            .static_file_provider
+            .account_changesets_iter(range.clone())?
+            .collect::<ProviderResult<Vec<_>>>()?;
+        account_changeset.extend(
+            self.get_or_take::<tables::AccountChangeSets, TAKE>(range)?
+                .into_iter()
+                .filter(|(block, _)| *block >= first_database_account_changeset),
+        );
 
         // iterate previous value and get plain state value to create changeset
         // Double option around Account represent if Account state is know (first option) and
@@ -1160,6 +1321,39 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         Ok(Vec::new())
     }
 
+    /// Removes the indices greater than or equal to `first_index` of the given keys from a
+    /// history table sharded by [`ShardedKey`].
+    fn unwind_sharded_history<T, K>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        first_index: u64,
+    ) -> ProviderResult<()>
+    where
+        T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+        K: Copy + PartialEq,
+    {
+        let mut cursor = self.tx.cursor_write::<T>()?;
+        for key in keys {
+            let partial_shard = unwind_history_shards::<_, T, _>(
+                &mut cursor,
+                ShardedKey::last(key),
+                first_index,
+                |sharded_key| sharded_key.key == key,
+            )?;
+
+            // Check the last returned partial shard.
+            // If it's not empty, the shard needs to be reinserted.
+            if !partial_shard.is_empty() {
+                cursor.insert(
+                    ShardedKey::last(key),
+                    BlockNumberList::new_pre_sorted(partial_shard),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Insert history index to the database.
     ///
     /// For each updated partial key, this function removes the last shard from
@@ -1215,12 +1409,8 @@ impl<TX: DbTx> AccountExtReader for DatabaseProvider<TX> {
         &self,
         range: impl RangeBounds<BlockNumber>,
     ) -> ProviderResult<BTreeSet<Address>> {
-        self.tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|entry| {
-                entry.map(|(_, account_before)| account_before.address).map_err(Into::into)
-            })
+        self.account_changesets_range(range)?
+            .map(|entry| entry.map(|(_, account_before)| account_before.address))
             .collect()
     }
 
@@ -1239,16 +1429,11 @@ impl<TX: DbTx> AccountExtReader for DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::AccountChangeSets>()?;
-
-        let account_transitions = changeset_cursor.walk_range(range)?.try_fold(
-            BTreeMap::new(),
-            |mut accounts: BTreeMap<Address, Vec<u64>>, entry| -> ProviderResult<_> {
-                let (index, account) = entry?;
-                accounts.entry(account.address).or_default().push(index);
-                Ok(accounts)
-            },
-        )?;
+        let mut account_transitions = BTreeMap::<Address, Vec<u64>>::new();
+        for entry in self.account_changesets_range(range)? {
+            let (index, account) = entry?;
+            account_transitions.entry(account.address).or_default().push(index);
+        }
 
         Ok(account_transitions)
     }
@@ -1259,6 +1444,10 @@ impl<TX: DbTx> ChangeSetReader for DatabaseProvider<TX> {
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
+        if let Some(changeset) = self.static_file_provider.account_changeset(block_number)? {
+            return Ok(changeset.accounts.clone())
+        }
+
         let range = block_number..=block_number;
         self.tx
             .cursor_read::<tables::AccountChangeSets>()?
@@ -1271,6 +1460,55 @@ impl<TX: DbTx> ChangeSetReader for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> AddressAppearancesReader for DatabaseProvider<TX> {
+    fn address_appearances_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self
+            .get_stage_checkpoint(StageId::IndexAddressAppearances)?
+            .map(|checkpoint| checkpoint.block_number))
+    }
+
+    fn address_appearances(
+        &self,
+        address: Address,
+        range: RangeInclusive<TxNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<TxNumber>> {
+        self.sharded_history_range::<tables::AddressAppearances, _>(address, range, reverse, limit)
+    }
+
+    fn contract_creation(&self, address: Address) -> ProviderResult<Option<ContractCreation>> {
+        Ok(self.tx.get::<tables::ContractCreators>(address)?)
+    }
+}
+
+impl<TX: DbTx> LogIndexReader for DatabaseProvider<TX> {
+    fn log_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self.get_stage_checkpoint(StageId::IndexLogs)?.map(|checkpoint| checkpoint.block_number))
+    }
+
+    fn log_address_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.sharded_history_range::<tables::LogAddressHistory, _>(
+            address,
+            range,
+            false,
+            usize::MAX,
+        )
+    }
+
+    fn log_topic_blocks(
+        &self,
+        topic: B256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.sharded_history_range::<tables::LogTopicHistory, _>(topic, range, false, usize::MAX)
+    }
+}
+
 impl<TX: DbTx> HeaderSyncGapProvider for DatabaseProvider<TX> {
     fn sync_gap(
         &self,
@@ -2150,36 +2388,28 @@ impl<TX: DbTx> StorageReader for DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
-        self.tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(BlockNumberAddress::range(range))?
-            // fold all storages and save its old state so we can remove it from HashedStorage
-            // it is needed as it is dup table.
-            .try_fold(BTreeMap::new(), |mut accounts: BTreeMap<Address, BTreeSet<B256>>, entry| {
-                let (BlockNumberAddress((_, address)), storage_entry) = entry?;
-                accounts.entry(address).or_default().insert(storage_entry.key);
-                Ok(accounts)
-            })
+        // fold all storages and save its old state so we can remove it from HashedStorage
+        // it is needed as it is dup table.
+        let mut accounts = BTreeMap::<Address, BTreeSet<B256>>::new();
+        for entry in self.storage_changesets_range(BlockNumberAddress::range(range))? {
+            let (BlockNumberAddress((_, address)), storage_entry) = entry?;
+            accounts.entry(address).or_default().insert(storage_entry.key);
+        }
+        Ok(accounts)
     }
 
     fn changed_storages_and_blocks_with_range(
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<(Address, B256), Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::StorageChangeSets>()?;
-
-        let storage_changeset_lists =
-            changeset_cursor.walk_range(BlockNumberAddress::range(range))?.try_fold(
-                BTreeMap::new(),
-                |mut storages: BTreeMap<(Address, B256), Vec<u64>>, entry| -> ProviderResult<_> {
-                    let (index, storage) = entry?;
-                    storages
-                        .entry((index.address(), storage.key))
-                        .or_default()
-                        .push(index.block_number());
-                    Ok(storages)
-                },
-            )?;
+        let mut storage_changeset_lists = BTreeMap::<(Address, B256), Vec<u64>>::new();
+        for entry in self.storage_changesets_range(BlockNumberAddress::range(range))? {
+            let (index, storage) = entry?;
+            storage_changeset_lists
+                .entry((index.address(), storage.key))
+                .or_default()
+                .push(index.block_number());
+        }
 
         Ok(storage_changeset_lists)
     }
@@ -2191,17 +2421,13 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<B256, Option<Account>>> {
         // Aggregate all block changesets and make a list of accounts that have been changed.
-        // Note that collecting and then reversing the order is necessary to ensure that the
-        // changes are applied in the correct order.
-        let hashed_accounts = self
-            .tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|entry| entry.map(|(_, e)| (keccak256(e.address), e.info)))
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter()
-            .rev()
-            .collect::<BTreeMap<_, _>>();
+        // Note that only the first change of an account in the range is kept, since it holds the
+        // state of the account before the range.
+        let mut hashed_accounts = BTreeMap::new();
+        for entry in self.account_changesets_range(range)? {
+            let (_, e) = entry?;
+            hashed_accounts.entry(keccak256(e.address)).or_insert(e.info);
+        }
 
         // Apply values to HashedState, and remove the account if it's None.
         let mut hashed_accounts_cursor = self.tx.cursor_write::<tables::HashedAccounts>()?;
@@ -2238,15 +2464,14 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
         range: Range<BlockNumberAddress>,
     ) -> ProviderResult<HashMap<B256, BTreeSet<B256>>> {
         // Aggregate all block changesets and make list of accounts that have been changed.
-        let mut changesets = self.tx.cursor_read::<tables::StorageChangeSets>()?;
-        let mut hashed_storages = changesets
-            .walk_range(range)?
+        let mut hashed_storages = self
+            .storage_changesets_range(range)?
             .map(|entry| {
                 entry.map(|(BlockNumberAddress((_, address)), storage_entry)| {
                     (keccak256(address), keccak256(storage_entry.key), storage_entry.value)
                 })
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<ProviderResult<Vec<_>>>()?;
         hashed_storages.sort_by_key(|(ha, hk, _)| (*ha, *hk));
 
         // Apply values to HashedState, and remove the account if it's None.
@@ -2396,11 +2621,9 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<usize> {
         let mut last_indices = self
-            .tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
+            .account_changesets_range(range)?
             .map(|entry| entry.map(|(index, account)| (account.address, index)))
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<ProviderResult<Vec<_>>>()?;
         last_indices.sort_by_key(|(a, _)| *a);
 
         // Unwind the account history index.
@@ -2442,13 +2665,11 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         range: Range<BlockNumberAddress>,
     ) -> ProviderResult<usize> {
         let mut storage_changesets = self
-            .tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(range)?
+            .storage_changesets_range(range)?
             .map(|entry| {
                 entry.map(|(BlockNumberAddress((bn, address)), storage)| (address, storage.key, bn))
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<ProviderResult<Vec<_>>>()?;
         storage_changesets.sort_by_key(|(address, key, _)| (*address, *key));
 
         let mut cursor = self.tx.cursor_write::<tables::StoragesHistory>()?;
@@ -2489,6 +2710,73 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         )
     }
 
+    fn unwind_address_appearances(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize> {
+        // All transactions after the block we unwind to are removed from the index.
+        let unwind_to = range.start().saturating_sub(1);
+        let first_tx = self
+            .block_body_indices(unwind_to)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(unwind_to))?
+            .next_tx_num();
+
+        let mut addresses = BTreeSet::new();
+        let mut block_appearances_cursor = self.tx.cursor_write::<tables::BlockAppearances>()?;
+        let mut walker = block_appearances_cursor.walk_range(range)?;
+        while let Some((_, address)) = walker.next().transpose()? {
+            addresses.insert(address);
+            walker.delete_current()?;
+        }
+
+        self.unwind_sharded_history::<tables::AddressAppearances, _>(
+            addresses.iter().copied(),
+            first_tx,
+        )?;
+
+        // Contracts are created by transactions they appeared in.
+        for &address in &addresses {
+            if self
+                .tx
+                .get::<tables::ContractCreators>(address)?
+                .is_some_and(|creation| creation.tx_number >= first_tx)
+            {
+                self.tx.delete::<tables::ContractCreators>(address, None)?;
+            }
+        }
+
+        Ok(addresses.len())
+    }
+
+    fn insert_address_appearances_index(
+        &self,
+        address_appearances: BTreeMap<Address, Vec<TxNumber>>,
+    ) -> ProviderResult<()> {
+        self.append_history_index::<_, tables::AddressAppearances>(
+            address_appearances,
+            ShardedKey::new,
+        )
+    }
+
+    fn unwind_log_index(
+        &self,
+        addresses: BTreeSet<Address>,
+        topics: BTreeSet<B256>,
+        first_block: BlockNumber,
+    ) -> ProviderResult<()> {
+        self.unwind_sharded_history::<tables::LogAddressHistory, _>(addresses, first_block)?;
+        self.unwind_sharded_history::<tables::LogTopicHistory, _>(topics, first_block)
+    }
+
+    fn insert_log_index(
+        &self,
+        addresses: BTreeMap<Address, Vec<BlockNumber>>,
+        topics: BTreeMap<B256, Vec<BlockNumber>>,
+    ) -> ProviderResult<()> {
+        self.append_history_index::<_, tables::LogAddressHistory>(addresses, ShardedKey::new)?;
+        self.append_history_index::<_, tables::LogTopicHistory>(topics, ShardedKey::new)
+    }
+
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()> {
         // account history stage
         {
@@ -1,9 +1,10 @@
 use crate::{
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BlockSource, BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotifications,
-    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, FullExecutionDataProvider, HeaderProvider, ProviderError,
-    PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt, RequestsProvider,
+    AccountReader, AddressAppearancesReader, BlockHashReader, BlockIdReader, BlockNumReader,
+    BlockReader, BlockReaderIdExt, BlockSource, BlockchainTreePendingStateProvider,
+    CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider, ForkChoiceNotifications,
+    ForkChoiceSubscriptions, FullExecutionDataProvider, HeaderProvider, LogIndexReader,
+    ProviderError, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt, RequestsProvider,
     StageCheckpointReader, StateProviderBox, StateProviderFactory, StaticFileProviderFactory,
     TransactionVariant, TransactionsProvider, TreeViewer, WithdrawalsProvider,
 };
@@ -15,7 +16,7 @@ use reth_blockchain_tree_api::{
 use reth_chainspec::{ChainInfo, ChainSpec};
 use reth_db_api::{
     database::Database,
-    models::{AccountBeforeTx, StoredBlockBodyIndices},
+    models::{AccountBeforeTx, ContractCreation, StoredBlockBodyIndices},
 };
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
@@ -154,6 +155,10 @@ where
     fn database_provider_ro(&self) -> ProviderResult<DatabaseProviderRO<DB>> {
         self.database.provider()
     }
+
+    fn database_provider_rw(&self) -> ProviderResult<DatabaseProviderRW<DB>> {
+        self.database.provider_rw()
+    }
 }
 
 impl<DB> StaticFileProviderFactory for BlockchainProvider<DB> {
@@ -716,6 +721,10 @@ where
     fn make_canonical(&self, block_hash: BlockHash) -> Result<CanonicalOutcome, CanonicalError> {
         self.tree.make_canonical(block_hash)
     }
+
+    fn unwind(&self, unwind_to: BlockNumber) -> Result<(), CanonicalError> {
+        self.tree.unwind(unwind_to)
+    }
 }
 
 impl<DB> BlockchainTreeViewer for BlockchainProvider<DB>
@@ -898,6 +907,19 @@ where
     }
 }
 
+impl<DB> ForkChoiceSubscriptions for BlockchainProvider<DB>
+where
+    DB: Send + Sync,
+{
+    fn subscribe_safe_block(&self) -> ForkChoiceNotifications {
+        self.chain_info.subscribe_safe_block()
+    }
+
+    fn subscribe_finalized_block(&self) -> ForkChoiceNotifications {
+        self.chain_info.subscribe_finalized_block()
+    }
+}
+
 impl<DB> ChangeSetReader for BlockchainProvider<DB>
 where
     DB: Database,
@@ -910,6 +932,54 @@ where
     }
 }
 
+impl<DB> AddressAppearancesReader for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn address_appearances_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.database.provider()?.address_appearances_tip()
+    }
+
+    fn address_appearances(
+        &self,
+        address: Address,
+        range: RangeInclusive<TxNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<TxNumber>> {
+        self.database.provider()?.address_appearances(address, range, reverse, limit)
+    }
+
+    fn contract_creation(&self, address: Address) -> ProviderResult<Option<ContractCreation>> {
+        self.database.provider()?.contract_creation(address)
+    }
+}
+
+impl<DB> LogIndexReader for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn log_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.database.provider()?.log_index_tip()
+    }
+
+    fn log_address_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.log_address_blocks(address, range)
+    }
+
+    fn log_topic_blocks(
+        &self,
+        topic: B256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.log_topic_blocks(topic, range)
+    }
+}
+
 impl<DB> AccountReader for BlockchainProvider<DB>
 where
     DB: Database + Sync + Send,
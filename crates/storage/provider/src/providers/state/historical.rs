@@ -29,6 +29,8 @@ use std::fmt::Debug;
 /// - [`tables::StoragesHistory`]
 /// - [`tables::AccountChangeSets`]
 /// - [`tables::StorageChangeSets`]
+///
+/// Changesets of blocks that were moved to static files are read from there instead.
 #[derive(Debug)]
 pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     /// Transaction
@@ -130,7 +132,26 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             );
         }
 
-        Ok(HashedPostState::from_revert_range(self.tx, self.block_number..=tip)?)
+        let mut revert_state =
+            HashedPostState::from_revert_range(self.tx, self.block_number..=tip)?;
+
+        // Reverts of blocks that were moved to static files take precedence, since they are older
+        // than the ones left in the database.
+        let account_reverts = self
+            .static_file_provider
+            .account_changesets_iter(self.block_number..=tip)?
+            .map(|entry| entry.map(|(_, account)| account))
+            .collect::<ProviderResult<Vec<_>>>()?;
+        let storage_reverts = self
+            .static_file_provider
+            .storage_changesets_iter(self.block_number..=tip)?
+            .map(|entry| entry.map(|(key, storage)| (key.address(), storage.key, storage.value)))
+            .collect::<ProviderResult<Vec<_>>>()?;
+        if !account_reverts.is_empty() || !storage_reverts.is_empty() {
+            revert_state.extend(HashedPostState::from_reverts(account_reverts, storage_reverts));
+        }
+
+        Ok(revert_state)
     }
 
     fn history_info<T, K>(
@@ -203,10 +224,23 @@ impl<'b, TX: DbTx> AccountReader for HistoricalStateProviderRef<'b, TX> {
         match self.account_history_lookup(address)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(self
-                .tx
-                .cursor_dup_read::<tables::AccountChangeSets>()?
-                .seek_by_key_subkey(changeset_block_number, address)?
-                .filter(|acc| acc.address == address)
+                .static_file_provider
+                .get_with_static_file_or_database(
+                    StaticFileSegment::AccountChangeSets,
+                    changeset_block_number,
+                    |static_file| {
+                        Ok(static_file
+                            .account_changeset(changeset_block_number)?
+                            .and_then(|changeset| changeset.get(&address).cloned()))
+                    },
+                    || {
+                        Ok(self
+                            .tx
+                            .cursor_dup_read::<tables::AccountChangeSets>()?
+                            .seek_by_key_subkey(changeset_block_number, address)?
+                            .filter(|acc| acc.address == address))
+                    },
+                )?
                 .ok_or(ProviderError::AccountChangesetNotFound {
                     block_number: changeset_block_number,
                     address,
@@ -281,16 +315,36 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
         match self.storage_history_lookup(address, storage_key)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(Some(
-                self.tx
-                    .cursor_dup_read::<tables::StorageChangeSets>()?
-                    .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
-                    .filter(|entry| entry.key == storage_key)
+                self.static_file_provider
+                    .get_with_static_file_or_database(
+                        StaticFileSegment::StorageChangeSets,
+                        changeset_block_number,
+                        |static_file| {
+                            Ok(static_file.storage_changeset(changeset_block_number)?.and_then(
+                                |changeset| {
+                                    changeset
+                                        .get(&address, &storage_key)
+                                        .map(|storage| storage.value)
+                                },
+                            ))
+                        },
+                        || {
+                            Ok(self
+                                .tx
+                                .cursor_dup_read::<tables::StorageChangeSets>()?
+                                .seek_by_key_subkey(
+                                    (changeset_block_number, address).into(),
+                                    storage_key,
+                                )?
+                                .filter(|entry| entry.key == storage_key)
+                                .map(|entry| entry.value))
+                        },
+                    )?
                     .ok_or_else(|| ProviderError::StorageChangesetNotFound {
                         block_number: changeset_block_number,
                         address,
                         storage_key: Box::new(storage_key),
-                    })?
-                    .value,
+                    })?,
             )),
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
                 .tx
@@ -404,14 +458,19 @@ mod tests {
         providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
         test_utils::create_test_provider_factory,
         AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
-        StaticFileProviderFactory,
+        StaticFileProviderFactory, StaticFileWriter,
     };
     use reth_db::{tables, BlockNumberList};
     use reth_db_api::{
-        models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
+        models::{
+            storage_sharded_key::StorageShardedKey, AccountBeforeTx, AccountChangeSet, ShardedKey,
+            StorageBeforeTx, StorageChangeSet,
+        },
         transaction::{DbTx, DbTxMut},
     };
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{
+        address, b256, Account, Address, StaticFileSegment, StorageEntry, B256, U256,
+    };
     use reth_storage_errors::provider::ProviderError;
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
@@ -655,6 +714,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn history_provider_get_from_static_files() {
+        let factory = create_test_provider_factory();
+        let static_file_provider = factory.static_file_provider();
+
+        let acc_plain = Account { nonce: 100, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at10 = Account { nonce: 10, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at3 = Account { nonce: 3, balance: U256::ZERO, bytecode_hash: None };
+
+        let entry_plain = StorageEntry { key: STORAGE, value: U256::from(100) };
+        let entry_at10 = StorageEntry { key: STORAGE, value: U256::from(10) };
+        let entry_at3 = StorageEntry { key: STORAGE, value: U256::from(3) };
+
+        // setup static files: changesets up to block 7 were moved out of the database
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        for block in 0..=7 {
+            let accounts = match block {
+                1 => vec![AccountBeforeTx { address: ADDRESS, info: None }],
+                3 => vec![AccountBeforeTx { address: ADDRESS, info: Some(acc_at3) }],
+                _ => Vec::new(),
+            };
+            writer.append_account_changeset(block, AccountChangeSet { accounts }).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        for block in 0..=7 {
+            let storages = if block == 3 {
+                vec![StorageBeforeTx { address: ADDRESS, key: STORAGE, value: entry_at3.value }]
+            } else {
+                Vec::new()
+            };
+            writer.append_storage_changeset(block, StorageChangeSet { storages }).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        // setup database: changesets after block 7
+        let tx = factory.provider_rw().unwrap().into_tx();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey { key: ADDRESS, highest_block_number: u64::MAX },
+            BlockNumberList::new([1, 3, 10]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey {
+                address: ADDRESS,
+                sharded_key: ShardedKey { key: STORAGE, highest_block_number: u64::MAX },
+            },
+            BlockNumberList::new([3, 10]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            10,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at10) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>((10, ADDRESS).into(), entry_at10).unwrap();
+        tx.put::<tables::PlainAccountState>(ADDRESS, acc_plain).unwrap();
+        tx.put::<tables::PlainStorageState>(ADDRESS, entry_plain).unwrap();
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+
+        // run
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 1, static_file_provider.clone())
+                .basic_account(ADDRESS),
+            Ok(None)
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 2, static_file_provider.clone())
+                .basic_account(ADDRESS),
+            Ok(Some(acc_at3))
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 4, static_file_provider.clone())
+                .basic_account(ADDRESS),
+            Ok(Some(acc_at10))
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 11, static_file_provider.clone())
+                .basic_account(ADDRESS),
+            Ok(Some(acc_plain))
+        );
+
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 0, static_file_provider.clone())
+                .storage(ADDRESS, STORAGE),
+            Ok(None)
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 3, static_file_provider.clone())
+                .storage(ADDRESS, STORAGE),
+            Ok(Some(entry_at3.value))
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 4, static_file_provider.clone())
+                .storage(ADDRESS, STORAGE),
+            Ok(Some(entry_at10.value))
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 11, static_file_provider)
+                .storage(ADDRESS, STORAGE),
+            Ok(Some(entry_plain.value))
+        );
+    }
+
     #[test]
     fn history_provider_unavailable() {
         let factory = create_test_provider_factory();
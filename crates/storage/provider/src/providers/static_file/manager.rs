@@ -8,30 +8,37 @@ use crate::{
     TransactionVariant, TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
 use dashmap::{mapref::entry::Entry as DashMapEntry, DashMap};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use reth_chainspec::ChainInfo;
 use reth_db::{
     lockfile::StorageLock,
-    static_file::{iter_static_files, HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask},
+    static_file::{
+        iter_static_files, AccountChangeSetMask, HeaderMask, ReceiptMask, StaticFileCursor,
+        StorageChangeSetMask, TransactionMask,
+    },
     tables,
 };
 use reth_db_api::{
     cursor::DbCursorRO,
-    models::{CompactU256, StoredBlockBodyIndices},
+    models::{
+        AccountBeforeTx, AccountChangeSet, BlockNumberAddress, CompactU256, StorageChangeSet,
+        StoredBlockBodyIndices,
+    },
     table::Table,
     transaction::DbTx,
 };
-use reth_nippy_jar::NippyJar;
+use reth_nippy_jar::{commit_staged_jars, compression::Compressors, NippyJar};
 use reth_primitives::{
     keccak256,
     static_file::{find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive},
     Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Header, Receipt,
-    SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment, StorageEntry,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use reth_stages_types::{PipelineTarget, StageId};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use schnellru::{ByLength, LruMap};
 use std::{
     collections::{hash_map::Entry, BTreeMap, HashMap},
     ops::{Deref, Range, RangeBounds, RangeInclusive},
@@ -41,6 +48,13 @@ use std::{
 use strum::IntoEnumIterator;
 use tracing::{info, warn};
 
+/// Directory inside the static files directory, where rewritten static files are staged before
+/// replacing the originals.
+const STAGING_DIRECTORY: &str = "staging";
+
+/// Number of decoded block changesets kept per changeset segment, see [`ChangesetCache`].
+const MAX_CACHED_CHANGESETS: u32 = 64;
+
 /// Alias type for a map that can be queried for block ranges from a transaction
 /// segment respectively. It uses `TxNumber` to represent the transaction end of a static file
 /// range.
@@ -107,6 +121,9 @@ pub struct StaticFileProviderInner {
     map: DashMap<(BlockNumber, StaticFileSegment), LoadedJar>,
     /// Max static file block for each segment
     static_files_max_block: RwLock<HashMap<StaticFileSegment, u64>>,
+    /// Min static file block for each segment. Only higher than `0` for the segments whose lower
+    /// static files were pruned.
+    static_files_min_block: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Available static file block ranges on disk indexed by max transactions.
     static_files_tx_index: RwLock<SegmentRanges>,
     /// Directory where `static_files` are located
@@ -121,13 +138,26 @@ pub struct StaticFileProviderInner {
     access: StaticFileAccess,
     /// Write lock for when access is [`StaticFileAccess::RW`].
     _lock_file: Option<StorageLock>,
+    /// Decoded account changesets of recently read blocks.
+    account_changesets: ChangesetCache<AccountChangeSet>,
+    /// Decoded storage changesets of recently read blocks.
+    storage_changesets: ChangesetCache<StorageChangeSet>,
 }
 
 impl StaticFileProviderInner {
     /// Creates a new [`StaticFileProviderInner`].
     fn new(path: impl AsRef<Path>, access: StaticFileAccess) -> ProviderResult<Self> {
         let _lock_file = if access.is_read_write() {
-            Some(StorageLock::try_acquire(path.as_ref())?)
+            let lock_file = StorageLock::try_acquire(path.as_ref())?;
+
+            // Finishes replacing the static files whose recompression was interrupted
+            for jar in commit_staged_jars(&path.as_ref().join(STAGING_DIRECTORY), path.as_ref())
+                .map_err(|e| ProviderError::NippyJar(e.to_string()))?
+            {
+                info!(target: "providers::static_file", ?jar, "Replaced static file with its recompressed version.");
+            }
+
+            Some(lock_file)
         } else {
             None
         };
@@ -136,12 +166,15 @@ impl StaticFileProviderInner {
             map: Default::default(),
             writers: Default::default(),
             static_files_max_block: Default::default(),
+            static_files_min_block: Default::default(),
             static_files_tx_index: Default::default(),
             path: path.as_ref().to_path_buf(),
             load_filters: false,
             metrics: None,
             access,
             _lock_file,
+            account_changesets: Default::default(),
+            storage_changesets: Default::default(),
         };
 
         Ok(provider)
@@ -293,20 +326,7 @@ impl StaticFileProvider {
         segment: StaticFileSegment,
         fixed_block_range: SegmentRangeInclusive,
     ) -> ProviderResult<()> {
-        let key = (fixed_block_range.end(), segment);
-        let jar = if let Some((_, jar)) = self.map.remove(&key) {
-            jar.jar
-        } else {
-            let mut jar = NippyJar::<SegmentHeader>::load(
-                &self.path.join(segment.filename(&fixed_block_range)),
-            )
-            .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
-            if self.load_filters {
-                jar.load_filters().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
-            }
-            jar
-        };
-
+        let jar = self.take_jar(segment, &fixed_block_range)?;
         jar.delete().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
 
         let mut segment_max_block = None;
@@ -318,6 +338,85 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Deletes every static file of a segment whose block range ends at or below `block`, and
+    /// returns their [`SegmentHeader`]s. The static file holding the highest block of the segment
+    /// is never deleted, since it's the one being written to.
+    ///
+    /// CAUTION: destructive. Deletes files on disk.
+    pub fn delete_segment_below_block(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<SegmentHeader>> {
+        let Some(highest_block) = self.get_highest_static_file_block(segment) else {
+            return Ok(Vec::new())
+        };
+        let highest_block_range = find_fixed_range(highest_block);
+
+        let mut deleted = Vec::new();
+        let mut lowest_block = self.get_lowest_static_file_block(segment).unwrap_or_default();
+        while lowest_block < highest_block_range.start() {
+            let fixed_block_range = find_fixed_range(lowest_block);
+            if fixed_block_range.end() > block {
+                break
+            }
+
+            // Readers stop looking for the range before its file is gone.
+            lowest_block = fixed_block_range.end() + 1;
+            self.static_files_min_block.write().insert(segment, lowest_block);
+            self.clear_changeset_cache(segment);
+
+            let jar = self.take_jar(segment, &fixed_block_range)?;
+            deleted.push(jar.user_header().clone());
+            jar.delete().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Removes the cached jar of a segment and block range, or loads it from disk if it's not
+    /// cached.
+    fn take_jar(
+        &self,
+        segment: StaticFileSegment,
+        fixed_block_range: &SegmentRangeInclusive,
+    ) -> ProviderResult<NippyJar<SegmentHeader>> {
+        if let Some((_, jar)) = self.map.remove(&(fixed_block_range.end(), segment)) {
+            return Ok(jar.jar)
+        }
+
+        let mut jar =
+            NippyJar::<SegmentHeader>::load(&self.path.join(segment.filename(fixed_block_range)))
+                .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        if self.load_filters {
+            jar.load_filters().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        }
+        Ok(jar)
+    }
+
+    /// Given a segment and block range it rewrites the jar with `compressor`, and replaces it on
+    /// disk. See [`NippyJar::recompress`].
+    ///
+    /// CAUTION: destructive. Replaces files on disk.
+    pub fn recompress_jar(
+        &self,
+        segment: StaticFileSegment,
+        fixed_block_range: &SegmentRangeInclusive,
+        compressor: Option<Compressors>,
+    ) -> ProviderResult<NippyJar<SegmentHeader>> {
+        if self.access.is_read_only() {
+            return Err(ProviderError::ReadOnlyStaticFileAccess)
+        }
+
+        // The writer of the segment may hold handles to the files that are going to be replaced.
+        self.writers.remove(&segment);
+        self.remove_cached_provider(segment, fixed_block_range.end());
+
+        NippyJar::<SegmentHeader>::load(&self.path.join(segment.filename(fixed_block_range)))
+            .and_then(|jar| jar.recompress(compressor, &self.path.join(STAGING_DIRECTORY)))
+            .map_err(|e| ProviderError::NippyJar(e.to_string()))
+    }
+
     /// Given a segment and block range it returns a cached
     /// [`StaticFileJarProvider`]. TODO(joshie): we should check the size and pop N if there's too
     /// many.
@@ -355,6 +454,9 @@ impl StaticFileProvider {
         segment: StaticFileSegment,
         block: u64,
     ) -> Option<SegmentRangeInclusive> {
+        if self.get_lowest_static_file_block(segment).is_some_and(|min| min > block) {
+            return None
+        }
         self.static_files_max_block
             .read()
             .get(&segment)
@@ -400,7 +502,12 @@ impl StaticFileProvider {
         segment: StaticFileSegment,
         segment_max_block: Option<BlockNumber>,
     ) -> ProviderResult<()> {
+        // appends, unwinds and pruning all go through here, the latter two change the changesets
+        // of blocks that may be cached
+        self.clear_changeset_cache(segment);
+
         let mut max_block = self.static_files_max_block.write();
+        let mut min_block = self.static_files_min_block.write();
         let mut tx_index = self.static_files_tx_index.write();
 
         match segment_max_block {
@@ -409,6 +516,12 @@ impl StaticFileProvider {
                 max_block.insert(segment, segment_max_block);
                 let fixed_range = find_fixed_range(segment_max_block);
 
+                // The min block only moves down if we unwound past it.
+                min_block
+                    .entry(segment)
+                    .and_modify(|min| *min = (*min).min(fixed_range.start()))
+                    .or_insert(fixed_range.start());
+
                 let jar = NippyJar::<SegmentHeader>::load(
                     &self.path.join(segment.filename(&fixed_range)),
                 )
@@ -462,6 +575,7 @@ impl StaticFileProvider {
             None => {
                 tx_index.remove(&segment);
                 max_block.remove(&segment);
+                min_block.remove(&segment);
             }
         };
 
@@ -471,14 +585,21 @@ impl StaticFileProvider {
     /// Initializes the inner transaction and block index
     pub fn initialize_index(&self) -> ProviderResult<()> {
         let mut max_block = self.static_files_max_block.write();
+        let mut min_block = self.static_files_min_block.write();
         let mut tx_index = self.static_files_tx_index.write();
 
+        min_block.clear();
         tx_index.clear();
+        self.account_changesets.clear();
+        self.storage_changesets.clear();
 
         for (segment, ranges) in
             iter_static_files(&self.path).map_err(|e| ProviderError::NippyJar(e.to_string()))?
         {
-            // Update last block for each segment
+            // Update first and last block for each segment
+            if let Some((block_range, _)) = ranges.first() {
+                min_block.insert(segment, find_fixed_range(block_range.start()).start());
+            }
             if let Some((block_range, _)) = ranges.last() {
                 max_block.insert(segment, block_range.end());
             }
@@ -547,6 +668,12 @@ impl StaticFileProvider {
                 continue
             }
 
+            if segment.is_changesets() && self.get_highest_static_file_block(segment).is_none() {
+                // Changesets are only moved to static files on nodes without history pruning, and
+                // only once their blocks are finalized.
+                continue
+            }
+
             let initial_highest_block = self.get_highest_static_file_block(segment);
 
             //  File consistency is broken if:
@@ -631,6 +758,9 @@ impl StaticFileProvider {
                     highest_tx,
                     highest_block,
                 )?,
+                StaticFileSegment::AccountChangeSets | StaticFileSegment::StorageChangeSets => {
+                    self.ensure_changeset_invariants(provider, segment, highest_block)?
+                }
             } {
                 update_unwind_target(unwind);
             }
@@ -690,7 +820,9 @@ impl StaticFileProvider {
             .get_stage_checkpoint(match segment {
                 StaticFileSegment::Headers => StageId::Headers,
                 StaticFileSegment::Transactions => StageId::Bodies,
-                StaticFileSegment::Receipts => StageId::Execution,
+                StaticFileSegment::Receipts |
+                StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets => StageId::Execution,
             })?
             .unwrap_or_default()
             .block_number;
@@ -735,11 +867,54 @@ impl StaticFileProvider {
         Ok(None)
     }
 
+    /// Check invariants of a changeset static file segment.
+    ///
+    /// Changesets are only moved to static files once their blocks are finalized, so the highest
+    /// static file block is usually behind the [`StageId::Execution`] checkpoint, and the
+    /// changeset tables may have no entries for blocks without changes. The only invariant is that
+    /// the static file is not ahead of the checkpoint: if it is, then we failed to do a database
+    /// commit **but committed** to static files on unwinding, and the extra rows are pruned.
+    fn ensure_changeset_invariants<TX: DbTx>(
+        &self,
+        provider: &DatabaseProvider<TX>,
+        segment: StaticFileSegment,
+        highest_static_file_block: Option<BlockNumber>,
+    ) -> ProviderResult<Option<BlockNumber>> {
+        let highest_static_file_block = highest_static_file_block.unwrap_or_default();
+        let checkpoint_block_number =
+            provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+
+        if checkpoint_block_number < highest_static_file_block {
+            info!(
+                target: "reth::providers",
+                ?segment,
+                from = highest_static_file_block,
+                to = checkpoint_block_number,
+                "Unwinding static file segment."
+            );
+            let mut writer = self.latest_writer(segment)?;
+            let blocks = highest_static_file_block - checkpoint_block_number;
+            if segment == StaticFileSegment::AccountChangeSets {
+                writer.prune_account_changesets(blocks)?;
+            } else {
+                writer.prune_storage_changesets(blocks)?;
+            }
+            writer.commit()?;
+        }
+
+        Ok(None)
+    }
+
     /// Gets the highest static file block if it exists for a static file segment.
     pub fn get_highest_static_file_block(&self, segment: StaticFileSegment) -> Option<BlockNumber> {
         self.static_files_max_block.read().get(&segment).copied()
     }
 
+    /// Gets the lowest static file block if it exists for a static file segment.
+    pub fn get_lowest_static_file_block(&self, segment: StaticFileSegment) -> Option<BlockNumber> {
+        self.static_files_min_block.read().get(&segment).copied()
+    }
+
     /// Gets the highest static file transaction.
     pub fn get_highest_static_file_tx(&self, segment: StaticFileSegment) -> Option<TxNumber> {
         self.static_files_tx_index
@@ -754,6 +929,10 @@ impl StaticFileProvider {
             headers: self.get_highest_static_file_block(StaticFileSegment::Headers),
             receipts: self.get_highest_static_file_block(StaticFileSegment::Receipts),
             transactions: self.get_highest_static_file_block(StaticFileSegment::Transactions),
+            account_changesets: self
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            storage_changesets: self
+                .get_highest_static_file_block(StaticFileSegment::StorageChangeSets),
         }
     }
 
@@ -796,11 +975,10 @@ impl StaticFileProvider {
         F: FnMut(&mut StaticFileCursor<'_>, u64) -> ProviderResult<Option<T>>,
         P: FnMut(&T) -> bool,
     {
-        let get_provider = |start: u64| match segment {
-            StaticFileSegment::Headers => {
+        let get_provider = |start: u64| {
+            if segment.is_block_based() {
                 self.get_segment_provider_from_block(segment, start, None)
-            }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            } else {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -835,7 +1013,7 @@ impl StaticFileProvider {
                                 "Could not find block or tx number on a range request"
                             );
 
-                            let err = if segment.is_headers() {
+                            let err = if segment.is_block_based() {
                                 ProviderError::MissingStaticFileBlock(segment, number)
                             } else {
                                 ProviderError::MissingStaticFileTx(segment, number)
@@ -866,11 +1044,10 @@ impl StaticFileProvider {
         F: Fn(&mut StaticFileCursor<'_>, u64) -> ProviderResult<Option<T>> + 'a,
         T: std::fmt::Debug,
     {
-        let get_provider = move |start: u64| match segment {
-            StaticFileSegment::Headers => {
+        let get_provider = move |start: u64| {
+            if segment.is_block_based() {
                 self.get_segment_provider_from_block(segment, start, None)
-            }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            } else {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -913,11 +1090,10 @@ impl StaticFileProvider {
         FD: Fn() -> ProviderResult<Option<T>>,
     {
         // If there is, check the maximum block or transaction number of the segment.
-        let static_file_upper_bound = match segment {
-            StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+        let static_file_upper_bound = if segment.is_block_based() {
+            self.get_highest_static_file_block(segment)
+        } else {
+            self.get_highest_static_file_tx(segment)
         };
 
         if static_file_upper_bound
@@ -955,11 +1131,10 @@ impl StaticFileProvider {
         let mut data = Vec::new();
 
         // If there is, check the maximum block or transaction number of the segment.
-        if let Some(static_file_upper_bound) = match segment {
-            StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+        if let Some(static_file_upper_bound) = if segment.is_block_based() {
+            self.get_highest_static_file_block(segment)
+        } else {
+            self.get_highest_static_file_tx(segment)
         } {
             if block_or_tx_range.start <= static_file_upper_bound {
                 let end = block_or_tx_range.end.min(static_file_upper_bound + 1);
@@ -979,6 +1154,142 @@ impl StaticFileProvider {
         Ok(data)
     }
 
+    /// Returns the account changeset of a block, if it is in static files.
+    ///
+    /// The decoded changesets of recently read blocks are cached, so looking up several accounts
+    /// of the same block decodes its changeset once.
+    pub fn account_changeset(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<Arc<AccountChangeSet>>> {
+        self.account_changesets.get_or_load(block, || {
+            self.get_segment_provider_from_block(StaticFileSegment::AccountChangeSets, block, None)
+                .and_then(|provider| {
+                    provider
+                        .cursor()?
+                        .get_one::<AccountChangeSetMask<AccountChangeSet>>(block.into())
+                })
+                .or_else(|err| {
+                    if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                        Ok(None)
+                    } else {
+                        Err(err)
+                    }
+                })
+        })
+    }
+
+    /// Returns the storage changeset of a block, if it is in static files.
+    ///
+    /// The decoded changesets of recently read blocks are cached, so looking up several storage
+    /// slots of the same block decodes its changeset once.
+    pub fn storage_changeset(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Option<Arc<StorageChangeSet>>> {
+        self.storage_changesets.get_or_load(block, || {
+            self.get_segment_provider_from_block(StaticFileSegment::StorageChangeSets, block, None)
+                .and_then(|provider| {
+                    provider
+                        .cursor()?
+                        .get_one::<StorageChangeSetMask<StorageChangeSet>>(block.into())
+                })
+                .or_else(|err| {
+                    if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                        Ok(None)
+                    } else {
+                        Err(err)
+                    }
+                })
+        })
+    }
+
+    /// Drops the cached changesets of the segment, if it's a changeset segment.
+    fn clear_changeset_cache(&self, segment: StaticFileSegment) {
+        match segment {
+            StaticFileSegment::AccountChangeSets => self.account_changesets.clear(),
+            StaticFileSegment::StorageChangeSets => self.storage_changesets.clear(),
+            _ => {}
+        }
+    }
+
+    /// Returns an iterator over the account changes of the blocks in `range` that are in static
+    /// files, in the same order as the [`tables::AccountChangeSets`] table.
+    pub fn account_changesets_iter(
+        &self,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumber, AccountBeforeTx)>> + '_>
+    {
+        Ok(self
+            .changesets_iter(StaticFileSegment::AccountChangeSets, range, |cursor, block| {
+                cursor.get_one::<AccountChangeSetMask<AccountChangeSet>>(block.into())
+            })?
+            .flat_map(|result| match result {
+                Ok((block, changeset)) => changeset
+                    .accounts
+                    .into_iter()
+                    .map(|account| Ok((block, account)))
+                    .collect::<Vec<_>>(),
+                Err(err) => vec![Err(err)],
+            }))
+    }
+
+    /// Returns an iterator over the storage changes of the blocks in `range` that are in static
+    /// files, in the same order as the [`tables::StorageChangeSets`] table.
+    pub fn storage_changesets_iter(
+        &self,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumberAddress, StorageEntry)>> + '_>
+    {
+        Ok(self
+            .changesets_iter(StaticFileSegment::StorageChangeSets, range, |cursor, block| {
+                cursor.get_one::<StorageChangeSetMask<StorageChangeSet>>(block.into())
+            })?
+            .flat_map(|result| match result {
+                Ok((block, changeset)) => changeset
+                    .storages
+                    .into_iter()
+                    .map(|storage| {
+                        Ok((
+                            BlockNumberAddress((block, storage.address)),
+                            StorageEntry { key: storage.key, value: storage.value },
+                        ))
+                    })
+                    .collect::<Vec<_>>(),
+                Err(err) => vec![Err(err)],
+            }))
+    }
+
+    /// Returns an iterator over the changesets of the blocks in `range` that are in the static
+    /// files of a changeset segment.
+    fn changesets_iter<'a, T, F>(
+        &'a self,
+        segment: StaticFileSegment,
+        range: impl RangeBounds<BlockNumber>,
+        get_fn: F,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumber, T)>> + 'a>
+    where
+        F: Fn(&mut StaticFileCursor<'_>, BlockNumber) -> ProviderResult<Option<T>> + 'a,
+        T: std::fmt::Debug + 'a,
+    {
+        let mut range = to_range(range);
+        range.start =
+            range.start.max(self.get_lowest_static_file_block(segment).unwrap_or_default());
+        range.end = range.end.min(
+            self.get_highest_static_file_block(segment).map(|block| block + 1).unwrap_or_default(),
+        );
+
+        let changesets = if range.is_empty() {
+            None
+        } else {
+            Some(self.fetch_range_iter(segment, range, move |cursor, block| {
+                Ok(get_fn(cursor, block)?.map(|changeset| (block, changeset)))
+            })?)
+        };
+
+        Ok(changesets.into_iter().flatten())
+    }
+
     #[cfg(any(test, feature = "test-utils"))]
     /// Returns `static_files` directory
     pub fn path(&self) -> &Path {
@@ -986,6 +1297,66 @@ impl StaticFileProvider {
     }
 }
 
+/// LRU cache of the decoded changesets of a changeset segment, keyed by block.
+///
+/// The cache is cleared whenever the static files of the segment change, since unwinds and pruning
+/// remove or replace the changesets of blocks. Changesets loaded while the cache was being cleared
+/// are not cached.
+#[derive(Debug)]
+struct ChangesetCache<T> {
+    inner: Mutex<ChangesetCacheInner<T>>,
+}
+
+#[derive(Debug)]
+struct ChangesetCacheInner<T> {
+    changesets: LruMap<BlockNumber, Arc<T>, ByLength>,
+    /// Incremented on every clear.
+    generation: u64,
+}
+
+impl<T> Default for ChangesetCache<T> {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(ChangesetCacheInner {
+                changesets: LruMap::new(ByLength::new(MAX_CACHED_CHANGESETS)),
+                generation: 0,
+            }),
+        }
+    }
+}
+
+impl<T> ChangesetCache<T> {
+    /// Returns the cached changeset of the block, or loads and caches it.
+    fn get_or_load(
+        &self,
+        block: BlockNumber,
+        load: impl FnOnce() -> ProviderResult<Option<T>>,
+    ) -> ProviderResult<Option<Arc<T>>> {
+        let generation = {
+            let mut inner = self.inner.lock();
+            if let Some(changeset) = inner.changesets.get(&block) {
+                return Ok(Some(changeset.clone()))
+            }
+            inner.generation
+        };
+
+        // the lock isn't held while decoding, so concurrent lookups of other blocks don't wait
+        let Some(changeset) = load()?.map(Arc::new) else { return Ok(None) };
+        let mut inner = self.inner.lock();
+        if inner.generation == generation {
+            inner.changesets.insert(block, changeset.clone());
+        }
+        Ok(Some(changeset))
+    }
+
+    /// Drops all cached changesets.
+    fn clear(&self) {
+        let mut inner = self.inner.lock();
+        inner.changesets.clear();
+        inner.generation += 1;
+    }
+}
+
 /// Helper trait to manage different [`StaticFileProviderRW`] of an `Arc<StaticFileProvider`
 pub trait StaticFileWriter {
     /// Returns a mutable reference to a [`StaticFileProviderRW`] of a [`StaticFileSegment`].
@@ -65,9 +65,10 @@ mod tests {
     };
     use reth_db_api::{
         cursor::DbCursorRO,
+        models::{AccountBeforeTx, AccountChangeSet},
         transaction::{DbTx, DbTxMut},
     };
-    use reth_primitives::{static_file::find_fixed_range, BlockNumber, B256, U256};
+    use reth_primitives::{static_file::find_fixed_range, Address, BlockNumber, B256, U256};
     use reth_testing_utils::generators::{self, random_header_range};
 
     #[test]
@@ -179,4 +180,74 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_delete_segment_below_block() {
+        let segment = StaticFileSegment::AccountChangeSets;
+        let account = AccountBeforeTx { address: Address::with_last_byte(1), info: None };
+
+        // Changesets of blocks 0..=BLOCKS_PER_STATIC_FILE, spread over two static files.
+        let static_files_path = tempfile::tempdir().unwrap();
+        let static_file_provider =
+            StaticFileProvider::read_write(static_files_path.path()).unwrap();
+        let mut writer = static_file_provider.latest_writer(segment).unwrap();
+        for block in 0..=BLOCKS_PER_STATIC_FILE {
+            let accounts = if block % 1000 == 0 { vec![account.clone()] } else { Vec::new() };
+            writer.append_account_changeset(block, AccountChangeSet { accounts }).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        assert_eq!(static_file_provider.get_lowest_static_file_block(segment), Some(0));
+
+        // The first static file still has blocks above the target.
+        assert!(static_file_provider
+            .delete_segment_below_block(segment, BLOCKS_PER_STATIC_FILE - 2)
+            .unwrap()
+            .is_empty());
+        // Repeated lookups share the decoded changeset until the files change.
+        let changeset = static_file_provider.account_changeset(0).unwrap().unwrap();
+        assert!(Arc::ptr_eq(
+            &changeset,
+            &static_file_provider.account_changeset(0).unwrap().unwrap()
+        ));
+
+        let deleted = static_file_provider
+            .delete_segment_below_block(segment, BLOCKS_PER_STATIC_FILE - 1)
+            .unwrap();
+        assert_eq!(
+            deleted.iter().map(|header| header.block_range().copied()).collect::<Vec<_>>(),
+            vec![Some(find_fixed_range(0))]
+        );
+        assert!(!static_files_path.path().join(segment.filename(&find_fixed_range(0))).exists());
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(segment),
+            Some(BLOCKS_PER_STATIC_FILE)
+        );
+        assert_eq!(
+            static_file_provider.get_highest_static_file_block(segment),
+            Some(BLOCKS_PER_STATIC_FILE)
+        );
+        assert_eq!(static_file_provider.account_changeset(0), Ok(None));
+        assert_eq!(
+            static_file_provider
+                .account_changesets_iter(..)
+                .unwrap()
+                .collect::<ProviderResult<Vec<_>>>(),
+            Ok(vec![(BLOCKS_PER_STATIC_FILE, account)])
+        );
+
+        // The static file being written to is never deleted.
+        assert!(static_file_provider
+            .delete_segment_below_block(segment, u64::MAX)
+            .unwrap()
+            .is_empty());
+        assert!(static_file_provider.account_changeset(BLOCKS_PER_STATIC_FILE).unwrap().is_some());
+
+        // The lowest block is read from disk on start-up.
+        let static_file_provider = StaticFileProvider::read_only(static_files_path.path()).unwrap();
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(segment),
+            Some(BLOCKS_PER_STATIC_FILE)
+        );
+    }
 }
@@ -5,7 +5,7 @@ use super::{
 };
 use dashmap::mapref::one::RefMut;
 use reth_codecs::Compact;
-use reth_db_api::models::CompactU256;
+use reth_db_api::models::{AccountChangeSet, CompactU256, StorageChangeSet};
 #[cfg(feature = "not-zkvm")]
 use reth_nippy_jar::{ConsistencyFailStrategy, NippyJar, NippyJarError, NippyJarWriter};
 use reth_primitives::{
@@ -146,7 +146,7 @@ impl StaticFileProviderRW {
         })?;
 
         // If we have lost rows (in this run or previous), we need to update the [SegmentHeader].
-        let expected_rows = if self.user_header().segment().is_headers() {
+        let expected_rows = if self.user_header().segment().is_block_based() {
             self.user_header().block_len().unwrap_or_default()
         } else {
             self.user_header().tx_len().unwrap_or_default()
@@ -179,6 +179,10 @@ impl StaticFileProviderRW {
                 StaticFileSegment::Receipts => {
                     self.prune_receipt_data(to_delete, last_block_number.expect("should exist"))?
                 }
+                segment @ (StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets) => {
+                    self.prune_changeset_data(segment, to_delete)?
+                }
             }
         }
 
@@ -348,13 +352,10 @@ impl StaticFileProviderRW {
     ) -> ProviderResult<()> {
         let mut remaining_rows = num_rows;
         while remaining_rows > 0 {
-            let len = match segment {
-                StaticFileSegment::Headers => {
-                    self.writer.user_header().block_len().unwrap_or_default()
-                }
-                StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                    self.writer.user_header().tx_len().unwrap_or_default()
-                }
+            let len = if segment.is_block_based() {
+                self.writer.user_header().block_len().unwrap_or_default()
+            } else {
+                self.writer.user_header().tx_len().unwrap_or_default()
             };
 
             if remaining_rows >= len {
@@ -494,6 +495,59 @@ impl StaticFileProviderRW {
         Ok(block_number)
     }
 
+    /// Appends the account changeset of a block to static file.
+    ///
+    /// It **CALLS** `increment_block()` since there is one changeset per block, even if empty.
+    ///
+    /// Returns the current [`BlockNumber`] as seen in the static file.
+    pub fn append_account_changeset(
+        &mut self,
+        block_number: BlockNumber,
+        changeset: AccountChangeSet,
+    ) -> ProviderResult<BlockNumber> {
+        self.append_changeset(StaticFileSegment::AccountChangeSets, block_number, changeset)
+    }
+
+    /// Appends the storage changeset of a block to static file.
+    ///
+    /// It **CALLS** `increment_block()` since there is one changeset per block, even if empty.
+    ///
+    /// Returns the current [`BlockNumber`] as seen in the static file.
+    pub fn append_storage_changeset(
+        &mut self,
+        block_number: BlockNumber,
+        changeset: StorageChangeSet,
+    ) -> ProviderResult<BlockNumber> {
+        self.append_changeset(StaticFileSegment::StorageChangeSets, block_number, changeset)
+    }
+
+    /// Appends the changeset of a block to a changeset static file.
+    fn append_changeset<V: Compact>(
+        &mut self,
+        segment: StaticFileSegment,
+        block_number: BlockNumber,
+        changeset: V,
+    ) -> ProviderResult<BlockNumber> {
+        let start = Instant::now();
+        self.ensure_no_queued_prune()?;
+
+        debug_assert!(self.writer.user_header().segment() == segment);
+
+        let block_number = self.increment_block(segment, block_number)?;
+
+        self.append_column(changeset)?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                segment,
+                StaticFileProviderOperation::Append,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(block_number)
+    }
+
     /// Appends transaction to static file.
     ///
     /// It **DOES NOT CALL** `increment_block()`, it should be handled elsewhere. There might be
@@ -616,6 +670,28 @@ impl StaticFileProviderRW {
         self.queue_prune(to_delete, None)
     }
 
+    /// Adds an instruction to prune the account changesets of the last `to_delete` blocks during
+    /// commit.
+    pub fn prune_account_changesets(&mut self, to_delete: u64) -> ProviderResult<()> {
+        debug_assert_eq!(self.writer.user_header().segment(), StaticFileSegment::AccountChangeSets);
+        self.queue_prune(to_delete, None)
+    }
+
+    /// Adds an instruction to prune the storage changesets of the last `to_delete` blocks during
+    /// commit.
+    pub fn prune_storage_changesets(&mut self, to_delete: u64) -> ProviderResult<()> {
+        debug_assert_eq!(self.writer.user_header().segment(), StaticFileSegment::StorageChangeSets);
+        self.queue_prune(to_delete, None)
+    }
+
+    /// Commits any pending changes, and recomputes the checksums of the static file from its data.
+    ///
+    /// Used after truncating corrupted data, whose checksums can't be subtracted.
+    pub fn rebuild_checksums(&mut self) -> ProviderResult<()> {
+        self.commit()?;
+        self.writer.rebuild_checksums().map_err(|e| ProviderError::NippyJar(e.to_string()))
+    }
+
     /// Adds an instruction to prune `to_delete` elements during commit.
     ///
     /// Note: `last_block` refers to the block the unwinds ends at if dealing with transaction-based
@@ -708,6 +784,29 @@ impl StaticFileProviderRW {
         Ok(())
     }
 
+    /// Prunes the changesets of the last `to_delete` blocks from the data file.
+    fn prune_changeset_data(
+        &mut self,
+        segment: StaticFileSegment,
+        to_delete: u64,
+    ) -> ProviderResult<()> {
+        let start = Instant::now();
+
+        debug_assert!(self.writer.user_header().segment() == segment);
+
+        self.truncate(segment, to_delete, None)?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                segment,
+                StaticFileProviderOperation::Prune,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(())
+    }
+
     fn reader(&self) -> StaticFileProvider {
         Self::upgrade_provider_to_strong_reference(&self.reader)
     }
@@ -762,7 +861,7 @@ fn create_jar(
 
     // Transaction and Receipt already have the compression scheme used natively in its encoding.
     // (zstd-dictionary)
-    if segment.is_headers() {
+    if segment.is_block_based() {
         jar = jar.with_lz4();
     }
 
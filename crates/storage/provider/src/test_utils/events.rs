@@ -1,12 +1,31 @@
 use std::sync::{Arc, Mutex};
-use tokio::sync::broadcast::{self, Sender};
+use tokio::sync::{
+    broadcast::{self, Sender},
+    watch,
+};
 
-use crate::{CanonStateNotification, CanonStateNotifications, CanonStateSubscriptions, Chain};
+use crate::{
+    CanonStateNotification, CanonStateNotifications, CanonStateSubscriptions, Chain,
+    ForkChoiceNotifications, ForkChoiceSubscriptions,
+};
+use reth_primitives::SealedHeader;
 
 /// A test `ChainEventSubscriptions`
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct TestCanonStateSubscriptions {
     canon_notif_tx: Arc<Mutex<Vec<Sender<CanonStateNotification>>>>,
+    safe_block_tx: Arc<watch::Sender<Option<SealedHeader>>>,
+    finalized_block_tx: Arc<watch::Sender<Option<SealedHeader>>>,
+}
+
+impl Default for TestCanonStateSubscriptions {
+    fn default() -> Self {
+        Self {
+            canon_notif_tx: Default::default(),
+            safe_block_tx: Arc::new(watch::channel(None).0),
+            finalized_block_tx: Arc::new(watch::channel(None).0),
+        }
+    }
 }
 
 impl TestCanonStateSubscriptions {
@@ -23,6 +42,18 @@ impl TestCanonStateSubscriptions {
         let event = CanonStateNotification::Reorg { old, new };
         self.canon_notif_tx.lock().as_mut().unwrap().retain(|tx| tx.send(event.clone()).is_ok())
     }
+
+    /// Sets the safe block that can be consumed with
+    /// [`TestCanonStateSubscriptions::subscribe_safe_block`]
+    pub fn set_safe(&self, header: SealedHeader) {
+        self.safe_block_tx.send_replace(Some(header));
+    }
+
+    /// Sets the finalized block that can be consumed with
+    /// [`TestCanonStateSubscriptions::subscribe_finalized_block`]
+    pub fn set_finalized(&self, header: SealedHeader) {
+        self.finalized_block_tx.send_replace(Some(header));
+    }
 }
 
 impl CanonStateSubscriptions for TestCanonStateSubscriptions {
@@ -33,3 +64,13 @@ impl CanonStateSubscriptions for TestCanonStateSubscriptions {
         canon_notif_rx
     }
 }
+
+impl ForkChoiceSubscriptions for TestCanonStateSubscriptions {
+    fn subscribe_safe_block(&self) -> ForkChoiceNotifications {
+        self.safe_block_tx.subscribe()
+    }
+
+    fn subscribe_finalized_block(&self) -> ForkChoiceNotifications {
+        self.finalized_block_tx.subscribe()
+    }
+}
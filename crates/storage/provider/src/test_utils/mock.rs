@@ -1,19 +1,21 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, FullExecutionDataProvider, HeaderProvider,
-    ReceiptProviderIdExt, RequestsProvider, StateProvider, StateProviderBox, StateProviderFactory,
-    StateRootProvider, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    AccountReader, AddressAppearancesReader, BlockHashReader, BlockIdReader, BlockNumReader,
+    BlockReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
+    FullExecutionDataProvider, HeaderProvider, LogIndexReader, ReceiptProviderIdExt,
+    RequestsProvider, StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider,
+    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use parking_lot::Mutex;
 use reth_chainspec::{ChainInfo, ChainSpec};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db_api::models::{AccountBeforeTx, ContractCreation, StoredBlockBodyIndices};
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
-    keccak256, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber,
-    BlockWithSenders, Bytecode, Bytes, Header, Receipt, SealedBlock, SealedBlockWithSenders,
-    SealedHeader, StorageKey, StorageValue, TransactionMeta, TransactionSigned,
-    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
+    keccak256, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumHash,
+    BlockNumber, BlockWithSenders, Bytecode, Bytes, Header, Receipt, SealedBlock,
+    SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
+    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
+    U256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie::{updates::TrieUpdates, AccountProof};
@@ -38,6 +40,11 @@ pub struct MockEthProvider {
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
     /// Local chain spec
     pub chain_spec: Arc<ChainSpec>,
+    /// The finalized block
+    pub finalized_block: Arc<Mutex<Option<BlockNumHash>>>,
+    /// The last block covered by the address appearances index, which is derived from the
+    /// senders and recipients of the transactions of the local blocks
+    pub address_appearances_tip: Arc<Mutex<Option<BlockNumber>>>,
 }
 
 impl Default for MockEthProvider {
@@ -47,6 +54,8 @@ impl Default for MockEthProvider {
             headers: Default::default(),
             accounts: Default::default(),
             chain_spec: Arc::new(reth_chainspec::ChainSpecBuilder::mainnet().build()),
+            finalized_block: Default::default(),
+            address_appearances_tip: Default::default(),
         }
     }
 }
@@ -126,6 +135,23 @@ impl MockEthProvider {
             self.add_account(address, account)
         }
     }
+
+    /// Set the finalized block
+    pub fn set_finalized(&self, block: BlockNumHash) {
+        *self.finalized_block.lock() = Some(block);
+    }
+
+    /// Index the address appearances of the local blocks up to the given block
+    pub fn set_address_appearances_tip(&self, tip: BlockNumber) {
+        *self.address_appearances_tip.lock() = Some(tip);
+    }
+
+    /// Returns the local blocks ordered by number, which defines the transaction numbers
+    fn blocks_by_number(&self) -> Vec<Block> {
+        let mut blocks = self.blocks.lock().values().cloned().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.number);
+        blocks
+    }
 }
 
 impl HeaderProvider for MockEthProvider {
@@ -193,9 +219,9 @@ impl ChainSpecProvider for MockEthProvider {
 
 impl TransactionsProvider for MockEthProvider {
     fn transaction_id(&self, tx_hash: TxHash) -> ProviderResult<Option<TxNumber>> {
-        let lock = self.blocks.lock();
-        let tx_number = lock
-            .values()
+        let tx_number = self
+            .blocks_by_number()
+            .iter()
             .flat_map(|block| &block.body)
             .position(|tx| tx.hash() == tx_hash)
             .map(|pos| pos as TxNumber);
@@ -204,8 +230,8 @@ impl TransactionsProvider for MockEthProvider {
     }
 
     fn transaction_by_id(&self, id: TxNumber) -> ProviderResult<Option<TransactionSigned>> {
-        let lock = self.blocks.lock();
-        let transaction = lock.values().flat_map(|block| &block.body).nth(id as usize).cloned();
+        let transaction =
+            self.blocks_by_number().iter().flat_map(|block| &block.body).nth(id as usize).cloned();
 
         Ok(transaction)
     }
@@ -257,9 +283,8 @@ impl TransactionsProvider for MockEthProvider {
     }
 
     fn transaction_block(&self, id: TxNumber) -> ProviderResult<Option<BlockNumber>> {
-        let lock = self.blocks.lock();
         let mut current_tx_number: TxNumber = 0;
-        for block in lock.values() {
+        for block in self.blocks_by_number() {
             if current_tx_number + (block.body.len() as TxNumber) > id {
                 return Ok(Some(block.header.number))
             }
@@ -425,7 +450,7 @@ impl BlockIdReader for MockEthProvider {
     }
 
     fn finalized_block_num_hash(&self) -> ProviderResult<Option<reth_primitives::BlockNumHash>> {
-        Ok(None)
+        Ok(*self.finalized_block.lock())
     }
 }
 
@@ -462,16 +487,26 @@ impl BlockReader for MockEthProvider {
         Ok(None)
     }
 
-    fn block_body_indices(&self, _num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
+    fn block_body_indices(&self, num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
+        let mut first_tx_num: TxNumber = 0;
+        for block in self.blocks_by_number() {
+            if block.number == num {
+                return Ok(Some(StoredBlockBodyIndices {
+                    first_tx_num,
+                    tx_count: block.body.len() as u64,
+                }))
+            }
+            first_tx_num += block.body.len() as TxNumber;
+        }
         Ok(None)
     }
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        Ok(None)
+        Ok(self.block(id)?.and_then(|block| block.with_recovered_senders()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -704,3 +739,61 @@ impl ChangeSetReader for MockEthProvider {
         Ok(Vec::default())
     }
 }
+
+impl AddressAppearancesReader for MockEthProvider {
+    fn address_appearances_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(*self.address_appearances_tip.lock())
+    }
+
+    fn address_appearances(
+        &self,
+        address: Address,
+        range: RangeInclusive<TxNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<TxNumber>> {
+        let Some(tip) = *self.address_appearances_tip.lock() else { return Ok(Vec::default()) };
+
+        let mut tx_numbers = self
+            .blocks_by_number()
+            .iter()
+            .take_while(|block| block.number <= tip)
+            .flat_map(|block| &block.body)
+            .enumerate()
+            .filter(|(_, tx)| tx.recover_signer() == Some(address) || tx.to() == Some(address))
+            .map(|(tx_number, _)| tx_number as TxNumber)
+            .filter(|tx_number| range.contains(tx_number))
+            .collect::<Vec<_>>();
+        if reverse {
+            tx_numbers.reverse();
+        }
+        tx_numbers.truncate(limit);
+        Ok(tx_numbers)
+    }
+
+    fn contract_creation(&self, _address: Address) -> ProviderResult<Option<ContractCreation>> {
+        Ok(None)
+    }
+}
+
+impl LogIndexReader for MockEthProvider {
+    fn log_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn log_address_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+
+    fn log_topic_blocks(
+        &self,
+        _topic: B256,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+}
@@ -2,7 +2,7 @@ use crate::{providers::StaticFileProvider, ProviderFactory};
 use reth_chainspec::{ChainSpec, MAINNET};
 use reth_db::{
     test_utils::{create_test_rw_db, create_test_static_files_dir, TempDatabase},
-    DatabaseEnv,
+    DatabaseEnv, MemoryDatabase,
 };
 use std::sync::Arc;
 
@@ -32,3 +32,13 @@ pub fn create_test_provider_factory_with_chain_spec(
         StaticFileProvider::read_write(static_dir.into_path()).expect("static file provider"),
     )
 }
+
+/// Creates test provider factory with mainnet chain spec, backed by an in-memory database.
+pub fn create_test_memory_provider_factory() -> ProviderFactory<Arc<MemoryDatabase>> {
+    let (static_dir, _) = create_test_static_files_dir();
+    ProviderFactory::new(
+        Arc::new(MemoryDatabase::new()),
+        MAINNET.clone(),
+        StaticFileProvider::read_write(static_dir.into_path()).expect("static file provider"),
+    )
+}
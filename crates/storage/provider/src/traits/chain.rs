@@ -2,14 +2,17 @@
 
 use crate::{BlockReceipts, Chain};
 use auto_impl::auto_impl;
-use reth_primitives::SealedBlockWithSenders;
+use reth_primitives::{SealedBlockWithSenders, SealedHeader};
 use std::{
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
 };
-use tokio::sync::broadcast;
-use tokio_stream::{wrappers::BroadcastStream, Stream};
+use tokio::sync::{broadcast, watch};
+use tokio_stream::{
+    wrappers::{BroadcastStream, WatchStream},
+    Stream,
+};
 use tracing::debug;
 
 /// Type alias for a receiver that receives [`CanonStateNotification`]
@@ -34,6 +37,62 @@ pub trait CanonStateSubscriptions: Send + Sync {
     }
 }
 
+/// Type alias for a receiver of the latest safe or finalized header, `None` until the consensus
+/// layer reported one.
+pub type ForkChoiceNotifications = watch::Receiver<Option<SealedHeader>>;
+
+/// A type that allows to subscribe to the safe and finalized blocks of the consensus layer.
+#[auto_impl(&, Arc)]
+pub trait ForkChoiceSubscriptions: Send + Sync {
+    /// Get notified when a new safe block of the chain is selected.
+    fn subscribe_safe_block(&self) -> ForkChoiceNotifications;
+
+    /// Get notified when a new finalized block of the chain is selected.
+    fn subscribe_finalized_block(&self) -> ForkChoiceNotifications;
+
+    /// Convenience method to get a stream of the new safe headers.
+    fn safe_block_stream(&self) -> ForkChoiceStream {
+        ForkChoiceStream::new(self.subscribe_safe_block())
+    }
+
+    /// Convenience method to get a stream of the new finalized headers.
+    fn finalized_block_stream(&self) -> ForkChoiceStream {
+        ForkChoiceStream::new(self.subscribe_finalized_block())
+    }
+}
+
+/// A Stream of the new safe or finalized headers.
+///
+/// The stream only yields headers that are selected after it was created, the current value of
+/// the subscription is skipped.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct ForkChoiceStream {
+    #[pin]
+    st: WatchStream<Option<SealedHeader>>,
+}
+
+impl ForkChoiceStream {
+    /// Creates a new stream from the given receiver.
+    pub fn new(rx: ForkChoiceNotifications) -> Self {
+        Self { st: WatchStream::from_changes(rx) }
+    }
+}
+
+impl Stream for ForkChoiceStream {
+    type Item = SealedHeader;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            return match ready!(self.as_mut().project().st.poll_next(cx)) {
+                Some(Some(header)) => Poll::Ready(Some(header)),
+                Some(None) => continue,
+                None => Poll::Ready(None),
+            }
+        }
+    }
+}
+
 /// A Stream of [CanonStateNotification].
 #[derive(Debug)]
 #[pin_project::pin_project]
@@ -1,8 +1,9 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, StageCheckpointReader, StateProviderFactory,
+    AccountReader, AddressAppearancesReader, BlockReaderIdExt, CanonStateSubscriptions,
+    ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider,
+    ForkChoiceSubscriptions, LogIndexReader, StageCheckpointReader, StateProviderFactory,
     StaticFileProviderFactory,
 };
 use reth_db_api::database::Database;
@@ -17,7 +18,10 @@ pub trait FullProvider<DB: Database>:
     + EvmEnvProvider
     + ChainSpecProvider
     + ChangeSetReader
+    + AddressAppearancesReader
+    + LogIndexReader
     + CanonStateSubscriptions
+    + ForkChoiceSubscriptions
     + StageCheckpointReader
     + Clone
     + Unpin
@@ -34,7 +38,10 @@ impl<T, DB: Database> FullProvider<DB> for T where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + AddressAppearancesReader
+        + LogIndexReader
         + CanonStateSubscriptions
+        + ForkChoiceSubscriptions
         + StageCheckpointReader
         + Clone
         + Unpin
@@ -1,9 +1,9 @@
 use auto_impl::auto_impl;
 use reth_db_api::models::BlockNumberAddress;
-use reth_primitives::{Address, BlockNumber, B256};
+use reth_primitives::{Address, BlockNumber, TxNumber, B256};
 use reth_storage_errors::provider::ProviderResult;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     ops::{Range, RangeInclusive},
 };
 
@@ -38,6 +38,38 @@ pub trait HistoryWriter: Send + Sync {
         storage_transitions: BTreeMap<(Address, B256), Vec<u64>>,
     ) -> ProviderResult<()>;
 
+    /// Unwind and clear the address appearances index and the contract creators of the given
+    /// blocks. Used inside the IndexAddressAppearances stage.
+    ///
+    /// Returns the number of addresses unwound.
+    fn unwind_address_appearances(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize>;
+
+    /// Insert address appearances index to database. Used inside the IndexAddressAppearances
+    /// stage.
+    fn insert_address_appearances_index(
+        &self,
+        address_appearances: BTreeMap<Address, Vec<TxNumber>>,
+    ) -> ProviderResult<()>;
+
+    /// Unwind and clear the log index entries of the given addresses and topics, from the given
+    /// block onwards. Used inside the IndexLogs stage.
+    fn unwind_log_index(
+        &self,
+        addresses: BTreeSet<Address>,
+        topics: BTreeSet<B256>,
+        first_block: BlockNumber,
+    ) -> ProviderResult<()>;
+
+    /// Insert log index to database. Used inside the IndexLogs stage.
+    fn insert_log_index(
+        &self,
+        addresses: BTreeMap<Address, Vec<BlockNumber>>,
+        topics: BTreeMap<B256, Vec<BlockNumber>>,
+    ) -> ProviderResult<()>;
+
     /// Read account/storage changesets and update account/storage history indices.
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()>;
 }
@@ -0,0 +1,28 @@
+use auto_impl::auto_impl;
+use reth_db_api::models::ContractCreation;
+use reth_primitives::{Address, BlockNumber, TxNumber};
+use reth_storage_errors::provider::ProviderResult;
+use std::ops::RangeInclusive;
+
+/// Client trait for reading the address appearances and contract creators indices.
+///
+/// The indices are only written if the `IndexAddressAppearances` stage is enabled.
+#[auto_impl(&, Arc, Box)]
+pub trait AddressAppearancesReader: Send + Sync {
+    /// Returns the highest block whose address appearances are indexed, or `None` if the index
+    /// was never built.
+    fn address_appearances_tip(&self) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns at most `limit` numbers of the transactions in the given range the address appeared
+    /// in, in ascending order, or in descending order if `reverse` is set.
+    fn address_appearances(
+        &self,
+        address: Address,
+        range: RangeInclusive<TxNumber>,
+        reverse: bool,
+        limit: usize,
+    ) -> ProviderResult<Vec<TxNumber>>;
+
+    /// Returns the transaction that created the contract at the given address, and its creator.
+    fn contract_creation(&self, address: Address) -> ProviderResult<Option<ContractCreation>>;
+}
@@ -13,6 +13,9 @@ pub use reth_storage_errors as errors;
 mod account;
 pub use account::*;
 
+mod appearances;
+pub use appearances::*;
+
 mod block;
 pub use block::*;
 
@@ -25,6 +28,9 @@ pub use block_hash::*;
 mod header;
 pub use header::*;
 
+mod logs;
+pub use logs::*;
+
 mod prune_checkpoint;
 pub use prune_checkpoint::*;
 
@@ -0,0 +1,30 @@
+use auto_impl::auto_impl;
+use reth_primitives::{Address, BlockNumber, B256};
+use reth_storage_errors::provider::ProviderResult;
+use std::ops::RangeInclusive;
+
+/// Client trait for reading the log index, which maps log addresses and topics to the blocks
+/// containing them.
+///
+/// The index is only written if the `IndexLogs` stage is enabled.
+#[auto_impl(&, Arc, Box)]
+pub trait LogIndexReader: Send + Sync {
+    /// Returns the highest block covered by the log index, or `None` if logs are not indexed.
+    fn log_index_tip(&self) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns the numbers of the blocks in the given range that contain logs emitted by the
+    /// address, in ascending order.
+    fn log_address_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+
+    /// Returns the numbers of the blocks in the given range that contain logs with the topic, in
+    /// ascending order.
+    fn log_topic_blocks(
+        &self,
+        topic: B256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
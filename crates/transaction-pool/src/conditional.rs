@@ -0,0 +1,164 @@
+//! Conditions attached to transactions submitted via `eth_sendRawTransactionConditional`.
+
+use reth_primitives::{Address, B256, U256};
+use reth_provider::{ProviderResult, StateProvider};
+use std::collections::HashMap;
+
+/// The expected storage of an account the transaction depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnownAccountState {
+    /// The account's storage root must match.
+    StorageRoot(B256),
+    /// The account's storage slots must have the given values.
+    Slots(HashMap<B256, B256>),
+}
+
+/// Conditions a transaction must satisfy to be included in a block.
+///
+/// Transactions that violate their conditions are evicted from the pool during maintenance.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionConditional {
+    /// The expected storage of accounts the transaction depends on.
+    pub known_accounts: HashMap<Address, KnownAccountState>,
+    /// The minimum block number the transaction can be included in.
+    pub block_number_min: Option<u64>,
+    /// The maximum block number the transaction can be included in.
+    pub block_number_max: Option<u64>,
+    /// The minimum block timestamp the transaction can be included in.
+    pub timestamp_min: Option<u64>,
+    /// The maximum block timestamp the transaction can be included in.
+    pub timestamp_max: Option<u64>,
+}
+
+impl TransactionConditional {
+    /// Returns true if the transaction can no longer be included in any block on top of the given
+    /// tip.
+    pub fn is_expired(&self, tip_number: u64, tip_timestamp: u64) -> bool {
+        self.block_number_max.is_some_and(|max| max <= tip_number) ||
+            self.timestamp_max.is_some_and(|max| max <= tip_timestamp)
+    }
+
+    /// Returns true if a block with the given number and timestamp satisfies the block number
+    /// and timestamp bounds.
+    pub fn matches_block(&self, number: u64, timestamp: u64) -> bool {
+        self.block_number_min.map_or(true, |min| number >= min) &&
+            self.block_number_max.map_or(true, |max| number <= max) &&
+            self.timestamp_min.map_or(true, |min| timestamp >= min) &&
+            self.timestamp_max.map_or(true, |max| timestamp <= max)
+    }
+
+    /// Returns true if a block with the given number and timestamp, built on top of the given
+    /// state, satisfies all conditions.
+    ///
+    /// Block builders must re-check this before including the transaction.
+    pub fn is_satisfied(
+        &self,
+        number: u64,
+        timestamp: u64,
+        state: &impl StateProvider,
+    ) -> ProviderResult<bool> {
+        if !self.matches_block(number, timestamp) {
+            return Ok(false)
+        }
+        self.known_accounts_match(state)
+    }
+
+    /// Returns true if the state matches all known accounts.
+    pub fn known_accounts_match(&self, state: &impl StateProvider) -> ProviderResult<bool> {
+        for (address, expected) in &self.known_accounts {
+            let matches = match expected {
+                KnownAccountState::StorageRoot(root) => {
+                    state.proof(*address, &[])?.storage_root == *root
+                }
+                KnownAccountState::Slots(slots) => {
+                    let mut matches = true;
+                    for (slot, value) in slots {
+                        let current = state.storage(*address, *slot)?.unwrap_or_default();
+                        if current != U256::from_be_bytes(value.0) {
+                            matches = false;
+                            break
+                        }
+                    }
+                    matches
+                }
+            };
+            if !matches {
+                return Ok(false)
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    #[test]
+    fn expiry() {
+        let conditional = TransactionConditional {
+            block_number_max: Some(10),
+            timestamp_max: Some(100),
+            ..Default::default()
+        };
+        assert!(!conditional.is_expired(9, 99));
+        assert!(conditional.is_expired(10, 99));
+        assert!(conditional.is_expired(9, 100));
+
+        assert!(conditional.matches_block(10, 100));
+        assert!(!conditional.matches_block(11, 100));
+    }
+
+    #[test]
+    fn known_slots() {
+        let address = Address::random();
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage(vec![(B256::ZERO, U256::from(1))]),
+        );
+
+        let mut conditional = TransactionConditional::default();
+        conditional.known_accounts.insert(
+            address,
+            KnownAccountState::Slots(HashMap::from([(B256::ZERO, B256::with_last_byte(1))])),
+        );
+        assert!(conditional.known_accounts_match(&provider).unwrap());
+
+        conditional.known_accounts.insert(
+            address,
+            KnownAccountState::Slots(HashMap::from([(B256::ZERO, B256::with_last_byte(2))])),
+        );
+        assert!(!conditional.known_accounts_match(&provider).unwrap());
+    }
+
+    #[test]
+    fn satisfied() {
+        let address = Address::random();
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage(vec![(B256::ZERO, U256::from(1))]),
+        );
+
+        let mut conditional = TransactionConditional {
+            block_number_min: Some(5),
+            timestamp_min: Some(50),
+            ..Default::default()
+        };
+        conditional.known_accounts.insert(
+            address,
+            KnownAccountState::Slots(HashMap::from([(B256::ZERO, B256::with_last_byte(1))])),
+        );
+        assert!(conditional.is_satisfied(5, 50, &provider).unwrap());
+        assert!(!conditional.is_satisfied(4, 50, &provider).unwrap());
+        assert!(!conditional.is_satisfied(5, 49, &provider).unwrap());
+
+        conditional.known_accounts.insert(
+            address,
+            KnownAccountState::Slots(HashMap::from([(B256::ZERO, B256::with_last_byte(2))])),
+        );
+        assert!(!conditional.is_satisfied(5, 50, &provider).unwrap());
+    }
+}
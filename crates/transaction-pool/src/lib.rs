@@ -161,6 +161,7 @@ use tracing::{instrument, trace};
 
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
+    conditional::{KnownAccountState, TransactionConditional},
     config::{
         LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
         REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
@@ -179,6 +180,7 @@ pub use crate::{
     },
 };
 
+pub mod conditional;
 pub mod error;
 pub mod maintain;
 pub mod metrics;
@@ -352,6 +354,16 @@ where
         results.pop().expect("result length is the same as the input")
     }
 
+    async fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let (_, tx) = self.validate(origin, transaction).await;
+        self.pool.add_conditional_transaction(origin, tx, conditional)
+    }
+
     async fn add_transactions(
         &self,
         origin: TransactionOrigin,
@@ -473,6 +485,10 @@ where
         self.inner().get_all(txs)
     }
 
+    fn get_conditional_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.inner().get_conditional_transactions()
+    }
+
     fn on_propagated(&self, txs: PropagatedTransactions) {
         self.inner().on_propagated(txs)
     }
@@ -354,6 +354,16 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 };
                 pool.on_canonical_state_change(update);
 
+                // evict conditional transactions that are no longer valid on the new chain
+                evict_violating_conditional_transactions(
+                    &client,
+                    &pool,
+                    &metrics,
+                    new_tip.hash(),
+                    new_tip.number,
+                    new_tip.timestamp,
+                );
+
                 // all transactions that were mined in the old chain but not in the new chain need
                 // to be re-injected
                 //
@@ -435,6 +445,16 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 };
                 pool.on_canonical_state_change(update);
 
+                // evict conditional transactions before they are picked for the next block
+                evict_violating_conditional_transactions(
+                    &client,
+                    &pool,
+                    &metrics,
+                    tip.hash(),
+                    tip.number,
+                    tip.timestamp,
+                );
+
                 // keep track of mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&blocks);
             }
@@ -554,6 +574,65 @@ where
     Ok(res)
 }
 
+/// Removes all transactions from the pool whose conditions are violated on top of the given tip.
+///
+/// Transactions are evicted if they can no longer be included in a block after the tip, or if the
+/// state at the tip no longer matches their known accounts.
+fn evict_violating_conditional_transactions<Client, P>(
+    client: &Client,
+    pool: &P,
+    metrics: &MaintainPoolMetrics,
+    tip_hash: BlockHash,
+    tip_number: BlockNumber,
+    tip_timestamp: u64,
+) where
+    Client: StateProviderFactory,
+    P: TransactionPool,
+{
+    let conditional_txs = pool.get_conditional_transactions();
+    if conditional_txs.is_empty() {
+        return
+    }
+
+    let mut state = None;
+    let mut violating = Vec::new();
+    for tx in conditional_txs {
+        let hash = *tx.hash();
+        let Some(conditional) = &tx.conditional else { continue };
+        if conditional.is_expired(tip_number, tip_timestamp) {
+            violating.push(hash);
+            continue
+        }
+        if conditional.known_accounts.is_empty() {
+            continue
+        }
+
+        if state.is_none() {
+            match client.state_by_block_hash(tip_hash) {
+                Ok(provider) => state = Some(provider),
+                Err(err) => {
+                    debug!(target: "txpool", %err, "failed to load state for conditional transactions");
+                    break
+                }
+            }
+        }
+        let state = state.as_ref().expect("state is loaded");
+        match conditional.known_accounts_match(state) {
+            Ok(true) => {}
+            Ok(false) => violating.push(hash),
+            Err(err) => {
+                debug!(target: "txpool", %err, %hash, "failed to check known accounts of conditional transaction");
+            }
+        }
+    }
+
+    if !violating.is_empty() {
+        trace!(target: "txpool", count = violating.len(), "evicting conditional transactions");
+        metrics.inc_evicted_conditional_transactions(violating.len());
+        pool.remove_transactions(violating);
+    }
+}
+
 /// Extracts all changed accounts from the `BundleState`
 fn changed_accounts_iter(
     execution_outcome: &ExecutionOutcome,
@@ -13,8 +13,9 @@ use crate::{
     validate::ValidTransaction,
     AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPoolTransaction,
     EthPooledTransaction, NewTransactionEvent, PoolResult, PoolSize, PoolTransaction,
-    PooledTransactionsElement, PropagatedTransactions, TransactionEvents, TransactionOrigin,
-    TransactionPool, TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
+    PooledTransactionsElement, PropagatedTransactions, TransactionConditional, TransactionEvents,
+    TransactionOrigin, TransactionPool, TransactionValidationOutcome, TransactionValidator,
+    ValidPoolTransaction,
 };
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, TxHash, U256};
@@ -63,6 +64,16 @@ impl TransactionPool for NoopTransactionPool {
         Err(PoolError::other(hash, Box::new(NoopInsertError::new(transaction))))
     }
 
+    async fn add_conditional_transaction(
+        &self,
+        _origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        _conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let hash = *transaction.hash();
+        Err(PoolError::other(hash, Box::new(NoopInsertError::new(transaction))))
+    }
+
     async fn add_transactions(
         &self,
         _origin: TransactionOrigin,
@@ -194,6 +205,10 @@ impl TransactionPool for NoopTransactionPool {
         vec![]
     }
 
+    fn get_conditional_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        vec![]
+    }
+
     fn on_propagated(&self, _txs: PropagatedTransactions) {}
 
     fn get_transactions_by_sender(
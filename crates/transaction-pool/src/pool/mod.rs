@@ -66,6 +66,7 @@
 //!    category (2.) and become pending.
 
 use crate::{
+    conditional::TransactionConditional,
     error::{PoolError, PoolErrorKind, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
@@ -149,6 +150,9 @@ where
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// Hashes of the transactions that were added with conditions, these may have been removed
+    /// from the pool since.
+    conditional_transactions: RwLock<HashSet<TxHash>>,
 }
 
 // === impl PoolInner ===
@@ -172,6 +176,7 @@ where
             config,
             blob_store,
             blob_store_metrics: Default::default(),
+            conditional_transactions: Default::default(),
         }
     }
 
@@ -409,12 +414,14 @@ where
 
     /// Add a single validated transaction into the pool.
     ///
-    /// Note: this is only used internally by [`Self::add_transactions()`], all new transaction(s)
-    /// come in through that function, either as a batch or `std::iter::once`.
+    /// Note: this is only used internally by [`Self::add_transactions()`] and
+    /// [`Self::add_conditional_transaction()`], all new transaction(s) come in through these
+    /// functions, either as a batch or `std::iter::once`.
     fn add_transaction(
         &self,
         origin: TransactionOrigin,
         tx: TransactionValidationOutcome<T::Transaction>,
+        conditional: Option<Box<TransactionConditional>>,
     ) -> PoolResult<TxHash> {
         match tx {
             TransactionValidationOutcome::Valid {
@@ -438,17 +445,25 @@ where
                     }
                 };
 
+                // conditional transactions are never propagated, peers would include them
+                // without checking their conditions
+                let is_conditional = conditional.is_some();
                 let tx = ValidPoolTransaction {
                     transaction,
                     transaction_id,
-                    propagate,
+                    propagate: propagate && !is_conditional,
                     timestamp: Instant::now(),
                     origin,
+                    conditional,
                 };
 
                 let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
                 let hash = *added.hash();
 
+                if is_conditional {
+                    self.conditional_transactions.write().insert(hash);
+                }
+
                 // transaction was successfully inserted into the pool
                 if let Some(sidecar) = maybe_sidecar {
                     // notify blob sidecar listeners
@@ -512,8 +527,43 @@ where
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
     ) -> Vec<PoolResult<TxHash>> {
-        let mut added =
-            transactions.into_iter().map(|tx| self.add_transaction(origin, tx)).collect::<Vec<_>>();
+        self.add_transactions_with_conditionals(
+            origin,
+            transactions.into_iter().map(|tx| (tx, None)),
+        )
+    }
+
+    /// Adds a single transaction to the pool that is evicted once it violates the given
+    /// conditions.
+    pub fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        tx: TransactionValidationOutcome<T::Transaction>,
+        conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let mut results = self.add_transactions_with_conditionals(
+            origin,
+            std::iter::once((tx, Some(Box::new(conditional)))),
+        );
+        results.pop().expect("result length is the same as the input")
+    }
+
+    /// Adds all transactions in the iterator with their optional conditions to the pool,
+    /// returning a list of results.
+    fn add_transactions_with_conditionals(
+        &self,
+        origin: TransactionOrigin,
+        transactions: impl IntoIterator<
+            Item = (
+                TransactionValidationOutcome<T::Transaction>,
+                Option<Box<TransactionConditional>>,
+            ),
+        >,
+    ) -> Vec<PoolResult<TxHash>> {
+        let mut added = transactions
+            .into_iter()
+            .map(|(tx, conditional)| self.add_transaction(origin, tx, conditional))
+            .collect::<Vec<_>>();
 
         // If at least one transaction was added successfully, then we enforce the pool size limits.
         let discarded =
@@ -716,6 +766,28 @@ where
         self.get_pool_data().get(tx_hash)
     }
 
+    /// Returns all transactions in the pool that were added with conditions.
+    ///
+    /// Hashes of conditional transactions that are no longer in the pool are pruned.
+    pub(crate) fn get_conditional_transactions(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let mut hashes = self.conditional_transactions.write();
+        if hashes.is_empty() {
+            return Vec::new()
+        }
+        let pool = self.get_pool_data();
+        let mut transactions = Vec::with_capacity(hashes.len());
+        hashes.retain(|hash| match pool.get(hash) {
+            Some(tx) if tx.conditional.is_some() => {
+                transactions.push(tx);
+                true
+            }
+            _ => false,
+        });
+        transactions
+    }
+
     /// Returns all transactions of the address
     pub(crate) fn get_transactions_by_sender(
         &self,
@@ -1135,7 +1207,8 @@ mod tests {
         blobstore::{BlobStore, InMemoryBlobStore},
         test_utils::{MockTransaction, TestPoolBuilder},
         validate::ValidTransaction,
-        BlockInfo, PoolConfig, SubPoolLimit, TransactionOrigin, TransactionValidationOutcome, U256,
+        BlockInfo, PoolConfig, SubPoolLimit, TransactionConditional, TransactionOrigin,
+        TransactionValidationOutcome, U256,
     };
     use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar};
     use std::{fs, path::PathBuf};
@@ -1203,6 +1276,7 @@ mod tests {
                         },
                         propagate: true,
                     },
+                    None,
                 )
                 .unwrap();
 
@@ -1219,4 +1293,42 @@ mod tests {
         // Assert that the pool's blob store matches the expected blob store.
         assert_eq!(*test_pool.blob_store(), blob_store);
     }
+
+    #[test]
+    fn conditional_transactions_are_tracked_and_not_propagated() {
+        let test_pool = &TestPoolBuilder::default().pool;
+
+        let valid = |transaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(1_000),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(transaction),
+            propagate: true,
+        };
+
+        let tx = MockTransaction::eip1559();
+        let hash = tx.get_hash();
+        test_pool
+            .add_conditional_transaction(
+                TransactionOrigin::External,
+                valid(tx),
+                TransactionConditional { block_number_max: Some(10), ..Default::default() },
+            )
+            .unwrap();
+        test_pool
+            .add_transactions(TransactionOrigin::External, [valid(MockTransaction::eip1559())])
+            .pop()
+            .unwrap()
+            .unwrap();
+
+        let conditional = test_pool.get_conditional_transactions();
+        assert_eq!(conditional.len(), 1);
+        assert_eq!(*conditional[0].hash(), hash);
+        assert!(!conditional[0].propagate);
+        assert_eq!(conditional[0].conditional.as_ref().unwrap().block_number_max, Some(10));
+
+        // hashes of removed transactions are pruned
+        test_pool.remove_transactions(vec![hash]);
+        assert!(test_pool.get_conditional_transactions().is_empty());
+        assert!(test_pool.conditional_transactions.read().is_empty());
+    }
 }
@@ -2,6 +2,7 @@
 
 use crate::{
     blobstore::BlobStoreError,
+    conditional::TransactionConditional,
     error::PoolResult,
     pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
     validate::ValidPoolTransaction,
@@ -97,6 +98,20 @@ pub trait TransactionPool: Send + Sync + Clone {
         transaction: Self::Transaction,
     ) -> impl Future<Output = PoolResult<TxHash>> + Send;
 
+    /// Adds an _unvalidated_ transaction into the pool that must satisfy the given conditions to
+    /// be included in a block.
+    ///
+    /// The transaction is evicted from the pool once its conditions are violated by a new
+    /// canonical block.
+    ///
+    /// Consumer: RPC
+    fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> impl Future<Output = PoolResult<TxHash>> + Send;
+
     /// Adds the given _unvalidated_ transaction into the pool.
     ///
     /// Returns a list of results.
@@ -321,6 +336,11 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Caution: This in case of blob transactions, this does not include the sidecar.
     fn get_all(&self, txs: Vec<TxHash>) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns all transactions in the pool that were added with conditions.
+    ///
+    /// Consumer: Txpool maintenance
+    fn get_conditional_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Notify the pool about transactions that are propagated to peers.
     ///
     /// Consumer: P2P
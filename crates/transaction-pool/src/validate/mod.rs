@@ -1,6 +1,7 @@
 //! Transaction validation abstractions.
 
 use crate::{
+    conditional::TransactionConditional,
     error::InvalidPoolTransactionError,
     identifier::{SenderId, TransactionId},
     traits::{PoolTransaction, TransactionOrigin},
@@ -225,6 +226,8 @@ pub struct ValidPoolTransaction<T: PoolTransaction> {
     pub timestamp: Instant,
     /// Where this transaction originated from.
     pub origin: TransactionOrigin,
+    /// The conditions the transaction must satisfy to be included, if it was submitted with any.
+    pub conditional: Option<Box<TransactionConditional>>,
 }
 
 // === impl ValidPoolTransaction ===
@@ -354,6 +357,7 @@ impl<T: PoolTransaction + Clone> Clone for ValidPoolTransaction<T> {
             propagate: self.propagate,
             timestamp: self.timestamp,
             origin: self.origin,
+            conditional: self.conditional.clone(),
         }
     }
 }
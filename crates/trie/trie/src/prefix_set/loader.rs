@@ -8,7 +8,7 @@ use reth_db_api::{
     transaction::DbTx,
     DatabaseError,
 };
-use reth_primitives::{keccak256, BlockNumber, StorageEntry, B256};
+use reth_primitives::{keccak256, Address, BlockNumber, StorageEntry, B256};
 use std::{
     collections::{HashMap, HashSet},
     ops::RangeInclusive,
@@ -27,16 +27,38 @@ impl<'a, TX> PrefixSetLoader<'a, TX> {
 impl<'a, TX: DbTx> PrefixSetLoader<'a, TX> {
     /// Load all account and storage changes for the given block range.
     pub fn load(self, range: RangeInclusive<BlockNumber>) -> Result<TriePrefixSets, DatabaseError> {
+        let mut account_changeset_cursor = self.cursor_read::<tables::AccountChangeSets>()?;
+        let mut storage_cursor = self.cursor_dup_read::<tables::StorageChangeSets>()?;
+        let storage_range = BlockNumberAddress::range(range.clone());
+
+        self.load_changes(
+            account_changeset_cursor
+                .walk_range(range)?
+                .map(|entry| entry.map(|(_, AccountBeforeTx { address, .. })| address)),
+            storage_cursor.walk_range(storage_range)?.map(|entry| {
+                entry.map(|(BlockNumberAddress((_, address)), StorageEntry { key, .. })| {
+                    (address, key)
+                })
+            }),
+        )
+    }
+
+    /// Load the prefix sets of the given changed accounts and storage slots, e.g. read from
+    /// changesets that are not in the database anymore.
+    pub fn load_changes<E: From<DatabaseError>>(
+        self,
+        changed_accounts: impl IntoIterator<Item = Result<Address, E>>,
+        changed_storages: impl IntoIterator<Item = Result<(Address, B256), E>>,
+    ) -> Result<TriePrefixSets, E> {
         // Initialize prefix sets.
         let mut account_prefix_set = PrefixSetMut::default();
         let mut storage_prefix_sets = HashMap::<B256, PrefixSetMut>::default();
         let mut destroyed_accounts = HashSet::default();
 
-        // Walk account changeset and insert account prefixes.
-        let mut account_changeset_cursor = self.cursor_read::<tables::AccountChangeSets>()?;
+        // Insert account prefixes.
         let mut account_plain_state_cursor = self.cursor_read::<tables::PlainAccountState>()?;
-        for account_entry in account_changeset_cursor.walk_range(range.clone())? {
-            let (_, AccountBeforeTx { address, .. }) = account_entry?;
+        for address in changed_accounts {
+            let address = address?;
             let hashed_address = keccak256(address);
             account_prefix_set.insert(Nibbles::unpack(hashed_address));
 
@@ -45,12 +67,10 @@ impl<'a, TX: DbTx> PrefixSetLoader<'a, TX> {
             }
         }
 
-        // Walk storage changeset and insert storage prefixes as well as account prefixes if missing
-        // from the account prefix set.
-        let mut storage_cursor = self.cursor_dup_read::<tables::StorageChangeSets>()?;
-        let storage_range = BlockNumberAddress::range(range);
-        for storage_entry in storage_cursor.walk_range(storage_range)? {
-            let (BlockNumberAddress((_, address)), StorageEntry { key, .. }) = storage_entry?;
+        // Insert storage prefixes as well as account prefixes if missing from the account prefix
+        // set.
+        for storage in changed_storages {
+            let (address, key) = storage?;
             let hashed_address = keccak256(address);
             account_prefix_set.insert(Nibbles::unpack(hashed_address));
             storage_prefix_sets
@@ -92,6 +92,36 @@ impl HashedPostState {
             }
         }
 
+        Ok(Self::from_plain_reverts(accounts, storages))
+    }
+
+    /// Initialize [`HashedPostState`] from account and storage reverts, ordered from the oldest to
+    /// the newest change.
+    ///
+    /// Like [`Self::from_revert_range`], only the value before the first change of each account
+    /// and storage slot is kept.
+    pub fn from_reverts(
+        account_reverts: impl IntoIterator<Item = AccountBeforeTx>,
+        storage_reverts: impl IntoIterator<Item = (Address, B256, U256)>,
+    ) -> Self {
+        let mut accounts = HashMap::<Address, Option<Account>>::default();
+        for AccountBeforeTx { address, info } in account_reverts {
+            accounts.entry(address).or_insert(info);
+        }
+
+        let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
+        for (address, slot, value) in storage_reverts {
+            storages.entry(address).or_default().entry(slot).or_insert(value);
+        }
+
+        Self::from_plain_reverts(accounts, storages)
+    }
+
+    /// Hashes the accounts and storages values before the reverted changes.
+    fn from_plain_reverts(
+        accounts: HashMap<Address, Option<Account>>,
+        storages: HashMap<Address, HashMap<B256, U256>>,
+    ) -> Self {
         let hashed_accounts = HashMap::from_iter(
             accounts.into_iter().map(|(address, info)| (keccak256(address), info)),
         );
@@ -109,7 +139,7 @@ impl HashedPostState {
             )
         }));
 
-        Ok(Self { accounts: hashed_accounts, storages: hashed_storages })
+        Self { accounts: hashed_accounts, storages: hashed_storages }
     }
 
     /// Set account entries on hashed state.